@@ -0,0 +1,2623 @@
+use axum::{
+    body::Body,
+    extract::{OriginalUri, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware as axum_mw,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
+    Router,
+};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use application::admin::{BulkTarget, BulkUserAction};
+use application::email_suppression::{normalize_email, EmailSuppression};
+use application::feature_flags::FlagSource;
+use application::imports::{ImportFormat, ImportSummary};
+use application::jobs::{JobRecord, JobStatus};
+use application::operations::Operation;
+use application::query_stats::{query_stats, QueryStats, LATENCY_BUCKETS_MS};
+use application::rate_limits::{RateLimitOverride, RateLimitSubject, RateLimitUsage};
+use application::request_signing::ApiClient;
+use application::tenancy;
+use application::tagging::parse_tag_list;
+use application::webhooks::CreateWebhook;
+use application::RateLimitCounter;
+use domain::{Cohort, FeatureFlag, NoteVisibility, PaginationParams, Rollout, Tag, User, UserCursor, UserFilter, UserNote, UserSegment, UserStatus, Webhook, WebhookDelivery};
+
+use crate::auth::{TokenResponse, ValidatedJson};
+use crate::error::ApiError;
+use crate::middleware::{jwt_auth, require_role, AuthUser};
+use crate::pagination::{AdminUsersResponse, NotesResponse, PageLinks, Paginated, WebhookDeliveriesResponse, WebhooksResponse};
+use crate::server_timing::Json;
+use crate::tenants;
+use crate::AppState;
+
+// ============================================================================
+// Routes
+// ============================================================================
+
+/// Admin routes; every request needs a valid token with the `admin` role
+pub fn admin_routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
+    // The job queue, raw tables and webhooks span every tenant
+    let global = Router::new()
+        .route("/jobs", get(list_jobs))
+        .route("/data", get(list_tables))
+        .route("/data/:table", get(browse_table));
+    #[cfg(feature = "webhooks")]
+    let global = global
+        .route("/webhooks", get(list_webhooks).post(create_webhook))
+        .route("/webhooks/:id", delete(delete_webhook))
+        .route("/webhooks/:id/deliveries", get(list_webhook_deliveries));
+    Router::new()
+        .merge(global.route_layer(axum_mw::from_fn(tenants::require_default_tenant)))
+        .route("/users", get(search_users))
+        .route("/users/bulk", post(bulk_user_action))
+        .route("/users/import", post(import_users))
+        .route("/users/:id", delete(delete_user))
+        .route("/users/:id/suspend", post(suspend_user))
+        .route("/users/:id/unsuspend", post(unsuspend_user))
+        .route("/users/:id/password-reset", post(force_password_reset))
+        .route("/users/:id/impersonate", post(impersonate_user))
+        .route("/users/:id/email-suppression", delete(lift_email_suppression))
+        .route("/users/:id/tags/:tag", put(tag_user).delete(untag_user))
+        .route("/users/:id/roles", get(list_user_roles))
+        .route("/users/:id/roles/:role", put(grant_user_role).delete(revoke_user_role))
+        .route("/users/:id/notes", get(list_user_notes).post(create_user_note))
+        .route("/users/:id/notes/:note_id", put(update_user_note).delete(delete_user_note))
+        .route("/tags", get(list_tags))
+        .route("/segments", get(list_segments).post(create_segment))
+        .route("/segments/:id", get(get_segment).delete(delete_segment))
+        .route("/segments/:id/users", get(list_segment_users))
+        .route("/operations/:id", get(get_operation))
+        .nest(
+            "/rate-limits",
+            Router::new()
+                .route("/", get(get_rate_limit_usage))
+                .route("/reset", post(reset_rate_limits))
+                .route("/overrides", get(list_rate_limit_overrides).post(create_rate_limit_override))
+                .route("/overrides/:id", delete(delete_rate_limit_override))
+                .route_layer(axum_mw::from_fn(tenants::require_default_tenant)),
+        )
+        .route(
+            "/db-stats",
+            get(get_db_stats)
+                .delete(reset_db_stats)
+                .route_layer(axum_mw::from_fn(tenants::require_default_tenant)),
+        )
+        .nest(
+            "/feature-flags",
+            Router::new()
+                .route("/", get(list_feature_flags).post(create_feature_flag))
+                .route("/:key", get(get_feature_flag).put(update_feature_flag).delete(delete_feature_flag))
+                .route_layer(axum_mw::from_fn(tenants::require_default_tenant)),
+        )
+        .nest(
+            "/api-clients",
+            Router::new()
+                .route("/", get(list_api_clients).post(create_api_client))
+                .route("/:id", delete(delete_api_client))
+                .route_layer(axum_mw::from_fn(tenants::require_default_tenant)),
+        )
+        .nest(
+            "/tenants",
+            tenants::admin::routes(state.tenants.clone())
+                .route_layer(axum_mw::from_fn(tenants::require_default_tenant)),
+        )
+        .route_layer(axum_mw::from_fn_with_state(state.clone(), require_role("admin")))
+        .route_layer(axum_mw::from_fn_with_state(state, jwt_auth))
+}
+
+// ============================================================================
+// DTOs
+// ============================================================================
+
+/// Job status filter
+#[derive(Deserialize)]
+pub struct JobFilter {
+    pub status: Option<String>,
+}
+
+/// Background job
+#[derive(Serialize, ToSchema)]
+pub struct JobResponse {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: String,
+    /// Job kind
+    #[schema(example = "jobs.prune")]
+    pub kind: String,
+    /// pending, running, succeeded or failed
+    #[schema(example = "pending")]
+    pub status: String,
+    /// Runs started so far
+    pub attempts: u32,
+    /// Error from the last failed run
+    pub last_error: Option<String>,
+    /// Next (or last) scheduled run, RFC 3339
+    pub run_at: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<JobRecord> for JobResponse {
+    fn from(job: JobRecord) -> Self {
+        Self {
+            id: job.id.to_string(),
+            kind: job.kind,
+            status: job.status.as_str().to_string(),
+            attempts: job.attempts,
+            last_error: job.last_error,
+            run_at: job.run_at.to_rfc3339(),
+            created_at: job.created_at.to_rfc3339(),
+            updated_at: job.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Paginated jobs with queue-wide counts per status
+#[derive(Serialize, ToSchema)]
+pub struct JobsResponse {
+    pub items: Vec<JobResponse>,
+    pub total: u64,
+    pub page: u32,
+    pub per_page: u32,
+    pub total_pages: u32,
+    pub has_next: bool,
+    pub has_prev: bool,
+    /// Number of jobs in each status
+    pub counts: BTreeMap<String, u64>,
+}
+
+/// User search filter
+#[derive(Deserialize)]
+pub struct UserSearch {
+    pub q: Option<String>,
+    pub status: Option<String>,
+    /// Comma-separated; users must carry all of them
+    pub tags: Option<String>,
+}
+
+/// User as seen by admins
+#[derive(Serialize, ToSchema)]
+pub struct AdminUserResponse {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: String,
+    #[schema(example = "john_doe")]
+    pub username: String,
+    #[schema(example = "john@example.com")]
+    pub email: String,
+    /// active or suspended
+    #[schema(example = "active")]
+    pub status: String,
+    /// The user must change their password
+    pub password_reset_required: bool,
+    pub avatar_url: Option<String>,
+    pub created_at: String,
+    /// Set when email to this user is suppressed after a bounce or complaint
+    pub email_suppression: Option<EmailSuppressionResponse>,
+    /// Tag names, alphabetically
+    #[schema(example = json!(["beta", "vip"]))]
+    pub tags: Vec<String>,
+}
+
+impl AdminUserResponse {
+    fn new(user: User, suppression: Option<EmailSuppression>, tags: Vec<Tag>) -> Self {
+        Self {
+            id: user.id.to_string(),
+            username: user.username.into(),
+            email: user.email.into(),
+            status: user.status.as_str().to_string(),
+            password_reset_required: user.password_reset_required,
+            avatar_url: user.avatar_url,
+            created_at: user.created_at.to_rfc3339(),
+            email_suppression: suppression.map(Into::into),
+            tags: tags.into_iter().map(|t| t.name).collect(),
+        }
+    }
+}
+
+/// Why email to a user is not delivered
+#[derive(Serialize, ToSchema)]
+pub struct EmailSuppressionResponse {
+    /// bounce or complaint
+    #[schema(example = "bounce")]
+    pub reason: String,
+    /// Provider that reported it (ses or sendgrid)
+    #[schema(example = "ses")]
+    pub provider: String,
+    #[schema(example = "smtp; 550 5.1.1 user unknown")]
+    pub detail: Option<String>,
+    pub since: String,
+}
+
+/// New note on a user
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct CreateNoteRequest {
+    #[validate(length(min = 1, max = 10000, message = "must be 1-10000 characters"))]
+    #[schema(example = "Called about a double charge; refund issued.")]
+    pub body: String,
+    /// `team` (every admin, the default) or `private` (only you)
+    #[schema(example = "team")]
+    pub visibility: Option<String>,
+}
+
+/// Changed note; omit `visibility` to keep it
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct UpdateNoteRequest {
+    #[validate(length(min = 1, max = 10000, message = "must be 1-10000 characters"))]
+    pub body: String,
+    pub visibility: Option<String>,
+}
+
+/// Internal note on a user, never shown to the user
+#[derive(Serialize, ToSchema)]
+pub struct NoteResponse {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: String,
+    pub user_id: String,
+    /// Admin who wrote it
+    pub author_id: String,
+    pub body: String,
+    /// team or private
+    #[schema(example = "team")]
+    pub visibility: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<UserNote> for NoteResponse {
+    fn from(note: UserNote) -> Self {
+        Self {
+            id: note.id.to_string(),
+            user_id: note.user_id.to_string(),
+            author_id: note.author_id.to_string(),
+            body: note.body,
+            visibility: note.visibility.as_str().to_string(),
+            created_at: note.created_at.to_rfc3339(),
+            updated_at: note.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Tag usable on users (and other taggable entities)
+#[derive(Serialize, ToSchema)]
+pub struct TagResponse {
+    #[schema(example = "vip")]
+    pub name: String,
+    pub created_at: String,
+}
+
+impl From<Tag> for TagResponse {
+    fn from(tag: Tag) -> Self {
+        Self {
+            name: tag.name,
+            created_at: tag.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Tags of the tenant, by name
+#[derive(Serialize, ToSchema)]
+pub struct TagsResponse {
+    pub items: Vec<TagResponse>,
+}
+
+/// New segment: a named user search
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct CreateSegmentRequest {
+    #[validate(length(min = 1, max = 100, message = "must be 1-100 characters"))]
+    #[schema(example = "Active VIPs")]
+    pub name: String,
+    pub filter: UserCriteria,
+}
+
+/// Saved user search
+#[derive(Serialize, ToSchema)]
+pub struct SegmentResponse {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: String,
+    #[schema(example = "Active VIPs")]
+    pub name: String,
+    pub filter: UserCriteria,
+    /// Admin who saved it
+    pub created_by: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<UserSegment> for SegmentResponse {
+    fn from(segment: UserSegment) -> Self {
+        Self {
+            id: segment.id.to_string(),
+            name: segment.name,
+            filter: segment.filter.into(),
+            created_by: segment.created_by.to_string(),
+            created_at: segment.created_at.to_rfc3339(),
+            updated_at: segment.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Segments of the tenant, by name
+#[derive(Serialize, ToSchema)]
+pub struct SegmentsResponse {
+    pub items: Vec<SegmentResponse>,
+}
+
+/// Whose rate limits: exactly one of `user` and `ip`
+#[derive(Deserialize, ToSchema)]
+pub struct RateLimitSubjectQuery {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub user: Option<Uuid>,
+    #[schema(example = "203.0.113.7")]
+    pub ip: Option<String>,
+}
+
+impl TryFrom<RateLimitSubjectQuery> for RateLimitSubject {
+    type Error = ApiError;
+
+    fn try_from(query: RateLimitSubjectQuery) -> Result<Self, Self::Error> {
+        match (query.user, query.ip) {
+            (Some(user), None) => Ok(Self::User(user)),
+            (None, Some(ip)) => ip
+                .trim()
+                .parse()
+                .map(Self::Ip)
+                .map_err(|_| ApiError::bad_request(format!("Invalid IP address '{}'", ip))),
+            _ => Err(ApiError::bad_request("Give exactly one of user and ip")),
+        }
+    }
+}
+
+/// Hits counted in one rate limit window
+#[derive(Serialize, ToSchema)]
+pub struct RateLimitCounterResponse {
+    /// What is limited, e.g. `support` or `export`
+    #[schema(example = "support")]
+    pub scope: String,
+    pub count: u32,
+    /// Limit in force, overrides included
+    pub limit: u32,
+    pub remaining: u32,
+    /// Seconds until the window starts over
+    pub resets_in_secs: u64,
+}
+
+impl From<RateLimitCounter> for RateLimitCounterResponse {
+    fn from(counter: RateLimitCounter) -> Self {
+        let scope = application::split_rate_limit_key(&counter.key).map_or(counter.key.as_str(), |(scope, _)| scope);
+        Self {
+            scope: scope.to_string(),
+            count: counter.count,
+            limit: counter.limit,
+            remaining: counter.limit.saturating_sub(counter.count),
+            resets_in_secs: counter.resets_in.as_secs(),
+        }
+    }
+}
+
+/// Temporary rate limit granted by an admin
+#[derive(Serialize, ToSchema)]
+pub struct RateLimitOverrideResponse {
+    pub id: String,
+    #[schema(example = "user:550e8400-e29b-41d4-a716-446655440000")]
+    pub subject: String,
+    /// Scope it applies to; every scope when absent
+    #[schema(example = "support")]
+    pub scope: Option<String>,
+    pub limit: u32,
+    pub reason: Option<String>,
+    /// Admin who granted it
+    pub created_by: String,
+    pub created_at: String,
+    pub expires_at: String,
+}
+
+impl From<RateLimitOverride> for RateLimitOverrideResponse {
+    fn from(entry: RateLimitOverride) -> Self {
+        Self {
+            id: entry.id.to_string(),
+            subject: entry.subject,
+            scope: entry.scope,
+            limit: entry.limit,
+            reason: entry.reason,
+            created_by: entry.created_by.to_string(),
+            created_at: entry.created_at.to_rfc3339(),
+            expires_at: entry.expires_at.to_rfc3339(),
+        }
+    }
+}
+
+/// A subject's current rate limit consumption on this instance
+#[derive(Serialize, ToSchema)]
+pub struct RateLimitUsageResponse {
+    #[schema(example = "user:550e8400-e29b-41d4-a716-446655440000")]
+    pub subject: String,
+    /// Open windows, by scope
+    pub counters: Vec<RateLimitCounterResponse>,
+    pub overrides: Vec<RateLimitOverrideResponse>,
+}
+
+impl RateLimitUsageResponse {
+    fn new(subject: &RateLimitSubject, usage: RateLimitUsage) -> Self {
+        Self {
+            subject: subject.to_string(),
+            counters: usage.counters.into_iter().map(Into::into).collect(),
+            overrides: usage.overrides.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Counters reset
+#[derive(Serialize, ToSchema)]
+pub struct RateLimitResetResponse {
+    pub reset: usize,
+}
+
+/// Active rate limit overrides, oldest first
+#[derive(Serialize, ToSchema)]
+pub struct RateLimitOverridesResponse {
+    pub items: Vec<RateLimitOverrideResponse>,
+}
+
+/// Grant a subject a different limit for a while
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct CreateRateLimitOverrideRequest {
+    pub user: Option<Uuid>,
+    pub ip: Option<String>,
+    /// Only checks of this scope; every scope when absent
+    #[schema(example = "support")]
+    pub scope: Option<String>,
+    /// Hits allowed per window instead of the configured limit
+    #[validate(range(min = 1, max = 1000000, message = "must be 1-1000000"))]
+    #[schema(example = 50)]
+    pub limit: u32,
+    #[validate(range(min = 1, max = 43200, message = "must be 1-43200 minutes (30 days)"))]
+    #[schema(example = 60)]
+    pub expires_in_minutes: u64,
+    #[validate(length(max = 500, message = "must be at most 500 characters"))]
+    pub reason: Option<String>,
+}
+
+/// A feature flag and where its definition comes from
+#[derive(Serialize, ToSchema)]
+pub struct FeatureFlagResponse {
+    #[schema(example = "new_dashboard")]
+    pub key: String,
+    pub enabled: bool,
+    /// Share of eligible users that see the feature, 0-100
+    #[schema(example = 25.0)]
+    pub percentage: f64,
+    /// Cohorts eligible for the feature; everyone when empty
+    #[schema(value_type = Vec<Object>, example = json!([{"role": "beta"}]))]
+    pub cohorts: Vec<Cohort>,
+    pub description: Option<String>,
+    /// `config` (features.flags) or `stored` (managed here)
+    #[schema(example = "stored")]
+    pub source: String,
+}
+
+impl From<(FeatureFlag, FlagSource)> for FeatureFlagResponse {
+    fn from((flag, source): (FeatureFlag, FlagSource)) -> Self {
+        Self {
+            key: flag.key,
+            enabled: flag.enabled,
+            percentage: flag.rollout.percentage,
+            cohorts: flag.rollout.cohorts,
+            description: flag.description,
+            source: source.as_str().to_string(),
+        }
+    }
+}
+
+/// Every feature flag in force, by key
+#[derive(Serialize, ToSchema)]
+pub struct FeatureFlagsResponse {
+    pub items: Vec<FeatureFlagResponse>,
+}
+
+/// Define a feature flag
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct FeatureFlagRequest {
+    /// Ignored on update; the path names the flag
+    #[serde(default)]
+    #[schema(example = "new_dashboard")]
+    pub key: String,
+    pub enabled: bool,
+    /// 100 when absent
+    #[validate(range(min = 0.0, max = 100.0, message = "must be 0-100"))]
+    #[schema(example = 25.0)]
+    pub percentage: Option<f64>,
+    #[serde(default)]
+    #[schema(value_type = Vec<Object>, example = json!([{"role": "beta"}]))]
+    pub cohorts: Vec<Cohort>,
+    #[validate(length(max = 500, message = "must be at most 500 characters"))]
+    pub description: Option<String>,
+}
+
+impl From<FeatureFlagRequest> for FeatureFlag {
+    fn from(request: FeatureFlagRequest) -> Self {
+        Self {
+            key: request.key,
+            enabled: request.enabled,
+            rollout: Rollout {
+                percentage: request.percentage.unwrap_or(100.0),
+                cohorts: request.cohorts,
+            },
+            description: request.description,
+        }
+    }
+}
+
+/// API client that signs its requests (see `request_signing`)
+#[derive(Serialize, ToSchema)]
+pub struct ApiClientResponse {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: String,
+    #[schema(example = "billing-service")]
+    pub name: String,
+    /// Sent in `X-Api-Key-Id`
+    #[schema(example = "ak_4f1c2a0e9b7d4e3a8c6f5b2d1e0a9c8b")]
+    pub key_id: String,
+    #[schema(example = json!(["admin"]))]
+    pub roles: Vec<String>,
+    pub created_by: String,
+    pub created_at: String,
+}
+
+impl From<ApiClient> for ApiClientResponse {
+    fn from(client: ApiClient) -> Self {
+        Self {
+            id: client.id.to_string(),
+            name: client.name,
+            key_id: client.key_id,
+            roles: client.roles,
+            created_by: client.created_by.to_string(),
+            created_at: client.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// A new API client, with the secret it signs with; shown only once
+#[derive(Serialize, ToSchema)]
+pub struct CreatedApiClientResponse {
+    #[serde(flatten)]
+    pub client: ApiClientResponse,
+    #[schema(example = "sk_9d8c7b6a5f4e3d2c1b0a9f8e7d6c5b4a3f2e1d0c9b8a7f6e")]
+    pub secret: String,
+}
+
+/// Every API client, oldest first
+#[derive(Serialize, ToSchema)]
+pub struct ApiClientsResponse {
+    pub items: Vec<ApiClientResponse>,
+}
+
+/// Register an API client
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct CreateApiClientRequest {
+    #[validate(length(min = 1, max = 100, message = "must be 1-100 characters"))]
+    #[schema(example = "billing-service")]
+    pub name: String,
+    /// Roles its requests are made with; none for the `user` role only
+    #[serde(default)]
+    #[schema(example = json!(["admin"]))]
+    pub roles: Vec<String>,
+}
+
+/// Roles granted to a user and the permissions they add; the `user` role
+/// every token carries is not listed
+#[derive(Serialize, ToSchema)]
+pub struct UserRolesResponse {
+    #[schema(example = json!(["admin"]))]
+    pub roles: Vec<String>,
+    #[schema(example = json!(["users:read", "users:write"]))]
+    pub permissions: Vec<String>,
+}
+
+impl From<domain::Authorization> for UserRolesResponse {
+    fn from(authorization: domain::Authorization) -> Self {
+        Self {
+            roles: authorization.roles.into_iter().collect(),
+            permissions: authorization.permissions.into_iter().collect(),
+        }
+    }
+}
+
+impl From<EmailSuppression> for EmailSuppressionResponse {
+    fn from(suppression: EmailSuppression) -> Self {
+        Self {
+            reason: suppression.reason.as_str().to_string(),
+            provider: suppression.provider,
+            detail: suppression.detail,
+            since: suppression.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Table available in the data browser
+#[derive(Serialize, ToSchema)]
+pub struct TableResponse {
+    #[schema(example = "users")]
+    pub name: String,
+    pub columns: Vec<String>,
+    /// Columns shown masked
+    pub masked: Vec<String>,
+}
+
+/// Page of rows from a browsable table (sensitive columns masked)
+#[derive(Serialize, ToSchema)]
+pub struct TableRowsResponse {
+    #[schema(example = "users")]
+    pub table: String,
+    #[schema(value_type = Vec<Object>)]
+    pub items: Vec<serde_json::Map<String, serde_json::Value>>,
+    pub total: u64,
+    pub page: u32,
+    pub per_page: u32,
+    pub total_pages: u32,
+    pub has_next: bool,
+    pub has_prev: bool,
+}
+
+/// Webhook registration
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct CreateWebhookRequest {
+    /// Endpoint receiving the signed POSTs
+    #[validate(url(message = "must be a valid URL"))]
+    #[schema(example = "https://example.com/hooks/rust-base")]
+    pub url: String,
+    /// Signing secret; generated when omitted
+    #[validate(length(min = 16, max = 256, message = "must be 16-256 characters"))]
+    pub secret: Option<String>,
+    /// Event names to deliver; empty or omitted means all
+    #[serde(default)]
+    #[schema(example = json!(["user.registered"]))]
+    pub events: Vec<String>,
+}
+
+/// Registered webhook
+#[derive(Serialize, ToSchema)]
+pub struct WebhookResponse {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: String,
+    #[schema(example = "https://example.com/hooks/rust-base")]
+    pub url: String,
+    /// Delivered events; empty means all
+    pub events: Vec<String>,
+    pub active: bool,
+    pub created_at: String,
+}
+
+impl From<Webhook> for WebhookResponse {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            id: webhook.id.to_string(),
+            url: webhook.url,
+            events: webhook.events,
+            active: webhook.active,
+            created_at: webhook.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Newly registered webhook, including its secret (shown only once)
+#[derive(Serialize, ToSchema)]
+pub struct CreatedWebhookResponse {
+    #[serde(flatten)]
+    pub webhook: WebhookResponse,
+    /// HMAC-SHA256 key for verifying `X-Webhook-Signature`
+    #[schema(example = "whsec_4f1c2a...")]
+    pub secret: String,
+}
+
+/// One event sent to a webhook
+#[derive(Serialize, ToSchema)]
+pub struct WebhookDeliveryResponse {
+    pub id: String,
+    #[schema(example = "user.registered")]
+    pub event: String,
+    /// Event bus id of the delivered event
+    pub event_id: u64,
+    /// pending, succeeded or failed
+    #[schema(example = "succeeded")]
+    pub status: String,
+    pub attempts: u32,
+    /// HTTP status of the last attempt
+    pub response_status: Option<u16>,
+    pub last_error: Option<String>,
+    /// Signed request body
+    pub payload: String,
+    pub created_at: String,
+    pub delivered_at: Option<String>,
+}
+
+impl From<WebhookDelivery> for WebhookDeliveryResponse {
+    fn from(delivery: WebhookDelivery) -> Self {
+        Self {
+            id: delivery.id.to_string(),
+            event: delivery.event,
+            event_id: delivery.event_id,
+            status: delivery.status.as_str().to_string(),
+            attempts: delivery.attempts,
+            response_status: delivery.response_status,
+            last_error: delivery.last_error,
+            payload: delivery.payload,
+            created_at: delivery.created_at.to_rfc3339(),
+            delivered_at: delivery.delivered_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+/// Action applied to many users in the background
+#[derive(Deserialize, ToSchema)]
+pub struct BulkUserRequest {
+    /// suspend, delete or add-role
+    #[schema(example = "suspend")]
+    pub action: String,
+    /// Role to grant with add-role
+    #[schema(example = "support")]
+    pub role: Option<String>,
+    /// Users to act on; give exactly one of `ids`, `filter` and `segment`
+    pub ids: Option<Vec<Uuid>>,
+    /// Act on every user matching the admin user search
+    pub filter: Option<UserCriteria>,
+    /// Act on every user of a saved segment, with its filter as of now
+    pub segment: Option<Uuid>,
+}
+
+/// Same criteria as `GET /admin/users`
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UserCriteria {
+    pub q: Option<String>,
+    /// active or suspended
+    pub status: Option<String>,
+    /// Users must carry all of them
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl TryFrom<UserCriteria> for UserFilter {
+    type Error = ApiError;
+
+    fn try_from(criteria: UserCriteria) -> Result<Self, Self::Error> {
+        Ok(UserFilter {
+            query: criteria.q.map(|q| q.trim().to_string()).filter(|q| !q.is_empty()),
+            status: criteria
+                .status
+                .map(|s| UserStatus::parse(&s).ok_or_else(|| ApiError::bad_request(format!("Unknown user status '{}'", s))))
+                .transpose()?,
+            tags: parse_tag_list(&criteria.tags.join(","))?,
+            ..UserFilter::default()
+        })
+    }
+}
+
+impl From<UserFilter> for UserCriteria {
+    fn from(filter: UserFilter) -> Self {
+        Self {
+            q: filter.query,
+            status: filter.status.map(|status| status.as_str().to_string()),
+            tags: filter.tags,
+        }
+    }
+}
+
+/// Result for one item of an operation
+#[derive(Serialize, ToSchema)]
+pub struct OperationItemResponse {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: String,
+    /// succeeded or failed
+    #[schema(example = "succeeded")]
+    pub status: String,
+    pub error: Option<String>,
+    /// Input line the item came from, for imports
+    pub line: Option<u64>,
+}
+
+/// A row that was not imported
+#[derive(Serialize, ToSchema)]
+pub struct ImportErrorResponse {
+    /// Input line, counting the CSV header
+    #[schema(example = 7)]
+    pub line: u64,
+    #[schema(example = "Conflict: Email already registered")]
+    pub error: String,
+}
+
+/// Outcome of an import run during the request
+#[derive(Serialize, ToSchema)]
+pub struct ImportSummaryResponse {
+    /// Rows read, blank lines and the header aside
+    pub total: u64,
+    pub imported: u64,
+    pub failed: u64,
+    pub errors: Vec<ImportErrorResponse>,
+}
+
+impl From<ImportSummary> for ImportSummaryResponse {
+    fn from(summary: ImportSummary) -> Self {
+        Self {
+            total: summary.total,
+            imported: summary.imported,
+            failed: summary.errors.len() as u64,
+            errors: summary
+                .errors
+                .into_iter()
+                .map(|row| ImportErrorResponse {
+                    line: row.line,
+                    error: row.error.unwrap_or_default(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Background operation with its progress and per-item results
+#[derive(Serialize, ToSchema)]
+pub struct OperationResponse {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: String,
+    #[schema(example = "users.bulk.suspend")]
+    pub kind: String,
+    /// pending, running, succeeded or failed
+    #[schema(example = "running")]
+    pub status: String,
+    /// Items to process, once known
+    pub total: Option<u64>,
+    pub processed: u64,
+    pub failed: u64,
+    /// Why the whole operation failed
+    pub error: Option<String>,
+    pub items: Vec<OperationItemResponse>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl From<Operation> for OperationResponse {
+    fn from(operation: Operation) -> Self {
+        Self {
+            id: operation.id.to_string(),
+            kind: operation.kind.clone(),
+            status: operation.status.as_str().to_string(),
+            total: operation.total,
+            processed: operation.items.len() as u64,
+            failed: operation.failed_items() as u64,
+            error: operation.error,
+            items: operation
+                .items
+                .into_iter()
+                .map(|item| OperationItemResponse {
+                    id: item.id.to_string(),
+                    status: if item.error.is_some() { "failed" } else { "succeeded" }.to_string(),
+                    error: item.error,
+                    line: item.line,
+                })
+                .collect(),
+            created_at: operation.created_at.to_rfc3339(),
+            updated_at: operation.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Query latency on this instance
+#[derive(Serialize, ToSchema)]
+pub struct DbStatsResponse {
+    /// Queries slower than this are logged under `slow_query`; null when off
+    #[schema(example = 500)]
+    pub slow_query_ms: Option<u64>,
+    /// Calls of query shapes past the tracking limit
+    pub untracked_calls: u64,
+    /// The most total time first
+    pub queries: Vec<QueryStatsResponse>,
+}
+
+/// Latency of one query shape; values are never recorded
+#[derive(Serialize, ToSchema)]
+pub struct QueryStatsResponse {
+    #[schema(example = "9f3c2b1a0d4e5f67")]
+    pub fingerprint: String,
+    #[schema(example = "SELECT id, username FROM users WHERE id = ?")]
+    pub statement: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub total_ms: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+    /// Percentiles are bucket upper bounds (capped at `max_ms`)
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub histogram: Vec<LatencyBucketResponse>,
+}
+
+/// Calls that took at most `le_ms` (and more than the previous bucket)
+#[derive(Serialize, ToSchema)]
+pub struct LatencyBucketResponse {
+    /// Upper bound; null for the last, unbounded bucket
+    pub le_ms: Option<u64>,
+    pub count: u64,
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+impl From<QueryStats> for QueryStatsResponse {
+    fn from(stats: QueryStats) -> Self {
+        Self {
+            mean_ms: millis(stats.mean()),
+            p50_ms: millis(stats.percentile(0.5)),
+            p95_ms: millis(stats.percentile(0.95)),
+            p99_ms: millis(stats.percentile(0.99)),
+            total_ms: millis(stats.total),
+            max_ms: millis(stats.max),
+            histogram: LATENCY_BUCKETS_MS
+                .iter()
+                .map(|bound| Some(*bound))
+                .chain([None])
+                .zip(stats.buckets)
+                .map(|(le_ms, count)| LatencyBucketResponse { le_ms, count })
+                .collect(),
+            fingerprint: stats.fingerprint,
+            statement: stats.statement,
+            calls: stats.calls,
+            errors: stats.errors,
+        }
+    }
+}
+
+/// How many query shapes `/admin/db-stats` lists
+#[derive(Deserialize)]
+pub struct DbStatsQuery {
+    pub limit: Option<usize>,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// List background jobs
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/jobs",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("status" = Option<String>, Query, description = "pending, running, succeeded or failed"),
+        ("page" = Option<u32>, Query, description = "Page number (default: 1); pages starting past `pagination.max_offset` rows are rejected"),
+        ("per_page" = Option<u32>, Query, description = "Items per page (default: 20, max: 100)")
+    ),
+    responses(
+        (status = 200, description = "Jobs", body = JobsResponse, headers(("link" = String, description = "RFC 5988 links to the first, prev, next and last pages"))),
+        (status = 400, description = "Unknown status or invalid tag", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse)
+    )
+)]
+pub async fn list_jobs(
+    State(state): State<Arc<AppState>>,
+    Query(filter): Query<JobFilter>,
+    Query(params): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+) -> Result<(PageLinks, Json<JobsResponse>), ApiError> {
+    let status = filter
+        .status
+        .map(|s| JobStatus::parse(&s).ok_or_else(|| ApiError::bad_request(format!("Unknown job status '{}'", s))))
+        .transpose()?;
+
+    let page = state.job_queue.list(status, &params).await?;
+    let links = PageLinks::new(&uri, &page);
+    let counts = state
+        .job_queue
+        .counts()
+        .await?
+        .into_iter()
+        .map(|(status, count)| (status.as_str().to_string(), count))
+        .collect();
+
+    Ok((links, Json(JobsResponse {
+        items: page.items.into_iter().map(Into::into).collect(),
+        total: page.total,
+        page: page.page,
+        per_page: page.per_page,
+        total_pages: page.total_pages,
+        has_next: page.has_next,
+        has_prev: page.has_prev,
+        counts,
+    })))
+}
+
+/// List tables available in the read-only data browser
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/data",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Browsable tables", body = Vec<TableResponse>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse)
+    )
+)]
+pub async fn list_tables(State(state): State<Arc<AppState>>) -> Json<Vec<TableResponse>> {
+    Json(
+        state
+            .data_browser
+            .tables()
+            .iter()
+            .map(|t| TableResponse {
+                name: t.name.to_string(),
+                columns: t.columns.iter().map(|c| c.to_string()).collect(),
+                masked: t.masked.iter().map(|(c, _)| c.to_string()).collect(),
+            })
+            .collect(),
+    )
+}
+
+/// Browse rows of a whitelisted table (read-only, sensitive columns masked)
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/data/{table}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("table" = String, Path, description = "Table name (see /admin/data)"),
+        ("page" = Option<u32>, Query, description = "Page number (default: 1); pages starting past `pagination.max_offset` rows are rejected"),
+        ("per_page" = Option<u32>, Query, description = "Items per page (default: 20, max: 100)")
+    ),
+    responses(
+        (status = 200, description = "Rows", body = TableRowsResponse, headers(("link" = String, description = "RFC 5988 links to the first, prev, next and last pages"))),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse),
+        (status = 404, description = "Table not browsable", body = ErrorResponse)
+    )
+)]
+pub async fn browse_table(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(table): Path<String>,
+    Query(params): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+) -> Result<(PageLinks, Json<TableRowsResponse>), ApiError> {
+    tracing::info!(
+        target: "audit",
+        admin_id = %claims.sub,
+        table = %table,
+        page = params.page,
+        "Admin data browser access"
+    );
+
+    let page = state.data_browser.browse(&table, &params).await?;
+    let links = PageLinks::new(&uri, &page);
+
+    Ok((links, Json(TableRowsResponse {
+        table,
+        items: page.items,
+        total: page.total,
+        page: page.page,
+        per_page: page.per_page,
+        total_pages: page.total_pages,
+        has_next: page.has_next,
+        has_prev: page.has_prev,
+    })))
+}
+
+/// Register a webhook endpoint
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/webhooks",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    request_body = CreateWebhookRequest,
+    responses(
+        (status = 201, description = "Webhook registered", body = CreatedWebhookResponse),
+        (status = 400, description = "Unknown event", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse)
+    )
+)]
+pub async fn create_webhook(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    ValidatedJson(payload): ValidatedJson<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<CreatedWebhookResponse>), ApiError> {
+    let webhook = state
+        .webhook_service
+        .create(CreateWebhook {
+            url: payload.url,
+            secret: payload.secret,
+            events: payload.events,
+        })
+        .await?;
+
+    tracing::info!(
+        target: "audit",
+        admin_id = %claims.sub,
+        webhook_id = %webhook.id,
+        url = %webhook.url,
+        "Webhook registered"
+    );
+
+    let secret = webhook.secret.clone();
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedWebhookResponse {
+            webhook: webhook.into(),
+            secret,
+        }),
+    ))
+}
+
+/// List webhook endpoints
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/webhooks",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("page" = Option<u32>, Query, description = "Page number (default: 1); pages starting past `pagination.max_offset` rows are rejected"),
+        ("per_page" = Option<u32>, Query, description = "Items per page (default: 20, max: 100)")
+    ),
+    responses(
+        (status = 200, description = "Webhooks", body = WebhooksResponse, headers(("link" = String, description = "RFC 5988 links to the first, prev, next and last pages"))),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse)
+    )
+)]
+pub async fn list_webhooks(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+) -> Result<(PageLinks, Json<WebhooksResponse>), ApiError> {
+    let page = state.webhook_service.list(&params).await?;
+    let links = PageLinks::new(&uri, &page);
+
+    Ok((links, Json(Paginated::from(page))))
+}
+
+/// Remove a webhook endpoint and its delivery history
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/webhooks/{id}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Webhook ID")),
+    responses(
+        (status = 204, description = "Webhook removed"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse)
+    )
+)]
+pub async fn delete_webhook(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.webhook_service.delete(id).await?;
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, webhook_id = %id, "Webhook removed");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Delivery history of a webhook, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/webhooks/{id}/deliveries",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Webhook ID"),
+        ("page" = Option<u32>, Query, description = "Page number (default: 1); pages starting past `pagination.max_offset` rows are rejected"),
+        ("per_page" = Option<u32>, Query, description = "Items per page (default: 20, max: 100)")
+    ),
+    responses(
+        (status = 200, description = "Deliveries", body = WebhookDeliveriesResponse, headers(("link" = String, description = "RFC 5988 links to the first, prev, next and last pages"))),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse),
+        (status = 404, description = "Webhook not found", body = ErrorResponse)
+    )
+)]
+pub async fn list_webhook_deliveries(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+) -> Result<(PageLinks, Json<WebhookDeliveriesResponse>), ApiError> {
+    let page = state.webhook_service.deliveries(id, &params).await?;
+    let links = PageLinks::new(&uri, &page);
+
+    Ok((links, Json(Paginated::from(page))))
+}
+
+/// Search users by username or email
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/users",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("q" = Option<String>, Query, description = "Substring of the username or email"),
+        ("status" = Option<String>, Query, description = "active or suspended"),
+        ("tags" = Option<String>, Query, description = "Comma-separated tags the users must all carry"),
+        ("page" = Option<u32>, Query, description = "Page number (default: 1); pages starting past `pagination.max_offset` rows are rejected"),
+        ("per_page" = Option<u32>, Query, description = "Items per page (default: 20, max: 100)")
+    ),
+    responses(
+        (
+            status = 200,
+            description = "Users. With `Accept: text/csv` or `application/x-ndjson`, every matching user is streamed instead of a page",
+            content(("application/json" = AdminUsersResponse), ("text/csv" = String), ("application/x-ndjson" = String)),
+            headers(("link" = String, description = "RFC 5988 links to the first, prev, next and last pages (JSON only)"))
+        ),
+        (status = 400, description = "Unknown status or invalid tag", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse)
+    )
+)]
+pub async fn search_users(
+    State(state): State<Arc<AppState>>,
+    Query(search): Query<UserSearch>,
+    Query(params): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let filter = UserFilter {
+        query: search.q.map(|q| q.trim().to_string()).filter(|q| !q.is_empty()),
+        status: search
+            .status
+            .map(|s| UserStatus::parse(&s).ok_or_else(|| ApiError::bad_request(format!("Unknown user status '{}'", s))))
+            .transpose()?,
+        tags: match &search.tags {
+            Some(tags) => parse_tag_list(tags)?,
+            None => Vec::new(),
+        },
+        ..UserFilter::default()
+    };
+    if let Some(format) = ExportFormat::from_accept(&headers) {
+        return Ok(export_users(state, filter, format));
+    }
+
+    let mut page = state.admin_users.search(&filter, &params).await?;
+    let links = PageLinks::new(&uri, &page);
+
+    let items = admin_user_responses(&state, std::mem::take(&mut page.items)).await?;
+    Ok((links, Json(Paginated::new(items, page))).into_response())
+}
+
+/// Users fetched per query while exporting
+const EXPORT_CHUNK: u32 = 500;
+
+/// Column names, the first line of a CSV export
+pub const EXPORT_CSV_HEADER: &str = "id,username,email,status,password_reset_required,avatar_url,created_at,email_suppression,tags\r\n";
+
+/// Row formats `GET /admin/users` streams in place of a JSON page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Ndjson,
+}
+
+impl ExportFormat {
+    /// First streamable media type listed in `Accept`; `None` keeps the JSON page
+    pub fn from_accept(headers: &HeaderMap) -> Option<Self> {
+        let accept = headers.get(header::ACCEPT)?.to_str().ok()?;
+        accept.split(',').find_map(|range| {
+            let media_type = range.split(';').next().unwrap_or_default().trim();
+            match media_type.to_ascii_lowercase().as_str() {
+                "text/csv" => Some(Self::Csv),
+                "application/x-ndjson" | "application/ndjson" => Some(Self::Ndjson),
+                _ => None,
+            }
+        })
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    /// Append `user` as one row
+    pub fn write(self, out: &mut String, user: &AdminUserResponse) {
+        match self {
+            Self::Csv => {
+                let created_at = user.created_at.as_str();
+                let suppression = user.email_suppression.as_ref().map(|s| s.reason.as_str());
+                let tags = user.tags.join(";");
+                let fields = [
+                    user.id.as_str(),
+                    user.username.as_str(),
+                    user.email.as_str(),
+                    user.status.as_str(),
+                    if user.password_reset_required { "true" } else { "false" },
+                    user.avatar_url.as_deref().unwrap_or_default(),
+                    created_at,
+                    suppression.unwrap_or_default(),
+                    tags.as_str(),
+                ];
+                for (i, field) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    push_csv_field(out, field);
+                }
+                out.push_str("\r\n");
+            }
+            Self::Ndjson => {
+                out.push_str(&serde_json::to_string(user).unwrap_or_default());
+                out.push('\n');
+            }
+        }
+    }
+}
+
+/// Quote `field` when needed. Fields a spreadsheet would read as a formula
+/// (user-chosen usernames may start with `=`, `+`, `-` or `@`) get a leading
+/// `'` so they open as text.
+fn push_csv_field(out: &mut String, field: &str) {
+    let quoted = field.contains([',', '"', '\r', '\n']);
+    if quoted {
+        out.push('"');
+    }
+    if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        out.push('\'');
+    }
+    if quoted {
+        out.push_str(&field.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(field);
+    }
+}
+
+/// Stream every user matching `filter`. The next chunk is only queried once
+/// the client has taken the previous one, so a slow reader holds one chunk in
+/// memory rather than the whole result. The caller's tenant is captured here
+/// because the body is polled after the request's tenant scope has ended.
+fn export_users(state: Arc<AppState>, filter: UserFilter, format: ExportFormat) -> Response {
+    let tenant = tenancy::current_tenant();
+    let csv_header = (format == ExportFormat::Csv).then(|| Ok::<_, std::io::Error>(axum::body::Bytes::from_static(EXPORT_CSV_HEADER.as_bytes())));
+    let rows = futures_util::stream::try_unfold(Some(None), move |cursor| {
+        let (state, filter) = (state.clone(), filter.clone());
+        async move {
+            let Some(after) = cursor else { return Ok(None) };
+            let chunk = async {
+                let users = state.admin_users.search_after(&filter, after, EXPORT_CHUNK).await?;
+                let next = (users.len() == EXPORT_CHUNK as usize).then(|| users.last().map(UserCursor::from));
+                Ok::<_, ApiError>((admin_user_responses(&state, users).await?, next))
+            };
+            let (users, next) = match tenant {
+                Some(tenant) => tenancy::with_tenant(tenant, chunk).await,
+                None => chunk.await,
+            }
+            .map_err(|e| {
+                tracing::error!(error = ?e, "User export failed");
+                std::io::Error::other("user export failed")
+            })?;
+            if users.is_empty() {
+                return Ok(None);
+            }
+            let mut out = String::new();
+            for user in &users {
+                format.write(&mut out, user);
+            }
+            Ok(Some((axum::body::Bytes::from(out), next)))
+        }
+    });
+
+    let body = Body::from_stream(futures_util::stream::iter(csv_header).chain(rows));
+    ([(header::CONTENT_TYPE, format.content_type())], body).into_response()
+}
+
+/// Suspend a user: sign-in is refused until unsuspended
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{id}/suspend",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "User suspended", body = AdminUserResponse),
+        (status = 400, description = "Cannot suspend yourself", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
+pub async fn suspend_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AdminUserResponse>, ApiError> {
+    let user = state.admin_users.suspend(admin_id(&claims)?, id).await?;
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, user_id = %id, "User suspended");
+    Ok(Json(admin_user_response(&state, user).await?))
+}
+
+/// Lift a user's suspension
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{id}/unsuspend",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "User reactivated", body = AdminUserResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
+pub async fn unsuspend_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AdminUserResponse>, ApiError> {
+    let user = state.admin_users.unsuspend(id).await?;
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, user_id = %id, "User unsuspended");
+    Ok(Json(admin_user_response(&state, user).await?))
+}
+
+/// Require a user to change their password
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{id}/password-reset",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Password reset required", body = AdminUserResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
+pub async fn force_password_reset(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AdminUserResponse>, ApiError> {
+    let user = state.admin_users.force_password_reset(id).await?;
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, user_id = %id, "Password reset forced");
+    Ok(Json(admin_user_response(&state, user).await?))
+}
+
+/// Act as a user
+///
+/// Returns a user token for them that also names the calling admin (`act`
+/// claim). It lasts `jwt.impersonation_expiration_minutes` and cannot be
+/// refreshed. Every request made with it is audited, and its responses carry
+/// `X-Impersonated-By`. Admins cannot be impersonated.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{id}/impersonate",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Impersonation token", body = TokenResponse),
+        (status = 400, description = "The user is an admin, or yourself", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
+pub async fn impersonate_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<TokenResponse>, ApiError> {
+    let token = state.impersonation.start(admin_id(&claims)?, id).await?;
+    Ok(Json(token.into()))
+}
+
+/// Permanently delete a user
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/users/{id}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "User ID")),
+    responses(
+        (status = 204, description = "User deleted"),
+        (status = 400, description = "Cannot delete yourself", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
+pub async fn delete_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.admin_users.delete(admin_id(&claims)?, id).await?;
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, user_id = %id, "User deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Deliver email to a user again after their address was suppressed
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/users/{id}/email-suppression",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "User ID")),
+    responses(
+        (status = 204, description = "Suppression lifted"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found or not suppressed", body = ErrorResponse)
+    )
+)]
+pub async fn lift_email_suppression(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let user = state
+        .user_service
+        .get_user(id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("User with id {} not found", id)))?;
+    if !state.email_suppressions.remove(&normalize_email(&user.email)).await? {
+        return Err(ApiError::not_found("Email to this user is not suppressed"));
+    }
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, user_id = %id, "Email suppression lifted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Tags of the current tenant
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/tags",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Tags", body = TagsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse)
+    )
+)]
+pub async fn list_tags(State(state): State<Arc<AppState>>) -> Result<Json<TagsResponse>, ApiError> {
+    let tags = state.tags.list().await?;
+    Ok(Json(TagsResponse {
+        items: tags.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// Tag a user, creating the tag on first use
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/users/{id}/tags/{tag}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "User ID"),
+        ("tag" = String, Path, description = "Tag name: letters, digits, '-', '_' or ':'")
+    ),
+    responses(
+        (status = 200, description = "User with the tag", body = AdminUserResponse),
+        (status = 400, description = "Invalid tag", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
+pub async fn tag_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path((id, tag)): Path<(Uuid, String)>,
+) -> Result<Json<AdminUserResponse>, ApiError> {
+    let user = find_user(&state, id).await?;
+    let tag = state.tags.tag(&user, &tag).await?;
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, user_id = %id, tag = %tag.name, "User tagged");
+    Ok(Json(admin_user_response(&state, user).await?))
+}
+
+/// Remove a tag from a user
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/users/{id}/tags/{tag}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "User ID"),
+        ("tag" = String, Path, description = "Tag name")
+    ),
+    responses(
+        (status = 204, description = "Tag removed"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found or not tagged", body = ErrorResponse)
+    )
+)]
+pub async fn untag_user(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path((id, tag)): Path<(Uuid, String)>,
+) -> Result<StatusCode, ApiError> {
+    let user = find_user(&state, id).await?;
+    state.tags.untag(&user, &tag).await?;
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, user_id = %id, tag = %tag, "User untagged");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Roles granted to a user
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/users/{id}/roles",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "User ID")),
+    responses(
+        (status = 200, description = "Granted roles", body = UserRolesResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
+pub async fn list_user_roles(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<UserRolesResponse>, ApiError> {
+    find_user(&state, id).await?;
+    Ok(Json(state.authz.stored(id).await?.into()))
+}
+
+/// Grant a role to a user; takes effect on their next request
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/users/{id}/roles/{role}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "User ID"),
+        ("role" = String, Path, description = "Role name: letters, digits, '-', '_' or ':'")
+    ),
+    responses(
+        (status = 200, description = "Roles after the grant", body = UserRolesResponse),
+        (status = 400, description = "Invalid role", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
+pub async fn grant_user_role(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path((id, role)): Path<(Uuid, String)>,
+) -> Result<Json<UserRolesResponse>, ApiError> {
+    find_user(&state, id).await?;
+    if state.authz.grant_role(id, &role).await? {
+        tracing::info!(target: "audit", admin_id = %claims.sub, user_id = %id, role = %role, "Role granted");
+    }
+    Ok(Json(state.authz.stored(id).await?.into()))
+}
+
+/// Revoke a role from a user
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/users/{id}/roles/{role}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "User ID"),
+        ("role" = String, Path, description = "Role name")
+    ),
+    responses(
+        (status = 204, description = "Role revoked"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found or role not granted", body = ErrorResponse)
+    )
+)]
+pub async fn revoke_user_role(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path((id, role)): Path<(Uuid, String)>,
+) -> Result<StatusCode, ApiError> {
+    find_user(&state, id).await?;
+    state.authz.revoke_role(id, &role).await?;
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, user_id = %id, role = %role, "Role revoked");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Suspend, delete or grant a role to many users at once. The work runs in
+/// the background; poll the returned operation for per-user results.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/bulk",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    request_body = BulkUserRequest,
+    responses(
+        (status = 202, description = "Operation queued; its URL is in `Location`", body = OperationResponse),
+        (status = 400, description = "Unknown action, invalid role, or not exactly one of `ids`, `filter` and `segment`", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "Segment not found", body = ErrorResponse)
+    )
+)]
+pub async fn bulk_user_action(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Json(payload): Json<BulkUserRequest>,
+) -> Result<(StatusCode, [(header::HeaderName, String); 1], Json<OperationResponse>), ApiError> {
+    let action = match (payload.action.as_str(), payload.role) {
+        ("suspend", None) => BulkUserAction::Suspend,
+        ("delete", None) => BulkUserAction::Delete,
+        ("add-role", Some(role)) => BulkUserAction::AddRole { role },
+        ("add-role", None) => return Err(ApiError::bad_request("add-role needs a `role`")),
+        ("suspend" | "delete", Some(_)) => return Err(ApiError::bad_request("`role` only applies to add-role")),
+        (action, _) => return Err(ApiError::bad_request(format!("Unknown bulk action '{}'", action))),
+    };
+    let target = match (payload.ids, payload.filter, payload.segment) {
+        (Some(ids), None, None) => BulkTarget::Ids(ids),
+        (None, Some(criteria), None) => BulkTarget::Filter(criteria.try_into()?),
+        (None, None, Some(segment)) => BulkTarget::Filter(state.segments.get(segment).await?.filter),
+        _ => return Err(ApiError::bad_request("Give exactly one of `ids`, `filter` and `segment`")),
+    };
+
+    let operation = state.bulk_users.submit(admin_id(&claims)?, action, target).await?;
+    let location = format!("/api/v1/admin/operations/{}", operation.id);
+    Ok((StatusCode::ACCEPTED, [(header::LOCATION, location)], Json(operation.into())))
+}
+
+/// Create users from CSV (a header naming `username`, `email` and `password`)
+/// or NDJSON. Rows are validated like registrations and inserted in batched
+/// transactions; bad rows are reported by line without stopping the import.
+/// Inputs up to `imports.inline_max_bytes` are imported during the request;
+/// larger ones run in the background as an operation.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/import",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    request_body(content = String, description = "CSV or NDJSON rows", content_type = "text/csv"),
+    responses(
+        (status = 200, description = "Imported during the request", body = ImportSummaryResponse),
+        (status = 202, description = "Operation queued; its URL is in `Location`", body = OperationResponse),
+        (status = 400, description = "CSV header without the required columns, or not UTF-8", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 413, description = "Larger than `imports.max_bytes`", body = ErrorResponse),
+        (status = 415, description = "Neither text/csv nor application/x-ndjson", body = ErrorResponse)
+    )
+)]
+pub async fn import_users(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    headers: HeaderMap,
+    body: Body,
+) -> Result<Response, ApiError> {
+    let admin_id = admin_id(&claims)?;
+    let format = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(ImportFormat::from_content_type)
+        .ok_or_else(|| {
+            ApiError::new(
+                StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                "UNSUPPORTED_MEDIA_TYPE",
+                "Send text/csv or application/x-ndjson",
+            )
+        })?;
+    let length = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    let imports = &state.user_imports;
+    let too_large = || {
+        ApiError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "PAYLOAD_TOO_LARGE",
+            format!("Imports are limited to {} bytes", imports.max_bytes()),
+        )
+    };
+
+    if !imports.runs_inline(length) {
+        let limit = usize::try_from(imports.max_bytes()).unwrap_or(usize::MAX);
+        let input = axum::body::to_bytes(body, limit).await.map_err(|_| too_large())?;
+        let operation = imports.submit(admin_id, format, input.to_vec()).await?;
+        let location = format!("/api/v1/admin/operations/{}", operation.id);
+        return Ok((StatusCode::ACCEPTED, [(header::LOCATION, location)], Json(OperationResponse::from(operation))).into_response());
+    }
+
+    // Rows are imported as their lines arrive
+    let mut run = imports.start(format);
+    let mut stream = body.into_data_stream();
+    let mut pending = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| ApiError::bad_request(format!("Failed to read the input: {}", e)))?;
+        pending.extend_from_slice(&chunk);
+        while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            run.push_line(utf8(&line)?).await?;
+        }
+    }
+    if !pending.is_empty() {
+        run.push_line(utf8(&pending)?).await?;
+    }
+    let summary = ImportSummary::from(run.finish().await?);
+
+    tracing::info!(
+        target: "audit",
+        %admin_id,
+        total = summary.total,
+        imported = summary.imported,
+        failed = summary.errors.len(),
+        "Users imported"
+    );
+    Ok(Json(ImportSummaryResponse::from(summary)).into_response())
+}
+
+fn utf8(line: &[u8]) -> Result<&str, ApiError> {
+    std::str::from_utf8(line).map_err(|_| ApiError::bad_request("The input is not UTF-8"))
+}
+
+/// Progress and per-item results of a background operation
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/operations/{id}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Operation ID")),
+    responses(
+        (status = 200, description = "The operation", body = OperationResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "Operation not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_operation(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<OperationResponse>, ApiError> {
+    let operation = state
+        .operations
+        .get(id)
+        .await?
+        // Other tenants' operations do not exist for this caller
+        .filter(|operation| operation.tenant_id == tenancy::current_tenant())
+        .ok_or_else(|| ApiError::not_found(format!("Operation with id {} not found", id)))?;
+    Ok(Json(operation.into()))
+}
+
+/// Notes on a user: the team's and your own private ones, newest first
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/users/{id}/notes",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "User ID"),
+        ("page" = Option<u32>, Query, description = "Page number (default: 1); pages starting past `pagination.max_offset` rows are rejected"),
+        ("per_page" = Option<u32>, Query, description = "Items per page (default: 20, max: 100)")
+    ),
+    responses(
+        (status = 200, description = "Notes", body = NotesResponse, headers(("link" = String, description = "RFC 5988 links to the first, prev, next and last pages"))),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
+pub async fn list_user_notes(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    Query(params): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+) -> Result<(PageLinks, Json<NotesResponse>), ApiError> {
+    let page = state.user_notes.list(admin_id(&claims)?, id, &params).await?;
+    let links = PageLinks::new(&uri, &page);
+
+    Ok((links, Json(Paginated::from(page))))
+}
+
+/// Leave a note on a user
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/users/{id}/notes",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "User ID")),
+    request_body = CreateNoteRequest,
+    responses(
+        (status = 201, description = "Note added", body = NoteResponse),
+        (status = 400, description = "Unknown visibility", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse)
+    )
+)]
+pub async fn create_user_note(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    ValidatedJson(request): ValidatedJson<CreateNoteRequest>,
+) -> Result<(StatusCode, Json<NoteResponse>), ApiError> {
+    let visibility = parse_visibility(request.visibility.as_deref())?.unwrap_or_default();
+    let note = state
+        .user_notes
+        .create(admin_id(&claims)?, id, request.body, visibility)
+        .await?;
+
+    tracing::info!(
+        target: "audit",
+        admin_id = %claims.sub,
+        user_id = %id,
+        note_id = %note.id,
+        visibility = note.visibility.as_str(),
+        "User note added"
+    );
+    Ok((StatusCode::CREATED, Json(note.into())))
+}
+
+/// Edit one of your notes
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/users/{id}/notes/{note_id}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "User ID"),
+        ("note_id" = String, Path, description = "Note ID")
+    ),
+    request_body = UpdateNoteRequest,
+    responses(
+        (status = 200, description = "Note updated", body = NoteResponse),
+        (status = 400, description = "Unknown visibility, or not its author", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User or note not found", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse)
+    )
+)]
+pub async fn update_user_note(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path((id, note_id)): Path<(Uuid, Uuid)>,
+    ValidatedJson(request): ValidatedJson<UpdateNoteRequest>,
+) -> Result<Json<NoteResponse>, ApiError> {
+    let visibility = parse_visibility(request.visibility.as_deref())?;
+    let note = state
+        .user_notes
+        .update(admin_id(&claims)?, id, note_id, request.body, visibility)
+        .await?;
+
+    tracing::info!(
+        target: "audit",
+        admin_id = %claims.sub,
+        user_id = %id,
+        note_id = %note_id,
+        visibility = note.visibility.as_str(),
+        "User note edited"
+    );
+    Ok(Json(note.into()))
+}
+
+/// Delete one of your notes
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/users/{id}/notes/{note_id}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "User ID"),
+        ("note_id" = String, Path, description = "Note ID")
+    ),
+    responses(
+        (status = 204, description = "Note deleted"),
+        (status = 400, description = "Not its author", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User or note not found", body = ErrorResponse)
+    )
+)]
+pub async fn delete_user_note(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path((id, note_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    state.user_notes.delete(admin_id(&claims)?, id, note_id).await?;
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, user_id = %id, note_id = %note_id, "User note deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Saved segments of the current tenant
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/segments",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Segments", body = SegmentsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse)
+    )
+)]
+pub async fn list_segments(State(state): State<Arc<AppState>>) -> Result<Json<SegmentsResponse>, ApiError> {
+    let segments = state.segments.list().await?;
+    Ok(Json(SegmentsResponse {
+        items: segments.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// Save a user search as a segment
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/segments",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    request_body = CreateSegmentRequest,
+    responses(
+        (status = 201, description = "Segment saved", body = SegmentResponse),
+        (status = 400, description = "Unknown status or invalid tag", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 409, description = "A segment with this name exists", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse)
+    )
+)]
+pub async fn create_segment(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    ValidatedJson(request): ValidatedJson<CreateSegmentRequest>,
+) -> Result<(StatusCode, Json<SegmentResponse>), ApiError> {
+    let filter = request.filter.try_into()?;
+    let segment = state.segments.create(admin_id(&claims)?, request.name, filter).await?;
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, segment_id = %segment.id, name = %segment.name, "User segment saved");
+    Ok((StatusCode::CREATED, Json(segment.into())))
+}
+
+/// A saved segment
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/segments/{id}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Segment ID")),
+    responses(
+        (status = 200, description = "The segment", body = SegmentResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "Segment not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_segment(State(state): State<Arc<AppState>>, Path(id): Path<Uuid>) -> Result<Json<SegmentResponse>, ApiError> {
+    Ok(Json(state.segments.get(id).await?.into()))
+}
+
+/// Delete a segment; bulk actions already started on it are not affected
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/segments/{id}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Segment ID")),
+    responses(
+        (status = 204, description = "Segment deleted"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "Segment not found", body = ErrorResponse)
+    )
+)]
+pub async fn delete_segment(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.segments.delete(id).await?;
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, segment_id = %id, "User segment deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Users currently matching a segment
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/segments/{id}/users",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Segment ID"),
+        ("page" = Option<u32>, Query, description = "Page number (default: 1); pages starting past `pagination.max_offset` rows are rejected"),
+        ("per_page" = Option<u32>, Query, description = "Items per page (default: 20, max: 100)")
+    ),
+    responses(
+        (status = 200, description = "Users", body = AdminUsersResponse, headers(("link" = String, description = "RFC 5988 links to the first, prev, next and last pages"))),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "Segment not found", body = ErrorResponse)
+    )
+)]
+pub async fn list_segment_users(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+) -> Result<(PageLinks, Json<AdminUsersResponse>), ApiError> {
+    let mut page = state.segments.members(id, &params).await?;
+    let links = PageLinks::new(&uri, &page);
+
+    let items = admin_user_responses(&state, std::mem::take(&mut page.items)).await?;
+    Ok((links, Json(Paginated::new(items, page))))
+}
+
+/// A user's or address's rate limit consumption
+///
+/// Counters are kept per instance, so this shows the instance that answers.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/rate-limits",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(
+        ("user" = Option<String>, Query, description = "User ID"),
+        ("ip" = Option<String>, Query, description = "Client IP address")
+    ),
+    responses(
+        (status = 200, description = "Open windows and overrides", body = RateLimitUsageResponse),
+        (status = 400, description = "Neither or both of user and ip, or an invalid address", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse)
+    )
+)]
+pub async fn get_rate_limit_usage(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<RateLimitSubjectQuery>,
+) -> Result<Json<RateLimitUsageResponse>, ApiError> {
+    let subject = query.try_into()?;
+    let usage = state.rate_limits.usage(&subject);
+    Ok(Json(RateLimitUsageResponse::new(&subject, usage)))
+}
+
+/// Start a user's or address's rate limit windows over
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/rate-limits/reset",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    request_body = RateLimitSubjectQuery,
+    responses(
+        (status = 200, description = "Counters reset on this instance", body = RateLimitResetResponse),
+        (status = 400, description = "Neither or both of user and ip, or an invalid address", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse)
+    )
+)]
+pub async fn reset_rate_limits(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Json(request): Json<RateLimitSubjectQuery>,
+) -> Result<Json<RateLimitResetResponse>, ApiError> {
+    let subject = request.try_into()?;
+    let reset = state.rate_limits.reset_counters(&subject);
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, %subject, reset, "Rate limits reset");
+    Ok(Json(RateLimitResetResponse { reset }))
+}
+
+/// Per-query latency histograms of this instance since startup or the last reset
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/db-stats",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("limit" = Option<usize>, Query, description = "Query shapes to list, the most total time first (default: 50)")),
+    responses(
+        (status = 200, description = "Query statistics", body = DbStatsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse)
+    )
+)]
+pub async fn get_db_stats(Query(query): Query<DbStatsQuery>) -> Json<DbStatsResponse> {
+    let registry = query_stats();
+    let queries = registry.snapshot().into_iter().take(query.limit.unwrap_or(50));
+    Json(DbStatsResponse {
+        slow_query_ms: registry.slow_threshold().map(|t| t.as_millis() as u64),
+        untracked_calls: registry.untracked(),
+        queries: queries.map(Into::into).collect(),
+    })
+}
+
+/// Clear this instance's query statistics
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/db-stats",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Statistics cleared"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse)
+    )
+)]
+pub async fn reset_db_stats(AuthUser(claims): AuthUser) -> StatusCode {
+    query_stats().reset();
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, "Query statistics reset");
+    StatusCode::NO_CONTENT
+}
+
+/// Active rate limit overrides
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/rate-limits/overrides",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Overrides", body = RateLimitOverridesResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse)
+    )
+)]
+pub async fn list_rate_limit_overrides(State(state): State<Arc<AppState>>) -> Json<RateLimitOverridesResponse> {
+    Json(RateLimitOverridesResponse {
+        items: state.rate_limits.overrides().into_iter().map(Into::into).collect(),
+    })
+}
+
+/// Grant a user or address a different rate limit for a while
+///
+/// The limit replaces the configured one for every check of the subject in
+/// `scope` (all scopes when absent) until it expires or is deleted.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/rate-limits/overrides",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    request_body = CreateRateLimitOverrideRequest,
+    responses(
+        (status = 201, description = "Override granted", body = RateLimitOverrideResponse),
+        (status = 400, description = "Neither or both of user and ip, or an invalid address or scope", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse)
+    )
+)]
+pub async fn create_rate_limit_override(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    ValidatedJson(request): ValidatedJson<CreateRateLimitOverrideRequest>,
+) -> Result<(StatusCode, Json<RateLimitOverrideResponse>), ApiError> {
+    let subject = RateLimitSubjectQuery {
+        user: request.user,
+        ip: request.ip,
+    }
+    .try_into()?;
+    let ttl = std::time::Duration::from_secs(request.expires_in_minutes * 60);
+    let entry = state
+        .rate_limits
+        .grant(admin_id(&claims)?, &subject, request.scope, request.limit, ttl, request.reason)
+        .await?;
+
+    tracing::info!(
+        target: "audit",
+        admin_id = %claims.sub,
+        override_id = %entry.id,
+        %subject,
+        scope = entry.scope.as_deref().unwrap_or("*"),
+        limit = entry.limit,
+        expires_at = %entry.expires_at,
+        "Rate limit override granted"
+    );
+    Ok((StatusCode::CREATED, Json(entry.into())))
+}
+
+/// Remove a rate limit override before it expires
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/rate-limits/overrides/{id}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Override ID")),
+    responses(
+        (status = 204, description = "Override removed"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse),
+        (status = 404, description = "Override not found", body = ErrorResponse)
+    )
+)]
+pub async fn delete_rate_limit_override(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.rate_limits.revoke(id).await?;
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, override_id = %id, "Rate limit override removed");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Every feature flag in force
+///
+/// Flags from `features.flags` are listed with source `config` unless a stored
+/// flag of the same key replaces them.
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/feature-flags",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Feature flags", body = FeatureFlagsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse)
+    )
+)]
+pub async fn list_feature_flags(State(state): State<Arc<AppState>>) -> Json<FeatureFlagsResponse> {
+    Json(FeatureFlagsResponse {
+        items: state.feature_flags.list().into_iter().map(Into::into).collect(),
+    })
+}
+
+/// A feature flag
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/feature-flags/{key}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("key" = String, Path, description = "Flag key")),
+    responses(
+        (status = 200, description = "Feature flag", body = FeatureFlagResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse),
+        (status = 404, description = "Flag not found", body = ErrorResponse)
+    )
+)]
+pub async fn get_feature_flag(
+    State(state): State<Arc<AppState>>,
+    Path(key): Path<String>,
+) -> Result<Json<FeatureFlagResponse>, ApiError> {
+    let flag = state
+        .feature_flags
+        .get(&key)
+        .ok_or_else(|| ApiError::not_found("Feature flag not found"))?;
+    Ok(Json(flag.into()))
+}
+
+/// Create a feature flag
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/feature-flags",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    request_body = FeatureFlagRequest,
+    responses(
+        (status = 201, description = "Flag created", body = FeatureFlagResponse),
+        (status = 400, description = "Invalid key", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse),
+        (status = 409, description = "A flag with the key exists", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse)
+    )
+)]
+pub async fn create_feature_flag(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    ValidatedJson(request): ValidatedJson<FeatureFlagRequest>,
+) -> Result<(StatusCode, Json<FeatureFlagResponse>), ApiError> {
+    let flag = state.feature_flags.create(request.into()).await?;
+
+    tracing::info!(
+        target: "audit",
+        admin_id = %claims.sub,
+        flag = %flag.key,
+        enabled = flag.enabled,
+        percentage = flag.rollout.percentage,
+        "Feature flag created"
+    );
+    Ok((StatusCode::CREATED, Json((flag, FlagSource::Stored).into())))
+}
+
+/// Replace a feature flag
+///
+/// Editing a flag from `features.flags` stores a copy that takes precedence
+/// over the configured one.
+#[utoipa::path(
+    put,
+    path = "/api/v1/admin/feature-flags/{key}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("key" = String, Path, description = "Flag key")),
+    request_body = FeatureFlagRequest,
+    responses(
+        (status = 200, description = "Flag updated", body = FeatureFlagResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse),
+        (status = 404, description = "Flag not found", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse)
+    )
+)]
+pub async fn update_feature_flag(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(key): Path<String>,
+    ValidatedJson(request): ValidatedJson<FeatureFlagRequest>,
+) -> Result<Json<FeatureFlagResponse>, ApiError> {
+    let flag = state.feature_flags.update(&key, request.into()).await?;
+
+    tracing::info!(
+        target: "audit",
+        admin_id = %claims.sub,
+        flag = %flag.key,
+        enabled = flag.enabled,
+        percentage = flag.rollout.percentage,
+        "Feature flag updated"
+    );
+    Ok(Json((flag, FlagSource::Stored).into()))
+}
+
+/// Delete a stored feature flag
+///
+/// A flag of the same key in `features.flags` is in force again afterwards.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/feature-flags/{key}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("key" = String, Path, description = "Flag key")),
+    responses(
+        (status = 204, description = "Flag deleted"),
+        (status = 400, description = "The flag only exists in configuration", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse),
+        (status = 404, description = "Flag not found", body = ErrorResponse)
+    )
+)]
+pub async fn delete_feature_flag(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(key): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    state.feature_flags.delete(&key).await?;
+
+    tracing::info!(target: "audit", admin_id = %claims.sub, flag = %key, "Feature flag deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Every API client
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/api-clients",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "API clients, without their secrets", body = ApiClientsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse)
+    )
+)]
+pub async fn list_api_clients(State(state): State<Arc<AppState>>) -> Result<Json<ApiClientsResponse>, ApiError> {
+    let clients = state.request_signing.list().await?;
+    Ok(Json(ApiClientsResponse {
+        items: clients.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// Register an API client
+///
+/// The response carries the client's secret, which is not shown again.
+/// Requests signed with it are made with the client's roles.
+#[utoipa::path(
+    post,
+    path = "/api/v1/admin/api-clients",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    request_body = CreateApiClientRequest,
+    responses(
+        (status = 201, description = "Client registered", body = CreatedApiClientResponse),
+        (status = 400, description = "Unknown role", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse)
+    )
+)]
+pub async fn create_api_client(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    ValidatedJson(request): ValidatedJson<CreateApiClientRequest>,
+) -> Result<(StatusCode, Json<CreatedApiClientResponse>), ApiError> {
+    let client = state
+        .request_signing
+        .create_client(&request.name, &request.roles, admin_id(&claims)?)
+        .await?;
+    let secret = client.secret.clone();
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedApiClientResponse {
+            client: client.into(),
+            secret,
+        }),
+    ))
+}
+
+/// Revoke an API client
+#[utoipa::path(
+    delete,
+    path = "/api/v1/admin/api-clients/{id}",
+    tag = "Admin",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Client ID")),
+    responses(
+        (status = 204, description = "Client revoked"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role in the default tenant required", body = ErrorResponse),
+        (status = 404, description = "Client not found", body = ErrorResponse)
+    )
+)]
+pub async fn delete_api_client(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.request_signing.revoke(id, admin_id(&claims)?).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn admin_user_response(state: &AppState, user: User) -> Result<AdminUserResponse, ApiError> {
+    let mut responses = admin_user_responses(state, vec![user]).await?;
+    Ok(responses.remove(0))
+}
+
+/// Users with their email suppression status and tags, looked up in one query each
+async fn admin_user_responses(state: &AppState, users: Vec<User>) -> Result<Vec<AdminUserResponse>, ApiError> {
+    let mut tags = state.tags.tags_of(&users).await?;
+    let emails: Vec<String> = users.iter().map(|u| normalize_email(&u.email)).collect();
+    let mut suppressions: HashMap<String, EmailSuppression> = state
+        .email_suppressions
+        .find_many(&emails)
+        .await?
+        .into_iter()
+        .map(|s| (s.email.clone(), s))
+        .collect();
+    Ok(users
+        .into_iter()
+        .map(|user| {
+            let suppression = suppressions.remove(&normalize_email(&user.email));
+            let tags = tags.remove(&user.id).unwrap_or_default();
+            AdminUserResponse::new(user, suppression, tags)
+        })
+        .collect())
+}
+
+fn admin_id(claims: &domain::Claims) -> Result<Uuid, ApiError> {
+    claims.sub.parse().map_err(|_| ApiError::internal("Invalid user ID in token"))
+}
+
+async fn find_user(state: &AppState, id: Uuid) -> Result<User, ApiError> {
+    state
+        .user_service
+        .get_user(id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("User with id {} not found", id)))
+}
+
+fn parse_visibility(visibility: Option<&str>) -> Result<Option<NoteVisibility>, ApiError> {
+    visibility
+        .map(|v| NoteVisibility::parse(v).ok_or_else(|| ApiError::bad_request(format!("Unknown note visibility '{}'", v))))
+        .transpose()
+}
@@ -0,0 +1,106 @@
+use std::collections::BTreeSet;
+
+use utoipa::openapi::OpenApi;
+
+// ============================================================================
+// Documentation Audiences
+// ============================================================================
+
+/// Who an OpenAPI document is written for. Each audience sees its own
+/// operations plus those of the audiences before it, so the internal
+/// document is the complete one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DocAudience {
+    /// Anyone with an account
+    Public,
+    /// Integrators such as email providers calling back into the API
+    Partner,
+    /// Operators and admin tooling
+    Internal,
+}
+
+impl DocAudience {
+    pub const ALL: [DocAudience; 3] = [DocAudience::Public, DocAudience::Partner, DocAudience::Internal];
+
+    /// URL segment, e.g. `/api-docs/public/openapi.json`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DocAudience::Public => "public",
+            DocAudience::Partner => "partner",
+            DocAudience::Internal => "internal",
+        }
+    }
+
+    /// Audience of the routes under an operation tag. Unknown tags stay
+    /// internal, so a new route is never published by accident.
+    pub fn of_tag(tag: &str) -> Self {
+        match tag {
+            "Authentication" | "Users" | "Organizations" | "Support" | "Health" => DocAudience::Public,
+            "Email" => DocAudience::Partner,
+            _ => DocAudience::Internal,
+        }
+    }
+
+    /// Where the document for this audience is served
+    pub fn doc_url(&self) -> String {
+        format!("/api-docs/{}/openapi.json", self.as_str())
+    }
+}
+
+/// `doc` reduced to the operations `audience` may see. Tags and component
+/// schemas that only those hidden operations used are dropped too, so the
+/// document doesn't leak internal DTOs.
+pub fn audience_doc(doc: &OpenApi, audience: DocAudience) -> OpenApi {
+    let mut doc = doc.clone();
+    for item in doc.paths.paths.values_mut() {
+        item.operations.retain(|_, operation| {
+            // Untagged operations have no audience yet: internal
+            let tags = operation.tags.as_deref().unwrap_or_default();
+            let needed = tags.iter().map(|tag| DocAudience::of_tag(tag)).max().unwrap_or(DocAudience::Internal);
+            needed <= audience
+        });
+    }
+    doc.paths.paths.retain(|_, item| !item.operations.is_empty());
+
+    let used_tags: BTreeSet<&String> = doc
+        .paths
+        .paths
+        .values()
+        .flat_map(|item| item.operations.values())
+        .flat_map(|operation| operation.tags.iter().flatten())
+        .collect();
+    if let Some(tags) = doc.tags.as_mut() {
+        tags.retain(|tag| used_tags.contains(&tag.name));
+    }
+
+    if let Some(components) = doc.components.as_mut() {
+        let mut pending = schema_refs(&serde_json::to_value(&doc.paths).unwrap_or_default());
+        pending.extend(schema_refs(&serde_json::to_value(&components.responses).unwrap_or_default()));
+        let mut used = BTreeSet::new();
+        while let Some(name) = pending.pop() {
+            if let Some(schema) = components.schemas.get(&name) {
+                if used.insert(name) {
+                    pending.extend(schema_refs(&serde_json::to_value(schema).unwrap_or_default()));
+                }
+            }
+        }
+        components.schemas.retain(|name, _| used.contains(name));
+    }
+    doc
+}
+
+/// Names of the component schemas `value` refers to
+fn schema_refs(value: &serde_json::Value) -> Vec<String> {
+    const PREFIX: &str = "#/components/schemas/";
+    match value {
+        serde_json::Value::Object(map) => map
+            .iter()
+            .flat_map(|(key, value)| match (key.as_str(), value.as_str()) {
+                ("$ref", Some(target)) => target.strip_prefix(PREFIX).map(str::to_string).into_iter().collect(),
+                _ => schema_refs(value),
+            })
+            .collect(),
+        serde_json::Value::Array(items) => items.iter().flat_map(schema_refs).collect(),
+        _ => Vec::new(),
+    }
+}
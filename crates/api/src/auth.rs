@@ -1,17 +1,25 @@
+use application::activity::{LoginClient, LoginRecord};
+use application::availability::{Availability, UsernameAvailability};
+use application::devices::Device;
 use axum::{
-    extract::{rejection::JsonRejection, FromRequest, Request, State},
-    http::StatusCode,
-    routing::post,
-    Json, Router,
+    extract::{Path, Query, Request, State},
+    http::{header::USER_AGENT, HeaderMap, StatusCode},
+    middleware as axum_mw,
+    routing::{get, post},
+    Router,
 };
+use chrono::{DateTime, Utc};
+use domain::TokenPair;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use utoipa::ToSchema;
 use validator::Validate;
 
-use application::AuthService;
-use crate::error::ApiError;
+use crate::conditional;
+use crate::error::{ApiError, FieldError};
+use crate::middleware::{optional_jwt_auth, AuthUser, ClientIp, OptionalAuthUser};
 use crate::AppState;
+use crate::server_timing::Json;
 
 // ============================================================================
 // Validated JSON Extractor
@@ -40,27 +48,42 @@ where
                 .await
                 .map_err(|e| ApiError::bad_request(format!("Failed to read body: {}", e)))?;
 
-            let value: T = serde_json::from_slice(&bytes)
-                .map_err(|e| ApiError::bad_request(format!("Invalid JSON: {}", e)))?;
-
-            value.validate().map_err(|e| {
-                let errors: Vec<String> = e
-                    .field_errors()
-                    .into_iter()
-                    .flat_map(|(field, errors)| {
-                        errors.iter().map(move |err| {
-                            format!("{}: {}", field, err.message.clone().unwrap_or_default())
-                        })
-                    })
-                    .collect();
-                ApiError::bad_request(errors.join(", "))
-            })?;
-
-            Ok(ValidatedJson(value))
+            tracing::debug!(body = %crate::logging::redacted_body(&bytes), "request body");
+
+            parse_validated(&bytes).map(ValidatedJson)
         })
     }
 }
 
+/// Deserialize and validate a JSON payload, mapping failures to `ApiError`.
+/// Shared by `ValidatedJson` and the fuzz targets.
+pub fn parse_validated<T>(bytes: &[u8]) -> Result<T, ApiError>
+where
+    T: serde::de::DeserializeOwned + Validate,
+{
+    let value: T = serde_json::from_slice(bytes)
+        .map_err(|e| ApiError::bad_request(format!("Invalid JSON: {}", e)))?;
+
+    value.validate().map_err(|e| {
+        let mut errors: Vec<FieldError> = e
+            .field_errors()
+            .into_iter()
+            .flat_map(|(field, errors)| {
+                errors.iter().map(move |err| FieldError {
+                    field: field.to_string(),
+                    code: err.code.to_string(),
+                    message: err.message.as_deref().unwrap_or("is invalid").to_string(),
+                })
+            })
+            .collect();
+        // Field order from the validator is unspecified; keep responses stable
+        errors.sort_by(|a, b| (&a.field, &a.code).cmp(&(&b.field, &b.code)));
+        ApiError::validation(errors)
+    })?;
+
+    Ok(value)
+}
+
 // ============================================================================
 // Request/Response DTOs with Validation
 // ============================================================================
@@ -93,6 +116,33 @@ pub struct LoginRequest {
     #[validate(length(min = 1, message = "cannot be empty"))]
     #[schema(example = "securepassword123")]
     pub password: String,
+    /// Stable identifier the client keeps for this device; the sign-in is
+    /// listed under `GET /me/devices`
+    #[validate(length(min = 1, max = 512, message = "must be 1 to 512 characters"))]
+    #[schema(example = "b3f1c0de-device-7")]
+    pub device_fingerprint: Option<String>,
+    /// Trust this device for `account.trusted_device_days`; needs `device_fingerprint`
+    #[serde(default)]
+    pub remember_device: bool,
+}
+
+/// Request body for exchanging a refresh token
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct RefreshRequest {
+    /// Refresh token from the last login or refresh; valid once
+    #[validate(length(min = 1, message = "cannot be empty"))]
+    pub refresh_token: String,
+}
+
+/// Request body for changing the current user's password
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct ChangePasswordRequest {
+    #[validate(length(min = 1, message = "cannot be empty"))]
+    pub current_password: String,
+    /// New password (8-128 characters)
+    #[validate(length(min = 8, max = 128, message = "must be 8-128 characters"))]
+    #[schema(min_length = 8)]
+    pub new_password: String,
 }
 
 /// Response after successful registration
@@ -114,6 +164,73 @@ pub struct TokenResponse {
     /// Token expiration time in seconds
     #[schema(example = 86400)]
     pub expires_in: i64,
+    /// Single-use token for `/auth/refresh`; absent when refresh tokens are disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+}
+
+impl From<TokenPair> for TokenResponse {
+    fn from(token: TokenPair) -> Self {
+        Self {
+            access_token: token.access_token,
+            token_type: token.token_type,
+            expires_in: token.expires_in,
+            refresh_token: token.refresh_token,
+        }
+    }
+}
+
+/// Values to check before registering; at least one
+#[derive(Deserialize)]
+pub struct AvailabilityQuery {
+    pub username: Option<String>,
+    pub email: Option<String>,
+}
+
+/// Whether a username is free, with free alternatives when it isn't
+#[derive(Serialize, ToSchema)]
+pub struct UsernameAvailabilityResponse {
+    /// As it would be registered (trimmed)
+    #[schema(example = "john_doe")]
+    pub value: String,
+    pub available: bool,
+    /// Up to 3 free usernames close to the requested one; empty when it is available
+    #[schema(example = json!(["john_doe2", "john_doe3", "john_doe417"]))]
+    pub suggestions: Vec<String>,
+}
+
+impl From<UsernameAvailability> for UsernameAvailabilityResponse {
+    fn from(username: UsernameAvailability) -> Self {
+        Self {
+            value: username.username,
+            available: username.available,
+            suggestions: username.suggestions,
+        }
+    }
+}
+
+/// Whether an email address can register
+#[derive(Serialize, ToSchema)]
+pub struct EmailAvailabilityResponse {
+    pub available: bool,
+}
+
+/// Availability of the values asked about; the others are left out
+#[derive(Serialize, ToSchema)]
+pub struct AvailabilityResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<UsernameAvailabilityResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<EmailAvailabilityResponse>,
+}
+
+impl From<Availability> for AvailabilityResponse {
+    fn from(availability: Availability) -> Self {
+        Self {
+            username: availability.username.map(Into::into),
+            email: availability.email.map(|available| EmailAvailabilityResponse { available }),
+        }
+    }
 }
 
 /// User data transfer object
@@ -134,10 +251,18 @@ pub struct UserDto {
 // Routes
 // ============================================================================
 
-pub fn auth_routes() -> Router<Arc<AppState>> {
+pub fn auth_routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
+    let guest_routes = Router::new()
+        .route("/", post(start_guest_session))
+        .route("/upgrade", post(upgrade_guest))
+        .route_layer(axum_mw::from_fn_with_state(state, optional_jwt_auth));
+
     Router::new()
         .route("/register", post(register))
         .route("/login", post(login))
+        .route("/refresh", post(refresh))
+        .route("/availability", get(check_availability))
+        .nest("/guest", guest_routes)
 }
 
 // ============================================================================
@@ -147,13 +272,14 @@ pub fn auth_routes() -> Router<Arc<AppState>> {
 /// Register a new user
 #[utoipa::path(
     post,
-    path = "/auth/register",
+    path = "/api/v1/auth/register",
     tag = "Authentication",
     request_body = RegisterRequest,
     responses(
         (status = 201, description = "User registered successfully", body = AuthResponse),
-        (status = 400, description = "Validation error"),
-        (status = 409, description = "Email already registered")
+        (status = 400, description = "Password does not meet the policy", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse),
+        (status = 409, description = "Email already registered", body = ErrorResponse)
     )
 )]
 pub async fn register(
@@ -170,8 +296,8 @@ pub async fn register(
         Json(AuthResponse {
             user: UserDto {
                 id: user.id.to_string(),
-                username: user.username,
-                email: user.email,
+                username: user.username.into(),
+                email: user.email.into(),
             },
         }),
     ))
@@ -180,28 +306,394 @@ pub async fn register(
 /// Login and get JWT token
 #[utoipa::path(
     post,
-    path = "/auth/login",
+    path = "/api/v1/auth/login",
     tag = "Authentication",
     request_body = LoginRequest,
     responses(
         (status = 200, description = "Login successful", body = TokenResponse),
-        (status = 401, description = "Invalid credentials")
+        (status = 400, description = "remember_device without device_fingerprint", body = ErrorResponse),
+        (status = 401, description = "Invalid credentials", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse)
     )
 )]
 pub async fn login(
     State(state): State<Arc<AppState>>,
+    ClientIp(client_ip): ClientIp,
+    headers: HeaderMap,
     ValidatedJson(payload): ValidatedJson<LoginRequest>,
 ) -> Result<Json<TokenResponse>, ApiError> {
+    if payload.remember_device && payload.device_fingerprint.is_none() {
+        return Err(ApiError::bad_request("remember_device needs a device_fingerprint"));
+    }
+
+    let client = LoginClient {
+        ip: client_ip,
+        user_agent: headers.get(USER_AGENT).and_then(|v| v.to_str().ok()).map(str::to_string),
+    };
     let token = state
         .auth_service
-        .login(payload.email, payload.password)
+        .login(payload.email, payload.password, client)
         .await?;
 
-    Ok(Json(TokenResponse {
-        access_token: token.access_token,
-        token_type: token.token_type,
-        expires_in: token.expires_in,
-    }))
+    if let Some(fingerprint) = payload.device_fingerprint {
+        let claims = state.token_service.validate(&token.access_token)?;
+        let user_id = claims.sub.parse::<uuid::Uuid>()
+            .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+        let name = headers.get(USER_AGENT).and_then(|v| v.to_str().ok());
+        state
+            .devices
+            .record_login(user_id, &fingerprint, name, payload.remember_device, Utc::now())
+            .await?;
+    }
+
+    Ok(Json(token.into()))
 }
 
+/// Exchange a refresh token for a new token pair. Each refresh token works
+/// once; presenting one again revokes every token issued since its login.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/refresh",
+    tag = "Authentication",
+    request_body = RefreshRequest,
+    responses(
+        (status = 200, description = "New access and refresh token", body = TokenResponse),
+        (status = 401, description = "Invalid, expired, revoked or reused refresh token", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse)
+    )
+)]
+pub async fn refresh(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(payload): ValidatedJson<RefreshRequest>,
+) -> Result<Json<TokenResponse>, ApiError> {
+    let token = state.auth_service.refresh(payload.refresh_token).await?;
+    Ok(Json(token.into()))
+}
+
+/// Check whether a username and/or email can register
+///
+/// Meant for sign-up forms. The answer is a boolean per value: nothing about
+/// the account holding it. Checks are limited per client IP
+/// (`rate_limit.availability_per_client` per `availability_window_secs`).
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/availability",
+    tag = "Authentication",
+    params(
+        ("username" = Option<String>, Query, description = "Username to check"),
+        ("email" = Option<String>, Query, description = "Email address to check")
+    ),
+    responses(
+        (status = 200, description = "Availability of each value given", body = AvailabilityResponse),
+        (status = 400, description = "Neither value given, or one is malformed", body = ErrorResponse),
+        (status = 429, description = "Too many checks from this client", body = ErrorResponse)
+    )
+)]
+pub async fn check_availability(
+    State(state): State<Arc<AppState>>,
+    ClientIp(client_ip): ClientIp,
+    Query(query): Query<AvailabilityQuery>,
+) -> Result<Json<AvailabilityResponse>, ApiError> {
+    let availability = state
+        .availability
+        .check(client_ip.as_deref(), query.username.as_deref(), query.email.as_deref())
+        .await?;
+    Ok(Json(availability.into()))
+}
 
+/// Start a guest session
+///
+/// Returns a short-lived token (`jwt.guest_expiration_minutes`) with the
+/// `anonymous` role and a new guest id as its subject. Guest tokens are
+/// accepted where signing in is optional; `POST /auth/guest/upgrade` turns
+/// the guest into an account with the same id.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/guest",
+    tag = "Authentication",
+    responses(
+        (status = 200, description = "Guest token", body = TokenResponse)
+    )
+)]
+pub async fn start_guest_session(State(state): State<Arc<AppState>>) -> Result<Json<TokenResponse>, ApiError> {
+    let token = state.auth_service.guest().await?;
+    Ok(Json(token.into()))
+}
+
+/// Register the current guest as a user
+///
+/// Needs the guest token. The new account keeps the guest id, so anything
+/// recorded for the guest stays theirs; sign in to get a user token.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/guest/upgrade",
+    tag = "Authentication",
+    security(("bearer_auth" = [])),
+    request_body = RegisterRequest,
+    responses(
+        (status = 201, description = "Account created with the guest id", body = AuthResponse),
+        (status = 400, description = "Password does not meet the policy", body = ErrorResponse),
+        (status = 401, description = "No guest token", body = ErrorResponse),
+        (status = 409, description = "Email already registered, or the guest was already upgraded", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse)
+    )
+)]
+pub async fn upgrade_guest(
+    State(state): State<Arc<AppState>>,
+    OptionalAuthUser(claims): OptionalAuthUser,
+    ValidatedJson(payload): ValidatedJson<RegisterRequest>,
+) -> Result<(StatusCode, Json<AuthResponse>), ApiError> {
+    let Some(claims) = claims.filter(|c| c.is_anonymous()) else {
+        return Err(ApiError::unauthorized("A guest token from /auth/guest is required"));
+    };
+    let guest_id = claims.sub.parse::<uuid::Uuid>()
+        .map_err(|_| ApiError::internal("Invalid guest ID in token"))?;
+
+    let user = state
+        .auth_service
+        .upgrade_guest(guest_id, payload.username, payload.email, payload.password)
+        .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(AuthResponse {
+            user: UserDto {
+                id: user.id.to_string(),
+                username: user.username.into(),
+                email: user.email.into(),
+            },
+        }),
+    ))
+}
+
+/// Change the current user's password (clears an admin-forced reset)
+#[utoipa::path(
+    put,
+    path = "/api/v1/me/password",
+    tag = "Authentication",
+    security(("bearer_auth" = [])),
+    request_body = ChangePasswordRequest,
+    responses(
+        (status = 204, description = "Password changed"),
+        (status = 400, description = "New password does not meet the policy", body = ErrorResponse),
+        (status = 401, description = "Unauthorized or wrong current password", body = ErrorResponse),
+        (status = 412, description = "If-Match does not match the current user", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse)
+    )
+)]
+pub async fn change_password(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    headers: HeaderMap,
+    ValidatedJson(payload): ValidatedJson<ChangePasswordRequest>,
+) -> Result<StatusCode, ApiError> {
+    let user_id = claims.sub.parse::<uuid::Uuid>()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    conditional::check_user_precondition(&state, user_id, &headers).await?;
+
+    state
+        .auth_service
+        .change_password(user_id, payload.current_password, payload.new_password)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Sign the current user out everywhere
+///
+/// Invalidates every access and refresh token issued to the user so far,
+/// including the one making the request. Other instances may accept access
+/// tokens for a few more seconds (`jwt.token_version_cache_secs`).
+#[utoipa::path(
+    post,
+    path = "/api/v1/me/logout-all",
+    tag = "Authentication",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "All tokens revoked"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn logout_all(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+) -> Result<StatusCode, ApiError> {
+    let user_id = claims.sub.parse::<uuid::Uuid>()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    state.token_versions.revoke_all(user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Devices
+// ============================================================================
+
+/// A device the current user has signed in from
+#[derive(Serialize, ToSchema)]
+pub struct DeviceResponse {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: String,
+    /// `User-Agent` of the last sign-in, when one was sent
+    #[schema(example = "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_0)")]
+    pub name: Option<String>,
+    pub first_seen_at: String,
+    pub last_seen_at: String,
+    /// Whether "remember this device" is in effect
+    pub trusted: bool,
+    /// When the trust runs out
+    pub trusted_until: Option<String>,
+}
+
+impl DeviceResponse {
+    fn new(device: Device, now: DateTime<Utc>) -> Self {
+        Self {
+            id: device.id.to_string(),
+            trusted: device.is_trusted(now),
+            name: device.name,
+            first_seen_at: device.first_seen_at.to_rfc3339(),
+            last_seen_at: device.last_seen_at.to_rfc3339(),
+            trusted_until: device.trusted_until.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+/// The current user's devices, most recently used first
+#[derive(Serialize, ToSchema)]
+pub struct DevicesResponse {
+    pub items: Vec<DeviceResponse>,
+}
+
+/// List the devices the current user has signed in from
+///
+/// Devices are recorded when `POST /auth/login` carries a `device_fingerprint`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/devices",
+    tag = "Authentication",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "The user's devices", body = DevicesResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_devices(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+) -> Result<Json<DevicesResponse>, ApiError> {
+    let user_id = claims.sub.parse::<uuid::Uuid>()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let now = Utc::now();
+    let items = state
+        .devices
+        .list(user_id)
+        .await?
+        .into_iter()
+        .map(|d| DeviceResponse::new(d, now))
+        .collect();
+    Ok(Json(DevicesResponse { items }))
+}
+
+/// Forget one of the current user's devices
+///
+/// A remembered device is no longer trusted. Tokens already issued to it
+/// stay valid; use `POST /me/logout-all` to end those too.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/me/devices/{id}",
+    tag = "Authentication",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Device ID")),
+    responses(
+        (status = 204, description = "Device removed"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Device not found", body = ErrorResponse)
+    )
+)]
+pub async fn delete_device(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<uuid::Uuid>,
+) -> Result<StatusCode, ApiError> {
+    let user_id = claims.sub.parse::<uuid::Uuid>()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    state.devices.remove(user_id, id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Sign-in History
+// ============================================================================
+
+/// One sign-in of the current user
+#[derive(Serialize, ToSchema)]
+pub struct LoginResponse {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: String,
+    /// Client address, as seen by the server (or `X-Forwarded-For` when trusted)
+    #[schema(example = "203.0.113.7")]
+    pub ip: Option<String>,
+    #[schema(example = "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_0)")]
+    pub user_agent: Option<String>,
+    pub created_at: String,
+}
+
+impl From<LoginRecord> for LoginResponse {
+    fn from(login: LoginRecord) -> Self {
+        Self {
+            id: login.id.to_string(),
+            ip: login.ip,
+            user_agent: login.user_agent,
+            created_at: login.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// The current user's recent sign-ins, newest first
+#[derive(Serialize, ToSchema)]
+pub struct LoginsResponse {
+    pub items: Vec<LoginResponse>,
+}
+
+#[derive(Deserialize)]
+pub struct LoginsQuery {
+    pub limit: Option<u32>,
+}
+
+/// List the current user's recent sign-ins
+///
+/// Each successful `POST /auth/login` is recorded with the client's IP and
+/// `User-Agent`; the newest 50 are kept. Lets users spot sign-ins that
+/// weren't theirs.
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/logins",
+    tag = "Authentication",
+    security(("bearer_auth" = [])),
+    params(("limit" = Option<u32>, Query, description = "Sign-ins to return (default: 20, max: 50)")),
+    responses(
+        (status = 200, description = "Recent sign-ins", body = LoginsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_logins(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Query(query): Query<LoginsQuery>,
+) -> Result<Json<LoginsResponse>, ApiError> {
+    let user_id = claims.sub.parse::<uuid::Uuid>()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let items = state
+        .activity
+        .recent_logins(user_id, query.limit.unwrap_or(20))
+        .await?
+        .into_iter()
+        .map(LoginResponse::from)
+        .collect();
+    Ok(Json(LoginsResponse { items }))
+}
@@ -0,0 +1,144 @@
+//! Operator commands run against a database, outside the server.
+//!
+//! ```text
+//! cargo run -p api --bin ops -- anonymize --database-url postgres://.../staging_copy --confirm staging_copy
+//! cargo run -p api --bin ops -- export-queues --database-url postgres://.../app --output queues.jsonl
+//! ```
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use api::logging;
+use infrastructure::{Anonymizer, Faker, QueueBackup, QueueSnapshot};
+
+#[derive(Parser)]
+#[command(name = "ops", about = "Rust Base operator commands")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Rewrite PII (emails, names, IPs, free text) with deterministic fake data.
+    /// Run it on a restored copy only, never on production.
+    Anonymize {
+        /// Database to rewrite. Deliberately not read from DATABASE_URL.
+        #[arg(long)]
+        database_url: String,
+        /// Name of that database, typed again as a safety check
+        #[arg(long)]
+        confirm: String,
+        /// Key for the fake-data mapping; the same salt gives the same fakes
+        #[arg(long, env = "ANONYMIZE_SALT")]
+        salt: String,
+        /// Rows rewritten per transaction
+        #[arg(long, default_value_t = 1000)]
+        batch_size: u32,
+    },
+    /// Save the job queue and pending webhook deliveries to a JSON lines file,
+    /// from one consistent snapshot
+    ExportQueues {
+        #[arg(long, env = "DATABASE_URL")]
+        database_url: String,
+        /// File to write; refuses to overwrite an existing one
+        #[arg(long)]
+        output: PathBuf,
+        /// Only pending/running rows, leaving finished history behind
+        #[arg(long)]
+        unfinished: bool,
+    },
+    /// Re-import a file from export-queues. Rows whose id already exists are
+    /// skipped, so it can be re-run safely.
+    RestoreQueues {
+        #[arg(long, env = "DATABASE_URL")]
+        database_url: String,
+        #[arg(long)]
+        input: PathBuf,
+        /// Name of the target database, typed again as a safety check
+        #[arg(long)]
+        confirm: String,
+        /// Run every check and insert, then roll back
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenvy::dotenv().ok();
+    let config = shared::Config::load(&shared::LoadOptions::from_env())?;
+    logging::init(&config.log, None);
+
+    match Cli::parse().command {
+        Command::Anonymize {
+            database_url,
+            confirm,
+            salt,
+            batch_size,
+        } => {
+            let pool = infrastructure::connect_pool(&database_url, &config.database.pool).await?;
+            let anonymizer = Anonymizer::new(pool, Faker::new(salt), batch_size);
+
+            let database = anonymizer.database_name().await?;
+            if database != confirm {
+                anyhow::bail!("Connected to '{}' but --confirm says '{}'; nothing changed", database, confirm);
+            }
+
+            let report = anonymizer.run().await?;
+            for (table, rows) in report.tables {
+                println!("{:<24} {:>10} rows", table, rows);
+            }
+        }
+        Command::ExportQueues {
+            database_url,
+            output,
+            unfinished,
+        } => {
+            let pool = infrastructure::connect_pool(&database_url, &config.database.pool).await?;
+            let snapshot = QueueBackup::new(pool).export(unfinished).await?;
+
+            let file = File::options().write(true).create_new(true).open(&output)?;
+            snapshot.write_to(BufWriter::new(file))?;
+
+            // Read it back so a bad disk or full volume shows up now, not during the restore
+            QueueSnapshot::read_from(BufReader::new(File::open(&output)?))?;
+
+            println!("Exported '{}' to {}", snapshot.header.database, output.display());
+            for (table, digest) in &snapshot.header.tables {
+                println!("{:<24} {:>10} rows  sha256:{}", table, digest.rows, digest.sha256);
+            }
+        }
+        Command::RestoreQueues {
+            database_url,
+            input,
+            confirm,
+            dry_run,
+        } => {
+            let snapshot = QueueSnapshot::read_from(BufReader::new(File::open(&input)?))?;
+            let pool = infrastructure::connect_pool(&database_url, &config.database.pool).await?;
+            let backup = QueueBackup::new(pool);
+
+            let database = backup.database_name().await?;
+            if database != confirm {
+                anyhow::bail!("Connected to '{}' but --confirm says '{}'; nothing changed", database, confirm);
+            }
+
+            println!(
+                "Restoring backup of '{}' taken {}{}",
+                snapshot.header.database,
+                snapshot.header.exported_at.to_rfc3339(),
+                if dry_run { " (dry run, rolled back)" } else { "" }
+            );
+            let report = backup.restore(&snapshot, dry_run).await?;
+            for (table, inserted, skipped) in report.tables {
+                println!("{:<24} {:>10} inserted {:>10} already present", table, inserted, skipped);
+            }
+        }
+    }
+
+    Ok(())
+}
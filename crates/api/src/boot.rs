@@ -0,0 +1,252 @@
+use futures_util::future::BoxFuture;
+use futures_util::FutureExt;
+use std::fmt;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::time::{Duration, Instant};
+
+/// Longest one teardown step may take before it is abandoned
+const TEARDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+// ============================================================================
+// Phases
+// ============================================================================
+
+/// Stages of bringing the server up, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BootPhase {
+    /// Layered settings loaded and validated
+    Config,
+    /// Logging, audit export and the metrics backend
+    Telemetry,
+    /// Primary and replica pools connected
+    Database,
+    /// Pending migrations applied (`database.run_migrations`)
+    Migrations,
+    /// Services wired up, caches warmed and self-checks run
+    Caches,
+    /// Event forwarders and job workers started
+    Workers,
+    /// Sockets bound; the server accepts requests once this completes
+    Listeners,
+}
+
+impl BootPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::Telemetry => "telemetry",
+            Self::Database => "database",
+            Self::Migrations => "migrations",
+            Self::Caches => "caches",
+            Self::Workers => "workers",
+            Self::Listeners => "listeners",
+        }
+    }
+
+    /// Where to look when the phase fails
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Self::Config => "check config/*.toml, APP__* variables and --set overrides (`--print-config` shows the result)",
+            Self::Telemetry => "check log.*, telemetry.* and AUDIT_* settings and that the collectors are reachable",
+            Self::Database => "check database.url / DATABASE_URL, that Postgres is up and database.pool.acquire_timeout_secs",
+            Self::Migrations => "run `sqlx migrate info`; a changed or failed migration must be fixed by hand",
+            Self::Caches => "check redis.url and the storage, scanner and email settings; the failing dependency is named above",
+            Self::Workers => "check JOB_WORKERS and the job queue tables",
+            Self::Listeners => "check that server.port (and server.grpc_port, server.tls.redirect_port) are free and the TLS files readable",
+        }
+    }
+}
+
+impl fmt::Display for BootPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How long a completed phase took
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseTiming {
+    pub phase: BootPhase,
+    pub elapsed: Duration,
+}
+
+// ============================================================================
+// Boot Failure
+// ============================================================================
+
+/// A phase failed (or panicked); what had been started was torn down
+#[derive(Debug)]
+pub struct BootFailure {
+    pub phase: BootPhase,
+    /// Phases that had completed before
+    pub completed: Vec<BootPhase>,
+    /// Since boot started
+    pub elapsed: Duration,
+    pub source: anyhow::Error,
+}
+
+impl fmt::Display for BootFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Startup failed in the {} phase after {} ms: {:#} (hint: {})",
+            self.phase,
+            self.elapsed.as_millis(),
+            self.source,
+            self.phase.hint()
+        )
+    }
+}
+
+impl std::error::Error for BootFailure {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+// ============================================================================
+// Boot Sequence
+// ============================================================================
+
+struct TeardownStep {
+    phase: BootPhase,
+    name: &'static str,
+    action: Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>,
+}
+
+/// Runs startup one phase at a time, in `BootPhase` order, logging how
+/// long each took. When a phase fails or panics, the teardown steps of the
+/// phases before it run newest first (closing pools, stopping workers) and
+/// the failure is logged with the phase, what had completed and a hint.
+pub struct Boot {
+    started: Instant,
+    current: Option<BootPhase>,
+    timings: Vec<PhaseTiming>,
+    teardown: Vec<TeardownStep>,
+}
+
+impl Default for Boot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Boot {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            current: None,
+            timings: Vec::new(),
+            teardown: Vec::new(),
+        }
+    }
+
+    /// Run `phase`. Phases must come in order; each runs at most once.
+    pub async fn run<T>(
+        &mut self,
+        phase: BootPhase,
+        work: impl Future<Output = anyhow::Result<T>>,
+    ) -> Result<T, BootFailure> {
+        assert!(
+            self.current.is_none_or(|current| current < phase),
+            "boot phase {} entered after {:?}",
+            phase,
+            self.current
+        );
+        self.current = Some(phase);
+        tracing::debug!(phase = %phase, "Boot phase started");
+
+        let started = Instant::now();
+        let result = match AssertUnwindSafe(work).catch_unwind().await {
+            Ok(result) => result,
+            Err(panic) => Err(anyhow::anyhow!("panicked: {}", panic_message(panic.as_ref()))),
+        };
+        let elapsed = started.elapsed();
+
+        match result {
+            Ok(value) => {
+                tracing::info!(phase = %phase, elapsed_ms = elapsed.as_millis() as u64, "✅ Boot phase {} done", phase);
+                self.timings.push(PhaseTiming { phase, elapsed });
+                Ok(value)
+            }
+            Err(source) => {
+                let failure = BootFailure {
+                    phase,
+                    completed: self.completed(),
+                    elapsed: self.started.elapsed(),
+                    source,
+                };
+                tracing::error!(
+                    phase = %phase,
+                    elapsed_ms = elapsed.as_millis() as u64,
+                    completed = %join(&failure.completed),
+                    error = %format!("{:#}", failure.source),
+                    hint = phase.hint(),
+                    "❌ Boot phase {} failed",
+                    phase
+                );
+                self.tear_down().await;
+                Err(failure)
+            }
+        }
+    }
+
+    /// Undo part of the last completed phase should a later one fail, e.g.
+    /// close the pools it opened
+    pub fn on_failure<F, Fut>(&mut self, name: &'static str, action: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let phase = self.current.expect("teardown steps belong to a completed phase");
+        self.teardown.push(TeardownStep {
+            phase,
+            name,
+            action: Box::new(move || action().boxed()),
+        });
+    }
+
+    /// Phases completed so far, in order
+    pub fn completed(&self) -> Vec<BootPhase> {
+        self.timings.iter().map(|t| t.phase).collect()
+    }
+
+    /// Boot succeeded: drop the teardown steps (graceful shutdown takes
+    /// over) and log the time spent in each phase
+    pub fn finish(self) -> Vec<PhaseTiming> {
+        let phases = self
+            .timings
+            .iter()
+            .map(|t| format!("{}={}ms", t.phase, t.elapsed.as_millis()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        tracing::info!(
+            total_ms = self.started.elapsed().as_millis() as u64,
+            phases = %phases,
+            "🏁 Boot complete"
+        );
+        self.timings
+    }
+
+    async fn tear_down(&mut self) {
+        while let Some(step) = self.teardown.pop() {
+            tracing::warn!(phase = %step.phase, step = step.name, "Tearing down: {}", step.name);
+            if tokio::time::timeout(TEARDOWN_TIMEOUT, (step.action)()).await.is_err() {
+                tracing::error!(phase = %step.phase, step = step.name, "Teardown step timed out");
+            }
+        }
+    }
+}
+
+fn join(phases: &[BootPhase]) -> String {
+    phases.iter().map(BootPhase::as_str).collect::<Vec<_>>().join(",")
+}
+
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use shared::CompressionConfig;
+use tower_http::compression::predicate::{NotForContentType, Predicate, SizeAbove};
+use tower_http::compression::CompressionLayer;
+use tower_http::decompression::RequestDecompressionLayer;
+
+use crate::startup::METRICS_CONTENT_TYPE;
+
+// ============================================================================
+// Response Compression
+// ============================================================================
+
+/// Compresses responses of at least `min_size_bytes` with the configured
+/// algorithms. Event streams (`/me/events`) and Prometheus scrapes
+/// (`/metrics`) are never compressed: buffering would delay events, and
+/// scrapers negotiate their own encoding. Nor are already compressed images.
+pub fn response_compression(config: &CompressionConfig) -> CompressionLayer<impl Predicate> {
+    let predicate = SizeAbove::new(config.min_size_bytes)
+        .and(NotForContentType::SSE)
+        .and(NotForContentType::const_new(METRICS_CONTENT_TYPE))
+        .and(NotForContentType::IMAGES);
+    CompressionLayer::new()
+        .br(config.enables("br"))
+        .gzip(config.enables("gzip"))
+        .compress_when(predicate)
+}
+
+/// Inflates request bodies sent with a configured `Content-Encoding`;
+/// other encodings get a 415
+pub fn request_decompression(config: &CompressionConfig) -> RequestDecompressionLayer {
+    RequestDecompressionLayer::new()
+        .br(config.enables("br"))
+        .gzip(config.enables("gzip"))
+}
+
+/// Middleware narrowing `Accept-Encoding` to the first configured algorithm
+/// the client accepts. `CompressionLayer` alone picks by q-value, then by
+/// its own fixed order; this makes `server.compression.algorithms` decide.
+pub async fn prefer_encoding(State(config): State<Arc<CompressionConfig>>, mut request: Request, next: Next) -> Response {
+    let accept = request.headers().get(header::ACCEPT_ENCODING).and_then(|v| v.to_str().ok());
+    if let Some(algorithm) = accept.and_then(|accept| preferred_encoding(accept, &config.algorithms)) {
+        let algorithm = HeaderValue::from_str(algorithm).expect("algorithm names are header-safe");
+        request.headers_mut().insert(header::ACCEPT_ENCODING, algorithm);
+    }
+    next.run(request).await
+}
+
+/// First of `algorithms` that an `Accept-Encoding` value allows, either by
+/// name or through `*`. A `q=0` entry refuses that encoding.
+pub fn preferred_encoding<'a>(accept: &str, algorithms: &'a [String]) -> Option<&'a str> {
+    let entries: Vec<(&str, bool)> = accept
+        .split(',')
+        .map(|entry| {
+            let mut parts = entry.split(';').map(str::trim);
+            let name = parts.next().unwrap_or_default();
+            let refused = parts
+                .filter_map(|param| param.strip_prefix("q=").or_else(|| param.strip_prefix("Q=")))
+                .any(|q| q.parse::<f32>().is_ok_and(|q| q <= 0.0));
+            (name, !refused)
+        })
+        .collect();
+    let allows = |algorithm: &str| {
+        entries
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(algorithm))
+            .or_else(|| entries.iter().find(|(name, _)| *name == "*"))
+            .is_some_and(|(_, accepted)| *accepted)
+    };
+    algorithms.iter().map(String::as_str).find(|algorithm| allows(algorithm))
+}
@@ -0,0 +1,85 @@
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::fmt::Display;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::AppState;
+
+// ============================================================================
+// Entity Tags
+// ============================================================================
+
+/// Strong ETag for one version of a resource: a hash of its id and last
+/// modification time
+pub fn entity_tag(id: impl Display, updated_at: DateTime<Utc>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(id.to_string().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(updated_at.timestamp_micros().to_le_bytes());
+    format!("\"{}\"", &hex::encode(hasher.finalize())[..32])
+}
+
+/// Whether `If-None-Match` lists `etag` (or is `*`); weak comparison, as
+/// RFC 9110 requires for GET
+pub fn not_modified(headers: &HeaderMap, etag: &str) -> bool {
+    header_matches(headers, header::IF_NONE_MATCH, |tag| {
+        tag.trim_start_matches("W/") == etag.trim_start_matches("W/")
+    })
+}
+
+/// Fail with 412 unless `If-Match` is absent, `*`, or lists `etag`. Weak
+/// tags never match: a precondition on an update needs the exact version.
+pub fn check_if_match(headers: &HeaderMap, etag: &str) -> Result<(), ApiError> {
+    if !headers.contains_key(header::IF_MATCH) || header_matches(headers, header::IF_MATCH, |tag| tag == etag) {
+        return Ok(());
+    }
+    Err(ApiError::precondition_failed(
+        "The resource was modified since it was fetched; reload it and retry",
+    ))
+}
+
+fn header_matches(headers: &HeaderMap, name: header::HeaderName, matches: impl Fn(&str) -> bool) -> bool {
+    headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|tag| tag == "*" || matches(tag))
+}
+
+/// `body` with its `ETag`, or an empty 304 when `If-None-Match` already has it
+pub fn conditional_response(headers: &HeaderMap, etag: &str, body: impl IntoResponse) -> Response {
+    if not_modified(headers, etag) {
+        return with_etag(etag, StatusCode::NOT_MODIFIED);
+    }
+    with_etag(etag, body)
+}
+
+pub fn with_etag(etag: &str, body: impl IntoResponse) -> Response {
+    let etag = HeaderValue::from_str(etag).expect("entity tags are ASCII");
+    ([(header::ETAG, etag)], body).into_response()
+}
+
+// ============================================================================
+// Users
+// ============================================================================
+
+/// Check `If-Match` against the stored user before changing it. Loads the
+/// user only when the header is present.
+pub async fn check_user_precondition(state: &AppState, user_id: Uuid, headers: &HeaderMap) -> Result<(), ApiError> {
+    if !headers.contains_key(header::IF_MATCH) {
+        return Ok(());
+    }
+    let user = state
+        .user_service
+        .get_user(user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("Current user not found"))?;
+    check_if_match(headers, &entity_tag(user.id, user.updated_at))
+}
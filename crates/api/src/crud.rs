@@ -0,0 +1,262 @@
+// ============================================================================
+// CRUD Scaffolding
+// ============================================================================
+
+/// Generates the HTTP side of a plain CRUD resource in a module: validated
+/// create/update DTOs, a response DTO, list/get/create/update/delete handlers
+/// with OpenAPI annotations, a `routes(service)` router and an `ApiDoc` to
+/// merge into the main document.
+///
+/// The entity needs a `Uuid` `id`, a `new(fields...)` constructor taking the
+/// listed fields in order, and public fields with the same names. Pair it with
+/// `application::crud::CrudService` and a repository (in infrastructure,
+/// `pg_repository!`). Generated routes carry no auth; wrap them with
+/// `route_layer` as needed.
+///
+/// ```ignore
+/// crud_entity! {
+///     pub mod notes for Note {
+///         path: "/api/v1/notes",
+///         item_path: "/api/v1/notes/{id}",
+///         tag: "Notes",
+///         create: CreateNoteRequest,
+///         update: UpdateNoteRequest,
+///         response: NoteResponse,
+///         list: NotesResponse,
+///         fields {
+///             /// Note title
+///             #[validate(length(min = 1, max = 200, message = "must be 1-200 characters"))]
+///             title: String,
+///         }
+///     }
+/// }
+///
+/// // .nest("/notes", notes::routes(note_service))
+/// ```
+#[macro_export]
+macro_rules! crud_entity {
+    (
+        $vis:vis mod $module:ident for $entity:path {
+            path: $path:literal,
+            item_path: $item_path:literal,
+            tag: $tag:literal,
+            create: $create:ident,
+            update: $update:ident,
+            response: $response:ident,
+            list: $list:ident,
+            fields {
+                $(
+                    $(#[doc = $doc:expr])*
+                    $(#[validate $rules:tt])*
+                    $field:ident : $ty:ty
+                ),+ $(,)?
+            }
+        }
+    ) => {
+        $vis mod $module {
+            #![allow(unused_imports)]
+            use super::*;
+
+            use ::std::sync::Arc;
+            use ::axum::extract::{OriginalUri, Path, Query, State};
+            use ::axum::http::StatusCode;
+            use ::axum::routing::get;
+            use ::axum::Router;
+            use ::application::crud::CrudService;
+            use ::domain::PaginationParams;
+            use $crate::auth::ValidatedJson;
+            use $crate::error::ApiError;
+            use $crate::pagination::PageLinks;
+            use $crate::server_timing::Json;
+
+            type Entity = $entity;
+
+            // ----------------------------------------------------------------
+            // DTOs
+            // ----------------------------------------------------------------
+
+            #[derive(::serde::Deserialize, ::validator::Validate, ::utoipa::ToSchema)]
+            pub struct $create {
+                $(
+                    $(#[doc = $doc])*
+                    $(#[validate $rules])*
+                    pub $field: $ty,
+                )+
+            }
+
+            /// Full replacement of the editable fields
+            #[derive(::serde::Deserialize, ::validator::Validate, ::utoipa::ToSchema)]
+            pub struct $update {
+                $(
+                    $(#[doc = $doc])*
+                    $(#[validate $rules])*
+                    pub $field: $ty,
+                )+
+            }
+
+            #[derive(::serde::Serialize, ::utoipa::ToSchema)]
+            pub struct $response {
+                pub id: String,
+                $(
+                    $(#[doc = $doc])*
+                    pub $field: $ty,
+                )+
+            }
+
+            impl From<Entity> for $response {
+                fn from(entity: Entity) -> Self {
+                    Self {
+                        id: entity.id.to_string(),
+                        $( $field: entity.$field, )+
+                    }
+                }
+            }
+
+            #[derive(::serde::Serialize, ::utoipa::ToSchema)]
+            pub struct $list {
+                pub items: Vec<$response>,
+                pub total: u64,
+                pub page: u32,
+                pub per_page: u32,
+                pub total_pages: u32,
+                pub has_next: bool,
+                pub has_prev: bool,
+            }
+
+            // ----------------------------------------------------------------
+            // Routes
+            // ----------------------------------------------------------------
+
+            pub fn routes<S>(service: Arc<CrudService<Entity>>) -> Router<S>
+            where
+                S: Clone + Send + Sync + 'static,
+            {
+                Router::new()
+                    .route("/", get(list).post(create))
+                    .route("/:id", get(get_one).put(update).delete(delete))
+                    .with_state(service)
+            }
+
+            #[derive(::utoipa::OpenApi)]
+            #[openapi(
+                paths(list, get_one, create, update, delete),
+                components(schemas($create, $update, $response, $list))
+            )]
+            pub struct ApiDoc;
+
+            // ----------------------------------------------------------------
+            // Handlers
+            // ----------------------------------------------------------------
+
+            #[::utoipa::path(
+                get,
+                path = $path,
+                tag = $tag,
+                params(
+                    ("page" = Option<u32>, Query, description = "Page number (default: 1); pages starting past `pagination.max_offset` rows are rejected"),
+                    ("per_page" = Option<u32>, Query, description = "Items per page (default: 20, max: 100)")
+                ),
+                responses(
+                    (status = 200, description = "Page of items", body = $list, headers(("link" = String, description = "RFC 5988 links to the first, prev, next and last pages"))),
+                    (status = 400, description = "Page too deep", body = ErrorResponse)
+                )
+            )]
+            pub async fn list(
+                State(service): State<Arc<CrudService<Entity>>>,
+                Query(params): Query<PaginationParams>,
+                OriginalUri(uri): OriginalUri,
+            ) -> Result<(PageLinks, Json<$list>), ApiError> {
+                let page = service.list(&params).await?;
+                let links = PageLinks::new(&uri, &page);
+
+                Ok((links, Json($list {
+                    items: page.items.into_iter().map(Into::into).collect(),
+                    total: page.total,
+                    page: page.page,
+                    per_page: page.per_page,
+                    total_pages: page.total_pages,
+                    has_next: page.has_next,
+                    has_prev: page.has_prev,
+                })))
+            }
+
+            #[::utoipa::path(
+                get,
+                path = $item_path,
+                tag = $tag,
+                params(("id" = String, Path, description = "UUID")),
+                responses(
+                    (status = 200, description = "Item", body = $response),
+                    (status = 404, description = "Not found", body = ErrorResponse)
+                )
+            )]
+            pub async fn get_one(
+                State(service): State<Arc<CrudService<Entity>>>,
+                Path(id): Path<::uuid::Uuid>,
+            ) -> Result<Json<$response>, ApiError> {
+                Ok(Json(service.get(id).await?.into()))
+            }
+
+            #[::utoipa::path(
+                post,
+                path = $path,
+                tag = $tag,
+                request_body = $create,
+                responses(
+                    (status = 201, description = "Created", body = $response),
+                    (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse)
+                )
+            )]
+            pub async fn create(
+                State(service): State<Arc<CrudService<Entity>>>,
+                ValidatedJson(payload): ValidatedJson<$create>,
+            ) -> Result<(StatusCode, Json<$response>), ApiError> {
+                let entity = service.create(Entity::new($(payload.$field),+)).await?;
+                Ok((StatusCode::CREATED, Json(entity.into())))
+            }
+
+            #[::utoipa::path(
+                put,
+                path = $item_path,
+                tag = $tag,
+                params(("id" = String, Path, description = "UUID")),
+                request_body = $update,
+                responses(
+                    (status = 200, description = "Updated", body = $response),
+                    (status = 404, description = "Not found", body = ErrorResponse),
+                    (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse)
+                )
+            )]
+            pub async fn update(
+                State(service): State<Arc<CrudService<Entity>>>,
+                Path(id): Path<::uuid::Uuid>,
+                ValidatedJson(payload): ValidatedJson<$update>,
+            ) -> Result<Json<$response>, ApiError> {
+                let entity = service
+                    .update(id, move |entity| {
+                        $( entity.$field = payload.$field; )+
+                    })
+                    .await?;
+                Ok(Json(entity.into()))
+            }
+
+            #[::utoipa::path(
+                delete,
+                path = $item_path,
+                tag = $tag,
+                params(("id" = String, Path, description = "UUID")),
+                responses(
+                    (status = 204, description = "Deleted"),
+                    (status = 404, description = "Not found", body = ErrorResponse)
+                )
+            )]
+            pub async fn delete(
+                State(service): State<Arc<CrudService<Entity>>>,
+                Path(id): Path<::uuid::Uuid>,
+            ) -> Result<StatusCode, ApiError> {
+                service.delete(id).await?;
+                Ok(StatusCode::NO_CONTENT)
+            }
+        }
+    };
+}
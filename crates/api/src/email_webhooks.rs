@@ -0,0 +1,143 @@
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Router,
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+use application::email_suppression::{
+    parse_sendgrid_events, parse_ses_notification, BounceNotice, EmailSuppressionList, SesNotification,
+};
+
+use crate::error::ApiError;
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+/// Header alternative to the `token` query parameter
+pub const WEBHOOK_TOKEN_HEADER: &str = "x-webhook-token";
+
+/// Inbound bounce/complaint notifications from the email provider
+pub struct EmailWebhooks {
+    suppressions: Arc<dyn EmailSuppressionList>,
+    /// Shared secret callers must present; the endpoints are off without one
+    token: Option<String>,
+}
+
+impl EmailWebhooks {
+    /// `token` is `email.webhook_token`
+    pub fn new(suppressions: Arc<dyn EmailSuppressionList>, token: Option<String>) -> Self {
+        Self { suppressions, token }
+    }
+
+    /// Providers cannot sign requests the same way, so both pass a shared
+    /// token: in the URL (SNS subscriptions) or a header
+    fn authorize(&self, query: &TokenQuery, headers: &HeaderMap) -> Result<(), ApiError> {
+        let Some(expected) = &self.token else {
+            return Err(ApiError::not_found("Email webhooks are not configured"));
+        };
+        let presented = query
+            .token
+            .as_deref()
+            .or_else(|| headers.get(WEBHOOK_TOKEN_HEADER).and_then(|h| h.to_str().ok()))
+            .unwrap_or_default();
+        // Compare digests so the comparison time does not depend on the token
+        if Sha256::digest(presented.as_bytes()) != Sha256::digest(expected.as_bytes()) {
+            return Err(ApiError::unauthorized("Invalid webhook token"));
+        }
+        Ok(())
+    }
+
+    async fn suppress(&self, notices: &[BounceNotice], provider: &str) -> Result<(), ApiError> {
+        for notice in notices {
+            self.suppressions.suppress(notice, provider).await?;
+            tracing::info!(provider, reason = notice.reason.as_str(), "Email address suppressed");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TokenQuery {
+    pub token: Option<String>,
+}
+
+// ============================================================================
+// Routes
+// ============================================================================
+
+/// Provider callbacks; authenticated by `EMAIL_WEBHOOK_TOKEN`, not a JWT
+pub fn email_webhook_routes<S>(webhooks: Arc<EmailWebhooks>) -> Router<S> {
+    Router::new()
+        .route("/webhooks/ses", post(ses_notifications))
+        .route("/webhooks/sendgrid", post(sendgrid_events))
+        .with_state(webhooks)
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Amazon SES bounce and complaint notifications, delivered by SNS
+#[utoipa::path(
+    post,
+    path = "/api/v1/email/webhooks/ses",
+    tag = "Email",
+    params(("token" = Option<String>, Query, description = "EMAIL_WEBHOOK_TOKEN (or the X-Webhook-Token header)")),
+    request_body(content = String, description = "SNS message", content_type = "application/json"),
+    responses(
+        (status = 204, description = "Processed"),
+        (status = 400, description = "Not an SES notification", body = ErrorResponse),
+        (status = 401, description = "Invalid token", body = ErrorResponse),
+        (status = 404, description = "Email webhooks are not configured", body = ErrorResponse)
+    )
+)]
+pub async fn ses_notifications(
+    State(webhooks): State<Arc<EmailWebhooks>>,
+    Query(query): Query<TokenQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, ApiError> {
+    webhooks.authorize(&query, &headers)?;
+
+    match parse_ses_notification(&body)? {
+        // Confirming is left to an operator, so a forged request cannot make
+        // the server fetch arbitrary URLs
+        SesNotification::SubscriptionConfirmation { subscribe_url } => {
+            tracing::warn!(%subscribe_url, "SNS subscription pending: open the SubscribeURL to confirm it");
+        }
+        SesNotification::Notices(notices) => webhooks.suppress(&notices, "ses").await?,
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// SendGrid event webhook (bounce and spamreport events)
+#[utoipa::path(
+    post,
+    path = "/api/v1/email/webhooks/sendgrid",
+    tag = "Email",
+    params(("token" = Option<String>, Query, description = "EMAIL_WEBHOOK_TOKEN (or the X-Webhook-Token header)")),
+    request_body(content = String, description = "Array of SendGrid events", content_type = "application/json"),
+    responses(
+        (status = 204, description = "Processed"),
+        (status = 400, description = "Not a SendGrid event batch", body = ErrorResponse),
+        (status = 401, description = "Invalid token", body = ErrorResponse),
+        (status = 404, description = "Email webhooks are not configured", body = ErrorResponse)
+    )
+)]
+pub async fn sendgrid_events(
+    State(webhooks): State<Arc<EmailWebhooks>>,
+    Query(query): Query<TokenQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<StatusCode, ApiError> {
+    webhooks.authorize(&query, &headers)?;
+
+    webhooks.suppress(&parse_sendgrid_events(&body)?, "sendgrid").await?;
+    Ok(StatusCode::NO_CONTENT)
+}
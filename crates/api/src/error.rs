@@ -1,27 +1,52 @@
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
-    Json,
 };
 use serde::Serialize;
+use serde_json::{json, Value};
+use utoipa::ToSchema;
 use application::ApplicationError;
 use domain::DomainError;
 
+use crate::server_timing::Json;
+
 // ============================================================================
 // API Error Response
 // ============================================================================
 
 /// Standardized error response body following REST API best practices.
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ErrorResponse {
     pub error: ErrorBody,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ErrorBody {
     /// Machine-readable error code (e.g., "NOT_FOUND", "VALIDATION_ERROR")
+    #[schema(example = "NOT_FOUND")]
     pub code: String,
     /// Human-readable error message
+    #[schema(example = "Entity not found: User with id 550e8400-e29b-41d4-a716-446655440000")]
+    pub message: String,
+    /// Structured context, e.g. `{"current_version": 3}` on a version conflict
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<Value>,
+    /// Every invalid request field, on a 422 `VALIDATION_ERROR`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+/// One failed check on a request field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FieldError {
+    /// Field name as sent in the request body
+    #[schema(example = "email")]
+    pub field: String,
+    /// The check that failed, e.g. "email", "length" or "range"
+    #[schema(example = "email")]
+    pub code: String,
+    #[schema(example = "must be a valid email")]
     pub message: String,
 }
 
@@ -36,6 +61,11 @@ pub struct ApiError {
     status: StatusCode,
     code: String,
     message: String,
+    /// Seconds for the `Retry-After` header
+    retry_after: Option<u64>,
+    details: Option<Value>,
+    /// Boxed to keep `Result<_, ApiError>` small
+    errors: Box<[FieldError]>,
 }
 
 impl ApiError {
@@ -44,9 +74,17 @@ impl ApiError {
             status,
             code: code.into(),
             message: message.into(),
+            retry_after: None,
+            details: None,
+            errors: Box::default(),
         }
     }
 
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
     pub fn not_found(message: impl Into<String>) -> Self {
         Self::new(StatusCode::NOT_FOUND, "NOT_FOUND", message)
     }
@@ -55,6 +93,17 @@ impl ApiError {
         Self::new(StatusCode::BAD_REQUEST, "BAD_REQUEST", message)
     }
 
+    /// The request body parsed but some fields are invalid
+    pub fn validation(errors: Vec<FieldError>) -> Self {
+        let mut fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        fields.dedup();
+        let message = format!("Invalid request fields: {}", fields.join(", "));
+        Self {
+            errors: errors.into_boxed_slice(),
+            ..Self::new(StatusCode::UNPROCESSABLE_ENTITY, "VALIDATION_ERROR", message)
+        }
+    }
+
     pub fn conflict(message: impl Into<String>) -> Self {
         Self::new(StatusCode::CONFLICT, "CONFLICT", message)
     }
@@ -66,6 +115,27 @@ impl ApiError {
     pub fn unauthorized(message: impl Into<String>) -> Self {
         Self::new(StatusCode::UNAUTHORIZED, "UNAUTHORIZED", message)
     }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, "FORBIDDEN", message)
+    }
+
+    /// A dependency failed or timed out; clients may retry
+    pub fn service_unavailable(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::SERVICE_UNAVAILABLE, "SERVICE_UNAVAILABLE", message)
+    }
+
+    /// An `If-Match` precondition did not hold
+    pub fn precondition_failed(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::PRECONDITION_FAILED, "PRECONDITION_FAILED", message)
+    }
+
+    pub fn too_many_requests(message: impl Into<String>, retry_after_secs: u64) -> Self {
+        Self {
+            retry_after: Some(retry_after_secs),
+            ..Self::new(StatusCode::TOO_MANY_REQUESTS, "RATE_LIMITED", message)
+        }
+    }
 }
 
 impl IntoResponse for ApiError {
@@ -74,10 +144,16 @@ impl IntoResponse for ApiError {
             error: ErrorBody {
                 code: self.code,
                 message: self.message,
+                details: self.details,
+                errors: self.errors.into_vec(),
             },
         };
 
-        (self.status, Json(body)).into_response()
+        let mut response = (self.status, Json(body)).into_response();
+        if let Some(secs) = self.retry_after {
+            response.headers_mut().insert(header::RETRY_AFTER, secs.into());
+        }
+        response
     }
 }
 
@@ -90,9 +166,15 @@ impl From<DomainError> for ApiError {
         match &err {
             DomainError::NotFound { .. } => ApiError::not_found(err.to_string()),
             DomainError::Validation(_) => ApiError::bad_request(err.to_string()),
-            DomainError::Conflict(_) => ApiError::conflict(err.to_string()),
+            DomainError::Conflict {
+                current_version: Some(version),
+                ..
+            } => ApiError::conflict(err.to_string()).with_details(json!({ "current_version": version })),
+            DomainError::Conflict { .. } => ApiError::conflict(err.to_string()),
             DomainError::Internal(_) => ApiError::internal(err.to_string()),
             DomainError::Unauthorized(_) => ApiError::unauthorized(err.to_string()),
+            DomainError::Forbidden(_) => ApiError::forbidden(err.to_string()),
+            DomainError::Unavailable(_) | DomainError::Timeout(_) => ApiError::service_unavailable(err.to_string()),
         }
     }
 }
@@ -102,6 +184,12 @@ impl From<ApplicationError> for ApiError {
         match err {
             ApplicationError::Domain(domain_err) => domain_err.into(),
             ApplicationError::UseCase(msg) => ApiError::bad_request(msg),
+            err @ ApplicationError::RateLimited { retry_after } => {
+                ApiError::too_many_requests(err.to_string(), retry_after.as_secs().max(1))
+            }
+            ApplicationError::WeakPassword(ref violations) => {
+                ApiError::bad_request(err.to_string()).with_details(json!({ "violations": violations }))
+            }
         }
     }
 }
@@ -0,0 +1,124 @@
+use application::FeatureFlagService;
+use axum::extract::State;
+use domain::{Claims, FlagContext};
+use serde::Serialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+use crate::error::ApiError;
+use crate::middleware::AuthUser;
+use crate::AppState;
+use crate::server_timing::Json;
+
+// ============================================================================
+// Flag Context
+// ============================================================================
+
+/// Everything flags can target about the caller: user id, roles from the
+/// token and granted ones, tenant and sign-up time
+pub async fn flag_context(state: &AppState, claims: &Claims) -> Result<FlagContext, ApiError> {
+    let authorization = state.authz.authorization(claims).await?;
+    let mut ctx = FlagContext::from_claims(claims).with_roles(authorization.roles.iter().cloned().collect());
+    if let Some(user_id) = ctx.user_id {
+        if let Some(user) = state.user_service.get_user(user_id).await? {
+            ctx = ctx.with_tenant(user.tenant_id.to_string()).with_signed_up_at(user.created_at);
+        }
+    }
+    Ok(ctx)
+}
+
+// ============================================================================
+// Features Extractor
+// ============================================================================
+
+/// The caller's feature flags, for handlers that branch on them. Anonymous
+/// callers only see flags rolled out to 100% of everyone.
+///
+/// Example:
+/// ```rust,ignore
+/// async fn dashboard(features: Features) -> impl IntoResponse {
+///     if features.is_enabled("new_dashboard").await { new_dashboard() } else { old_dashboard() }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Features {
+    flags: Arc<dyn FeatureFlagService>,
+    ctx: FlagContext,
+}
+
+impl Features {
+    pub fn new(flags: Arc<dyn FeatureFlagService>, ctx: FlagContext) -> Self {
+        Self { flags, ctx }
+    }
+
+    /// Unknown flags, and flags that fail to evaluate, are off
+    pub async fn is_enabled(&self, key: &str) -> bool {
+        match self.flags.is_enabled(key, &self.ctx).await {
+            Ok(enabled) => enabled,
+            Err(e) => {
+                tracing::warn!(flag = key, "Feature flag evaluation failed: {}", e);
+                false
+            }
+        }
+    }
+
+    pub fn context(&self) -> &FlagContext {
+        &self.ctx
+    }
+}
+
+impl axum::extract::FromRequestParts<Arc<AppState>> for Features {
+    type Rejection = ApiError;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        state: &'life1 Arc<AppState>,
+    ) -> core::pin::Pin<
+        Box<dyn core::future::Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>,
+    >
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            let ctx = match parts.extensions.get::<Claims>() {
+                Some(claims) => flag_context(state, claims).await?,
+                None => FlagContext::default(),
+            };
+            Ok(Features::new(state.feature_flags.clone(), ctx))
+        })
+    }
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Flags enabled for the caller
+#[derive(Serialize, ToSchema)]
+pub struct FeaturesResponse {
+    /// Keys of the enabled flags, sorted
+    #[schema(example = json!(["new_dashboard"]))]
+    pub features: Vec<String>,
+}
+
+/// Get the feature flags enabled for the current user
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/features",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Enabled feature flags", body = FeaturesResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn get_my_features(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+) -> Result<Json<FeaturesResponse>, ApiError> {
+    let ctx = flag_context(&state, &claims).await?;
+    let features = state.feature_flags.enabled_flags(&ctx).await?;
+    Ok(Json(FeaturesResponse { features }))
+}
@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use application::storage::PUBLIC_PREFIX;
+
+use crate::error::ApiError;
+use crate::AppState;
+
+// ============================================================================
+// Routes
+// ============================================================================
+
+/// `/files/*key`: serves stored files for backends without their own public
+/// endpoint (local disk). Keys under `public/` are open; others need a URL
+/// from `FileStorage::presign`.
+pub fn file_routes() -> Router<Arc<AppState>> {
+    Router::new().route("/files/*key", get(serve_file))
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Presigned URL parameters
+#[derive(Deserialize)]
+pub struct Presigned {
+    pub expires: Option<i64>,
+    pub signature: Option<String>,
+}
+
+pub async fn serve_file(
+    State(state): State<Arc<AppState>>,
+    Path(key): Path<String>,
+    Query(presigned): Query<Presigned>,
+) -> Result<Response, ApiError> {
+    let public = key.starts_with(PUBLIC_PREFIX);
+    let signed = match (presigned.expires, presigned.signature.as_deref()) {
+        (Some(expires), Some(signature)) => state.file_storage.verify_presigned(&key, expires, signature),
+        _ => false,
+    };
+    // Unsigned private keys look missing, so their names cannot be probed
+    if !public && !signed {
+        return Err(ApiError::not_found("File not found"));
+    }
+
+    let file = state
+        .file_storage
+        .get(&key)
+        .await?
+        .ok_or_else(|| ApiError::not_found("File not found"))?;
+
+    // Public keys are never rewritten (uploads get fresh keys)
+    let cache_control = if public {
+        "public, max-age=31536000, immutable"
+    } else {
+        "private, no-store"
+    };
+    let content_type =
+        HeaderValue::from_str(&file.content_type).unwrap_or(HeaderValue::from_static("application/octet-stream"));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CACHE_CONTROL, HeaderValue::from_static(cache_control)),
+            (header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff")),
+        ],
+        file.bytes,
+    )
+        .into_response())
+}
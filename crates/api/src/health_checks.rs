@@ -0,0 +1,82 @@
+use axum::{
+    extract::{FromRequestParts, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use application::secrets::constant_time_eq;
+use shared::{HealthCheckSettings, IpNetwork, PROBE_PATHS};
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use crate::middleware::ClientIp;
+
+/// Header carrying `health_checks.secret`
+pub const HEALTH_CHECK_SECRET_HEADER: &str = "x-health-check-secret";
+
+/// Marks a request to a probe route from a trusted network or with the shared
+/// secret. `jwt_auth`, `require_role` and `require_permission` let it through.
+#[derive(Debug, Clone, Copy)]
+pub struct TrustedProbe;
+
+// ============================================================================
+// Access
+// ============================================================================
+
+/// Who may call the probe routes without a token, built from `[health_checks]`
+#[derive(Debug, Clone, Default)]
+pub struct HealthCheckAccess {
+    networks: Vec<IpNetwork>,
+    secret: Option<String>,
+    paths: Vec<String>,
+}
+
+impl HealthCheckAccess {
+    /// Invalid networks and paths other than `PROBE_PATHS` are skipped;
+    /// `Config::validate` reports them
+    pub fn new(settings: &HealthCheckSettings) -> Self {
+        Self {
+            networks: settings.trusted_networks.iter().filter_map(|n| n.parse().ok()).collect(),
+            secret: settings.secret.clone().filter(|s| !s.is_empty()),
+            paths: settings
+                .paths
+                .iter()
+                .filter(|p| PROBE_PATHS.contains(&p.as_str()))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Whether a request to `path` from `ip`, carrying `secret`, is a trusted probe
+    pub fn is_trusted(&self, path: &str, ip: Option<IpAddr>, secret: Option<&str>) -> bool {
+        if !self.paths.iter().any(|p| p == path) {
+            return false;
+        }
+        let from_trusted_network = ip.is_some_and(|ip| self.networks.iter().any(|n| n.contains(ip)));
+        let with_secret = match (&self.secret, secret) {
+            (Some(expected), Some(given)) => constant_time_eq(expected.as_bytes(), given.as_bytes()),
+            _ => false,
+        };
+        from_trusted_network || with_secret
+    }
+}
+
+// ============================================================================
+// Middleware
+// ============================================================================
+
+/// Tag trusted probes with `TrustedProbe`. The caller's address is the one
+/// rate limiting sees (`ClientIp`), so a proxy in a trusted network does not
+/// vouch for the clients it forwards when `TRUST_FORWARDED_FOR` is on.
+pub async fn trusted_probes(State(access): State<Arc<HealthCheckAccess>>, request: Request, next: Next) -> Response {
+    let (mut parts, body) = request.into_parts();
+    let Ok(ClientIp(ip)) = ClientIp::from_request_parts(&mut parts, &()).await;
+    let secret = parts
+        .headers
+        .get(HEALTH_CHECK_SECRET_HEADER)
+        .and_then(|v| v.to_str().ok());
+    let ip = ip.and_then(|ip| ip.parse().ok());
+    if access.is_trusted(parts.uri.path(), ip, secret) {
+        parts.extensions.insert(TrustedProbe);
+    }
+    next.run(Request::from_parts(parts, body)).await
+}
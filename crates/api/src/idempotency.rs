@@ -0,0 +1,209 @@
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+
+use application::idempotency::{IdempotencyClaim, IdempotencyStore, StoredResponse};
+use application::token_versions::TokenVersions;
+use application::TokenService;
+use shared::IdempotencySettings;
+
+use crate::error::ApiError;
+use crate::middleware::bearer_token;
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// Set on responses replayed from the store
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Largest request body fingerprinted; bigger requests are refused with 413
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Not stored, so a retry runs again: the caller may fix its credentials
+/// (401, 403), the first request may still hold a lock (409), or the limit
+/// may have reset (429)
+const UNSTORED_STATUSES: &[StatusCode] = &[
+    StatusCode::UNAUTHORIZED,
+    StatusCode::FORBIDDEN,
+    StatusCode::CONFLICT,
+    StatusCode::TOO_MANY_REQUESTS,
+];
+
+/// Not replayed: recomputed per response, or tied to one connection
+const UNSTORED_HEADERS: &[&str] = &["content-length", "date", "set-cookie", "transfer-encoding", "connection", "x-request-id"];
+
+/// `Idempotency-Key` handling for POST requests (see `idempotency_keys`)
+pub struct Idempotency {
+    store: Arc<dyn IdempotencyStore>,
+    token_service: Arc<dyn TokenService>,
+    /// Tokens revoked by logout-all get no scope
+    token_versions: Arc<TokenVersions>,
+    /// How long a response is replayed
+    ttl: Duration,
+    /// After this long, a key still marked in progress is assumed abandoned
+    lock_timeout: Duration,
+}
+
+impl Idempotency {
+    /// With the `[idempotency]` settings
+    pub fn new(
+        store: Arc<dyn IdempotencyStore>,
+        token_service: Arc<dyn TokenService>,
+        token_versions: Arc<TokenVersions>,
+        settings: &IdempotencySettings,
+    ) -> Self {
+        Self {
+            store,
+            token_service,
+            token_versions,
+            ttl: Duration::from_secs(settings.ttl_hours * 3600),
+            lock_timeout: Duration::from_secs(settings.lock_timeout_secs),
+        }
+    }
+}
+
+// ============================================================================
+// Middleware
+// ============================================================================
+
+/// Replays the first response to an authenticated POST carrying an
+/// `Idempotency-Key`, so clients can retry safely.
+///
+/// Keys are scoped per user. Anonymous requests, and those with a revoked
+/// token, pass through untouched, so one caller can never receive another's
+/// response. While the first request is running, duplicates get 409. Reusing
+/// a key for a different request (method, path or body) gets 422. Server
+/// errors, 401, 403, 409 and 429 are not stored, so their retries run again.
+pub async fn idempotency_keys(
+    State(idempotency): State<Arc<Idempotency>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(request).await);
+    };
+    if request.method() != Method::POST {
+        return Ok(next.run(request).await);
+    }
+    let key = key
+        .to_str()
+        .ok()
+        .filter(|k| !k.is_empty() && k.len() <= 255)
+        .ok_or_else(|| ApiError::bad_request("Idempotency-Key must be 1-255 visible ASCII characters"))?
+        .to_string();
+
+    // Outer layer: jwt_auth has not run yet, so identify the user ourselves,
+    // with the same token version check
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| bearer_token(Some(h)).ok());
+    let claims = match token {
+        Some(token) => idempotency
+            .token_versions
+            .authenticate(idempotency.token_service.as_ref(), token)
+            .await
+            .ok(),
+        None => None,
+    };
+    let Some(scope) = claims.map(|claims| format!("user:{}", claims.sub)) else {
+        return Ok(next.run(request).await);
+    };
+
+    let (parts, body) = request.into_parts();
+    let bytes = axum::body::to_bytes(body, MAX_BODY_BYTES).await.map_err(|_| {
+        ApiError::new(StatusCode::PAYLOAD_TOO_LARGE, "PAYLOAD_TOO_LARGE", "Request body too large")
+    })?;
+    let fingerprint = fingerprint(&parts.method, &parts.uri.to_string(), &bytes);
+
+    let claim = idempotency
+        .store
+        .claim(&scope, &key, &fingerprint, idempotency.ttl, idempotency.lock_timeout)
+        .await?;
+    match claim {
+        IdempotencyClaim::Acquired => {}
+        IdempotencyClaim::InProgress => {
+            return Err(ApiError::new(
+                StatusCode::CONFLICT,
+                "IDEMPOTENCY_KEY_IN_USE",
+                "A request with this Idempotency-Key is still being processed",
+            ))
+        }
+        IdempotencyClaim::Mismatch => {
+            return Err(ApiError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "IDEMPOTENCY_KEY_REUSED",
+                "This Idempotency-Key was already used for a different request",
+            ))
+        }
+        IdempotencyClaim::Completed(stored) => return Ok(replay(stored)),
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(bytes))).await;
+    let status = response.status();
+    if status.is_server_error() || UNSTORED_STATUSES.contains(&status) {
+        if let Err(e) = idempotency.store.release(&scope, &key).await {
+            tracing::warn!(error = %e, "Failed to release idempotency key");
+        }
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            let _ = idempotency.store.release(&scope, &key).await;
+            return Err(ApiError::internal(format!("Failed to read response body: {}", e)));
+        }
+    };
+    let stored = StoredResponse {
+        status: status.as_u16(),
+        headers: parts
+            .headers
+            .iter()
+            .filter(|(name, _)| !UNSTORED_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect(),
+        body: bytes.to_vec(),
+    };
+    if let Err(e) = idempotency.store.complete(&scope, &key, &stored).await {
+        tracing::warn!(error = %e, "Failed to store idempotent response");
+    }
+
+    Ok(Response::from_parts(parts, Body::from(bytes)))
+}
+
+/// Method, URI and body hash identifying the request a key was first used for
+fn fingerprint(method: &Method, uri: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(uri.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let mut response = Response::new(Body::from(stored.body));
+    *response.status_mut() = status;
+    let headers = response.headers_mut();
+    for (name, value) in stored.headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+            headers.append(name, value);
+        }
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
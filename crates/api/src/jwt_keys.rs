@@ -0,0 +1,70 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use infrastructure::JwtTokenService;
+use shared::{Config, LoadOptions};
+
+/// Reload the JWT secrets from the configuration whenever the process
+/// receives SIGHUP and, if `interval` is set, that often. A configuration
+/// that fails to load or validate is logged and the current keys kept.
+pub fn reload_jwt_keys(tokens: Arc<JwtTokenService>, options: LoadOptions, interval: Option<Duration>) {
+    tokio::spawn(async move {
+        #[cfg(unix)]
+        let mut hangup = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+            Ok(signal) => Some(signal),
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGHUP: {}", e);
+                None
+            }
+        };
+        let mut ticks = interval.map(|period| {
+            let mut ticks = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            ticks
+        });
+
+        loop {
+            let hangup_received = async {
+                #[cfg(unix)]
+                if let Some(hangup) = hangup.as_mut() {
+                    return hangup.recv().await;
+                }
+                std::future::pending().await
+            };
+            let tick = async {
+                match ticks.as_mut() {
+                    Some(ticks) => ticks.tick().await,
+                    None => std::future::pending().await,
+                }
+            };
+            let trigger = tokio::select! {
+                received = hangup_received => received,
+                _ = tick => Some(()),
+            };
+            if trigger.is_none() {
+                // The signal stream ended; keep to the interval
+                #[cfg(unix)]
+                {
+                    hangup = None;
+                }
+                continue;
+            }
+            reload(&tokens, &options);
+        }
+    });
+}
+
+fn reload(tokens: &JwtTokenService, options: &LoadOptions) {
+    let config = Config::load(options).map_err(|e| e.to_string()).and_then(|config| {
+        config.validate(&options.profile).map_err(|e| e.to_string())?;
+        Ok(config)
+    });
+    match config {
+        Ok(config) => {
+            if tokens.set_secrets(&config.jwt.secret, &config.jwt.previous_secrets) {
+                tracing::info!(keys = ?tokens.key_ids(), "🔐 JWT keys reloaded");
+            }
+        }
+        Err(e) => tracing::error!("JWT key reload failed, keeping the current keys: {}", e),
+    }
+}
@@ -0,0 +1,120 @@
+pub mod admin;
+pub mod api_docs;
+pub mod auth;
+pub mod boot;
+pub mod compression;
+pub mod conditional;
+pub mod crud;
+pub mod email_webhooks;
+pub mod error;
+pub mod features;
+pub mod files;
+pub mod health_checks;
+pub mod idempotency;
+pub mod jwt_keys;
+pub mod logging;
+pub mod middleware;
+pub mod notifications;
+pub mod organizations;
+pub mod pagination;
+pub mod realtime;
+pub mod request_signing;
+pub mod server;
+pub mod server_timing;
+pub mod session;
+pub mod startup;
+pub mod support;
+pub mod tenants;
+pub mod tls;
+pub mod users;
+pub mod versioning;
+pub mod well_known;
+
+use std::sync::Arc;
+
+use application::account_deletion::AccountDeletionService;
+use application::activity::UserActivity;
+use application::admin::{AdminUserService, BulkUserActions};
+use application::authz::AuthorizationService;
+use application::availability::AvailabilityService;
+use application::data_browser::DataBrowserService;
+use application::devices::DeviceService;
+use application::email_suppression::EmailSuppressionList;
+use application::feature_flags::ManagedFeatureFlags;
+use application::impersonation::Impersonation;
+use application::imports::UserImports;
+use application::jobs::JobQueue;
+use application::notes::UserNoteService;
+use application::notifications::NotificationService;
+use application::operations::OperationStore;
+use application::organizations::OrganizationService;
+use application::presence::PresenceTracker;
+use application::queries::UserQueryService;
+use application::rate_limits::ManagedRateLimiter;
+use application::request_signing::RequestSigning;
+use application::segments::SegmentService;
+use application::settings::UserSettingsService;
+use application::data_export::DataExportService;
+use application::storage::{AvatarService, FileStorage};
+use application::crud::CrudService;
+use application::support::SupportService;
+use application::tagging::TagService;
+use application::token_versions::TokenVersions;
+use application::webhooks::WebhookService;
+use application::{AuthService, ConsistencyTracker, EventBus, ExperimentService, TokenService, UnitOfWork, UserService};
+use domain::Tenant;
+use email_webhooks::EmailWebhooks;
+use realtime::ConnectionManager;
+use session::CookieSessions;
+use startup::StartupReport;
+
+// ============================================================================
+// Application State
+// ============================================================================
+
+pub struct AppState {
+    pub user_service: Arc<dyn UserService>,
+    /// Read path for user lookups and listings
+    pub user_queries: Arc<UserQueryService>,
+    pub auth_service: Arc<dyn AuthService>,
+    pub availability: Arc<AvailabilityService>,
+    pub authz: Arc<AuthorizationService>,
+    pub token_service: Arc<dyn TokenService>,
+    pub token_versions: Arc<TokenVersions>,
+    pub impersonation: Arc<Impersonation>,
+    pub realtime: Arc<ConnectionManager>,
+    pub presence: Arc<PresenceTracker>,
+    pub event_bus: Arc<dyn EventBus>,
+    pub unit_of_work: Arc<dyn UnitOfWork>,
+    pub consistency: Arc<ConsistencyTracker>,
+    pub feature_flags: Arc<ManagedFeatureFlags>,
+    pub experiments: Arc<dyn ExperimentService>,
+    pub job_queue: Arc<dyn JobQueue>,
+    pub data_browser: Arc<DataBrowserService>,
+    pub support_service: Arc<dyn SupportService>,
+    pub webhook_service: Arc<dyn WebhookService>,
+    pub file_storage: Arc<dyn FileStorage>,
+    pub avatars: Arc<AvatarService>,
+    pub data_exports: Arc<DataExportService>,
+    pub account_deletions: Arc<AccountDeletionService>,
+    pub devices: Arc<DeviceService>,
+    pub activity: Arc<UserActivity>,
+    pub admin_users: Arc<dyn AdminUserService>,
+    pub bulk_users: Arc<BulkUserActions>,
+    pub user_imports: Arc<UserImports>,
+    pub operations: Arc<dyn OperationStore>,
+    pub user_notes: Arc<UserNoteService>,
+    pub notifications: Arc<NotificationService>,
+    pub segments: Arc<SegmentService>,
+    pub user_settings: Arc<UserSettingsService>,
+    pub organizations: Arc<OrganizationService>,
+    pub rate_limits: Arc<ManagedRateLimiter>,
+    pub email_suppressions: Arc<dyn EmailSuppressionList>,
+    /// Bounce and complaint notifications under `/email`
+    pub email_webhooks: Arc<EmailWebhooks>,
+    pub tenants: Arc<CrudService<Tenant>>,
+    pub tags: Arc<TagService>,
+    pub request_signing: Arc<RequestSigning>,
+    pub sessions: Arc<CookieSessions>,
+    pub startup: Arc<StartupReport>,
+}
@@ -0,0 +1,325 @@
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    extract::{MatchedPath, State},
+    http::Request,
+    middleware::Next,
+    response::Response,
+};
+use rand::Rng;
+use serde_json::Value;
+use infrastructure::AuditExporter;
+use shared::{HttpLogSettings, LogSettings};
+use std::{collections::HashMap, sync::Arc, time::Instant};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Span, Subscriber};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::{filter::filter_fn, util::SubscriberInitExt, EnvFilter};
+
+use crate::middleware::{AuthenticatedUserId, RequestId};
+
+// ============================================================================
+// Subscriber Setup
+// ============================================================================
+
+/// Field names whose values are never written to logs.
+pub(crate) const SENSITIVE_KEYS: &[&str] = &["password", "token", "secret", "authorization"];
+
+static SERVICE_NAME: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+static REGION: std::sync::OnceLock<String> = std::sync::OnceLock::new();
+
+/// Service name reported on every request span (`log.service_name`, set by `init`)
+pub fn service_name() -> &'static str {
+    SERVICE_NAME.get().map(String::as_str).unwrap_or(env!("CARGO_PKG_NAME"))
+}
+
+/// Deployment region (`log.region`, set by `init`), reported on request
+/// spans and in the `x-served-by` header.
+pub fn region() -> &'static str {
+    REGION.get().map(String::as_str).unwrap_or("local")
+}
+
+/// Initialize tracing. `log.format = "json"` switches to structured JSON output,
+/// anything else keeps the human-readable formatter. Events with the `audit`
+/// target are also handed to `audit` when export is configured, whatever `log.level` says.
+pub fn init(settings: &LogSettings, audit: Option<AuditExporter>) {
+    SERVICE_NAME.get_or_init(|| settings.service_name.clone());
+    REGION.get_or_init(|| settings.region.clone());
+    let filter = EnvFilter::new(&settings.level);
+    let json = settings.format.eq_ignore_ascii_case("json");
+
+    let registry = tracing_subscriber::registry().with(
+        audit
+            .map(AuditLayer::new)
+            .with_filter(filter_fn(|metadata| metadata.target() == AUDIT_TARGET)),
+    );
+    if json {
+        registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .flatten_event(true)
+                    .with_current_span(false)
+                    .with_span_list(true)
+                    .with_filter(filter),
+            )
+            .init();
+    } else {
+        registry.with(tracing_subscriber::fmt::layer().with_filter(filter)).init();
+    }
+}
+
+// ============================================================================
+// Audit Export
+// ============================================================================
+
+/// Target used by `tracing::info!(target: "audit", ...)` audit events
+pub const AUDIT_TARGET: &str = "audit";
+
+/// Forwards audit events to the exporter as flat JSON records
+/// (`timestamp`, `level`, `message` and the event fields, redacted).
+pub struct AuditLayer {
+    exporter: AuditExporter,
+}
+
+impl AuditLayer {
+    pub fn new(exporter: AuditExporter) -> Self {
+        Self { exporter }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for AuditLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = serde_json::Map::new();
+        fields.insert("timestamp".into(), chrono::Utc::now().to_rfc3339().into());
+        fields.insert("level".into(), event.metadata().level().as_str().into());
+        fields.insert("service".into(), service_name().into());
+        event.record(&mut JsonVisitor(&mut fields));
+
+        let mut record = Value::Object(fields);
+        redact(&mut record);
+        self.exporter.export(record);
+    }
+}
+
+struct JsonVisitor<'a>(&'a mut serde_json::Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().into(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().into(), format!("{:?}", value).into());
+    }
+}
+
+/// Root span for each HTTP request, used with `TraceLayer::make_span_with`.
+/// `tenant_id` and `user_id` start empty and are recorded by `resolve_tenant`
+/// and by `jwt_auth` once the token is validated.
+pub fn make_request_span(request: &Request<Body>) -> Span {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(|r| r.0.as_str())
+        .unwrap_or("unknown");
+
+    tracing::info_span!(
+        "request",
+        service = %service_name(),
+        region = %region(),
+        version = env!("CARGO_PKG_VERSION"),
+        method = %request.method(),
+        uri = %redacted_uri(request.uri()),
+        request_id = %request_id,
+        tenant_id = tracing::field::Empty,
+        user_id = tracing::field::Empty,
+    )
+}
+
+// ============================================================================
+// Field Redaction
+// ============================================================================
+
+/// The URI with sensitive query parameters (e.g. the WebSocket or webhook `token`) masked
+pub fn redacted_uri(uri: &axum::http::Uri) -> String {
+    let Some(query) = uri.query() else {
+        return uri.to_string();
+    };
+    let query: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if SENSITIVE_KEYS.iter().any(|s| key.to_ascii_lowercase().contains(s)) => {
+                format!("{}=[REDACTED]", key)
+            }
+            _ => pair.to_string(),
+        })
+        .collect();
+    format!("{}?{}", uri.path(), query.join("&"))
+}
+
+/// Replace the values of sensitive fields (passwords, tokens, secrets) in place.
+pub fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if SENSITIVE_KEYS.iter().any(|s| key.contains(s)) {
+                    *field = Value::String("[REDACTED]".to_string());
+                } else {
+                    redact(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// Render a request body for logging with sensitive fields redacted.
+/// Non-JSON bodies are summarized by size rather than logged verbatim.
+pub fn redacted_body(bytes: &[u8]) -> String {
+    match serde_json::from_slice::<Value>(bytes) {
+        Ok(mut value) => {
+            redact(&mut value);
+            value.to_string()
+        }
+        Err(_) => format!("<{} bytes, non-JSON>", bytes.len()),
+    }
+}
+
+// ============================================================================
+// Request/Response Logging
+// ============================================================================
+
+/// Bodies larger than this are never buffered for logging.
+const MAX_BUFFERED_BODY: u64 = 1024 * 1024;
+
+/// Configuration for the opt-in request/response logging middleware.
+#[derive(Debug, Clone)]
+pub struct HttpLogConfig {
+    pub enabled: bool,
+    /// Maximum number of body characters written per log line
+    pub max_body_len: usize,
+    /// Fraction of requests logged for routes without an override (0.0 - 1.0)
+    pub default_sample_rate: f64,
+    /// Per-route overrides keyed by matched route path (e.g. `/users/:id`)
+    pub route_sample_rates: HashMap<String, f64>,
+}
+
+impl HttpLogConfig {
+    /// From the `[http_log]` settings
+    pub fn from_settings(settings: &HttpLogSettings) -> Self {
+        Self {
+            enabled: settings.enabled,
+            max_body_len: settings.max_body,
+            default_sample_rate: settings.sample_rate,
+            route_sample_rates: settings.route_rates().into_iter().collect(),
+        }
+    }
+
+    fn sample_rate(&self, route: &str) -> f64 {
+        self.route_sample_rates
+            .get(route)
+            .copied()
+            .unwrap_or(self.default_sample_rate)
+    }
+}
+
+/// Middleware logging method, path, status, latency, user id and truncated,
+/// redacted bodies for a sampled subset of requests.
+pub async fn log_requests(
+    State(config): State<Arc<HttpLogConfig>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if !config.enabled {
+        return next.run(request).await;
+    }
+
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    let rate = config.sample_rate(&route);
+    if rate <= 0.0 || (rate < 1.0 && !rand::thread_rng().gen_bool(rate)) {
+        return next.run(request).await;
+    }
+
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let started = Instant::now();
+
+    let (parts, body) = request.into_parts();
+    let (request_body, body) = capture_body(body, config.max_body_len).await;
+    let response = next.run(Request::from_parts(parts, body)).await;
+
+    let status = response.status();
+    let user_id = response
+        .extensions()
+        .get::<AuthenticatedUserId>()
+        .map(|u| u.0.clone())
+        .unwrap_or_default();
+
+    let (parts, body) = response.into_parts();
+    let (response_body, body) = capture_body(body, config.max_body_len).await;
+
+    tracing::info!(
+        target: "http",
+        method = %method,
+        path = %path,
+        route = %route,
+        status = status.as_u16(),
+        latency_ms = started.elapsed().as_millis() as u64,
+        user_id = %user_id,
+        request_body = %request_body,
+        response_body = %response_body,
+        "request completed"
+    );
+
+    Response::from_parts(parts, body)
+}
+
+/// Buffer a body (when small enough) and return its redacted, truncated
+/// rendering together with a body that can be passed on unchanged.
+async fn capture_body(body: Body, max_len: usize) -> (String, Body) {
+    match body.size_hint().upper() {
+        Some(0) => return (String::new(), body),
+        Some(n) if n <= MAX_BUFFERED_BODY => {}
+        _ => return ("<streamed>".to_string(), body),
+    }
+
+    match axum::body::to_bytes(body, MAX_BUFFERED_BODY as usize).await {
+        Ok(bytes) => (truncate(redacted_body(&bytes), max_len), Body::from(bytes)),
+        Err(_) => ("<unreadable>".to_string(), Body::from(Bytes::new())),
+    }
+}
+
+fn truncate(mut text: String, max_len: usize) -> String {
+    if text.len() > max_len {
+        let mut end = max_len;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str("...");
+    }
+    text
+}
@@ -1,33 +1,92 @@
-mod auth;
-mod error;
-mod middleware;
-
 use axum::{
-    extract::{Path, Query, State},
+    extract::{DefaultBodyLimit, Multipart, OriginalUri, Path, Query, State},
     middleware as axum_mw,
-    routing::get,
-    Json, Router,
+    routing::{delete, get, post, put},
+    response::{IntoResponse, Response},
+    Router,
 };
-use http::Method;
-use serde::Serialize;
+use http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use serde::{Deserialize, Serialize};
 use std::{sync::Arc, time::Duration};
 use tokio::net::TcpListener;
+use tower::Layer;
+use tower_cookies::CookieManagerLayer;
 use tower_http::{
-    cors::{Any, CorsLayer},
+    cors::{AllowHeaders, AllowOrigin, CorsLayer, ExposeHeaders},
     trace::TraceLayer,
 };
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use utoipa::{OpenApi, ToSchema};
+use clap::Parser;
+use shared::{Config, CorsConfig, CronSettings, LoadOptions};
+use validator::Validate;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme};
+use utoipa::openapi::{Content, Ref, ResponseBuilder};
+use utoipa::{Modify, OpenApi, ToSchema};
 use utoipa_swagger_ui::SwaggerUi;
 
-use application::{AuthService, AuthServiceImpl, TokenService, UserService, UserServiceImpl};
-use domain::PaginationParams;
-use infrastructure::{ArgonPasswordHasher, JwtConfig, JwtTokenService, PostgresUserRepository};
-use error::ApiError;
-use middleware::{AuthUser, RequestId};
+use api::{admin, auth, compression, conditional, email_webhooks, features, files, health_checks, idempotency, jwt_keys, logging, middleware, notifications, organizations, realtime, request_signing, server, server_timing, startup, support, tenants, tls, versioning, well_known, AppState};
+use api::api_docs::{audience_doc, DocAudience};
+use api::error::{ApiError, ErrorResponse};
+use api::middleware::{AuthUser, RequestId};
+use api::auth::ValidatedJson;
+use api::pagination::{PageLinks, Paginated, PaginatedUserResponse};
+use api::email_webhooks::EmailWebhooks;
+use api::boot::{Boot, BootPhase};
+use api::server_timing::Json;
+use api::session::CookieSessions;
+use api::startup::{ConfigSources, StartupReport};
+use api::tenants::TenantResolver;
+use api::users::{BatchUsersRequest, BatchUsersResponse, UserResponse};
+use api::health_checks::HealthCheckAccess;
+use application::account_deletion::{AccountDeletionService, AccountDeletionStore, EraseAccountJob};
+use application::activity::UserActivity;
+use application::admin::{AdminUserServiceImpl, BulkUserActionJob, BulkUserActions};
+use application::authz::AuthorizationService;
+use application::availability::{AvailabilityLimits, AvailabilityService};
+use application::cron::{CronScheduler, CronTask};
+use application::locks::DistributedLock;
+use application::crud::CrudService;
+use application::data_browser::DataBrowserService;
+use application::devices::DeviceService;
+use application::email::{self, EmailSender, SendEmailJob};
+use application::email_suppression::{EmailSuppressionList, SuppressingEmailSender};
+use application::idempotency::{IdempotencyStore, PruneIdempotencyKeysJob};
+use application::impersonation::Impersonation;
+use application::imports::{UserImportJob, UserImporter, UserImports};
+use application::jobs::{JobQueue, JobRunner, PruneJobsJob};
+use application::notes::UserNoteService;
+use application::messaging::spawn_event_relay;
+use application::notifications::{spawn_event_notifications, NotificationService};
+use application::operations::OperationStore;
+use application::password_policy::PasswordPolicy;
+use application::presence::{PresenceStore, PresenceTracker};
+use application::queries::UserQueryService;
+use application::refresh_tokens::{PruneRefreshTokensJob, PurgeRevokedSessionsTask, RefreshTokenStore, RefreshTokens};
+use application::resilience::ResilientRepository;
+use application::rollups::RollupMetricsTask;
+use application::feature_flags::ManagedFeatureFlags;
+use application::rate_limits::{ManagedRateLimiter, RateLimitOverrideStore};
+use application::segments::SegmentService;
+use application::organizations::OrganizationService;
+use application::settings::{NotificationUpdate, SettingsUpdate, UserSettingsService};
+use application::data_export::{self, DataExport, DataExportService, DataExportStore, DeleteExportJob, ExportFormat, ExportUserDataJob};
+use application::storage::{AvatarService, FileStorage, ProcessAvatarJob, UploadScanner};
+use application::support::{ContactLimits, SupportServiceImpl};
+use application::request_signing::RequestSigning;
+use application::tagging::TagService;
+use application::tenancy::{TenantDirectory, TenantScopedUserRepository};
+use application::token_versions::TokenVersions;
+use application::webhooks::WebhookServiceImpl;
+#[cfg(feature = "webhooks")]
+use application::webhooks::{self, DeliverWebhookJob};
+use domain::{WebhookDeliveryRepository, WebhookRepository};
+use application::{AuthServiceImpl, ConsistencyTracker, EventBus, ExperimentServiceImpl, TokenService, UserServiceImpl};
+use domain::{FlagContext, PaginationParams, UserSettings};
+use infrastructure::{feature_flags_from_settings, ArgonPasswordHasher, AuditExportConfig, AuditExporter, ConsoleEmailSender, InMemoryLock, InMemoryPresenceStore, PgEmailSuppressionList, PgApiClientStore, PgDeviceStore, Database, DatabaseDiagnostics, PgIdempotencyStore, EmailConfig, EmailRenderer, EmailTransport, InMemoryRateLimiter, InMemoryEventBus, JwtConfig, JwtTokenService, LocalFileStorage, NativeImageProcessor, PgAccountDeletionStore, PgActivityStore, PgAdvisoryLock, PgDataBrowser, PgDataExportStore, PgExperimentAssignmentStore, PgJobQueue, PgInvitationStore, PgMetricRollupStore, PgOperationStore, PgRateLimitOverrideStore, PgRefreshTokenStore, PgUnitOfWork, PgUserReadModel, PostgresMembershipRepository, PostgresNotificationRepository, PostgresOrganizationRepository, PostgresRoleRepository, PostgresSupportTicketRepository, PostgresTagRepository, PostgresTenantRepository, PostgresUserNoteRepository, PostgresUserRepository, PostgresUserSegmentRepository, PostgresUserSettingsRepository, PostgresWebhookDeliveryRepository, PostgresWebhookRepository, S3FileStorage, ScannerConfig, SmtpEmailSender, PgFeatureFlagStore, StorageBackend, StorageConfig, TracingAnalyticsSink};
+#[cfg(feature = "webhooks")]
+use infrastructure::HttpWebhookSender;
 
 // Re-export auth types for OpenAPI
-use auth::{RegisterRequest, LoginRequest, AuthResponse, TokenResponse, UserDto};
+use auth::{RegisterRequest, LoginRequest, RefreshRequest, ChangePasswordRequest, AuthResponse, TokenResponse, UserDto};
 
 // ============================================================================
 // OpenAPI Documentation
@@ -45,117 +104,1136 @@ use auth::{RegisterRequest, LoginRequest, AuthResponse, TokenResponse, UserDto};
     paths(
         auth::register,
         auth::login,
+        auth::refresh,
+        auth::check_availability,
+        auth::start_guest_session,
+        auth::upgrade_guest,
+        auth::change_password,
+        auth::logout_all,
+        auth::list_devices,
+        auth::delete_device,
+        auth::list_logins,
+        notifications::list_notifications,
+        notifications::mark_notification_read,
         list_users,
+        autocomplete_users,
         get_user,
+        get_users_batch,
         get_current_user,
+        upload_avatar,
+        delete_avatar,
+        request_data_export,
+        get_data_export,
+        get_my_settings,
+        update_my_settings,
+        download_data_export,
+        delete_account,
+        cancel_account_deletion,
+        get_my_experiments,
+        expose_experiment,
+        features::get_my_features,
+        organizations::list_organizations,
+        organizations::create_organization,
+        organizations::get_organization,
+        organizations::rename_organization,
+        organizations::delete_organization,
+        organizations::list_members,
+        organizations::change_member_role,
+        organizations::remove_member,
+        organizations::list_invitations,
+        organizations::invite_member,
+        organizations::revoke_invitation,
+        organizations::accept_invitation,
+        admin::list_jobs,
+        admin::list_tables,
+        admin::browse_table,
+        admin::create_webhook,
+        admin::list_webhooks,
+        admin::delete_webhook,
+        admin::list_webhook_deliveries,
+        admin::search_users,
+        admin::bulk_user_action,
+        admin::import_users,
+        admin::get_operation,
+        admin::suspend_user,
+        admin::unsuspend_user,
+        admin::force_password_reset,
+        admin::impersonate_user,
+        admin::delete_user,
+        admin::lift_email_suppression,
+        admin::list_tags,
+        admin::get_rate_limit_usage,
+        admin::reset_rate_limits,
+        admin::get_db_stats,
+        admin::reset_db_stats,
+        admin::list_rate_limit_overrides,
+        admin::create_rate_limit_override,
+        admin::delete_rate_limit_override,
+        admin::list_feature_flags,
+        admin::get_feature_flag,
+        admin::create_feature_flag,
+        admin::update_feature_flag,
+        admin::delete_feature_flag,
+        admin::list_api_clients,
+        admin::create_api_client,
+        admin::delete_api_client,
+        admin::list_segments,
+        admin::create_segment,
+        admin::get_segment,
+        admin::delete_segment,
+        admin::list_segment_users,
+        admin::tag_user,
+        admin::untag_user,
+        admin::list_user_roles,
+        admin::grant_user_role,
+        admin::revoke_user_role,
+        admin::list_user_notes,
+        admin::create_user_note,
+        admin::update_user_note,
+        admin::delete_user_note,
+        email_webhooks::ses_notifications,
+        email_webhooks::sendgrid_events,
+        support::contact,
+        realtime::user_events,
+        realtime::user_presence,
         health_check,
+        startup::health_info,
     ),
     components(schemas(
         RegisterRequest,
         LoginRequest,
+        RefreshRequest,
+        ChangePasswordRequest,
         AuthResponse,
         TokenResponse,
+        auth::AvailabilityResponse,
+        auth::UsernameAvailabilityResponse,
+        auth::EmailAvailabilityResponse,
+        auth::DeviceResponse,
+        auth::DevicesResponse,
+        auth::LoginResponse,
+        auth::LoginsResponse,
+        notifications::NotificationResponse,
+        notifications::NotificationsResponse,
         UserDto,
         UserResponse,
+        BatchUsersRequest,
+        BatchUsersResponse,
+        AvatarUpload,
+        DataExportResponse,
+        UserSettingsResponse,
+        NotificationSettings,
+        UpdateSettingsRequest,
+        UpdateNotificationSettings,
+        organizations::OrganizationRequest,
+        organizations::OrganizationResponse,
+        organizations::OrganizationsResponse,
+        organizations::MemberResponse,
+        organizations::ChangeRoleRequest,
+        organizations::InviteRequest,
+        organizations::InvitationResponse,
+        organizations::InvitationsResponse,
+        AccountDeletionResponse,
+        // Every Paginated<T> alias (see api::pagination)
         PaginatedUserResponse,
+        UserSuggestion,
+        UserSuggestionsResponse,
+        realtime::PresenceResponse,
+        ErrorResponse,
+        api::error::ErrorBody,
+        api::error::FieldError,
         HealthResponse,
+        startup::HealthInfoResponse,
+        startup::SelfCheck,
+        startup::CheckStatus,
+        startup::ConfigEntry,
+        startup::ConfigSource,
+        ExperimentAssignmentResponse,
+        ExperimentsResponse,
+        admin::JobResponse,
+        admin::JobsResponse,
+        admin::TableResponse,
+        admin::TableRowsResponse,
+        admin::CreateWebhookRequest,
+        admin::WebhookResponse,
+        admin::CreatedWebhookResponse,
+        admin::WebhookDeliveryResponse,
+        admin::AdminUserResponse,
+        admin::BulkUserRequest,
+        admin::UserCriteria,
+        admin::OperationResponse,
+        admin::OperationItemResponse,
+        admin::ImportSummaryResponse,
+        admin::ImportErrorResponse,
+        admin::EmailSuppressionResponse,
+        admin::CreateNoteRequest,
+        admin::UpdateNoteRequest,
+        admin::NoteResponse,
+        admin::TagResponse,
+        admin::TagsResponse,
+        admin::CreateSegmentRequest,
+        admin::SegmentResponse,
+        admin::SegmentsResponse,
+        admin::RateLimitSubjectQuery,
+        admin::RateLimitCounterResponse,
+        admin::RateLimitOverrideResponse,
+        admin::RateLimitUsageResponse,
+        admin::RateLimitResetResponse,
+        admin::DbStatsResponse,
+        admin::QueryStatsResponse,
+        admin::LatencyBucketResponse,
+        admin::RateLimitOverridesResponse,
+        admin::CreateRateLimitOverrideRequest,
+        features::FeaturesResponse,
+        admin::FeatureFlagResponse,
+        admin::FeatureFlagsResponse,
+        admin::FeatureFlagRequest,
+        admin::ApiClientResponse,
+        admin::CreatedApiClientResponse,
+        admin::ApiClientsResponse,
+        admin::CreateApiClientRequest,
+        admin::UserRolesResponse,
+        support::ContactSupportRequest,
+        support::ContactSupportResponse,
     )),
     tags(
         (name = "Authentication", description = "User registration and login"),
         (name = "Users", description = "User management endpoints"),
+        (name = "Organizations", description = "Organizations, their members and invitations"),
+        (name = "Support", description = "Contact the support team"),
+        (name = "Email", description = "Email provider callbacks (bounces and complaints)"),
+        (name = "Admin", description = "Administration endpoints (admin role)"),
         (name = "Health", description = "Health check endpoints")
-    )
+    ),
+    modifiers(&SecurityAddon)
 )]
 struct ApiDoc;
 
-// ============================================================================
-// Application State
-// ============================================================================
+/// Defines the `bearer_auth` scheme that secured paths refer to
+struct SecurityAddon;
 
-pub struct AppState {
-    pub user_service: Arc<dyn UserService>,
-    pub auth_service: Arc<dyn AuthService>,
-    pub token_service: Arc<dyn TokenService>,
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .description(Some("Access token from `POST /api/v1/auth/login`"))
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// The v1 document, including resources generated by `crud_entity!`
+fn api_doc() -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+    doc.merge(tenants::admin::ApiDoc::openapi());
+    secure_admin_paths(&mut doc);
+    // Builds without the `webhooks` feature don't serve /admin/webhooks
+    #[cfg(not(feature = "webhooks"))]
+    doc.paths.paths.retain(|path, _| !path.starts_with("/api/v1/admin/webhooks"));
+    doc
+}
+
+/// Generated CRUD paths carry no auth annotations; everything under
+/// `/api/v1/admin/` needs an admin's token
+fn secure_admin_paths(doc: &mut utoipa::openapi::OpenApi) {
+    let error = |description: &str| {
+        ResponseBuilder::new()
+            .description(description)
+            .content("application/json", Content::new(Ref::from_schema_name("ErrorResponse")))
+            .build()
+    };
+    let admin_paths = doc.paths.paths.iter_mut().filter(|(path, _)| path.starts_with("/api/v1/admin/"));
+    for operation in admin_paths.flat_map(|(_, item)| item.operations.values_mut()) {
+        if operation.security.is_none() {
+            operation.security = Some(vec![SecurityRequirement::new("bearer_auth", Vec::<String>::new())]);
+            let responses = &mut operation.responses.responses;
+            responses.insert("401".to_string(), error("Missing or invalid token").into());
+            responses.insert("403".to_string(), error("Admin role required").into());
+        }
+    }
 }
 
 // ============================================================================
 // Main Entry Point
 // ============================================================================
 
+#[derive(Parser)]
+#[command(name = "api", about = "Rust Base API server")]
+struct Cli {
+    /// Directory with default.toml, <profile>.toml and local.toml [env: CONFIG_DIR]
+    #[arg(long)]
+    config_dir: Option<std::path::PathBuf>,
+    /// Configuration profile, e.g. production [env: APP_ENV]
+    #[arg(long)]
+    profile: Option<String>,
+    /// Override a setting, e.g. `--set jwt.expiration_hours=1` (repeatable)
+    #[arg(long = "set", value_name = "KEY=VALUE", value_parser = shared::parse_override)]
+    overrides: Vec<(String, String)>,
+    /// Listen port; same as `--set server.port=...`
+    #[arg(long)]
+    port: Option<u16>,
+    /// Print the effective configuration (secrets redacted) and exit
+    #[arg(long)]
+    print_config: bool,
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Load .env file
-    dotenvy::dotenv().ok();
+    // Load .env file, remembering which settings it provided
+    let config_sources = ConfigSources::load_dotenv();
+    let cli = Cli::parse();
 
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(
-            std::env::var("RUST_LOG").unwrap_or_else(|_| "info,tower_http=debug".into()),
-        ))
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    // Startup runs phase by phase (see boot::BootPhase); a failing phase
+    // tears down what the earlier ones started and names itself in the error
+    let mut boot = Boot::new();
 
-    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    
-    let pool = sqlx::PgPool::connect(&database_url).await?;
-    
-    // Create shared dependencies
-    let user_repository = Arc::new(PostgresUserRepository::new(pool));
+    // Layered settings: config/*.toml, environment, then command-line
+    // overrides; `--print-config` stops after loading, before validation
+    let mut load_options = LoadOptions::from_env();
+    if let Some(dir) = cli.config_dir {
+        load_options.config_dir = dir;
+    }
+    if let Some(profile) = cli.profile {
+        load_options.profile = profile;
+    }
+    load_options.overrides = cli.overrides;
+    if let Some(port) = cli.port {
+        load_options.overrides.push(("server.port".to_string(), port.to_string()));
+    }
+    let config = boot
+        .run(BootPhase::Config, async {
+            let config = Config::load(&load_options)?;
+            if cli.print_config {
+                println!("{}", serde_json::to_string_pretty(&config.redacted())?);
+                return Ok(None);
+            }
+            config.validate(&load_options.profile)?;
+            domain::set_max_offset(config.pagination.max_offset);
+            domain::set_ignore_gmail_dots(config.account.ignore_gmail_dots);
+            let slow_query_ms = config.database.slow_query_ms;
+            application::query_stats::query_stats()
+                .set_slow_threshold((slow_query_ms > 0).then(|| Duration::from_millis(slow_query_ms)));
+            Ok(Some(config))
+        })
+        .await?;
+    let Some(config) = config else {
+        return Ok(());
+    };
+
+    // Initialize tracing (log.format = "json" for structured output), exporting
+    // audit events when audit.sink is set. Metrics backend (telemetry.backend);
+    // Prometheus is also scraped at /metrics.
+    let prometheus = boot
+        .run(BootPhase::Telemetry, async {
+            let audit_exporter = match AuditExportConfig::from_settings(&config.audit, &config.log) {
+                Some(config) => Some(AuditExporter::spawn(config).await?),
+                None => None,
+            };
+            logging::init(&config.log, audit_exporter);
+            let telemetry = infrastructure::telemetry_from_settings(&config.telemetry, logging::service_name())?;
+            shared::telemetry::set_telemetry(telemetry.telemetry);
+            Ok(telemetry.prometheus)
+        })
+        .await?;
+
+    // Primary pool, plus an optional read replica (reads fall back to the
+    // primary for read-your-writes, and while the replica lags more than
+    // database.replica_max_lag_ms)
+    let (database, pools) = boot
+        .run(BootPhase::Database, async {
+            let pool_config = &config.database.pool;
+            let pool = infrastructure::connect_pool(&config.database.url, pool_config).await?;
+            if pool_config.metrics_interval_secs > 0 {
+                let interval = Duration::from_secs(pool_config.metrics_interval_secs);
+                infrastructure::spawn_pool_metrics(pool.clone(), "primary", interval);
+            }
+            let mut pools = vec![pool.clone()];
+            let mut database = Database::new(pool).with_window_counts(config.pagination.count == "window");
+
+            if let Some(replica_url) = &config.database.replica_url {
+                let max_lag_ms = config.database.replica_max_lag_ms;
+                let replica = infrastructure::connect_pool(replica_url, pool_config).await?;
+                if pool_config.metrics_interval_secs > 0 {
+                    let interval = Duration::from_secs(pool_config.metrics_interval_secs);
+                    infrastructure::spawn_pool_metrics(replica.clone(), "replica", interval);
+                }
+                pools.push(replica.clone());
+                database = database
+                    .with_replica(replica)
+                    .with_max_replica_lag(Duration::from_millis(max_lag_ms));
+                database.spawn_replica_lag_monitor(Duration::from_secs(1));
+                tracing::info!(max_lag_ms, "📚 Read replica configured");
+            }
+            Ok((database, pools))
+        })
+        .await?;
+    let database_primary = pools[0].clone();
+    boot.on_failure("close database pools", move || async move {
+        for pool in pools {
+            pool.close().await;
+        }
+    });
+
+    // Announced as service.migrations_applied once the event bus is up
+    let applied_migrations = boot
+        .run(BootPhase::Migrations, async {
+            if !config.database.run_migrations {
+                return Ok(Vec::new());
+            }
+            let applied = infrastructure::run_migrations(&database_primary).await?;
+            tracing::info!(count = applied.len(), "🗄️  Migrations applied: {:?}", applied);
+            Ok(applied)
+        })
+        .await?;
+
+    let services = boot
+        .run(
+            BootPhase::Caches,
+            build_services(&config, &config_sources, &load_options, database, DatabaseDiagnostics::new(database_primary)),
+        )
+        .await?;
+    let Services {
+        state,
+        job_runner,
+        cron,
+        job_workers,
+        #[cfg(feature = "webhooks")]
+        webhook_repository,
+        #[cfg(feature = "webhooks")]
+        delivery_repository,
+        tenant_repository,
+        idempotency_store,
+    } = services;
+
+    let workers = boot
+        .run(BootPhase::Workers, async {
+            // Domain events to WebSocket/SSE clients, notifications, welcome emails and webhooks
+            realtime::spawn_event_forwarder(state.event_bus.clone(), state.realtime.clone());
+            spawn_event_notifications(state.event_bus.clone(), state.notifications.clone());
+            email::spawn_welcome_emails(state.event_bus.clone(), state.job_queue.clone());
+            #[cfg(feature = "webhooks")]
+            webhooks::spawn_webhook_dispatcher(
+                state.event_bus.clone(),
+                webhook_repository,
+                delivery_repository,
+                state.job_queue.clone(),
+            );
+
+            // Domain events to the message broker, for services outside this one
+            if let Some(broker) = infrastructure::broker_from_settings(&config.broker).await? {
+                tracing::info!(backend = %config.broker.backend, "📨 Message broker connected");
+                if config.broker.publish_events {
+                    spawn_event_relay(state.event_bus.clone(), broker.publisher.clone());
+                }
+            }
+
+            #[cfg(feature = "jobs")]
+            let mut tasks = {
+                let tasks = Arc::new(job_runner).spawn(job_workers);
+                tracing::info!("⚙️  {} job workers started", job_workers);
+                tasks
+            };
+            // Jobs stay queued for the instances built with `jobs`
+            #[cfg(not(feature = "jobs"))]
+            let mut tasks = {
+                let _ = (job_runner, job_workers);
+                tracing::info!("⚙️  Job workers are left out of this build");
+                Vec::new()
+            };
+            if let Some(cron) = cron {
+                tracing::info!(tasks = ?cron.tasks(), "⏰ Cron scheduler started");
+                tasks.extend(Arc::new(cron).spawn());
+            }
+            Ok(tasks)
+        })
+        .await?;
+    boot.on_failure("stop job workers", move || async move {
+        for task in workers {
+            task.abort();
+        }
+    });
+
+    // Lifecycle events (service.started, service.shutting_down) go out on the bus
+    let event_bus = state.event_bus.clone();
+
+    let (listener, tls, app) = boot
+        .run(BootPhase::Listeners, async {
+            // gRPC server on its own port, sharing the same application services
+            #[cfg(feature = "grpc")]
+            {
+                let grpc_addr = std::net::SocketAddr::from(([0, 0, 0, 0], config.server.grpc_port));
+                let grpc_services = grpc::GrpcServices {
+                    user_service: state.user_service.clone(),
+                    auth_service: state.auth_service.clone(),
+                    token_service: state.token_service.clone(),
+                    token_versions: state.token_versions.clone(),
+                };
+                tokio::spawn(async move {
+                    tracing::info!("🔌 gRPC listening on {}", grpc_addr);
+                    if let Err(e) = grpc::serve(grpc_addr, grpc_services).await {
+                        tracing::error!("gRPC server failed: {}", e);
+                    }
+                });
+            }
+
+            // Client IPs come from X-Forwarded-For only behind a trusted proxy
+            middleware::set_trust_forwarded_for(config.server.trust_forwarded_for);
+
+            // Request/response logging (opt-in via http_log.enabled)
+            let http_log = Arc::new(logging::HttpLogConfig::from_settings(&config.http_log));
+
+            // Tenant of each request (X-Tenant-Id, or the subdomain under tenancy.base_domain)
+            let tenant_resolver = Arc::new(TenantResolver::new(
+                Arc::new(TenantDirectory::new(tenant_repository)),
+                &config.tenancy,
+            ));
+
+            // Idempotency-Key replay for authenticated POSTs
+            let idempotency = Arc::new(idempotency::Idempotency::new(
+                idempotency_store,
+                state.token_service.clone(),
+                state.token_versions.clone(),
+                &config.idempotency,
+            ));
+
+            // CORS (cors.* settings; see shared::CorsConfig)
+            let cors = cors_layer(&config.cors);
+
+            // gzip/br in both directions (server.compression.* settings)
+            let compression_config = Arc::new(config.server.compression.clone());
+
+            // Auth/db/serialize breakdown in Server-Timing for admins ([server.timing])
+            let server_timing_config = Arc::new(config.server.timing.clone());
+
+            // HMAC-signed requests from API clients ([request_signing])
+            let signed_requests = Arc::new(request_signing::SignedRequests::new(
+                state.request_signing.clone(),
+                &config.request_signing,
+            ));
+
+            // Load balancer probes trusted by network or shared secret ([health_checks])
+            let health_check_access = Arc::new(HealthCheckAccess::new(&config.health_checks));
+
+            // Combine all routes with global middlewares
+            let router = Router::new()
+                .merge(swagger_ui())
+                .route("/health", get(health_check))
+                .merge(startup::health_info_routes(state.clone()))
+                .merge(startup::metrics_routes(prometheus))
+                .merge(files::file_routes())
+                .merge(well_known::well_known_routes(&config.well_known))
+                .merge(realtime::realtime_routes())
+                .nest("/api/v1", api_v1_routes(state.clone()))
+                .nest("/api/v2", api_v2_routes(state.clone()))
+                .layer(CookieManagerLayer::new())
+                .layer(axum_mw::from_fn(middleware::http_metrics))
+                .layer(axum_mw::from_fn_with_state(signed_requests, request_signing::signed_requests))
+                .layer(axum_mw::from_fn_with_state(http_log, logging::log_requests))
+                .layer(axum_mw::from_fn_with_state(tenant_resolver, tenants::resolve_tenant))
+                .layer(TraceLayer::new_for_http().make_span_with(logging::make_request_span))
+                .layer(axum_mw::from_fn_with_state(idempotency, idempotency::idempotency_keys))
+                .layer(axum_mw::from_fn_with_state(state.clone(), middleware::read_your_writes))
+                .layer(axum_mw::from_fn_with_state(health_check_access, health_checks::trusted_probes))
+                .layer(axum_mw::from_fn_with_state(server_timing_config, server_timing::server_timing))
+                .layer(axum_mw::from_fn(middleware::request_id))
+                .layer(axum_mw::from_fn(middleware::served_by))
+                .layer(compression::response_compression(&compression_config))
+                .layer(axum_mw::from_fn_with_state(compression_config.clone(), compression::prefer_encoding))
+                .layer(compression::request_decompression(&compression_config))
+                .layer(cors)
+                .with_state(state);
+
+            // Version negotiation rewrites legacy paths, so it has to run before routing
+            let app = axum_mw::from_fn(versioning::negotiate_version).layer(router);
+
+            // HTTPS served directly when server.tls has a certificate and key
+            let tls_config = &config.server.tls;
+            let tls = match (&tls_config.cert_path, &tls_config.key_path) {
+                (Some(cert_path), Some(key_path)) => {
+                    Some(Arc::new(tls::ReloadableTls::load(cert_path, key_path)?.with_http2(config.server.http2)))
+                }
+                _ => None,
+            };
+            let scheme = if tls.is_some() { "https" } else { "http" };
+
+            // HTTP/2, keep-alive and TCP settings (server.*) apply to both schemes
+            let addr = format!("{}:{}", config.server.host, config.server.port);
+            let listener = server::bind(&config.server).await?;
+            tracing::info!(
+                service = %logging::service_name(),
+                region = %logging::region(),
+                version = env!("CARGO_PKG_VERSION"),
+                "🚀 Server listening on {}://{}",
+                scheme,
+                addr
+            );
+            tracing::info!("📖 Swagger UI: {}://{}/swagger-ui/", scheme, addr);
+            tracing::info!("📄 OpenAPI JSON: {}://{}/api-docs/openapi.json", scheme, addr);
+
+            if let Some(tls) = &tls {
+                tls::reload_on_sighup(tls.clone());
+            }
+            if let Some(redirect_port) = tls_config.redirect_port.filter(|_| tls.is_some()) {
+                let redirect_addr = format!("{}:{}", config.server.host, redirect_port);
+                let redirect_listener = TcpListener::bind(&redirect_addr).await?;
+                let redirect = tls::https_redirect(config.server.port);
+                tracing::info!("↪️  Redirecting http://{} to HTTPS", redirect_addr);
+                tokio::spawn(async move {
+                    if let Err(e) = axum::serve(redirect_listener, redirect).await {
+                        tracing::error!("HTTPS redirect listener failed: {}", e);
+                    }
+                });
+            }
+            Ok((listener, tls, app))
+        })
+        .await?;
+    boot.finish();
+
+    startup::publish_started(event_bus.as_ref(), applied_migrations);
+    server::serve(listener, &config.server, tls, app, startup::shutdown_signal(event_bus)).await?;
+
+    Ok(())
+}
+
+/// What the caches phase wires up for the phases after it
+struct Services {
+    state: Arc<AppState>,
+    job_runner: JobRunner,
+    cron: Option<CronScheduler>,
+    job_workers: usize,
+    #[cfg(feature = "webhooks")]
+    webhook_repository: Arc<dyn WebhookRepository>,
+    #[cfg(feature = "webhooks")]
+    delivery_repository: Arc<dyn WebhookDeliveryRepository>,
+    tenant_repository: Arc<PostgresTenantRepository>,
+    idempotency_store: Arc<dyn IdempotencyStore>,
+}
+
+/// Repositories and services, with their caches warmed (authorization,
+/// rate limit overrides, presence) and the startup self-checks run
+async fn build_services(
+    config: &Config,
+    config_sources: &ConfigSources,
+    load_options: &LoadOptions,
+    database: Database,
+    diagnostics: DatabaseDiagnostics,
+) -> anyhow::Result<Services> {
+    // Shared dependencies; user queries are confined to the request's tenant
+    let unscoped_users = Arc::new(PostgresUserRepository::new(database.clone()));
+    let user_repository = Arc::new(TenantScopedUserRepository::new(unscoped_users.clone()));
+    // Reads served as projections, without loading password hashes
+    let user_queries = Arc::new(UserQueryService::new(Arc::new(PgUserReadModel::new(database.clone()))));
+    let tenant_repository = Arc::new(PostgresTenantRepository::new(database.clone()));
+    let tags = Arc::new(TagService::new(Arc::new(PostgresTagRepository::new(database.clone()))));
+    // API clients signing their requests, within request_signing.max_skew_secs
+    let request_signing = Arc::new(
+        RequestSigning::new(Arc::new(PgApiClientStore::new(database.clone())))
+            .with_max_skew(Duration::from_secs(config.request_signing.max_skew_secs)),
+    );
+    // Devices users sign in from; remembered ones stay trusted for account.trusted_device_days
+    let devices = Arc::new(
+        DeviceService::new(Arc::new(PgDeviceStore::new(database.clone())))
+            .with_trust_period(Duration::from_secs(u64::from(config.account.trusted_device_days) * 86_400)),
+    );
+    // Sign-ins are recorded as they happen; last_seen_at is written in batches,
+    // at most once per user every account.last_seen_interval_secs
+    let last_seen_interval = Duration::from_secs(config.account.last_seen_interval_secs.max(1));
+    let activity = Arc::new(
+        UserActivity::new(Arc::new(PgActivityStore::new(database.clone()))).with_debounce(last_seen_interval),
+    );
+    activity.clone().spawn_flush(last_seen_interval.min(Duration::from_secs(60)));
+    let note_repository = Arc::new(PostgresUserNoteRepository::new(database.clone()));
+    let notification_repository = Arc::new(PostgresNotificationRepository::new(database.clone()));
+    let segment_repository = Arc::new(PostgresUserSegmentRepository::new(database.clone()));
+    let settings_repository = Arc::new(PostgresUserSettingsRepository::new(database.clone()));
+    let organization_repository = Arc::new(PostgresOrganizationRepository::new(database.clone()));
+    let membership_repository = Arc::new(PostgresMembershipRepository::new(database.clone()));
+    let invitation_store = Arc::new(PgInvitationStore::new(database.clone()));
+    // Roles granted to users are cached per request and for authz.cache_ttl_secs across requests
+    let authz = Arc::new(
+        AuthorizationService::new(Arc::new(PostgresRoleRepository::new(database.clone())))
+            .with_ttl(Duration::from_secs(config.authz.cache_ttl_secs)),
+    );
+    let unit_of_work = Arc::new(PgUnitOfWork::new(database.clone()));
+    let job_queue: Arc<dyn JobQueue> = Arc::new(PgJobQueue::new(database.clone()));
+    let data_browser = Arc::new(DataBrowserService::new(Arc::new(PgDataBrowser::new(database.clone()))));
+    let webhook_repository: Arc<dyn WebhookRepository> = Arc::new(PostgresWebhookRepository::new(database.clone()));
+    let delivery_repository: Arc<dyn WebhookDeliveryRepository> =
+        Arc::new(PostgresWebhookDeliveryRepository::new(database.clone()));
+    let idempotency_store: Arc<dyn IdempotencyStore> = Arc::new(PgIdempotencyStore::new(database.clone()));
+    let email_suppressions: Arc<dyn EmailSuppressionList> = Arc::new(PgEmailSuppressionList::new(database.clone()));
+    let operations: Arc<dyn OperationStore> = Arc::new(PgOperationStore::new(database.clone()));
+    let refresh_token_store: Arc<dyn RefreshTokenStore> = Arc::new(PgRefreshTokenStore::new(database.clone()));
+    let account_deletion_store: Arc<dyn AccountDeletionStore> = Arc::new(PgAccountDeletionStore::new(database.clone()));
+    let data_export_store: Arc<dyn DataExportStore> = Arc::new(PgDataExportStore::new(database.clone()));
+    let rate_limit_override_store: Arc<dyn RateLimitOverrideStore> = Arc::new(PgRateLimitOverrideStore::new(database.clone()));
+    // Named locks keep singleton work (e.g. each cron task) on one replica
+    let lock = distributed_lock(config, &database).await?;
+    let metric_rollups = Arc::new(PgMetricRollupStore::new(database.clone()));
+    let experiment_assignments = Arc::new(PgExperimentAssignmentStore::new(database.clone()));
+    // Per-instance counters shared by every rate-limited use case; admins can
+    // raise a user's or address's limits for a while (/admin/rate-limits)
+    let rate_limits = Arc::new(ManagedRateLimiter::new(Arc::new(InMemoryRateLimiter::new()), rate_limit_override_store));
+    rate_limits.refresh().await?;
+    rate_limits
+        .clone()
+        .spawn_refresh(Duration::from_secs(config.rate_limit.override_refresh_secs.max(1)));
+    // features.flags, overridden and extended by admins (/admin/feature-flags)
+    let feature_flags = Arc::new(ManagedFeatureFlags::new(
+        Arc::new(PgFeatureFlagStore::new(database.clone())),
+        feature_flags_from_settings(&config.features)?,
+    ));
+    feature_flags.refresh().await?;
+    feature_flags
+        .clone()
+        .spawn_refresh(Duration::from_secs(config.features.refresh_secs.max(1)));
+    let support_service = Arc::new(SupportServiceImpl::new(
+        Arc::new(PostgresSupportTicketRepository::new(database)),
+        rate_limits.clone(),
+        job_queue.clone(),
+        config.support.notify_emails.clone(),
+    )
+    .with_limits(ContactLimits {
+        per_sender: config.rate_limit.support_per_sender,
+        per_email: config.rate_limit.support_per_email,
+        window: Duration::from_secs(config.rate_limit.support_window_secs),
+    }));
+    let storage_config = StorageConfig::from_settings(&config.storage);
+    let file_storage: Arc<dyn FileStorage> = match &storage_config.backend {
+        StorageBackend::Local { root } => Arc::new(LocalFileStorage::new(
+            root.clone(),
+            storage_config.public_url.clone(),
+            storage_config.signing_secret.clone(),
+        )),
+        StorageBackend::S3 { bucket } => Arc::new(S3FileStorage::new(bucket.clone(), storage_config.public_url.clone())?),
+    };
     let password_hasher = Arc::new(ArgonPasswordHasher::new());
-    let jwt_config = JwtConfig::from_env();
-    let token_service: Arc<dyn TokenService> = Arc::new(JwtTokenService::new(jwt_config));
+    let jwt_config = JwtConfig::new(config.jwt.secret.clone(), config.jwt.expiration_hours)
+        .with_previous_secrets(config.jwt.previous_secrets.clone())
+        .with_guest_expiration_minutes(config.jwt.guest_expiration_minutes)
+        .with_impersonation_expiration_minutes(config.jwt.impersonation_expiration_minutes);
+
+    // Log the effective configuration and self-check results (GET /health/info)
+    let startup = Arc::new(StartupReport::run(config_sources, config, &diagnostics).await);
+    startup.log();
+    // Secrets rotate without a restart: they are reloaded on SIGHUP and
+    // every jwt.key_reload_secs
+    let jwt_tokens = Arc::new(JwtTokenService::new(jwt_config));
+    let key_reload = (config.jwt.key_reload_secs > 0).then(|| Duration::from_secs(config.jwt.key_reload_secs));
+    jwt_keys::reload_jwt_keys(jwt_tokens.clone(), load_options.clone(), key_reload);
+    let token_service: Arc<dyn TokenService> = jwt_tokens;
+    let event_bus: Arc<dyn EventBus> = Arc::new(InMemoryEventBus::default());
+    let realtime = Arc::new(realtime::ConnectionManager::new());
+
+    // Uploads are scanned before they are stored (scanner.backend); infected
+    // files are quarantined and the uploader is notified
+    let upload_scanner = Arc::new(UploadScanner::new(
+        ScannerConfig::from_settings(&config.scanner).build()?,
+        file_storage.clone(),
+        event_bus.clone(),
+    ));
+    let avatars = Arc::new(
+        AvatarService::new(user_repository.clone(), file_storage.clone(), config.account.avatar_max_bytes)
+            .with_scanner(upload_scanner)
+            .with_processing(job_queue.clone()),
+    );
+    let avatar_processing = Arc::new(ProcessAvatarJob::new(
+        user_repository.clone(),
+        file_storage.clone(),
+        Arc::new(NativeImageProcessor::new()),
+    ));
+    // Account data exports are built by the job workers and emailed as a
+    // single-use link; the file is deleted once downloaded or expired
+    let data_exports = Arc::new(DataExportService::new(
+        data_export_store.clone(),
+        file_storage.clone(),
+        job_queue.clone(),
+    ));
+    // Deleted accounts are anonymized by a job scheduled at the end of the grace period
+    let account_deletions = Arc::new(
+        AccountDeletionService::new(account_deletion_store.clone(), job_queue.clone())
+            .with_grace_period(Duration::from_secs(u64::from(config.account.deletion_grace_days) * 86_400)),
+    );
+    let erase_accounts = Arc::new(
+        EraseAccountJob::new(account_deletion_store, user_repository.clone(), avatars.clone()).with_activity(activity.clone()),
+    );
+    let export_data = Arc::new(
+        ExportUserDataJob::new(
+            data_export_store,
+            user_repository.clone(),
+            refresh_token_store.clone(),
+            file_storage.clone(),
+            job_queue.clone(),
+        )
+        .with_download_url(config.account.export_download_url.clone()),
+    );
+    let delete_exports = Arc::new(DeleteExportJob::new(file_storage.clone()));
+
+    // Presence is shared through Redis when redis.url is set (required with
+    // several instances); otherwise it is tracked in process memory
+    let presence_store: Arc<dyn PresenceStore> = match &config.redis.url {
+        #[cfg(feature = "redis")]
+        Some(url) => Arc::new(infrastructure::RedisPresenceStore::connect(url).await?),
+        #[cfg(not(feature = "redis"))]
+        Some(_) => anyhow::bail!("redis.url is set, but this build leaves out the `redis` feature"),
+        None => Arc::new(InMemoryPresenceStore::new()),
+    };
+    let presence = Arc::new(PresenceTracker::new(presence_store));
     
     // Create services
     let user_service = Arc::new(UserServiceImpl::new(user_repository.clone()));
-    let auth_service = Arc::new(AuthServiceImpl::new(
+    let token_versions = Arc::new(
+        TokenVersions::new(user_repository.clone())
+            .with_cache_ttl(Duration::from_secs(config.jwt.token_version_cache_secs)),
+    );
+    let impersonation = Arc::new(Impersonation::new(user_repository.clone(), authz.clone(), token_service.clone()));
+    let admin_users = Arc::new(AdminUserServiceImpl::new(user_repository.clone()));
+    let bulk_users = Arc::new(BulkUserActions::new(operations.clone(), job_queue.clone()));
+    let bulk_user_actions = Arc::new(BulkUserActionJob::new(
+        operations.clone(),
+        admin_users.clone(),
+        user_repository.clone(),
+        authz.clone(),
+    ));
+    let user_notes = Arc::new(UserNoteService::new(note_repository, user_repository.clone()));
+    let notifications = Arc::new(NotificationService::new(notification_repository, event_bus.clone()));
+    let segments = Arc::new(SegmentService::new(segment_repository, user_repository.clone()));
+    let user_settings = Arc::new(UserSettingsService::new(settings_repository));
+    let organizations = Arc::new(
+        OrganizationService::new(
+            organization_repository,
+            membership_repository,
+            invitation_store,
+            user_repository.clone(),
+            job_queue.clone(),
+        )
+        .with_invitation_ttl(Duration::from_secs(u64::from(config.organizations.invitation_ttl_days) * 86_400))
+        .with_invitation_url(config.organizations.invitation_url.clone()),
+    );
+    let password_policy = PasswordPolicy {
+        min_length: config.password.min_length,
+        require_lowercase: config.password.require_lowercase,
+        require_uppercase: config.password.require_uppercase,
+        require_digit: config.password.require_digit,
+        require_symbol: config.password.require_symbol,
+        min_score: config.password.min_score,
+        banned: config.password.banned.iter().map(|p| p.to_lowercase()).collect(),
+    };
+    // Admin imports: small ones during the request, the rest as operations
+    let user_importer = Arc::new(
+        UserImporter::new(user_repository.clone(), password_hasher.clone(), unit_of_work.clone())
+            .with_password_policy(password_policy.clone())
+            .with_batch_size(config.imports.batch_size),
+    );
+    let user_imports = Arc::new(
+        UserImports::new(user_importer.clone(), operations.clone(), job_queue.clone(), file_storage.clone())
+            .with_limits(config.imports.inline_max_bytes, config.imports.max_bytes),
+    );
+    let import_users = Arc::new(UserImportJob::new(user_importer, operations.clone(), file_storage.clone()));
+    let mut auth_service = AuthServiceImpl::new(
         user_repository,
         password_hasher,
         token_service.clone(),
-    ));
+        event_bus.clone(),
+    )
+    .with_password_policy(password_policy.clone())
+    .with_activity(activity.clone());
+    if config.jwt.refresh_expiration_days > 0 {
+        auth_service = auth_service.with_refresh_tokens(Arc::new(RefreshTokens::new(
+            refresh_token_store.clone(),
+            Duration::from_secs(config.jwt.refresh_expiration_days as u64 * 86_400),
+        )));
+    }
+    let auth_service = Arc::new(auth_service);
+    // Sign-up forms check usernames and emails across every tenant, as registration does
+    let availability = Arc::new(
+        AvailabilityService::new(unscoped_users, rate_limits.clone()).with_limits(AvailabilityLimits {
+            per_client: config.rate_limit.availability_per_client,
+            window: Duration::from_secs(config.rate_limit.availability_window_secs),
+        }),
+    );
     
     let state = Arc::new(AppState {
         user_service,
+        user_queries,
         auth_service,
+        availability,
+        authz,
         token_service,
+        token_versions,
+        impersonation,
+        realtime,
+        presence,
+        event_bus,
+        unit_of_work,
+        consistency: Arc::new(ConsistencyTracker::new(Duration::from_secs(30))),
+        feature_flags,
+        experiments: Arc::new(ExperimentServiceImpl::new(
+            infrastructure::experiments_from_settings(&config.experiments)?,
+            Arc::new(TracingAnalyticsSink::new()),
+            experiment_assignments,
+        )),
+        job_queue: job_queue.clone(),
+        data_browser,
+        support_service,
+        webhook_service: Arc::new(WebhookServiceImpl::new(webhook_repository.clone(), delivery_repository.clone())),
+        file_storage,
+        avatars,
+        data_exports,
+        account_deletions,
+        devices,
+        activity,
+        admin_users,
+        bulk_users,
+        user_imports,
+        operations,
+        user_notes,
+        notifications,
+        segments,
+        user_settings,
+        organizations,
+        rate_limits,
+        email_suppressions: email_suppressions.clone(),
+        email_webhooks: Arc::new(EmailWebhooks::new(email_suppressions.clone(), config.email.webhook_token.clone())),
+        tenants: Arc::new(CrudService::new(
+            Arc::new(ResilientRepository::new(tenant_repository.clone())),
+            "Tenant",
+        )),
+        tags,
+        request_signing,
+        sessions: Arc::new(CookieSessions::from_settings(&config.session)),
+        startup,
     });
 
-    // CORS configuration
-    let cors = CorsLayer::new()
+    // Email (email.transport = "smtp" to deliver; logged to the console otherwise),
+    // skipping addresses suppressed after bounces and complaints
+    let email_config = EmailConfig::from_settings(&config.email);
+    let email_renderer = EmailRenderer::new(email_config.app_name.clone());
+    let email_sender: Arc<dyn EmailSender> = match email_config.transport {
+        EmailTransport::Smtp => Arc::new(SmtpEmailSender::new(&email_config, email_renderer)?),
+        EmailTransport::Console => Arc::new(ConsoleEmailSender::new(email_renderer)),
+    };
+    let email_sender: Arc<dyn EmailSender> = Arc::new(SuppressingEmailSender::new(email_sender, email_suppressions));
+
+    // Background jobs, run by the workers phase
+    let job_workers = config.jobs.workers;
+    let job_retention_days = config.jobs.retention_days;
+    let prune_refresh_tokens = Arc::new(PruneRefreshTokensJob::new(refresh_token_store.clone()));
+    let job_runner = JobRunner::new(job_queue.clone())
+        .register(Arc::new(SendEmailJob::new(email_sender)))
+        .register(avatar_processing)
+        .register(export_data)
+        .register(delete_exports)
+        .register(erase_accounts)
+        .register(bulk_user_actions)
+        .register(import_users)
+        .register_recurring(
+            Arc::new(PruneJobsJob::new(job_queue, Duration::from_secs(job_retention_days * 86_400))),
+            Duration::from_secs(3600),
+        )
+        .register_recurring(
+            Arc::new(PruneIdempotencyKeysJob::new(idempotency_store.clone())),
+            Duration::from_secs(3600),
+        )
+        // Now run by cron; finishes runs queued before the move
+        .register(prune_refresh_tokens.clone());
+    // Deliveries queued by the webhook dispatcher
+    #[cfg(feature = "webhooks")]
+    let job_runner = job_runner.register(Arc::new(DeliverWebhookJob::new(
+        webhook_repository.clone(),
+        delivery_repository.clone(),
+        Arc::new(HttpWebhookSender::new()),
+    )));
+
+    let revoked_session_retention = Duration::from_secs(config.cron.revoked_session_retention_days * 86_400);
+    let cron = cron_scheduler(
+        &config.cron,
+        lock,
+        Duration::from_secs(config.locks.ttl_secs),
+        vec![
+            prune_refresh_tokens,
+            Arc::new(PurgeRevokedSessionsTask::new(refresh_token_store, revoked_session_retention)),
+            Arc::new(RollupMetricsTask::new(metric_rollups).with_max_backfill_days(config.cron.rollup_backfill_days)),
+        ],
+    )?;
+
+    Ok(Services {
+        state,
+        job_runner,
+        cron,
+        job_workers,
+        #[cfg(feature = "webhooks")]
+        webhook_repository,
+        #[cfg(feature = "webhooks")]
+        delivery_repository,
+        tenant_repository,
+        idempotency_store,
+    })
+}
+
+/// The `locks.backend` lock; `redis` connects to `redis.url`
+async fn distributed_lock(config: &Config, database: &Database) -> anyhow::Result<Arc<dyn DistributedLock>> {
+    Ok(match config.locks.backend.as_str() {
+        "redis" => match &config.redis.url {
+            #[cfg(feature = "redis")]
+            Some(url) => Arc::new(infrastructure::RedisLock::connect(url).await?),
+            #[cfg(not(feature = "redis"))]
+            Some(_) => anyhow::bail!("locks.backend is 'redis', but this build leaves out the `redis` feature"),
+            None => anyhow::bail!("locks.backend is 'redis', but redis.url is not set"),
+        },
+        "memory" => Arc::new(InMemoryLock::new()),
+        _ => Arc::new(PgAdvisoryLock::new(database.clone())),
+    })
+}
+
+/// Scheduler for the `tasks` that `cron.schedules` turns on; `None` when
+/// `cron.enabled` is off
+fn cron_scheduler(
+    settings: &CronSettings,
+    lock: Arc<dyn DistributedLock>,
+    lock_ttl: Duration,
+    tasks: Vec<Arc<dyn CronTask>>,
+) -> anyhow::Result<Option<CronScheduler>> {
+    if !settings.enabled {
+        return Ok(None);
+    }
+    for name in settings.schedules.keys() {
+        if !tasks.iter().any(|task| task.name() == name) {
+            anyhow::bail!("cron.schedules.{}: there is no such task", name);
+        }
+    }
+
+    let mut scheduler = CronScheduler::new(lock)
+        .with_clock_skew(Duration::from_secs(settings.clock_skew_secs))
+        .with_lock_ttl(lock_ttl);
+    for task in tasks {
+        match settings.schedules.get(task.name()).map(|expression| expression.trim()) {
+            Some(expression) if !expression.is_empty() => {
+                scheduler = scheduler
+                    .register(task.clone(), expression)
+                    .map_err(|e| anyhow::anyhow!("cron.schedules.{}: {}", task.name(), e))?;
+            }
+            _ => tracing::info!(task = task.name(), "Cron task turned off"),
+        }
+    }
+    Ok(Some(scheduler))
+}
+
+fn cors_layer(config: &CorsConfig) -> CorsLayer {
+    let allowed_origins = if config.allows_any_origin() {
+        AllowOrigin::any()
+    } else {
+        let config = config.clone();
+        AllowOrigin::predicate(move |origin, _| origin.to_str().is_ok_and(|o| config.allows(o)))
+    };
+    // Browsers reject a wildcard header list on credentialed requests
+    let allowed_headers = if config.allow_credentials {
+        AllowHeaders::mirror_request()
+    } else {
+        AllowHeaders::any()
+    };
+    CorsLayer::new()
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS])
-        .allow_headers(Any)
-        .allow_origin(Any)
-        .max_age(Duration::from_secs(3600));
+        .allow_headers(allowed_headers)
+        .allow_origin(allowed_origins)
+        .allow_credentials(config.allow_credentials)
+        .expose_headers(ExposeHeaders::list(
+            config.exposed_headers.iter().filter_map(|h| h.parse::<HeaderName>().ok()),
+        ))
+        .max_age(Duration::from_secs(config.max_age_secs))
+}
+
+// ============================================================================
+// Versioned Routes
+// ============================================================================
+
+/// Routes served under `/api/v1`
+fn api_v1_routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
+    // The caller's own account; signed API clients have none
+    let me_routes = Router::new()
+        .route("/me", get(get_current_user).delete(delete_account))
+        .route("/me/events", get(realtime::user_events))
+        .route("/me/experiments", get(get_my_experiments))
+        .route("/me/experiments/:key/exposures", post(expose_experiment))
+        .route("/me/features", get(features::get_my_features))
+        .route("/me/password", put(auth::change_password))
+        .route("/me/logout-all", post(auth::logout_all))
+        .route("/me/devices", get(auth::list_devices))
+        .route("/me/devices/:id", delete(auth::delete_device))
+        .route("/me/logins", get(auth::list_logins))
+        .route("/me/notifications", get(notifications::list_notifications))
+        .route("/me/notifications/:id/read", post(notifications::mark_notification_read))
+        .route(
+            "/me/avatar",
+            post(upload_avatar)
+                .delete(delete_avatar)
+                // Room for the multipart framing around the file
+                .layer(DefaultBodyLimit::max(state.avatars.max_bytes() + 16 * 1024)),
+        )
+        .route("/me/export", get(get_data_export).post(request_data_export))
+        .route("/me/settings", get(get_my_settings).put(update_my_settings))
+        .route("/me/cancel-deletion", post(cancel_account_deletion))
+        .route_layer(axum_mw::from_fn(request_signing::users_only));
 
     // Protected routes (require authentication)
     let protected_routes = Router::new()
-        .route("/me", get(get_current_user))
+        .route("/users/autocomplete", get(autocomplete_users))
+        .route("/users/:id/presence", get(realtime::user_presence))
+        .merge(me_routes)
         .route_layer(axum_mw::from_fn_with_state(state.clone(), middleware::jwt_auth));
 
     // Public routes
-    let public_routes = Router::new()
-        .route("/health", get(health_check))
+    Router::new()
         .route("/users", get(list_users))
+        .route("/users/batch", post(get_users_batch))
         .route("/users/:id", get(get_user))
-        .nest("/auth", auth::auth_routes());
-
-    // Combine all routes with global middlewares
-    let app = Router::new()
-        .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
-        .merge(public_routes)
+        .route("/exports/:token", get(download_data_export))
+        .nest("/auth", auth::auth_routes(state.clone()))
+        .nest("/orgs", organizations::organization_routes(state.clone()))
+        .nest("/support", support::support_routes(state.clone()))
+        .nest("/email", email_webhooks::email_webhook_routes(state.email_webhooks.clone()))
+        .nest("/admin", admin::admin_routes(state))
         .merge(protected_routes)
-        .layer(TraceLayer::new_for_http())
-        .layer(axum_mw::from_fn(middleware::request_id))
-        .layer(cors)
-        .with_state(state);
-
-    let addr = "0.0.0.0:3000";
-    let listener = TcpListener::bind(addr).await?;
-    tracing::info!("🚀 Server listening on {}", addr);
-    tracing::info!("📖 Swagger UI: http://{}/swagger-ui/", addr);
-    tracing::info!("📄 OpenAPI JSON: http://{}/api-docs/openapi.json", addr);
-    axum::serve(listener, app).await?;
+}
 
-    Ok(())
+/// Routes served under `/api/v2`.
+///
+/// Starts out identical to v1. When a breaking DTO change lands, build the
+/// affected routes here with v2 handlers instead of inheriting them.
+fn api_v2_routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
+    api_v1_routes(state)
+}
+
+/// Swagger UI with the versioned documents and the v1 document filtered per
+/// audience (`/api-docs/{public,partner,internal}/openapi.json`)
+fn swagger_ui() -> SwaggerUi {
+    let doc = api_doc();
+    let ui = SwaggerUi::new("/swagger-ui")
+        .url("/api-docs/openapi.json", doc.clone())
+        .url("/api-docs/v2/openapi.json", api_v2_doc());
+    DocAudience::ALL
+        .into_iter()
+        .fold(ui, |ui, audience| ui.url(audience.doc_url(), audience_doc(&doc, audience)))
+}
+
+/// OpenAPI document for v2, derived from v1 until the versions diverge
+fn api_v2_doc() -> utoipa::openapi::OpenApi {
+    let mut doc = api_doc();
+    doc.info.version = "2.0.0".to_string();
+    doc.paths.paths = std::mem::take(&mut doc.paths.paths)
+        .into_iter()
+        .map(|(path, item)| match path.strip_prefix("/api/v1/") {
+            Some(rest) => (format!("/api/v2/{}", rest), item),
+            None => (path, item),
+        })
+        .collect();
+    doc
 }
 
 // ============================================================================
@@ -168,6 +1246,9 @@ struct HealthResponse {
     /// API status
     #[schema(example = "ok")]
     status: String,
+    /// Region serving the request (`REGION`)
+    #[schema(example = "eu-west-1")]
+    region: String,
     /// Request tracking ID
     #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
     request_id: String,
@@ -185,6 +1266,7 @@ struct HealthResponse {
 async fn health_check(request_id: RequestId) -> Json<HealthResponse> {
     Json(HealthResponse {
         status: "ok".to_string(),
+        region: logging::region().to_string(),
         request_id: request_id.0,
     })
 }
@@ -193,37 +1275,46 @@ async fn health_check(request_id: RequestId) -> Json<HealthResponse> {
 // Request/Response DTOs
 // ============================================================================
 
-/// User response object
+/// Autocomplete query
+#[derive(Deserialize)]
+struct AutocompleteQuery {
+    /// Username prefix
+    q: String,
+    limit: Option<u32>,
+}
+
+/// Minimal user for mention and assignee pickers
 #[derive(Serialize, ToSchema)]
-struct UserResponse {
-    /// User UUID
+struct UserSuggestion {
     #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
     id: String,
-    /// Username
-    #[schema(example = "john_doe")]
+    #[schema(example = "johndoe")]
     username: String,
-    /// Email address
-    #[schema(example = "john@example.com")]
-    email: String,
+    avatar_url: Option<String>,
+}
+
+/// Autocomplete matches, best first
+#[derive(Serialize, ToSchema)]
+struct UserSuggestionsResponse {
+    items: Vec<UserSuggestion>,
+}
+
+/// Experiment variant assigned to the current user
+#[derive(Serialize, ToSchema)]
+struct ExperimentAssignmentResponse {
+    /// Experiment key
+    #[schema(example = "checkout_copy")]
+    experiment: String,
+    /// Assigned variant
+    #[schema(example = "control")]
+    variant: String,
 }
 
-/// Paginated response wrapper for users
+/// Current user's experiment assignments
 #[derive(Serialize, ToSchema)]
-struct PaginatedUserResponse {
-    /// List of users
-    items: Vec<UserResponse>,
-    /// Total number of users
-    #[schema(example = 100)]
-    total: u64,
-    /// Current page number
-    #[schema(example = 1)]
-    page: u32,
-    /// Items per page
-    #[schema(example = 20)]
-    per_page: u32,
-    /// Total number of pages
-    #[schema(example = 5)]
-    total_pages: u32,
+struct ExperimentsResponse {
+    /// Experiments the user is enrolled in
+    assignments: Vec<ExperimentAssignmentResponse>,
 }
 
 // ============================================================================
@@ -233,72 +1324,127 @@ struct PaginatedUserResponse {
 /// List all users with pagination
 #[utoipa::path(
     get,
-    path = "/users",
+    path = "/api/v1/users",
     tag = "Users",
     params(
-        ("page" = Option<u32>, Query, description = "Page number (default: 1)"),
+        ("page" = Option<u32>, Query, description = "Page number (default: 1); pages starting past `pagination.max_offset` rows are rejected"),
         ("per_page" = Option<u32>, Query, description = "Items per page (default: 20, max: 100)")
     ),
     responses(
-        (status = 200, description = "List of users", body = PaginatedUserResponse)
+        (status = 200, description = "List of users", body = PaginatedUserResponse, headers(("link" = String, description = "RFC 5988 links to the first, prev, next and last pages"))),
+        (status = 400, description = "Page too deep", body = ErrorResponse)
     )
 )]
 async fn list_users(
     State(state): State<Arc<AppState>>,
     Query(params): Query<PaginationParams>,
-) -> Result<Json<PaginatedUserResponse>, ApiError> {
-    let page = state
-        .user_service
-        .list_users(&params)
-        .await?;
+    OriginalUri(uri): OriginalUri,
+) -> Result<(PageLinks, Json<PaginatedUserResponse>), ApiError> {
+    let page = state.user_queries.list(&params).await?;
+    let links = PageLinks::new(&uri, &page);
+
+    Ok((links, Json(Paginated::from(page))))
+}
+
+/// Suggestions returned when `limit` is not given
+const AUTOCOMPLETE_DEFAULT_LIMIT: u32 = 10;
+const AUTOCOMPLETE_MAX_LIMIT: u32 = 25;
+/// Matches the server-side cache, so browsers do not re-ask within it
+const AUTOCOMPLETE_CACHE_CONTROL: &str = "private, max-age=30";
+
+/// Active users whose username starts with `q`, for mention/assignee pickers
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/autocomplete",
+    tag = "Users",
+    params(
+        ("q" = String, Query, description = "Username prefix (case-insensitive)"),
+        ("limit" = Option<u32>, Query, description = "Suggestions (default: 10, max: 25)")
+    ),
+    responses(
+        (status = 200, description = "Matches, exact username first", body = UserSuggestionsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+async fn autocomplete_users(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AutocompleteQuery>,
+) -> Result<Response, ApiError> {
+    let prefix = query.q.trim();
+    let users = if prefix.is_empty() {
+        Vec::new()
+    } else {
+        let limit = query.limit.unwrap_or(AUTOCOMPLETE_DEFAULT_LIMIT).clamp(1, AUTOCOMPLETE_MAX_LIMIT);
+        state.user_service.autocomplete(prefix, limit).await?
+    };
 
-    let items: Vec<UserResponse> = page
-        .items
+    let items = users
         .into_iter()
-        .map(|u| UserResponse {
-            id: u.id.to_string(),
-            username: u.username,
-            email: u.email,
+        .map(|user| UserSuggestion {
+            id: user.id.to_string(),
+            username: user.username.into(),
+            avatar_url: user.avatar_url,
         })
         .collect();
-
-    Ok(Json(PaginatedUserResponse {
-        items,
-        total: page.total,
-        page: page.page,
-        per_page: page.per_page,
-        total_pages: page.total_pages,
-    }))
+    Ok((
+        [(http::header::CACHE_CONTROL, AUTOCOMPLETE_CACHE_CONTROL)],
+        Json(UserSuggestionsResponse { items }),
+    )
+        .into_response())
 }
 
 /// Get a user by ID
 #[utoipa::path(
     get,
-    path = "/users/{id}",
+    path = "/api/v1/users/{id}",
     tag = "Users",
     params(
         ("id" = String, Path, description = "User UUID")
     ),
     responses(
-        (status = 200, description = "User found", body = UserResponse),
-        (status = 404, description = "User not found")
+        (status = 200, description = "User found", body = UserResponse,
+            headers(("ETag" = String, description = "Current version, for If-None-Match"))),
+        (status = 304, description = "Not modified since the ETag in If-None-Match"),
+        (status = 404, description = "User not found", body = ErrorResponse)
     )
 )]
 async fn get_user(
     State(state): State<Arc<AppState>>,
     Path(id): Path<uuid::Uuid>,
-) -> Result<Json<UserResponse>, ApiError> {
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
     let user = state
-        .user_service
-        .get_user(id)
+        .user_queries
+        .get(id)
         .await?
         .ok_or_else(|| ApiError::not_found(format!("User with id {} not found", id)))?;
 
-    Ok(Json(UserResponse {
-        id: user.id.to_string(),
-        username: user.username,
-        email: user.email,
-    }))
+    let etag = conditional::entity_tag(user.id, user.updated_at);
+    Ok(conditional::conditional_response(&headers, &etag, Json(UserResponse::from(user))))
+}
+
+/// Get several users by ID in one request
+#[utoipa::path(
+    post,
+    path = "/api/v1/users/batch",
+    tag = "Users",
+    request_body = BatchUsersRequest,
+    responses(
+        (status = 200, description = "Users found, keyed by id", body = BatchUsersResponse),
+        (status = 422, description = "No ids, or more than 100", body = ErrorResponse)
+    )
+)]
+async fn get_users_batch(
+    State(state): State<Arc<AppState>>,
+    ValidatedJson(request): ValidatedJson<BatchUsersRequest>,
+) -> Result<Json<BatchUsersResponse>, ApiError> {
+    let users = state.user_service.get_users(&request.ids).await?;
+    let users = users
+        .into_iter()
+        .map(|user| (user.id.to_string(), UserResponse::from(user)))
+        .collect();
+    Ok(Json(BatchUsersResponse { users }))
 }
 
 // ============================================================================
@@ -308,33 +1454,537 @@ async fn get_user(
 /// Get current authenticated user
 #[utoipa::path(
     get,
-    path = "/me",
+    path = "/api/v1/me",
     tag = "Users",
     security(("bearer_auth" = [])),
     responses(
-        (status = 200, description = "Current user info", body = UserResponse),
-        (status = 401, description = "Unauthorized")
+        (status = 200, description = "Current user info", body = UserResponse,
+            headers(("ETag" = String, description = "Current version, for If-None-Match and If-Match"))),
+        (status = 304, description = "Not modified since the ETag in If-None-Match"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
     )
 )]
 async fn get_current_user(
     State(state): State<Arc<AppState>>,
     AuthUser(claims): AuthUser,
-) -> Result<Json<UserResponse>, ApiError> {
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
     let user_id = claims.sub.parse::<uuid::Uuid>()
         .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
     
     let user = state
-        .user_service
-        .get_user(user_id)
+        .user_queries
+        .get(user_id)
         .await?
         .ok_or_else(|| ApiError::not_found("Current user not found"))?;
 
-    Ok(Json(UserResponse {
-        id: user.id.to_string(),
-        username: user.username,
-        email: user.email,
-    }))
+    let etag = conditional::entity_tag(user.id, user.updated_at);
+    Ok(conditional::conditional_response(&headers, &etag, Json(UserResponse::from(user))))
+}
+
+/// Avatar upload (multipart/form-data)
+#[derive(ToSchema)]
+#[allow(dead_code)]
+struct AvatarUpload {
+    /// PNG, JPEG, GIF or WebP image
+    #[schema(value_type = String, format = Binary)]
+    file: Vec<u8>,
 }
 
+/// Upload an avatar for the current user
+#[utoipa::path(
+    post,
+    path = "/api/v1/me/avatar",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    request_body(content = AvatarUpload, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Avatar updated", body = UserResponse),
+        (status = 400, description = "Missing file, unsupported type or too large", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 412, description = "If-Match does not match the current user", body = ErrorResponse),
+        (status = 413, description = "Upload too large", body = ErrorResponse)
+    )
+)]
+async fn upload_avatar(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    headers: HeaderMap,
+    mut multipart: Multipart,
+) -> Result<Response, ApiError> {
+    let user_id = claims.sub.parse::<uuid::Uuid>()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    conditional::check_user_precondition(&state, user_id, &headers).await?;
+
+    let mut upload = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| ApiError::bad_request(format!("Invalid multipart body: {}", e)))?
+    {
+        if field.name() == Some("file") {
+            let content_type = field.content_type().unwrap_or_default().to_string();
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|e| ApiError::bad_request(format!("Invalid multipart body: {}", e)))?;
+            upload = Some((bytes, content_type));
+            break;
+        }
+    }
+    let (bytes, content_type) = upload.ok_or_else(|| ApiError::bad_request("Missing 'file' field"))?;
+
+    let user = state.avatars.upload(user_id, bytes.to_vec(), &content_type).await?;
 
+    Ok(with_user_etag(user))
+}
 
+/// Remove the current user's avatar
+#[utoipa::path(
+    delete,
+    path = "/api/v1/me/avatar",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Avatar removed", body = UserResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 412, description = "If-Match does not match the current user", body = ErrorResponse)
+    )
+)]
+async fn delete_avatar(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let user_id = claims.sub.parse::<uuid::Uuid>()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    conditional::check_user_precondition(&state, user_id, &headers).await?;
+
+    let user = state.avatars.remove(user_id).await?;
+
+    Ok(with_user_etag(user))
+}
+
+/// A data export request and where it stands
+#[derive(Serialize, ToSchema)]
+struct DataExportResponse {
+    id: uuid::Uuid,
+    /// json or zip
+    #[schema(example = "json")]
+    format: String,
+    /// pending, ready, downloaded or expired
+    #[schema(example = "pending")]
+    status: String,
+    /// RFC 3339
+    requested_at: String,
+    /// When the emailed link stops working (RFC 3339), once the export is built
+    #[schema(nullable = false)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_at: Option<String>,
+    /// RFC 3339
+    #[schema(nullable = false)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    downloaded_at: Option<String>,
+    /// Minutes the emailed download link stays valid
+    #[schema(example = 60)]
+    link_expires_in_minutes: u64,
+}
+
+impl From<DataExport> for DataExportResponse {
+    fn from(export: DataExport) -> Self {
+        Self {
+            id: export.id,
+            format: export.format.as_str().to_string(),
+            status: export.status().as_str().to_string(),
+            requested_at: export.requested_at.to_rfc3339(),
+            expires_at: export.expires_at.map(|t| t.to_rfc3339()),
+            downloaded_at: export.downloaded_at.map(|t| t.to_rfc3339()),
+            link_expires_in_minutes: data_export::DEFAULT_LINK_TTL.as_secs() / 60,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct DataExportQuery {
+    format: Option<String>,
+}
+
+/// Request an export of the current user's data (GDPR)
+///
+/// The profile and sessions are exported in the background and a single-use
+/// download link is emailed to the user. One export per user per day.
+#[utoipa::path(
+    post,
+    path = "/api/v1/me/export",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    params(
+        ("format" = Option<String>, Query, description = "json (default) or zip")
+    ),
+    responses(
+        (status = 202, description = "Export queued; the link will be emailed", body = DataExportResponse),
+        (status = 400, description = "Unknown format", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 429, description = "An export was already requested today, see Retry-After", body = ErrorResponse)
+    )
+)]
+async fn request_data_export(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Query(query): Query<DataExportQuery>,
+) -> Result<(StatusCode, Json<DataExportResponse>), ApiError> {
+    let user_id = claims.sub.parse::<uuid::Uuid>()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+    let format = query
+        .format
+        .map(|f| ExportFormat::parse(&f).ok_or_else(|| ApiError::bad_request(format!("Unknown export format '{}'", f))))
+        .transpose()?
+        .unwrap_or_default();
+
+    let export = state.data_exports.request(user_id, format).await?;
+
+    Ok((StatusCode::ACCEPTED, Json(export.into())))
+}
+
+/// Status of the current user's latest data export
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/export",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Latest export", body = DataExportResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "No export requested yet", body = ErrorResponse)
+    )
+)]
+async fn get_data_export(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+) -> Result<Json<DataExportResponse>, ApiError> {
+    let user_id = claims.sub.parse::<uuid::Uuid>()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let export = state
+        .data_exports
+        .latest(user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("No data export requested"))?;
+
+    Ok(Json(export.into()))
+}
+
+/// The current user's settings
+#[derive(Serialize, ToSchema)]
+struct UserSettingsResponse {
+    /// BCP 47 language tag
+    #[schema(example = "en")]
+    locale: String,
+    /// IANA time zone name
+    #[schema(example = "UTC")]
+    timezone: String,
+    notifications: NotificationSettings,
+    /// RFC 3339; the defaults report the time they were read
+    updated_at: String,
+}
+
+/// Which notifications the user receives
+#[derive(Serialize, ToSchema)]
+struct NotificationSettings {
+    email: bool,
+    push: bool,
+    /// New sign-ins, password and email changes
+    security_alerts: bool,
+    product_updates: bool,
+    weekly_digest: bool,
+}
+
+impl From<UserSettings> for UserSettingsResponse {
+    fn from(settings: UserSettings) -> Self {
+        let notifications = settings.notifications;
+        Self {
+            locale: settings.locale,
+            timezone: settings.timezone,
+            notifications: NotificationSettings {
+                email: notifications.email,
+                push: notifications.push,
+                security_alerts: notifications.security_alerts,
+                product_updates: notifications.product_updates,
+                weekly_digest: notifications.weekly_digest,
+            },
+            updated_at: settings.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Partial settings update: omitted or null fields keep their value and
+/// unknown fields are rejected
+#[derive(Deserialize, Validate, ToSchema)]
+#[serde(deny_unknown_fields)]
+struct UpdateSettingsRequest {
+    /// BCP 47 language tag, e.g. en or pt-BR
+    #[validate(length(min = 2, max = 35, message = "must be 2-35 characters"))]
+    #[schema(example = "pt-BR")]
+    locale: Option<String>,
+    /// UTC or an IANA name such as Europe/Berlin
+    #[validate(length(min = 3, max = 64, message = "must be 3-64 characters"))]
+    #[schema(example = "Europe/Berlin")]
+    timezone: Option<String>,
+    notifications: Option<UpdateNotificationSettings>,
+}
+
+/// Notification preferences to change
+#[derive(Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+struct UpdateNotificationSettings {
+    email: Option<bool>,
+    push: Option<bool>,
+    security_alerts: Option<bool>,
+    product_updates: Option<bool>,
+    weekly_digest: Option<bool>,
+}
+
+impl From<UpdateSettingsRequest> for SettingsUpdate {
+    fn from(request: UpdateSettingsRequest) -> Self {
+        let notifications = request
+            .notifications
+            .map(|n| NotificationUpdate {
+                email: n.email,
+                push: n.push,
+                security_alerts: n.security_alerts,
+                product_updates: n.product_updates,
+                weekly_digest: n.weekly_digest,
+            })
+            .unwrap_or_default();
+        Self {
+            locale: request.locale,
+            timezone: request.timezone,
+            notifications,
+        }
+    }
+}
+
+/// Get the current user's settings
+///
+/// Users who never changed their settings get the defaults.
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/settings",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Current settings", body = UserSettingsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+async fn get_my_settings(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+) -> Result<Json<UserSettingsResponse>, ApiError> {
+    let user_id = claims.sub.parse::<uuid::Uuid>()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let settings = state.user_settings.get(user_id).await?;
+
+    Ok(Json(settings.into()))
+}
+
+/// Update the current user's settings
+///
+/// Only the fields present are changed, including single notification
+/// preferences.
+#[utoipa::path(
+    put,
+    path = "/api/v1/me/settings",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    request_body = UpdateSettingsRequest,
+    responses(
+        (status = 200, description = "Updated settings", body = UserSettingsResponse),
+        (status = 400, description = "Unknown field, invalid locale or time zone", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 422, description = "Locale or time zone too short or too long", body = ErrorResponse)
+    )
+)]
+async fn update_my_settings(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    ValidatedJson(request): ValidatedJson<UpdateSettingsRequest>,
+) -> Result<Json<UserSettingsResponse>, ApiError> {
+    let user_id = claims.sub.parse::<uuid::Uuid>()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let settings = state.user_settings.update(user_id, request.into()).await?;
+
+    Ok(Json(settings.into()))
+}
+
+/// Download a data export
+///
+/// The link from the export email; it works once and until it expires.
+#[utoipa::path(
+    get,
+    path = "/api/v1/exports/{token}",
+    tag = "Users",
+    params(
+        ("token" = String, Path, description = "Download token from the export email")
+    ),
+    responses(
+        (status = 200, description = "The export file (application/json or application/zip)"),
+        (status = 404, description = "Unknown, already used or expired link", body = ErrorResponse)
+    )
+)]
+async fn download_data_export(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> Result<Response, ApiError> {
+    let (export, file) = state.data_exports.download(&token).await?;
+
+    let disposition = format!(
+        "attachment; filename=\"data-export-{}.{}\"",
+        export.requested_at.format("%Y%m%d"),
+        export.format.as_str()
+    );
+    Ok((
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static(export.format.content_type())),
+            (
+                header::CONTENT_DISPOSITION,
+                HeaderValue::from_str(&disposition).unwrap_or(HeaderValue::from_static("attachment")),
+            ),
+            (header::CACHE_CONTROL, HeaderValue::from_static("private, no-store")),
+        ],
+        file.bytes,
+    )
+        .into_response())
+}
+
+#[derive(Serialize, ToSchema)]
+struct AccountDeletionResponse {
+    /// When the account will be anonymized (RFC 3339)
+    #[schema(example = "2026-11-16T09:30:00+00:00")]
+    delete_after: String,
+}
+
+/// Delete the current user's account (GDPR right to erasure)
+///
+/// The deletion takes effect after a grace period, during which the account
+/// keeps working and `POST /me/cancel-deletion` aborts it. Then the username
+/// and email are replaced by hashes and the account can no longer sign in.
+/// Asking again while a deletion is pending returns the same deadline.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/me",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 202, description = "Deletion scheduled", body = AccountDeletionResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+async fn delete_account(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+) -> Result<(StatusCode, Json<AccountDeletionResponse>), ApiError> {
+    let user_id = claims.sub.parse::<uuid::Uuid>()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let deletion = state.account_deletions.request(user_id).await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(AccountDeletionResponse {
+            delete_after: deletion.delete_after.to_rfc3339(),
+        }),
+    ))
+}
+
+/// Cancel the pending deletion of the current user's account
+#[utoipa::path(
+    post,
+    path = "/api/v1/me/cancel-deletion",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 204, description = "Deletion cancelled"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "No deletion pending", body = ErrorResponse)
+    )
+)]
+async fn cancel_account_deletion(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+) -> Result<StatusCode, ApiError> {
+    let user_id = claims.sub.parse::<uuid::Uuid>()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    state.account_deletions.cancel(user_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The updated user with its new ETag
+fn with_user_etag(user: domain::User) -> Response {
+    conditional::with_etag(&conditional::entity_tag(user.id, user.updated_at), Json(UserResponse::from(user)))
+}
+
+/// Get the current user's experiment assignments
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/experiments",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Experiment assignments", body = ExperimentsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+async fn get_my_experiments(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+) -> Result<Json<ExperimentsResponse>, ApiError> {
+    let assignments = state
+        .experiments
+        .assignments(&FlagContext::from_claims(&claims))
+        .await?;
+
+    Ok(Json(ExperimentsResponse {
+        assignments: assignments
+            .into_iter()
+            .map(|a| ExperimentAssignmentResponse {
+                experiment: a.experiment,
+                variant: a.variant,
+            })
+            .collect(),
+    }))
+}
+
+/// Record that the current user saw their variant of an experiment
+#[utoipa::path(
+    post,
+    path = "/api/v1/me/experiments/{key}/exposures",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    params(("key" = String, Path, description = "Experiment key")),
+    responses(
+        (status = 200, description = "The variant shown; an exposure event was emitted", body = ExperimentAssignmentResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "No such experiment, or the caller is not enrolled", body = ErrorResponse)
+    )
+)]
+async fn expose_experiment(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(key): Path<String>,
+) -> Result<Json<ExperimentAssignmentResponse>, ApiError> {
+    let assignment = state
+        .experiments
+        .expose(&key, &FlagContext::from_claims(&claims))
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Not enrolled in experiment '{}'", key)))?;
+
+    Ok(Json(ExperimentAssignmentResponse {
+        experiment: assignment.experiment,
+        variant: assignment.variant,
+    }))
+}
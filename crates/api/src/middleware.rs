@@ -1,17 +1,20 @@
 use axum::{
-    extract::{Request, State},
-    http::{header, StatusCode},
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderValue},
     middleware::Next,
-    response::{IntoResponse, Response},
-    Json,
+    response::Response,
 };
 use std::sync::Arc;
 use tracing::{info_span, Instrument};
 
-use application::TokenService;
+use application::timings;
+use application::FeatureFlagService;
 use domain::Claims;
 use crate::AppState;
 use crate::error::ApiError;
+use crate::health_checks::TrustedProbe;
+use crate::request_signing::SignedClient;
+use crate::server_timing;
 
 // ============================================================================
 // Request ID Extension
@@ -21,6 +24,14 @@ use crate::error::ApiError;
 #[derive(Debug, Clone)]
 pub struct RequestId(pub String);
 
+/// Response header naming the admin behind an impersonation token
+pub const IMPERSONATED_BY_HEADER: &str = "x-impersonated-by";
+
+/// Authenticated user ID, attached to the response by `jwt_auth` so that
+/// outer middleware (e.g. request logging) can see who made the request.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUserId(pub String);
+
 /// Middleware to generate and inject request ID
 pub async fn request_id(
     mut request: Request,
@@ -37,6 +48,41 @@ pub async fn request_id(
     response
 }
 
+/// Response header naming the region that handled the request
+pub const SERVED_BY_HEADER: &str = "x-served-by";
+
+/// Middleware adding `x-served-by: <region>` to every response, so clients
+/// and load balancers can tell which region answered
+pub async fn served_by(request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    if let Ok(region) = HeaderValue::from_str(crate::logging::region()) {
+        response.headers_mut().insert(SERVED_BY_HEADER, region);
+    }
+    response
+}
+
+/// Middleware recording `http_requests_total` and
+/// `http_request_duration_seconds` per method, route template and status.
+/// Labelling by template (`/api/v1/users/:id`) keeps the series count bounded.
+pub async fn http_metrics(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let started = std::time::Instant::now();
+
+    let response = next.run(request).await;
+
+    let status = response.status().as_u16().to_string();
+    let labels = [("method", method.as_str()), ("route", route.as_str()), ("status", status.as_str())];
+    let metrics = shared::telemetry::telemetry();
+    metrics.increment("http_requests_total", 1, &labels);
+    metrics.observe("http_request_duration_seconds", started.elapsed().as_secs_f64(), &labels);
+    response
+}
+
 // ============================================================================
 // JWT Authentication Middleware
 // ============================================================================
@@ -45,11 +91,20 @@ pub async fn request_id(
 /// - Validates JWT from Authorization header
 /// - Returns proper JSON error responses
 /// - Adds Claims and creates tracing span with user context
+/// - Lets trusted health check probes (`TrustedProbe`) through without a token
+/// - Times itself as the `auth` phase and sends admins their `Server-Timing`
+/// - Audits requests made with impersonation tokens and names the admin in
+///   `X-Impersonated-By`
 pub async fn jwt_auth(
     State(state): State<Arc<AppState>>,
     mut request: Request,
     next: Next,
 ) -> Result<Response, ApiError> {
+    if request.extensions().get::<TrustedProbe>().is_some() {
+        return Ok(next.run(request).await);
+    }
+    let auth_timer = timings::start(timings::AUTH);
+
     // Extract request ID for error responses
     let request_id = request
         .extensions()
@@ -57,32 +112,61 @@ pub async fn jwt_auth(
         .map(|r| r.0.clone())
         .unwrap_or_default();
 
-    // Get Authorization header
-    let auth_header = request
-        .headers()
-        .get(header::AUTHORIZATION)
-        .and_then(|h| h.to_str().ok());
+    // API clients authenticated by their request signature carry no token
+    let signed = request.extensions().get::<SignedClient>().is_some();
+    let claims = match request.extensions().get::<Claims>() {
+        Some(claims) if signed => claims.clone(),
+        _ => {
+            // Get Authorization header
+            let auth_header = request
+                .headers()
+                .get(header::AUTHORIZATION)
+                .and_then(|h| h.to_str().ok());
 
-    let token = match auth_header {
-        Some(h) if h.starts_with("Bearer ") => &h[7..],
-        Some(_) => {
-            return Err(ApiError::unauthorized("Invalid Authorization header format. Use: Bearer <token>"));
-        }
-        None => {
-            return Err(ApiError::unauthorized("Missing Authorization header"));
+            let token = bearer_token(auth_header)?;
+
+            // Validate token
+            state
+                .token_service
+                .validate(token)
+                .map_err(|e| ApiError::unauthorized(e.to_string()))?
         }
     };
 
-    // Validate token
-    let claims = state
-        .token_service
-        .validate(token)
-        .map_err(|e| ApiError::unauthorized(e.to_string()))?;
+    // Guest tokens only work where anonymous callers do
+    if claims.is_anonymous() && request.extensions().get::<GuestsAllowed>().is_none() {
+        return Err(ApiError::unauthorized("Guest sessions need an account for this; see /auth/guest/upgrade"));
+    }
+
+    // Tokens only work on their own tenant (tokens without one belong to the default tenant)
+    if let Some(tenant) = application::tenancy::current_tenant() {
+        let token_tenant = claims
+            .tenant_id
+            .as_deref()
+            .and_then(|t| t.parse().ok())
+            .unwrap_or(domain::Tenant::DEFAULT_ID);
+        if token_tenant != tenant {
+            return Err(ApiError::unauthorized("Token was issued for another tenant"));
+        }
+    }
+
+    // Tokens issued before the user's last logout-all
+    if !signed {
+        state.token_versions.check(&claims).await?;
+    }
+
+    // Only users acting for themselves count as seen: not guests, API clients or impersonators
+    if !signed && !claims.is_anonymous() && claims.actor().is_none() {
+        if let Ok(user_id) = claims.sub.parse() {
+            state.activity.seen(user_id, chrono::Utc::now());
+        }
+    }
 
     // Add claims to request extensions
     let user_id = claims.sub.clone();
     let user_email = claims.email.clone();
-    request.extensions_mut().insert(claims);
+    request.extensions_mut().insert(claims.clone());
+    tracing::Span::current().record("user_id", user_id.as_str());
 
     // Create tracing span with user context
     let span = info_span!(
@@ -92,34 +176,231 @@ pub async fn jwt_auth(
         request_id = %request_id,
     );
 
-    Ok(next.run(request).instrument(span).await)
+    // Admin acting as the user, with what they did
+    let impersonation = claims
+        .actor()
+        .map(|actor| (actor.to_string(), request.method().clone(), request.uri().path().to_string()));
+
+    drop(auth_timer);
+
+    // Role and permission checks made while handling the request share one lookup
+    let mut response = application::authz::with_request_cache(async {
+        let mut response = next.run(request).instrument(span).await;
+        server_timing::allow_admins(&state, &claims, &mut response).await;
+        response
+    })
+    .await;
+
+    if let Some((actor, method, path)) = impersonation {
+        tracing::info!(
+            target: "audit",
+            admin_id = %actor,
+            user_id = %user_id,
+            %method,
+            %path,
+            status = response.status().as_u16(),
+            "Impersonated request"
+        );
+        if let Ok(value) = HeaderValue::from_str(&actor) {
+            response.headers_mut().insert(IMPERSONATED_BY_HEADER, value);
+        }
+    }
+    response.extensions_mut().insert(AuthenticatedUserId(user_id));
+    Ok(response)
+}
+
+/// Set by `optional_jwt_auth`: routes open to anonymous callers take guest tokens too
+#[derive(Debug, Clone, Copy)]
+struct GuestsAllowed;
+
+/// Like `jwt_auth`, but lets anonymous requests through (pair with
+/// `OptionalAuthUser`). A token that is sent must still be valid; guest
+/// tokens (`Claims::is_anonymous`) are accepted here and nowhere else.
+pub async fn optional_jwt_auth(
+    state: State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if request.headers().contains_key(header::AUTHORIZATION) {
+        request.extensions_mut().insert(GuestsAllowed);
+        jwt_auth(state, request, next).await
+    } else {
+        Ok(next.run(request).await)
+    }
+}
+
+/// Extract the token from an `Authorization: Bearer <token>` header value.
+pub fn bearer_token(auth_header: Option<&str>) -> Result<&str, ApiError> {
+    match auth_header {
+        Some(h) => h
+            .strip_prefix("Bearer ")
+            .ok_or_else(|| ApiError::unauthorized("Invalid Authorization header format. Use: Bearer <token>")),
+        None => Err(ApiError::unauthorized("Missing Authorization header")),
+    }
+}
+
+// ============================================================================
+// Read-Your-Writes Middleware
+// ============================================================================
+
+/// Header carrying the replication position a client's reads must observe
+pub const CONSISTENCY_TOKEN_HEADER: &str = "x-consistency-token";
+
+/// Establishes the read-consistency session for replica routing.
+///
+/// The required position comes from the client's `X-Consistency-Token` or,
+/// for authenticated users, from their last recorded write. When the request
+/// writes, the new position is remembered for the user and returned in the
+/// `X-Consistency-Token` response header.
+pub async fn read_your_writes(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // Outer layer: jwt_auth has not run yet, so identify the session ourselves
+    let session_key = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| bearer_token(Some(h)).ok())
+        .and_then(|token| state.token_service.validate(token).ok())
+        .map(|claims| claims.sub);
+
+    let required = request
+        .headers()
+        .get(CONSISTENCY_TOKEN_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(str::to_string)
+        .or_else(|| session_key.as_deref().and_then(|key| state.consistency.get(key)));
+
+    let session = Arc::new(application::ConsistencySession::new(required));
+    let mut response = application::with_consistency(session.clone(), next.run(request)).await;
+
+    if let Some(position) = session.written_position() {
+        if let Some(key) = &session_key {
+            state.consistency.record(key, position.clone());
+        }
+        if let Ok(value) = position.parse() {
+            response.headers_mut().insert(CONSISTENCY_TOKEN_HEADER, value);
+        }
+    }
+
+    response
+}
+
+// ============================================================================
+// Transaction-per-Request Middleware
+// ============================================================================
+
+/// Opt-in middleware running the whole request inside one database transaction.
+/// Commits on a 2xx response and rolls back otherwise, so handlers that touch
+/// several aggregates either persist everything or nothing.
+///
+/// Example:
+/// ```rust,ignore
+/// .route_layer(axum::middleware::from_fn_with_state(state.clone(), transaction_per_request))
+/// ```
+pub async fn transaction_per_request(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let tx = state.unit_of_work.begin().await?;
+    let response = application::with_transaction(tx.clone(), next.run(request)).await;
+
+    if response.status().is_success() {
+        tx.commit().await?;
+    } else {
+        tx.rollback().await?;
+    }
+
+    Ok(response)
 }
 
 // ============================================================================
 // Role-Based Access Control Middleware
 // ============================================================================
 
-/// Middleware factory for role-based access control.
+type AccessCheck = std::pin::Pin<Box<dyn std::future::Future<Output = Result<Response, ApiError>> + Send>>;
+
+/// Middleware factory for role-based access control. Roles come from the
+/// token and from the roles granted to the user (cached, see `AuthorizationService`).
 /// Use with `axum::middleware::from_fn_with_state`.
 /// 
 /// Example:
-/// ```rust
-/// .route_layer(axum::middleware::from_fn(require_role("admin")))
+/// ```rust,ignore
+/// .route_layer(axum::middleware::from_fn_with_state(state.clone(), require_role("admin")))
 /// ```
-pub fn require_role(required_role: &'static str) -> impl Fn(Request, Next) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<Response, ApiError>> + Send>> + Clone {
-    move |request: Request, next: Next| {
+pub fn require_role(required_role: &'static str) -> impl Fn(State<Arc<AppState>>, Request, Next) -> AccessCheck + Clone {
+    move |State(state): State<Arc<AppState>>, request: Request, next: Next| {
         Box::pin(async move {
+            if request.extensions().get::<TrustedProbe>().is_some() {
+                return Ok(next.run(request).await);
+            }
             let claims = request
                 .extensions()
                 .get::<Claims>()
                 .ok_or_else(|| ApiError::unauthorized("Authentication required"))?;
 
-            if !claims.roles.contains(&required_role.to_string()) {
-                return Err(ApiError::new(
-                    StatusCode::FORBIDDEN,
-                    "FORBIDDEN",
-                    format!("Required role '{}' not found", required_role),
-                ));
+            let auth_timer = timings::start(timings::AUTH);
+            let allowed = state.authz.has_role(claims, required_role).await?;
+            drop(auth_timer);
+            if !allowed {
+                return Err(ApiError::forbidden(format!("Required role '{}' not found", required_role)));
+            }
+
+            Ok(next.run(request).await)
+        })
+    }
+}
+
+/// Middleware factory requiring a `resource:action` permission granted by
+/// one of the caller's roles.
+///
+/// Example:
+/// ```rust,ignore
+/// .route_layer(axum::middleware::from_fn_with_state(state.clone(), require_permission("users:delete")))
+/// ```
+pub fn require_permission(permission: &'static str) -> impl Fn(State<Arc<AppState>>, Request, Next) -> AccessCheck + Clone {
+    move |State(state): State<Arc<AppState>>, request: Request, next: Next| {
+        Box::pin(async move {
+            if request.extensions().get::<TrustedProbe>().is_some() {
+                return Ok(next.run(request).await);
+            }
+            let claims = request
+                .extensions()
+                .get::<Claims>()
+                .ok_or_else(|| ApiError::unauthorized("Authentication required"))?;
+
+            let auth_timer = timings::start(timings::AUTH);
+            let allowed = state.authz.has_permission(claims, permission).await?;
+            drop(auth_timer);
+            if !allowed {
+                return Err(ApiError::forbidden(format!("Permission '{}' required", permission)));
+            }
+
+            Ok(next.run(request).await)
+        })
+    }
+}
+
+/// Middleware factory hiding routes behind a feature flag: callers the flag
+/// is off for get a 404, as if the route did not exist. Put it inside
+/// `jwt_auth` (or `optional_jwt_auth`) so per-user rollouts apply.
+///
+/// Example:
+/// ```rust,ignore
+/// .route_layer(axum::middleware::from_fn_with_state(state.clone(), require_feature("new_dashboard")))
+/// ```
+pub fn require_feature(flag: &'static str) -> impl Fn(State<Arc<AppState>>, Request, Next) -> AccessCheck + Clone {
+    move |State(state): State<Arc<AppState>>, request: Request, next: Next| {
+        Box::pin(async move {
+            let ctx = match request.extensions().get::<Claims>() {
+                Some(claims) => crate::features::flag_context(&state, claims).await?,
+                None => domain::FlagContext::default(),
+            };
+            if !state.feature_flags.is_enabled(flag, &ctx).await? {
+                return Err(ApiError::not_found("Not found"));
             }
 
             Ok(next.run(request).await)
@@ -134,7 +415,7 @@ pub fn require_role(required_role: &'static str) -> impl Fn(Request, Next) -> st
 /// Extractor to get authenticated user claims in handlers.
 /// 
 /// Example:
-/// ```rust
+/// ```rust,ignore
 /// async fn protected_handler(AuthUser(claims): AuthUser) -> impl IntoResponse {
 ///     format!("Hello, user {}", claims.sub)
 /// }
@@ -232,3 +513,57 @@ where
         })
     }
 }
+
+// ============================================================================
+// Client IP Extractor
+// ============================================================================
+
+/// Caller's IP address for rate limiting: the peer address, or the first
+/// `X-Forwarded-For` entry with `server.trust_forwarded_for` (only behind a
+/// proxy that sets it, since clients can forge the header).
+#[derive(Debug, Clone)]
+pub struct ClientIp(pub Option<String>);
+
+static TRUST_FORWARDED_FOR: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+
+/// Apply `server.trust_forwarded_for`; call once at startup
+pub fn set_trust_forwarded_for(trust: bool) {
+    TRUST_FORWARDED_FOR.get_or_init(|| trust);
+}
+
+fn trust_forwarded_for() -> bool {
+    TRUST_FORWARDED_FOR.get().copied().unwrap_or(false)
+}
+
+impl<S> axum::extract::FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 S,
+    ) -> core::pin::Pin<
+        Box<dyn core::future::Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>,
+    >
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            let forwarded = trust_forwarded_for()
+                .then(|| parts.headers.get("x-forwarded-for"))
+                .flatten()
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.split(',').next())
+                .map(|ip| ip.trim().to_string());
+            let peer = parts
+                .extensions
+                .get::<axum::extract::ConnectInfo<std::net::SocketAddr>>()
+                .map(|info| info.0.ip().to_string());
+            Ok(ClientIp(forwarded.or(peer)))
+        })
+    }
+}
@@ -0,0 +1,133 @@
+use axum::extract::{OriginalUri, Path, Query, State};
+use domain::{Notification, PaginationParams};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::middleware::AuthUser;
+use crate::pagination::{NotificationPage, PageLinks, Paginated};
+use crate::AppState;
+use crate::server_timing::Json;
+
+// ============================================================================
+// Request/Response DTOs
+// ============================================================================
+
+/// In-app notification of the current user
+#[derive(Serialize, ToSchema)]
+pub struct NotificationResponse {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: String,
+    /// What it is about; clients pick icons and links by it
+    #[schema(example = "welcome")]
+    pub kind: String,
+    #[schema(example = "Welcome, john_doe!")]
+    pub title: String,
+    pub body: String,
+    pub read: bool,
+    /// When it was first read (RFC 3339)
+    pub read_at: Option<String>,
+    pub created_at: String,
+}
+
+impl From<Notification> for NotificationResponse {
+    fn from(notification: Notification) -> Self {
+        Self {
+            id: notification.id.to_string(),
+            read: notification.is_read(),
+            kind: notification.kind,
+            title: notification.title,
+            body: notification.body,
+            read_at: notification.read_at.map(|t| t.to_rfc3339()),
+            created_at: notification.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// One page of notifications with the unread total
+#[derive(Serialize, ToSchema)]
+pub struct NotificationsResponse {
+    #[serde(flatten)]
+    pub page: NotificationPage,
+    /// Unread notifications in total, whatever the page or filter
+    #[schema(example = 3)]
+    pub unread: u64,
+}
+
+/// Notification filter
+#[derive(Deserialize)]
+pub struct NotificationFilter {
+    /// Only unread notifications
+    #[serde(default)]
+    pub unread: bool,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// List the current user's notifications, newest first
+///
+/// New notifications are also pushed as `notification.created` events over
+/// `/ws` and `/me/events`.
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/notifications",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    params(
+        ("unread" = Option<bool>, Query, description = "Only unread notifications (default: false)"),
+        ("page" = Option<u32>, Query, description = "Page number (default: 1); pages starting past `pagination.max_offset` rows are rejected"),
+        ("per_page" = Option<u32>, Query, description = "Items per page (default: 20, max: 100)")
+    ),
+    responses(
+        (status = 200, description = "Notifications", body = NotificationsResponse, headers(("link" = String, description = "RFC 5988 links to the first, prev, next and last pages"))),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_notifications(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Query(filter): Query<NotificationFilter>,
+    Query(params): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+) -> Result<(PageLinks, Json<NotificationsResponse>), ApiError> {
+    let inbox = state.notifications.inbox(user_id(&claims.sub)?, filter.unread, &params).await?;
+    let links = PageLinks::new(&uri, &inbox.page);
+
+    Ok((
+        links,
+        Json(NotificationsResponse {
+            page: Paginated::from(inbox.page),
+            unread: inbox.unread,
+        }),
+    ))
+}
+
+/// Mark one of the current user's notifications read
+#[utoipa::path(
+    post,
+    path = "/api/v1/me/notifications/{id}/read",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Notification ID")),
+    responses(
+        (status = 200, description = "Notification, now read", body = NotificationResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Notification not found", body = ErrorResponse)
+    )
+)]
+pub async fn mark_notification_read(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<NotificationResponse>, ApiError> {
+    let notification = state.notifications.mark_read(user_id(&claims.sub)?, id).await?;
+    Ok(Json(notification.into()))
+}
+
+fn user_id(sub: &str) -> Result<Uuid, ApiError> {
+    sub.parse().map_err(|_| ApiError::internal("Invalid user ID in token"))
+}
@@ -0,0 +1,505 @@
+use axum::{
+    extract::{OriginalUri, Path, Query, State},
+    http::StatusCode,
+    middleware as axum_mw,
+    routing::{get, post, put},
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use application::organizations::Invitation;
+use domain::{Membership, OrgRole, Organization, PaginationParams};
+
+use crate::auth::ValidatedJson;
+use crate::error::ApiError;
+use crate::middleware::{jwt_auth, AuthUser};
+use crate::pagination::{MembersResponse, PageLinks, Paginated};
+use crate::AppState;
+use crate::server_timing::Json;
+
+// ============================================================================
+// Routes
+// ============================================================================
+
+/// Organization routes; every request needs a valid token, and what the
+/// caller may do follows their role in the organization
+pub fn organization_routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_organizations).post(create_organization))
+        .route("/invitations/:token/accept", post(accept_invitation))
+        .route("/:id", get(get_organization).put(rename_organization).delete(delete_organization))
+        .route("/:id/members", get(list_members))
+        .route("/:id/members/:user_id", put(change_member_role).delete(remove_member))
+        .route("/:id/invitations", get(list_invitations).post(invite_member))
+        .route("/:id/invitations/:invitation_id", axum::routing::delete(revoke_invitation))
+        .route_layer(axum_mw::from_fn_with_state(state, jwt_auth))
+}
+
+// ============================================================================
+// DTOs
+// ============================================================================
+
+/// Create or rename an organization
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct OrganizationRequest {
+    #[validate(length(min = 1, max = 100, message = "must be 1-100 characters"))]
+    #[schema(example = "Acme Inc.")]
+    pub name: String,
+}
+
+/// An organization and the caller's role in it
+#[derive(Serialize, ToSchema)]
+pub struct OrganizationResponse {
+    pub id: Uuid,
+    #[schema(example = "Acme Inc.")]
+    pub name: String,
+    /// owner, admin or member
+    #[schema(example = "owner")]
+    pub role: String,
+    pub created_by: Uuid,
+    /// RFC 3339
+    pub created_at: String,
+}
+
+impl OrganizationResponse {
+    fn new(organization: Organization, role: OrgRole) -> Self {
+        Self {
+            id: organization.id,
+            name: organization.name,
+            role: role.as_str().to_string(),
+            created_by: organization.created_by,
+            created_at: organization.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// The caller's organizations
+#[derive(Serialize, ToSchema)]
+pub struct OrganizationsResponse {
+    pub items: Vec<OrganizationResponse>,
+}
+
+/// A member of an organization
+#[derive(Serialize, ToSchema)]
+pub struct MemberResponse {
+    pub user_id: Uuid,
+    /// owner, admin or member
+    #[schema(example = "member")]
+    pub role: String,
+    /// RFC 3339
+    pub joined_at: String,
+}
+
+impl From<Membership> for MemberResponse {
+    fn from(membership: Membership) -> Self {
+        Self {
+            user_id: membership.user_id,
+            role: membership.role.as_str().to_string(),
+            joined_at: membership.joined_at.to_rfc3339(),
+        }
+    }
+}
+
+/// New role for a member
+#[derive(Deserialize, ToSchema)]
+pub struct ChangeRoleRequest {
+    /// owner, admin or member
+    #[schema(example = "admin")]
+    pub role: String,
+}
+
+/// Invite someone by email
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct InviteRequest {
+    #[validate(email(message = "must be a valid email"))]
+    #[schema(example = "jane@example.com")]
+    pub email: String,
+    /// owner, admin or member (default)
+    #[serde(default = "default_role")]
+    #[schema(example = "member")]
+    pub role: String,
+}
+
+fn default_role() -> String {
+    OrgRole::Member.as_str().to_string()
+}
+
+/// A pending invitation
+#[derive(Serialize, ToSchema)]
+pub struct InvitationResponse {
+    pub id: Uuid,
+    #[schema(example = "jane@example.com")]
+    pub email: String,
+    #[schema(example = "member")]
+    pub role: String,
+    pub invited_by: Uuid,
+    /// RFC 3339
+    pub created_at: String,
+    /// RFC 3339
+    pub expires_at: String,
+}
+
+impl From<Invitation> for InvitationResponse {
+    fn from(invitation: Invitation) -> Self {
+        Self {
+            id: invitation.id,
+            email: invitation.email,
+            role: invitation.role.as_str().to_string(),
+            invited_by: invitation.invited_by,
+            created_at: invitation.created_at.to_rfc3339(),
+            expires_at: invitation.expires_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Pending invitations, newest first
+#[derive(Serialize, ToSchema)]
+pub struct InvitationsResponse {
+    pub items: Vec<InvitationResponse>,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// The caller's organizations
+#[utoipa::path(
+    get,
+    path = "/api/v1/orgs",
+    tag = "Organizations",
+    security(("bearer_auth" = [])),
+    responses(
+        (status = 200, description = "Organizations the caller belongs to, by name", body = OrganizationsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn list_organizations(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+) -> Result<Json<OrganizationsResponse>, ApiError> {
+    let organizations = state.organizations.list(user_id(&claims)?).await?;
+
+    Ok(Json(OrganizationsResponse {
+        items: organizations
+            .into_iter()
+            .map(|(organization, role)| OrganizationResponse::new(organization, role))
+            .collect(),
+    }))
+}
+
+/// Create an organization; the caller becomes its owner
+#[utoipa::path(
+    post,
+    path = "/api/v1/orgs",
+    tag = "Organizations",
+    security(("bearer_auth" = [])),
+    request_body = OrganizationRequest,
+    responses(
+        (status = 201, description = "Organization created", body = OrganizationResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 422, description = "Invalid name", body = ErrorResponse)
+    )
+)]
+pub async fn create_organization(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    ValidatedJson(request): ValidatedJson<OrganizationRequest>,
+) -> Result<(StatusCode, Json<OrganizationResponse>), ApiError> {
+    let organization = state.organizations.create(user_id(&claims)?, request.name).await?;
+
+    Ok((StatusCode::CREATED, Json(OrganizationResponse::new(organization, OrgRole::Owner))))
+}
+
+/// An organization the caller belongs to
+#[utoipa::path(
+    get,
+    path = "/api/v1/orgs/{id}",
+    tag = "Organizations",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Organization ID")),
+    responses(
+        (status = 200, description = "The organization", body = OrganizationResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Not found or not a member", body = ErrorResponse)
+    )
+)]
+pub async fn get_organization(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<OrganizationResponse>, ApiError> {
+    let (organization, role) = state.organizations.get(user_id(&claims)?, id).await?;
+
+    Ok(Json(OrganizationResponse::new(organization, role)))
+}
+
+/// Rename an organization (admins and owners)
+#[utoipa::path(
+    put,
+    path = "/api/v1/orgs/{id}",
+    tag = "Organizations",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Organization ID")),
+    request_body = OrganizationRequest,
+    responses(
+        (status = 200, description = "Organization renamed", body = OrganizationResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "The caller is only a member", body = ErrorResponse),
+        (status = 404, description = "Not found or not a member", body = ErrorResponse)
+    )
+)]
+pub async fn rename_organization(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    ValidatedJson(request): ValidatedJson<OrganizationRequest>,
+) -> Result<Json<OrganizationResponse>, ApiError> {
+    let caller = user_id(&claims)?;
+    let organization = state.organizations.rename(caller, id, request.name).await?;
+    let (_, role) = state.organizations.get(caller, id).await?;
+
+    Ok(Json(OrganizationResponse::new(organization, role)))
+}
+
+/// Delete an organization with its memberships and invitations (owners)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/orgs/{id}",
+    tag = "Organizations",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Organization ID")),
+    responses(
+        (status = 204, description = "Organization deleted"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "The caller is not an owner", body = ErrorResponse),
+        (status = 404, description = "Not found or not a member", body = ErrorResponse)
+    )
+)]
+pub async fn delete_organization(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    state.organizations.delete(user_id(&claims)?, id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Members of an organization, owners first
+#[utoipa::path(
+    get,
+    path = "/api/v1/orgs/{id}/members",
+    tag = "Organizations",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Organization ID"),
+        ("page" = Option<u32>, Query, description = "Page number (default: 1)"),
+        ("per_page" = Option<u32>, Query, description = "Items per page (default: 20, max: 100)")
+    ),
+    responses(
+        (status = 200, description = "Members", body = MembersResponse, headers(("link" = String, description = "RFC 5988 links to the first, prev, next and last pages"))),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Not found or not a member", body = ErrorResponse)
+    )
+)]
+pub async fn list_members(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    Query(params): Query<PaginationParams>,
+    OriginalUri(uri): OriginalUri,
+) -> Result<(PageLinks, Json<MembersResponse>), ApiError> {
+    let page = state.organizations.members(user_id(&claims)?, id, &params).await?;
+    let links = PageLinks::new(&uri, &page);
+
+    Ok((links, Json(Paginated::from(page))))
+}
+
+/// Change a member's role
+///
+/// Admins manage members and admins; only owners grant or take away
+/// ownership. The last owner cannot step down.
+#[utoipa::path(
+    put,
+    path = "/api/v1/orgs/{id}/members/{user_id}",
+    tag = "Organizations",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Organization ID"),
+        ("user_id" = String, Path, description = "Member's user ID")
+    ),
+    request_body = ChangeRoleRequest,
+    responses(
+        (status = 200, description = "Role changed", body = MemberResponse),
+        (status = 400, description = "Unknown role, or the last owner", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not allowed for the caller's role", body = ErrorResponse),
+        (status = 404, description = "Organization or member not found", body = ErrorResponse)
+    )
+)]
+pub async fn change_member_role(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path((id, member_id)): Path<(Uuid, Uuid)>,
+    Json(request): Json<ChangeRoleRequest>,
+) -> Result<Json<MemberResponse>, ApiError> {
+    let role = parse_role(&request.role)?;
+    let member = state.organizations.change_role(user_id(&claims)?, id, member_id, role).await?;
+
+    Ok(Json(member.into()))
+}
+
+/// Remove a member, or leave with your own user ID
+#[utoipa::path(
+    delete,
+    path = "/api/v1/orgs/{id}/members/{user_id}",
+    tag = "Organizations",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Organization ID"),
+        ("user_id" = String, Path, description = "Member's user ID")
+    ),
+    responses(
+        (status = 204, description = "Member removed"),
+        (status = 400, description = "The last owner", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not allowed for the caller's role", body = ErrorResponse),
+        (status = 404, description = "Organization or member not found", body = ErrorResponse)
+    )
+)]
+pub async fn remove_member(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path((id, member_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    state.organizations.remove_member(user_id(&claims)?, id, member_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Pending invitations (admins and owners)
+#[utoipa::path(
+    get,
+    path = "/api/v1/orgs/{id}/invitations",
+    tag = "Organizations",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Organization ID")),
+    responses(
+        (status = 200, description = "Invitations neither accepted nor expired", body = InvitationsResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "The caller is only a member", body = ErrorResponse),
+        (status = 404, description = "Not found or not a member", body = ErrorResponse)
+    )
+)]
+pub async fn list_invitations(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<InvitationsResponse>, ApiError> {
+    let invitations = state.organizations.invitations(user_id(&claims)?, id).await?;
+
+    Ok(Json(InvitationsResponse {
+        items: invitations.into_iter().map(Into::into).collect(),
+    }))
+}
+
+/// Invite someone by email (admins and owners)
+///
+/// The invitee gets a link with a single-use token; they accept it signed
+/// in with the invited address. Only owners can invite owners.
+#[utoipa::path(
+    post,
+    path = "/api/v1/orgs/{id}/invitations",
+    tag = "Organizations",
+    security(("bearer_auth" = [])),
+    params(("id" = String, Path, description = "Organization ID")),
+    request_body = InviteRequest,
+    responses(
+        (status = 201, description = "Invitation sent", body = InvitationResponse),
+        (status = 400, description = "Unknown role", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Not allowed for the caller's role", body = ErrorResponse),
+        (status = 404, description = "Not found or not a member", body = ErrorResponse),
+        (status = 409, description = "Already a member", body = ErrorResponse),
+        (status = 422, description = "Invalid email", body = ErrorResponse)
+    )
+)]
+pub async fn invite_member(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(id): Path<Uuid>,
+    ValidatedJson(request): ValidatedJson<InviteRequest>,
+) -> Result<(StatusCode, Json<InvitationResponse>), ApiError> {
+    let role = parse_role(&request.role)?;
+    let invitation = state.organizations.invite(user_id(&claims)?, id, &request.email, role).await?;
+
+    Ok((StatusCode::CREATED, Json(invitation.into())))
+}
+
+/// Revoke a pending invitation (admins and owners)
+#[utoipa::path(
+    delete,
+    path = "/api/v1/orgs/{id}/invitations/{invitation_id}",
+    tag = "Organizations",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "Organization ID"),
+        ("invitation_id" = String, Path, description = "Invitation ID")
+    ),
+    responses(
+        (status = 204, description = "Invitation revoked"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "The caller is only a member", body = ErrorResponse),
+        (status = 404, description = "Organization or invitation not found", body = ErrorResponse)
+    )
+)]
+pub async fn revoke_invitation(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path((id, invitation_id)): Path<(Uuid, Uuid)>,
+) -> Result<StatusCode, ApiError> {
+    state.organizations.revoke_invitation(user_id(&claims)?, id, invitation_id).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Accept an invitation with the token from its email
+#[utoipa::path(
+    post,
+    path = "/api/v1/orgs/invitations/{token}/accept",
+    tag = "Organizations",
+    security(("bearer_auth" = [])),
+    params(("token" = String, Path, description = "Token from the invitation email")),
+    responses(
+        (status = 200, description = "Joined; the new membership", body = MemberResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Signed in with another email address", body = ErrorResponse),
+        (status = 404, description = "Unknown, used or expired invitation", body = ErrorResponse),
+        (status = 409, description = "Already a member", body = ErrorResponse)
+    )
+)]
+pub async fn accept_invitation(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    Path(token): Path<String>,
+) -> Result<Json<MemberResponse>, ApiError> {
+    let membership = state.organizations.accept_invitation(user_id(&claims)?, &token).await?;
+
+    Ok(Json(membership.into()))
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn user_id(claims: &domain::Claims) -> Result<Uuid, ApiError> {
+    claims.sub.parse().map_err(|_| ApiError::internal("Invalid user ID in token"))
+}
+
+fn parse_role(role: &str) -> Result<OrgRole, ApiError> {
+    OrgRole::parse(role).ok_or_else(|| ApiError::bad_request(format!("Unknown role '{}' (owner, admin or member)", role)))
+}
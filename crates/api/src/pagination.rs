@@ -0,0 +1,146 @@
+use std::convert::Infallible;
+
+use axum::{
+    http::{header, HeaderValue, Uri},
+    response::{IntoResponseParts, ResponseParts},
+};
+use domain::Page;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::admin::{AdminUserResponse, NoteResponse, WebhookDeliveryResponse, WebhookResponse};
+use crate::notifications::NotificationResponse;
+use crate::organizations::MemberResponse;
+use crate::users::UserResponse;
+
+// ============================================================================
+// Page Body
+// ============================================================================
+
+/// One page of a list endpoint. utoipa documents generic schemas through
+/// aliases, so each list body is declared once below and gets its own named
+/// schema; register any one of them in the `ApiDoc` to add them all.
+///
+/// ```ignore
+/// let links = PageLinks::new(&uri, &page);
+/// Ok((links, Json(Paginated::from(page))))
+/// ```
+#[derive(Serialize, ToSchema)]
+#[aliases(
+    PaginatedUserResponse = Paginated<UserResponse>,
+    AdminUsersResponse = Paginated<AdminUserResponse>,
+    NotesResponse = Paginated<NoteResponse>,
+    WebhooksResponse = Paginated<WebhookResponse>,
+    WebhookDeliveriesResponse = Paginated<WebhookDeliveryResponse>,
+    MembersResponse = Paginated<MemberResponse>,
+    NotificationPage = Paginated<NotificationResponse>
+)]
+pub struct Paginated<T> {
+    /// Items on this page
+    pub items: Vec<T>,
+    /// Total number of items
+    #[schema(example = 100)]
+    pub total: u64,
+    /// Current page number
+    #[schema(example = 1)]
+    pub page: u32,
+    /// Items per page
+    #[schema(example = 20)]
+    pub per_page: u32,
+    /// Total number of pages
+    #[schema(example = 5)]
+    pub total_pages: u32,
+    /// A later page has items
+    pub has_next: bool,
+    /// This is not the first page
+    pub has_prev: bool,
+}
+
+impl<T> Paginated<T> {
+    /// `page` with its items replaced by `items`, for items that take more
+    /// than a conversion to build
+    pub fn new<U>(items: Vec<T>, page: Page<U>) -> Self {
+        Self {
+            items,
+            total: page.total,
+            page: page.page,
+            per_page: page.per_page,
+            total_pages: page.total_pages,
+            has_next: page.has_next,
+            has_prev: page.has_prev,
+        }
+    }
+}
+
+impl<T, U: Into<T>> From<Page<U>> for Paginated<T> {
+    fn from(mut page: Page<U>) -> Self {
+        let items = std::mem::take(&mut page.items).into_iter().map(Into::into).collect();
+        Self::new(items, page)
+    }
+}
+
+// ============================================================================
+// Link Header (RFC 5988)
+// ============================================================================
+
+/// `Link` header pointing at the first, previous, next and last pages of a
+/// list response. Returned from list handlers next to the body:
+///
+/// ```ignore
+/// let links = PageLinks::new(&uri, &page);
+/// Ok((links, Json(response)))
+/// ```
+///
+/// URLs keep the request's path and other query parameters (filters), so
+/// clients follow them as-is.
+#[derive(Debug, Clone)]
+pub struct PageLinks(Option<HeaderValue>);
+
+impl PageLinks {
+    /// Links for `page`, relative to the request `uri` (use `OriginalUri`,
+    /// so nested and versioned routes link back to what the client called)
+    pub fn new<T>(uri: &Uri, page: &Page<T>) -> Self {
+        let last = page.total_pages.max(1);
+        let mut rels = vec![("first", 1)];
+        if page.has_prev {
+            rels.push(("prev", (page.page - 1).min(last)));
+        }
+        if page.has_next {
+            rels.push(("next", page.page + 1));
+        }
+        rels.push(("last", last));
+
+        let links: Vec<String> = rels
+            .into_iter()
+            .map(|(rel, number)| format!("<{}>; rel=\"{}\"", page_url(uri, number, page.per_page), rel))
+            .collect();
+        Self(HeaderValue::from_str(&links.join(", ")).ok())
+    }
+}
+
+impl IntoResponseParts for PageLinks {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        if let Some(value) = self.0 {
+            res.headers_mut().insert(header::LINK, value);
+        }
+        Ok(res)
+    }
+}
+
+/// `uri` with its `page` and `per_page` query parameters replaced
+fn page_url(uri: &Uri, page: u32, per_page: u32) -> String {
+    let mut query: Vec<&str> = uri
+        .query()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| {
+            let key = pair.split('=').next().unwrap_or_default();
+            !pair.is_empty() && key != "page" && key != "per_page"
+        })
+        .collect();
+    let paging = format!("page={}&per_page={}", page, per_page);
+    query.push(&paging);
+    format!("{}?{}", uri.path(), query.join("&"))
+}
@@ -0,0 +1,472 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, Query, State,
+    },
+    http::HeaderMap,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Response,
+    },
+    routing::get,
+    Router,
+};
+use futures_util::{stream, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::BroadcastStream;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use application::presence::{Presence, PresenceStatus};
+use application::tenancy::with_tenant;
+use application::{EventBus, RealtimeEvent, RealtimePublisher};
+use domain::{Claims, EventEnvelope, Tenant};
+use crate::error::ApiError;
+use crate::middleware::AuthUser;
+use crate::AppState;
+use crate::server_timing::Json;
+
+/// How long an unauthenticated socket may wait before sending its auth message
+const AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Interval between SSE keep-alive comments
+const SSE_HEARTBEAT: Duration = Duration::from_secs(15);
+
+/// Users one socket may watch the presence of
+const MAX_WATCHED_USERS: usize = 100;
+
+// ============================================================================
+// Connection Manager
+// ============================================================================
+
+type ConnectionId = u64;
+type UserConnections = Vec<(ConnectionId, mpsc::UnboundedSender<String>)>;
+
+/// Tracks live WebSocket connections per user and fans events out to them.
+#[derive(Default)]
+pub struct ConnectionManager {
+    connections: RwLock<HashMap<Uuid, UserConnections>>,
+    next_id: AtomicU64,
+}
+
+impl ConnectionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a connection for `user_id`, returning its id and outbound receiver.
+    pub fn register(&self, user_id: Uuid) -> (ConnectionId, mpsc::UnboundedReceiver<String>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.connections
+            .write()
+            .unwrap()
+            .entry(user_id)
+            .or_default()
+            .push((id, tx));
+        (id, rx)
+    }
+
+    pub fn unregister(&self, user_id: Uuid, connection_id: ConnectionId) {
+        let mut connections = self.connections.write().unwrap();
+        if let Some(user_connections) = connections.get_mut(&user_id) {
+            user_connections.retain(|(id, _)| *id != connection_id);
+            if user_connections.is_empty() {
+                connections.remove(&user_id);
+            }
+        }
+    }
+
+    /// Number of live connections for a user
+    pub fn connection_count(&self, user_id: Uuid) -> usize {
+        self.connections
+            .read()
+            .unwrap()
+            .get(&user_id)
+            .map_or(0, Vec::len)
+    }
+}
+
+impl RealtimePublisher for ConnectionManager {
+    fn publish(&self, user_id: Uuid, event: RealtimeEvent) {
+        let Ok(message) = serde_json::to_string(&event) else {
+            return;
+        };
+        if let Some(user_connections) = self.connections.read().unwrap().get(&user_id) {
+            for (_, tx) in user_connections {
+                // A closed receiver means the socket task is shutting down
+                let _ = tx.send(message.clone());
+            }
+        }
+    }
+}
+
+/// Forward every domain event to the concerned user's live WebSocket connections.
+pub fn spawn_event_forwarder(event_bus: Arc<dyn EventBus>, connections: Arc<ConnectionManager>) {
+    let mut events = event_bus.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(envelope) => {
+                    let Some(user_id) = envelope.event.user_id() else {
+                        continue;
+                    };
+                    let data = serde_json::to_value(&envelope.event).unwrap_or_default();
+                    connections.publish(user_id, RealtimeEvent::new(envelope.event.name(), data));
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "websocket event forwarder lagged behind");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+// ============================================================================
+// Routes
+// ============================================================================
+
+/// Public real-time routes (authentication happens during the upgrade)
+pub fn realtime_routes() -> Router<Arc<AppState>> {
+    Router::new().route("/ws", get(ws_handler))
+}
+
+// ============================================================================
+// Server-Sent Events
+// ============================================================================
+
+/// Stream the current user's domain events as Server-Sent Events.
+///
+/// Send `Last-Event-ID` on reconnect to replay events missed while offline
+/// (as far back as the bus history allows).
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/events",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    params(
+        ("Last-Event-ID" = Option<u64>, Header, description = "Resume after this event id")
+    ),
+    responses(
+        (status = 200, description = "Event stream", content_type = "text/event-stream"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse)
+    )
+)]
+pub async fn user_events(
+    State(state): State<Arc<AppState>>,
+    AuthUser(claims): AuthUser,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let user_id = claims
+        .sub
+        .parse::<Uuid>()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+
+    // Subscribe before reading history so nothing published in between is lost
+    let live = BroadcastStream::new(state.event_bus.subscribe());
+    let replay = match last_event_id {
+        Some(id) => state.event_bus.events_since(id),
+        None => Vec::new(),
+    };
+    let mut last_sent = replay.last().map(|e| e.id).or(last_event_id).unwrap_or(0);
+
+    let replay = stream::iter(replay.into_iter().filter(move |e| e.event.user_id() == Some(user_id)));
+    let live = live.filter_map(move |item| {
+        let event = match item {
+            // Skip anything already delivered from the replay buffer
+            Ok(envelope) if envelope.id > last_sent && envelope.event.user_id() == Some(user_id) => {
+                last_sent = envelope.id;
+                Some(envelope)
+            }
+            // Lagged receivers resync on the client's next reconnect via Last-Event-ID
+            _ => None,
+        };
+        std::future::ready(event)
+    });
+
+    let events = replay.chain(live).map(|envelope| Ok(to_sse_event(&envelope)));
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::new().interval(SSE_HEARTBEAT)))
+}
+
+fn to_sse_event(envelope: &EventEnvelope) -> Event {
+    Event::default()
+        .id(envelope.id.to_string())
+        .event(envelope.event.name())
+        .json_data(&envelope.event)
+        .unwrap_or_else(|_| Event::default().id(envelope.id.to_string()).event("error"))
+}
+
+// ============================================================================
+// WebSocket
+// ============================================================================
+
+#[derive(Deserialize)]
+pub struct WsParams {
+    /// JWT access token; if omitted the first message must be `{"type":"auth","token":"..."}`
+    token: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Auth { token: String },
+    /// Start receiving `presence.changed` events for these users
+    SubscribePresence { user_ids: Vec<Uuid> },
+    UnsubscribePresence { user_ids: Vec<Uuid> },
+}
+
+/// Upgrade to a WebSocket authenticated by `?token=` or an initial auth message
+pub async fn ws_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<WsParams>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, ApiError> {
+    // Reject bad query-param tokens before upgrading so clients get a plain 401
+    let claims = match params.token {
+        Some(token) => Some(
+            state
+                .token_versions
+                .authenticate(state.token_service.as_ref(), &token)
+                .await
+                .map_err(|e| ApiError::unauthorized(e.to_string()))?,
+        ),
+        None => None,
+    };
+
+    Ok(ws.on_upgrade(move |socket| handle_socket(state, socket, claims)))
+}
+
+async fn handle_socket(state: Arc<AppState>, mut socket: WebSocket, claims: Option<Claims>) {
+    let claims = match claims {
+        Some(claims) => claims,
+        None => match authenticate_first_message(&state, &mut socket).await {
+            Some(claims) => claims,
+            None => {
+                let _ = socket
+                    .send(Message::Text(r#"{"event":"error","data":"unauthorized"}"#.into()))
+                    .await;
+                let _ = socket.send(Message::Close(None)).await;
+                return;
+            }
+        },
+    };
+
+    let Ok(user_id) = claims.sub.parse::<Uuid>() else {
+        return;
+    };
+    // The upgrade runs outside the request's tenant context
+    let tenant = claims
+        .tenant_id
+        .as_deref()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or(Tenant::DEFAULT_ID);
+
+    let (connection_id, mut outbound) = state.realtime.register(user_id);
+    tracing::debug!(%user_id, connection_id, "websocket connected");
+
+    // Presence ids must be unique across instances, unlike `connection_id`
+    let presence_id = Uuid::new_v4().to_string();
+    if let Err(e) = state.presence.heartbeat(user_id, &presence_id).await {
+        tracing::warn!(%user_id, error = %e, "presence heartbeat failed");
+    }
+    let period = state.presence.heartbeat_interval();
+    let mut heartbeat = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    let mut presence_changes = state.presence.subscribe();
+    let mut watched = HashSet::new();
+
+    let _ = socket
+        .send(Message::Text(r#"{"event":"connected","data":null}"#.into()))
+        .await;
+
+    loop {
+        tokio::select! {
+            Some(message) = outbound.recv() => {
+                if socket.send(Message::Text(message)).await.is_err() {
+                    break;
+                }
+            }
+            _ = heartbeat.tick() => {
+                if let Err(e) = state.presence.heartbeat(user_id, &presence_id).await {
+                    tracing::warn!(%user_id, error = %e, "presence heartbeat failed");
+                }
+            }
+            change = presence_changes.recv() => match change {
+                Ok(change) if watched.contains(&change.user_id) => {
+                    let data = serde_json::to_value(&change).unwrap_or_default();
+                    if !send_event(&mut socket, RealtimeEvent::new("presence.changed", data)).await {
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(%user_id, skipped, "presence subscriber lagged behind");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(Message::Text(text))) => {
+                    let Ok(command) = serde_json::from_str::<ClientMessage>(&text) else {
+                        continue;
+                    };
+                    let events = match command {
+                        ClientMessage::SubscribePresence { user_ids } => {
+                            watch_presence(&state, tenant, &mut watched, user_ids).await
+                        }
+                        ClientMessage::UnsubscribePresence { user_ids } => {
+                            for id in &user_ids {
+                                watched.remove(id);
+                            }
+                            Vec::new()
+                        }
+                        ClientMessage::Auth { .. } => Vec::new(),
+                    };
+                    for event in events {
+                        if !send_event(&mut socket, event).await {
+                            break;
+                        }
+                    }
+                }
+                // Pings are answered automatically
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    state.realtime.unregister(user_id, connection_id);
+    if let Err(e) = state.presence.disconnected(user_id, &presence_id).await {
+        tracing::warn!(%user_id, error = %e, "presence disconnect failed");
+    }
+    tracing::debug!(%user_id, connection_id, "websocket disconnected");
+}
+
+/// Watch the presence of the users in `user_ids` the socket's tenant can see,
+/// returning their current presence (or an error event past the limit)
+async fn watch_presence(
+    state: &AppState,
+    tenant: Uuid,
+    watched: &mut HashSet<Uuid>,
+    user_ids: Vec<Uuid>,
+) -> Vec<RealtimeEvent> {
+    let mut events = Vec::new();
+    for id in user_ids {
+        if watched.contains(&id) {
+            continue;
+        }
+        if watched.len() >= MAX_WATCHED_USERS {
+            events.push(RealtimeEvent::new(
+                "error",
+                serde_json::json!(format!("at most {} users can be watched", MAX_WATCHED_USERS)),
+            ));
+            break;
+        }
+        // Unknown and other-tenant users are skipped silently
+        if !matches!(with_tenant(tenant, state.user_service.get_user(id)).await, Ok(Some(_))) {
+            continue;
+        }
+        match state.presence.presence(id).await {
+            Ok(presence) => {
+                watched.insert(id);
+                let data = serde_json::to_value(PresenceResponse::from(presence)).unwrap_or_default();
+                events.push(RealtimeEvent::new("presence", data));
+            }
+            Err(e) => tracing::warn!(user_id = %id, error = %e, "presence lookup failed"),
+        }
+    }
+    events
+}
+
+/// Send `event` as a text frame; false once the socket is gone
+async fn send_event(socket: &mut WebSocket, event: RealtimeEvent) -> bool {
+    let Ok(message) = serde_json::to_string(&event) else {
+        return true;
+    };
+    socket.send(Message::Text(message)).await.is_ok()
+}
+
+async fn authenticate_first_message(state: &AppState, socket: &mut WebSocket) -> Option<Claims> {
+    let message = tokio::time::timeout(AUTH_TIMEOUT, socket.recv()).await.ok()??.ok()?;
+    let Message::Text(text) = message else {
+        return None;
+    };
+    let ClientMessage::Auth { token } = serde_json::from_str(&text).ok()? else {
+        return None;
+    };
+    state.token_versions.authenticate(state.token_service.as_ref(), &token).await.ok()
+}
+
+// ============================================================================
+// Presence
+// ============================================================================
+
+/// Whether a user has a live WebSocket connection on any instance
+#[derive(Serialize, ToSchema)]
+pub struct PresenceResponse {
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub user_id: String,
+    /// `online` or `offline`
+    #[schema(example = "online")]
+    pub status: String,
+    /// Last connect, heartbeat or disconnect (RFC 3339)
+    pub last_seen: Option<String>,
+}
+
+impl From<Presence> for PresenceResponse {
+    fn from(presence: Presence) -> Self {
+        Self {
+            user_id: presence.user_id.to_string(),
+            status: match presence.status {
+                PresenceStatus::Online => "online",
+                PresenceStatus::Offline => "offline",
+            }
+            .to_string(),
+            last_seen: presence.last_seen.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+/// Get a user's online/offline presence
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{id}/presence",
+    tag = "Users",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = String, Path, description = "User UUID")
+    ),
+    responses(
+        (status = 200, description = "Current presence", body = PresenceResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse)
+    )
+)]
+pub async fn user_presence(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<PresenceResponse>, ApiError> {
+    state
+        .user_service
+        .get_user(id)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("User with id {} not found", id)))?;
+
+    let presence = state.presence.presence(id).await?;
+    Ok(Json(presence.into()))
+}
@@ -0,0 +1,148 @@
+use application::request_signing::{content_sha256, RequestSigning, SignedRequest};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use chrono::Utc;
+use domain::Claims;
+use shared::RequestSigningSettings;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::versioning::RequestedUri;
+
+/// Public key id of the calling client, `ak_...`
+pub const KEY_ID_HEADER: &str = "x-api-key-id";
+/// Unix seconds the request was signed at
+pub const TIMESTAMP_HEADER: &str = "x-signature-timestamp";
+/// Hex SHA-256 of the body (of the empty string when there is none)
+pub const CONTENT_SHA256_HEADER: &str = "x-content-sha256";
+/// `sha256=<hex HMAC>` of timestamp, method, path and body digest
+pub const SIGNATURE_HEADER: &str = "x-signature";
+
+/// Set on requests with a valid signature, next to `Claims` carrying the
+/// client's id and roles. `jwt_auth` accepts them without a token.
+#[derive(Debug, Clone)]
+pub struct SignedClient {
+    pub id: Uuid,
+    pub name: String,
+    pub key_id: String,
+}
+
+// ============================================================================
+// Verification
+// ============================================================================
+
+/// Verifies signed server-to-server requests, built from `[request_signing]`
+pub struct SignedRequests {
+    signing: Arc<RequestSigning>,
+    max_body_bytes: usize,
+    max_skew_secs: i64,
+}
+
+impl SignedRequests {
+    pub fn new(signing: Arc<RequestSigning>, settings: &RequestSigningSettings) -> Self {
+        Self {
+            signing,
+            max_body_bytes: settings.max_body_bytes,
+            max_skew_secs: i64::try_from(settings.max_skew_secs).unwrap_or(i64::MAX),
+        }
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Result<&'a str, ApiError> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| {
+            ApiError::unauthorized(format!(
+                "Signed requests need {}, {}, {} and {}",
+                KEY_ID_HEADER, TIMESTAMP_HEADER, CONTENT_SHA256_HEADER, SIGNATURE_HEADER
+            ))
+        })
+}
+
+// ============================================================================
+// Middleware
+// ============================================================================
+
+/// Authenticate requests carrying `X-Api-Key-Id` by their signature. The
+/// body is read whole to check its digest, then handed on unchanged.
+/// Requests without the header pass untouched, to `jwt_auth` as usual.
+pub async fn signed_requests(
+    State(verifier): State<Arc<SignedRequests>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if !request.headers().contains_key(KEY_ID_HEADER) {
+        return Ok(next.run(request).await);
+    }
+
+    let (mut parts, body) = request.into_parts();
+    let body = to_bytes(body, verifier.max_body_bytes).await.map_err(|_| {
+        ApiError::new(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "PAYLOAD_TOO_LARGE",
+            format!("Signed request bodies are limited to {} bytes", verifier.max_body_bytes),
+        )
+    })?;
+
+    let headers = &parts.headers;
+    let timestamp = header(headers, TIMESTAMP_HEADER)?
+        .parse::<i64>()
+        .map_err(|_| ApiError::unauthorized(format!("{} must be Unix seconds", TIMESTAMP_HEADER)))?;
+    let digest = header(headers, CONTENT_SHA256_HEADER)?;
+    if !digest.eq_ignore_ascii_case(&content_sha256(&body)) {
+        return Err(ApiError::unauthorized(format!("Body does not match {}", CONTENT_SHA256_HEADER)));
+    }
+    // The path as sent, before a legacy one was routed to its version
+    let uri = parts.extensions.get::<RequestedUri>().map_or(&parts.uri, |requested| &requested.0);
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    let signed = SignedRequest {
+        key_id: header(headers, KEY_ID_HEADER)?,
+        timestamp,
+        method: parts.method.as_str(),
+        path,
+        content_sha256: &digest.to_ascii_lowercase(),
+        signature: header(headers, SIGNATURE_HEADER)?,
+    };
+    let client = verifier.signing.verify(&signed, Utc::now()).await?;
+
+    // Like tokens, every client has the `user` role
+    let mut roles = client.roles.clone();
+    if !roles.iter().any(|r| r == "user") {
+        roles.push("user".to_string());
+    }
+    parts.extensions.insert(Claims {
+        sub: client.id.to_string(),
+        email: String::new(),
+        roles,
+        exp: timestamp.saturating_add(verifier.max_skew_secs),
+        iat: timestamp,
+        tenant_id: None,
+        token_version: 0,
+        act: None,
+    });
+    parts.extensions.insert(SignedClient {
+        id: client.id,
+        name: client.name,
+        key_id: client.key_id,
+    });
+    Ok(next.run(Request::from_parts(parts, Body::from(body))).await)
+}
+
+/// For routes about the caller's own account (`/me`): an API client has no
+/// user behind its `Claims`, so signed requests get 403 instead of a lookup
+/// of a user that does not exist.
+pub async fn users_only(request: Request, next: Next) -> Result<Response, ApiError> {
+    if request.extensions().get::<SignedClient>().is_some() {
+        return Err(ApiError::forbidden(
+            "API clients have no account of their own; /me routes need a user's token",
+        ));
+    }
+    Ok(next.run(request).await)
+}
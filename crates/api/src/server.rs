@@ -0,0 +1,179 @@
+use std::convert::Infallible;
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, Request},
+    response::Response,
+};
+use hyper::server::conn::http1;
+use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
+use hyper_util::server::conn::auto;
+use shared::ServerConfig;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpSocket};
+use tokio::sync::watch;
+use tower::{Service, ServiceExt};
+
+use crate::tls::ReloadableTls;
+
+// ============================================================================
+// Listener
+// ============================================================================
+
+/// Bind `server.host:server.port` with the configured listen backlog
+pub async fn bind(config: &ServerConfig) -> io::Result<TcpListener> {
+    let addr = tokio::net::lookup_host((config.host.as_str(), config.port))
+        .await?
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, format!("Cannot resolve {}", config.host)))?;
+    let socket = match addr {
+        SocketAddr::V4(_) => TcpSocket::new_v4()?,
+        SocketAddr::V6(_) => TcpSocket::new_v6()?,
+    };
+    // Restarting must not wait for the old socket's TIME_WAIT to pass
+    socket.set_reuseaddr(true)?;
+    socket.bind(addr)?;
+    socket.listen(config.backlog)
+}
+
+/// HTTP/1.1 and HTTP/2 settings from `ServerConfig`. With HTTP/2 off, the
+/// HTTP/1 builder serves connections directly: the auto builder ignores
+/// `http1_only` on connections that may be upgraded (WebSockets).
+enum Connections {
+    Auto(auto::Builder<TokioExecutor>),
+    Http1(http1::Builder),
+}
+
+impl Connections {
+    fn new(config: &ServerConfig) -> Self {
+        let header_read_timeout = Duration::from_secs(config.keep_alive_timeout_secs);
+        if !config.http2 {
+            let mut builder = http1::Builder::new();
+            builder
+                .timer(TokioTimer::new())
+                .keep_alive(config.keep_alive)
+                .header_read_timeout(header_read_timeout);
+            return Self::Http1(builder);
+        }
+
+        let mut builder = auto::Builder::new(TokioExecutor::new());
+        builder
+            .http1()
+            .timer(TokioTimer::new())
+            .keep_alive(config.keep_alive)
+            .header_read_timeout(header_read_timeout);
+        builder
+            .http2()
+            .timer(TokioTimer::new())
+            .max_concurrent_streams(config.http2_max_concurrent_streams)
+            .keep_alive_interval(config.http2_keep_alive_interval_secs.map(Duration::from_secs))
+            .keep_alive_timeout(Duration::from_secs(config.http2_keep_alive_timeout_secs));
+        Self::Auto(builder)
+    }
+
+    /// Serve one connection until it closes. Once `shutdown` fires, the
+    /// request in flight finishes and the connection is closed.
+    async fn serve<IO, S>(&self, stream: IO, remote: SocketAddr, app: S, mut shutdown: watch::Receiver<()>)
+    where
+        IO: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+        S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+        S::Future: Send,
+    {
+        let service = hyper::service::service_fn(move |request: hyper::Request<hyper::body::Incoming>| {
+            let mut request = request.map(Body::new);
+            request.extensions_mut().insert(ConnectInfo(remote));
+            app.clone().oneshot(request)
+        });
+        let io = TokioIo::new(stream);
+        let result = match self {
+            Self::Auto(builder) => {
+                let connection = builder.serve_connection_with_upgrades(io, service);
+                tokio::pin!(connection);
+                tokio::select! {
+                    result = connection.as_mut() => result,
+                    _ = shutdown.changed() => {
+                        connection.as_mut().graceful_shutdown();
+                        connection.await
+                    }
+                }
+            }
+            Self::Http1(builder) => {
+                let connection = builder.serve_connection(io, service).with_upgrades();
+                tokio::pin!(connection);
+                tokio::select! {
+                    result = connection.as_mut() => result.map_err(Into::into),
+                    _ = shutdown.changed() => {
+                        connection.as_mut().graceful_shutdown();
+                        connection.await.map_err(Into::into)
+                    }
+                }
+            }
+        };
+        if let Err(e) = result {
+            tracing::debug!(%remote, "Connection closed with an error: {}", e);
+        }
+    }
+}
+
+// ============================================================================
+// Serving
+// ============================================================================
+
+/// Serve `app` on `listener` until `shutdown` resolves, then wait for open
+/// connections to finish. Speaks TLS when `tls` is given. Unlike
+/// `axum::serve` this applies the HTTP/1.1, HTTP/2 and TCP settings of
+/// `config`. Requests carry `ConnectInfo<SocketAddr>` all the same.
+pub async fn serve<S>(
+    listener: TcpListener,
+    config: &ServerConfig,
+    tls: Option<Arc<ReloadableTls>>,
+    app: S,
+    shutdown: impl Future<Output = ()>,
+) -> io::Result<()>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    let connections = Arc::new(Connections::new(config));
+    // Every connection task holds a receiver; all dropped means all closed
+    let (stop, stopped) = watch::channel(());
+    tokio::pin!(shutdown);
+    loop {
+        let (tcp, remote) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    // Usually out of file descriptors; keep serving the others
+                    tracing::warn!("Failed to accept a connection: {}", e);
+                    continue;
+                }
+            },
+            _ = &mut shutdown => break,
+        };
+        if let Err(e) = tcp.set_nodelay(config.tcp_nodelay) {
+            tracing::debug!(%remote, "Failed to set TCP_NODELAY: {}", e);
+        }
+
+        let (connections, app, stopped) = (connections.clone(), app.clone(), stopped.clone());
+        let acceptor = tls.as_ref().map(|tls| tls.acceptor());
+        tokio::spawn(async move {
+            match acceptor {
+                Some(acceptor) => match acceptor.accept(tcp).await {
+                    Ok(stream) => connections.serve(stream, remote, app, stopped).await,
+                    Err(e) => tracing::debug!(%remote, "TLS handshake failed: {}", e),
+                },
+                None => connections.serve(tcp, remote, app, stopped).await,
+            }
+        });
+    }
+
+    drop((listener, stopped));
+    let _ = stop.send(());
+    stop.closed().await;
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use application::timings::{self, PhaseTiming, RequestTimings};
+use axum::{
+    extract::{FromRequest, Request, State},
+    http::HeaderValue,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use domain::Claims;
+use serde::Serialize;
+use shared::ServerTimingConfig;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::AppState;
+
+/// Response header carrying the breakdown
+pub const SERVER_TIMING_HEADER: &str = "server-timing";
+
+/// Request header asking for the breakdown (`server.timing.debug_header`)
+pub const DEBUG_TIMING_HEADER: &str = "x-debug-timing";
+
+/// Set on responses to admins by `jwt_auth`; their requests get the breakdown
+#[derive(Debug, Clone, Copy)]
+pub struct ServerTimingAllowed;
+
+// ============================================================================
+// Middleware
+// ============================================================================
+
+/// Collect the time each request spends authenticating, in the database and
+/// serializing its response (see `application::timings`), and report it in
+/// `Server-Timing` to admins and, when enabled, to requests carrying
+/// `X-Debug-Timing`. Everyone else gets the response unchanged.
+pub async fn server_timing(State(config): State<Arc<ServerTimingConfig>>, request: Request, next: Next) -> Response {
+    if !config.enabled {
+        return next.run(request).await;
+    }
+    let requested = config.debug_header && request.headers().contains_key(DEBUG_TIMING_HEADER);
+
+    let started = Instant::now();
+    let timings = Arc::new(RequestTimings::default());
+    let mut response = timings::with_timings(timings.clone(), next.run(request)).await;
+
+    if requested || response.extensions().get::<ServerTimingAllowed>().is_some() {
+        let value = header_value(&timings.phases(), started.elapsed());
+        if let Ok(value) = HeaderValue::from_str(&value) {
+            response.headers_mut().insert(SERVER_TIMING_HEADER, value);
+        }
+    }
+    response
+}
+
+/// Mark the response of an admin's request for the breakdown. Role lookups
+/// are shared with the request's own checks, so this is usually free.
+pub async fn allow_admins(state: &AppState, claims: &Claims, response: &mut Response) {
+    if timings::current_timings().is_none() {
+        return;
+    }
+    if state.authz.has_role(claims, "admin").await.unwrap_or(false) {
+        response.extensions_mut().insert(ServerTimingAllowed);
+    }
+}
+
+/// `auth;dur=1.2, db;dur=3.4;desc="2 queries", total;dur=9.8` with
+/// durations in milliseconds
+pub fn header_value(phases: &[PhaseTiming], total: Duration) -> String {
+    let mut value = String::new();
+    for timing in phases {
+        let _ = write!(value, "{};dur={:.1}", timing.phase, millis(timing.elapsed));
+        if timing.phase == timings::DB {
+            let plural = if timing.count == 1 { "query" } else { "queries" };
+            let _ = write!(value, ";desc=\"{} {}\"", timing.count, plural);
+        }
+        value.push_str(", ");
+    }
+    let _ = write!(value, "total;dur={:.1}", millis(total));
+    value
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+// ============================================================================
+// Json
+// ============================================================================
+
+/// `axum::Json`, with response serialization counted as the request's
+/// `serialize` phase. Handlers use it in place of `axum::Json`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+impl<S, T> FromRequest<S> for Json<T>
+where
+    S: Send + Sync,
+    axum::Json<T>: FromRequest<S>,
+{
+    type Rejection = <axum::Json<T> as FromRequest<S>>::Rejection;
+
+    fn from_request<'life0, 'async_trait>(
+        req: Request,
+        state: &'life0 S,
+    ) -> core::pin::Pin<Box<dyn core::future::Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            let axum::Json(value) = axum::Json::<T>::from_request(req, state).await?;
+            Ok(Json(value))
+        })
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        let _timer = timings::start(timings::SERIALIZE);
+        axum::Json(self.0).into_response()
+    }
+}
@@ -0,0 +1,196 @@
+use std::time::Duration;
+
+use application::oauth::{OAuthStateStore, PendingAuthorization};
+use application::secrets::constant_time_eq;
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::Utc;
+use rand::RngCore;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha512};
+use shared::SessionSettings;
+use tower_cookies::cookie::{time, SameSite};
+use tower_cookies::{Cookie, Cookies, Key};
+
+// ============================================================================
+// Cookie Sessions
+// ============================================================================
+
+/// Cookie holding queued flash messages
+pub const FLASH_COOKIE: &str = "flash";
+/// Cookie holding the CSRF token for double-submit checks
+pub const CSRF_COOKIE: &str = "csrf";
+/// Header the CSRF token is echoed back in
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// Flash messages live until read, or this long
+const FLASH_TTL: Duration = Duration::from_secs(300);
+const CSRF_TTL: Duration = Duration::from_secs(24 * 3600);
+
+/// Value stored in a session cookie, with its own expiry so a captured
+/// cookie cannot be replayed forever
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    exp: i64,
+    data: T,
+}
+
+/// Short-lived state kept in encrypted, authenticated cookies instead of a
+/// server-side store: flash messages, CSRF tokens, OAuth state/PKCE
+/// verifiers. Needs `CookieManagerLayer` on the router and the `Cookies`
+/// extractor in handlers. Values must stay small (browsers cap cookies at
+/// about 4 KB).
+#[derive(Clone)]
+pub struct CookieSessions {
+    key: Key,
+    secure: bool,
+}
+
+impl CookieSessions {
+    /// `secret` should be at least 32 random bytes; the cookie key is derived from it
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            key: Key::from(&Sha512::digest(secret)),
+            secure: true,
+        }
+    }
+
+    /// From the `[session]` settings, already validated. Without a secret a
+    /// random one is used, so sessions do not survive a restart or span
+    /// several instances.
+    pub fn from_settings(settings: &SessionSettings) -> Self {
+        let sessions = match &settings.secret {
+            Some(secret) => Self::new(secret.as_bytes()),
+            None => {
+                tracing::warn!("session.secret not set; cookie sessions will not survive a restart");
+                let mut secret = [0u8; 64];
+                rand::thread_rng().fill_bytes(&mut secret);
+                Self::new(&secret)
+            }
+        };
+        sessions.with_secure(settings.cookie_secure)
+    }
+
+    /// Mark cookies `Secure` (HTTPS only). Browsers accept secure cookies
+    /// from `http://localhost` too.
+    pub fn with_secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Store `value` encrypted under `name`, readable for `ttl`
+    pub fn set<T: Serialize>(&self, cookies: &Cookies, name: &str, value: &T, ttl: Duration) {
+        let envelope = Envelope {
+            exp: Utc::now().timestamp() + ttl.as_secs() as i64,
+            data: value,
+        };
+        let Ok(json) = serde_json::to_string(&envelope) else {
+            return;
+        };
+        let mut cookie = Cookie::new(name.to_string(), json);
+        cookie.set_http_only(true);
+        cookie.set_secure(self.secure);
+        cookie.set_same_site(SameSite::Lax);
+        cookie.set_path("/");
+        cookie.set_max_age(time::Duration::seconds(ttl.as_secs() as i64));
+        cookies.private(&self.key).add(cookie);
+    }
+
+    /// The value under `name`; `None` when missing, expired, tampered with
+    /// or not a `T`
+    pub fn get<T: DeserializeOwned>(&self, cookies: &Cookies, name: &str) -> Option<T> {
+        let cookie = cookies.private(&self.key).get(name)?;
+        let envelope: Envelope<T> = serde_json::from_str(cookie.value()).ok()?;
+        (envelope.exp > Utc::now().timestamp()).then_some(envelope.data)
+    }
+
+    /// Read and clear `name`, for one-shot values such as OAuth state
+    pub fn take<T: DeserializeOwned>(&self, cookies: &Cookies, name: &str) -> Option<T> {
+        let value = self.get(cookies, name);
+        self.remove(cookies, name);
+        value
+    }
+
+    pub fn remove(&self, cookies: &Cookies, name: &str) {
+        if cookies.get(name).is_some() {
+            let mut cookie = Cookie::new(name.to_string(), "");
+            cookie.set_path("/");
+            cookies.remove(cookie);
+        }
+    }
+
+    // ------------------------------------------------------------------------
+    // Flash messages
+    // ------------------------------------------------------------------------
+
+    /// Queue a message for the next response that calls `take_flashes`
+    pub fn flash(&self, cookies: &Cookies, message: impl Into<String>) {
+        let mut messages: Vec<String> = self.get(cookies, FLASH_COOKIE).unwrap_or_default();
+        messages.push(message.into());
+        self.set(cookies, FLASH_COOKIE, &messages, FLASH_TTL);
+    }
+
+    pub fn take_flashes(&self, cookies: &Cookies) -> Vec<String> {
+        self.take(cookies, FLASH_COOKIE).unwrap_or_default()
+    }
+
+    // ------------------------------------------------------------------------
+    // CSRF
+    // ------------------------------------------------------------------------
+
+    /// The caller's CSRF token, issued on first use. Clients echo it in
+    /// `CSRF_HEADER` on unsafe requests authenticated by cookies.
+    pub fn csrf_token(&self, cookies: &Cookies) -> String {
+        if let Some(token) = self.get::<String>(cookies, CSRF_COOKIE) {
+            return token;
+        }
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let token = hex::encode(bytes);
+        self.set(cookies, CSRF_COOKIE, &token, CSRF_TTL);
+        token
+    }
+
+    /// Whether `token` is the caller's CSRF token
+    pub fn verify_csrf(&self, cookies: &Cookies, token: &str) -> bool {
+        let Some(expected) = self.get::<String>(cookies, CSRF_COOKIE) else {
+            return false;
+        };
+        constant_time_eq(expected.as_bytes(), token.as_bytes())
+    }
+}
+
+// ============================================================================
+// Cookie OAuth State Store
+// ============================================================================
+
+/// Keeps pending OAuth authorizations in the browser, one encrypted cookie
+/// per attempt (`oauth_<state>`), so parallel logins in several tabs do not
+/// clobber each other. The callback must come back to the same browser,
+/// which also ties the state to the user agent that started the flow.
+pub struct CookieOAuthStateStore {
+    sessions: CookieSessions,
+    cookies: Cookies,
+}
+
+impl CookieOAuthStateStore {
+    pub fn new(sessions: CookieSessions, cookies: Cookies) -> Self {
+        Self { sessions, cookies }
+    }
+}
+
+fn oauth_cookie(state: &str) -> String {
+    format!("oauth_{}", state)
+}
+
+#[async_trait]
+impl OAuthStateStore for CookieOAuthStateStore {
+    async fn save(&self, state: &str, pending: &PendingAuthorization, ttl: Duration) -> Result<(), ApplicationError> {
+        self.sessions.set(&self.cookies, &oauth_cookie(state), pending, ttl);
+        Ok(())
+    }
+
+    async fn take(&self, state: &str) -> Result<Option<PendingAuthorization>, ApplicationError> {
+        Ok(self.sessions.take(&self.cookies, &oauth_cookie(state)))
+    }
+}
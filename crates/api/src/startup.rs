@@ -0,0 +1,498 @@
+use axum::{extract::State, http::header, middleware as axum_mw, routing::get, Router};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+use application::EventBus;
+use domain::DomainEvent;
+use infrastructure::{DatabaseDiagnostics, PrometheusTelemetry};
+use shared::{redact_url, Config, DEFAULT_JWT_SECRET};
+
+use crate::logging::SENSITIVE_KEYS;
+use crate::middleware::{jwt_auth, require_role};
+use crate::AppState;
+use crate::server_timing::Json;
+
+// ============================================================================
+// Configuration Catalog
+// ============================================================================
+
+/// Settings read from the environment, with the default used when unset
+/// (`None`: unset means disabled, or the setting is required)
+pub const CONFIG_KEYS: &[(&str, Option<&str>)] = &[
+    ("DATABASE_URL", None),
+    ("DATABASE_REPLICA_URL", None),
+    ("DATABASE_REPLICA_MAX_LAG_MS", Some("5000")),
+    ("REGION", Some("local")),
+    ("APP_ENV", Some("development")),
+    ("CONFIG_DIR", Some("config")),
+    ("TENANT_BASE_DOMAIN", None),
+    ("REDIS_URL", None),
+    ("JWT_SECRET", Some(DEFAULT_JWT_SECRET)),
+    ("JWT_EXPIRATION_HOURS", Some("24")),
+    ("AUTHZ_CACHE_TTL_SECS", Some("30")),
+    ("RUST_LOG", Some("info,tower_http=debug")),
+    ("LOG_FORMAT", Some("text")),
+    ("SERVICE_NAME", Some("api")),
+    ("GRPC_PORT", Some("50051")),
+    ("FEATURE_FLAGS", Some("[]")),
+    ("EXPERIMENTS", Some("[]")),
+    ("HOSTNAME", None),
+    ("EMAIL_TRANSPORT", Some("console")),
+    ("EMAIL_FROM", Some("Rust Base <no-reply@example.com>")),
+    ("APP_NAME", Some("Rust Base")),
+    ("SMTP_HOST", Some("localhost")),
+    ("SMTP_PORT", Some("587")),
+    ("SMTP_USERNAME", None),
+    ("SMTP_PASSWORD", None),
+    ("SMTP_SECURITY", Some("starttls")),
+    ("SUPPORT_NOTIFY_EMAILS", None),
+    ("EMAIL_WEBHOOK_TOKEN", None),
+    ("TRUST_FORWARDED_FOR", Some("false")),
+    ("JOB_WORKERS", Some("2")),
+    ("JOB_RETENTION_DAYS", Some("7")),
+    ("STORAGE_BACKEND", Some("local")),
+    ("STORAGE_LOCAL_DIR", Some("./data/uploads")),
+    ("STORAGE_S3_BUCKET", None),
+    ("STORAGE_PUBLIC_URL", Some("/files")),
+    ("STORAGE_SIGNING_SECRET", Some("random per process")),
+    ("AVATAR_MAX_BYTES", Some("2097152")),
+    ("FILE_SCANNER", Some("none")),
+    ("CLAMAV_ADDR", Some("127.0.0.1:3310")),
+    ("ICAP_URL", None),
+    ("SESSION_SECRET", Some("random per process")),
+    ("SESSION_COOKIE_SECURE", Some("true")),
+    ("AUDIT_SINK", None),
+    ("AUDIT_SYSLOG_ADDR", Some("127.0.0.1:514")),
+    ("AUDIT_HTTP_URL", None),
+    ("AUDIT_HTTP_AUTHORIZATION", None),
+    ("AUDIT_S3_BUCKET", None),
+    ("AUDIT_S3_PREFIX", Some("audit/")),
+    ("AUDIT_BATCH_SIZE", Some("100")),
+    ("AUDIT_FLUSH_SECS", Some("5")),
+    ("AUDIT_BUFFER", Some("10000")),
+    ("IDEMPOTENCY_TTL_HOURS", Some("24")),
+    ("IDEMPOTENCY_LOCK_TIMEOUT_SECS", Some("60")),
+    ("HTTP_LOG_ENABLED", Some("false")),
+    ("HTTP_LOG_MAX_BODY", Some("1024")),
+    ("HTTP_LOG_SAMPLE_RATE", Some("1.0")),
+    ("HTTP_LOG_ROUTE_SAMPLE_RATES", None),
+];
+
+/// Shortest JWT secret accepted without a warning, in bytes
+const MIN_JWT_SECRET_LEN: usize = 32;
+/// Estimated entropy below which the JWT secret is reported as guessable
+const MIN_JWT_SECRET_BITS: f64 = 128.0;
+/// Database/local clock difference that starts to matter for token expiry
+const MAX_CLOCK_SKEW_MS: i64 = 2_000;
+
+// ============================================================================
+// Configuration Sources
+// ============================================================================
+
+/// Where the effective value of a setting came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    /// Process environment
+    Environment,
+    /// The `.env` file
+    DotEnv,
+    /// Unset: the built-in default applies
+    Default,
+    /// Unset, without a default
+    Unset,
+}
+
+/// One setting of the effective configuration, secrets redacted
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ConfigEntry {
+    #[schema(example = "JOB_WORKERS")]
+    pub key: String,
+    #[schema(example = "4")]
+    pub value: Option<String>,
+    pub source: ConfigSource,
+    /// Also set in `.env`, but the process environment wins
+    pub overrides_dotenv: bool,
+}
+
+/// Which variables the process inherited and which `.env` added
+pub struct ConfigSources {
+    dotenv_path: Option<PathBuf>,
+    inherited: HashSet<String>,
+    dotenv_keys: HashSet<String>,
+}
+
+impl ConfigSources {
+    /// Load `.env` (without overriding the environment), remembering where
+    /// each variable came from. Call before anything reads the environment.
+    pub fn load_dotenv() -> Self {
+        let inherited = std::env::vars_os().filter_map(|(key, _)| key.into_string().ok()).collect();
+        let dotenv_path = dotenvy::dotenv().ok();
+        let dotenv_keys = dotenv_path
+            .as_ref()
+            .and_then(|path| dotenvy::from_path_iter(path).ok())
+            .map(|items| items.filter_map(Result::ok).map(|(key, _)| key).collect())
+            .unwrap_or_default();
+        Self {
+            dotenv_path,
+            inherited,
+            dotenv_keys,
+        }
+    }
+
+    /// The effective value and source of every `CONFIG_KEYS` entry
+    pub fn report(&self) -> Vec<ConfigEntry> {
+        CONFIG_KEYS
+            .iter()
+            .map(|(key, default)| {
+                let in_dotenv = self.dotenv_keys.contains(*key);
+                let (value, source) = match std::env::var(key) {
+                    Ok(value) if self.inherited.contains(*key) => (Some(redact(key, &value)), ConfigSource::Environment),
+                    Ok(value) => (Some(redact(key, &value)), ConfigSource::DotEnv),
+                    Err(_) => match default {
+                        Some(default) => (Some(redact(key, default)), ConfigSource::Default),
+                        None => (None, ConfigSource::Unset),
+                    },
+                };
+                ConfigEntry {
+                    key: key.to_string(),
+                    value,
+                    overrides_dotenv: in_dotenv && source == ConfigSource::Environment,
+                    source,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Hide secrets (same key names as log redaction) and URL passwords
+pub fn redact(key: &str, value: &str) -> String {
+    let lower = key.to_ascii_lowercase();
+    if SENSITIVE_KEYS.iter().any(|s| lower.contains(s)) {
+        return "[REDACTED]".to_string();
+    }
+    redact_url(value)
+}
+
+// ============================================================================
+// Self-Checks
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+}
+
+/// Result of one startup self-check
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SelfCheck {
+    #[schema(example = "clock_skew")]
+    pub name: String,
+    pub status: CheckStatus,
+    #[schema(example = "Database clock is 12 ms ahead")]
+    pub message: String,
+}
+
+impl SelfCheck {
+    fn ok(name: &str, message: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Ok,
+            message: message.into(),
+        }
+    }
+
+    fn warn(name: &str, message: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            status: CheckStatus::Warn,
+            message: message.into(),
+        }
+    }
+}
+
+/// Flag the built-in default, short secrets and secrets with little
+/// variety (Shannon estimate over the characters used)
+pub fn check_jwt_secret(secret: &str) -> SelfCheck {
+    const NAME: &str = "jwt_secret";
+    if secret == DEFAULT_JWT_SECRET {
+        return SelfCheck::warn(NAME, "JWT_SECRET is the built-in default; anyone can forge tokens");
+    }
+    if secret.len() < MIN_JWT_SECRET_LEN {
+        return SelfCheck::warn(
+            NAME,
+            format!("JWT_SECRET is {} bytes; use at least {}", secret.len(), MIN_JWT_SECRET_LEN),
+        );
+    }
+    let bits = estimated_entropy_bits(secret);
+    if bits < MIN_JWT_SECRET_BITS {
+        return SelfCheck::warn(
+            NAME,
+            format!("JWT_SECRET has about {:.0} bits of entropy; use a random value", bits),
+        );
+    }
+    SelfCheck::ok(NAME, format!("{} bytes, about {:.0} bits of entropy", secret.len(), bits))
+}
+
+fn estimated_entropy_bits(value: &str) -> f64 {
+    let mut counts = std::collections::HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_insert(0usize) += 1;
+    }
+    let len = value.chars().count() as f64;
+    let per_char: f64 = counts
+        .values()
+        .map(|&n| {
+            let p = n as f64 / len;
+            -p * p.log2()
+        })
+        .sum();
+    per_char * len
+}
+
+async fn check_migrations(diagnostics: &DatabaseDiagnostics) -> SelfCheck {
+    const NAME: &str = "migrations";
+    match diagnostics.migration_status().await {
+        Ok(status) if status.is_current() => SelfCheck::ok(NAME, format!("{} applied, none pending", status.applied)),
+        Ok(status) => {
+            let mut problems = Vec::new();
+            if !status.pending.is_empty() {
+                problems.push(format!("pending: {}", status.pending.join(", ")));
+            }
+            if !status.failed.is_empty() {
+                problems.push(format!("failed: {:?}", status.failed));
+            }
+            if !status.modified.is_empty() {
+                problems.push(format!("changed after being applied: {:?}", status.modified));
+            }
+            if !status.unknown.is_empty() {
+                problems.push(format!("applied but unknown to this build: {:?}", status.unknown));
+            }
+            SelfCheck::warn(NAME, problems.join("; "))
+        }
+        Err(e) => SelfCheck::warn(NAME, format!("Could not read migration status: {}", e)),
+    }
+}
+
+async fn check_clock_skew(diagnostics: &DatabaseDiagnostics) -> SelfCheck {
+    const NAME: &str = "clock_skew";
+    match diagnostics.clock_skew().await {
+        Ok(skew) => {
+            let ms = skew.num_milliseconds();
+            let message = format!(
+                "Database clock is {} ms {}",
+                ms.abs(),
+                if ms >= 0 { "ahead" } else { "behind" }
+            );
+            if ms.abs() > MAX_CLOCK_SKEW_MS {
+                SelfCheck::warn(NAME, format!("{}; token expiry and scheduled jobs will drift", message))
+            } else {
+                SelfCheck::ok(NAME, message)
+            }
+        }
+        Err(e) => SelfCheck::warn(NAME, format!("Could not read the database clock: {}", e)),
+    }
+}
+
+// ============================================================================
+// Startup Report
+// ============================================================================
+
+/// Effective configuration and self-check results, gathered once at boot
+pub struct StartupReport {
+    pub started_at: DateTime<Utc>,
+    pub dotenv_path: Option<PathBuf>,
+    /// The layered `shared::Config`, redacted
+    pub settings: serde_json::Value,
+    pub config: Vec<ConfigEntry>,
+    pub checks: Vec<SelfCheck>,
+}
+
+impl StartupReport {
+    pub async fn run(sources: &ConfigSources, settings: &Config, diagnostics: &DatabaseDiagnostics) -> Self {
+        Self {
+            started_at: Utc::now(),
+            dotenv_path: sources.dotenv_path.clone(),
+            settings: serde_json::to_value(settings.redacted()).unwrap_or_default(),
+            config: sources.report(),
+            checks: vec![
+                check_jwt_secret(&settings.jwt.secret),
+                check_migrations(diagnostics).await,
+                check_clock_skew(diagnostics).await,
+            ],
+        }
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &SelfCheck> {
+        self.checks.iter().filter(|check| check.status == CheckStatus::Warn)
+    }
+
+    /// One line for the configuration, one per failed check
+    pub fn log(&self) {
+        let describe = |source: ConfigSource| {
+            self.config
+                .iter()
+                .filter(|entry| entry.source == source)
+                .map(|entry| format!("{}={}", entry.key, entry.value.as_deref().unwrap_or("")))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let overriding: Vec<&str> = self
+            .config
+            .iter()
+            .filter(|entry| entry.overrides_dotenv)
+            .map(|entry| entry.key.as_str())
+            .collect();
+        tracing::info!(
+            dotenv = %self.dotenv_path.as_ref().map(|p| p.display().to_string()).unwrap_or_else(|| "none".to_string()),
+            environment = %describe(ConfigSource::Environment),
+            dotenv_values = %describe(ConfigSource::DotEnv),
+            defaults = %describe(ConfigSource::Default),
+            overriding_dotenv = %overriding.join(" "),
+            settings = %self.settings,
+            "⚙️  Effective configuration"
+        );
+        for check in self.warnings() {
+            tracing::warn!(check = %check.name, "Self-check: {}", check.message);
+        }
+    }
+}
+
+// ============================================================================
+// Lifecycle Events
+// ============================================================================
+
+/// Announce on the event bus that this instance accepts requests, after
+/// the migrations it applied (if any)
+pub fn publish_started(event_bus: &dyn EventBus, applied_migrations: Vec<i64>) {
+    if !applied_migrations.is_empty() {
+        event_bus.publish(DomainEvent::MigrationsApplied {
+            versions: applied_migrations,
+        });
+    }
+    event_bus.publish(DomainEvent::ServiceStarted {
+        service: crate::logging::service_name().to_string(),
+        region: crate::logging::region().to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    });
+}
+
+/// Resolves on Ctrl+C or SIGTERM, after publishing `service.shutting_down`.
+/// Pass to `with_graceful_shutdown`: in-flight requests still complete.
+pub async fn shutdown_signal(event_bus: Arc<dyn EventBus>) {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+
+    tracing::info!("🛑 Shutting down, draining in-flight requests");
+    event_bus.publish(DomainEvent::ShuttingDown {
+        service: crate::logging::service_name().to_string(),
+        region: crate::logging::region().to_string(),
+    });
+}
+
+// ============================================================================
+// Routes
+// ============================================================================
+
+/// `GET /health/info`; admins only, since warnings point at weaknesses
+pub fn health_info_routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/health/info", get(health_info))
+        .route_layer(axum_mw::from_fn_with_state(state.clone(), require_role("admin")))
+        .route_layer(axum_mw::from_fn_with_state(state, jwt_auth))
+}
+
+/// Prometheus text exposition format
+pub const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// `GET /metrics` in the Prometheus text format when `telemetry.backend` is
+/// `prometheus`; no route otherwise. Unauthenticated so scrapers need no token.
+pub fn metrics_routes(registry: Option<Arc<PrometheusTelemetry>>) -> Router<Arc<AppState>> {
+    match registry {
+        Some(registry) => Router::new().route(
+            "/metrics",
+            get(move || async move {
+                ([(header::CONTENT_TYPE, METRICS_CONTENT_TYPE)], registry.render())
+            }),
+        ),
+        None => Router::new(),
+    }
+}
+
+/// Build, configuration and self-check details
+#[derive(Serialize, ToSchema)]
+pub struct HealthInfoResponse {
+    /// `ok`, or `degraded` when a self-check warned
+    #[schema(example = "degraded")]
+    pub status: String,
+    #[schema(example = "0.1.0")]
+    pub version: String,
+    #[schema(example = "eu-west-1")]
+    pub region: String,
+    /// RFC 3339 boot time
+    #[schema(example = "2026-10-16T08:00:00Z")]
+    pub started_at: String,
+    #[schema(example = 3600)]
+    pub uptime_seconds: i64,
+    pub checks: Vec<SelfCheck>,
+    /// Layered settings (files, environment, command line), secrets redacted
+    #[schema(value_type = Object)]
+    pub settings: serde_json::Value,
+    /// Environment variables read at startup, secrets redacted
+    pub config: Vec<ConfigEntry>,
+}
+
+/// Startup self-checks and effective configuration
+#[utoipa::path(
+    get,
+    path = "/health/info",
+    tag = "Admin",
+    responses(
+        (status = 200, description = "Self-check results", body = HealthInfoResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse)
+    ),
+    security(("bearer_auth" = []))
+)]
+pub async fn health_info(State(state): State<Arc<AppState>>) -> Json<HealthInfoResponse> {
+    let report = &state.startup;
+    Json(HealthInfoResponse {
+        status: if report.warnings().next().is_some() { "degraded" } else { "ok" }.to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        region: crate::logging::region().to_string(),
+        started_at: report.started_at.to_rfc3339(),
+        uptime_seconds: (Utc::now() - report.started_at).num_seconds(),
+        checks: report.checks.clone(),
+        settings: report.settings.clone(),
+        config: report.config.clone(),
+    })
+}
@@ -0,0 +1,127 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    middleware as axum_mw,
+    routing::post,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use application::support::ContactRequest;
+
+use crate::auth::ValidatedJson;
+use crate::error::ApiError;
+use crate::middleware::{optional_jwt_auth, ClientIp, OptionalAuthUser};
+use crate::AppState;
+use crate::server_timing::Json;
+
+// ============================================================================
+// Request/Response DTOs with Validation
+// ============================================================================
+
+/// Contact form submission
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct ContactSupportRequest {
+    /// Sender name
+    #[validate(length(min = 1, max = 100, message = "must be 1-100 characters"))]
+    #[schema(example = "John Doe")]
+    pub name: String,
+    /// Reply-to address
+    #[validate(email(message = "must be a valid email"))]
+    #[schema(example = "john@example.com")]
+    pub email: String,
+    /// Subject line
+    #[validate(length(min = 3, max = 200, message = "must be 3-200 characters"))]
+    #[schema(example = "Cannot change my username")]
+    pub subject: String,
+    /// Message body
+    #[validate(length(min = 10, max = 5000, message = "must be 10-5000 characters"))]
+    #[schema(example = "Hi, when I try to change my username I get an error...")]
+    pub message: String,
+    /// Honeypot: leave empty. Hidden in real forms, filled in by bots.
+    #[serde(default)]
+    #[schema(example = "")]
+    pub website: String,
+}
+
+/// Accepted contact submission
+#[derive(Serialize, ToSchema)]
+pub struct ContactSupportResponse {
+    /// Ticket reference to quote in follow-ups
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub ticket_id: String,
+}
+
+// ============================================================================
+// Routes
+// ============================================================================
+
+/// Support routes; authentication is optional
+pub fn support_routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/contact", post(contact))
+        .route_layer(axum_mw::from_fn_with_state(state, optional_jwt_auth))
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Contact support
+#[utoipa::path(
+    post,
+    path = "/api/v1/support/contact",
+    tag = "Support",
+    request_body = ContactSupportRequest,
+    security((), ("bearer_auth" = [])),
+    responses(
+        (status = 202, description = "Message received", body = ContactSupportResponse),
+        (status = 401, description = "Invalid token", body = ErrorResponse),
+        (status = 422, description = "Invalid request fields, listed in `errors`", body = ErrorResponse),
+        (status = 429, description = "Too many messages, see Retry-After", body = ErrorResponse)
+    )
+)]
+pub async fn contact(
+    State(state): State<Arc<AppState>>,
+    OptionalAuthUser(claims): OptionalAuthUser,
+    ClientIp(client_ip): ClientIp,
+    ValidatedJson(payload): ValidatedJson<ContactSupportRequest>,
+) -> Result<(StatusCode, Json<ContactSupportResponse>), ApiError> {
+    // Bots that fill the honeypot get the same answer but nothing is stored
+    if !payload.website.is_empty() {
+        tracing::info!(client_ip = ?client_ip, "Dropped support message caught by honeypot");
+        return Ok((
+            StatusCode::ACCEPTED,
+            Json(ContactSupportResponse {
+                ticket_id: uuid::Uuid::new_v4().to_string(),
+            }),
+        ));
+    }
+
+    let user_id = claims
+        .map(|c| c.sub.parse::<uuid::Uuid>())
+        .transpose()
+        .map_err(|_| ApiError::internal("Invalid user ID in token"))?;
+
+    let ticket = state
+        .support_service
+        .contact(ContactRequest {
+            user_id,
+            client_ip,
+            name: payload.name,
+            email: payload.email,
+            subject: payload.subject,
+            message: payload.message,
+        })
+        .await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(ContactSupportResponse {
+            ticket_id: ticket.id.to_string(),
+        }),
+    ))
+}
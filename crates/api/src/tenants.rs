@@ -0,0 +1,125 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+use validator::ValidationError;
+
+use application::tenancy::{current_tenant, with_tenant, TenantDirectory};
+use domain::Tenant;
+use shared::TenancySettings;
+
+use crate::crud_entity;
+use crate::error::ApiError;
+
+// ============================================================================
+// Tenant Resolution
+// ============================================================================
+
+/// Names the tenant of a request by id or slug; takes precedence over the subdomain
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+/// Works out which tenant each request is for
+pub struct TenantResolver {
+    directory: Arc<TenantDirectory>,
+    /// Hosts below this domain name their tenant in the first label
+    base_domain: Option<String>,
+}
+
+impl TenantResolver {
+    /// With `tenancy.base_domain` (e.g. `example.com`; unset disables subdomains)
+    pub fn new(directory: Arc<TenantDirectory>, settings: &TenancySettings) -> Self {
+        Self {
+            directory,
+            base_domain: settings
+                .base_domain
+                .as_deref()
+                .map(|d| d.trim().trim_start_matches('.').to_ascii_lowercase())
+                .filter(|d| !d.is_empty()),
+        }
+    }
+
+    /// The tenant id or slug a request names, if any
+    fn requested(&self, headers: &HeaderMap) -> Option<String> {
+        if let Some(value) = headers.get(TENANT_HEADER).and_then(|h| h.to_str().ok()) {
+            return Some(value.to_string());
+        }
+        let host = headers.get(header::HOST).and_then(|h| h.to_str().ok())?;
+        subdomain(host, self.base_domain.as_deref()?).map(str::to_string)
+    }
+}
+
+/// Tenant label of `host` under `base_domain` (`acme.example.com` -> `acme`).
+/// The bare domain and `www` name no tenant.
+pub fn subdomain<'a>(host: &'a str, base_domain: &str) -> Option<&'a str> {
+    let host = host.split(':').next().unwrap_or(host);
+    let label = host
+        .len()
+        .checked_sub(base_domain.len())
+        .filter(|&at| host[at..].eq_ignore_ascii_case(base_domain))
+        .and_then(|at| host[..at].strip_suffix('.'))?;
+    (!label.is_empty() && !label.contains('.') && !label.eq_ignore_ascii_case("www")).then_some(label)
+}
+
+/// Run the request in the context of its tenant (the default tenant when it
+/// names none), so tenant-scoped repositories filter every query
+pub async fn resolve_tenant(
+    State(resolver): State<Arc<TenantResolver>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let tenant = match resolver.requested(request.headers()) {
+        Some(key) => resolver
+            .directory
+            .resolve(&key)
+            .await?
+            .ok_or_else(|| ApiError::not_found(format!("Unknown tenant '{}'", key)))?,
+        None => Tenant::DEFAULT_ID,
+    };
+    tracing::Span::current().record("tenant_id", tracing::field::display(tenant));
+    Ok(with_tenant(tenant, next.run(request)).await)
+}
+
+/// Only admins of the default tenant may manage tenants and other settings
+/// shared by every tenant
+pub async fn require_default_tenant(request: Request, next: Next) -> Result<Response, ApiError> {
+    if current_tenant().is_some_and(|tenant| tenant != Tenant::DEFAULT_ID) {
+        return Err(ApiError::forbidden("Only available to admins of the default tenant"));
+    }
+    Ok(next.run(request).await)
+}
+
+// ============================================================================
+// Tenant Administration
+// ============================================================================
+
+fn validate_slug(slug: &str) -> Result<(), ValidationError> {
+    let valid_chars = slug.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+    if !valid_chars || slug.starts_with('-') || slug.ends_with('-') {
+        return Err(ValidationError::new("slug")
+            .with_message("must be lowercase letters, digits and inner hyphens".into()));
+    }
+    Ok(())
+}
+
+crud_entity! {
+    pub mod admin for Tenant {
+        path: "/api/v1/admin/tenants",
+        item_path: "/api/v1/admin/tenants/{id}",
+        tag: "Admin",
+        create: CreateTenantRequest,
+        update: UpdateTenantRequest,
+        response: TenantResponse,
+        list: TenantsResponse,
+        fields {
+            /// Subdomain label, e.g. `acme` for `acme.example.com`
+            #[validate(length(min = 1, max = 63, message = "must be 1-63 characters"), custom(function = "validate_slug"))]
+            slug: String,
+            /// Display name
+            #[validate(length(min = 1, max = 200, message = "must be 1-200 characters"))]
+            name: String,
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use axum::{
+    extract::Request,
+    http::{header, uri::Authority, StatusCode, Uri},
+    response::{IntoResponse, Redirect, Response},
+    Router,
+};
+use rustls_pki_types::pem::PemObject;
+use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls;
+use tokio_rustls::TlsAcceptor;
+
+// ============================================================================
+// Certificates
+// ============================================================================
+
+/// Certificate and key for the HTTPS listener, swappable while serving.
+/// New connections use whatever was loaded last; open ones keep theirs.
+pub struct ReloadableTls {
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    http2: bool,
+    current: RwLock<Arc<rustls::ServerConfig>>,
+}
+
+impl ReloadableTls {
+    /// Read both PEM files; fails if either is missing, malformed or the
+    /// key does not match the certificate
+    pub fn load(cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> io::Result<Self> {
+        let (cert_path, key_path) = (cert_path.into(), key_path.into());
+        let config = server_config(&cert_path, &key_path, true)?;
+        Ok(Self {
+            cert_path,
+            key_path,
+            http2: true,
+            current: RwLock::new(Arc::new(config)),
+        })
+    }
+
+    /// Whether to offer `h2` during the handshake (`server.http2`)
+    pub fn with_http2(mut self, http2: bool) -> Self {
+        let mut config = (**self.current.get_mut().unwrap()).clone();
+        config.alpn_protocols = alpn_protocols(http2);
+        self.http2 = http2;
+        self.current = RwLock::new(Arc::new(config));
+        self
+    }
+
+    /// Re-read the files. On failure the previous certificate stays in use.
+    pub fn reload(&self) -> io::Result<()> {
+        let config = server_config(&self.cert_path, &self.key_path, self.http2)?;
+        *self.current.write().unwrap() = Arc::new(config);
+        Ok(())
+    }
+
+    pub(crate) fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(self.current.read().unwrap().clone())
+    }
+}
+
+fn alpn_protocols(http2: bool) -> Vec<Vec<u8>> {
+    if http2 {
+        vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+    } else {
+        vec![b"http/1.1".to_vec()]
+    }
+}
+
+fn server_config(cert_path: &Path, key_path: &Path, http2: bool) -> io::Result<rustls::ServerConfig> {
+    let invalid = |path: &Path, e: &dyn std::fmt::Display| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e))
+    };
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| invalid(cert_path, &e))?;
+    if certs.is_empty() {
+        return Err(invalid(cert_path, &"no certificate found"));
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path).map_err(|e| invalid(key_path, &e))?;
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut config = rustls::ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| invalid(cert_path, &e))?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| invalid(key_path, &e))?;
+    config.alpn_protocols = alpn_protocols(http2);
+    Ok(config)
+}
+
+/// Reload the certificate whenever the process receives SIGHUP, e.g. after
+/// a renewal. A bad file is logged and the old certificate kept.
+pub fn reload_on_sighup(tls: Arc<ReloadableTls>) {
+    #[cfg(unix)]
+    tokio::spawn(async move {
+        let mut hangup = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+            Ok(signal) => signal,
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGHUP: {}", e);
+                return;
+            }
+        };
+        while hangup.recv().await.is_some() {
+            match tls.reload() {
+                Ok(()) => tracing::info!(cert = %tls.cert_path.display(), "🔐 TLS certificate reloaded"),
+                Err(e) => tracing::error!("TLS certificate reload failed, keeping the previous one: {}", e),
+            }
+        }
+    });
+    #[cfg(not(unix))]
+    let _ = tls;
+}
+
+// ============================================================================
+// HTTP -> HTTPS Redirect
+// ============================================================================
+
+/// Plain-HTTP app sending every request to the same host and path over
+/// HTTPS on `https_port`, with a permanent (308) redirect
+pub fn https_redirect(https_port: u16) -> Router {
+    Router::new().fallback(move |request: Request| async move { redirect_to_https(&request, https_port) })
+}
+
+fn redirect_to_https(request: &Request, https_port: u16) -> Response {
+    let host = request
+        .headers()
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .and_then(|host| host.parse::<Authority>().ok());
+    let Some(host) = host else {
+        return (StatusCode::BAD_REQUEST, "Missing or invalid Host header").into_response();
+    };
+    let authority = match https_port {
+        443 => host.host().to_string(),
+        port => format!("{}:{}", host.host(), port),
+    };
+    let path = request.uri().path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    match Uri::builder().scheme("https").authority(authority).path_and_query(path).build() {
+        Ok(target) => Redirect::permanent(&target.to_string()).into_response(),
+        Err(_) => (StatusCode::BAD_REQUEST, "Invalid request target").into_response(),
+    }
+}
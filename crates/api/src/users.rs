@@ -0,0 +1,72 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+// ============================================================================
+// DTOs
+// ============================================================================
+
+/// User response object
+#[derive(Serialize, ToSchema)]
+pub struct UserResponse {
+    /// User UUID
+    #[schema(example = "550e8400-e29b-41d4-a716-446655440000")]
+    pub id: String,
+    /// Username
+    #[schema(example = "john_doe")]
+    pub username: String,
+    /// Email address
+    #[schema(example = "john@example.com")]
+    pub email: String,
+    /// Avatar image URL
+    #[schema(example = "/files/public/avatars/550e8400-e29b-41d4-a716-446655440000/3f2a.png")]
+    pub avatar_url: Option<String>,
+    /// An admin requires a password change (PUT /me/password)
+    pub password_reset_required: bool,
+    /// Incremented by every update
+    #[schema(example = 3)]
+    pub version: i64,
+}
+
+impl From<domain::User> for UserResponse {
+    fn from(user: domain::User) -> Self {
+        Self {
+            id: user.id.to_string(),
+            username: user.username.into(),
+            email: user.email.into(),
+            avatar_url: user.avatar_url,
+            password_reset_required: user.password_reset_required,
+            version: user.version,
+        }
+    }
+}
+
+impl From<domain::UserView> for UserResponse {
+    fn from(user: domain::UserView) -> Self {
+        Self {
+            id: user.id.to_string(),
+            username: user.username.into(),
+            email: user.email.into(),
+            avatar_url: user.avatar_url,
+            password_reset_required: user.password_reset_required,
+            version: user.version,
+        }
+    }
+}
+
+/// Users to look up at once
+#[derive(Deserialize, Validate, ToSchema)]
+pub struct BatchUsersRequest {
+    /// User UUIDs (1-100); duplicates are ignored
+    #[validate(length(min = 1, max = 100, message = "must be 1-100 ids"))]
+    pub ids: Vec<Uuid>,
+}
+
+/// Users found, keyed by id; ids without a user are left out
+#[derive(Serialize, ToSchema)]
+pub struct BatchUsersResponse {
+    pub users: BTreeMap<String, UserResponse>,
+}
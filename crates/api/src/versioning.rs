@@ -0,0 +1,164 @@
+use axum::{
+    extract::Request,
+    http::{header, HeaderValue, StatusCode, Uri},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::error::ApiError;
+
+// ============================================================================
+// API Version
+// ============================================================================
+
+/// Response header echoing the version that served the request
+pub const API_VERSION_HEADER: &str = "api-version";
+
+/// Vendor media type prefix, e.g. `Accept: application/vnd.rustbase.v2+json`
+const VENDOR_MEDIA_PREFIX: &str = "application/vnd.rustbase.";
+
+/// Unversioned paths that are routed to the negotiated version.
+/// Everything else (health, docs, websocket) lives outside `/api`.
+const VERSIONED_PREFIXES: &[&str] = &["/users", "/auth", "/me", "/admin", "/support", "/email"];
+
+/// The URI a legacy request was sent with, kept when `negotiate_version`
+/// rewrites it; request signatures cover this one
+#[derive(Debug, Clone)]
+pub struct RequestedUri(pub Uri);
+
+/// Public API version, resolved from the path or the `Accept` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApiVersion {
+    #[default]
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    pub const ALL: [ApiVersion; 2] = [ApiVersion::V1, ApiVersion::V2];
+
+    /// Path segment / media type label (`v1`, `v2`)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "v1",
+            ApiVersion::V2 => "v2",
+        }
+    }
+
+    /// Parse a `v1`-style label
+    pub fn parse(label: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.as_str() == label)
+    }
+
+    /// Version requested through a vendor media type in `Accept`.
+    /// `Ok(None)` when no vendor type is present; `Err` carries the unknown label.
+    pub fn from_accept(accept: &str) -> Result<Option<Self>, String> {
+        for range in accept.split(',') {
+            let media_type = range.split(';').next().unwrap_or_default().trim();
+            let Some(rest) = media_type.strip_prefix(VENDOR_MEDIA_PREFIX) else {
+                continue;
+            };
+            let label = rest.strip_suffix("+json").unwrap_or(rest);
+            return Self::parse(label).map(Some).ok_or_else(|| label.to_string());
+        }
+        Ok(None)
+    }
+}
+
+// ============================================================================
+// Version Negotiation Middleware
+// ============================================================================
+
+/// Resolve the API version and route unversioned requests to it.
+///
+/// `/api/v2/users` is served by v2 as-is. A legacy `/users` request is
+/// rewritten to `/api/{version}/users`, where the version comes from the
+/// `Accept` header and defaults to v1, so existing clients keep working.
+///
+/// This rewrites the URI, so it must wrap the whole `Router` rather than
+/// being added with `Router::layer` (which runs after routing).
+pub async fn negotiate_version(mut request: Request, next: Next) -> Result<Response, ApiError> {
+    let path = request.uri().path();
+
+    let version = if let Some(rest) = path.strip_prefix("/api/") {
+        let label = rest.split('/').next().unwrap_or_default();
+        ApiVersion::parse(label)
+    } else if is_versioned_path(path) {
+        let accept = request
+            .headers()
+            .get(header::ACCEPT)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        let version = ApiVersion::from_accept(accept)
+            .map_err(|label| {
+                ApiError::new(
+                    StatusCode::NOT_ACCEPTABLE,
+                    "UNSUPPORTED_API_VERSION",
+                    format!("API version '{}' is not supported", label),
+                )
+            })?
+            .unwrap_or_default();
+        let requested = RequestedUri(request.uri().clone());
+        *request.uri_mut() = versioned_uri(request.uri(), version);
+        request.extensions_mut().insert(requested);
+        Some(version)
+    } else {
+        None
+    };
+
+    let Some(version) = version else {
+        return Ok(next.run(request).await);
+    };
+
+    request.extensions_mut().insert(version);
+    let mut response = next.run(request).await;
+    response
+        .headers_mut()
+        .insert(API_VERSION_HEADER, HeaderValue::from_static(version.as_str()));
+    Ok(response)
+}
+
+fn is_versioned_path(path: &str) -> bool {
+    VERSIONED_PREFIXES.iter().any(|prefix| {
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    })
+}
+
+fn versioned_uri(uri: &Uri, version: ApiVersion) -> Uri {
+    let path_and_query = uri.path_and_query().map(|pq| pq.as_str()).unwrap_or("/");
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = format!("/api/{}{}", version.as_str(), path_and_query)
+        .parse()
+        .ok();
+    Uri::from_parts(parts).unwrap_or_else(|_| uri.clone())
+}
+
+// ============================================================================
+// ApiVersion Extractor
+// ============================================================================
+
+/// Handlers shared between versions can branch on `ApiVersion`.
+/// Falls back to v1 when the negotiation middleware is not mounted.
+impl<S> axum::extract::FromRequestParts<S> for ApiVersion
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    fn from_request_parts<'life0, 'life1, 'async_trait>(
+        parts: &'life0 mut axum::http::request::Parts,
+        _state: &'life1 S,
+    ) -> core::pin::Pin<
+        Box<dyn core::future::Future<Output = Result<Self, Self::Rejection>> + Send + 'async_trait>,
+    >
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(async move {
+            Ok(parts.extensions.get::<ApiVersion>().copied().unwrap_or_default())
+        })
+    }
+}
@@ -0,0 +1,108 @@
+use axum::{
+    http::header,
+    response::Redirect,
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use shared::{OpenIdSettings, SecurityTxtSettings, WellKnownSettings};
+
+use crate::server_timing::Json;
+
+// ============================================================================
+// Routes
+// ============================================================================
+
+/// `/.well-known/*` documents built once from `[well_known]`; each route is
+/// left out when its settings are missing, so it answers 404 like any
+/// unknown path
+pub fn well_known_routes<S: Clone + Send + Sync + 'static>(settings: &WellKnownSettings) -> Router<S> {
+    let mut router = Router::new();
+
+    if let Some(body) = security_txt(&settings.security_txt, Utc::now()) {
+        router = router.route(
+            "/.well-known/security.txt",
+            get(move || async move { ([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], body) }),
+        );
+    }
+    if let Some(url) = settings.change_password_url.clone() {
+        router = router.route("/.well-known/change-password", get(move || async move { Redirect::to(&url) }));
+    }
+    if let Some(document) = openid_configuration(&settings.openid) {
+        router = router.route("/.well-known/openid-configuration", get(move || async move { Json(document) }));
+    }
+    router
+}
+
+// ============================================================================
+// Documents
+// ============================================================================
+
+/// RFC 9116 security.txt, or `None` without a contact (the one required field)
+pub fn security_txt(settings: &SecurityTxtSettings, now: DateTime<Utc>) -> Option<String> {
+    if settings.contacts.is_empty() {
+        return None;
+    }
+    let expires = now + chrono::Duration::days(settings.expires_days.into());
+
+    let mut lines: Vec<String> = settings.contacts.iter().map(|c| format!("Contact: {}", c)).collect();
+    lines.push(format!("Expires: {}", expires.to_rfc3339_opts(SecondsFormat::Secs, true)));
+    let optional = [
+        ("Encryption", &settings.encryption),
+        ("Acknowledgments", &settings.acknowledgments),
+        ("Policy", &settings.policy),
+        ("Hiring", &settings.hiring),
+        ("Canonical", &settings.canonical),
+    ];
+    for (field, value) in optional {
+        if let Some(value) = value {
+            lines.push(format!("{}: {}", field, value));
+        }
+    }
+    if !settings.preferred_languages.is_empty() {
+        lines.push(format!("Preferred-Languages: {}", settings.preferred_languages.join(", ")));
+    }
+    lines.push(String::new());
+    Some(lines.join("\n"))
+}
+
+/// OpenID Provider metadata (OpenID Connect Discovery 1.0, section 3)
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenIdConfiguration {
+    pub issuer: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorization_endpoint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_endpoint: Option<String>,
+    pub userinfo_endpoint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jwks_uri: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub scopes_supported: Vec<String>,
+    pub response_types_supported: Vec<&'static str>,
+    pub subject_types_supported: Vec<&'static str>,
+    pub id_token_signing_alg_values_supported: Vec<&'static str>,
+}
+
+/// Discovery document for `settings`, or `None` without an issuer. The user
+/// info endpoint is this API's `/me`; tokens are signed with the shared
+/// JWT secret (HS256).
+pub fn openid_configuration(settings: &OpenIdSettings) -> Option<OpenIdConfiguration> {
+    let issuer = settings.issuer.clone()?;
+    Some(OpenIdConfiguration {
+        userinfo_endpoint: format!("{}/api/v1/me", issuer),
+        issuer,
+        authorization_endpoint: settings.authorization_endpoint.clone(),
+        token_endpoint: settings.token_endpoint.clone(),
+        jwks_uri: settings.jwks_uri.clone(),
+        scopes_supported: settings.scopes_supported.clone(),
+        response_types_supported: if settings.authorization_endpoint.is_some() {
+            vec!["code"]
+        } else {
+            Vec::new()
+        },
+        subject_types_supported: vec!["public"],
+        id_token_signing_alg_values_supported: vec!["HS256"],
+    })
+}
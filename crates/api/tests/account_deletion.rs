@@ -0,0 +1,162 @@
+//! Account deletion: scheduled with a grace period, cancellable, then anonymized by a job.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use application::account_deletion::{AccountDeletionService, AccountDeletionStore, EraseAccountJob, ScheduledDeletion};
+use application::jobs::Job;
+use application::storage::{AvatarService, FileStorage};
+use application::testing::{MockUserRepository, RecordingJobQueue};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, SubsecRound, Timelike, Utc};
+use domain::{DomainError, Repository, User, UserStatus};
+use infrastructure::LocalFileStorage;
+use uuid::Uuid;
+
+#[derive(Default)]
+struct MemoryDeletions {
+    pending: Mutex<HashMap<Uuid, ScheduledDeletion>>,
+}
+
+#[async_trait]
+impl AccountDeletionStore for MemoryDeletions {
+    /// Keeps microseconds only, like a `TIMESTAMPTZ` column
+    async fn schedule(&self, deletion: &ScheduledDeletion) -> Result<(), ApplicationError> {
+        let stored = ScheduledDeletion {
+            requested_at: deletion.requested_at.trunc_subsecs(6),
+            delete_after: deletion.delete_after.trunc_subsecs(6),
+            ..deletion.clone()
+        };
+        self.pending.lock().unwrap().insert(deletion.user_id, stored);
+        Ok(())
+    }
+
+    async fn find(&self, user_id: Uuid) -> Result<Option<ScheduledDeletion>, ApplicationError> {
+        Ok(self.pending.lock().unwrap().get(&user_id).cloned())
+    }
+
+    async fn remove(&self, user_id: Uuid) -> Result<bool, ApplicationError> {
+        Ok(self.pending.lock().unwrap().remove(&user_id).is_some())
+    }
+}
+
+struct Fixture {
+    users: Arc<MockUserRepository>,
+    storage: Arc<dyn FileStorage>,
+    store: Arc<MemoryDeletions>,
+    queue: Arc<RecordingJobQueue>,
+    deletions: AccountDeletionService,
+    job: EraseAccountJob,
+}
+
+fn fixture(users: Vec<User>) -> Fixture {
+    let root = std::env::temp_dir().join(format!("account-deletion-{}", Uuid::new_v4()));
+    let storage: Arc<dyn FileStorage> = Arc::new(LocalFileStorage::new(&root, "https://api.example.com/files", "secret"));
+    let users = Arc::new(MockUserRepository::with_users(users));
+    let store = Arc::new(MemoryDeletions::default());
+    let queue = Arc::new(RecordingJobQueue::new());
+    let avatars = Arc::new(AvatarService::new(users.clone(), storage.clone(), 1024 * 1024));
+    Fixture {
+        deletions: AccountDeletionService::new(store.clone(), queue.clone()).with_grace_period(Duration::from_secs(7 * 86_400)),
+        job: EraseAccountJob::new(store.clone(), users.clone(), avatars),
+        users,
+        storage,
+        store,
+        queue,
+    }
+}
+
+impl Fixture {
+    fn take_jobs(&self) -> Vec<(serde_json::Value, DateTime<Utc>)> {
+        self.queue.take(EraseAccountJob::KIND)
+    }
+}
+
+#[tokio::test]
+async fn deletion_is_scheduled_after_the_grace_period_and_can_be_cancelled() {
+    let alice = User::new("alice".parse().unwrap(), "alice@example.com".parse().unwrap(), "hash".into());
+    let f = fixture(vec![alice.clone()]);
+
+    let deletion = f.deletions.request(alice.id).await.unwrap();
+    let grace = deletion.delete_after - deletion.requested_at;
+    assert_eq!(grace, chrono::Duration::days(7));
+    // Asking again keeps the original deadline and queues nothing new
+    assert_eq!(f.deletions.request(alice.id).await.unwrap(), deletion);
+    let [(payload, run_at)]: [_; 1] = f.take_jobs().try_into().unwrap();
+    assert_eq!(run_at, deletion.delete_after);
+
+    f.deletions.cancel(alice.id).await.unwrap();
+    assert_eq!(f.deletions.pending(alice.id).await.unwrap(), None);
+    assert!(matches!(
+        f.deletions.cancel(alice.id).await,
+        Err(ApplicationError::Domain(DomainError::NotFound { .. }))
+    ));
+
+    // The job still runs at the deadline, and leaves the account alone
+    f.job.run(payload).await.unwrap();
+    assert_eq!(f.users.find_by_id(alice.id).await.unwrap().unwrap().email, "alice@example.com");
+}
+
+#[tokio::test]
+async fn the_job_anonymizes_the_account_at_the_deadline() {
+    let mut alice = User::new("alice".parse().unwrap(), "alice@example.com".parse().unwrap(), "hash".into());
+    let avatar = "public/avatars/alice/a.png";
+    alice.avatar_url = Some(format!("https://api.example.com/files/{}", avatar));
+    let bob = User::new("bob".parse().unwrap(), "bob@example.com".parse().unwrap(), "hash".into());
+    let f = fixture(vec![alice.clone(), bob.clone()]);
+    f.storage.put(avatar, b"png".to_vec(), "image/png").await.unwrap();
+
+    f.deletions.request(alice.id).await.unwrap();
+    let [(payload, _)]: [_; 1] = f.take_jobs().try_into().unwrap();
+    f.job.run(payload).await.unwrap();
+
+    let erased = f.users.find_by_id(alice.id).await.unwrap().unwrap();
+    assert!(erased.username.starts_with("deleted-"), "{}", erased.username);
+    assert!(erased.email.ends_with("@deleted.invalid"), "{}", erased.email);
+    assert!(!erased.email.contains("alice"));
+    assert_eq!(erased.password_hash, "");
+    assert_eq!(erased.avatar_url, None);
+    assert_eq!(erased.status, UserStatus::Suspended);
+    assert!(f.storage.get(avatar).await.unwrap().is_none());
+    assert!(f.store.pending.lock().unwrap().is_empty());
+
+    // Other accounts are untouched
+    assert_eq!(f.users.find_by_id(bob.id).await.unwrap().unwrap().email, bob.email);
+}
+
+#[tokio::test]
+async fn a_job_from_a_cancelled_request_does_not_erase_a_later_one_early() {
+    let alice = User::new("alice".parse().unwrap(), "alice@example.com".parse().unwrap(), "hash".into());
+    let f = fixture(vec![alice.clone()]);
+
+    f.deletions.request(alice.id).await.unwrap();
+    f.deletions.cancel(alice.id).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    let second = f.deletions.request(alice.id).await.unwrap();
+    let [(first_job, _), _]: [_; 2] = f.take_jobs().try_into().unwrap();
+
+    f.job.run(first_job).await.unwrap();
+    assert_eq!(f.users.find_by_id(alice.id).await.unwrap().unwrap().username, "alice");
+    assert_eq!(f.deletions.pending(alice.id).await.unwrap(), Some(second));
+}
+
+#[tokio::test]
+async fn deadlines_survive_a_store_that_keeps_microseconds() {
+    let alice = User::new("alice".parse().unwrap(), "alice@example.com".parse().unwrap(), "hash".into());
+    let f = fixture(vec![alice.clone()]);
+
+    let deletion = f.deletions.request(alice.id).await.unwrap();
+    assert_eq!(deletion.delete_after.nanosecond() % 1_000, 0);
+    assert_eq!(f.deletions.pending(alice.id).await.unwrap(), Some(deletion.clone()));
+
+    // Jobs queued before deadlines were truncated carry nanoseconds
+    let [(mut payload, _)]: [_; 1] = f.take_jobs().try_into().unwrap();
+    let nanos = deletion.delete_after + chrono::Duration::nanoseconds(789);
+    payload["delete_after"] = serde_json::to_value(nanos).unwrap();
+    f.job.run(payload).await.unwrap();
+
+    assert_eq!(f.users.find_by_id(alice.id).await.unwrap().unwrap().status, UserStatus::Suspended);
+    assert_eq!(f.deletions.pending(alice.id).await.unwrap(), None);
+}
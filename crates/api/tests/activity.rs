@@ -0,0 +1,159 @@
+//! User activity: sign-in history, debounced last-seen writes and forgetting a user's sign-ins.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use application::activity::{ActivityStore, LoginClient, LoginRecord, UserActivity, MAX_LOGINS_KEPT};
+use application::testing::{MockPasswordHasher, MockTokenService, MockUserRepository};
+use application::{ApplicationError, AuthService, AuthServiceImpl};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use infrastructure::InMemoryEventBus;
+use uuid::Uuid;
+
+#[derive(Default)]
+struct MemoryActivity {
+    logins: Mutex<Vec<LoginRecord>>,
+    seen: Mutex<HashMap<Uuid, DateTime<Utc>>>,
+    writes: Mutex<Vec<usize>>,
+    failing: AtomicBool,
+}
+
+#[async_trait]
+impl ActivityStore for MemoryActivity {
+    async fn record_login(&self, login: &LoginRecord, keep: u32) -> Result<(), ApplicationError> {
+        let mut logins = self.logins.lock().unwrap();
+        logins.insert(0, login.clone());
+        logins.truncate(keep as usize);
+        self.seen.lock().unwrap().insert(login.user_id, login.created_at);
+        Ok(())
+    }
+
+    async fn record_seen(&self, seen: &[(Uuid, DateTime<Utc>)]) -> Result<(), ApplicationError> {
+        if self.failing.load(Ordering::SeqCst) {
+            return Err(DomainError::unavailable("connection lost").into());
+        }
+        self.writes.lock().unwrap().push(seen.len());
+        let mut last = self.seen.lock().unwrap();
+        for (user_id, at) in seen {
+            let entry = last.entry(*user_id).or_insert(*at);
+            *entry = (*entry).max(*at);
+        }
+        Ok(())
+    }
+
+    async fn recent_logins(&self, user_id: Uuid, limit: u32) -> Result<Vec<LoginRecord>, ApplicationError> {
+        let logins = self.logins.lock().unwrap();
+        Ok(logins.iter().filter(|l| l.user_id == user_id).take(limit as usize).cloned().collect())
+    }
+
+    async fn forget(&self, user_id: Uuid) -> Result<u64, ApplicationError> {
+        let mut logins = self.logins.lock().unwrap();
+        let before = logins.len();
+        logins.retain(|l| l.user_id != user_id);
+        Ok((before - logins.len()) as u64)
+    }
+}
+
+fn minutes(n: i64) -> chrono::Duration {
+    chrono::Duration::minutes(n)
+}
+
+#[tokio::test]
+async fn successful_sign_ins_are_recorded_with_their_client() {
+    let store = Arc::new(MemoryActivity::default());
+    let activity = Arc::new(UserActivity::new(store.clone()));
+    let users = Arc::new(MockUserRepository::new());
+    let auth = AuthServiceImpl::new(
+        users.clone(),
+        Arc::new(MockPasswordHasher::new()),
+        Arc::new(MockTokenService::new()),
+        Arc::new(InMemoryEventBus::default()),
+    )
+    .with_activity(activity.clone());
+    let alice = auth
+        .register("alice".into(), "alice@example.com".into(), "Correct-Horse-7".into())
+        .await
+        .unwrap();
+
+    let client = LoginClient {
+        ip: Some("203.0.113.7".into()),
+        user_agent: Some(format!("  Mozilla/5.0 {}", "x".repeat(600))),
+    };
+    auth.login("alice@example.com".into(), "Correct-Horse-7".into(), client).await.unwrap();
+    assert!(auth.login("alice@example.com".into(), "wrong".into(), LoginClient::default()).await.is_err());
+    auth.login("alice@example.com".into(), "Correct-Horse-7".into(), LoginClient::default()).await.unwrap();
+
+    let logins = activity.recent_logins(alice.id, 20).await.unwrap();
+    assert_eq!(logins.len(), 2);
+    assert_eq!((logins[0].ip.as_deref(), logins[0].user_agent.as_deref()), (None, None));
+    assert_eq!(logins[1].ip.as_deref(), Some("203.0.113.7"));
+    let user_agent = logins[1].user_agent.as_deref().unwrap();
+    assert!(user_agent.starts_with("Mozilla/5.0") && user_agent.chars().count() == 512);
+    assert!(store.seen.lock().unwrap().contains_key(&alice.id));
+
+    assert_eq!(activity.forget(alice.id).await.unwrap(), 2);
+    assert!(activity.recent_logins(alice.id, 20).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn history_is_capped_per_user() {
+    let store = Arc::new(MemoryActivity::default());
+    let activity = UserActivity::new(store.clone());
+    let user = Uuid::new_v4();
+    let start = Utc::now();
+    for i in 0..MAX_LOGINS_KEPT + 5 {
+        activity.record_login(user, &LoginClient::default(), start + minutes(i64::from(i))).await.unwrap();
+    }
+
+    let logins = activity.recent_logins(user, 1000).await.unwrap();
+    assert_eq!(logins.len(), MAX_LOGINS_KEPT as usize);
+    assert_eq!(logins[0].created_at, start + minutes(i64::from(MAX_LOGINS_KEPT + 4)));
+    assert_eq!(activity.recent_logins(user, 3).await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn requests_are_written_once_per_interval_in_batches() {
+    let store = Arc::new(MemoryActivity::default());
+    let activity = UserActivity::new(store.clone()).with_debounce(Duration::from_secs(300));
+    let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+    let now = Utc::now();
+
+    for at in [now, now + minutes(1), now + minutes(4)] {
+        activity.seen(alice, at);
+    }
+    activity.seen(bob, now);
+    assert_eq!(activity.flush().await.unwrap(), 2);
+    assert_eq!(activity.flush().await.unwrap(), 0);
+    assert_eq!(store.seen.lock().unwrap()[&alice], now);
+
+    activity.seen(alice, now + minutes(5));
+    assert_eq!(activity.flush().await.unwrap(), 1);
+    assert_eq!(store.seen.lock().unwrap()[&alice], now + minutes(5));
+    assert_eq!(*store.writes.lock().unwrap(), [2, 1]);
+
+    // A sign-in writes last_seen_at itself, so requests right after it don't
+    activity.record_login(bob, &LoginClient::default(), now + minutes(6)).await.unwrap();
+    activity.seen(bob, now + minutes(7));
+    assert_eq!(activity.flush().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn marks_survive_a_failed_flush() {
+    let store = Arc::new(MemoryActivity::default());
+    let activity = UserActivity::new(store.clone());
+    let user = Uuid::new_v4();
+    let now = Utc::now();
+
+    activity.seen(user, now);
+    store.failing.store(true, Ordering::SeqCst);
+    assert!(activity.flush().await.is_err());
+    assert!(store.seen.lock().unwrap().is_empty());
+
+    store.failing.store(false, Ordering::SeqCst);
+    assert_eq!(activity.flush().await.unwrap(), 1);
+    assert_eq!(store.seen.lock().unwrap()[&user], now);
+}
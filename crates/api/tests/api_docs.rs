@@ -0,0 +1,85 @@
+//! Per-audience OpenAPI documents: operations, tags and schemas filtered by route tag.
+
+use api::api_docs::{audience_doc, DocAudience};
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+#[derive(Serialize, ToSchema)]
+struct Profile {
+    name: String,
+}
+
+#[derive(Serialize, ToSchema)]
+struct Job {
+    kind: String,
+}
+
+#[derive(Serialize, ToSchema)]
+struct Jobs {
+    jobs: Vec<Job>,
+}
+
+#[utoipa::path(get, path = "/me", tag = "Users", responses((status = 200, body = Profile)))]
+#[allow(dead_code)]
+async fn me() {}
+
+#[utoipa::path(post, path = "/email/ses", tag = "Email", responses((status = 204)))]
+#[allow(dead_code)]
+async fn ses() {}
+
+#[utoipa::path(get, path = "/admin/jobs", tag = "Admin", responses((status = 200, body = Jobs)))]
+#[allow(dead_code)]
+async fn jobs() {}
+
+#[utoipa::path(delete, path = "/me", tag = "Admin", responses((status = 204)))]
+#[allow(dead_code)]
+async fn delete_me() {}
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(me, ses, jobs, delete_me),
+    components(schemas(Profile, Job, Jobs)),
+    tags((name = "Users"), (name = "Email"), (name = "Admin"))
+)]
+struct Doc;
+
+fn paths(audience: DocAudience) -> Vec<String> {
+    let doc = audience_doc(&Doc::openapi(), audience);
+    let doc = serde_json::to_value(doc).unwrap();
+    let mut paths: Vec<String> = doc["paths"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .flat_map(|(path, item)| item.as_object().unwrap().keys().map(move |method| format!("{} {}", method, path)))
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn each_audience_sees_its_own_and_less_restricted_routes() {
+    assert_eq!(paths(DocAudience::Public), ["get /me"]);
+    assert_eq!(paths(DocAudience::Partner), ["get /me", "post /email/ses"]);
+    assert_eq!(paths(DocAudience::Internal), ["delete /me", "get /admin/jobs", "get /me", "post /email/ses"]);
+    assert_eq!(DocAudience::Partner.doc_url(), "/api-docs/partner/openapi.json");
+}
+
+#[test]
+fn hidden_routes_take_their_tags_and_schemas_with_them() {
+    let public = audience_doc(&Doc::openapi(), DocAudience::Public);
+    let tags: Vec<_> = public.tags.unwrap().into_iter().map(|tag| tag.name).collect();
+    assert_eq!(tags, ["Users"]);
+    let schemas: Vec<_> = public.components.unwrap().schemas.into_keys().collect();
+    assert_eq!(schemas, ["Profile"]);
+
+    // Schemas only reachable through another schema are kept
+    let internal = audience_doc(&Doc::openapi(), DocAudience::Internal);
+    let schemas: Vec<_> = internal.components.unwrap().schemas.into_keys().collect();
+    assert_eq!(schemas, ["Job", "Jobs", "Profile"]);
+}
+
+#[test]
+fn unknown_tags_are_internal() {
+    assert_eq!(DocAudience::of_tag("Billing"), DocAudience::Internal);
+    assert_eq!(DocAudience::of_tag("Users"), DocAudience::Public);
+}
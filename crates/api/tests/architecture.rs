@@ -0,0 +1,153 @@
+//! Clean Architecture boundary checks.
+//!
+//! Dependencies must point inwards: api/grpc -> infrastructure -> application -> domain.
+//! The HTTP client and the protobuf contracts stand alone so consumers do not pull in the server.
+//! These tests read every crate's `Cargo.toml` and scan its sources so that
+//! a stray dependency or `use` fails CI instead of silently eroding the layers.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Workspace-internal crates
+const INTERNAL: &[&str] = &["domain", "application", "infrastructure", "api", "grpc", "shared", "client", "proto"];
+
+struct Rule {
+    krate: &'static str,
+    /// Internal crates this crate may depend on
+    allowed_internal: &'static [&'static str],
+    /// External crates that must never appear in dependencies or source
+    forbidden_external: &'static [&'static str],
+}
+
+const RULES: &[Rule] = &[
+    Rule {
+        krate: "domain",
+        allowed_internal: &[],
+        forbidden_external: &["sqlx", "axum", "tower", "tower-http", "jsonwebtoken", "argon2", "lapin", "rdkafka"],
+    },
+    Rule {
+        krate: "application",
+        allowed_internal: &["domain", "shared"],
+        forbidden_external: &["sqlx", "axum", "tower", "tower-http", "lapin", "rdkafka"],
+    },
+    Rule {
+        krate: "infrastructure",
+        allowed_internal: &["domain", "application", "shared", "proto"],
+        forbidden_external: &["axum", "tower-http"],
+    },
+    Rule {
+        krate: "grpc",
+        allowed_internal: &["domain", "application", "shared", "proto"],
+        forbidden_external: &["sqlx", "axum"],
+    },
+    Rule {
+        krate: "api",
+        allowed_internal: &["domain", "application", "infrastructure", "grpc", "shared"],
+        forbidden_external: &["sqlx", "lapin", "rdkafka"],
+    },
+    Rule {
+        krate: "client",
+        allowed_internal: &[],
+        forbidden_external: &["sqlx", "axum", "tower", "tower-http", "utoipa"],
+    },
+    Rule {
+        krate: "proto",
+        allowed_internal: &[],
+        forbidden_external: &["sqlx", "axum", "tower-http", "utoipa"],
+    },
+];
+
+fn crates_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().to_path_buf()
+}
+
+/// Normal (non-dev) dependency names declared in a crate's manifest
+fn dependencies(krate: &str) -> Vec<String> {
+    let manifest = fs::read_to_string(crates_dir().join(krate).join("Cargo.toml")).unwrap();
+    let manifest: toml::Value = manifest.parse().unwrap();
+    manifest
+        .get("dependencies")
+        .and_then(|d| d.as_table())
+        .map(|deps| deps.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn rust_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            rust_files(&path, out);
+        } else if path.extension().is_some_and(|e| e == "rs") {
+            out.push(path);
+        }
+    }
+}
+
+/// `file:line` locations where `name::` is referenced in non-comment code
+fn source_references(krate: &str, name: &str) -> Vec<String> {
+    let ident = name.replace('-', "_");
+    let needle = format!("{}::", ident);
+    let mut files = Vec::new();
+    rust_files(&crates_dir().join(krate).join("src"), &mut files);
+
+    let mut hits = Vec::new();
+    for file in files {
+        let source = fs::read_to_string(&file).unwrap();
+        for (i, line) in source.lines().enumerate() {
+            let code = line.split("//").next().unwrap_or_default();
+            let referenced = code.match_indices(&needle).any(|(pos, _)| {
+                // Skip matches that are the tail of a longer path segment (e.g. `my_sqlx::`)
+                code[..pos]
+                    .chars()
+                    .next_back()
+                    .is_none_or(|c| !(c.is_alphanumeric() || c == '_'))
+            });
+            if referenced {
+                hits.push(format!("{}:{}", file.display(), i + 1));
+            }
+        }
+    }
+    hits
+}
+
+#[test]
+fn internal_dependencies_point_inwards() {
+    let mut violations = Vec::new();
+    for rule in RULES {
+        for dep in dependencies(rule.krate) {
+            if INTERNAL.contains(&dep.as_str()) && !rule.allowed_internal.contains(&dep.as_str()) {
+                violations.push(format!("{} must not depend on {}", rule.krate, dep));
+            }
+        }
+    }
+    assert!(violations.is_empty(), "layering violations:\n{}", violations.join("\n"));
+}
+
+#[test]
+fn forbidden_external_crates_are_not_dependencies() {
+    let mut violations = Vec::new();
+    for rule in RULES {
+        for dep in dependencies(rule.krate) {
+            if rule.forbidden_external.contains(&dep.as_str()) {
+                violations.push(format!("{} must not depend on {}", rule.krate, dep));
+            }
+        }
+    }
+    assert!(violations.is_empty(), "layering violations:\n{}", violations.join("\n"));
+}
+
+#[test]
+fn forbidden_crates_are_not_referenced_in_source() {
+    let mut violations = Vec::new();
+    for rule in RULES {
+        let outer_layers = INTERNAL
+            .iter()
+            .filter(|c| **c != rule.krate && !rule.allowed_internal.contains(c));
+        for name in rule.forbidden_external.iter().chain(outer_layers) {
+            for location in source_references(rule.krate, name) {
+                violations.push(format!("{} references {} at {}", rule.krate, name, location));
+            }
+        }
+    }
+    assert!(violations.is_empty(), "layering violations:\n{}", violations.join("\n"));
+}
@@ -0,0 +1,95 @@
+//! Caching of authorization lookups within and across requests.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use application::authz::{with_request_cache, AuthorizationService};
+use async_trait::async_trait;
+use domain::{Authorization, Claims, DomainError, RoleRepository};
+use uuid::Uuid;
+
+/// Roles in memory, counting lookups
+#[derive(Default)]
+struct CountingRoles {
+    grants: Mutex<HashMap<Uuid, BTreeSet<String>>>,
+    lookups: AtomicUsize,
+}
+
+#[async_trait]
+impl RoleRepository for CountingRoles {
+    async fn authorization(&self, user_id: Uuid, extra_roles: &[String]) -> Result<Authorization, DomainError> {
+        self.lookups.fetch_add(1, Ordering::SeqCst);
+        let mut roles = self.grants.lock().unwrap().get(&user_id).cloned().unwrap_or_default();
+        roles.extend(extra_roles.iter().cloned());
+        let permissions = if roles.contains("admin") {
+            BTreeSet::from(["users:delete".to_string()])
+        } else {
+            BTreeSet::new()
+        };
+        Ok(Authorization { roles, permissions })
+    }
+
+    async fn grant(&self, user_id: Uuid, role: &str) -> Result<bool, DomainError> {
+        Ok(self.grants.lock().unwrap().entry(user_id).or_default().insert(role.to_string()))
+    }
+
+    async fn revoke(&self, user_id: Uuid, role: &str) -> Result<bool, DomainError> {
+        Ok(self.grants.lock().unwrap().entry(user_id).or_default().remove(role))
+    }
+}
+
+fn claims(user: Uuid) -> Claims {
+    Claims {
+        sub: user.to_string(),
+        email: "test@example.com".to_string(),
+        roles: vec!["user".to_string()],
+        exp: 0,
+        iat: 0,
+        tenant_id: None,
+        token_version: 0,
+        act: None,
+    }
+}
+
+#[tokio::test]
+async fn a_request_looks_roles_up_once() {
+    let roles = Arc::new(CountingRoles::default());
+    // No reuse across requests, so only the request cache can help
+    let authz = AuthorizationService::new(roles.clone()).with_ttl(Duration::ZERO);
+    let claims = claims(Uuid::new_v4());
+
+    with_request_cache(async {
+        assert!(authz.has_role(&claims, "user").await.unwrap());
+        assert!(!authz.has_role(&claims, "admin").await.unwrap());
+        assert!(!authz.has_permission(&claims, "users:delete").await.unwrap());
+    })
+    .await;
+    assert_eq!(roles.lookups.load(Ordering::SeqCst), 1);
+
+    with_request_cache(authz.authorization(&claims)).await.unwrap();
+    assert_eq!(roles.lookups.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn role_changes_invalidate_cached_decisions() {
+    let roles = Arc::new(CountingRoles::default());
+    let authz = AuthorizationService::new(roles.clone());
+    let user = Uuid::new_v4();
+    let claims = claims(user);
+
+    assert!(!authz.has_permission(&claims, "users:delete").await.unwrap());
+    assert!(!authz.has_permission(&claims, "users:delete").await.unwrap());
+    assert_eq!(roles.lookups.load(Ordering::SeqCst), 1);
+
+    assert!(authz.grant_role(user, " Admin ").await.unwrap());
+    assert!(authz.has_role(&claims, "admin").await.unwrap());
+    assert!(authz.has_permission(&claims, "users:delete").await.unwrap());
+    assert_eq!(roles.lookups.load(Ordering::SeqCst), 2);
+
+    authz.revoke_role(user, "admin").await.unwrap();
+    assert!(!authz.has_role(&claims, "admin").await.unwrap());
+    assert!(authz.revoke_role(user, "admin").await.is_err());
+    assert!(authz.grant_role(user, "not a role").await.is_err());
+}
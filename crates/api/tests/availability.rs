@@ -0,0 +1,108 @@
+//! Sign-up availability: username and email checks, suggestions, and the per-client limit.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use application::availability::{suggestion_candidates, AvailabilityLimits, AvailabilityService, MAX_SUGGESTIONS};
+use application::testing::MockUserRepository;
+use application::ApplicationError;
+use domain::{DomainError, User, Username};
+use infrastructure::InMemoryRateLimiter;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+fn user(name: &str) -> User {
+    User::new(name.parse().unwrap(), format!("{}@example.com", name).parse().unwrap(), String::new())
+}
+
+fn service(users: impl IntoIterator<Item = User>) -> AvailabilityService {
+    AvailabilityService::new(
+        Arc::new(MockUserRepository::with_users(users)),
+        Arc::new(InMemoryRateLimiter::new()),
+    )
+}
+
+#[tokio::test]
+async fn taken_usernames_come_with_free_suggestions() {
+    let availability = service([user("john_doe"), user("john_doe1")]);
+
+    let taken = availability.check(Some("10.0.0.1"), Some(" john_doe "), None).await.unwrap();
+    let username = taken.username.unwrap();
+    assert_eq!((username.username.as_str(), username.available), ("john_doe", false));
+    assert_eq!(username.suggestions.len(), MAX_SUGGESTIONS);
+    assert_eq!(username.suggestions[..2], ["john_doe2", "john_doe3"]);
+    assert!(username.suggestions.iter().all(|s| s.starts_with("john_doe") && s != "john_doe1"));
+    assert_eq!(taken.email, None);
+
+    // Case is part of a username
+    let free = availability.check(Some("10.0.0.1"), Some("John_Doe"), None).await.unwrap();
+    let username = free.username.unwrap();
+    assert!(username.available);
+    assert!(username.suggestions.is_empty());
+}
+
+#[tokio::test]
+async fn emails_are_checked_by_their_normalized_form() {
+    let availability = service([user("alice")]);
+
+    let result = availability
+        .check(Some("10.0.0.1"), None, Some(" Alice@Example.com"))
+        .await
+        .unwrap();
+    assert_eq!((result.username, result.email), (None, Some(false)));
+
+    let result = availability
+        .check(Some("10.0.0.1"), Some("bob"), Some("bob@example.com"))
+        .await
+        .unwrap();
+    assert_eq!((result.username.map(|u| u.available), result.email), (Some(true), Some(true)));
+}
+
+#[tokio::test]
+async fn malformed_or_missing_values_are_rejected() {
+    let availability = service([]);
+    assert!(matches!(availability.check(None, None, None).await, Err(ApplicationError::UseCase(_))));
+    assert!(matches!(
+        availability.check(None, Some("ab"), None).await,
+        Err(ApplicationError::Domain(DomainError::Validation(_)))
+    ));
+    assert!(matches!(
+        availability.check(None, None, Some("not-an-email")).await,
+        Err(ApplicationError::Domain(DomainError::Validation(_)))
+    ));
+}
+
+#[tokio::test]
+async fn checks_are_limited_per_client() {
+    let availability = service([]).with_limits(AvailabilityLimits {
+        per_client: 2,
+        window: Duration::from_secs(60),
+    });
+
+    for _ in 0..2 {
+        availability.check(Some("10.0.0.1"), Some("carol"), None).await.unwrap();
+    }
+    assert!(matches!(
+        availability.check(Some("10.0.0.1"), Some("carol"), None).await,
+        Err(ApplicationError::RateLimited { .. })
+    ));
+    // Malformed input is refused before it counts
+    assert!(matches!(
+        availability.check(Some("10.0.0.2"), Some("x"), None).await,
+        Err(ApplicationError::Domain(_))
+    ));
+    assert!(availability.check(Some("10.0.0.2"), Some("carol"), None).await.is_ok());
+}
+
+#[test]
+fn suggestions_stay_valid_usernames() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let long: Username = "x".repeat(Username::MAX_LEN).parse().unwrap();
+    let candidates = suggestion_candidates(&long, &mut rng);
+    assert!(!candidates.is_empty());
+    assert!(candidates.iter().all(|c| c.chars().count() <= Username::MAX_LEN && c.parse::<Username>().is_ok()));
+    assert!(candidates.iter().all(|c| c != long.as_str()));
+
+    let spaced: Username = "ada lovelace".parse().unwrap();
+    assert_eq!(suggestion_candidates(&spaced, &mut rng)[0], "ada lovelace1");
+}
@@ -0,0 +1,49 @@
+//! Avatar re-encoding, resizing and variant keys.
+
+use application::storage::{avatar_variant_key, ImageProcessor};
+use application::ApplicationError;
+use domain::DomainError;
+use infrastructure::NativeImageProcessor;
+
+/// A 2x2 red PNG carrying an `eXIf` chunk with the text `GPS-SECRET`
+const PNG_WITH_EXIF: &[u8] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52, 0x00, 0x00, 0x00,
+    0x02, 0x00, 0x00, 0x00, 0x02, 0x08, 0x02, 0x00, 0x00, 0x00, 0xfd, 0xd4, 0x9a, 0x73, 0x00, 0x00, 0x00, 0x12, 0x65,
+    0x58, 0x49, 0x66, 0x4d, 0x4d, 0x00, 0x2a, 0x00, 0x00, 0x00, 0x08, 0x47, 0x50, 0x53, 0x2d, 0x53, 0x45, 0x43, 0x52,
+    0x45, 0x54, 0x35, 0x2a, 0xd9, 0x34, 0x00, 0x00, 0x00, 0x10, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0xf8, 0xcf,
+    0xc0, 0x00, 0x44, 0x0c, 0x10, 0x0a, 0x00, 0x1f, 0xee, 0x03, 0xfd, 0x8b, 0x5f, 0x14, 0xd4, 0x00, 0x00, 0x00, 0x00,
+    0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+];
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|window| window == needle)
+}
+
+#[test]
+fn metadata_is_stripped_and_variants_are_generated() {
+    assert!(contains(PNG_WITH_EXIF, b"GPS-SECRET"));
+    let processed = NativeImageProcessor::new().process(PNG_WITH_EXIF, &[16, 32]).unwrap();
+
+    assert!(processed.original.starts_with(b"\x89PNG"));
+    assert!(!contains(&processed.original, b"GPS-SECRET"));
+    assert_eq!(processed.variants.len(), 2);
+    for variant in &processed.variants {
+        assert!(variant.starts_with(b"\x89PNG"));
+        assert!(!contains(variant, b"GPS-SECRET"));
+    }
+}
+
+#[test]
+fn undecodable_and_oversized_images_are_invalid() {
+    let result = NativeImageProcessor::new().process(b"\x89PNG\r\n\x1a\nnot really", &[64]);
+    assert!(matches!(result, Err(ApplicationError::Domain(DomainError::Validation(_)))));
+
+    let result = NativeImageProcessor::new().with_max_dimension(1).process(PNG_WITH_EXIF, &[64]);
+    assert!(matches!(result, Err(ApplicationError::Domain(DomainError::Validation(_)))));
+}
+
+#[test]
+fn variant_keys_keep_the_extension() {
+    assert_eq!(avatar_variant_key("public/avatars/u/abc.png", 128), "public/avatars/u/abc_128.png");
+    assert_eq!(avatar_variant_key("public/avatars/u.v/abc", 64), "public/avatars/u.v/abc_64");
+}
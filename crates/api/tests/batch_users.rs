@@ -0,0 +1,58 @@
+//! Batch user lookup: one query for many ids, missing and other tenants' users left out.
+
+use std::sync::Arc;
+
+use application::tenancy::{with_tenant, TenantScopedUserRepository};
+use application::testing::MockUserRepository;
+use application::{ApplicationError, UserService, UserServiceImpl, MAX_BATCH_USERS};
+use domain::{DomainError, User};
+use uuid::Uuid;
+
+fn user(name: &str, tenant_id: Uuid) -> User {
+    User {
+        tenant_id,
+        ..User::new(name.parse().unwrap(), format!("{}@example.com", name).parse().unwrap(), String::new())
+    }
+}
+
+fn sorted_names(users: &[User]) -> Vec<&str> {
+    let mut names: Vec<&str> = users.iter().map(|u| u.username.as_str()).collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn existing_users_are_returned_once() {
+    let tenant = Uuid::new_v4();
+    let (alice, bob, carol) = (user("alice", tenant), user("bob", tenant), user("carol", tenant));
+    let service = UserServiceImpl::new(Arc::new(MockUserRepository::with_users([alice.clone(), bob.clone(), carol])));
+
+    let found = service.get_users(&[bob.id, Uuid::new_v4(), alice.id, bob.id]).await.unwrap();
+    assert_eq!(sorted_names(&found), ["alice", "bob"]);
+    assert!(service.get_users(&[]).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn batches_are_bounded() {
+    let service = UserServiceImpl::new(Arc::new(MockUserRepository::new()));
+    let too_many: Vec<Uuid> = (0..=MAX_BATCH_USERS).map(|_| Uuid::new_v4()).collect();
+    assert!(matches!(
+        service.get_users(&too_many).await,
+        Err(ApplicationError::Domain(DomainError::Validation(_)))
+    ));
+    // Duplicates count once
+    let repeated = vec![Uuid::new_v4(); MAX_BATCH_USERS + 1];
+    assert!(service.get_users(&repeated).await.is_ok());
+}
+
+#[tokio::test]
+async fn other_tenants_users_are_left_out() {
+    let (acme, globex) = (Uuid::new_v4(), Uuid::new_v4());
+    let (alice, bob) = (user("alice", acme), user("bob", globex));
+    let repository = TenantScopedUserRepository::new(Arc::new(MockUserRepository::with_users([alice.clone(), bob.clone()])));
+    let service = UserServiceImpl::new(Arc::new(repository));
+
+    let found = with_tenant(acme, service.get_users(&[alice.id, bob.id])).await.unwrap();
+    assert_eq!(sorted_names(&found), ["alice"]);
+    assert_eq!(service.get_users(&[alice.id, bob.id]).await.unwrap().len(), 2);
+}
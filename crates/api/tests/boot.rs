@@ -0,0 +1,79 @@
+//! Startup phases: ordering, failure diagnostics and partial teardown.
+
+use std::sync::{Arc, Mutex};
+
+use api::boot::{Boot, BootPhase};
+
+/// Teardown steps that record their name when they run
+fn record(boot: &mut Boot, log: &Arc<Mutex<Vec<&'static str>>>, name: &'static str) {
+    let log = log.clone();
+    boot.on_failure(name, move || async move { log.lock().unwrap().push(name) });
+}
+
+#[tokio::test]
+async fn a_failing_phase_tears_down_earlier_phases_newest_first() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut boot = Boot::new();
+
+    boot.run(BootPhase::Config, async { Ok(()) }).await.unwrap();
+    let pools = boot.run(BootPhase::Database, async { Ok(2) }).await.unwrap();
+    assert_eq!(pools, 2);
+    record(&mut boot, &log, "close database pools");
+    boot.run(BootPhase::Workers, async { Ok(()) }).await.unwrap();
+    record(&mut boot, &log, "stop job workers");
+
+    let failure = boot
+        .run(BootPhase::Listeners, async { Err::<(), _>(anyhow::anyhow!("Address already in use")) })
+        .await
+        .unwrap_err();
+
+    assert_eq!(failure.phase, BootPhase::Listeners);
+    assert_eq!(failure.completed, [BootPhase::Config, BootPhase::Database, BootPhase::Workers]);
+    assert_eq!(*log.lock().unwrap(), ["stop job workers", "close database pools"]);
+    let message = failure.to_string();
+    assert!(message.starts_with("Startup failed in the listeners phase after "), "{}", message);
+    assert!(message.contains("Address already in use (hint: check that server.port"), "{}", message);
+}
+
+#[tokio::test]
+async fn a_panicking_phase_fails_like_an_error() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut boot = Boot::new();
+    boot.run(BootPhase::Database, async { Ok(()) }).await.unwrap();
+    record(&mut boot, &log, "close database pools");
+
+    let failure = boot
+        .run(BootPhase::Caches, async {
+            if log.lock().unwrap().is_empty() {
+                panic!("REDIS_URL is not a URL");
+            }
+            Ok(())
+        })
+        .await
+        .unwrap_err();
+
+    assert_eq!(failure.phase, BootPhase::Caches);
+    assert_eq!(format!("{:#}", failure.source), "panicked: REDIS_URL is not a URL");
+    assert_eq!(*log.lock().unwrap(), ["close database pools"]);
+}
+
+#[tokio::test]
+async fn finishing_reports_each_phase_and_skips_teardown() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut boot = Boot::new();
+    boot.run(BootPhase::Database, async { Ok(()) }).await.unwrap();
+    record(&mut boot, &log, "close database pools");
+    boot.run(BootPhase::Listeners, async { Ok(()) }).await.unwrap();
+
+    let phases: Vec<BootPhase> = boot.finish().into_iter().map(|t| t.phase).collect();
+    assert_eq!(phases, [BootPhase::Database, BootPhase::Listeners]);
+    assert!(log.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+#[should_panic(expected = "boot phase database entered after Some(Caches)")]
+async fn phases_run_in_order() {
+    let mut boot = Boot::new();
+    boot.run(BootPhase::Caches, async { Ok(()) }).await.unwrap();
+    let _ = boot.run(BootPhase::Database, async { Ok(()) }).await;
+}
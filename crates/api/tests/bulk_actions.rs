@@ -0,0 +1,224 @@
+//! Admin bulk user actions: queued as operations, run in the background, with a result per user.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+use application::admin::{AdminUserServiceImpl, BulkTarget, BulkUserAction, BulkUserActionJob, BulkUserActions};
+use application::authz::AuthorizationService;
+use application::jobs::Job;
+use application::operations::{Operation, OperationStatus, OperationStore};
+use application::testing::{MockUserRepository, RecordingJobQueue};
+use application::ApplicationError;
+use async_trait::async_trait;
+use domain::{Authorization, DomainError, RoleRepository, User, UserFilter, UserStatus};
+use uuid::Uuid;
+
+#[derive(Default)]
+struct MemoryOperations {
+    operations: Mutex<HashMap<Uuid, Operation>>,
+    /// Every saved state, to check progress is recorded while running
+    updates: Mutex<Vec<Operation>>,
+}
+
+#[async_trait]
+impl OperationStore for MemoryOperations {
+    async fn create(&self, operation: &Operation) -> Result<(), ApplicationError> {
+        self.operations.lock().unwrap().insert(operation.id, operation.clone());
+        Ok(())
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<Operation>, ApplicationError> {
+        Ok(self.operations.lock().unwrap().get(&id).cloned())
+    }
+
+    async fn update(&self, operation: &Operation) -> Result<(), ApplicationError> {
+        self.operations.lock().unwrap().insert(operation.id, operation.clone());
+        self.updates.lock().unwrap().push(operation.clone());
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct MemoryRoles {
+    grants: Mutex<HashMap<Uuid, BTreeSet<String>>>,
+}
+
+#[async_trait]
+impl RoleRepository for MemoryRoles {
+    async fn authorization(&self, user_id: Uuid, extra_roles: &[String]) -> Result<Authorization, DomainError> {
+        let mut roles = self.grants.lock().unwrap().get(&user_id).cloned().unwrap_or_default();
+        roles.extend(extra_roles.iter().cloned());
+        Ok(Authorization {
+            roles,
+            permissions: BTreeSet::new(),
+        })
+    }
+
+    async fn grant(&self, user_id: Uuid, role: &str) -> Result<bool, DomainError> {
+        Ok(self.grants.lock().unwrap().entry(user_id).or_default().insert(role.to_string()))
+    }
+
+    async fn revoke(&self, user_id: Uuid, role: &str) -> Result<bool, DomainError> {
+        Ok(self.grants.lock().unwrap().entry(user_id).or_default().remove(role))
+    }
+}
+
+struct Fixture {
+    users: Arc<MockUserRepository>,
+    roles: Arc<MemoryRoles>,
+    operations: Arc<MemoryOperations>,
+    queue: Arc<RecordingJobQueue>,
+    actions: BulkUserActions,
+    job: BulkUserActionJob,
+}
+
+fn fixture(users: Vec<User>) -> Fixture {
+    let users = Arc::new(MockUserRepository::with_users(users));
+    let roles = Arc::new(MemoryRoles::default());
+    let operations = Arc::new(MemoryOperations::default());
+    let queue = Arc::new(RecordingJobQueue::new());
+    Fixture {
+        actions: BulkUserActions::new(operations.clone(), queue.clone()),
+        job: BulkUserActionJob::new(
+            operations.clone(),
+            Arc::new(AdminUserServiceImpl::new(users.clone())),
+            users.clone(),
+            Arc::new(AuthorizationService::new(roles.clone())),
+        ),
+        users,
+        roles,
+        operations,
+        queue,
+    }
+}
+
+impl Fixture {
+    /// Run every queued job, then return the operation's final state
+    async fn run(&self, id: Uuid) -> Operation {
+        let payloads: Vec<_> = self.queue.take(BulkUserActionJob::KIND).into_iter().map(|(payload, _)| payload).collect();
+        for payload in payloads {
+            self.job.run(payload).await.unwrap();
+        }
+        self.operations.get(id).await.unwrap().unwrap()
+    }
+}
+
+fn user(name: &str) -> User {
+    User::new(name.parse().unwrap(), format!("{}@example.com", name).parse().unwrap(), "hash".to_string())
+}
+
+#[tokio::test]
+async fn suspends_listed_users_and_reports_each_failure() {
+    let (admin, alice, bob) = (user("admin"), user("alice"), user("bob"));
+    let missing = Uuid::new_v4();
+    let f = fixture(vec![admin.clone(), alice.clone(), bob.clone()]);
+
+    let ids = vec![alice.id, bob.id, alice.id, admin.id, missing];
+    let operation = f.actions.submit(admin.id, BulkUserAction::Suspend, BulkTarget::Ids(ids)).await.unwrap();
+    assert_eq!(operation.status, OperationStatus::Pending);
+    assert_eq!(operation.kind, "users.bulk.suspend");
+
+    let operation = f.run(operation.id).await;
+    assert_eq!(operation.status, OperationStatus::Succeeded);
+    assert_eq!(operation.total, Some(4));
+    let results: Vec<(Uuid, bool)> = operation.items.iter().map(|item| (item.id, item.error.is_none())).collect();
+    assert_eq!(results, [(alice.id, true), (bob.id, true), (admin.id, false), (missing, false)]);
+    assert!(operation.items[2].error.as_deref().unwrap().contains("own account"));
+    assert_eq!(operation.failed_items(), 2);
+
+    let suspended: Vec<String> = f
+        .users
+        .users()
+        .into_iter()
+        .filter(|u| u.status == UserStatus::Suspended)
+        .map(|u| u.username.into())
+        .collect();
+    assert_eq!(suspended, ["alice", "bob"]);
+}
+
+#[tokio::test]
+async fn a_filter_is_resolved_when_the_job_runs() {
+    let (admin, alice) = (user("admin"), user("alice"));
+    let bob = User {
+        status: UserStatus::Suspended,
+        ..user("bob")
+    };
+    let f = fixture(vec![admin.clone(), alice.clone(), bob.clone()]);
+
+    let filter = UserFilter {
+        status: Some(UserStatus::Active),
+        ..UserFilter::default()
+    };
+    let action = BulkUserAction::AddRole { role: " Support ".to_string() };
+    let operation = f.actions.submit(admin.id, action, BulkTarget::Filter(filter)).await.unwrap();
+
+    let operation = f.run(operation.id).await;
+    assert_eq!(operation.status, OperationStatus::Succeeded);
+    assert_eq!(operation.total, Some(2));
+    assert_eq!(operation.failed_items(), 0);
+    let grants = f.roles.grants.lock().unwrap();
+    assert!(grants[&alice.id].contains("support"));
+    assert!(grants[&admin.id].contains("support"));
+    assert!(!grants.contains_key(&bob.id));
+}
+
+#[tokio::test]
+async fn progress_is_saved_in_batches_and_a_retry_skips_done_users() {
+    let admin = user("admin");
+    let users: Vec<User> = (0..150).map(|i| user(&format!("user{}", i))).collect();
+    let ids: Vec<Uuid> = users.iter().map(|u| u.id).collect();
+    let f = fixture(users);
+
+    let operation = f.actions.submit(admin.id, BulkUserAction::Delete, BulkTarget::Ids(ids)).await.unwrap();
+    let payload = f.queue.payloads(BulkUserActionJob::KIND)[0].clone();
+    let operation = f.run(operation.id).await;
+    assert_eq!(operation.items.len(), 150);
+    assert_eq!(operation.failed_items(), 0);
+    assert!(f.users.users().is_empty());
+
+    let processed: Vec<(OperationStatus, usize)> = f
+        .operations
+        .updates
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|op| (op.status, op.items.len()))
+        .collect();
+    assert_eq!(
+        processed,
+        [
+            (OperationStatus::Running, 0),
+            (OperationStatus::Running, 100),
+            (OperationStatus::Running, 150),
+            (OperationStatus::Succeeded, 150)
+        ]
+    );
+
+    // Interrupted after the first batch: the retry picks up where it stopped
+    let mut interrupted = operation.clone();
+    interrupted.status = OperationStatus::Running;
+    interrupted.items.truncate(100);
+    f.operations.update(&interrupted).await.unwrap();
+    f.job.run(payload).await.unwrap();
+    let resumed = f.operations.get(operation.id).await.unwrap().unwrap();
+    assert_eq!(resumed.items.len(), 150);
+    assert_eq!(resumed.items[..100], operation.items[..100]);
+    // Already deleted by the first run
+    assert_eq!(resumed.failed_items(), 50);
+}
+
+#[tokio::test]
+async fn invalid_requests_are_rejected_before_queueing() {
+    let admin = user("admin");
+    let f = fixture(vec![admin.clone()]);
+
+    let empty = f.actions.submit(admin.id, BulkUserAction::Delete, BulkTarget::Ids(Vec::new())).await;
+    assert!(matches!(empty, Err(ApplicationError::Domain(DomainError::Validation(_)))));
+
+    let role = BulkUserAction::AddRole { role: "no spaces".to_string() };
+    let invalid_role = f.actions.submit(admin.id, role, BulkTarget::Ids(vec![admin.id])).await;
+    assert!(matches!(invalid_role, Err(ApplicationError::Domain(DomainError::Validation(_)))));
+
+    assert!(f.queue.payloads(BulkUserActionJob::KIND).is_empty());
+    assert!(f.operations.operations.lock().unwrap().is_empty());
+}
@@ -0,0 +1,138 @@
+//! The typed client: DTOs in sync with the OpenAPI schemas, auth and errors.
+
+use std::collections::BTreeSet;
+
+use api::auth::{RegisterRequest, ValidatedJson};
+use api::error::ApiError;
+use axum::extract::Path;
+use axum::http::HeaderMap;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use client::{ClientError, RustBaseClient};
+use serde::Serialize;
+use serde_json::json;
+use utoipa::openapi::{RefOr, Schema};
+use utoipa::ToSchema;
+
+/// Property names of a server DTO's OpenAPI schema
+fn schema_fields<'a, T: ToSchema<'a>>() -> BTreeSet<String> {
+    match T::schema().1 {
+        RefOr::T(Schema::Object(object)) => object.properties.keys().cloned().collect(),
+        _ => panic!("{} is not an object schema", T::schema().0),
+    }
+}
+
+/// Field names of a client model
+fn client_fields<T: Serialize + Default>() -> BTreeSet<String> {
+    match serde_json::to_value(T::default()).unwrap() {
+        serde_json::Value::Object(map) => map.keys().cloned().collect(),
+        _ => panic!("not a struct"),
+    }
+}
+
+#[test]
+fn client_models_match_the_server_schemas() {
+    use api::{auth, error};
+    assert_eq!(client_fields::<client::RegisterRequest>(), schema_fields::<auth::RegisterRequest>());
+    assert_eq!(client_fields::<client::LoginRequest>(), schema_fields::<auth::LoginRequest>());
+    assert_eq!(client_fields::<client::RefreshRequest>(), schema_fields::<auth::RefreshRequest>());
+    assert_eq!(client_fields::<client::ChangePasswordRequest>(), schema_fields::<auth::ChangePasswordRequest>());
+    assert_eq!(client_fields::<client::AuthResponse>(), schema_fields::<auth::AuthResponse>());
+    assert_eq!(client_fields::<client::TokenResponse>(), schema_fields::<auth::TokenResponse>());
+    assert_eq!(client_fields::<client::UserDto>(), schema_fields::<auth::UserDto>());
+    assert_eq!(client_fields::<client::BatchUsersRequest>(), schema_fields::<api::users::BatchUsersRequest>());
+    assert_eq!(client_fields::<client::BatchUsersResponse>(), schema_fields::<api::users::BatchUsersResponse>());
+    assert_eq!(client_fields::<client::ErrorResponse>(), schema_fields::<error::ErrorResponse>());
+    assert_eq!(client_fields::<client::ErrorBody>(), schema_fields::<error::ErrorBody>());
+    assert_eq!(client_fields::<client::FieldError>(), schema_fields::<error::FieldError>());
+}
+
+const TOKEN: &str = "t0k3n";
+
+/// Stand-in server answering like the real handlers
+async fn serve() -> String {
+    let user = json!({
+        "id": "550e8400-e29b-41d4-a716-446655440000",
+        "username": "john_doe",
+        "email": "john@example.com",
+        "avatar_url": null,
+        "password_reset_required": false,
+        "version": 3
+    });
+    let app = Router::new()
+        .route(
+            "/api/v1/auth/register",
+            post(|ValidatedJson(_): ValidatedJson<RegisterRequest>| async { Json(json!({})) }),
+        )
+        .route(
+            "/api/v1/auth/login",
+            post(|| async { Json(json!({"access_token": TOKEN, "token_type": "Bearer", "expires_in": 86400})) }),
+        )
+        .route(
+            "/api/v1/me",
+            get(move |headers: HeaderMap| async move {
+                match headers.get("authorization").and_then(|v| v.to_str().ok()) {
+                    Some(value) if value == format!("Bearer {}", TOKEN) => Ok(Json(user)),
+                    _ => Err(ApiError::unauthorized("Missing bearer token")),
+                }
+            }),
+        )
+        .route(
+            "/api/v1/users/:id",
+            get(|Path(id): Path<String>| async move {
+                Err::<Json<()>, _>(ApiError::not_found(format!("User with id {} not found", id)))
+            }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}/", addr)
+}
+
+#[tokio::test]
+async fn login_keeps_the_token_for_later_calls() {
+    let mut client = RustBaseClient::new(serve().await);
+    let unauthenticated = client.me().await.unwrap_err();
+    assert_eq!(unauthenticated.status().map(|s| s.as_u16()), Some(401));
+    assert_eq!(unauthenticated.code(), Some("UNAUTHORIZED"));
+
+    let token = client.login("john@example.com", "securepassword123").await.unwrap();
+    assert_eq!(token.token_type, "Bearer");
+    assert_eq!(client.token(), Some(TOKEN));
+
+    let me = client.me().await.unwrap();
+    assert_eq!(me.username, "john_doe");
+    assert_eq!(me.version, 3);
+}
+
+#[tokio::test]
+async fn api_errors_are_typed() {
+    let client = RustBaseClient::new(serve().await);
+    match client.get_user("missing").await {
+        Err(ClientError::Api { status, code, message, .. }) => {
+            assert_eq!(status.as_u16(), 404);
+            assert_eq!(code, "NOT_FOUND");
+            assert_eq!(message, "User with id missing not found");
+        }
+        other => panic!("expected an API error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn invalid_fields_are_listed() {
+    let client = RustBaseClient::new(serve().await);
+    let request = client::RegisterRequest {
+        username: "jo".into(),
+        email: "not-an-email".into(),
+        password: "securepassword123".into(),
+    };
+    match client.register(&request).await {
+        Err(ClientError::Api { status, code, errors, .. }) => {
+            assert_eq!(status.as_u16(), 422);
+            assert_eq!(code, "VALIDATION_ERROR");
+            let fields: Vec<_> = errors.iter().map(|e| (e.field.as_str(), e.code.as_str())).collect();
+            assert_eq!(fields, [("email", "email"), ("username", "length")]);
+        }
+        other => panic!("expected a validation error, got {:?}", other),
+    }
+}
@@ -0,0 +1,107 @@
+//! gzip/br response compression, request decompression and the configured algorithm order.
+
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+use api::compression::{preferred_encoding, prefer_encoding, request_decompression, response_compression};
+use api::startup::METRICS_CONTENT_TYPE;
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use shared::CompressionConfig;
+use tower::ServiceExt;
+
+fn app(config: CompressionConfig) -> Router {
+    let big = "x".repeat(4096);
+    let config = Arc::new(config);
+    Router::new()
+        .route("/big", get(move || async move { big }))
+        .route("/small", get(|| async { "ok" }))
+        .route(
+            "/metrics",
+            get(|| async { ([(header::CONTENT_TYPE, METRICS_CONTENT_TYPE)], "m ".repeat(4096)) }),
+        )
+        .route(
+            "/events",
+            get(|| async { ([(header::CONTENT_TYPE, "text/event-stream")], "data: x\n\n".repeat(512)) }),
+        )
+        .route("/echo", post(|body: String| async move { body }))
+        .layer(response_compression(&config))
+        .layer(middleware::from_fn_with_state(config.clone(), prefer_encoding))
+        .layer(request_decompression(&config))
+}
+
+async fn get_with(app: &Router, path: &str, accept: &str) -> (Option<String>, Vec<u8>) {
+    let request = Request::get(path).header(header::ACCEPT_ENCODING, accept).body(Body::empty()).unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let encoding = response.headers().get(header::CONTENT_ENCODING).map(|v| v.to_str().unwrap().to_string());
+    (encoding, to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec())
+}
+
+#[tokio::test]
+async fn large_responses_are_compressed_small_ones_are_not() {
+    let app = app(CompressionConfig::default());
+
+    let (encoding, body) = get_with(&app, "/big", "gzip").await;
+    assert_eq!(encoding.as_deref(), Some("gzip"));
+    let mut text = String::new();
+    GzDecoder::new(body.as_slice()).read_to_string(&mut text).unwrap();
+    assert_eq!(text, "x".repeat(4096));
+
+    assert_eq!(get_with(&app, "/small", "gzip").await.0, None);
+}
+
+#[tokio::test]
+async fn event_streams_and_metrics_are_never_compressed() {
+    let app = app(CompressionConfig::default());
+    assert_eq!(get_with(&app, "/events", "gzip, br").await.0, None);
+    assert_eq!(get_with(&app, "/metrics", "gzip, br").await.0, None);
+}
+
+#[tokio::test]
+async fn the_configured_order_wins_over_the_client_order() {
+    let gzip_first = CompressionConfig {
+        algorithms: vec!["gzip".to_string(), "br".to_string()],
+        ..CompressionConfig::default()
+    };
+    assert_eq!(get_with(&app(gzip_first), "/big", "br, gzip").await.0.as_deref(), Some("gzip"));
+    assert_eq!(get_with(&app(CompressionConfig::default()), "/big", "gzip, br").await.0.as_deref(), Some("br"));
+
+    let off = CompressionConfig {
+        algorithms: Vec::new(),
+        ..CompressionConfig::default()
+    };
+    assert_eq!(get_with(&app(off), "/big", "gzip, br").await.0, None);
+}
+
+#[test]
+fn refused_encodings_are_skipped() {
+    let algorithms = ["br".to_string(), "gzip".to_string()];
+    assert_eq!(preferred_encoding("br;q=0, gzip;q=0.5", &algorithms), Some("gzip"));
+    assert_eq!(preferred_encoding("*", &algorithms), Some("br"));
+    assert_eq!(preferred_encoding("*;q=0, identity", &algorithms), None);
+    assert_eq!(preferred_encoding("deflate", &algorithms), None);
+}
+
+#[tokio::test]
+async fn gzipped_request_bodies_are_inflated() {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(b"{\"hello\":\"world\"}").unwrap();
+    let request = Request::post("/echo")
+        .header(header::CONTENT_ENCODING, "gzip")
+        .body(Body::from(encoder.finish().unwrap()))
+        .unwrap();
+
+    let response = app(CompressionConfig::default()).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(to_bytes(response.into_body(), usize::MAX).await.unwrap(), "{\"hello\":\"world\"}");
+
+    let request = Request::post("/echo").header(header::CONTENT_ENCODING, "zstd").body(Body::from("x")).unwrap();
+    let response = app(CompressionConfig::default()).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
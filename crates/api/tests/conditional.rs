@@ -0,0 +1,39 @@
+//! ETag helpers for conditional requests.
+
+use api::conditional::{check_if_match, entity_tag, not_modified};
+use axum::http::{header, HeaderMap, HeaderValue};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+fn headers(name: header::HeaderName, value: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(name, HeaderValue::from_str(value).unwrap());
+    headers
+}
+
+#[test]
+fn etag_changes_with_every_update() {
+    let (id, at) = (Uuid::new_v4(), Utc::now());
+    assert_eq!(entity_tag(id, at), entity_tag(id, at));
+    assert_ne!(entity_tag(id, at), entity_tag(id, at + Duration::microseconds(1)));
+    assert_ne!(entity_tag(id, at), entity_tag(Uuid::new_v4(), at));
+}
+
+#[test]
+fn if_none_match_uses_weak_comparison() {
+    let etag = entity_tag(Uuid::new_v4(), Utc::now());
+    assert!(not_modified(&headers(header::IF_NONE_MATCH, &format!("\"other\", W/{}", etag)), &etag));
+    assert!(not_modified(&headers(header::IF_NONE_MATCH, "*"), &etag));
+    assert!(!not_modified(&headers(header::IF_NONE_MATCH, "\"other\""), &etag));
+    assert!(!not_modified(&HeaderMap::new(), &etag));
+}
+
+#[test]
+fn if_match_needs_the_exact_version() {
+    let etag = entity_tag(Uuid::new_v4(), Utc::now());
+    assert!(check_if_match(&HeaderMap::new(), &etag).is_ok());
+    assert!(check_if_match(&headers(header::IF_MATCH, &etag), &etag).is_ok());
+    assert!(check_if_match(&headers(header::IF_MATCH, "*"), &etag).is_ok());
+    assert!(check_if_match(&headers(header::IF_MATCH, &format!("W/{}", etag)), &etag).is_err());
+    assert!(check_if_match(&headers(header::IF_MATCH, "\"stale\""), &etag).is_err());
+}
@@ -0,0 +1,144 @@
+//! Cron tasks: schedules, one run per due time across replicas, and the built-in rollup task.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use application::cron::{parse_schedule, CronScheduler, CronTask};
+use application::locks::DistributedLock;
+use application::rollups::{DailyMetric, MetricRollupStore, RollupMetricsTask};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{Days, NaiveDate, Utc};
+use domain::DomainError;
+use infrastructure::InMemoryLock;
+
+/// Counts its runs; fails while `failing` is set
+#[derive(Default)]
+struct Counter {
+    runs: AtomicUsize,
+    failing: std::sync::atomic::AtomicBool,
+}
+
+#[async_trait]
+impl CronTask for Counter {
+    fn name(&self) -> &'static str {
+        "count"
+    }
+
+    async fn run(&self) -> Result<(), ApplicationError> {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        if self.failing.load(Ordering::SeqCst) {
+            return Err(DomainError::internal("boom").into());
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn schedules_take_five_or_six_fields() {
+    let hourly = parse_schedule("0 * * * *").unwrap();
+    let next = hourly.upcoming(Utc).next().unwrap();
+    assert_eq!(next.format("%M:%S").to_string(), "00:00");
+
+    let weekdays = parse_schedule("30 15 3 * * Mon-Fri").unwrap();
+    assert_eq!(weekdays.upcoming(Utc).next().unwrap().format("%H:%M:%S").to_string(), "03:15:30");
+
+    for invalid in ["", "every hour", "61 * * * *"] {
+        assert!(matches!(
+            parse_schedule(invalid),
+            Err(ApplicationError::Domain(DomainError::Validation(_)))
+        ));
+    }
+}
+
+#[tokio::test]
+async fn replicas_run_each_due_time_once() {
+    let lock: Arc<dyn DistributedLock> = Arc::new(InMemoryLock::new());
+    let replica = || CronScheduler::new(lock.clone()).with_clock_skew(Duration::from_millis(200));
+    let (a, b) = (replica(), replica());
+    let task = Counter::default();
+
+    let due = Utc::now();
+    let (ran_a, ran_b) = tokio::join!(a.run(&task, due), b.run(&task, due));
+    assert_eq!([ran_a.unwrap(), ran_b.unwrap()].iter().filter(|ran| **ran).count(), 1);
+    assert_eq!(task.runs.load(Ordering::SeqCst), 1);
+
+    // The winner held the lock past the due time, so it is free again now
+    assert!(b.run(&task, Utc::now()).await.unwrap());
+    assert_eq!(task.runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn a_failed_run_releases_its_lock() {
+    let scheduler = CronScheduler::new(Arc::new(InMemoryLock::new())).with_clock_skew(Duration::ZERO);
+    let task = Counter::default();
+    task.failing.store(true, Ordering::SeqCst);
+    assert!(scheduler.run(&task, Utc::now()).await.is_err());
+
+    task.failing.store(false, Ordering::SeqCst);
+    assert!(scheduler.run(&task, Utc::now()).await.unwrap());
+    assert_eq!(task.runs.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn spawned_tasks_run_on_schedule() {
+    let task = Arc::new(Counter::default());
+    let scheduler = CronScheduler::new(Arc::new(InMemoryLock::new()))
+        .with_clock_skew(Duration::ZERO)
+        .register(task.clone(), "* * * * * *")
+        .unwrap();
+    assert_eq!(scheduler.tasks(), ["count"]);
+    assert!(CronScheduler::new(Arc::new(InMemoryLock::new())).register(task.clone(), "nope").is_err());
+
+    let handles = Arc::new(scheduler).spawn();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while task.runs.load(Ordering::SeqCst) < 2 {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .unwrap();
+    for handle in handles {
+        handle.abort();
+    }
+}
+
+/// Remembers the days rolled up
+#[derive(Default)]
+struct MemoryRollups {
+    days: Mutex<Vec<NaiveDate>>,
+}
+
+#[async_trait]
+impl MetricRollupStore for MemoryRollups {
+    async fn last_day(&self) -> Result<Option<NaiveDate>, ApplicationError> {
+        Ok(self.days.lock().unwrap().iter().max().copied())
+    }
+
+    async fn rollup(&self, day: NaiveDate) -> Result<Vec<DailyMetric>, ApplicationError> {
+        self.days.lock().unwrap().push(day);
+        Ok(vec![DailyMetric { day, metric: "signups".into(), value: 0 }])
+    }
+}
+
+#[tokio::test]
+async fn rollups_catch_up_on_missed_days() {
+    let store = Arc::new(MemoryRollups::default());
+    let task = RollupMetricsTask::new(store.clone()).with_max_backfill_days(3);
+    let today = Utc::now().date_naive();
+    let days_ago = |n| today - Days::new(n);
+
+    // The first run only rolls up yesterday
+    task.run().await.unwrap();
+    assert_eq!(*store.days.lock().unwrap(), [days_ago(1)]);
+    assert!(task.due_days(today).await.unwrap().is_empty());
+
+    // Two days later both missed days are due; after a long outage only the newest three
+    assert_eq!(task.due_days(today + Days::new(2)).await.unwrap(), [today, today + Days::new(1)]);
+    assert_eq!(
+        task.due_days(today + Days::new(30)).await.unwrap(),
+        [today + Days::new(27), today + Days::new(28), today + Days::new(29)]
+    );
+}
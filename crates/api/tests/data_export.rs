@@ -0,0 +1,268 @@
+//! Account data exports: one per day, built in the background and delivered as a single-use, expiring link.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use std::io::Read;
+
+use application::data_export::{
+    DataExport, DataExportService, DataExportStore, DeleteExportJob, ExportFormat, ExportStatus, ExportUserDataJob, EXPORT_PREFIX,
+};
+use application::email::EmailJobPayload;
+use application::jobs::Job;
+use application::refresh_tokens::{RefreshTokenRecord, RefreshTokenStore};
+use application::storage::FileStorage;
+use application::testing::{MockUserRepository, RecordingJobQueue};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{DomainError, User};
+use infrastructure::LocalFileStorage;
+use uuid::Uuid;
+
+#[derive(Default)]
+struct MemoryExports {
+    exports: Mutex<HashMap<Uuid, DataExport>>,
+}
+
+#[async_trait]
+impl DataExportStore for MemoryExports {
+    async fn create(&self, export: &DataExport) -> Result<(), ApplicationError> {
+        self.exports.lock().unwrap().insert(export.id, export.clone());
+        Ok(())
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Option<DataExport>, ApplicationError> {
+        Ok(self.exports.lock().unwrap().get(&id).cloned())
+    }
+
+    async fn latest(&self, user_id: Uuid) -> Result<Option<DataExport>, ApplicationError> {
+        let exports = self.exports.lock().unwrap();
+        Ok(exports.values().filter(|e| e.user_id == user_id).max_by_key(|e| e.requested_at).cloned())
+    }
+
+    async fn mark_ready(&self, id: Uuid, key: &str, token_hash: &str, expires_at: DateTime<Utc>) -> Result<(), ApplicationError> {
+        let mut exports = self.exports.lock().unwrap();
+        let export = exports.get_mut(&id).unwrap();
+        export.key = Some(key.to_string());
+        export.token_hash = Some(token_hash.to_string());
+        export.expires_at = Some(expires_at);
+        Ok(())
+    }
+
+    async fn claim_download(&self, token_hash: &str, now: DateTime<Utc>) -> Result<Option<DataExport>, ApplicationError> {
+        let mut exports = self.exports.lock().unwrap();
+        let Some(export) = exports.values_mut().find(|e| e.token_hash.as_deref() == Some(token_hash)) else {
+            return Ok(None);
+        };
+        if export.downloaded_at.is_some() || export.expires_at.is_some_and(|t| t <= now) {
+            return Ok(None);
+        }
+        export.downloaded_at = Some(now);
+        Ok(Some(export.clone()))
+    }
+}
+
+/// Serves a fixed set of tokens; exports only read them, so writes are refused
+struct FixedTokens(Vec<RefreshTokenRecord>);
+
+fn read_only() -> ApplicationError {
+    ApplicationError::use_case("FixedTokens is read-only")
+}
+
+#[async_trait]
+impl RefreshTokenStore for FixedTokens {
+    async fn create(&self, _token: &RefreshTokenRecord) -> Result<(), ApplicationError> {
+        Err(read_only())
+    }
+
+    async fn find(&self, token_hash: &str) -> Result<Option<RefreshTokenRecord>, ApplicationError> {
+        Ok(self.0.iter().find(|t| t.token_hash == token_hash).cloned())
+    }
+
+    async fn mark_used(&self, _id: Uuid) -> Result<bool, ApplicationError> {
+        Err(read_only())
+    }
+
+    async fn revoke_family(&self, _family_id: Uuid) -> Result<u64, ApplicationError> {
+        Err(read_only())
+    }
+
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<RefreshTokenRecord>, ApplicationError> {
+        Ok(self.0.iter().filter(|t| t.user_id == user_id).cloned().collect())
+    }
+
+    async fn prune(&self, _before: DateTime<Utc>) -> Result<u64, ApplicationError> {
+        Err(read_only())
+    }
+
+    async fn prune_revoked(&self, _before: DateTime<Utc>) -> Result<u64, ApplicationError> {
+        Err(read_only())
+    }
+}
+
+fn token(user_id: Uuid, family_id: Uuid, minutes_ago: i64) -> RefreshTokenRecord {
+    let created_at = Utc::now() - chrono::Duration::minutes(minutes_ago);
+    RefreshTokenRecord {
+        id: Uuid::new_v4(),
+        family_id,
+        user_id,
+        token_hash: Uuid::new_v4().to_string(),
+        expires_at: created_at + chrono::Duration::days(30),
+        created_at,
+        used_at: None,
+        revoked_at: None,
+        token_version: 0,
+    }
+}
+
+struct Fixture {
+    user: User,
+    exports: Arc<MemoryExports>,
+    storage: Arc<dyn FileStorage>,
+    queue: Arc<RecordingJobQueue>,
+    service: DataExportService,
+    job: ExportUserDataJob,
+}
+
+fn fixture(tokens: impl FnOnce(Uuid) -> Vec<RefreshTokenRecord>) -> Fixture {
+    let root = std::env::temp_dir().join(format!("data-export-{}", Uuid::new_v4()));
+    let storage: Arc<dyn FileStorage> = Arc::new(LocalFileStorage::new(&root, "https://api.example.com/files", "secret"));
+    let user = User::new("alice".parse().unwrap(), "alice@example.com".parse().unwrap(), "hash".into());
+    let users = Arc::new(MockUserRepository::with_users([user.clone()]));
+    let exports = Arc::new(MemoryExports::default());
+    let queue = Arc::new(RecordingJobQueue::new());
+    Fixture {
+        service: DataExportService::new(exports.clone(), storage.clone(), queue.clone()),
+        job: ExportUserDataJob::new(exports.clone(), users, Arc::new(FixedTokens(tokens(user.id))), storage.clone(), queue.clone())
+            .with_link_ttl(Duration::from_secs(900))
+            .with_download_url("https://api.example.com/api/v1/exports/"),
+        user,
+        exports,
+        storage,
+        queue,
+    }
+}
+
+impl Fixture {
+    /// Request an export and run its job; returns the export and the emailed link's token
+    async fn export(&self, format: ExportFormat) -> (DataExport, String) {
+        let export = self.service.request(self.user.id, format).await.unwrap();
+        let [(payload, _)]: [_; 1] = self.queue.take(ExportUserDataJob::KIND).try_into().unwrap();
+        self.job.run(payload).await.unwrap();
+
+        let [(email, _)]: [_; 1] = self.queue.take("email.send").try_into().unwrap();
+        let email: EmailJobPayload = serde_json::from_value(email).unwrap();
+        assert_eq!(email.to, "alice@example.com");
+        assert_eq!(email.vars["expires_in_minutes"], 15);
+        let url = email.vars["download_url"].as_str().unwrap();
+        let token = url.strip_prefix("https://api.example.com/api/v1/exports/").expect(url).to_string();
+        (self.service.latest(self.user.id).await.unwrap().unwrap_or(export), token)
+    }
+}
+
+fn is_not_found<T>(result: Result<T, ApplicationError>) -> bool {
+    matches!(result, Err(ApplicationError::Domain(DomainError::NotFound { .. })))
+}
+
+#[tokio::test]
+async fn one_export_per_user_per_day() {
+    let f = fixture(|_| Vec::new());
+    let bob = Uuid::new_v4();
+
+    let export = f.service.request(f.user.id, ExportFormat::Json).await.unwrap();
+    assert_eq!(export.status(), ExportStatus::Pending);
+    let Err(ApplicationError::RateLimited { retry_after }) = f.service.request(f.user.id, ExportFormat::Zip).await else {
+        panic!("expected the second export of the day to be refused");
+    };
+    assert!(retry_after > Duration::from_secs(23 * 3600));
+    f.service.request(bob, ExportFormat::Zip).await.unwrap();
+
+    assert_eq!(f.queue.take(ExportUserDataJob::KIND).len(), 2);
+    assert_eq!(f.service.latest(f.user.id).await.unwrap(), Some(export));
+
+    // The stored exports decide, so a restarted or second instance refuses too
+    let other_instance = DataExportService::new(f.exports.clone(), f.storage.clone(), f.queue.clone());
+    assert!(matches!(
+        other_instance.request(f.user.id, ExportFormat::Json).await,
+        Err(ApplicationError::RateLimited { .. })
+    ));
+
+    // A day later the user may ask again
+    let mut old = f.exports.exports.lock().unwrap().values().find(|e| e.user_id == f.user.id).cloned().unwrap();
+    old.requested_at -= chrono::Duration::hours(25);
+    f.exports.exports.lock().unwrap().insert(old.id, old);
+    other_instance.request(f.user.id, ExportFormat::Zip).await.unwrap();
+}
+
+#[tokio::test]
+async fn exports_are_emailed_as_a_single_use_link() {
+    let f = fixture(|user_id| {
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        vec![token(user_id, first, 90), token(user_id, first, 30), token(user_id, second, 10)]
+    });
+
+    let (export, token) = f.export(ExportFormat::Json).await;
+    assert_eq!(export.status(), ExportStatus::Ready);
+    let key = export.key.clone().unwrap();
+    assert_eq!(key, format!("{}{}/{}.json", EXPORT_PREFIX, f.user.id, export.id.simple()));
+
+    let (downloaded, file) = f.service.download(&token).await.unwrap();
+    assert_eq!(downloaded.status(), ExportStatus::Downloaded);
+    assert_eq!(file.content_type, "application/json");
+    let data: serde_json::Value = serde_json::from_slice(&file.bytes).unwrap();
+    assert_eq!(data["user"]["email"], "alice@example.com");
+    assert!(data["user"].get("password_hash").is_none());
+    // One session per login, refreshes folded in
+    let sessions = data["sessions"].as_array().unwrap();
+    assert_eq!(sessions.len(), 2);
+    assert_ne!(sessions[0]["started_at"], sessions[0]["last_refreshed_at"]);
+    assert!(sessions[0].get("token_hash").is_none());
+
+    // The file is gone with the first download
+    assert!(f.storage.get(&key).await.unwrap().is_none());
+    assert!(is_not_found(f.service.download(&token).await));
+    assert!(is_not_found(f.service.download("not-a-token").await));
+    assert_eq!(f.service.latest(f.user.id).await.unwrap().unwrap().status(), ExportStatus::Downloaded);
+}
+
+#[tokio::test]
+async fn zip_exports_hold_one_file_per_kind_of_data() {
+    let f = fixture(|user_id| vec![token(user_id, Uuid::new_v4(), 5)]);
+
+    let (_, token) = f.export(ExportFormat::Zip).await;
+    let (export, file) = f.service.download(&token).await.unwrap();
+    assert_eq!(file.content_type, "application/zip");
+    assert!(export.key.unwrap().ends_with(".zip"));
+
+    let mut zip = zip::ZipArchive::new(std::io::Cursor::new(file.bytes)).unwrap();
+    let mut names: Vec<_> = zip.file_names().map(str::to_string).collect();
+    names.sort();
+    assert_eq!(names, ["sessions.json", "user.json"]);
+    let mut user = String::new();
+    zip.by_name("user.json").unwrap().read_to_string(&mut user).unwrap();
+    assert_eq!(serde_json::from_str::<serde_json::Value>(&user).unwrap()["username"], "alice");
+}
+
+#[tokio::test]
+async fn unused_exports_expire_and_are_deleted() {
+    let f = fixture(|_| Vec::new());
+    let (export, token) = f.export(ExportFormat::Json).await;
+    let key = export.key.clone().unwrap();
+
+    let [(delete, run_at)]: [_; 1] = f.queue.take(DeleteExportJob::KIND).try_into().unwrap();
+    assert_eq!(delete["key"], key);
+    assert!(run_at > Utc::now() + chrono::Duration::minutes(14));
+
+    // Past the deadline the link no longer works and the file is removed
+    f.exports.exports.lock().unwrap().get_mut(&export.id).unwrap().expires_at = Some(Utc::now());
+    assert_eq!(f.service.latest(f.user.id).await.unwrap().unwrap().status(), ExportStatus::Expired);
+    assert!(is_not_found(f.service.download(&token).await));
+    DeleteExportJob::new(f.storage.clone()).run(delete).await.unwrap();
+    assert!(f.storage.get(&key).await.unwrap().is_none());
+
+    // Only export files can be deleted through the job
+    let avatar = serde_json::json!({ "key": "public/avatars/a.png" });
+    assert!(DeleteExportJob::new(f.storage.clone()).run(avatar).await.is_err());
+}
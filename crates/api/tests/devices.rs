@@ -0,0 +1,125 @@
+//! Trusted devices: sign-ins recorded per fingerprint, remembered devices and how trust ends.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use application::devices::{Device, DeviceService, DeviceStore};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::Utc;
+use domain::DomainError;
+use uuid::Uuid;
+
+#[derive(Default)]
+struct MemoryDevices {
+    devices: Mutex<Vec<Device>>,
+}
+
+#[async_trait]
+impl DeviceStore for MemoryDevices {
+    async fn find(&self, user_id: Uuid, fingerprint_hash: &str) -> Result<Option<Device>, ApplicationError> {
+        Ok(self
+            .devices
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|d| d.user_id == user_id && d.fingerprint_hash == fingerprint_hash)
+            .cloned())
+    }
+
+    async fn save(&self, device: &Device) -> Result<(), ApplicationError> {
+        let mut devices = self.devices.lock().unwrap();
+        devices.retain(|d| d.id != device.id);
+        devices.push(device.clone());
+        Ok(())
+    }
+
+    async fn list(&self, user_id: Uuid) -> Result<Vec<Device>, ApplicationError> {
+        let mut devices: Vec<Device> = self.devices.lock().unwrap().iter().filter(|d| d.user_id == user_id).cloned().collect();
+        devices.sort_by_key(|d| std::cmp::Reverse(d.last_seen_at));
+        Ok(devices)
+    }
+
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<bool, ApplicationError> {
+        let mut devices = self.devices.lock().unwrap();
+        let before = devices.len();
+        devices.retain(|d| !(d.user_id == user_id && d.id == id));
+        Ok(devices.len() != before)
+    }
+}
+
+fn service(trust_days: u64) -> DeviceService {
+    DeviceService::new(Arc::new(MemoryDevices::default())).with_trust_period(Duration::from_secs(trust_days * 86_400))
+}
+
+#[tokio::test]
+async fn sign_ins_are_grouped_by_fingerprint() {
+    let devices = service(30);
+    let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+    let now = Utc::now();
+
+    let laptop = devices.record_login(alice, "laptop", Some("Firefox"), false, now).await.unwrap();
+    let again = devices
+        .record_login(alice, "laptop", None, false, now + chrono::Duration::hours(1))
+        .await
+        .unwrap();
+    assert_eq!(again.id, laptop.id);
+    assert_eq!(again.name.as_deref(), Some("Firefox"));
+    assert_eq!(again.first_seen_at, now);
+    assert!(!again.fingerprint_hash.contains("laptop"));
+
+    devices.record_login(alice, "phone", None, false, now).await.unwrap();
+    devices.record_login(bob, "laptop", None, false, now).await.unwrap();
+    let listed = devices.list(alice).await.unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[0].id, laptop.id);
+
+    assert!(matches!(
+        devices.record_login(alice, "  ", None, false, now).await,
+        Err(ApplicationError::Domain(DomainError::Validation(_)))
+    ));
+}
+
+#[tokio::test]
+async fn remembered_devices_are_trusted_for_the_period() {
+    let devices = service(30);
+    let alice = Uuid::new_v4();
+    let now = Utc::now();
+
+    devices.record_login(alice, "laptop", None, false, now).await.unwrap();
+    assert!(!devices.is_trusted(alice, "laptop", now).await.unwrap());
+
+    let device = devices.record_login(alice, "laptop", None, true, now).await.unwrap();
+    assert!(device.is_trusted(now));
+    assert!(devices.is_trusted(alice, "laptop", now + chrono::Duration::days(29)).await.unwrap());
+    assert!(!devices.is_trusted(alice, "laptop", now + chrono::Duration::days(31)).await.unwrap());
+    // Only for the user who asked, and only that device
+    assert!(!devices.is_trusted(Uuid::new_v4(), "laptop", now).await.unwrap());
+    assert!(!devices.is_trusted(alice, "phone", now).await.unwrap());
+
+    // A later sign-in without remembering keeps the trust
+    devices.record_login(alice, "laptop", None, false, now).await.unwrap();
+    assert!(devices.is_trusted(alice, "laptop", now).await.unwrap());
+
+    // Nothing is remembered when trust is configured off
+    let untrusting = service(0);
+    untrusting.record_login(alice, "laptop", None, true, now).await.unwrap();
+    assert!(!untrusting.is_trusted(alice, "laptop", now).await.unwrap());
+}
+
+#[tokio::test]
+async fn removed_devices_lose_their_trust() {
+    let devices = service(30);
+    let alice = Uuid::new_v4();
+    let now = Utc::now();
+    let device = devices.record_login(alice, "laptop", None, true, now).await.unwrap();
+
+    // Not someone else's to remove
+    assert!(matches!(
+        devices.remove(Uuid::new_v4(), device.id).await,
+        Err(ApplicationError::Domain(DomainError::NotFound { .. }))
+    ));
+    devices.remove(alice, device.id).await.unwrap();
+    assert!(!devices.is_trusted(alice, "laptop", now).await.unwrap());
+    assert!(devices.list(alice).await.unwrap().is_empty());
+}
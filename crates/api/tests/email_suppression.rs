@@ -0,0 +1,74 @@
+//! Bounce/complaint parsing for the email provider webhooks.
+
+use application::email_suppression::{
+    parse_sendgrid_events, parse_ses_notification, BounceNotice, SesNotification, SuppressionReason,
+};
+use serde_json::json;
+
+fn sns(message: serde_json::Value) -> Vec<u8> {
+    json!({ "Type": "Notification", "Message": message.to_string() }).to_string().into_bytes()
+}
+
+#[test]
+fn ses_permanent_bounces_and_complaints_are_suppressed() {
+    let bounce = sns(json!({
+        "notificationType": "Bounce",
+        "bounce": {
+            "bounceType": "Permanent",
+            "bouncedRecipients": [{ "emailAddress": "Gone@Example.com", "diagnosticCode": "smtp; 550 5.1.1" }]
+        }
+    }));
+    assert_eq!(
+        parse_ses_notification(&bounce).unwrap(),
+        SesNotification::Notices(vec![BounceNotice {
+            email: "gone@example.com".to_string(),
+            reason: SuppressionReason::Bounce,
+            detail: Some("smtp; 550 5.1.1".to_string()),
+        }])
+    );
+
+    let complaint = sns(json!({
+        "notificationType": "Complaint",
+        "complaint": { "complainedRecipients": [{ "emailAddress": "angry@example.com" }], "complaintFeedbackType": "abuse" }
+    }));
+    let SesNotification::Notices(notices) = parse_ses_notification(&complaint).unwrap() else {
+        panic!("expected notices");
+    };
+    assert_eq!(notices[0].reason, SuppressionReason::Complaint);
+
+    let transient = sns(json!({
+        "notificationType": "Bounce",
+        "bounce": { "bounceType": "Transient", "bouncedRecipients": [{ "emailAddress": "full@example.com" }] }
+    }));
+    assert_eq!(parse_ses_notification(&transient).unwrap(), SesNotification::Notices(vec![]));
+}
+
+#[test]
+fn ses_subscription_confirmation_is_reported() {
+    let body = json!({ "Type": "SubscriptionConfirmation", "SubscribeURL": "https://sns.example/confirm" });
+    assert_eq!(
+        parse_ses_notification(body.to_string().as_bytes()).unwrap(),
+        SesNotification::SubscriptionConfirmation {
+            subscribe_url: "https://sns.example/confirm".to_string()
+        }
+    );
+}
+
+#[test]
+fn sendgrid_blocks_and_deliveries_are_ignored() {
+    let body = json!([
+        { "email": "gone@example.com", "event": "bounce", "type": "bounce", "reason": "550 no such user" },
+        { "email": "busy@example.com", "event": "bounce", "type": "blocked" },
+        { "email": "spam@example.com", "event": "spamreport" },
+        { "email": "ok@example.com", "event": "delivered" }
+    ]);
+    let notices = parse_sendgrid_events(body.to_string().as_bytes()).unwrap();
+    let summary: Vec<_> = notices.iter().map(|n| (n.email.as_str(), n.reason)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("gone@example.com", SuppressionReason::Bounce),
+            ("spam@example.com", SuppressionReason::Complaint)
+        ]
+    );
+}
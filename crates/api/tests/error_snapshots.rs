@@ -0,0 +1,185 @@
+//! Snapshot tests pinning the public error contract (status + JSON body).
+//! Review changes with `cargo insta review`.
+
+use api::auth::{parse_validated, LoginRequest, RegisterRequest};
+use api::error::ApiError;
+use application::password_policy::PasswordPolicy;
+use application::ApplicationError;
+use axum::response::IntoResponse;
+use domain::DomainError;
+use serde_json::{json, Value};
+
+async fn render(err: ApiError) -> Value {
+    let response = err.into_response();
+    let status = response.status().as_u16();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    json!({ "status": status, "body": body })
+}
+
+async fn render_validation<T>(payload: &str) -> Value
+where
+    T: serde::de::DeserializeOwned + validator::Validate,
+{
+    match parse_validated::<T>(payload.as_bytes()) {
+        Ok(_) => panic!("payload unexpectedly passed validation: {}", payload),
+        Err(err) => render(err).await,
+    }
+}
+
+// ============================================================================
+// ApiError constructors
+// ============================================================================
+
+#[tokio::test]
+async fn not_found() {
+    insta::assert_json_snapshot!(render(ApiError::not_found("User with id 42 not found")).await);
+}
+
+#[tokio::test]
+async fn bad_request() {
+    insta::assert_json_snapshot!(render(ApiError::bad_request("Malformed input")).await);
+}
+
+#[tokio::test]
+async fn conflict() {
+    insta::assert_json_snapshot!(render(ApiError::conflict("Email already registered")).await);
+}
+
+#[tokio::test]
+async fn internal() {
+    insta::assert_json_snapshot!(render(ApiError::internal("Something went wrong")).await);
+}
+
+#[tokio::test]
+async fn unauthorized() {
+    insta::assert_json_snapshot!(render(ApiError::unauthorized("Missing Authorization header")).await);
+}
+
+#[tokio::test]
+async fn too_many_requests() {
+    let response = ApiError::too_many_requests("Slow down", 30).into_response();
+    assert_eq!(response.headers()["retry-after"], "30");
+    insta::assert_json_snapshot!(render(ApiError::too_many_requests("Slow down", 30)).await);
+}
+
+#[tokio::test]
+async fn precondition_failed() {
+    insta::assert_json_snapshot!(render(ApiError::precondition_failed("Resource was modified (ETag mismatch)")).await);
+}
+
+#[tokio::test]
+async fn forbidden() {
+    insta::assert_json_snapshot!(render(ApiError::forbidden("Required role 'admin' not found")).await);
+}
+
+// ============================================================================
+// Domain / application error mapping
+// ============================================================================
+
+#[tokio::test]
+async fn domain_not_found() {
+    let err: ApiError = DomainError::not_found("User", "42").into();
+    insta::assert_json_snapshot!(render(err).await);
+}
+
+#[tokio::test]
+async fn domain_validation() {
+    let err: ApiError = DomainError::validation("Password must be at least 8 characters").into();
+    insta::assert_json_snapshot!(render(err).await);
+}
+
+#[tokio::test]
+async fn domain_conflict() {
+    let err: ApiError = DomainError::conflict("Email already registered").into();
+    insta::assert_json_snapshot!(render(err).await);
+}
+
+#[tokio::test]
+async fn domain_version_conflict() {
+    let err: ApiError = DomainError::version_conflict("User", "42", 7).into();
+    insta::assert_json_snapshot!(render(err).await);
+}
+
+#[tokio::test]
+async fn domain_internal() {
+    let err: ApiError = DomainError::internal("connection refused").into();
+    insta::assert_json_snapshot!(render(err).await);
+}
+
+#[tokio::test]
+async fn domain_unauthorized() {
+    let err: ApiError = DomainError::unauthorized("Invalid credentials").into();
+    insta::assert_json_snapshot!(render(err).await);
+}
+
+#[tokio::test]
+async fn domain_forbidden() {
+    let err: ApiError = DomainError::forbidden("Only owners can transfer the organization").into();
+    insta::assert_json_snapshot!(render(err).await);
+}
+
+#[tokio::test]
+async fn domain_unavailable() {
+    let err: ApiError = DomainError::unavailable("User query failed transiently: connection reset").into();
+    insta::assert_json_snapshot!(render(err).await);
+}
+
+#[tokio::test]
+async fn domain_timeout() {
+    let err: ApiError = DomainError::timeout("User query exceeded the statement timeout").into();
+    insta::assert_json_snapshot!(render(err).await);
+}
+
+#[tokio::test]
+async fn application_use_case() {
+    let err: ApiError = ApplicationError::use_case("Cannot do that right now").into();
+    insta::assert_json_snapshot!(render(err).await);
+}
+
+#[tokio::test]
+async fn application_rate_limited() {
+    let err: ApiError = ApplicationError::RateLimited {
+        retry_after: std::time::Duration::from_secs(90),
+    }
+    .into();
+    insta::assert_json_snapshot!(render(err).await);
+}
+
+#[tokio::test]
+async fn application_weak_password() {
+    let policy = PasswordPolicy {
+        require_digit: true,
+        ..PasswordPolicy::default()
+    };
+    let err: ApiError = policy.validate("password", &[]).unwrap_err().into();
+    insta::assert_json_snapshot!(render(err).await);
+}
+
+// ============================================================================
+// Validation failure shapes
+// ============================================================================
+
+#[tokio::test]
+async fn validation_invalid_json() {
+    insta::assert_json_snapshot!(render_validation::<LoginRequest>("{not json").await);
+}
+
+#[tokio::test]
+async fn validation_missing_field() {
+    insta::assert_json_snapshot!(render_validation::<LoginRequest>(r#"{"email":"john@example.com"}"#).await);
+}
+
+#[tokio::test]
+async fn validation_single_field() {
+    let payload = r#"{"email":"john@example.com","password":""}"#;
+    insta::assert_json_snapshot!(render_validation::<LoginRequest>(payload).await);
+}
+
+#[tokio::test]
+async fn validation_multiple_fields() {
+    let payload = r#"{"username":"jo","email":"not-an-email","password":"short"}"#;
+    insta::assert_json_snapshot!(render_validation::<RegisterRequest>(payload).await);
+}
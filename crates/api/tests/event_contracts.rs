@@ -0,0 +1,58 @@
+//! Domain events survive the protobuf contract in the `proto` crate unchanged.
+
+use chrono::{TimeZone, Utc};
+use domain::{DomainEvent, EventEnvelope};
+use infrastructure::{decode_event, encode_event};
+use prost::Message;
+use proto::events::v1 as pb;
+use uuid::Uuid;
+
+fn every_event() -> Vec<DomainEvent> {
+    let user_id = Uuid::new_v4();
+    vec![
+        DomainEvent::UserRegistered { user_id, username: "alice".into(), email: "alice@example.com".into() },
+        DomainEvent::UserLoggedIn { user_id },
+        DomainEvent::FileQuarantined { user_id, purpose: "avatar".into(), signature: "Eicar-Test-Signature".into() },
+        DomainEvent::ServiceStarted { service: "api".into(), region: "eu-west-1".into(), version: "0.1.0".into() },
+        DomainEvent::MigrationsApplied { versions: vec![20240101000000, 20240102000000] },
+        DomainEvent::ShuttingDown { service: "api".into(), region: "eu-west-1".into() },
+        DomainEvent::NotificationCreated {
+            user_id,
+            notification_id: Uuid::new_v4(),
+            kind: "welcome".into(),
+            title: "Welcome, alice!".into(),
+        },
+    ]
+}
+
+#[test]
+fn every_event_round_trips() {
+    let events = every_event();
+    assert_eq!(events.len(), DomainEvent::NAMES.len(), "add the new event to every_event and events.proto");
+
+    for (id, event) in events.into_iter().enumerate() {
+        let envelope = EventEnvelope {
+            id: id as u64 + 1,
+            occurred_at: Utc.timestamp_opt(1_760_000_000, 123_456_789).unwrap(),
+            event,
+        };
+        let decoded = decode_event(&encode_event(&envelope)).unwrap();
+        assert_eq!(serde_json::to_value(&decoded).unwrap(), serde_json::to_value(&envelope).unwrap());
+
+        let raw = pb::EventEnvelope::decode(encode_event(&envelope).as_slice()).unwrap();
+        assert_eq!(raw.name, envelope.event.name());
+    }
+}
+
+#[test]
+fn events_from_a_newer_producer_are_refused() {
+    let unknown = pb::EventEnvelope {
+        id: 7,
+        occurred_at: Some(prost_types::Timestamp { seconds: 1_760_000_000, nanos: 0 }),
+        name: "user.teleported".to_string(),
+        event: None,
+    };
+    let err = decode_event(&unknown.encode_to_vec()).unwrap_err();
+    assert!(err.to_string().contains("user.teleported"), "{}", err);
+    assert!(decode_event(b"\xff\xff").is_err());
+}
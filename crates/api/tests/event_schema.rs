@@ -0,0 +1,69 @@
+//! Versioned event envelopes and upcasting of events stored under older schemas.
+
+use application::event_schema::{upcasters, EventUpcasters, VersionedEvent};
+use chrono::Utc;
+use domain::{DomainEvent, EventEnvelope};
+use serde_json::json;
+use uuid::Uuid;
+
+fn registered(username: &str) -> EventEnvelope {
+    EventEnvelope {
+        id: 42,
+        occurred_at: Utc::now(),
+        event: DomainEvent::UserRegistered { user_id: Uuid::new_v4(), username: username.into(), email: "a@example.com".into() },
+    }
+}
+
+#[test]
+fn envelopes_are_stored_with_their_schema_version() {
+    let envelope = registered("alice");
+    let stored: serde_json::Value = serde_json::from_str(&VersionedEvent::new(&envelope).unwrap().to_json().unwrap()).unwrap();
+
+    // The plain envelope plus `schema_version`, so existing receivers keep working
+    let mut plain = serde_json::to_value(&envelope).unwrap();
+    plain["schema_version"] = json!(1);
+    assert_eq!(stored, plain);
+}
+
+#[test]
+fn rows_stored_before_versioning_decode_as_version_one() {
+    let envelope = registered("alice");
+    let legacy = serde_json::to_string(&envelope).unwrap();
+
+    assert_eq!(VersionedEvent::parse(&legacy).unwrap().schema_version, 1);
+    let decoded = upcasters().decode(&legacy).unwrap();
+    assert_eq!(serde_json::to_value(decoded).unwrap(), serde_json::to_value(envelope).unwrap());
+}
+
+#[test]
+fn old_events_go_through_every_step() {
+    let upcasters = EventUpcasters::new()
+        .register("user.renamed", 1, |mut event| {
+            event["name"] = event["username"].take();
+            Ok(event)
+        })
+        .register("user.renamed", 2, |event| Ok(json!({ "handle": event["name"], "display_name": event["name"] })));
+    let v1 = VersionedEvent {
+        id: 1,
+        occurred_at: Utc::now(),
+        schema_version: 1,
+        event: json!({ "type": "user.renamed", "username": "bob" }),
+    };
+
+    let v3 = upcasters.upcast_to(v1.clone(), 3).unwrap();
+    assert_eq!(v3.schema_version, 3);
+    assert_eq!(v3.event, json!({ "type": "user.renamed", "handle": "bob", "display_name": "bob" }));
+
+    // A gap in the chain is an error, not a silently stale event
+    let err = EventUpcasters::new().upcast_to(v1, 2).unwrap_err();
+    assert!(err.to_string().contains("No upcaster for user.renamed v1"), "{}", err);
+}
+
+#[test]
+fn events_from_a_newer_build_are_refused() {
+    let mut newer = VersionedEvent::new(&registered("alice")).unwrap();
+    newer.schema_version = DomainEvent::schema_version("user.registered") + 1;
+
+    let err = upcasters().upcast(newer).unwrap_err();
+    assert!(err.to_string().contains("newer than this build"), "{}", err);
+}
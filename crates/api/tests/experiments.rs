@@ -0,0 +1,95 @@
+//! Experiments: assignment events once per assignment and exposure events per view.
+
+use std::sync::{Arc, Mutex};
+
+use application::{AnalyticsEvent, AnalyticsSink, ExperimentService, ExperimentServiceImpl};
+use domain::{Experiment, FlagContext};
+use infrastructure::InMemoryExperimentAssignmentStore;
+use uuid::Uuid;
+
+#[derive(Default)]
+struct RecordingSink {
+    events: Mutex<Vec<AnalyticsEvent>>,
+}
+
+impl AnalyticsSink for RecordingSink {
+    fn track(&self, event: AnalyticsEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+}
+
+impl RecordingSink {
+    /// `(type, experiment, variant)` of every event so far
+    fn taken(&self) -> Vec<(String, String, String)> {
+        self.events
+            .lock()
+            .unwrap()
+            .drain(..)
+            .map(|event| {
+                let json = serde_json::to_value(&event).unwrap();
+                let field = |name: &str| json[name].as_str().unwrap().to_string();
+                (field("type"), field("experiment"), field("variant"))
+            })
+            .collect()
+    }
+}
+
+fn experiment(key: &str, variant: &str) -> Experiment {
+    serde_json::from_value(serde_json::json!({"key": key, "variants": [{"name": variant, "weight": 1}]})).unwrap()
+}
+
+fn service(experiments: Vec<Experiment>, sink: Arc<RecordingSink>, store: Arc<InMemoryExperimentAssignmentStore>) -> ExperimentServiceImpl {
+    ExperimentServiceImpl::new(experiments, sink, store)
+}
+
+fn event(kind: &str, experiment: &str, variant: &str) -> (String, String, String) {
+    (kind.to_string(), experiment.to_string(), variant.to_string())
+}
+
+#[tokio::test]
+async fn assignments_are_tracked_once_per_user() {
+    let sink = Arc::new(RecordingSink::default());
+    let store = Arc::new(InMemoryExperimentAssignmentStore::new());
+    let experiments = service(vec![experiment("checkout_copy", "short")], sink.clone(), store.clone());
+    let ctx = FlagContext::for_user(Uuid::new_v4());
+
+    for _ in 0..3 {
+        assert_eq!(experiments.assignments(&ctx).await.unwrap().len(), 1);
+    }
+    assert_eq!(sink.taken(), [event("experiment_assigned", "checkout_copy", "short")]);
+
+    // Another user, or a changed variant, is a new assignment
+    experiments.assignments(&FlagContext::for_user(Uuid::new_v4())).await.unwrap();
+    assert_eq!(sink.taken().len(), 1);
+    let reconfigured = service(vec![experiment("checkout_copy", "long")], sink.clone(), store);
+    reconfigured.assignments(&ctx).await.unwrap();
+    assert_eq!(sink.taken(), [event("experiment_assigned", "checkout_copy", "long")]);
+}
+
+#[tokio::test]
+async fn every_exposure_is_tracked() {
+    let sink = Arc::new(RecordingSink::default());
+    let experiments = service(
+        vec![experiment("checkout_copy", "short")],
+        sink.clone(),
+        Arc::new(InMemoryExperimentAssignmentStore::new()),
+    );
+    let ctx = FlagContext::for_user(Uuid::new_v4());
+
+    // The first exposure also assigns
+    let shown = experiments.expose("checkout_copy", &ctx).await.unwrap().unwrap();
+    assert_eq!(shown.variant, "short");
+    experiments.expose("checkout_copy", &ctx).await.unwrap();
+    assert_eq!(
+        sink.taken(),
+        [
+            event("experiment_assigned", "checkout_copy", "short"),
+            event("experiment_exposed", "checkout_copy", "short"),
+            event("experiment_exposed", "checkout_copy", "short"),
+        ]
+    );
+
+    assert!(experiments.expose("missing", &ctx).await.unwrap().is_none());
+    assert!(experiments.expose("checkout_copy", &FlagContext::default()).await.unwrap().is_none());
+    assert!(sink.taken().is_empty());
+}
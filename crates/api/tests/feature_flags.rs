@@ -0,0 +1,136 @@
+//! Feature flags: configured defaults, admin-managed overrides and evaluation per caller.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use api::features::Features;
+use application::feature_flags::{FeatureFlagStore, FlagSource, ManagedFeatureFlags};
+use application::{ApplicationError, FeatureFlagService};
+use async_trait::async_trait;
+use domain::{Cohort, DomainError, FeatureFlag, FlagContext, Rollout};
+use uuid::Uuid;
+
+#[derive(Default)]
+struct MemoryFlags {
+    flags: Mutex<BTreeMap<String, FeatureFlag>>,
+}
+
+#[async_trait]
+impl FeatureFlagStore for MemoryFlags {
+    async fn list(&self) -> Result<Vec<FeatureFlag>, ApplicationError> {
+        Ok(self.flags.lock().unwrap().values().cloned().collect())
+    }
+
+    async fn save(&self, flag: &FeatureFlag) -> Result<(), ApplicationError> {
+        self.flags.lock().unwrap().insert(flag.key.clone(), flag.clone());
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<bool, ApplicationError> {
+        Ok(self.flags.lock().unwrap().remove(key).is_some())
+    }
+}
+
+fn flag(key: &str, enabled: bool, percentage: f64, cohorts: Vec<Cohort>) -> FeatureFlag {
+    FeatureFlag {
+        key: key.to_string(),
+        enabled,
+        rollout: Rollout { percentage, cohorts },
+        description: None,
+    }
+}
+
+fn flags(store: Arc<MemoryFlags>) -> ManagedFeatureFlags {
+    ManagedFeatureFlags::new(store, vec![flag("new_dashboard", false, 100.0, vec![])])
+}
+
+#[tokio::test]
+async fn stored_flags_override_configured_ones() {
+    let flags = flags(Arc::default());
+    let ctx = FlagContext::for_user(Uuid::new_v4());
+    assert!(!flags.is_enabled("new_dashboard", &ctx).await.unwrap());
+
+    flags.update("new_dashboard", flag("ignored", true, 100.0, vec![])).await.unwrap();
+    let (current, source) = flags.get("new_dashboard").unwrap();
+    assert_eq!((current.key.as_str(), source), ("new_dashboard", FlagSource::Stored));
+    assert!(flags.is_enabled("new_dashboard", &ctx).await.unwrap());
+
+    // Deleting the stored copy brings the configured flag back
+    flags.delete("new_dashboard").await.unwrap();
+    assert_eq!(flags.get("new_dashboard").unwrap().1, FlagSource::Config);
+    assert!(!flags.is_enabled("new_dashboard", &ctx).await.unwrap());
+
+    let configured_only = flags.delete("new_dashboard").await;
+    assert!(matches!(configured_only, Err(ApplicationError::Domain(DomainError::Validation(_)))));
+}
+
+#[tokio::test]
+async fn flags_are_created_once_and_validated() {
+    let flags = flags(Arc::default());
+
+    flags.create(flag("beta.search", true, 50.0, vec![])).await.unwrap();
+    let duplicate = flags.create(flag("beta.search", false, 100.0, vec![])).await;
+    assert!(matches!(duplicate, Err(ApplicationError::Domain(DomainError::Conflict { .. }))));
+    let configured = flags.create(flag("new_dashboard", true, 100.0, vec![])).await;
+    assert!(matches!(configured, Err(ApplicationError::Domain(DomainError::Conflict { .. }))));
+
+    for invalid in [flag("Beta", true, 100.0, vec![]), flag("1st", true, 100.0, vec![]), flag("ok", true, 101.0, vec![])] {
+        let result = flags.create(invalid).await;
+        assert!(matches!(result, Err(ApplicationError::Domain(DomainError::Validation(_)))));
+    }
+
+    let missing = flags.update("unknown", flag("unknown", true, 100.0, vec![])).await;
+    assert!(matches!(missing, Err(ApplicationError::Domain(DomainError::NotFound { .. }))));
+    let missing = flags.delete("unknown").await;
+    assert!(matches!(missing, Err(ApplicationError::Domain(DomainError::NotFound { .. }))));
+
+    let keys: Vec<_> = flags.list().into_iter().map(|(f, _)| f.key).collect();
+    assert_eq!(keys, ["beta.search", "new_dashboard"]);
+}
+
+#[tokio::test]
+async fn changes_from_other_instances_apply_on_refresh() {
+    let store = Arc::new(MemoryFlags::default());
+    let (here, there) = (flags(store.clone()), flags(store));
+
+    there.create(flag("beta.search", true, 100.0, vec![])).await.unwrap();
+    assert!(here.get("beta.search").is_none());
+
+    here.refresh().await.unwrap();
+    assert!(here.get("beta.search").is_some());
+}
+
+#[tokio::test]
+async fn flags_target_roles_and_a_stable_share_of_users() {
+    let flags = flags(Arc::default());
+    flags.create(flag("beta.search", true, 100.0, vec![Cohort::Role("beta".into())])).await.unwrap();
+    flags.create(flag("half", true, 50.0, vec![])).await.unwrap();
+
+    let tester = FlagContext::for_user(Uuid::new_v4()).with_roles(vec!["beta".into()]);
+    assert!(flags.is_enabled("beta.search", &tester).await.unwrap());
+    assert!(!flags.is_enabled("beta.search", &FlagContext::for_user(Uuid::new_v4())).await.unwrap());
+
+    let users: Vec<_> = (0..400).map(|_| FlagContext::for_user(Uuid::new_v4())).collect();
+    let mut enabled = 0;
+    for ctx in &users {
+        let first = flags.is_enabled("half", ctx).await.unwrap();
+        assert_eq!(first, flags.is_enabled("half", ctx).await.unwrap(), "rollouts are sticky");
+        enabled += first as usize;
+    }
+    assert!((120..280).contains(&enabled), "{} of 400 users", enabled);
+}
+
+#[tokio::test]
+async fn handlers_branch_on_the_callers_flags() {
+    let flags = Arc::new(flags(Arc::default()));
+    flags.create(flag("beta.search", true, 100.0, vec![Cohort::Role("beta".into())])).await.unwrap();
+
+    let tester = FlagContext::for_user(Uuid::new_v4()).with_roles(vec!["beta".into()]);
+    let features = Features::new(flags.clone(), tester.clone());
+    assert!(features.is_enabled("beta.search").await);
+    assert!(!features.is_enabled("unknown").await);
+    assert_eq!(flags.enabled_flags(&tester).await.unwrap(), ["beta.search"]);
+
+    let anonymous = Features::new(flags, FlagContext::default());
+    assert!(!anonymous.is_enabled("beta.search").await);
+}
@@ -0,0 +1,49 @@
+//! gRPC transport: bearer tokens are checked like on the REST API.
+#![cfg(feature = "grpc")]
+
+use std::sync::Arc;
+
+use application::testing::{MockPasswordHasher, MockTokenService, MockUserRepository};
+use application::token_versions::TokenVersions;
+use application::{AuthServiceImpl, TokenService, UserServiceImpl};
+use domain::User;
+use grpc::pb::user_service_server::UserService as _;
+use grpc::pb::GetCurrentUserRequest;
+use grpc::{GrpcServices, UserGrpcService};
+use infrastructure::InMemoryEventBus;
+use tonic::{Code, Request};
+
+fn get_current_user_request(token: &str) -> Request<GetCurrentUserRequest> {
+    let mut request = Request::new(GetCurrentUserRequest {});
+    request
+        .metadata_mut()
+        .insert("authorization", format!("Bearer {}", token).parse().unwrap());
+    request
+}
+
+#[tokio::test]
+async fn tokens_revoked_by_a_logout_all_are_rejected() {
+    let alice = User::new("alice".parse().unwrap(), "alice@example.com".parse().unwrap(), String::new());
+    let users = Arc::new(MockUserRepository::with_users([alice.clone()]));
+    let tokens = Arc::new(MockTokenService::new());
+    let token_versions = Arc::new(TokenVersions::new(users.clone()));
+    let service = UserGrpcService::new(GrpcServices {
+        user_service: Arc::new(UserServiceImpl::new(users.clone())),
+        auth_service: Arc::new(AuthServiceImpl::new(
+            users.clone(),
+            Arc::new(MockPasswordHasher::new()),
+            tokens.clone(),
+            Arc::new(InMemoryEventBus::default()),
+        )),
+        token_service: tokens.clone(),
+        token_versions: token_versions.clone(),
+    });
+    let token = tokens.generate(&alice).unwrap().access_token;
+
+    let user = service.get_current_user(get_current_user_request(&token)).await.unwrap();
+    assert_eq!(user.into_inner().id, alice.id.to_string());
+
+    token_versions.revoke_all(alice.id).await.unwrap();
+    let status = service.get_current_user(get_current_user_request(&token)).await.unwrap_err();
+    assert_eq!(status.code(), Code::Unauthenticated);
+}
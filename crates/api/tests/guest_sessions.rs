@@ -0,0 +1,96 @@
+//! Guest sessions: anonymous tokens and upgrading a guest to an account that keeps its id.
+
+use std::sync::Arc;
+
+use application::activity::LoginClient;
+use application::testing::{MockPasswordHasher, MockTokenService, MockUserRepository};
+use application::{ApplicationError, AuthService, AuthServiceImpl, TokenService};
+use chrono::Utc;
+use domain::{Claims, DomainError, Tenant};
+use infrastructure::{InMemoryEventBus, JwtConfig, JwtTokenService};
+use uuid::Uuid;
+
+fn auth(users: Arc<MockUserRepository>, tokens: Arc<MockTokenService>) -> AuthServiceImpl {
+    AuthServiceImpl::new(
+        users,
+        Arc::new(MockPasswordHasher::new()),
+        tokens,
+        Arc::new(InMemoryEventBus::default()),
+    )
+}
+
+#[tokio::test]
+async fn guests_get_anonymous_tokens() {
+    let tokens = Arc::new(MockTokenService::new());
+    let auth = auth(Arc::new(MockUserRepository::new()), tokens.clone());
+
+    let first = tokens.validate(&auth.guest().await.unwrap().access_token).unwrap();
+    let second = tokens.validate(&auth.guest().await.unwrap().access_token).unwrap();
+    assert!(first.is_anonymous());
+    assert_eq!(first.roles, [Claims::ANONYMOUS_ROLE]);
+    assert_eq!(first.tenant_id, Some(Tenant::DEFAULT_ID.to_string()));
+    assert_ne!(first.sub, second.sub);
+}
+
+#[tokio::test]
+async fn upgraded_guests_keep_their_id() {
+    let users = Arc::new(MockUserRepository::new());
+    let tokens = Arc::new(MockTokenService::new());
+    let auth = auth(users.clone(), tokens.clone());
+
+    let guest = tokens.validate(&auth.guest().await.unwrap().access_token).unwrap();
+    let guest_id: Uuid = guest.sub.parse().unwrap();
+    let user = auth
+        .upgrade_guest(guest_id, "alice".into(), "alice@example.com".into(), "Correct-Horse-7".into())
+        .await
+        .unwrap();
+    assert_eq!(user.id, guest_id);
+    // Past the guest token's version, which retires it
+    assert!(user.token_version > guest.token_version);
+
+    // Signing in gives a regular user token for the same id
+    let token = auth.login("alice@example.com".into(), "Correct-Horse-7".into(), LoginClient::default()).await.unwrap();
+    let claims = tokens.validate(&token.access_token).unwrap();
+    assert_eq!(claims.sub, guest.sub);
+    assert!(!claims.is_anonymous());
+
+    assert!(matches!(
+        auth.upgrade_guest(guest_id, "alice2".into(), "alice2@example.com".into(), "Correct-Horse-7".into())
+            .await,
+        Err(ApplicationError::Domain(DomainError::Conflict { .. }))
+    ));
+    assert_eq!(users.users().len(), 1);
+}
+
+#[tokio::test]
+async fn upgrades_follow_the_registration_rules() {
+    let auth = auth(Arc::new(MockUserRepository::new()), Arc::new(MockTokenService::new()));
+    auth.register("bob".into(), "bob@example.com".into(), "Correct-Horse-7".into())
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        auth.upgrade_guest(Uuid::new_v4(), "bobby".into(), "bob@example.com".into(), "Correct-Horse-7".into())
+            .await,
+        Err(ApplicationError::Domain(DomainError::Conflict { .. }))
+    ));
+    assert!(auth
+        .upgrade_guest(Uuid::new_v4(), "carol".into(), "carol@example.com".into(), "short".into())
+        .await
+        .is_err());
+}
+
+#[test]
+fn jwt_guest_tokens_are_short_lived() {
+    let tokens = JwtTokenService::new(JwtConfig::new("secret".into(), 24).with_guest_expiration_minutes(15));
+    let guest_id = Uuid::new_v4();
+    let tenant_id = Uuid::new_v4();
+
+    let pair = tokens.generate_guest(guest_id, tenant_id).unwrap();
+    assert_eq!(pair.expires_in, 15 * 60);
+    let claims = tokens.validate(&pair.access_token).unwrap();
+    assert_eq!(claims.sub, guest_id.to_string());
+    assert_eq!(claims.tenant_id, Some(tenant_id.to_string()));
+    assert!(claims.is_anonymous() && claims.email.is_empty());
+    assert!(claims.exp <= Utc::now().timestamp() + 15 * 60);
+}
@@ -0,0 +1,136 @@
+//! Trusted health check probes: recognized by network or shared secret, on the probe routes only.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use api::health_checks::{trusted_probes, HealthCheckAccess, TrustedProbe, HEALTH_CHECK_SECRET_HEADER};
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, Request},
+    http::StatusCode,
+    middleware,
+    routing::get,
+    Router,
+};
+use shared::{Config, ConfigError, HealthCheckSettings, IpNetwork};
+use tower::ServiceExt;
+
+const SECRET: &str = "probe-secret-0123456789";
+
+fn settings() -> HealthCheckSettings {
+    HealthCheckSettings {
+        trusted_networks: vec!["10.0.0.0/8".to_string(), "fd00::/8".to_string()],
+        secret: Some(SECRET.to_string()),
+        ..HealthCheckSettings::default()
+    }
+}
+
+fn ip(value: &str) -> Option<IpAddr> {
+    Some(value.parse().unwrap())
+}
+
+/// 200 for trusted probes, 401 for everyone else, like `jwt_auth` would answer
+async fn guarded(request: Request) -> StatusCode {
+    if request.extensions().get::<TrustedProbe>().is_some() {
+        StatusCode::OK
+    } else {
+        StatusCode::UNAUTHORIZED
+    }
+}
+
+fn app() -> Router {
+    Router::new()
+        .route("/health/info", get(guarded))
+        .route("/api/v1/admin/users", get(guarded))
+        .layer(middleware::from_fn_with_state(
+            Arc::new(HealthCheckAccess::new(&settings())),
+            trusted_probes,
+        ))
+}
+
+async fn call(path: &str, peer: &str, secret: Option<&str>) -> StatusCode {
+    let mut request = Request::get(path).body(Body::empty()).unwrap();
+    request
+        .extensions_mut()
+        .insert(ConnectInfo(SocketAddr::new(peer.parse().unwrap(), 40_000)));
+    if let Some(secret) = secret {
+        request.headers_mut().insert(HEALTH_CHECK_SECRET_HEADER, secret.parse().unwrap());
+    }
+    app().oneshot(request).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn probes_are_trusted_by_network_or_secret() {
+    assert_eq!(call("/health/info", "10.1.2.3", None).await, StatusCode::OK);
+    assert_eq!(call("/health/info", "203.0.113.7", Some(SECRET)).await, StatusCode::OK);
+
+    assert_eq!(call("/health/info", "203.0.113.7", None).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(call("/health/info", "203.0.113.7", Some("probe-secret-wrong")).await, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn other_routes_are_never_trusted() {
+    assert_eq!(call("/api/v1/admin/users", "10.1.2.3", None).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(call("/api/v1/admin/users", "10.1.2.3", Some(SECRET)).await, StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn nothing_is_trusted_by_default() {
+    let access = HealthCheckAccess::new(&HealthCheckSettings::default());
+    assert!(!access.is_trusted("/health", ip("127.0.0.1"), None));
+    assert!(!access.is_trusted("/health", ip("127.0.0.1"), Some("")));
+}
+
+#[test]
+fn networks_match_by_prefix() {
+    let network: IpNetwork = "192.168.0.0/16".parse().unwrap();
+    assert!(network.contains("192.168.40.1".parse().unwrap()));
+    assert!(!network.contains("192.169.0.1".parse().unwrap()));
+    // IPv4 peers of a dual-stack listener
+    assert!(network.contains("::ffff:192.168.1.1".parse().unwrap()));
+
+    let host: IpNetwork = "10.0.0.5".parse().unwrap();
+    assert!(host.contains("10.0.0.5".parse().unwrap()));
+    assert!(!host.contains("10.0.0.6".parse().unwrap()));
+
+    let everything: IpNetwork = "0.0.0.0/0".parse().unwrap();
+    assert!(everything.contains("8.8.8.8".parse().unwrap()));
+    assert!(!everything.contains("2001:db8::1".parse().unwrap()));
+
+    let v6: IpNetwork = "fd00::/8".parse().unwrap();
+    assert!(v6.contains("fd12::1".parse().unwrap()));
+
+    for invalid in ["10.0.0.0/33", "fd00::/129", "10.0.0/8", "internal", "10.0.0.0/x"] {
+        assert!(invalid.parse::<IpNetwork>().is_err(), "{:?} should be rejected", invalid);
+    }
+}
+
+#[test]
+fn settings_are_checked() {
+    let mut config = Config::default();
+    config.database.url = "postgres://localhost/app".to_string();
+    config.health_checks.trusted_networks = vec!["10.0.0.0/8".to_string(), "10.0.0.0/99".to_string()];
+    config.health_checks.secret = Some("short".to_string());
+    config.health_checks.paths.push("metrics".to_string());
+
+    let Err(ConfigError::Invalid(problems)) = config.validate("development") else {
+        panic!("expected the health check settings to be rejected");
+    };
+    assert_eq!(problems.len(), 3, "{:?}", problems);
+    assert!(problems.iter().all(|p| p.starts_with("health_checks.")), "{:?}", problems);
+
+    // Only probe routes: a trusted probe skips the role checks
+    config.health_checks = settings();
+    config.health_checks.paths.push("/api/v1/admin/users".to_string());
+    let Err(ConfigError::Invalid(problems)) = config.validate("development") else {
+        panic!("expected a non-probe path to be rejected");
+    };
+    assert!(problems[0].contains("/api/v1/admin/users"), "{:?}", problems);
+    let access = HealthCheckAccess::new(&config.health_checks);
+    assert!(!access.is_trusted("/api/v1/admin/users", ip("10.1.2.3"), Some(SECRET)));
+    assert!(access.is_trusted("/health/info", ip("10.1.2.3"), None));
+
+    config.health_checks = settings();
+    config.validate("development").unwrap();
+    assert_eq!(config.redacted().health_checks.secret.as_deref(), Some("[REDACTED]"));
+}
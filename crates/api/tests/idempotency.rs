@@ -0,0 +1,234 @@
+//! `Idempotency-Key` middleware against an in-memory store.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use api::idempotency::{idempotency_keys, Idempotency};
+use application::idempotency::{IdempotencyClaim, IdempotencyStore, StoredResponse};
+use application::testing::MockUserRepository;
+use application::token_versions::TokenVersions;
+use application::{ApplicationError, TokenService};
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    extract::State,
+    http::{Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::post,
+    Router,
+};
+use chrono::{DateTime, Utc};
+use domain::{Claims, DomainError, TokenPair, User};
+use shared::IdempotencySettings;
+use uuid::Uuid;
+use tower::ServiceExt;
+
+/// (scope, key) -> (fingerprint, response once completed)
+type Keys = HashMap<(String, String), (String, Option<StoredResponse>)>;
+
+#[derive(Default)]
+struct InMemoryStore(Mutex<Keys>);
+
+#[async_trait]
+impl IdempotencyStore for InMemoryStore {
+    async fn claim(
+        &self,
+        scope: &str,
+        key: &str,
+        fingerprint: &str,
+        _ttl: Duration,
+        _lock_timeout: Duration,
+    ) -> Result<IdempotencyClaim, ApplicationError> {
+        let mut keys = self.0.lock().unwrap();
+        Ok(match keys.get(&(scope.to_string(), key.to_string())) {
+            None => {
+                keys.insert((scope.to_string(), key.to_string()), (fingerprint.to_string(), None));
+                IdempotencyClaim::Acquired
+            }
+            Some((f, _)) if f != fingerprint => IdempotencyClaim::Mismatch,
+            Some((_, None)) => IdempotencyClaim::InProgress,
+            Some((_, Some(response))) => IdempotencyClaim::Completed(response.clone()),
+        })
+    }
+
+    async fn complete(&self, scope: &str, key: &str, response: &StoredResponse) -> Result<(), ApplicationError> {
+        if let Some(entry) = self.0.lock().unwrap().get_mut(&(scope.to_string(), key.to_string())) {
+            entry.1 = Some(response.clone());
+        }
+        Ok(())
+    }
+
+    async fn release(&self, scope: &str, key: &str) -> Result<(), ApplicationError> {
+        self.0.lock().unwrap().remove(&(scope.to_string(), key.to_string()));
+        Ok(())
+    }
+
+    async fn prune(&self, _before: DateTime<Utc>) -> Result<u64, ApplicationError> {
+        Ok(0)
+    }
+}
+
+/// Accepts any token and uses it as the user id; issued tokens are just that id.
+/// A `:stale` suffix names the same user, but `reject_stale` refuses it.
+struct FakeTokens;
+
+impl TokenService for FakeTokens {
+    fn generate(&self, user: &User) -> Result<TokenPair, DomainError> {
+        Ok(TokenPair::new(user.id.to_string(), 3600))
+    }
+
+    fn generate_guest(&self, guest_id: Uuid, _tenant_id: Uuid) -> Result<TokenPair, DomainError> {
+        Ok(TokenPair::new(guest_id.to_string(), 3600))
+    }
+
+    fn generate_impersonation(&self, user: &User, _actor_id: Uuid) -> Result<TokenPair, DomainError> {
+        Ok(TokenPair::new(user.id.to_string(), 3600))
+    }
+
+    fn validate(&self, token: &str) -> Result<Claims, DomainError> {
+        Ok(Claims {
+            sub: token.trim_end_matches(":stale").to_string(),
+            email: String::new(),
+            roles: vec![],
+            exp: 0,
+            iat: 0,
+            tenant_id: None,
+            token_version: 0,
+            act: None,
+        })
+    }
+}
+
+async fn create(State(calls): State<Arc<AtomicU32>>, body: String) -> (StatusCode, String) {
+    let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+    if body == "fail" {
+        return (StatusCode::INTERNAL_SERVER_ERROR, "boom".to_string());
+    }
+    (StatusCode::CREATED, format!("created #{}", n))
+}
+
+/// Stands in for jwt_auth, which runs inside the idempotency layer
+async fn reject_stale(request: Request<Body>, next: Next) -> Response {
+    let stale = request
+        .headers()
+        .get("authorization")
+        .is_some_and(|h| h.to_str().unwrap().ends_with(":stale"));
+    if stale {
+        return (StatusCode::UNAUTHORIZED, "token expired").into_response();
+    }
+    next.run(request).await
+}
+
+fn app(calls: Arc<AtomicU32>) -> Router {
+    app_with_users(calls, Arc::new(MockUserRepository::new()))
+}
+
+fn app_with_users(calls: Arc<AtomicU32>, users: Arc<MockUserRepository>) -> Router {
+    let idempotency = Arc::new(Idempotency::new(
+        Arc::new(InMemoryStore::default()),
+        Arc::new(FakeTokens),
+        Arc::new(TokenVersions::new(users).with_cache_ttl(Duration::ZERO)),
+        &IdempotencySettings::default(),
+    ));
+    Router::new()
+        .route("/things", post(create))
+        .with_state(calls)
+        .layer(middleware::from_fn(reject_stale))
+        .layer(middleware::from_fn_with_state(idempotency, idempotency_keys))
+}
+
+async fn send(app: &Router, user: Option<&str>, key: Option<&str>, body: &str) -> (StatusCode, Option<String>, String) {
+    let mut request = Request::post("/things");
+    if let Some(user) = user {
+        request = request.header("authorization", format!("Bearer {}", user));
+    }
+    if let Some(key) = key {
+        request = request.header("idempotency-key", key);
+    }
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::from(body.to_string())).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let replayed = response
+        .headers()
+        .get("idempotent-replayed")
+        .map(|v| v.to_str().unwrap().to_string());
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, replayed, String::from_utf8(bytes.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn retries_replay_the_first_response() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let app = app(calls.clone());
+
+    let first = send(&app, Some("alice"), Some("k1"), "a").await;
+    assert_eq!(first, (StatusCode::CREATED, None, "created #1".to_string()));
+
+    let retry = send(&app, Some("alice"), Some("k1"), "a").await;
+    assert_eq!(retry, (StatusCode::CREATED, Some("true".to_string()), "created #1".to_string()));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // Scoped per user: the same key from someone else is a new request
+    let other = send(&app, Some("bob"), Some("k1"), "a").await;
+    assert_eq!(other.2, "created #2");
+}
+
+#[tokio::test]
+async fn reusing_a_key_for_another_request_is_rejected() {
+    let app = app(Arc::new(AtomicU32::new(0)));
+
+    send(&app, Some("alice"), Some("k1"), "a").await;
+    let (status, _, _) = send(&app, Some("alice"), Some("k1"), "b").await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn server_errors_and_anonymous_requests_are_not_stored() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let app = app(calls.clone());
+
+    send(&app, Some("alice"), Some("k1"), "fail").await;
+    let (status, replayed, _) = send(&app, Some("alice"), Some("k1"), "fail").await;
+    assert_eq!((status, replayed), (StatusCode::INTERNAL_SERVER_ERROR, None));
+
+    send(&app, None, Some("k2"), "a").await;
+    let (_, replayed, _) = send(&app, None, Some("k2"), "a").await;
+    assert_eq!(replayed, None);
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn rejected_tokens_are_not_stored() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let app = app(calls.clone());
+
+    let (status, _, _) = send(&app, Some("alice:stale"), Some("k1"), "a").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Same user and key with a fresh token: the handler runs
+    let retry = send(&app, Some("alice"), Some("k1"), "a").await;
+    assert_eq!(retry, (StatusCode::CREATED, None, "created #1".to_string()));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn revoked_tokens_do_not_replay() {
+    let user = User::new("alice".parse().unwrap(), "alice@example.com".parse().unwrap(), String::new());
+    let users = Arc::new(MockUserRepository::with_users([user]));
+    let user_id = users.users().remove(0).id.to_string();
+    let calls = Arc::new(AtomicU32::new(0));
+    let app = app_with_users(calls.clone(), users.clone());
+
+    send(&app, Some(&user_id), Some("k1"), "a").await;
+    TokenVersions::new(users).revoke_all(user_id.parse().unwrap()).await.unwrap();
+
+    // The revoked token is treated as anonymous; jwt_auth rejects it further in
+    let (_, replayed, body) = send(&app, Some(&user_id), Some("k1"), "a").await;
+    assert_eq!((replayed, body.as_str()), (None, "created #2"));
+}
@@ -0,0 +1,107 @@
+//! Impersonation tokens: who may be impersonated and what the tokens record.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+use application::authz::AuthorizationService;
+use application::impersonation::Impersonation;
+use application::testing::{MockTokenService, MockUserRepository};
+use application::{ApplicationError, TokenService};
+use async_trait::async_trait;
+use domain::{Authorization, DomainError, RoleRepository, User};
+use infrastructure::{JwtConfig, JwtTokenService};
+use uuid::Uuid;
+
+#[derive(Default)]
+struct MemoryRoles {
+    grants: Mutex<HashMap<Uuid, BTreeSet<String>>>,
+}
+
+#[async_trait]
+impl RoleRepository for MemoryRoles {
+    async fn authorization(&self, user_id: Uuid, extra_roles: &[String]) -> Result<Authorization, DomainError> {
+        let mut roles = self.grants.lock().unwrap().get(&user_id).cloned().unwrap_or_default();
+        roles.extend(extra_roles.iter().cloned());
+        Ok(Authorization {
+            roles,
+            permissions: BTreeSet::new(),
+        })
+    }
+
+    async fn grant(&self, user_id: Uuid, role: &str) -> Result<bool, DomainError> {
+        Ok(self.grants.lock().unwrap().entry(user_id).or_default().insert(role.to_string()))
+    }
+
+    async fn revoke(&self, user_id: Uuid, role: &str) -> Result<bool, DomainError> {
+        Ok(self.grants.lock().unwrap().entry(user_id).or_default().remove(role))
+    }
+}
+
+struct Fixture {
+    impersonation: Impersonation,
+    tokens: Arc<MockTokenService>,
+    admin: User,
+    other_admin: User,
+    alice: User,
+}
+
+async fn fixture() -> Fixture {
+    let admin = User::new("admin".parse().unwrap(), "admin@example.com".parse().unwrap(), String::new());
+    let other_admin = User::new("root".parse().unwrap(), "root@example.com".parse().unwrap(), String::new());
+    let alice = User::new("alice".parse().unwrap(), "alice@example.com".parse().unwrap(), String::new());
+    let users = Arc::new(MockUserRepository::with_users([admin.clone(), other_admin.clone(), alice.clone()]));
+    let authz = Arc::new(AuthorizationService::new(Arc::new(MemoryRoles::default())));
+    authz.grant_role(admin.id, "admin").await.unwrap();
+    authz.grant_role(other_admin.id, "admin").await.unwrap();
+    let tokens = Arc::new(MockTokenService::new());
+    Fixture {
+        impersonation: Impersonation::new(users, authz, tokens.clone()),
+        tokens,
+        admin,
+        other_admin,
+        alice,
+    }
+}
+
+#[tokio::test]
+async fn tokens_name_the_user_and_the_admin() {
+    let f = fixture().await;
+    let token = f.impersonation.start(f.admin.id, f.alice.id).await.unwrap();
+    assert!(token.refresh_token.is_none());
+
+    let claims = f.tokens.validate(&token.access_token).unwrap();
+    assert_eq!(claims.sub, f.alice.id.to_string());
+    assert_eq!(claims.actor(), Some(f.admin.id.to_string().as_str()));
+    assert_eq!(claims.roles, ["user"]);
+}
+
+#[tokio::test]
+async fn admins_and_missing_users_cannot_be_impersonated() {
+    let f = fixture().await;
+    let is_validation = |r: &Result<_, ApplicationError>| matches!(r, Err(ApplicationError::Domain(DomainError::Validation(_))));
+
+    assert!(is_validation(&f.impersonation.start(f.admin.id, f.other_admin.id).await));
+    assert!(is_validation(&f.impersonation.start(f.alice.id, f.alice.id).await));
+    assert!(matches!(
+        f.impersonation.start(f.admin.id, Uuid::new_v4()).await,
+        Err(ApplicationError::Domain(DomainError::NotFound { .. }))
+    ));
+    assert_eq!(f.tokens.issued(), 0);
+}
+
+#[test]
+fn jwt_impersonation_tokens_carry_the_actor() {
+    let tokens = JwtTokenService::new(JwtConfig::new("secret".into(), 24).with_impersonation_expiration_minutes(10));
+    let user = User::new("alice".parse().unwrap(), "alice@example.com".parse().unwrap(), String::new());
+    let admin_id = Uuid::new_v4();
+
+    let pair = tokens.generate_impersonation(&user, admin_id).unwrap();
+    assert_eq!(pair.expires_in, 600);
+    let claims = tokens.validate(&pair.access_token).unwrap();
+    assert_eq!(claims.act, Some(admin_id.to_string()));
+    assert_eq!(claims.token_version, user.token_version);
+
+    // Regular tokens leave the claim out entirely
+    let regular = tokens.validate(&tokens.generate(&user).unwrap().access_token).unwrap();
+    assert_eq!(regular.actor(), None);
+}
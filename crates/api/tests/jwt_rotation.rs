@@ -0,0 +1,92 @@
+//! JWT secret rotation: signing with the newest secret, accepting previous ones and reloading them.
+
+use std::fs;
+use std::sync::Arc;
+use std::time::Duration;
+
+use api::jwt_keys::reload_jwt_keys;
+use application::TokenService;
+use domain::DomainError;
+use infrastructure::{JwtConfig, JwtTokenService};
+use shared::LoadOptions;
+use uuid::Uuid;
+
+const OLD: &str = "old-secret-0123456789abcdef0123456789";
+const NEW: &str = "new-secret-0123456789abcdef0123456789";
+
+fn guest_token(tokens: &JwtTokenService) -> String {
+    tokens.generate_guest(Uuid::new_v4(), Uuid::new_v4()).unwrap().access_token
+}
+
+#[test]
+fn rotated_secrets_sign_new_tokens_and_accept_old_ones() {
+    let tokens = JwtTokenService::new(JwtConfig::new(OLD.into(), 24));
+    let issued_before = guest_token(&tokens);
+
+    assert!(tokens.set_secrets(NEW, &[OLD.to_string()]));
+    assert!(!tokens.set_secrets(NEW, &[OLD.to_string()]));
+    let issued_after = guest_token(&tokens);
+    tokens.validate(&issued_before).unwrap();
+    tokens.validate(&issued_after).unwrap();
+
+    // Only the newest secret signs; tokens name it in their `kid`
+    let only_new = JwtTokenService::new(JwtConfig::new(NEW.into(), 24));
+    only_new.validate(&issued_after).unwrap();
+    assert!(matches!(only_new.validate(&issued_before), Err(DomainError::Unauthorized(_))));
+    assert_eq!(tokens.key_ids().len(), 2);
+    assert_eq!(tokens.key_ids()[0], only_new.key_ids()[0]);
+
+    // Once the old secret is dropped, its tokens are rejected
+    tokens.set_secrets(NEW, &[]);
+    assert!(matches!(tokens.validate(&issued_before), Err(DomainError::Unauthorized(_))));
+    tokens.validate(&issued_after).unwrap();
+}
+
+#[test]
+fn previous_secrets_come_from_the_config() {
+    let tokens = JwtTokenService::new(JwtConfig::new(NEW.into(), 24).with_previous_secrets(vec![OLD.into()]));
+    let old = JwtTokenService::new(JwtConfig::new(OLD.into(), 24));
+    tokens.validate(&guest_token(&old)).unwrap();
+
+    let mut forged = guest_token(&old);
+    forged.pop();
+    assert!(matches!(tokens.validate(&forged), Err(DomainError::Unauthorized(_))));
+}
+
+#[tokio::test]
+async fn keys_are_reloaded_from_the_config_files() {
+    let dir = std::env::temp_dir().join(format!("rust-base-jwt-keys-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let write = |secret: &str, previous: &[&str]| {
+        let file = format!(
+            "[database]\nurl = \"postgres://localhost/app\"\n[jwt]\nsecret = \"{}\"\nprevious_secrets = {:?}\n",
+            secret, previous
+        );
+        fs::write(dir.join("default.toml"), file).unwrap();
+    };
+    write(OLD, &[]);
+
+    let tokens = Arc::new(JwtTokenService::new(JwtConfig::new(OLD.into(), 24)));
+    let issued_before = guest_token(&tokens);
+    let options = LoadOptions {
+        config_dir: dir.clone(),
+        profile: "development".to_string(),
+        overrides: Vec::new(),
+    };
+    reload_jwt_keys(tokens.clone(), options, Some(Duration::from_millis(20)));
+
+    write(NEW, &[OLD]);
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while tokens.key_ids().len() < 2 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    tokens.validate(&issued_before).unwrap();
+
+    // A broken file keeps the current keys
+    write("", &[]);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(tokens.key_ids().len(), 2);
+}
@@ -0,0 +1,22 @@
+//! Lifecycle events published at startup.
+
+use api::startup::publish_started;
+use application::EventBus;
+use domain::DomainEvent;
+use infrastructure::InMemoryEventBus;
+
+#[test]
+fn startup_announces_migrations_then_the_service() {
+    let bus = InMemoryEventBus::default();
+
+    publish_started(&bus, vec![20261016001300]);
+    let events: Vec<DomainEvent> = bus.events_since(0).into_iter().map(|e| e.event).collect();
+    assert!(matches!(&events[0], DomainEvent::MigrationsApplied { versions } if versions == &[20261016001300]));
+    assert!(matches!(&events[1], DomainEvent::ServiceStarted { version, .. } if version == env!("CARGO_PKG_VERSION")));
+    assert!(events.iter().all(|e| e.user_id().is_none() && DomainEvent::NAMES.contains(&e.name())));
+
+    // Nothing to announce when no migration ran
+    let bus = InMemoryEventBus::default();
+    publish_started(&bus, Vec::new());
+    assert_eq!(bus.events_since(0).len(), 1);
+}
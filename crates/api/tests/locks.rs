@@ -0,0 +1,68 @@
+//! Distributed locks: exclusive holders, release on drop and cancelling work whose lock was lost.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use application::locks::{with_lock, DistributedLock, LockGuard};
+use application::ApplicationError;
+use async_trait::async_trait;
+use domain::DomainError;
+use infrastructure::InMemoryLock;
+use tokio::sync::watch;
+
+const TTL: Duration = Duration::from_secs(30);
+
+#[tokio::test]
+async fn one_holder_at_a_time_until_the_guard_drops() {
+    let lock = InMemoryLock::new();
+    let guard = lock.try_acquire("reindex", TTL).await.unwrap().unwrap();
+    assert!(!guard.is_lost());
+    assert!(lock.try_acquire("reindex", TTL).await.unwrap().is_none());
+    assert!(lock.try_acquire("other", TTL).await.unwrap().is_some());
+
+    drop(guard);
+    assert!(lock.try_acquire("reindex", TTL).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn with_lock_skips_work_held_elsewhere() {
+    let lock = Arc::new(InMemoryLock::new());
+    assert_eq!(with_lock(lock.as_ref(), "report", TTL, async { 7 }).await.unwrap(), Some(7));
+
+    let held = lock.try_acquire("report", TTL).await.unwrap().unwrap();
+    let result = with_lock(lock.as_ref(), "report", TTL, async { unreachable!("ran while held") }).await;
+    assert_eq!(result.unwrap(), None::<()>);
+
+    // Released again once the work finished, whatever it returned
+    drop(held);
+    let failed: Result<(), &str> = Err("boom");
+    assert_eq!(with_lock(lock.as_ref(), "report", TTL, async { failed }).await.unwrap(), Some(Err("boom")));
+    assert!(lock.try_acquire("report", TTL).await.unwrap().is_some());
+}
+
+/// Hands out guards that report the lock lost once `lose` is sent
+struct Leases {
+    lose: watch::Sender<bool>,
+}
+
+#[async_trait]
+impl DistributedLock for Leases {
+    async fn try_acquire(&self, _name: &str, _ttl: Duration) -> Result<Option<LockGuard>, ApplicationError> {
+        Ok(Some(LockGuard::new(()).with_lost_signal(self.lose.subscribe())))
+    }
+}
+
+#[tokio::test]
+async fn work_stops_when_its_lock_is_lost() {
+    let leases = Leases {
+        lose: watch::channel(false).0,
+    };
+    let lose = leases.lose.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        lose.send_replace(true);
+    });
+
+    let result = with_lock(&leases, "migrate", TTL, std::future::pending::<()>()).await;
+    assert!(matches!(result, Err(ApplicationError::Domain(DomainError::Unavailable(_)))));
+}
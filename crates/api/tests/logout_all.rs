@@ -0,0 +1,95 @@
+//! Soft logout-all: bumping a user's token version rejects every token issued before it.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use application::testing::{MockTokenService, MockUserRepository};
+use application::token_versions::TokenVersions;
+use application::{ApplicationError, TokenService};
+use domain::{DomainError, Repository, User};
+
+fn alice() -> User {
+    User::new("alice".parse().unwrap(), "alice@example.com".parse().unwrap(), String::new())
+}
+
+fn is_unauthorized(result: Result<(), ApplicationError>) -> bool {
+    matches!(result, Err(ApplicationError::Domain(DomainError::Unauthorized(_))))
+}
+
+#[tokio::test]
+async fn tokens_issued_before_a_logout_all_are_rejected() {
+    let users = Arc::new(MockUserRepository::with_users([alice()]));
+    let user = users.users().remove(0);
+    let tokens = MockTokenService::new();
+    let versions = TokenVersions::new(users.clone());
+
+    let old = tokens.validate(&tokens.generate(&user).unwrap().access_token).unwrap();
+    assert!(versions.check(&old).await.is_ok());
+
+    assert_eq!(versions.revoke_all(user.id).await.unwrap(), 1);
+    assert!(is_unauthorized(versions.check(&old).await));
+
+    // A token issued afterwards carries the new version
+    let user = users.find_by_id(user.id).await.unwrap().unwrap();
+    let new = tokens.validate(&tokens.generate(&user).unwrap().access_token).unwrap();
+    assert_eq!(new.token_version, 1);
+    assert!(versions.check(&new).await.is_ok());
+}
+
+#[tokio::test]
+async fn versions_are_cached_until_the_ttl_passes() {
+    let users = Arc::new(MockUserRepository::with_users([alice()]));
+    let user = users.users().remove(0);
+    let tokens = MockTokenService::new();
+    let claims = tokens.validate(&tokens.generate(&user).unwrap().access_token).unwrap();
+
+    // Another instance bumps the version behind this one's back
+    let cached = TokenVersions::new(users.clone()).with_cache_ttl(Duration::from_secs(60));
+    let uncached = TokenVersions::new(users.clone()).with_cache_ttl(Duration::ZERO);
+    assert!(cached.check(&claims).await.is_ok());
+    TokenVersions::new(users.clone()).revoke_all(user.id).await.unwrap();
+
+    assert!(cached.check(&claims).await.is_ok());
+    assert!(is_unauthorized(uncached.check(&claims).await));
+}
+
+#[tokio::test]
+async fn unknown_users_have_nothing_to_revoke() {
+    let versions = TokenVersions::new(Arc::new(MockUserRepository::new()));
+    let user = alice();
+    let tokens = MockTokenService::new();
+    let claims = tokens.validate(&tokens.generate(&user).unwrap().access_token).unwrap();
+
+    assert!(versions.check(&claims).await.is_ok());
+    assert!(matches!(
+        versions.revoke_all(user.id).await,
+        Err(ApplicationError::Domain(DomainError::NotFound { .. }))
+    ));
+}
+
+#[tokio::test]
+async fn profile_updates_keep_the_token_version() {
+    let users = Arc::new(MockUserRepository::with_users([alice()]));
+    let stale = users.users().remove(0);
+    TokenVersions::new(users.clone()).revoke_all(stale.id).await.unwrap();
+
+    let updated = users.update(&User { username: "alice2".parse().unwrap(), ..stale }).await.unwrap();
+    assert_eq!(updated.token_version, 1);
+}
+
+#[tokio::test]
+async fn authenticating_a_raw_token_checks_its_version() {
+    // WebSocket connections authenticate this way, outside the HTTP middleware
+    let users = Arc::new(MockUserRepository::with_users([alice()]));
+    let user = users.users().remove(0);
+    let tokens = MockTokenService::new();
+    let versions = TokenVersions::new(users.clone());
+    let token = tokens.generate(&user).unwrap().access_token;
+
+    let claims = versions.authenticate(&tokens, &token).await.unwrap();
+    assert_eq!(claims.sub, user.id.to_string());
+
+    versions.revoke_all(user.id).await.unwrap();
+    assert!(is_unauthorized(versions.authenticate(&tokens, &token).await.map(|_| ())));
+    assert!(is_unauthorized(versions.authenticate(&tokens, "not-a-token").await.map(|_| ())));
+}
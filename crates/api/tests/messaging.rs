@@ -0,0 +1,180 @@
+//! Message broker port: envelopes, topic routing, consumer supervision and the domain event relay.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use application::event_schema::VersionedEvent;
+use application::messaging::{
+    event_message, spawn_consumer, spawn_event_relay, topic_matches, MessageConsumer, MessageEnvelope,
+    MessageHandler, MessagePublisher, RestartBackoff,
+};
+use application::{ApplicationError, EventBus};
+use async_trait::async_trait;
+use domain::{DomainError, DomainEvent};
+use infrastructure::{broker_from_settings, InMemoryBroker, InMemoryEventBus};
+use serde::{Deserialize, Serialize};
+use shared::{BrokerSettings, SubscriptionSettings};
+use uuid::Uuid;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Greeting {
+    name: String,
+}
+
+fn subscription(name: &str, topics: &[&str]) -> SubscriptionSettings {
+    SubscriptionSettings {
+        name: name.to_string(),
+        topics: topics.iter().map(|t| t.to_string()).collect(),
+    }
+}
+
+fn greeting(name: &str) -> MessageEnvelope {
+    MessageEnvelope::new("greeting", &Greeting { name: name.into() }).unwrap()
+}
+
+/// Records what it handled and fails the first `failures` messages
+#[derive(Default)]
+struct Recorder {
+    handled: Mutex<Vec<MessageEnvelope>>,
+    failures: AtomicUsize,
+}
+
+#[async_trait]
+impl MessageHandler for Recorder {
+    async fn handle(&self, message: MessageEnvelope) -> Result<(), ApplicationError> {
+        let failing = self.failures.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1));
+        if failing.is_ok() {
+            return Err(DomainError::unavailable("downstream is down").into());
+        }
+        self.handled.lock().unwrap().push(message);
+        Ok(())
+    }
+}
+
+impl Recorder {
+    async fn wait_for(&self, count: usize) -> Vec<MessageEnvelope> {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if self.handled.lock().unwrap().len() >= count {
+                    return self.handled.lock().unwrap().clone();
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap()
+    }
+}
+
+#[test]
+fn envelopes_round_trip_and_reject_garbage() {
+    let message = greeting("alice");
+    let decoded = MessageEnvelope::decode(&message.encode()).unwrap();
+    assert_eq!(decoded, message);
+    assert_eq!(decoded.payload_as::<Greeting>().unwrap(), Greeting { name: "alice".into() });
+
+    assert!(matches!(
+        MessageEnvelope::decode(b"not json"),
+        Err(ApplicationError::Domain(DomainError::Validation(_)))
+    ));
+    assert!(decoded.payload_as::<Vec<u32>>().is_err());
+}
+
+#[test]
+fn topics_match_amqp_patterns() {
+    assert!(topic_matches("events.user.registered", "events.user.registered"));
+    assert!(topic_matches("events.*.registered", "events.user.registered"));
+    assert!(!topic_matches("events.*", "events.user.registered"));
+    assert!(topic_matches("events.#", "events.user.registered"));
+    assert!(topic_matches("events.#", "events"));
+    assert!(topic_matches("#.registered", "events.user.registered"));
+    assert!(topic_matches("#", "anything.at.all"));
+    assert!(!topic_matches("events.user", "events.user.registered"));
+    assert!(!topic_matches("audit.#", "events.user.registered"));
+}
+
+#[tokio::test]
+async fn each_subscription_queues_the_topics_it_names() {
+    let broker = Arc::new(InMemoryBroker::new(vec![
+        subscription("users", &["events.user.*"]),
+        subscription("everything", &["#"]),
+    ]));
+    broker.publish("events.user.registered", None, &greeting("a")).await.unwrap();
+    broker.publish("events.file.quarantined", None, &greeting("b")).await.unwrap();
+    assert_eq!((broker.pending("users"), broker.pending("everything")), (1, 2));
+
+    let recorder = Arc::new(Recorder::default());
+    let consumer = spawn_consumer(broker.clone(), "users".into(), recorder.clone(), RestartBackoff::default());
+    let handled = recorder.wait_for(1).await;
+    assert_eq!(handled[0].payload_as::<Greeting>().unwrap().name, "a");
+    assert_eq!(broker.pending("users"), 0);
+    assert_eq!(broker.pending("everything"), 2);
+    consumer.abort();
+}
+
+#[tokio::test]
+async fn failed_messages_are_redelivered_after_a_restart() {
+    let broker = Arc::new(InMemoryBroker::new(vec![subscription("work", &["jobs.#"])]));
+    for name in ["first", "second"] {
+        broker.publish("jobs.greet", None, &greeting(name)).await.unwrap();
+    }
+
+    let recorder = Arc::new(Recorder {
+        failures: AtomicUsize::new(2),
+        ..Default::default()
+    });
+    let backoff = RestartBackoff {
+        initial: Duration::from_millis(10),
+        max: Duration::from_millis(50),
+    };
+    let consumer = spawn_consumer(broker.clone(), "work".into(), recorder.clone(), backoff);
+
+    // The first message failed twice and was retried, keeping its place
+    let handled = recorder.wait_for(2).await;
+    let names: Vec<String> = handled.iter().map(|m| m.payload_as::<Greeting>().unwrap().name).collect();
+    assert_eq!(names, ["first", "second"]);
+    assert_eq!(broker.pending("work"), 0);
+    consumer.abort();
+}
+
+#[tokio::test]
+async fn domain_events_are_relayed_by_name_and_user() {
+    let settings = BrokerSettings {
+        backend: "memory".into(),
+        subscriptions: vec![subscription("registrations", &["events.user.registered"])],
+        ..Default::default()
+    };
+    let broker = broker_from_settings(&settings).await.unwrap().unwrap();
+    let events = Arc::new(InMemoryEventBus::default());
+    spawn_event_relay(events.clone(), broker.publisher.clone());
+
+    let user_id = Uuid::new_v4();
+    events.publish(DomainEvent::UserLoggedIn { user_id });
+    events.publish(DomainEvent::UserRegistered {
+        user_id,
+        username: "alice".into(),
+        email: "alice@example.com".into(),
+    });
+
+    let recorder = Arc::new(Recorder::default());
+    let consumer = broker.spawn_consumer("registrations", recorder.clone());
+    let handled = recorder.wait_for(1).await;
+    assert_eq!(handled.len(), 1);
+    assert_eq!(handled[0].kind, "user.registered");
+    let event: VersionedEvent = handled[0].payload_as().unwrap();
+    assert_eq!(event.event["type"], "user_registered");
+    consumer.abort();
+
+    let (topic, key, _) = event_message(&events.events_since(0)[0]).unwrap();
+    assert_eq!((topic.as_str(), key), ("events.user.logged_in", Some(user_id.to_string())));
+}
+
+#[tokio::test]
+async fn unconfigured_brokers_are_skipped_and_unknown_subscriptions_fail() {
+    assert!(broker_from_settings(&BrokerSettings::default()).await.unwrap().is_none());
+
+    let broker = Arc::new(InMemoryBroker::new(Vec::new()));
+    let result = broker.consume("missing", Arc::new(Recorder::default())).await;
+    assert!(matches!(result, Err(ApplicationError::Domain(DomainError::NotFound { .. }))));
+}
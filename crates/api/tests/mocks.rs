@@ -0,0 +1,71 @@
+//! Unit-testing services against the `test-util` mocks.
+
+use std::sync::Arc;
+
+use application::activity::LoginClient;
+use application::testing::{MockPasswordHasher, MockTokenService, MockUserRepository};
+use application::{ApplicationError, AuthService, AuthServiceImpl, TokenService};
+use domain::{DomainError, Repository, User, UserFilter, UserRepository};
+use infrastructure::InMemoryEventBus;
+
+fn auth(users: Arc<MockUserRepository>, tokens: Arc<MockTokenService>) -> AuthServiceImpl {
+    AuthServiceImpl::new(
+        users,
+        Arc::new(MockPasswordHasher::new()),
+        tokens,
+        Arc::new(InMemoryEventBus::default()),
+    )
+}
+
+#[tokio::test]
+async fn registered_users_can_log_in() {
+    let users = Arc::new(MockUserRepository::new());
+    let tokens = Arc::new(MockTokenService::new());
+    let auth = auth(users.clone(), tokens.clone());
+
+    let alice = auth
+        .register("alice".into(), "alice@example.com".into(), "Correct-Horse-7".into())
+        .await
+        .unwrap();
+    assert_eq!(users.users().len(), 1);
+    assert!(matches!(
+        auth.register("alice2".into(), "alice@example.com".into(), "Correct-Horse-7".into()).await,
+        Err(ApplicationError::Domain(DomainError::Conflict { .. }))
+    ));
+
+    let token = auth.login("alice@example.com".into(), "Correct-Horse-7".into(), LoginClient::default()).await.unwrap();
+    assert_eq!(tokens.validate(&token.access_token).unwrap().sub, alice.id.to_string());
+    assert!(tokens.validate("forged").is_err());
+    assert!(auth.login("alice@example.com".into(), "wrong".into(), LoginClient::default()).await.is_err());
+
+    users.set_failing(true);
+    assert!(matches!(
+        auth.login("alice@example.com".into(), "Correct-Horse-7".into(), LoginClient::default()).await,
+        Err(ApplicationError::Domain(DomainError::Internal(_)))
+    ));
+}
+
+#[tokio::test]
+async fn mock_repository_enforces_versions_and_orders_matches() {
+    let users = MockUserRepository::with_users([
+        User::new("bobby".parse().unwrap(), "bobby@example.com".parse().unwrap(), String::new()),
+        User::new("bob".parse().unwrap(), "bob@example.com".parse().unwrap(), String::new()),
+    ]);
+    let bob = users.find_by_username("bob").await.unwrap().unwrap();
+
+    let updated = users.update(&bob).await.unwrap();
+    assert_eq!(updated.version, bob.version + 1);
+    assert!(matches!(
+        users.update(&bob).await,
+        Err(DomainError::Conflict { current_version: Some(_), .. })
+    ));
+
+    let names: Vec<String> = users
+        .autocomplete("BO", &UserFilter::default(), 10)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|u| u.username.into())
+        .collect();
+    assert_eq!(names, ["bob", "bobby"]);
+}
@@ -0,0 +1,151 @@
+//! In-app notifications: fan-out from domain events, unread counts and marking them read.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use application::notifications::{notification_for, spawn_event_notifications, NotificationService};
+use application::{ApplicationError, EventBus};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{DomainError, DomainEvent, Notification, NotificationRepository, Page, PaginationParams};
+use infrastructure::InMemoryEventBus;
+use uuid::Uuid;
+
+#[derive(Default)]
+struct MemoryNotifications {
+    notifications: Mutex<Vec<Notification>>,
+}
+
+#[async_trait]
+impl NotificationRepository for MemoryNotifications {
+    async fn create(&self, notification: &Notification) -> Result<Notification, DomainError> {
+        self.notifications.lock().unwrap().push(notification.clone());
+        Ok(notification.clone())
+    }
+
+    async fn list_for_user(&self, user_id: Uuid, unread_only: bool, params: &PaginationParams) -> Result<Page<Notification>, DomainError> {
+        let mut matching: Vec<Notification> = self
+            .notifications
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|n| n.user_id == user_id && !(unread_only && n.is_read()))
+            .cloned()
+            .collect();
+        matching.reverse();
+        let total = matching.len() as u64;
+        let items = matching.into_iter().skip(params.offset() as usize).take(params.limit() as usize).collect();
+        Ok(Page::new(items, total, params))
+    }
+
+    async fn unread_count(&self, user_id: Uuid) -> Result<u64, DomainError> {
+        let notifications = self.notifications.lock().unwrap();
+        Ok(notifications.iter().filter(|n| n.user_id == user_id && !n.is_read()).count() as u64)
+    }
+
+    async fn mark_read(&self, user_id: Uuid, id: Uuid, at: DateTime<Utc>) -> Result<Option<Notification>, DomainError> {
+        let mut notifications = self.notifications.lock().unwrap();
+        Ok(notifications.iter_mut().find(|n| n.id == id && n.user_id == user_id).map(|n| {
+            n.read_at.get_or_insert(at);
+            n.clone()
+        }))
+    }
+}
+
+fn service() -> (Arc<NotificationService>, Arc<InMemoryEventBus>) {
+    let events = Arc::new(InMemoryEventBus::default());
+    let service = NotificationService::new(Arc::new(MemoryNotifications::default()), events.clone());
+    (Arc::new(service), events)
+}
+
+fn registered(user_id: Uuid) -> DomainEvent {
+    DomainEvent::UserRegistered {
+        user_id,
+        username: "alice".into(),
+        email: "alice@example.com".into(),
+    }
+}
+
+#[tokio::test]
+async fn user_events_become_notifications_and_are_announced() {
+    let (service, events) = service();
+    let user = Uuid::new_v4();
+
+    let welcome = service.handle(&registered(user)).await.unwrap().unwrap();
+    assert_eq!((welcome.kind.as_str(), welcome.title.as_str()), ("welcome", "Welcome, alice!"));
+    match &events.events_since(0)[..] {
+        [envelope] => assert!(matches!(
+            &envelope.event,
+            DomainEvent::NotificationCreated { user_id, notification_id, kind, .. }
+                if *user_id == user && *notification_id == welcome.id && kind == "welcome"
+        )),
+        other => panic!("expected one event, got {}", other.len()),
+    }
+
+    // Sign-ins, lifecycle events and the announcements themselves notify nobody
+    assert!(service.handle(&DomainEvent::UserLoggedIn { user_id: user }).await.unwrap().is_none());
+    assert!(service.handle(&events.events_since(0)[0].event).await.unwrap().is_none());
+    let shutdown = DomainEvent::ShuttingDown { service: "api".into(), region: "local".into() };
+    assert!(notification_for(&shutdown).is_none());
+    assert_eq!(events.events_since(0).len(), 1);
+}
+
+#[tokio::test]
+async fn inbox_counts_unread_and_reads_are_kept() {
+    let (service, _) = service();
+    let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+    let mut ids = Vec::new();
+    for i in 0..3 {
+        ids.push(service.notify(alice, "test", format!("#{}", i), String::new()).await.unwrap().id);
+    }
+    service.notify(bob, "test", "for bob".into(), String::new()).await.unwrap();
+
+    let inbox = service.inbox(alice, false, &PaginationParams::new(1, 2)).await.unwrap();
+    assert_eq!((inbox.page.total, inbox.unread), (3, 3));
+    assert_eq!(inbox.page.items.iter().map(|n| n.title.as_str()).collect::<Vec<_>>(), ["#2", "#1"]);
+
+    let read = service.mark_read(alice, ids[2]).await.unwrap();
+    let first_read = read.read_at.unwrap();
+    let again = service.mark_read(alice, ids[2]).await.unwrap();
+    assert_eq!(again.read_at, Some(first_read));
+
+    let unread = service.inbox(alice, true, &PaginationParams::new(1, 20)).await.unwrap();
+    assert_eq!((unread.page.total, unread.unread), (2, 2));
+    assert!(unread.page.items.iter().all(|n| !n.is_read()));
+
+    // Someone else's notification does not exist for alice
+    let bobs = service.inbox(bob, false, &PaginationParams::new(1, 20)).await.unwrap().page.items[0].id;
+    assert!(matches!(
+        service.mark_read(alice, bobs).await,
+        Err(ApplicationError::Domain(DomainError::NotFound { .. }))
+    ));
+    assert_eq!(service.inbox(bob, false, &PaginationParams::new(1, 20)).await.unwrap().unread, 1);
+}
+
+#[tokio::test]
+async fn published_events_reach_the_inbox() {
+    let (service, events) = service();
+    let user = Uuid::new_v4();
+    spawn_event_notifications(events.clone(), service.clone());
+    let mut announcements = events.subscribe();
+
+    events.publish(DomainEvent::FileQuarantined {
+        user_id: user,
+        purpose: "avatar".into(),
+        signature: "Eicar-Test-Signature".into(),
+    });
+    let announced = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            if let DomainEvent::NotificationCreated { kind, .. } = announcements.recv().await.unwrap().event {
+                return kind;
+            }
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(announced, "file_quarantined");
+
+    let inbox = service.inbox(user, true, &PaginationParams::new(1, 20)).await.unwrap();
+    assert_eq!(inbox.unread, 1);
+    assert_eq!(inbox.page.items[0].title, "Upload blocked");
+}
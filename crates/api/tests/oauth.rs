@@ -0,0 +1,81 @@
+//! OAuth state/PKCE bookkeeping: redirect allowlist, one-time states, nonces.
+
+use std::time::Duration;
+
+use api::session::{CookieOAuthStateStore, CookieSessions};
+use application::oauth::{pkce_challenge, OAuthFlow, OAuthStateStore};
+use application::ApplicationError;
+use domain::DomainError;
+use infrastructure::InMemoryOAuthStateStore;
+use tower_cookies::Cookies;
+
+const CALLBACK: &str = "https://app.example.com/auth/callback";
+
+fn flow() -> OAuthFlow {
+    OAuthFlow::new([CALLBACK])
+}
+
+async fn round_trip(store: &dyn OAuthStateStore) {
+    let flow = flow();
+    let request = flow.begin(store, "google", CALLBACK).await.unwrap();
+    assert_eq!(request.code_challenge_method, "S256");
+    assert_eq!(request.state.len(), 43);
+
+    let pending = flow.complete(store, &request.state).await.unwrap();
+    assert_eq!(pending.provider, "google");
+    assert_eq!(pending.redirect_uri, CALLBACK);
+    assert_eq!(pkce_challenge(&pending.code_verifier), request.code_challenge);
+    pending.verify_nonce(Some(&request.nonce)).unwrap();
+
+    let replay = flow.complete(store, &request.state).await;
+    assert!(matches!(replay, Err(ApplicationError::Domain(DomainError::Unauthorized(_)))));
+}
+
+#[tokio::test]
+async fn states_are_single_use_in_memory_and_in_cookies() {
+    round_trip(&InMemoryOAuthStateStore::new()).await;
+    let sessions = CookieSessions::new(b"0123456789abcdef0123456789abcdef");
+    round_trip(&CookieOAuthStateStore::new(sessions, Cookies::default())).await;
+}
+
+#[tokio::test]
+async fn unknown_and_expired_states_are_rejected() {
+    let store = InMemoryOAuthStateStore::new();
+    let flow = flow().with_ttl(Duration::ZERO);
+    let request = flow.begin(&store, "github", CALLBACK).await.unwrap();
+    assert!(flow.complete(&store, &request.state).await.is_err());
+    assert!(flow.complete(&store, "made-up").await.is_err());
+    assert!(flow.complete(&store, "").await.is_err());
+}
+
+#[tokio::test]
+async fn redirect_uris_must_match_the_allowlist_exactly() {
+    let store = InMemoryOAuthStateStore::new();
+    for uri in [
+        "https://evil.example.com/auth/callback",
+        "https://app.example.com/auth/callback/../../steal",
+        "https://app.example.com/auth/callback?next=https://evil.example.com",
+        "http://app.example.com/auth/callback",
+    ] {
+        let result = flow().begin(&store, "google", uri).await;
+        assert!(matches!(result, Err(ApplicationError::Domain(DomainError::Validation(_)))), "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn id_token_nonce_must_match() {
+    let store = InMemoryOAuthStateStore::new();
+    let flow = flow();
+    let request = flow.begin(&store, "google", CALLBACK).await.unwrap();
+    let pending = flow.complete(&store, &request.state).await.unwrap();
+    assert!(pending.verify_nonce(None).is_err());
+    assert!(pending.verify_nonce(Some("another-nonce")).is_err());
+}
+
+#[test]
+fn pkce_challenge_is_base64url_sha256() {
+    assert_eq!(
+        pkce_challenge("verifier-0123456789-0123456789-0123456789-abc"),
+        "uE2T3AGHOnS-NjpxOKWgYVLWgRsEsgk2csQmWWiPzr8"
+    );
+}
@@ -0,0 +1,360 @@
+//! Organizations: owner/admin/member permissions, the last-owner rule and emailed invitations.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use application::email::{EmailJobPayload, EmailTemplate, SendEmailJob};
+use application::organizations::{Invitation, InvitationStore, OrganizationService};
+use application::testing::{MockUserRepository, RecordingJobQueue};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{
+    DomainError, Membership, MembershipRepository, OrgRole, Organization, OrganizationRepository, Page,
+    PaginationParams, Repository, User,
+};
+use uuid::Uuid;
+
+// ============================================================================
+// In-memory adapters
+// ============================================================================
+
+#[derive(Default)]
+struct Store {
+    organizations: Mutex<HashMap<Uuid, Organization>>,
+    members: Mutex<Vec<Membership>>,
+    invitations: Mutex<Vec<Invitation>>,
+}
+
+#[async_trait]
+impl Repository<Organization> for Store {
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Organization>, DomainError> {
+        Ok(self.organizations.lock().unwrap().get(&id).cloned())
+    }
+
+    async fn find_all(&self, params: &PaginationParams) -> Result<Page<Organization>, DomainError> {
+        let items: Vec<Organization> = self.organizations.lock().unwrap().values().cloned().collect();
+        let total = items.len() as u64;
+        Ok(Page::new(items, total, params))
+    }
+
+    async fn create(&self, organization: &Organization) -> Result<Organization, DomainError> {
+        self.organizations.lock().unwrap().insert(organization.id, organization.clone());
+        Ok(organization.clone())
+    }
+
+    async fn update(&self, organization: &Organization) -> Result<Organization, DomainError> {
+        self.organizations.lock().unwrap().insert(organization.id, organization.clone());
+        Ok(organization.clone())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, DomainError> {
+        self.members.lock().unwrap().retain(|m| m.organization_id != id);
+        self.invitations.lock().unwrap().retain(|i| i.organization_id != id);
+        Ok(self.organizations.lock().unwrap().remove(&id).is_some())
+    }
+
+    async fn count(&self) -> Result<u64, DomainError> {
+        Ok(self.organizations.lock().unwrap().len() as u64)
+    }
+}
+
+#[async_trait]
+impl OrganizationRepository for Store {
+    async fn create_with_owner(&self, organization: &Organization, owner: &Membership) -> Result<Organization, DomainError> {
+        self.members.lock().unwrap().push(owner.clone());
+        Repository::create(self, organization).await
+    }
+
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<(Organization, OrgRole)>, DomainError> {
+        let organizations = self.organizations.lock().unwrap();
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| m.user_id == user_id)
+            .filter_map(|m| organizations.get(&m.organization_id).map(|o| (o.clone(), m.role)))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl MembershipRepository for Store {
+    async fn find(&self, organization_id: Uuid, user_id: Uuid) -> Result<Option<Membership>, DomainError> {
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|m| m.organization_id == organization_id && m.user_id == user_id)
+            .cloned())
+    }
+
+    async fn list(&self, organization_id: Uuid, params: &PaginationParams) -> Result<Page<Membership>, DomainError> {
+        let mut items: Vec<Membership> =
+            self.members.lock().unwrap().iter().filter(|m| m.organization_id == organization_id).cloned().collect();
+        items.sort_by_key(|m| std::cmp::Reverse(m.role));
+        let total = items.len() as u64;
+        Ok(Page::new(items, total, params))
+    }
+
+    async fn add(&self, membership: &Membership) -> Result<Membership, DomainError> {
+        if self.find(membership.organization_id, membership.user_id).await?.is_some() {
+            return Err(DomainError::conflict("Membership already exists"));
+        }
+        self.members.lock().unwrap().push(membership.clone());
+        Ok(membership.clone())
+    }
+
+    async fn set_role(&self, organization_id: Uuid, user_id: Uuid, role: OrgRole) -> Result<Option<Membership>, DomainError> {
+        let mut members = self.members.lock().unwrap();
+        let member = members.iter_mut().find(|m| m.organization_id == organization_id && m.user_id == user_id);
+        Ok(member.map(|m| {
+            m.role = role;
+            m.clone()
+        }))
+    }
+
+    async fn remove(&self, organization_id: Uuid, user_id: Uuid) -> Result<bool, DomainError> {
+        let mut members = self.members.lock().unwrap();
+        let before = members.len();
+        members.retain(|m| !(m.organization_id == organization_id && m.user_id == user_id));
+        Ok(members.len() < before)
+    }
+
+    async fn count_owners(&self, organization_id: Uuid) -> Result<u64, DomainError> {
+        Ok(self
+            .members
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| m.organization_id == organization_id && m.role == OrgRole::Owner)
+            .count() as u64)
+    }
+}
+
+#[async_trait]
+impl InvitationStore for Store {
+    async fn create(&self, invitation: &Invitation) -> Result<(), ApplicationError> {
+        self.invitations.lock().unwrap().push(invitation.clone());
+        Ok(())
+    }
+
+    async fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<Invitation>, ApplicationError> {
+        Ok(self.invitations.lock().unwrap().iter().find(|i| i.token_hash == token_hash).cloned())
+    }
+
+    async fn list_pending(&self, organization_id: Uuid, now: DateTime<Utc>) -> Result<Vec<Invitation>, ApplicationError> {
+        Ok(self
+            .invitations
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|i| i.organization_id == organization_id && i.is_pending(now))
+            .cloned()
+            .collect())
+    }
+
+    async fn claim(&self, id: Uuid, now: DateTime<Utc>) -> Result<bool, ApplicationError> {
+        let mut invitations = self.invitations.lock().unwrap();
+        match invitations.iter_mut().find(|i| i.id == id && i.is_pending(now)) {
+            Some(invitation) => {
+                invitation.accepted_at = Some(now);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    async fn delete(&self, organization_id: Uuid, id: Uuid) -> Result<bool, ApplicationError> {
+        let mut invitations = self.invitations.lock().unwrap();
+        let before = invitations.len();
+        invitations.retain(|i| !(i.organization_id == organization_id && i.id == id));
+        Ok(invitations.len() < before)
+    }
+}
+
+// ============================================================================
+// Fixture
+// ============================================================================
+
+struct Fixture {
+    service: OrganizationService,
+    store: Arc<Store>,
+    queue: Arc<RecordingJobQueue>,
+    owner: User,
+    alice: User,
+    bob: User,
+}
+
+fn fixture() -> Fixture {
+    let owner = User::new("owner".parse().unwrap(), "owner@example.com".parse().unwrap(), "hash".into());
+    let alice = User::new("alice".parse().unwrap(), "alice@example.com".parse().unwrap(), "hash".into());
+    let bob = User::new("bob".parse().unwrap(), "bob@example.com".parse().unwrap(), "hash".into());
+    let users = Arc::new(MockUserRepository::with_users([owner.clone(), alice.clone(), bob.clone()]));
+    let store = Arc::new(Store::default());
+    let queue = Arc::new(RecordingJobQueue::new());
+    Fixture {
+        service: OrganizationService::new(store.clone(), store.clone(), store.clone(), users, queue.clone())
+            .with_invitation_url("https://app.example.com/invitations/"),
+        store,
+        queue,
+        owner,
+        alice,
+        bob,
+    }
+}
+
+impl Fixture {
+    async fn organization(&self) -> Organization {
+        self.service.create(self.owner.id, "  Acme  ".to_string()).await.unwrap()
+    }
+
+    /// Invite `user` and return the token from the emailed link
+    /// The last invitation email queued
+    fn sent_email(&self) -> EmailJobPayload {
+        let (payload, _) = self.queue.take(SendEmailJob::KIND).pop().unwrap();
+        serde_json::from_value(payload).unwrap()
+    }
+
+    async fn invite(&self, organization_id: Uuid, user: &User, role: OrgRole) -> String {
+        self.service.invite(self.owner.id, organization_id, &user.email, role).await.unwrap();
+        let email = self.sent_email();
+        let url = email.vars["accept_url"].as_str().unwrap().to_string();
+        url.rsplit('/').next().unwrap().to_string()
+    }
+
+    async fn join(&self, organization_id: Uuid, user: &User, role: OrgRole) {
+        let token = self.invite(organization_id, user, role).await;
+        self.service.accept_invitation(user.id, &token).await.unwrap();
+    }
+}
+
+fn is_validation(result: Result<impl std::fmt::Debug, ApplicationError>) -> bool {
+    matches!(result, Err(ApplicationError::Domain(DomainError::Validation(_))))
+}
+
+fn is_forbidden(result: Result<impl std::fmt::Debug, ApplicationError>) -> bool {
+    matches!(result, Err(ApplicationError::Domain(DomainError::Forbidden(_))))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[tokio::test]
+async fn the_creator_owns_the_organization_and_outsiders_cannot_see_it() {
+    let f = fixture();
+    let organization = f.organization().await;
+
+    assert_eq!(organization.name, "Acme");
+    let (_, role) = f.service.get(f.owner.id, organization.id).await.unwrap();
+    assert_eq!(role, OrgRole::Owner);
+    assert_eq!(f.service.list(f.owner.id).await.unwrap().len(), 1);
+
+    assert!(f.service.list(f.alice.id).await.unwrap().is_empty());
+    let outsider = f.service.get(f.alice.id, organization.id).await;
+    assert!(matches!(outsider, Err(ApplicationError::Domain(DomainError::NotFound { .. }))));
+}
+
+#[tokio::test]
+async fn invitations_are_emailed_and_accepted_once_by_the_invited_address() {
+    let f = fixture();
+    let organization = f.organization().await;
+
+    f.service.invite(f.owner.id, organization.id, "Alice@Example.com", OrgRole::Admin).await.unwrap();
+    let email = f.sent_email();
+    assert_eq!(email.to, "alice@example.com");
+    assert_eq!(email.template, EmailTemplate::OrganizationInvitation);
+    assert_eq!(email.vars["organization"], "Acme");
+    assert_eq!(email.vars["inviter"], "owner");
+    let url = email.vars["accept_url"].as_str().unwrap();
+    assert!(url.starts_with("https://app.example.com/invitations/"), "{}", url);
+    let token = url.rsplit('/').next().unwrap();
+
+    let pending = f.service.invitations(f.owner.id, organization.id).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_ne!(pending[0].token_hash, token, "only the hash is stored");
+
+    assert!(is_forbidden(f.service.accept_invitation(f.bob.id, token).await), "another address");
+    let membership = f.service.accept_invitation(f.alice.id, token).await.unwrap();
+    assert_eq!(membership.role, OrgRole::Admin);
+
+    let reused = f.service.accept_invitation(f.alice.id, token).await;
+    assert!(matches!(reused, Err(ApplicationError::Domain(DomainError::NotFound { .. }))));
+    assert!(f.service.invitations(f.owner.id, organization.id).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn expired_and_revoked_invitations_cannot_be_accepted() {
+    let f = fixture();
+    let organization = f.organization().await;
+
+    let token = f.invite(organization.id, &f.alice, OrgRole::Member).await;
+    f.store.invitations.lock().unwrap()[0].expires_at = Utc::now() - chrono::Duration::seconds(1);
+    assert!(f.service.accept_invitation(f.alice.id, &token).await.is_err());
+
+    let token = f.invite(organization.id, &f.bob, OrgRole::Member).await;
+    let id = f.service.invitations(f.owner.id, organization.id).await.unwrap()[0].id;
+    f.service.revoke_invitation(f.owner.id, organization.id, id).await.unwrap();
+    assert!(f.service.accept_invitation(f.bob.id, &token).await.is_err());
+}
+
+#[tokio::test]
+async fn members_cannot_be_invited_twice() {
+    let f = fixture();
+    let organization = f.organization().await;
+    f.join(organization.id, &f.alice, OrgRole::Member).await;
+
+    let again = f.service.invite(f.owner.id, organization.id, &f.alice.email, OrgRole::Member).await;
+    assert!(matches!(again, Err(ApplicationError::Domain(DomainError::Conflict { .. }))));
+}
+
+#[tokio::test]
+async fn roles_limit_what_members_and_admins_may_change() {
+    let f = fixture();
+    let organization = f.organization().await;
+    f.join(organization.id, &f.alice, OrgRole::Admin).await;
+    f.join(organization.id, &f.bob, OrgRole::Member).await;
+    let id = organization.id;
+
+    // Members only look
+    assert!(is_forbidden(f.service.rename(f.bob.id, id, "Bob's".into()).await));
+    assert!(is_forbidden(f.service.invite(f.bob.id, id, "carol@example.com", OrgRole::Member).await));
+    assert!(is_forbidden(f.service.remove_member(f.bob.id, id, f.alice.id).await));
+    assert_eq!(f.service.members(f.bob.id, id, &PaginationParams::default()).await.unwrap().total, 3);
+
+    // Admins manage members and admins, but not owners
+    f.service.rename(f.alice.id, id, "Acme Corp".into()).await.unwrap();
+    assert!(is_forbidden(f.service.invite(f.alice.id, id, "carol@example.com", OrgRole::Owner).await));
+    assert!(is_forbidden(f.service.change_role(f.alice.id, id, f.bob.id, OrgRole::Owner).await));
+    assert!(is_forbidden(f.service.remove_member(f.alice.id, id, f.owner.id).await));
+    assert!(is_forbidden(f.service.delete(f.alice.id, id).await));
+    let promoted = f.service.change_role(f.alice.id, id, f.bob.id, OrgRole::Admin).await.unwrap();
+    assert_eq!(promoted.role, OrgRole::Admin);
+    f.service.remove_member(f.alice.id, id, f.bob.id).await.unwrap();
+
+    // Anyone may leave
+    f.service.remove_member(f.alice.id, id, f.alice.id).await.unwrap();
+    assert_eq!(f.service.members(f.owner.id, id, &PaginationParams::default()).await.unwrap().total, 1);
+}
+
+#[tokio::test]
+async fn the_last_owner_cannot_step_down_or_leave() {
+    let f = fixture();
+    let organization = f.organization().await;
+    let id = organization.id;
+
+    assert!(is_validation(f.service.change_role(f.owner.id, id, f.owner.id, OrgRole::Admin).await));
+    assert!(is_validation(f.service.remove_member(f.owner.id, id, f.owner.id).await));
+
+    f.join(id, &f.alice, OrgRole::Member).await;
+    f.service.change_role(f.owner.id, id, f.alice.id, OrgRole::Owner).await.unwrap();
+    f.service.remove_member(f.owner.id, id, f.owner.id).await.unwrap();
+
+    let (_, role) = f.service.get(f.alice.id, id).await.unwrap();
+    assert_eq!(role, OrgRole::Owner);
+    f.service.delete(f.alice.id, id).await.unwrap();
+    assert!(f.service.list(f.alice.id).await.unwrap().is_empty());
+}
@@ -0,0 +1,105 @@
+//! Pagination: deep offsets are rejected before they reach a query, list responses link to other pages
+//! and share one documented body.
+
+use axum::http::{header, StatusCode, Uri};
+use axum::response::IntoResponse;
+use utoipa::openapi::{RefOr, Schema};
+use utoipa::OpenApi;
+
+use api::error::ApiError;
+use api::pagination::{PageLinks, Paginated, PaginatedUserResponse};
+use api::users::UserResponse;
+use application::testing::MockUserRepository;
+use domain::{DomainError, Page, PaginationParams, Repository, User, DEFAULT_MAX_OFFSET};
+
+#[test]
+fn offsets_up_to_the_maximum_are_accepted() {
+    assert!(PaginationParams::new(1, 20).validate_offset(0).is_ok());
+    assert!(PaginationParams::new(51, 20).validate_offset(1000).is_ok());
+
+    let Err(DomainError::Validation(message)) = PaginationParams::new(52, 20).validate_offset(1000) else {
+        panic!("expected offset 1020 to be rejected");
+    };
+    assert!(message.contains("1020") && message.contains("filters"), "{}", message);
+
+    // page * per_page saturates instead of wrapping back under the limit
+    assert!(PaginationParams { page: u32::MAX, per_page: u32::MAX }.validate_offset(1000).is_err());
+}
+
+#[tokio::test]
+async fn deep_pages_are_a_bad_request() {
+    let users = MockUserRepository::with_users([User::new("alice".parse().unwrap(), "alice@example.com".parse().unwrap(), String::new())]);
+    let last_page = DEFAULT_MAX_OFFSET / 20 + 1;
+    assert!(users.find_all(&PaginationParams::new(last_page, 20)).await.unwrap().items.is_empty());
+
+    let err = users.find_all(&PaginationParams::new(last_page + 1, 20)).await.unwrap_err();
+    assert_eq!(ApiError::from(err).into_response().status(), StatusCode::BAD_REQUEST);
+}
+
+fn link_header(uri: &str, page: &Page<()>) -> String {
+    let response = (PageLinks::new(&uri.parse::<Uri>().unwrap(), page), "").into_response();
+    response.headers()[header::LINK].to_str().unwrap().to_string()
+}
+
+#[test]
+fn pages_know_their_neighbours() {
+    let page = Page::<()>::new(Vec::new(), 45, &PaginationParams::new(2, 20));
+    assert!(page.has_prev && page.has_next);
+
+    let last = Page::<()>::new(Vec::new(), 45, &PaginationParams::new(3, 20));
+    assert!(last.has_prev && !last.has_next);
+
+    let empty = Page::<()>::new(Vec::new(), 0, &PaginationParams::new(1, 20));
+    assert!(!empty.has_prev && !empty.has_next);
+}
+
+#[test]
+fn link_header_keeps_filters_and_replaces_paging() {
+    let page = Page::<()>::new(Vec::new(), 45, &PaginationParams::new(2, 20));
+    assert_eq!(
+        link_header("/api/v1/admin/users?q=jo&page=2&tags=vip,beta&per_page=20", &page),
+        "</api/v1/admin/users?q=jo&tags=vip,beta&page=1&per_page=20>; rel=\"first\", \
+         </api/v1/admin/users?q=jo&tags=vip,beta&page=1&per_page=20>; rel=\"prev\", \
+         </api/v1/admin/users?q=jo&tags=vip,beta&page=3&per_page=20>; rel=\"next\", \
+         </api/v1/admin/users?q=jo&tags=vip,beta&page=3&per_page=20>; rel=\"last\""
+    );
+
+    // No prev on the first page, no next on the last; an empty list still has one page
+    let empty = Page::<()>::new(Vec::new(), 0, &PaginationParams::new(1, 20));
+    assert_eq!(
+        link_header("/users", &empty),
+        "</users?page=1&per_page=20>; rel=\"first\", </users?page=1&per_page=20>; rel=\"last\""
+    );
+}
+
+#[test]
+fn list_bodies_convert_the_page_items() {
+    let user = User::new("alice".parse().unwrap(), "alice@example.com".parse().unwrap(), String::new());
+    let page = Page::new(vec![user], 41, &PaginationParams::new(2, 20));
+
+    let body: Paginated<UserResponse> = Paginated::from(page);
+    assert_eq!(body.items[0].username, "alice");
+    assert_eq!((body.total, body.page, body.total_pages), (41, 2, 3));
+    assert!(body.has_next && body.has_prev);
+}
+
+#[test]
+fn every_list_body_is_documented_from_one_generic_schema() {
+    #[derive(OpenApi)]
+    #[openapi(components(schemas(PaginatedUserResponse)))]
+    struct Doc;
+
+    let schemas = Doc::openapi().components.unwrap().schemas;
+    let names: Vec<_> = schemas.keys().map(String::as_str).collect();
+    for name in ["AdminUsersResponse", "MembersResponse", "NotesResponse", "PaginatedUserResponse", "WebhooksResponse"] {
+        assert!(names.contains(&name), "{} missing from {:?}", name, names);
+    }
+    assert!(!names.contains(&"Paginated"));
+
+    let Some(RefOr::T(Schema::Object(users))) = schemas.get("PaginatedUserResponse") else {
+        panic!("expected an object schema");
+    };
+    let items = serde_json::to_value(&users.properties["items"]).unwrap();
+    assert_eq!(items["items"]["$ref"], "#/components/schemas/UserResponse");
+    assert!(users.required.contains(&"has_next".to_string()));
+}
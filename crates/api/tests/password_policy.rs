@@ -0,0 +1,46 @@
+//! Password policy rules and strength estimates.
+
+use application::password_policy::PasswordPolicy;
+
+fn rules(policy: &PasswordPolicy, password: &str, user_inputs: &[&str]) -> Vec<&'static str> {
+    policy.check(password, user_inputs).into_iter().map(|v| v.rule).collect()
+}
+
+#[test]
+fn every_failed_rule_is_reported() {
+    let policy = PasswordPolicy {
+        min_length: 12,
+        require_uppercase: true,
+        require_digit: true,
+        require_symbol: true,
+        ..PasswordPolicy::default()
+    };
+    assert_eq!(
+        rules(&policy, "password", &[]),
+        ["min_length", "uppercase", "digit", "symbol", "common", "strength"]
+    );
+    assert!(rules(&policy, "Correct-Horse-Battery-9", &[]).is_empty());
+}
+
+#[test]
+fn common_and_banned_passwords_are_rejected() {
+    let mut policy = PasswordPolicy {
+        min_score: 0,
+        ..PasswordPolicy::default()
+    };
+    assert_eq!(rules(&policy, "P@ssw0rd", &[]), ["common"]);
+    policy.banned.insert("acmecorp2024".to_string());
+    assert_eq!(rules(&policy, "AcmeCorp2024", &[]), ["common"]);
+}
+
+#[test]
+fn patterns_and_user_inputs_lower_the_score() {
+    let policy = PasswordPolicy::default();
+    for weak in ["qwertyuiop", "abcdefgh123", "aaaaaaaaaaaa", "monkey1234"] {
+        assert!(policy.score(weak, &[]) < 2, "{} scored {}", weak, policy.score(weak, &[]));
+    }
+    assert!(policy.score("jdoe-jdoe-jdoe", &["jdoe", "jdoe@example.com"]) < 2);
+    assert!(policy.score("jdoe-jdoe-jdoe", &[]) >= 2);
+    assert_eq!(policy.score("vT8#qL2!mZ9@", &[]), 4);
+    assert!(policy.score("tangerine velvet orbit", &[]) >= 3);
+}
@@ -0,0 +1,34 @@
+//! Online/offline transitions across several connections.
+
+use std::sync::Arc;
+
+use application::presence::{PresenceStatus, PresenceTracker};
+use infrastructure::InMemoryPresenceStore;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn only_first_connect_and_last_disconnect_are_announced() {
+    let tracker = PresenceTracker::new(Arc::new(InMemoryPresenceStore::new()));
+    let mut changes = tracker.subscribe();
+    let user = Uuid::new_v4();
+
+    assert_eq!(tracker.presence(user).await.unwrap().status, PresenceStatus::Offline);
+    assert!(tracker.presence(user).await.unwrap().last_seen.is_none());
+
+    tracker.heartbeat(user, "phone").await.unwrap();
+    tracker.heartbeat(user, "laptop").await.unwrap();
+    tracker.heartbeat(user, "phone").await.unwrap();
+    let change = changes.try_recv().unwrap();
+    assert_eq!((change.user_id, change.status), (user, PresenceStatus::Online));
+    assert!(changes.try_recv().is_err());
+
+    tracker.disconnected(user, "phone").await.unwrap();
+    assert!(changes.try_recv().is_err());
+    assert_eq!(tracker.presence(user).await.unwrap().status, PresenceStatus::Online);
+
+    tracker.disconnected(user, "laptop").await.unwrap();
+    assert_eq!(changes.try_recv().unwrap().status, PresenceStatus::Offline);
+    let presence = tracker.presence(user).await.unwrap();
+    assert_eq!(presence.status, PresenceStatus::Offline);
+    assert!(presence.last_seen.is_some());
+}
@@ -0,0 +1,84 @@
+//! Query statistics: latency histograms per query shape, slow-query threshold and the admin view.
+
+use std::time::Duration;
+
+use api::admin::QueryStatsResponse;
+use application::query_stats::{QueryStatsRegistry, LATENCY_BUCKETS_MS, MAX_TRACKED_QUERIES};
+
+const SELECT: &str = "SELECT id FROM users WHERE id = ?";
+const UPDATE: &str = "UPDATE users SET status = ? WHERE id = ?";
+
+fn ms(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn calls_are_bucketed_per_fingerprint() {
+    let registry = QueryStatsRegistry::default();
+    for elapsed in [1, 3, 3, 4, 8, 40, 7000] {
+        registry.record("a1", SELECT, ms(elapsed), false);
+    }
+    registry.record("b2", UPDATE, ms(2), true);
+
+    let queries = registry.snapshot();
+    assert_eq!(queries.iter().map(|q| q.fingerprint.as_str()).collect::<Vec<_>>(), ["a1", "b2"]);
+    let select = &queries[0];
+    assert_eq!((select.calls, select.errors, select.max), (7, 0, ms(7000)));
+    assert_eq!(select.total, ms(7059));
+    assert_eq!(select.mean(), ms(1008) + Duration::from_nanos(428_571));
+    // 1 | 2 | 5 | 10 | 25 | 50 | ... | 5000 | overflow
+    assert_eq!(select.buckets[..6], [1, 0, 3, 1, 0, 1]);
+    assert_eq!(select.buckets[LATENCY_BUCKETS_MS.len()], 1);
+    assert_eq!(select.percentile(0.5), ms(5));
+    assert_eq!(select.percentile(0.8), ms(50));
+    assert_eq!(select.percentile(0.99), ms(7000));
+    assert_eq!((queries[1].calls, queries[1].errors), (1, 1));
+    // A bucket bound above every call is capped at the slowest one
+    assert_eq!(queries[1].percentile(0.5), ms(2));
+
+    registry.reset();
+    assert!(registry.snapshot().is_empty());
+}
+
+#[test]
+fn only_calls_over_the_threshold_are_slow() {
+    let registry = QueryStatsRegistry::default();
+    assert!(!registry.record("a1", SELECT, ms(10_000), false));
+
+    registry.set_slow_threshold(Some(ms(500)));
+    assert_eq!(registry.slow_threshold(), Some(ms(500)));
+    assert!(!registry.record("a1", SELECT, ms(500), false));
+    assert!(registry.record("a1", SELECT, ms(501), false));
+
+    registry.set_slow_threshold(None);
+    assert!(!registry.record("a1", SELECT, ms(501), false));
+}
+
+#[test]
+fn new_shapes_past_the_limit_are_counted_but_not_tracked() {
+    let registry = QueryStatsRegistry::default();
+    for i in 0..MAX_TRACKED_QUERIES {
+        registry.record(&format!("{:016x}", i), SELECT, ms(1), false);
+    }
+    registry.record("overflow", SELECT, ms(1), false);
+    registry.record("0000000000000000", SELECT, ms(1), false);
+
+    assert_eq!(registry.snapshot().len(), MAX_TRACKED_QUERIES);
+    assert_eq!(registry.untracked(), 1);
+}
+
+#[test]
+fn responses_carry_milliseconds_and_bucket_bounds() {
+    let registry = QueryStatsRegistry::default();
+    registry.record("a1", SELECT, Duration::from_micros(1500), false);
+    registry.record("a1", SELECT, ms(6000), false);
+
+    let response = QueryStatsResponse::from(registry.snapshot().remove(0));
+    assert_eq!((response.calls, response.total_ms, response.max_ms), (2, 6001.5, 6000.0));
+    assert_eq!((response.p50_ms, response.p99_ms), (2.0, 6000.0));
+    assert_eq!(response.histogram.len(), LATENCY_BUCKETS_MS.len() + 1);
+    assert_eq!((response.histogram[1].le_ms, response.histogram[1].count), (Some(2), 1));
+    let last = response.histogram.last().unwrap();
+    assert_eq!((last.le_ms, last.count), (None, 1));
+    assert_eq!(response.statement, SELECT);
+}
@@ -0,0 +1,39 @@
+//! Query fingerprints recorded on `db.query` spans carry the statement's shape, never its values.
+
+use infrastructure::{normalize_query, query_fingerprint};
+
+#[test]
+fn literals_and_bind_parameters_are_replaced() {
+    let sql = r#"
+        SELECT id, username FROM users
+        WHERE email = $1 AND note = 'it''s secret' AND age > 42
+        LIMIT $2 OFFSET 3.5
+    "#;
+    assert_eq!(
+        normalize_query(sql),
+        "SELECT id, username FROM users WHERE email = ? AND note = ? AND age > ? LIMIT ? OFFSET ?"
+    );
+
+    // Digits inside identifiers and casts are part of the shape
+    assert_eq!(normalize_query("SELECT md5(x)::int4 FROM t1"), "SELECT md5(x)::int4 FROM t1");
+}
+
+#[test]
+fn value_lists_collapse_to_one_placeholder() {
+    assert_eq!(normalize_query("WHERE id IN ($1, $2, $3)"), "WHERE id IN (?)");
+    assert_eq!(normalize_query("WHERE id IN ($1,$2)"), "WHERE id IN (?)");
+    assert_eq!(normalize_query("VALUES ('a', 1, $3)"), "VALUES (?)");
+}
+
+#[test]
+fn fingerprints_group_queries_that_differ_only_in_values() {
+    let a = query_fingerprint(&normalize_query("SELECT * FROM users WHERE id IN ($1, $2)"));
+    let b = query_fingerprint(&normalize_query("SELECT *  FROM users\n WHERE id IN ($1, $2, $3, $4)"));
+    let c = query_fingerprint(&normalize_query("SELECT * FROM tenants WHERE id IN ($1, $2)"));
+
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+    assert_eq!(a.len(), 16);
+    // FNV-1a of the empty string: stable across builds and processes
+    assert_eq!(query_fingerprint(""), "cbf29ce484222325");
+}
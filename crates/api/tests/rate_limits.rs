@@ -0,0 +1,139 @@
+//! Rate limit administration: usage per subject, counter resets and temporary overrides.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use application::rate_limits::{ManagedRateLimiter, RateLimitOverride, RateLimitOverrideStore, RateLimitSubject};
+use application::{ApplicationError, RateLimiter};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use infrastructure::InMemoryRateLimiter;
+use uuid::Uuid;
+
+const HOUR: Duration = Duration::from_secs(3600);
+
+#[derive(Default)]
+struct MemoryOverrides {
+    overrides: Mutex<HashMap<Uuid, RateLimitOverride>>,
+}
+
+#[async_trait]
+impl RateLimitOverrideStore for MemoryOverrides {
+    async fn create(&self, entry: &RateLimitOverride) -> Result<(), ApplicationError> {
+        self.overrides.lock().unwrap().insert(entry.id, entry.clone());
+        Ok(())
+    }
+
+    async fn list_active(&self, now: DateTime<Utc>) -> Result<Vec<RateLimitOverride>, ApplicationError> {
+        let mut active: Vec<_> = self.overrides.lock().unwrap().values().filter(|o| o.expires_at > now).cloned().collect();
+        active.sort_by_key(|o| o.created_at);
+        Ok(active)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, ApplicationError> {
+        Ok(self.overrides.lock().unwrap().remove(&id).is_some())
+    }
+}
+
+fn limiter(store: Arc<MemoryOverrides>) -> ManagedRateLimiter {
+    ManagedRateLimiter::new(Arc::new(InMemoryRateLimiter::new()), store)
+}
+
+/// Hits allowed on `key` with a configured limit of 1
+fn allowed(limiter: &ManagedRateLimiter, key: &str) -> usize {
+    (0..10).take_while(|_| limiter.check(key, 1, HOUR).is_ok()).count()
+}
+
+#[tokio::test]
+async fn overrides_replace_the_limit_of_their_subject_and_scope() {
+    let limiter = limiter(Arc::default());
+    let (admin, alice, bob) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+    let entry = limiter
+        .grant(admin, &RateLimitSubject::User(alice), Some(" Support ".into()), 3, HOUR, Some("Bulk import".into()))
+        .await
+        .unwrap();
+    assert_eq!(entry.subject, format!("user:{}", alice));
+    assert_eq!(entry.scope.as_deref(), Some("support"));
+
+    assert_eq!(allowed(&limiter, &format!("support:user:{}", alice)), 3);
+    assert_eq!(allowed(&limiter, &format!("export:user:{}", alice)), 1);
+    assert_eq!(allowed(&limiter, &format!("support:user:{}", bob)), 1);
+
+    // Without a scope it covers them all; the most generous override wins
+    limiter.grant(admin, &RateLimitSubject::User(bob), None, 5, HOUR, None).await.unwrap();
+    limiter.grant(admin, &RateLimitSubject::User(bob), Some("export".into()), 2, HOUR, None).await.unwrap();
+    assert_eq!(allowed(&limiter, &format!("export:user:{}", bob)), 5);
+
+    limiter.revoke(entry.id).await.unwrap();
+    limiter.reset_counters(&RateLimitSubject::User(alice));
+    assert_eq!(allowed(&limiter, &format!("support:user:{}", alice)), 1);
+    let missing = limiter.revoke(entry.id).await;
+    assert!(matches!(missing, Err(ApplicationError::Domain(DomainError::NotFound { .. }))));
+}
+
+#[tokio::test]
+async fn usage_lists_open_windows_and_reset_starts_them_over() {
+    let limiter = limiter(Arc::default());
+    let ip = RateLimitSubject::Ip("2001:db8::7".parse().unwrap());
+    let key = "support:ip:2001:db8::7";
+
+    limiter.check(key, 5, HOUR).unwrap();
+    limiter.check(key, 5, HOUR).unwrap();
+    limiter.check("export:ip:2001:db8::7", 1, Duration::from_secs(60)).unwrap();
+    limiter.check("support:ip:2001:db8::8", 5, HOUR).unwrap();
+    let granted = limiter.grant(Uuid::new_v4(), &ip, None, 10, HOUR, None).await.unwrap();
+
+    let usage = limiter.usage(&ip);
+    let counters: Vec<(&str, u32)> = usage.counters.iter().map(|c| (c.key.as_str(), c.count)).collect();
+    assert_eq!(counters, [("export:ip:2001:db8::7", 1), ("support:ip:2001:db8::7", 2)]);
+    // The limit reported is the one in force, the override's
+    assert_eq!(usage.counters[1].limit, 10);
+    assert!(usage.counters[0].resets_in <= Duration::from_secs(60));
+    assert_eq!(usage.overrides, [granted]);
+
+    assert_eq!(limiter.reset_counters(&ip), 2);
+    assert!(limiter.usage(&ip).counters.is_empty());
+    assert_eq!(limiter.counters("ip:2001:db8::8").len(), 1);
+}
+
+#[tokio::test]
+async fn overrides_from_other_instances_apply_after_a_refresh() {
+    let store = Arc::new(MemoryOverrides::default());
+    let (here, there) = (limiter(store.clone()), limiter(store.clone()));
+    let alice = Uuid::new_v4();
+    let key = format!("support:user:{}", alice);
+
+    here.grant(Uuid::new_v4(), &RateLimitSubject::User(alice), None, 4, HOUR, None).await.unwrap();
+    assert_eq!(allowed(&there, &key), 1);
+    there.refresh().await.unwrap();
+    there.reset_counters(&RateLimitSubject::User(alice));
+    assert_eq!(allowed(&there, &key), 4);
+
+    // Expired overrides stop applying without a refresh
+    for entry in store.overrides.lock().unwrap().values_mut() {
+        entry.expires_at = Utc::now();
+    }
+    there.refresh().await.unwrap();
+    assert!(there.overrides().is_empty());
+}
+
+#[tokio::test]
+async fn invalid_overrides_are_rejected() {
+    let limiter = limiter(Arc::default());
+    let subject = RateLimitSubject::User(Uuid::new_v4());
+    let admin = Uuid::new_v4();
+
+    for (scope, limit, ttl) in [
+        (None, 0, HOUR),
+        (None, 5, Duration::ZERO),
+        (None, 5, Duration::from_secs(31 * 86_400)),
+        (Some("support:user".to_string()), 5, HOUR),
+    ] {
+        let result = limiter.grant(admin, &subject, scope, limit, ttl, None).await;
+        assert!(matches!(result, Err(ApplicationError::Domain(DomainError::Validation(_)))));
+    }
+    assert!(limiter.overrides().is_empty());
+}
@@ -0,0 +1,213 @@
+//! Refresh tokens: rotated on every use, and a reused token revokes its whole family.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use application::activity::LoginClient;
+use application::cron::CronTask;
+use application::refresh_tokens::{
+    PruneRefreshTokensJob, PurgeRevokedSessionsTask, RefreshTokenRecord, RefreshTokenStore, RefreshTokens,
+};
+use application::testing::{MockPasswordHasher, MockTokenService, MockUserRepository};
+use application::token_versions::TokenVersions;
+use application::{ApplicationError, AuthService, AuthServiceImpl};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{DomainError, TokenPair};
+use infrastructure::InMemoryEventBus;
+use uuid::Uuid;
+
+#[derive(Default)]
+struct MemoryRefreshTokens {
+    tokens: Mutex<HashMap<Uuid, RefreshTokenRecord>>,
+}
+
+#[async_trait]
+impl RefreshTokenStore for MemoryRefreshTokens {
+    async fn create(&self, token: &RefreshTokenRecord) -> Result<(), ApplicationError> {
+        self.tokens.lock().unwrap().insert(token.id, token.clone());
+        Ok(())
+    }
+
+    async fn find(&self, token_hash: &str) -> Result<Option<RefreshTokenRecord>, ApplicationError> {
+        Ok(self.tokens.lock().unwrap().values().find(|t| t.token_hash == token_hash).cloned())
+    }
+
+    async fn mark_used(&self, id: Uuid) -> Result<bool, ApplicationError> {
+        let mut tokens = self.tokens.lock().unwrap();
+        let token = tokens.get_mut(&id).unwrap();
+        if token.used_at.is_some() {
+            return Ok(false);
+        }
+        token.used_at = Some(Utc::now());
+        Ok(true)
+    }
+
+    async fn revoke_family(&self, family_id: Uuid) -> Result<u64, ApplicationError> {
+        let mut revoked = 0;
+        for token in self.tokens.lock().unwrap().values_mut() {
+            if token.family_id == family_id && token.revoked_at.is_none() {
+                token.revoked_at = Some(Utc::now());
+                revoked += 1;
+            }
+        }
+        Ok(revoked)
+    }
+
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<RefreshTokenRecord>, ApplicationError> {
+        let mut tokens: Vec<_> = self.tokens.lock().unwrap().values().filter(|t| t.user_id == user_id).cloned().collect();
+        tokens.sort_by_key(|t| t.created_at);
+        Ok(tokens)
+    }
+
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64, ApplicationError> {
+        let mut tokens = self.tokens.lock().unwrap();
+        let count = tokens.len();
+        tokens.retain(|_, t| t.expires_at >= before);
+        Ok((count - tokens.len()) as u64)
+    }
+
+    async fn prune_revoked(&self, before: DateTime<Utc>) -> Result<u64, ApplicationError> {
+        let mut tokens = self.tokens.lock().unwrap();
+        let count = tokens.len();
+        tokens.retain(|_, t| t.revoked_at.is_none_or(|at| at >= before));
+        Ok((count - tokens.len()) as u64)
+    }
+}
+
+const PASSWORD: &str = "Correct-Horse-7";
+
+/// Auth service with refresh tokens and a registered `alice`
+async fn auth(store: Arc<MemoryRefreshTokens>, ttl: Duration) -> AuthServiceImpl {
+    let auth = AuthServiceImpl::new(
+        Arc::new(MockUserRepository::new()),
+        Arc::new(MockPasswordHasher::new()),
+        Arc::new(MockTokenService::new()),
+        Arc::new(InMemoryEventBus::default()),
+    )
+    .with_refresh_tokens(Arc::new(RefreshTokens::new(store, ttl)));
+    auth.register("alice".into(), "alice@example.com".into(), PASSWORD.into()).await.unwrap();
+    auth
+}
+
+async fn login(auth: &AuthServiceImpl) -> String {
+    let token = auth.login("alice@example.com".into(), PASSWORD.into(), LoginClient::default()).await.unwrap();
+    token.refresh_token.expect("login issues a refresh token")
+}
+
+fn is_unauthorized(result: Result<TokenPair, ApplicationError>) -> bool {
+    matches!(result, Err(ApplicationError::Domain(DomainError::Unauthorized(_))))
+}
+
+#[tokio::test]
+async fn each_refresh_rotates_the_token() {
+    let store = Arc::new(MemoryRefreshTokens::default());
+    let auth = auth(store.clone(), Duration::from_secs(3600)).await;
+
+    let first = login(&auth).await;
+    let pair = auth.refresh(first.clone()).await.unwrap();
+    let second = pair.refresh_token.unwrap();
+    assert_ne!(second, first);
+    assert!(!pair.access_token.is_empty());
+
+    let third = auth.refresh(second).await.unwrap().refresh_token.unwrap();
+    assert!(auth.refresh(third).await.is_ok());
+
+    // Only hashes are stored, all in the login's family
+    let tokens = store.tokens.lock().unwrap();
+    assert_eq!(tokens.len(), 4);
+    assert!(tokens.values().all(|t| t.token_hash != first));
+    let families: Vec<Uuid> = tokens.values().map(|t| t.family_id).collect();
+    assert!(families.iter().all(|f| *f == families[0]));
+}
+
+#[tokio::test]
+async fn reusing_a_rotated_token_revokes_the_family() {
+    let store = Arc::new(MemoryRefreshTokens::default());
+    let auth = auth(store.clone(), Duration::from_secs(3600)).await;
+
+    let stolen = login(&auth).await;
+    let other_session = login(&auth).await;
+    let latest = auth.refresh(stolen.clone()).await.unwrap().refresh_token.unwrap();
+
+    // The copy is replayed: rejected, and the legitimate newest token dies with it
+    assert!(is_unauthorized(auth.refresh(stolen).await));
+    assert!(is_unauthorized(auth.refresh(latest).await));
+
+    // Other logins are separate families
+    assert!(auth.refresh(other_session).await.is_ok());
+}
+
+#[tokio::test]
+async fn invalid_and_expired_tokens_are_rejected() {
+    let store = Arc::new(MemoryRefreshTokens::default());
+    let auth = auth(store.clone(), Duration::ZERO).await;
+
+    let expired = login(&auth).await;
+    assert!(is_unauthorized(auth.refresh(expired).await));
+    assert!(is_unauthorized(auth.refresh("made-up".into()).await));
+    // Neither counts as reuse
+    assert!(store.tokens.lock().unwrap().values().all(|t| t.revoked_at.is_none()));
+
+    CronTask::run(&PruneRefreshTokensJob::new(store.clone())).await.unwrap();
+    assert!(store.tokens.lock().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn without_refresh_tokens_login_issues_none() {
+    let auth = AuthServiceImpl::new(
+        Arc::new(MockUserRepository::new()),
+        Arc::new(MockPasswordHasher::new()),
+        Arc::new(MockTokenService::new()),
+        Arc::new(InMemoryEventBus::default()),
+    );
+    auth.register("alice".into(), "alice@example.com".into(), PASSWORD.into()).await.unwrap();
+
+    let token = auth.login("alice@example.com".into(), PASSWORD.into(), LoginClient::default()).await.unwrap();
+    assert_eq!(token.refresh_token, None);
+    assert!(is_unauthorized(auth.refresh("anything".into()).await));
+}
+
+#[tokio::test]
+async fn logout_all_ends_every_refresh_family() {
+    let store = Arc::new(MemoryRefreshTokens::default());
+    let users = Arc::new(MockUserRepository::new());
+    let auth = AuthServiceImpl::new(
+        users.clone(),
+        Arc::new(MockPasswordHasher::new()),
+        Arc::new(MockTokenService::new()),
+        Arc::new(InMemoryEventBus::default()),
+    )
+    .with_refresh_tokens(Arc::new(RefreshTokens::new(store, Duration::from_secs(3600))));
+    let alice = auth.register("alice".into(), "alice@example.com".into(), PASSWORD.into()).await.unwrap();
+
+    let phone = login(&auth).await;
+    let laptop = auth.refresh(login(&auth).await).await.unwrap().refresh_token.unwrap();
+    TokenVersions::new(users).revoke_all(alice.id).await.unwrap();
+
+    assert!(is_unauthorized(auth.refresh(phone).await));
+    assert!(is_unauthorized(auth.refresh(laptop).await));
+
+    // Signing in again starts a family at the new version
+    let fresh = login(&auth).await;
+    assert!(auth.refresh(fresh).await.is_ok());
+}
+
+#[tokio::test]
+async fn revoked_sessions_are_purged_after_the_retention_period() {
+    let store = Arc::new(MemoryRefreshTokens::default());
+    let auth = auth(store.clone(), Duration::from_secs(3600)).await;
+    let stolen = login(&auth).await;
+    let kept = login(&auth).await;
+    auth.refresh(stolen.clone()).await.unwrap();
+    assert!(is_unauthorized(auth.refresh(stolen).await));
+
+    // Within the retention period the revoked session is still listed
+    PurgeRevokedSessionsTask::new(store.clone(), Duration::from_secs(3600)).run().await.unwrap();
+    assert_eq!(store.tokens.lock().unwrap().len(), 3);
+
+    PurgeRevokedSessionsTask::new(store.clone(), Duration::ZERO).run().await.unwrap();
+    assert_eq!(store.tokens.lock().unwrap().len(), 1);
+    assert!(auth.refresh(kept).await.is_ok());
+}
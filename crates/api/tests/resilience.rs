@@ -0,0 +1,111 @@
+//! Retries and the circuit breaker of `ResilientRepository`.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use application::resilience::ResilientRepository;
+use async_trait::async_trait;
+use domain::{DomainError, Page, PaginationParams, Repository, User};
+use uuid::Uuid;
+
+/// Fails with the queued errors, then finds nothing
+#[derive(Default)]
+struct FlakyRepository {
+    errors: Mutex<VecDeque<DomainError>>,
+    calls: AtomicUsize,
+}
+
+impl FlakyRepository {
+    fn failing(errors: impl IntoIterator<Item = DomainError>) -> Arc<Self> {
+        Arc::new(Self {
+            errors: Mutex::new(errors.into_iter().collect()),
+            calls: AtomicUsize::new(0),
+        })
+    }
+
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    fn next(&self) -> Result<(), DomainError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        match self.errors.lock().unwrap().pop_front() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl Repository<User> for FlakyRepository {
+    async fn find_by_id(&self, _id: Uuid) -> Result<Option<User>, DomainError> {
+        self.next().map(|_| None)
+    }
+
+    async fn find_all(&self, params: &PaginationParams) -> Result<Page<User>, DomainError> {
+        self.next().map(|_| Page::new(Vec::new(), 0, params))
+    }
+
+    async fn create(&self, user: &User) -> Result<User, DomainError> {
+        self.next().map(|_| user.clone())
+    }
+
+    async fn update(&self, user: &User) -> Result<User, DomainError> {
+        self.next().map(|_| user.clone())
+    }
+
+    async fn delete(&self, _id: Uuid) -> Result<bool, DomainError> {
+        self.next().map(|_| true)
+    }
+
+    async fn count(&self) -> Result<u64, DomainError> {
+        self.next().map(|_| 0)
+    }
+}
+
+#[tokio::test]
+async fn transient_failures_are_retried_and_timeouts_are_not() {
+    let inner = FlakyRepository::failing([DomainError::unavailable("connection reset"), DomainError::unavailable("deadlock")]);
+    let repo = ResilientRepository::new(inner.clone()).with_retries(2, Duration::ZERO);
+    assert_eq!(repo.count().await.unwrap(), 0);
+    assert_eq!(inner.calls(), 3);
+
+    let inner = FlakyRepository::failing([DomainError::timeout("statement timeout")]);
+    let repo = ResilientRepository::new(inner.clone()).with_retries(2, Duration::ZERO);
+    assert!(matches!(repo.count().await, Err(DomainError::Timeout(_))));
+    assert_eq!(inner.calls(), 1);
+
+    let inner = FlakyRepository::failing((0..3).map(|_| DomainError::unavailable("connection reset")));
+    let repo = ResilientRepository::new(inner.clone()).with_retries(1, Duration::ZERO);
+    assert!(matches!(repo.find_by_id(Uuid::new_v4()).await, Err(DomainError::Unavailable(_))));
+    assert_eq!(inner.calls(), 2);
+}
+
+#[tokio::test]
+async fn repeated_failures_open_the_circuit_until_a_trial_call_succeeds() {
+    let inner = FlakyRepository::failing((0..3).map(|_| DomainError::internal("connection refused")));
+    let repo = ResilientRepository::new(inner.clone()).with_circuit(2, Duration::from_millis(50));
+
+    assert!(repo.count().await.is_err());
+    assert!(!repo.is_open());
+    assert!(repo.count().await.is_err());
+    assert!(repo.is_open());
+
+    let Err(DomainError::Internal(message)) = repo.count().await else {
+        panic!("expected the open circuit to reject the call");
+    };
+    assert_eq!(message, "circuit open");
+    assert_eq!(inner.calls(), 2);
+
+    // The trial call fails: open again without waiting for the threshold
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert!(repo.count().await.is_err());
+    assert!(repo.is_open());
+    assert_eq!(inner.calls(), 3);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(repo.count().await.unwrap(), 0);
+    assert!(!repo.is_open());
+}
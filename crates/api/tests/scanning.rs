@@ -0,0 +1,57 @@
+//! Infected uploads are quarantined and announced to the uploader.
+
+use std::sync::Arc;
+
+use application::storage::{FileScanner, FileStorage, ScanVerdict, UploadScanner, QUARANTINE_PREFIX};
+use application::{ApplicationError, EventBus};
+use async_trait::async_trait;
+use domain::DomainEvent;
+use infrastructure::{InMemoryEventBus, LocalFileStorage, NoopFileScanner};
+use uuid::Uuid;
+
+struct EicarScanner;
+
+#[async_trait]
+impl FileScanner for EicarScanner {
+    async fn scan(&self, bytes: &[u8]) -> Result<ScanVerdict, ApplicationError> {
+        Ok(if bytes.starts_with(b"X5O!") {
+            ScanVerdict::Infected {
+                signature: "Eicar-Test-Signature".to_string(),
+            }
+        } else {
+            ScanVerdict::Clean
+        })
+    }
+}
+
+#[tokio::test]
+async fn infected_uploads_are_quarantined() {
+    let root = std::env::temp_dir().join(format!("scanning-{}", Uuid::new_v4()));
+    let storage: Arc<dyn FileStorage> = Arc::new(LocalFileStorage::new(&root, "/files", "secret"));
+    let events = Arc::new(InMemoryEventBus::default());
+    let scanner = UploadScanner::new(Arc::new(EicarScanner), storage, events.clone());
+    let user = Uuid::new_v4();
+
+    scanner.check(user, "avatar", b"\x89PNG\r\n\x1a\n", "image/png").await.unwrap();
+    assert!(events.events_since(0).is_empty());
+
+    assert!(scanner.check(user, "avatar", b"X5O!P%@AP", "image/png").await.is_err());
+    let quarantined = std::fs::read_dir(root.join(QUARANTINE_PREFIX).join("avatar").join(user.to_string()))
+        .unwrap()
+        .count();
+    assert_eq!(quarantined, 1);
+    match &events.events_since(0)[..] {
+        [envelope] => assert!(matches!(
+            &envelope.event,
+            DomainEvent::FileQuarantined { user_id, signature, .. } if *user_id == user && signature == "Eicar-Test-Signature"
+        )),
+        other => panic!("expected one event, got {}", other.len()),
+    }
+
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[tokio::test]
+async fn noop_scanner_accepts_everything() {
+    assert_eq!(NoopFileScanner.scan(b"X5O!P%@AP").await.unwrap(), ScanVerdict::Clean);
+}
@@ -0,0 +1,175 @@
+//! Saved user segments: per-tenant named filters whose members follow the users' data.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use application::segments::SegmentService;
+use application::tenancy::{with_tenant, TenantScopedUserRepository};
+use application::testing::MockUserRepository;
+use application::ApplicationError;
+use async_trait::async_trait;
+use domain::{
+    DomainError, Page, PaginationParams, Repository, Tenant, User, UserFilter, UserSegment, UserSegmentRepository,
+    UserStatus,
+};
+use uuid::Uuid;
+
+#[derive(Default)]
+struct MemorySegments {
+    segments: Mutex<HashMap<Uuid, UserSegment>>,
+}
+
+#[async_trait]
+impl Repository<UserSegment> for MemorySegments {
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<UserSegment>, DomainError> {
+        Ok(self.segments.lock().unwrap().get(&id).cloned())
+    }
+
+    async fn find_all(&self, params: &PaginationParams) -> Result<Page<UserSegment>, DomainError> {
+        let items: Vec<UserSegment> = self.segments.lock().unwrap().values().cloned().collect();
+        let total = items.len() as u64;
+        Ok(Page::new(items, total, params))
+    }
+
+    async fn create(&self, segment: &UserSegment) -> Result<UserSegment, DomainError> {
+        let mut segments = self.segments.lock().unwrap();
+        let taken = segments
+            .values()
+            .any(|s| s.tenant_id == segment.tenant_id && s.name.to_lowercase() == segment.name.to_lowercase());
+        if taken {
+            return Err(DomainError::conflict("UserSegment already exists"));
+        }
+        segments.insert(segment.id, segment.clone());
+        Ok(segment.clone())
+    }
+
+    async fn update(&self, segment: &UserSegment) -> Result<UserSegment, DomainError> {
+        self.segments.lock().unwrap().insert(segment.id, segment.clone());
+        Ok(segment.clone())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, DomainError> {
+        Ok(self.segments.lock().unwrap().remove(&id).is_some())
+    }
+
+    async fn count(&self) -> Result<u64, DomainError> {
+        Ok(self.segments.lock().unwrap().len() as u64)
+    }
+}
+
+#[async_trait]
+impl UserSegmentRepository for MemorySegments {
+    async fn list(&self, tenant_id: Uuid) -> Result<Vec<UserSegment>, DomainError> {
+        let mut segments: Vec<UserSegment> =
+            self.segments.lock().unwrap().values().filter(|s| s.tenant_id == tenant_id).cloned().collect();
+        segments.sort_by_key(|s| s.name.to_lowercase());
+        Ok(segments)
+    }
+}
+
+fn user(name: &str, status: UserStatus) -> User {
+    User {
+        status,
+        ..User::new(name.parse().unwrap(), format!("{}@example.com", name).parse().unwrap(), String::new())
+    }
+}
+
+fn active() -> UserFilter {
+    UserFilter {
+        status: Some(UserStatus::Active),
+        ..UserFilter::default()
+    }
+}
+
+fn usernames(page: Page<User>) -> Vec<String> {
+    let mut names: Vec<String> = page.items.into_iter().map(|u| u.username.into()).collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn members_follow_the_users_data() {
+    let users = Arc::new(MockUserRepository::with_users([
+        user("alice", UserStatus::Active),
+        user("bob", UserStatus::Suspended),
+    ]));
+    let segments = SegmentService::new(Arc::new(MemorySegments::default()), users.clone());
+    let admin = Uuid::new_v4();
+
+    let filter = UserFilter {
+        query: Some("  example  ".to_string()),
+        tags: vec![" VIP ".to_string(), "vip".to_string()],
+        ..active()
+    };
+    let segment = segments.create(admin, "  Active VIPs ".to_string(), filter).await.unwrap();
+    assert_eq!(segment.name, "Active VIPs");
+    assert_eq!(segment.created_by, admin);
+    assert_eq!(segment.filter.query.as_deref(), Some("example"));
+    assert_eq!(segment.filter.tags, ["vip"]);
+
+    let everyone = segments.create(admin, "Active".to_string(), active()).await.unwrap();
+    let params = PaginationParams::new(1, 20);
+    assert_eq!(usernames(segments.members(everyone.id, &params).await.unwrap()), ["alice"]);
+
+    // Evaluated on every use, not when saved
+    users.create(&user("carol", UserStatus::Active)).await.unwrap();
+    assert_eq!(usernames(segments.members(everyone.id, &params).await.unwrap()), ["alice", "carol"]);
+
+    let names: Vec<String> = segments.list().await.unwrap().into_iter().map(|s| s.name).collect();
+    assert_eq!(names, ["Active", "Active VIPs"]);
+}
+
+#[tokio::test]
+async fn names_are_validated_and_unique() {
+    let segments = SegmentService::new(Arc::new(MemorySegments::default()), Arc::new(MockUserRepository::new()));
+    let admin = Uuid::new_v4();
+
+    segments.create(admin, "Churn risk".to_string(), active()).await.unwrap();
+    assert!(matches!(
+        segments.create(admin, "churn RISK".to_string(), active()).await,
+        Err(ApplicationError::Domain(DomainError::Conflict { .. }))
+    ));
+    assert!(matches!(
+        segments.create(admin, "   ".to_string(), active()).await,
+        Err(ApplicationError::Domain(DomainError::Validation(_)))
+    ));
+    let bad_tag = UserFilter {
+        tags: vec!["no spaces".to_string()],
+        ..UserFilter::default()
+    };
+    assert!(matches!(
+        segments.create(admin, "Bad tag".to_string(), bad_tag).await,
+        Err(ApplicationError::Domain(DomainError::Validation(_)))
+    ));
+}
+
+#[tokio::test]
+async fn segments_belong_to_their_tenant() {
+    let repo = Arc::new(TenantScopedUserRepository::new(Arc::new(MockUserRepository::new())));
+    let segments = SegmentService::new(Arc::new(MemorySegments::default()), repo.clone());
+    let acme = Uuid::new_v4();
+    let admin = Uuid::new_v4();
+
+    with_tenant(acme, repo.create(&user("alice", UserStatus::Active))).await.unwrap();
+    with_tenant(Tenant::DEFAULT_ID, repo.create(&user("bob", UserStatus::Active))).await.unwrap();
+
+    let acme_segment = with_tenant(acme, segments.create(admin, "Active".to_string(), active())).await.unwrap();
+    assert_eq!(acme_segment.tenant_id, acme);
+    let members = with_tenant(acme, segments.members(acme_segment.id, &PaginationParams::new(1, 20))).await.unwrap();
+    assert_eq!(usernames(members), ["alice"]);
+
+    // The same name is free in another tenant, which cannot see or delete acme's segment
+    with_tenant(Tenant::DEFAULT_ID, async {
+        segments.create(admin, "Active".to_string(), active()).await.unwrap();
+        assert!(matches!(
+            segments.get(acme_segment.id).await,
+            Err(ApplicationError::Domain(DomainError::NotFound { .. }))
+        ));
+        assert!(segments.delete(acme_segment.id).await.is_err());
+        assert_eq!(segments.list().await.unwrap().len(), 1);
+    })
+    .await;
+
+    with_tenant(acme, segments.delete(acme_segment.id)).await.unwrap();
+    assert!(with_tenant(acme, segments.list()).await.unwrap().is_empty());
+}
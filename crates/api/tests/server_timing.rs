@@ -0,0 +1,105 @@
+//! Server-Timing: auth, db and serialize phases reported to admins and to debug requests.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use api::server_timing::{header_value, server_timing, Json, ServerTimingAllowed, DEBUG_TIMING_HEADER, SERVER_TIMING_HEADER};
+use application::timings::{self, PhaseTiming, RequestTimings};
+use axum::{body::Body, extract::Request, http::StatusCode, middleware, response::IntoResponse, routing::get, Router};
+use serde_json::json;
+use shared::ServerTimingConfig;
+use tower::ServiceExt;
+
+/// Two "queries" and a JSON body
+async fn handler() -> Json<serde_json::Value> {
+    for _ in 0..2 {
+        let _query = timings::start(timings::DB);
+        tokio::time::sleep(Duration::from_millis(2)).await;
+    }
+    Json(json!({"ok": true}))
+}
+
+/// What `jwt_auth` does for admins
+async fn admin_handler() -> impl IntoResponse {
+    let mut response = handler().await.into_response();
+    response.extensions_mut().insert(ServerTimingAllowed);
+    response
+}
+
+fn app(config: ServerTimingConfig) -> Router {
+    Router::new()
+        .route("/", get(handler))
+        .route("/admin", get(admin_handler))
+        .layer(middleware::from_fn_with_state(Arc::new(config), server_timing))
+}
+
+async fn call(config: ServerTimingConfig, path: &str, debug: bool) -> Option<String> {
+    let mut request = Request::get(path).body(Body::empty()).unwrap();
+    if debug {
+        request.headers_mut().insert(DEBUG_TIMING_HEADER, "1".parse().unwrap());
+    }
+    let response = app(config).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    response
+        .headers()
+        .get(SERVER_TIMING_HEADER)
+        .map(|v| v.to_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn debug_requests_get_the_breakdown() {
+    let value = call(ServerTimingConfig::default(), "/", true).await.unwrap();
+    let phases: Vec<&str> = value.split(", ").map(|p| p.split(';').next().unwrap()).collect();
+    assert_eq!(phases, ["db", "serialize", "total"], "{}", value);
+    assert!(value.contains("desc=\"2 queries\""), "{}", value);
+
+    assert_eq!(call(ServerTimingConfig::default(), "/", false).await, None);
+}
+
+#[tokio::test]
+async fn admins_get_the_breakdown_without_asking() {
+    assert!(call(ServerTimingConfig::default(), "/admin", false).await.is_some());
+
+    let no_debug_header = ServerTimingConfig {
+        debug_header: false,
+        ..ServerTimingConfig::default()
+    };
+    assert_eq!(call(no_debug_header.clone(), "/", true).await, None);
+    assert!(call(no_debug_header, "/admin", false).await.is_some());
+
+    let disabled = ServerTimingConfig {
+        enabled: false,
+        ..ServerTimingConfig::default()
+    };
+    assert_eq!(call(disabled, "/admin", true).await, None);
+}
+
+#[tokio::test]
+async fn phases_add_up_per_request() {
+    let timings = Arc::new(RequestTimings::default());
+    timings::with_timings(timings.clone(), async {
+        for _ in 0..3 {
+            drop(timings::start(timings::DB));
+        }
+        drop(timings::start(timings::AUTH));
+    })
+    .await;
+    let phases = timings.phases();
+    assert_eq!(phases.iter().map(|p| (p.phase, p.count)).collect::<Vec<_>>(), [("db", 3), ("auth", 1)]);
+
+    // Outside a request nothing is collected, and nothing fails
+    drop(timings::start(timings::DB));
+    assert_eq!(timings.phases().len(), 2);
+}
+
+#[test]
+fn header_values_are_in_milliseconds() {
+    let phases = [
+        PhaseTiming { phase: "auth", elapsed: Duration::from_micros(1_250), count: 1 },
+        PhaseTiming { phase: "db", elapsed: Duration::from_millis(4), count: 1 },
+    ];
+    assert_eq!(
+        header_value(&phases, Duration::from_millis(10)),
+        "auth;dur=1.2, db;dur=4.0;desc=\"1 query\", total;dur=10.0"
+    );
+}
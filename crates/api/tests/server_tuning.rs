@@ -0,0 +1,69 @@
+//! Listener and connection settings from `ServerConfig`: HTTP/2, keep-alive and TCP options.
+
+use std::net::SocketAddr;
+
+use api::server::{bind, serve};
+use axum::{extract::ConnectInfo, http::Version, routing::get, Router};
+use shared::ServerConfig;
+
+/// Serve a `/ip` route with `config` on an ephemeral port; returns its base URL
+async fn spawn(config: ServerConfig) -> String {
+    let config = ServerConfig {
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        ..config
+    };
+    let listener = bind(&config).await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let app = Router::new().route(
+        "/ip",
+        get(|ConnectInfo(remote): ConnectInfo<SocketAddr>| async move { remote.ip().to_string() }),
+    );
+    tokio::spawn(async move { serve(listener, &config, None, app, std::future::pending()).await });
+    url
+}
+
+fn h2_client() -> reqwest::Client {
+    reqwest::Client::builder().http2_prior_knowledge().build().unwrap()
+}
+
+#[tokio::test]
+async fn serves_http1_and_h2_with_the_defaults() {
+    let url = spawn(ServerConfig::default()).await;
+
+    let response = reqwest::get(format!("{}/ip", url)).await.unwrap();
+    assert_eq!(response.version(), Version::HTTP_11);
+    assert_eq!(response.text().await.unwrap(), "127.0.0.1");
+
+    let response = h2_client().get(format!("{}/ip", url)).send().await.unwrap();
+    assert_eq!(response.version(), Version::HTTP_2);
+    assert_eq!(response.text().await.unwrap(), "127.0.0.1");
+}
+
+#[tokio::test]
+async fn http2_can_be_switched_off() {
+    let url = spawn(ServerConfig {
+        http2: false,
+        ..ServerConfig::default()
+    })
+    .await;
+
+    assert_eq!(reqwest::get(format!("{}/ip", url)).await.unwrap().version(), Version::HTTP_11);
+    assert!(h2_client().get(format!("{}/ip", url)).send().await.is_err());
+}
+
+#[tokio::test]
+async fn tuning_without_keep_alive_or_nodelay_still_serves() {
+    let url = spawn(ServerConfig {
+        keep_alive: false,
+        tcp_nodelay: false,
+        backlog: 1,
+        ..ServerConfig::default()
+    })
+    .await;
+
+    let client = reqwest::Client::new();
+    for _ in 0..3 {
+        assert_eq!(client.get(format!("{}/ip", url)).send().await.unwrap().text().await.unwrap(), "127.0.0.1");
+    }
+}
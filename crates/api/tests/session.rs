@@ -0,0 +1,51 @@
+//! Encrypted cookie sessions: round trips, one-shot values, flash and CSRF.
+
+use std::time::Duration;
+
+use api::session::CookieSessions;
+use tower_cookies::{Cookie, Cookies};
+
+const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+#[test]
+fn values_round_trip_and_are_unreadable_with_another_key() {
+    let sessions = CookieSessions::new(SECRET);
+    let cookies = Cookies::default();
+    sessions.set(&cookies, "oauth", &("state-1", 42), Duration::from_secs(60));
+
+    let stored = cookies.get("oauth").unwrap().value().to_string();
+    assert!(!stored.contains("state-1"), "values must be encrypted");
+    assert_eq!(sessions.get::<(String, u32)>(&cookies, "oauth"), Some(("state-1".to_string(), 42)));
+    assert_eq!(CookieSessions::new(b"another secret, also 32 bytes long").get::<(String, u32)>(&cookies, "oauth"), None);
+
+    cookies.add(Cookie::new("forged", "{\"exp\":9999999999,\"data\":1}"));
+    assert_eq!(sessions.get::<u32>(&cookies, "forged"), None);
+}
+
+#[test]
+fn expired_and_taken_values_are_gone() {
+    let sessions = CookieSessions::new(SECRET);
+    let cookies = Cookies::default();
+    sessions.set(&cookies, "stale", &1, Duration::ZERO);
+    assert_eq!(sessions.get::<u32>(&cookies, "stale"), None);
+
+    sessions.set(&cookies, "once", &"pkce-verifier", Duration::from_secs(60));
+    assert_eq!(sessions.take::<String>(&cookies, "once").as_deref(), Some("pkce-verifier"));
+    assert_eq!(sessions.take::<String>(&cookies, "once"), None);
+}
+
+#[test]
+fn flash_messages_and_csrf_tokens() {
+    let sessions = CookieSessions::new(SECRET);
+    let cookies = Cookies::default();
+    sessions.flash(&cookies, "Saved");
+    sessions.flash(&cookies, "Email sent");
+    assert_eq!(sessions.take_flashes(&cookies), ["Saved", "Email sent"]);
+    assert!(sessions.take_flashes(&cookies).is_empty());
+
+    let token = sessions.csrf_token(&cookies);
+    assert_eq!(sessions.csrf_token(&cookies), token);
+    assert!(sessions.verify_csrf(&cookies, &token));
+    assert!(!sessions.verify_csrf(&cookies, "not-the-token"));
+    assert!(!sessions.verify_csrf(&Cookies::default(), &token));
+}
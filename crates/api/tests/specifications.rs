@@ -0,0 +1,87 @@
+//! Specifications: composing user criteria, evaluating them in memory and translating them to SQL.
+
+use std::sync::Arc;
+
+use application::tenancy::{with_tenant, TenantScopedUserRepository};
+use application::testing::MockUserRepository;
+use chrono::{Duration, Utc};
+use domain::{PaginationParams, Repository, Specification, User, UserCriterion, UserStatus};
+use infrastructure::push_specification;
+use sqlx::{Postgres, QueryBuilder};
+use uuid::Uuid;
+
+fn user(name: &str, domain: &str, days_ago: i64) -> User {
+    User {
+        created_at: Utc::now() - Duration::days(days_ago),
+        ..User::new(name.parse().unwrap(), format!("{}@{}", name, domain).parse().unwrap(), String::new())
+    }
+}
+
+fn names(users: &[User]) -> Vec<&str> {
+    users.iter().map(|u| u.username.as_str()).collect()
+}
+
+#[tokio::test]
+async fn criteria_compose_with_and_or_not() {
+    let mut suspended = user("carol", "example.com", 1);
+    suspended.status = UserStatus::Suspended;
+    let repo = MockUserRepository::with_users([
+        user("alice", "example.com", 10),
+        user("bob", "Other.org", 2),
+        suspended,
+        user("dave", "example.com", 3),
+    ]);
+    let params = PaginationParams::new(1, 10);
+    let week_ago = Utc::now() - Duration::days(7);
+
+    let recent_at_example =
+        Specification::from(UserCriterion::ByEmailDomain("EXAMPLE.com".into())).and(UserCriterion::CreatedAfter(week_ago).into());
+    let page = repo.find_by_spec(&recent_at_example, &params).await.unwrap();
+    // Newest first
+    assert_eq!(names(&page.items), ["carol", "dave"]);
+
+    let active = Specification::from(UserCriterion::HasStatus(UserStatus::Suspended)).not();
+    let page = repo
+        .find_by_spec(&recent_at_example.clone().and(active.clone()), &params)
+        .await
+        .unwrap();
+    assert_eq!(names(&page.items), ["dave"]);
+
+    let either = Specification::from(UserCriterion::ByEmailDomain("other.org".into()))
+        .or(UserCriterion::CreatedBefore(week_ago).into());
+    let page = repo.find_by_spec(&either, &PaginationParams::new(1, 1)).await.unwrap();
+    assert_eq!((names(&page.items), page.total), (vec!["bob"], 2));
+}
+
+#[tokio::test]
+async fn tenants_only_see_their_own_matches() {
+    let repo = TenantScopedUserRepository::new(Arc::new(MockUserRepository::new()));
+    let acme = Uuid::new_v4();
+    with_tenant(acme, repo.create(&user("alice", "example.com", 1))).await.unwrap();
+    repo.create(&user("bob", "example.com", 1)).await.unwrap();
+
+    let spec = Specification::from(UserCriterion::ByEmailDomain("example.com".into()));
+    let params = PaginationParams::new(1, 10);
+    let page = with_tenant(acme, repo.find_by_spec(&spec, &params)).await.unwrap();
+    assert_eq!(names(&page.items), ["alice"]);
+    assert_eq!(repo.find_by_spec(&spec, &params).await.unwrap().total, 2);
+}
+
+#[test]
+fn specifications_become_one_parenthesized_condition() {
+    let spec = Specification::from(UserCriterion::ByEmailDomain(" Example.COM".into())).and(
+        Specification::from(UserCriterion::CreatedAfter(Utc::now()))
+            .or(Specification::from(UserCriterion::HasStatus(UserStatus::Active)).not()),
+    );
+
+    let mut query = QueryBuilder::<Postgres>::new("SELECT id FROM users WHERE ");
+    push_specification(&mut query, &spec);
+    assert_eq!(
+        query.sql(),
+        "SELECT id FROM users WHERE ((split_part(email, '@', 2) = $1) AND ((created_at > $2) OR (NOT (status = $3))))"
+    );
+
+    let mut query = QueryBuilder::<Postgres>::new("");
+    push_specification(&mut query, &Specification::<User>::from(UserCriterion::InTenant(Uuid::new_v4())));
+    assert_eq!(query.sql(), "(tenant_id = $1)");
+}
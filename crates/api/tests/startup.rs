@@ -0,0 +1,23 @@
+//! Startup self-checks that need no database.
+
+use api::startup::{check_jwt_secret, redact, CheckStatus};
+
+#[test]
+fn weak_jwt_secrets_are_flagged() {
+    for secret in ["super-secret-key-change-in-production", "short", &"ab".repeat(32)] {
+        assert_eq!(check_jwt_secret(secret).status, CheckStatus::Warn, "{}", secret);
+    }
+    let random = "kT9vQ2xR7mWz4pLs8YbN3cHf6JdG1uEa5XoV0iKq";
+    assert_eq!(check_jwt_secret(random).status, CheckStatus::Ok);
+}
+
+#[test]
+fn secrets_and_url_passwords_are_redacted() {
+    assert_eq!(redact("SMTP_PASSWORD", "hunter2"), "[REDACTED]");
+    assert_eq!(
+        redact("DATABASE_URL", "postgres://app:hunter2@db:5432/app"),
+        "postgres://app:[REDACTED]@db:5432/app"
+    );
+    assert_eq!(redact("DATABASE_URL", "postgres://db/app"), "postgres://db/app");
+    assert_eq!(redact("JOB_WORKERS", "4"), "4");
+}
@@ -0,0 +1,94 @@
+//! Streaming reads: users streamed by filter, tenant fixed at creation, chunked processing.
+
+use std::sync::Arc;
+
+use application::streaming::{chunks, for_each_chunk};
+use application::tenancy::{with_tenant, TenantScopedUserRepository};
+use application::testing::MockUserRepository;
+use application::ApplicationError;
+use chrono::{Duration, Utc};
+use domain::{DomainError, User, UserFilter, UserRepository, UserStatus};
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use uuid::Uuid;
+
+fn user(name: &str, tenant_id: Uuid, minutes_ago: i64) -> User {
+    User {
+        tenant_id,
+        created_at: Utc::now() - Duration::minutes(minutes_ago),
+        ..User::new(name.parse().unwrap(), format!("{}@example.com", name).parse().unwrap(), String::new())
+    }
+}
+
+fn names(users: &[User]) -> Vec<&str> {
+    users.iter().map(|u| u.username.as_str()).collect()
+}
+
+#[tokio::test]
+async fn streams_follow_the_filter_and_the_tenant_of_their_creation() {
+    let (acme, globex) = (Uuid::new_v4(), Uuid::new_v4());
+    let suspended = User {
+        status: UserStatus::Suspended,
+        ..user("dave", acme, 0)
+    };
+    let repository = TenantScopedUserRepository::new(Arc::new(MockUserRepository::with_users([
+        user("alice", acme, 3),
+        user("bob", globex, 2),
+        user("carol", acme, 1),
+        suspended,
+    ])));
+
+    let all: Vec<User> = repository.stream_all(&UserFilter::default()).try_collect().await.unwrap();
+    assert_eq!(names(&all), ["dave", "carol", "bob", "alice"]);
+
+    let active = UserFilter {
+        status: Some(UserStatus::Active),
+        ..UserFilter::default()
+    };
+    // Created inside the tenant scope, polled outside it
+    let stream = with_tenant(acme, async { repository.stream_all(&active) }).await;
+    let scoped: Vec<User> = stream.try_collect().await.unwrap();
+    assert_eq!(names(&scoped), ["carol", "alice"]);
+}
+
+#[tokio::test]
+async fn chunks_hold_up_to_the_size_and_stop_at_errors() {
+    let items = stream::iter((1..=7).map(Ok::<_, DomainError>));
+    let sizes: Vec<Vec<i32>> = chunks(items, 3).try_collect().await.unwrap();
+    assert_eq!(sizes, [vec![1, 2, 3], vec![4, 5, 6], vec![7]]);
+
+    assert!(chunks(stream::iter(Vec::<Result<i32, DomainError>>::new()), 3).next().await.is_none());
+
+    let failing = stream::iter([Ok(1), Ok(2), Ok(3), Err(DomainError::internal("connection lost")), Ok(5)]);
+    let mut chunked = chunks(failing, 2);
+    assert_eq!(chunked.next().await.unwrap().unwrap(), [1, 2]);
+    assert!(chunked.next().await.unwrap().is_err());
+    assert!(chunked.next().await.is_none());
+}
+
+#[tokio::test]
+async fn each_chunk_is_processed_in_order() {
+    let users = MockUserRepository::with_users((0..5).map(|i| user(&format!("user{}", i), Uuid::new_v4(), i)));
+
+    let mut seen = Vec::new();
+    let processed = for_each_chunk(users.stream_all(&UserFilter::default()), 2, |chunk| {
+        seen.push(chunk.len());
+        async { Ok(()) }
+    })
+    .await
+    .unwrap();
+    assert_eq!((processed, seen), (5, vec![2, 2, 1]));
+
+    // An error from the callback stops before the next chunk is read
+    let mut calls = 0;
+    let result = for_each_chunk(users.stream_all(&UserFilter::default()), 2, |_| {
+        calls += 1;
+        async { Err(ApplicationError::use_case("disk full")) }
+    })
+    .await;
+    assert!(matches!(result, Err(ApplicationError::UseCase(_))));
+    assert_eq!(calls, 1);
+
+    users.set_failing(true);
+    let result = for_each_chunk(users.stream_all(&UserFilter::default()), 2, |_| async { Ok(()) }).await;
+    assert!(matches!(result, Err(ApplicationError::Domain(DomainError::Internal(_)))));
+}
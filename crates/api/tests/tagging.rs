@@ -0,0 +1,20 @@
+//! Tag name normalization and tag-list filters.
+
+use application::tagging::parse_tag_list;
+use domain::Tag;
+
+#[test]
+fn tag_names_are_normalized_and_validated() {
+    assert_eq!(Tag::normalize("  VIP ").unwrap(), "vip");
+    assert_eq!(Tag::normalize("region:eu-west_1").unwrap(), "region:eu-west_1");
+    assert!(Tag::normalize("").is_err());
+    assert!(Tag::normalize("two words").is_err());
+    assert!(Tag::normalize(&"x".repeat(Tag::MAX_NAME_LEN + 1)).is_err());
+}
+
+#[test]
+fn tag_filters_drop_blanks_and_duplicates() {
+    assert_eq!(parse_tag_list("vip, Beta,,vip").unwrap(), vec!["vip", "beta"]);
+    assert!(parse_tag_list("").unwrap().is_empty());
+    assert!(parse_tag_list("vip,no way").is_err());
+}
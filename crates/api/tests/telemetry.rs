@@ -0,0 +1,99 @@
+//! Telemetry backends: Prometheus exposition, OTLP export, statsd datagrams and selection.
+
+use std::net::UdpSocket;
+
+use infrastructure::{telemetry_from_settings, PrometheusTelemetry, StatsdTelemetry};
+use shared::telemetry::Telemetry;
+use shared::{Config, ConfigError, TelemetrySettings};
+
+#[test]
+fn prometheus_renders_counters_gauges_and_histograms() {
+    let metrics = PrometheusTelemetry::new("app");
+    let labels = [("route", "/users/:id"), ("method", "GET")];
+    metrics.increment("http_requests_total", 1, &labels);
+    metrics.increment("http_requests_total", 2, &labels);
+    metrics.gauge("db_pool_idle", 3.0, &[("pool", "primary")]);
+    metrics.observe("http_request_duration_seconds", 0.02, &[]);
+    metrics.observe("http_request_duration_seconds", 30.0, &[]);
+    metrics.increment("escaped_total", 1, &[("value", "a \"b\"")]);
+
+    let text = metrics.render();
+    assert!(text.contains("# TYPE app_http_requests_total counter\n"), "{}", text);
+    // Labels are sorted, so the same series never renders twice
+    assert!(text.contains("app_http_requests_total{method=\"GET\",route=\"/users/:id\"} 3\n"), "{}", text);
+    assert!(text.contains("app_db_pool_idle{pool=\"primary\"} 3\n"), "{}", text);
+    assert!(text.contains("app_http_request_duration_seconds_bucket{le=\"0.01\"} 0\n"), "{}", text);
+    assert!(text.contains("app_http_request_duration_seconds_bucket{le=\"0.025\"} 1\n"), "{}", text);
+    assert!(text.contains("app_http_request_duration_seconds_bucket{le=\"10\"} 1\n"), "{}", text);
+    assert!(text.contains("app_http_request_duration_seconds_bucket{le=\"+Inf\"} 2\n"), "{}", text);
+    assert!(text.contains("app_http_request_duration_seconds_count 2\n"), "{}", text);
+    assert!(text.contains("app_escaped_total{value=\"a \\\"b\\\"\"} 1\n"), "{}", text);
+}
+
+#[test]
+fn otlp_export_carries_the_same_series() {
+    let metrics = PrometheusTelemetry::new("app");
+    metrics.increment("jobs_total", 5, &[("queue", "email")]);
+    metrics.observe("job_seconds", 0.2, &[]);
+
+    let request = metrics.otlp_request("api");
+    let resource = &request["resourceMetrics"][0];
+    assert_eq!(resource["resource"]["attributes"][0]["value"]["stringValue"], "api");
+    let exported = resource["scopeMetrics"][0]["metrics"].as_array().unwrap();
+
+    let counter = exported.iter().find(|m| m["name"] == "app_jobs_total").unwrap();
+    assert_eq!(counter["sum"]["isMonotonic"], true);
+    assert_eq!(counter["sum"]["dataPoints"][0]["asInt"], "5");
+    assert_eq!(counter["sum"]["dataPoints"][0]["attributes"][0]["key"], "queue");
+
+    let histogram = exported.iter().find(|m| m["name"] == "app_job_seconds").unwrap();
+    let point = &histogram["histogram"]["dataPoints"][0];
+    assert_eq!(point["count"], "1");
+    assert_eq!(
+        point["bucketCounts"].as_array().unwrap().len(),
+        point["explicitBounds"].as_array().unwrap().len() + 1
+    );
+}
+
+#[test]
+fn statsd_sends_tagged_datagrams() {
+    let agent = UdpSocket::bind("127.0.0.1:0").unwrap();
+    agent.set_read_timeout(Some(std::time::Duration::from_secs(5))).unwrap();
+    let metrics = StatsdTelemetry::new(&agent.local_addr().unwrap().to_string(), "app").unwrap();
+
+    metrics.increment("logins_total", 2, &[("method", "password"), ("tenant", "default")]);
+    let mut buf = [0u8; 512];
+    let len = agent.recv(&mut buf).unwrap();
+    assert_eq!(&buf[..len], b"app.logins_total:2|c|#method:password,tenant:default");
+
+    assert_eq!(metrics.line("db_pool_idle", "4", "g", &[]), "app.db_pool_idle:4|g");
+}
+
+#[tokio::test]
+async fn backend_is_selected_and_validated_from_config() {
+    let backend = telemetry_from_settings(&TelemetrySettings::default(), "api").unwrap();
+    assert!(backend.prometheus.is_none());
+
+    let prometheus = TelemetrySettings {
+        backend: "prometheus".to_string(),
+        ..Default::default()
+    };
+    let backend = telemetry_from_settings(&prometheus, "api").unwrap();
+    backend.telemetry.increment("selected_total", 1, &[]);
+    assert!(backend.prometheus.unwrap().render().contains("rust_base_selected_total 1"));
+
+    let mut config = Config::default();
+    config.database.url = "postgres://db/app".to_string();
+    config.telemetry = TelemetrySettings {
+        backend: "statsd".to_string(),
+        otlp_endpoint: Some("http://collector:4318/v1/metrics".to_string()),
+        ..Default::default()
+    };
+    let Err(ConfigError::Invalid(problems)) = config.validate("development") else {
+        panic!("expected an OTLP endpoint without the prometheus backend to be rejected");
+    };
+    assert!(problems.iter().any(|p| p.starts_with("telemetry")), "{:?}", problems);
+
+    config.telemetry.backend = "graphite".to_string();
+    assert!(config.validate("development").is_err());
+}
@@ -0,0 +1,73 @@
+//! Tenant resolution and tenant-scoped user queries.
+
+use std::sync::Arc;
+
+use api::tenants::{require_default_tenant, subdomain};
+use application::tenancy::{with_tenant, TenantScopedUserRepository};
+use application::testing::MockUserRepository;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::routing::get;
+use axum::{middleware, Router};
+use domain::{DomainError, Repository, Tenant, User, UserRepository};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[test]
+fn tenant_is_the_first_label_below_the_base_domain() {
+    assert_eq!(subdomain("acme.example.com", "example.com"), Some("acme"));
+    assert_eq!(subdomain("Acme.Example.com:8443", "example.com"), Some("Acme"));
+    assert_eq!(subdomain("example.com", "example.com"), None);
+    assert_eq!(subdomain("www.example.com", "example.com"), None);
+    assert_eq!(subdomain("a.b.example.com", "example.com"), None);
+    assert_eq!(subdomain("acme.notexample.com", "example.com"), None);
+}
+
+fn user(name: &str) -> User {
+    User::new(name.parse().unwrap(), format!("{}@example.com", name).parse().unwrap(), String::new())
+}
+
+#[tokio::test]
+async fn scoped_repository_hides_other_tenants() {
+    let repo = TenantScopedUserRepository::new(Arc::new(MockUserRepository::new()));
+    let acme = Uuid::new_v4();
+
+    let alice = with_tenant(acme, repo.create(&user("alice"))).await.unwrap();
+    let bob = with_tenant(Tenant::DEFAULT_ID, repo.create(&user("bob"))).await.unwrap();
+    assert_eq!(alice.tenant_id, acme);
+    assert_eq!(bob.tenant_id, Tenant::DEFAULT_ID);
+
+    with_tenant(acme, async {
+        assert!(repo.find_by_id(bob.id).await.unwrap().is_none());
+        assert!(repo.find_by_email("bob@example.com").await.unwrap().is_none());
+        assert_eq!(repo.count().await.unwrap(), 1);
+        assert!(matches!(repo.update(&bob).await, Err(DomainError::NotFound { .. })));
+        assert!(!repo.delete(bob.id).await.unwrap());
+    })
+    .await;
+
+    // Outside a tenant context (jobs, gRPC) nothing is filtered
+    assert_eq!(repo.count().await.unwrap(), 2);
+}
+
+#[tokio::test]
+async fn cross_tenant_admin_routes_refuse_other_tenants() {
+    // The admin routes spanning every tenant sit behind this guard
+    let app = Router::new()
+        .route("/admin/jobs", get(|| async { "jobs" }))
+        .route("/admin/data/:table", get(|| async { "rows" }))
+        .route("/admin/webhooks", get(|| async { "webhooks" }))
+        .route_layer(middleware::from_fn(require_default_tenant));
+    let call = |tenant: Uuid, path: &'static str| {
+        let app = app.clone();
+        with_tenant(tenant, async move {
+            let request = Request::get(path).body(Body::empty()).unwrap();
+            app.oneshot(request).await.unwrap().status()
+        })
+    };
+
+    for path in ["/admin/jobs", "/admin/data/users", "/admin/webhooks"] {
+        assert_eq!(call(Uuid::new_v4(), path).await, StatusCode::FORBIDDEN, "{}", path);
+        assert_eq!(call(Tenant::DEFAULT_ID, path).await, StatusCode::OK, "{}", path);
+    }
+}
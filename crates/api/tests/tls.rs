@@ -0,0 +1,97 @@
+//! HTTPS served by the binary: certificate hot reload and the HTTP -> HTTPS redirect.
+
+use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use api::server::serve;
+use api::tls::{https_redirect, ReloadableTls};
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::{header, Request, StatusCode},
+    routing::get,
+    Router,
+};
+use shared::ServerConfig;
+use tokio::net::TcpListener;
+use tower::ServiceExt;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/tls").join(name)
+}
+
+/// Copy a fixture certificate and key to where the server reads them from
+fn install(dir: &Path, name: &str) {
+    fs::copy(fixture(&format!("{}.cert.pem", name)), dir.join("cert.pem")).unwrap();
+    fs::copy(fixture(&format!("{}.key.pem", name)), dir.join("key.pem")).unwrap();
+}
+
+/// Client trusting only the named fixture certificate
+fn client_trusting(name: &str) -> reqwest::Client {
+    let cert = reqwest::Certificate::from_pem(&fs::read(fixture(&format!("{}.cert.pem", name))).unwrap()).unwrap();
+    reqwest::Client::builder()
+        .tls_built_in_root_certs(false)
+        .add_root_certificate(cert)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn serves_https_and_picks_up_a_reloaded_certificate() {
+    let dir = std::env::temp_dir().join(format!("tls-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    install(&dir, "first");
+    let tls = Arc::new(ReloadableTls::load(dir.join("cert.pem"), dir.join("key.pem")).unwrap());
+
+    let app = Router::new().route(
+        "/ip",
+        get(|ConnectInfo(remote): ConnectInfo<SocketAddr>| async move { remote.ip().to_string() }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let tls_server = tls.clone();
+    let url = format!("https://localhost:{}/ip", listener.local_addr().unwrap().port());
+    let server = tokio::spawn(async move {
+        serve(listener, &ServerConfig::default(), Some(tls_server), app, std::future::pending()).await
+    });
+
+    let response = client_trusting("first").get(&url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text().await.unwrap(), "127.0.0.1");
+    assert!(client_trusting("second").get(&url).send().await.is_err());
+
+    // Renewed certificate on disk, then SIGHUP
+    install(&dir, "second");
+    tls.reload().unwrap();
+    assert_eq!(client_trusting("second").get(&url).send().await.unwrap().status(), StatusCode::OK);
+
+    // A broken renewal keeps the working certificate
+    fs::write(dir.join("key.pem"), "not a key").unwrap();
+    assert!(tls.reload().is_err());
+    assert_eq!(client_trusting("second").get(&url).send().await.unwrap().status(), StatusCode::OK);
+
+    server.abort();
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn mismatched_key_is_rejected_at_startup() {
+    let err = ReloadableTls::load(fixture("first.cert.pem"), fixture("second.key.pem")).err().unwrap();
+    assert!(err.to_string().contains("second.key.pem"), "{}", err);
+}
+
+#[tokio::test]
+async fn plain_http_is_redirected_to_https() {
+    let redirect = |port: u16, host: &str| {
+        let request = Request::get("/api/v1/users?page=2").header(header::HOST, host).body(Body::empty()).unwrap();
+        async move {
+            let response = https_redirect(port).oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+            response.headers()[header::LOCATION].to_str().unwrap().to_string()
+        }
+    };
+
+    assert_eq!(redirect(8443, "api.example.com:8080").await, "https://api.example.com:8443/api/v1/users?page=2");
+    assert_eq!(redirect(443, "api.example.com").await, "https://api.example.com/api/v1/users?page=2");
+}
@@ -0,0 +1,119 @@
+//! User exports: format negotiation, row encoding and walking every user in keyset chunks.
+
+use std::sync::Arc;
+
+use api::admin::{AdminUserResponse, ExportFormat};
+use application::admin::{AdminUserService, AdminUserServiceImpl};
+use application::tenancy::{with_tenant, TenantScopedUserRepository};
+use application::testing::MockUserRepository;
+use axum::http::{header, HeaderMap, HeaderValue};
+use chrono::{Duration, Utc};
+use domain::{User, UserCursor, UserFilter, UserStatus};
+use uuid::Uuid;
+
+fn accept(value: &str) -> Option<ExportFormat> {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::ACCEPT, HeaderValue::from_str(value).unwrap());
+    ExportFormat::from_accept(&headers)
+}
+
+fn response() -> AdminUserResponse {
+    AdminUserResponse {
+        id: "550e8400-e29b-41d4-a716-446655440000".into(),
+        username: "john_doe".into(),
+        email: "john@example.com".into(),
+        status: "active".into(),
+        password_reset_required: false,
+        avatar_url: Some("https://cdn.example.com/a,b.png".into()),
+        created_at: "2026-10-17T00:00:00+00:00".into(),
+        email_suppression: None,
+        tags: vec!["beta".into(), "vip".into()],
+    }
+}
+
+#[test]
+fn streaming_formats_are_chosen_by_accept() {
+    assert_eq!(accept("text/csv"), Some(ExportFormat::Csv));
+    assert_eq!(accept("application/json;q=0.9, Application/X-NDJSON"), Some(ExportFormat::Ndjson));
+    assert_eq!(accept("text/csv; charset=utf-8"), Some(ExportFormat::Csv));
+    assert_eq!(accept("application/json"), None);
+    assert_eq!(accept("*/*"), None);
+    assert_eq!(ExportFormat::from_accept(&HeaderMap::new()), None);
+}
+
+#[test]
+fn rows_are_encoded_one_per_line() {
+    let mut csv = String::new();
+    ExportFormat::Csv.write(&mut csv, &response());
+    assert_eq!(
+        csv,
+        "550e8400-e29b-41d4-a716-446655440000,john_doe,john@example.com,active,false,\"https://cdn.example.com/a,b.png\",2026-10-17T00:00:00+00:00,,beta;vip\r\n"
+    );
+
+    // Spreadsheets must not read user-chosen fields as formulas
+    let formulas = AdminUserResponse {
+        username: "=cmd|' /C calc'!A0".into(),
+        avatar_url: None,
+        tags: vec!["@vip".into(), "+1".into()],
+        ..response()
+    };
+    let mut csv = String::new();
+    ExportFormat::Csv.write(&mut csv, &formulas);
+    let fields: Vec<&str> = csv.trim_end().split(',').collect();
+    assert_eq!(fields[1], "'=cmd|' /C calc'!A0");
+    assert_eq!(fields[8], "'@vip;+1");
+
+    let mut ndjson = String::new();
+    ExportFormat::Ndjson.write(&mut ndjson, &response());
+    ExportFormat::Ndjson.write(&mut ndjson, &response());
+    let lines: Vec<serde_json::Value> = ndjson.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["tags"], serde_json::json!(["beta", "vip"]));
+}
+
+#[tokio::test]
+async fn chunks_cover_every_user_once_in_order() {
+    let (acme, globex) = (Uuid::new_v4(), Uuid::new_v4());
+    let now = Utc::now();
+    // Pairs share a creation time, so the cursor has to break ties by id
+    let users: Vec<User> = (0..25)
+        .map(|i| User {
+            tenant_id: if i % 5 == 0 { globex } else { acme },
+            created_at: now - Duration::seconds(i / 2),
+            status: if i == 3 { UserStatus::Suspended } else { UserStatus::Active },
+            ..User::new(format!("user{}", i).parse().unwrap(), format!("user{}@example.com", i).parse().unwrap(), String::new())
+        })
+        .collect();
+    let repository = TenantScopedUserRepository::new(Arc::new(MockUserRepository::with_users(users.clone())));
+    let service = AdminUserServiceImpl::new(Arc::new(repository));
+
+    let export = |filter: UserFilter| {
+        let service = &service;
+        async move {
+            let (mut seen, mut after) = (Vec::new(), None);
+            loop {
+                let chunk = service.search_after(&filter, after, 4).await.unwrap();
+                after = chunk.last().map(UserCursor::from);
+                seen.extend(chunk.into_iter().map(|u| u.id));
+                if after.is_none() {
+                    return seen;
+                }
+            }
+        }
+    };
+
+    let mut expected = users.clone();
+    expected.sort_by_key(|u| std::cmp::Reverse((u.created_at, u.id)));
+    let all = export(UserFilter::default()).await;
+    assert_eq!(all, expected.iter().map(|u| u.id).collect::<Vec<_>>());
+
+    let suspended = UserFilter {
+        status: Some(UserStatus::Suspended),
+        ..UserFilter::default()
+    };
+    assert_eq!(export(suspended).await, [users[3].id]);
+
+    let scoped = with_tenant(globex, export(UserFilter::default())).await;
+    assert_eq!(scoped.len(), 5);
+    assert!(scoped.iter().all(|id| users.iter().any(|u| u.id == *id && u.tenant_id == globex)));
+}
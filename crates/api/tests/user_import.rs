@@ -0,0 +1,202 @@
+//! User imports: CSV and NDJSON rows, per-row errors, batched transactions and background runs.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use application::imports::{ImportFormat, ImportSummary, ParsedLine, RowParser, UserImportJob, UserImporter, UserImports};
+use application::jobs::Job;
+use application::operations::{Operation, OperationStatus, OperationStore};
+use application::storage::FileStorage;
+use application::testing::{MockPasswordHasher, MockUserRepository, RecordingJobQueue};
+use application::{ApplicationError, Transaction, UnitOfWork};
+use async_trait::async_trait;
+use domain::{DomainError, User};
+use infrastructure::LocalFileStorage;
+use uuid::Uuid;
+
+/// Counts committed transactions; the mock repository ignores them
+#[derive(Default)]
+struct CountingUnitOfWork {
+    commits: Arc<AtomicUsize>,
+}
+
+struct CountingTransaction {
+    commits: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl UnitOfWork for CountingUnitOfWork {
+    async fn begin(&self) -> Result<Arc<dyn Transaction>, DomainError> {
+        Ok(Arc::new(CountingTransaction {
+            commits: self.commits.clone(),
+        }))
+    }
+}
+
+#[async_trait]
+impl Transaction for CountingTransaction {
+    async fn commit(&self) -> Result<(), DomainError> {
+        self.commits.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn rollback(&self) -> Result<(), DomainError> {
+        Ok(())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Default)]
+struct MemoryOperations {
+    operations: Mutex<HashMap<Uuid, Operation>>,
+}
+
+#[async_trait]
+impl OperationStore for MemoryOperations {
+    async fn create(&self, operation: &Operation) -> Result<(), ApplicationError> {
+        self.operations.lock().unwrap().insert(operation.id, operation.clone());
+        Ok(())
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<Operation>, ApplicationError> {
+        Ok(self.operations.lock().unwrap().get(&id).cloned())
+    }
+
+    async fn update(&self, operation: &Operation) -> Result<(), ApplicationError> {
+        self.operations.lock().unwrap().insert(operation.id, operation.clone());
+        Ok(())
+    }
+}
+
+fn importer(users: Arc<MockUserRepository>, unit_of_work: Arc<CountingUnitOfWork>) -> UserImporter {
+    UserImporter::new(users, Arc::new(MockPasswordHasher::new()), unit_of_work)
+}
+
+async fn import(importer: &UserImporter, format: ImportFormat, input: &str) -> Result<ImportSummary, ApplicationError> {
+    let mut run = importer.start(format);
+    for line in input.lines() {
+        run.push_line(line).await?;
+    }
+    Ok(ImportSummary::from(run.finish().await?))
+}
+
+#[test]
+fn csv_rows_follow_the_header() {
+    let mut parser = RowParser::new(ImportFormat::Csv);
+    assert_eq!(parser.parse("Email,username,password,role").unwrap(), ParsedLine::Skip);
+    match parser.parse(r#"bob@example.com,"Bob ""the builder""",Correct-Horse-7,admin"#).unwrap() {
+        ParsedLine::Row(row) => {
+            assert_eq!(row.username, r#"Bob "the builder""#);
+            assert_eq!(row.email, "bob@example.com");
+        }
+        other => panic!("expected a row, got {:?}", other),
+    }
+    assert_eq!(parser.parse("  \r\n").unwrap(), ParsedLine::Skip);
+    assert!(matches!(parser.parse("carol@example.com").unwrap(), ParsedLine::Invalid(_)));
+    assert!(matches!(parser.parse(r#""carol,carol@example.com,pw"#).unwrap(), ParsedLine::Invalid(_)));
+
+    let mut parser = RowParser::new(ImportFormat::Csv);
+    assert!(matches!(parser.parse("username,email"), Err(DomainError::Validation(_))));
+
+    assert_eq!(ImportFormat::from_content_type("text/csv; charset=utf-8"), Some(ImportFormat::Csv));
+    assert_eq!(ImportFormat::from_content_type("application/x-ndjson"), Some(ImportFormat::Ndjson));
+    assert_eq!(ImportFormat::from_content_type("application/json"), None);
+}
+
+#[tokio::test]
+async fn bad_rows_are_reported_by_line_and_the_rest_imported() {
+    let existing = User::new("taken".parse().unwrap(), "taken@example.com".parse().unwrap(), String::new());
+    let users = Arc::new(MockUserRepository::with_users([existing]));
+    let importer = importer(users.clone(), Arc::default());
+
+    let input = "username,email,password
+alice,alice@example.com,Correct-Horse-7
+
+bob,not-an-email,Correct-Horse-7
+carol,Taken@example.com,Correct-Horse-7
+dave,ALICE@example.com,Correct-Horse-7
+erin,erin@example.com,short
+frank,frank@example.com,Correct-Horse-7
+";
+    let summary = import(&importer, ImportFormat::Csv, input).await.unwrap();
+    assert_eq!((summary.total, summary.imported), (6, 2));
+    let errors: Vec<(u64, &str)> = summary
+        .errors
+        .iter()
+        .map(|row| (row.line, row.error.as_deref().unwrap()))
+        .collect();
+    assert_eq!(errors[0], (4, "Validation failed: Email is not a valid address"));
+    assert_eq!(errors[1], (5, "Conflict: Email already registered"));
+    assert_eq!(errors[2], (6, "Conflict: Email appears earlier in this import"));
+    assert_eq!(errors[3].0, 7);
+
+    let imported: Vec<User> = users.users().into_iter().filter(|u| u.username != "taken").collect();
+    assert_eq!(imported.len(), 2);
+    assert!(imported.iter().all(|u| u.password_reset_required && u.password_hash == "mock$Correct-Horse-7"));
+}
+
+#[tokio::test]
+async fn rows_are_inserted_in_batches() {
+    let users = Arc::new(MockUserRepository::new());
+    let unit_of_work = Arc::new(CountingUnitOfWork::default());
+    let importer = importer(users.clone(), unit_of_work.clone()).with_batch_size(2);
+
+    let input: String = (0..5)
+        .map(|i| format!("{{\"username\":\"user{0}\",\"email\":\"user{0}@example.com\",\"password\":\"Correct-Horse-7\"}}\n", i))
+        .chain(["{\"username\":\"broken\"}\n".to_string()])
+        .collect();
+    let summary = import(&importer, ImportFormat::Ndjson, &input).await.unwrap();
+    assert_eq!((summary.total, summary.imported, summary.errors[0].line), (6, 5, 6));
+    assert!(summary.errors[0].error.as_deref().unwrap().starts_with("Invalid JSON"));
+    // Two full batches and the remainder
+    assert_eq!(unit_of_work.commits.load(Ordering::SeqCst), 3);
+    assert_eq!(users.users().len(), 5);
+
+    // A header without the required columns stops the import
+    assert!(matches!(
+        import(&importer, ImportFormat::Csv, "name,email\nx,x@example.com\n").await,
+        Err(ApplicationError::Domain(DomainError::Validation(_)))
+    ));
+}
+
+#[tokio::test]
+async fn large_imports_run_as_operations() {
+    let root = std::env::temp_dir().join(format!("user-import-{}", Uuid::new_v4()));
+    let storage: Arc<dyn FileStorage> = Arc::new(LocalFileStorage::new(&root, "https://api.example.com/files", "secret"));
+    let users = Arc::new(MockUserRepository::new());
+    let importer = Arc::new(importer(users.clone(), Arc::default()));
+    let operations = Arc::new(MemoryOperations::default());
+    let queue = Arc::new(RecordingJobQueue::new());
+    let imports = UserImports::new(importer.clone(), operations.clone(), queue.clone(), storage.clone()).with_limits(64, 1024);
+    assert!(imports.runs_inline(Some(64)));
+    assert!(!imports.runs_inline(Some(65)));
+    assert!(!imports.runs_inline(None));
+
+    let admin = Uuid::new_v4();
+    let input = "username,email,password\nalice,alice@example.com,Correct-Horse-7\nbob,bob@example,Correct-Horse-7\n";
+    let operation = imports.submit(admin, ImportFormat::Csv, input.as_bytes().to_vec()).await.unwrap();
+    assert_eq!((operation.kind.as_str(), operation.status), ("users.import", OperationStatus::Pending));
+    assert!(matches!(
+        imports.submit(admin, ImportFormat::Csv, vec![b'x'; 1025]).await,
+        Err(ApplicationError::Domain(DomainError::Validation(_)))
+    ));
+
+    let job = UserImportJob::new(importer, operations.clone(), storage.clone());
+    let payload = queue.take(UserImportJob::KIND).remove(0).0;
+    job.run(payload.clone()).await.unwrap();
+
+    let done = operations.get(operation.id).await.unwrap().unwrap();
+    assert_eq!((done.status, done.total, done.failed_items()), (OperationStatus::Succeeded, Some(2), 1));
+    assert_eq!(done.items.iter().map(|item| item.line).collect::<Vec<_>>(), [Some(2), Some(3)]);
+    assert_eq!(done.items[0].id, users.users()[0].id);
+    // The upload is removed once imported, and a rerun changes nothing
+    let key = payload["key"].as_str().unwrap();
+    assert!(storage.get(key).await.unwrap().is_none());
+    job.run(payload).await.unwrap();
+    assert_eq!(users.users().len(), 1);
+}
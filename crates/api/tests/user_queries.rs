@@ -0,0 +1,66 @@
+//! User queries: projections served by the read model, tenant scoping and what views leave out.
+
+use std::sync::Arc;
+
+use application::queries::UserQueryService;
+use application::tenancy::with_tenant;
+use application::testing::MockUserRepository;
+use application::ApplicationError;
+use chrono::{Duration, Utc};
+use domain::{DomainError, PaginationParams, User, UserView};
+use uuid::Uuid;
+
+fn user(name: &str, tenant_id: Uuid, days_ago: i64) -> User {
+    User {
+        tenant_id,
+        created_at: Utc::now() - Duration::days(days_ago),
+        password_hash: "secret-hash".into(),
+        ..User::new(name.parse().unwrap(), format!("{}@example.com", name).parse().unwrap(), String::new())
+    }
+}
+
+#[tokio::test]
+async fn views_carry_what_is_displayed_and_no_secrets() {
+    let alice = user("alice", Uuid::new_v4(), 1);
+    let queries = UserQueryService::new(Arc::new(MockUserRepository::with_users([alice.clone()])));
+
+    let view = queries.get(alice.id).await.unwrap().unwrap();
+    assert_eq!(view, UserView::from(&alice));
+    assert_eq!((view.username.as_str(), view.email.as_str()), ("alice", "alice@example.com"));
+    let json = serde_json::to_value(&view).unwrap();
+    assert!(json.get("password_hash").is_none());
+    assert!(json.get("token_version").is_none());
+
+    assert!(queries.get(Uuid::new_v4()).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn listings_are_newest_first_and_tenant_scoped() {
+    let (acme, globex) = (Uuid::new_v4(), Uuid::new_v4());
+    let queries = UserQueryService::new(Arc::new(MockUserRepository::with_users([
+        user("alice", acme, 3),
+        user("bob", globex, 2),
+        user("carol", acme, 1),
+    ])));
+    let params = PaginationParams::new(1, 10);
+    let names = |views: &[UserView]| views.iter().map(|v| v.username.to_string()).collect::<Vec<_>>();
+
+    let page = queries.list(&params).await.unwrap();
+    assert_eq!(names(&page.items), ["carol", "bob", "alice"]);
+
+    let page = with_tenant(acme, queries.list(&params)).await.unwrap();
+    assert_eq!((names(&page.items), page.total), (vec!["carol".to_string(), "alice".to_string()], 2));
+
+    let bob = queries.list(&params).await.unwrap().items[1].id;
+    assert!(with_tenant(acme, queries.get(bob)).await.unwrap().is_none());
+    assert!(with_tenant(globex, queries.get(bob)).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn deep_pages_are_rejected_before_querying() {
+    let queries = UserQueryService::new(Arc::new(MockUserRepository::new()));
+    assert!(matches!(
+        queries.list(&PaginationParams::new(1_000_000, 100)).await,
+        Err(ApplicationError::Domain(DomainError::Validation(_)))
+    ));
+}
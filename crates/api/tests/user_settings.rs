@@ -0,0 +1,123 @@
+//! User settings: defaults until the first save, partial updates and locale/time zone validation.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use application::settings::{normalize_locale, validate_timezone, NotificationUpdate, SettingsUpdate, UserSettingsService};
+use application::ApplicationError;
+use async_trait::async_trait;
+use domain::{DomainError, NotificationPreferences, UserSettings, UserSettingsRepository};
+use uuid::Uuid;
+
+#[derive(Default)]
+struct MemorySettings {
+    settings: Mutex<HashMap<Uuid, UserSettings>>,
+}
+
+#[async_trait]
+impl UserSettingsRepository for MemorySettings {
+    async fn find(&self, user_id: Uuid) -> Result<Option<UserSettings>, DomainError> {
+        Ok(self.settings.lock().unwrap().get(&user_id).cloned())
+    }
+
+    async fn upsert(&self, settings: &UserSettings) -> Result<UserSettings, DomainError> {
+        self.settings.lock().unwrap().insert(settings.user_id, settings.clone());
+        Ok(settings.clone())
+    }
+}
+
+fn service() -> (UserSettingsService, Arc<MemorySettings>) {
+    let store = Arc::new(MemorySettings::default());
+    (UserSettingsService::new(store.clone()), store)
+}
+
+#[tokio::test]
+async fn unsaved_settings_read_as_the_defaults() {
+    let (service, store) = service();
+    let user_id = Uuid::new_v4();
+
+    let settings = service.get(user_id).await.unwrap();
+
+    assert_eq!(settings.locale, "en");
+    assert_eq!(settings.timezone, "UTC");
+    assert_eq!(settings.notifications, NotificationPreferences::default());
+    assert!(store.settings.lock().unwrap().is_empty(), "reading must not save a row");
+}
+
+#[tokio::test]
+async fn updates_only_change_the_fields_given() {
+    let (service, _) = service();
+    let user_id = Uuid::new_v4();
+
+    service
+        .update(
+            user_id,
+            SettingsUpdate {
+                locale: Some("pt-br".to_string()),
+                notifications: NotificationUpdate { weekly_digest: Some(true), ..Default::default() },
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let settings = service
+        .update(
+            user_id,
+            SettingsUpdate {
+                timezone: Some("America/Sao_Paulo".to_string()),
+                notifications: NotificationUpdate { push: Some(false), ..Default::default() },
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(settings.locale, "pt-BR");
+    assert_eq!(settings.timezone, "America/Sao_Paulo");
+    assert!(settings.notifications.weekly_digest);
+    assert!(!settings.notifications.push);
+    assert!(settings.notifications.email);
+    assert_eq!(service.get(user_id).await.unwrap(), settings);
+}
+
+#[tokio::test]
+async fn invalid_values_are_rejected_without_saving() {
+    let (service, store) = service();
+    let user_id = Uuid::new_v4();
+
+    let result = service
+        .update(
+            user_id,
+            SettingsUpdate {
+                locale: Some("fr".to_string()),
+                timezone: Some("Mars/Olympus_Mons".to_string()),
+                ..Default::default()
+            },
+        )
+        .await;
+
+    assert!(matches!(result, Err(ApplicationError::Domain(DomainError::Validation(_)))));
+    assert!(store.settings.lock().unwrap().is_empty());
+}
+
+#[test]
+fn locales_are_put_in_canonical_case() {
+    assert_eq!(normalize_locale("EN").unwrap(), "en");
+    assert_eq!(normalize_locale("pt_br").unwrap(), "pt-BR");
+    assert_eq!(normalize_locale("zh-hant-tw").unwrap(), "zh-Hant-TW");
+    assert_eq!(normalize_locale("es-419").unwrap(), "es-419");
+
+    for invalid in ["", "e", "english", "en-", "en US", "12-US"] {
+        assert!(normalize_locale(invalid).is_err(), "{:?} should be rejected", invalid);
+    }
+}
+
+#[test]
+fn time_zones_must_look_like_iana_names() {
+    for valid in ["UTC", "Europe/Berlin", "America/Argentina/Salta", "Etc/GMT+5", "America/Port-au-Prince"] {
+        assert_eq!(validate_timezone(valid).unwrap(), valid);
+    }
+    for invalid in ["", "utc", "Berlin", "Europe/", "Moon/Base", "Europe/Ber lin", "../etc/passwd"] {
+        assert!(validate_timezone(invalid).is_err(), "{:?} should be rejected", invalid);
+    }
+}
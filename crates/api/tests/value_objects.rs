@@ -0,0 +1,102 @@
+//! Email and Username value objects: normalization, rejection, and users built from them.
+
+use std::sync::Arc;
+
+use application::activity::LoginClient;
+use application::testing::{MockPasswordHasher, MockTokenService, MockUserRepository};
+use application::{ApplicationError, AuthService, AuthServiceImpl};
+use domain::{DomainError, Email, User, UserRepository, Username};
+use infrastructure::InMemoryEventBus;
+
+#[test]
+fn emails_are_trimmed_and_lowercased() {
+    let email = Email::parse("  Alice@Example.COM ").unwrap();
+    assert_eq!(email, "alice@example.com");
+    assert_eq!(email.domain(), "example.com");
+    assert_eq!(email, "ALICE@example.com".parse::<Email>().unwrap());
+
+    for invalid in ["", "   ", "alice", "@example.com", "alice@", "alice@localhost", "a@b@example.com", "al ice@example.com"] {
+        assert!(
+            matches!(Email::parse(invalid), Err(DomainError::Validation(_))),
+            "{:?} should be rejected",
+            invalid
+        );
+    }
+    let long = format!("{}@example.com", "a".repeat(Email::MAX_LEN));
+    assert!(Email::parse(long).is_err());
+}
+
+#[test]
+fn gmail_dots_are_dropped_only_when_asked() {
+    let gmail = Email::parse("J.Doe@GMail.com").unwrap();
+    assert_eq!(gmail.normalized_with(false), "j.doe@gmail.com");
+    assert_eq!(gmail.normalized_with(true), "jdoe@gmail.com");
+    assert_eq!(Email::parse("j.d.o.e@googlemail.com").unwrap().normalized_with(true), "jdoe@googlemail.com");
+    // Other providers may treat dots as significant
+    assert_eq!(Email::parse("j.doe@example.com").unwrap().normalized_with(true), "j.doe@example.com");
+
+    assert_eq!(domain::normalize_email(" Jane@Example.com "), "jane@example.com");
+    assert_eq!(domain::normalize_email(" Not An Address "), "not an address");
+}
+
+#[test]
+fn usernames_are_trimmed_and_keep_their_case() {
+    assert_eq!(Username::parse("  John_Doe ").unwrap(), "John_Doe");
+    assert!(Username::parse("ab").is_err());
+    assert!(Username::parse("  ab  ").is_err());
+    assert!(Username::parse("x".repeat(Username::MAX_LEN)).is_ok());
+    assert!(Username::parse("x".repeat(Username::MAX_LEN + 1)).is_err());
+    assert!(Username::parse("bad\nname").is_err());
+    // Characters, not bytes
+    assert!(Username::parse("ééé").is_ok());
+}
+
+#[test]
+fn deserializing_goes_through_the_same_rules() {
+    let user = User::new("alice".parse().unwrap(), "alice@example.com".parse().unwrap(), String::new());
+    let mut json = serde_json::to_value(&user).unwrap();
+    assert_eq!(json["email"], "alice@example.com");
+    // Never serialized, but required to read a user back
+    json["password_hash"] = "".into();
+
+    json["email"] = " Alice@Example.com".into();
+    let parsed: User = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(parsed.email, "alice@example.com");
+
+    json["email"] = "not an email".into();
+    assert!(serde_json::from_value::<User>(json).is_err());
+}
+
+#[tokio::test]
+async fn accounts_store_and_match_normalized_emails() {
+    let users = Arc::new(MockUserRepository::new());
+    let auth = AuthServiceImpl::new(
+        users.clone(),
+        Arc::new(MockPasswordHasher::new()),
+        Arc::new(MockTokenService::new()),
+        Arc::new(InMemoryEventBus::default()),
+    );
+
+    let user = auth
+        .register(" bob ".into(), "Bob@Example.com".into(), "Correct-Horse-7".into())
+        .await
+        .unwrap();
+    assert_eq!((user.username.as_str(), user.email.as_str()), ("bob", "bob@example.com"));
+
+    assert!(auth.login("BOB@example.com ".into(), "Correct-Horse-7".into(), LoginClient::default()).await.is_ok());
+    assert_eq!(users.find_by_email(" BoB@example.COM").await.unwrap().map(|u| u.id), Some(user.id));
+    assert!(matches!(
+        auth.register("bobby".into(), "bob@EXAMPLE.com".into(), "Correct-Horse-7".into()).await,
+        Err(ApplicationError::Domain(DomainError::Conflict { .. }))
+    ));
+    assert!(matches!(
+        auth.register("carol".into(), "carol@nowhere".into(), "Correct-Horse-7".into()).await,
+        Err(ApplicationError::Domain(DomainError::Validation(_)))
+    ));
+    // Not an address at all: same answer as a wrong password
+    assert!(matches!(
+        auth.login("bob".into(), "Correct-Horse-7".into(), LoginClient::default()).await,
+        Err(ApplicationError::Domain(DomainError::Unauthorized(_)))
+    ));
+    assert_eq!(users.users().len(), 1);
+}
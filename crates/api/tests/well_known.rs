@@ -0,0 +1,108 @@
+//! `/.well-known/` documents generated from `[well_known]`.
+
+use api::well_known::{security_txt, well_known_routes};
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    response::Response,
+    Router,
+};
+use chrono::{TimeZone, Utc};
+use shared::{Config, ConfigError, SecurityTxtSettings, WellKnownSettings};
+use tower::ServiceExt;
+
+async fn get(app: &Router, path: &str) -> Response {
+    app.clone()
+        .oneshot(Request::get(path).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+async fn body(response: Response) -> String {
+    String::from_utf8(to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec()).unwrap()
+}
+
+#[test]
+fn security_txt_lists_the_configured_fields() {
+    let settings = SecurityTxtSettings {
+        contacts: vec!["mailto:security@example.com".into(), "https://example.com/security".into()],
+        expires_days: 30,
+        policy: Some("https://example.com/policy".into()),
+        preferred_languages: vec!["en".into(), "vi".into()],
+        ..Default::default()
+    };
+    let now = Utc.with_ymd_and_hms(2026, 10, 17, 8, 0, 0).unwrap();
+
+    assert_eq!(
+        security_txt(&settings, now).unwrap(),
+        "Contact: mailto:security@example.com\n\
+         Contact: https://example.com/security\n\
+         Expires: 2026-11-16T08:00:00Z\n\
+         Policy: https://example.com/policy\n\
+         Preferred-Languages: en, vi\n"
+    );
+    assert_eq!(security_txt(&SecurityTxtSettings::default(), now), None);
+}
+
+#[tokio::test]
+async fn only_configured_documents_are_served() {
+    let app: Router = well_known_routes(&WellKnownSettings::default());
+    for path in ["/.well-known/security.txt", "/.well-known/change-password", "/.well-known/openid-configuration"] {
+        assert_eq!(get(&app, path).await.status(), StatusCode::NOT_FOUND, "{}", path);
+    }
+
+    let mut settings = WellKnownSettings::default();
+    settings.security_txt.contacts = vec!["mailto:security@example.com".into()];
+    settings.change_password_url = Some("https://app.example.com/settings/password".into());
+    settings.openid.issuer = Some("https://api.example.com".into());
+    settings.openid.token_endpoint = Some("https://api.example.com/oauth/token".into());
+    let app: Router = well_known_routes(&settings);
+
+    let response = get(&app, "/.well-known/security.txt").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "text/plain; charset=utf-8");
+    assert!(body(response).await.starts_with("Contact: mailto:security@example.com\nExpires: "));
+
+    let response = get(&app, "/.well-known/change-password").await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    assert_eq!(response.headers()[header::LOCATION], "https://app.example.com/settings/password");
+
+    let response = get(&app, "/.well-known/openid-configuration").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let document: serde_json::Value = serde_json::from_str(&body(response).await).unwrap();
+    assert_eq!(document["issuer"], "https://api.example.com");
+    assert_eq!(document["token_endpoint"], "https://api.example.com/oauth/token");
+    assert_eq!(document["userinfo_endpoint"], "https://api.example.com/api/v1/me");
+    assert!(document.get("authorization_endpoint").is_none());
+}
+
+#[test]
+fn well_known_settings_are_checked() {
+    let mut config = Config::default();
+    config.database.url = "postgres://localhost/app".to_string();
+    config.well_known.security_txt.contacts = vec!["security@example.com".into()];
+    config.well_known.change_password_url = Some("settings/password".into());
+    config.well_known.openid.token_endpoint = Some("https://api.example.com/oauth/token".into());
+
+    let Err(ConfigError::Invalid(problems)) = config.validate("development") else {
+        panic!("expected the well-known settings to be rejected");
+    };
+    assert_eq!(
+        problems,
+        [
+            "well_known.security_txt.contacts: 'security@example.com' is not a mailto:, tel: or https:// URI",
+            "well_known.change_password_url: 'settings/password' is not a path or URL",
+            "well_known.openid endpoints need an issuer",
+        ]
+    );
+
+    config.well_known.security_txt.contacts = vec!["mailto:security@example.com".into()];
+    config.well_known.change_password_url = Some("/settings/password".into());
+    config.well_known.openid.issuer = Some("http://localhost:3000".into());
+    config.validate("development").unwrap();
+    // Production issuers must use https
+    let Err(ConfigError::Invalid(problems)) = config.validate("production") else {
+        panic!("expected an http issuer to be rejected in production");
+    };
+    assert!(problems.iter().any(|p| p.starts_with("well_known.openid.issuer")));
+}
@@ -0,0 +1,193 @@
+use async_trait::async_trait;
+use chrono::{DateTime, SubsecRound, Utc};
+use domain::{DomainError, Email, UserRepository, UserStatus, Username};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::activity::UserActivity;
+use crate::jobs::{Job, JobQueue};
+use crate::storage::AvatarService;
+use crate::ApplicationError;
+
+/// How long a user can change their mind before their data is erased
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(30 * 86_400);
+
+// ============================================================================
+// Deletion Schedule
+// ============================================================================
+
+/// A pending account deletion
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledDeletion {
+    pub user_id: Uuid,
+    pub requested_at: DateTime<Utc>,
+    /// When the account is anonymized, unless cancelled before
+    pub delete_after: DateTime<Utc>,
+}
+
+/// Storage of pending deletions for dependency injection
+#[async_trait]
+pub trait AccountDeletionStore: Send + Sync {
+    async fn schedule(&self, deletion: &ScheduledDeletion) -> Result<(), ApplicationError>;
+
+    async fn find(&self, user_id: Uuid) -> Result<Option<ScheduledDeletion>, ApplicationError>;
+
+    /// Drop the user's pending deletion; false when there was none
+    async fn remove(&self, user_id: Uuid) -> Result<bool, ApplicationError>;
+}
+
+// ============================================================================
+// Deletion Requests
+// ============================================================================
+
+/// Account deletion on the user's request (GDPR right to erasure). The
+/// account keeps working during the grace period, so the user can sign in
+/// and cancel; `EraseAccountJob` anonymizes it once the period is over.
+pub struct AccountDeletionService {
+    store: Arc<dyn AccountDeletionStore>,
+    job_queue: Arc<dyn JobQueue>,
+    grace_period: Duration,
+}
+
+impl AccountDeletionService {
+    pub fn new(store: Arc<dyn AccountDeletionStore>, job_queue: Arc<dyn JobQueue>) -> Self {
+        Self {
+            store,
+            job_queue,
+            grace_period: DEFAULT_GRACE_PERIOD,
+        }
+    }
+
+    pub fn with_grace_period(mut self, grace_period: Duration) -> Self {
+        self.grace_period = grace_period;
+        self
+    }
+
+    /// Schedule the deletion of `user_id`. Asking again while one is pending
+    /// returns that one rather than pushing the deadline back.
+    pub async fn request(&self, user_id: Uuid) -> Result<ScheduledDeletion, ApplicationError> {
+        if let Some(pending) = self.store.find(user_id).await? {
+            return Ok(pending);
+        }
+        // Microseconds, as stores keep them, so the job's copy matches the stored one
+        let requested_at = Utc::now().trunc_subsecs(6);
+        let deletion = ScheduledDeletion {
+            user_id,
+            requested_at,
+            delete_after: requested_at + chrono::Duration::from_std(self.grace_period).unwrap_or_default(),
+        };
+        self.store.schedule(&deletion).await?;
+        EraseAccountJob::enqueue(self.job_queue.as_ref(), &deletion).await?;
+        tracing::info!(target: "audit", %user_id, delete_after = %deletion.delete_after, "Account deletion requested");
+        Ok(deletion)
+    }
+
+    pub async fn pending(&self, user_id: Uuid) -> Result<Option<ScheduledDeletion>, ApplicationError> {
+        self.store.find(user_id).await
+    }
+
+    /// Cancel the pending deletion of `user_id`
+    pub async fn cancel(&self, user_id: Uuid) -> Result<(), ApplicationError> {
+        if !self.store.remove(user_id).await? {
+            return Err(DomainError::not_found("Account deletion", user_id.to_string()).into());
+        }
+        tracing::info!(target: "audit", %user_id, "Account deletion cancelled");
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Erasure Job
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EraseAccountPayload {
+    user_id: Uuid,
+    delete_after: DateTime<Utc>,
+}
+
+/// Anonymizes an account once its grace period is over: username and email
+/// are replaced by hashes, the password and avatar removed and the account
+/// suspended, and the sign-in history dropped. The row stays so references
+/// to the user remain valid.
+pub struct EraseAccountJob {
+    store: Arc<dyn AccountDeletionStore>,
+    users: Arc<dyn UserRepository>,
+    avatars: Arc<AvatarService>,
+    activity: Option<Arc<UserActivity>>,
+}
+
+impl EraseAccountJob {
+    pub const KIND: &'static str = "user.erase";
+
+    pub fn new(store: Arc<dyn AccountDeletionStore>, users: Arc<dyn UserRepository>, avatars: Arc<AvatarService>) -> Self {
+        Self {
+            store,
+            users,
+            avatars,
+            activity: None,
+        }
+    }
+
+    /// Also drop the sign-in history, with the addresses it holds
+    pub fn with_activity(mut self, activity: Arc<UserActivity>) -> Self {
+        self.activity = Some(activity);
+        self
+    }
+
+    /// Runs at the deadline; the job checks the deletion is still pending then
+    pub async fn enqueue(queue: &dyn JobQueue, deletion: &ScheduledDeletion) -> Result<(), ApplicationError> {
+        let payload = serde_json::to_value(EraseAccountPayload {
+            user_id: deletion.user_id,
+            delete_after: deletion.delete_after,
+        })
+        .map_err(|e| ApplicationError::use_case(format!("Invalid erase payload: {}", e)))?;
+        queue.enqueue(Self::KIND, payload, deletion.delete_after).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Job for EraseAccountJob {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    async fn run(&self, payload: serde_json::Value) -> Result<(), ApplicationError> {
+        let EraseAccountPayload { user_id, delete_after } = serde_json::from_value(payload)
+            .map_err(|e| ApplicationError::use_case(format!("Invalid erase payload: {}", e)))?;
+        // Cancelled, or cancelled and requested again: that request has its own job
+        match self.store.find(user_id).await? {
+            Some(pending) if pending.delete_after == delete_after.trunc_subsecs(6) => {}
+            _ => return Ok(()),
+        }
+
+        if self.users.find_by_id(user_id).await?.is_some() {
+            let mut user = self.avatars.remove(user_id).await?;
+            user.username = Username::parse(format!("deleted-{}", &pseudonym(user_id, &user.username)[..16]))?;
+            user.email = Email::parse(format!("{}@deleted.invalid", pseudonym(user_id, &user.email)))?;
+            user.password_hash = String::new();
+            user.password_reset_required = false;
+            user.status = UserStatus::Suspended;
+            self.users.update(&user).await?;
+        }
+        if let Some(activity) = &self.activity {
+            activity.forget(user_id).await?;
+        }
+        self.store.remove(user_id).await?;
+        tracing::info!(target: "audit", %user_id, "Account erased");
+        Ok(())
+    }
+}
+
+/// SHA-256 of `value` salted with the user id, hex, so the same email gives
+/// unrelated pseudonyms on different accounts
+fn pseudonym(user_id: Uuid, value: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(user_id.as_bytes());
+    hasher.update(value.to_lowercase().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
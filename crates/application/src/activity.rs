@@ -0,0 +1,173 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::ApplicationError;
+
+/// How often a user's `last_seen_at` is written unless configured
+pub const DEFAULT_SEEN_DEBOUNCE: Duration = Duration::from_secs(300);
+
+/// Sign-ins kept per user; older ones are dropped as new ones are recorded
+pub const MAX_LOGINS_KEPT: u32 = 50;
+
+/// Longest user agent kept, in characters
+const MAX_USER_AGENT_LEN: usize = 512;
+
+// ============================================================================
+// Sign-ins
+// ============================================================================
+
+/// Where a sign-in came from, as far as the transport can tell
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoginClient {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+/// One successful sign-in
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoginRecord {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Persistence of sign-ins and `users.last_*_at`, for dependency injection
+#[async_trait]
+pub trait ActivityStore: Send + Sync {
+    /// Store the sign-in, set the user's `last_login_at` and `last_seen_at`
+    /// to its time, and drop the user's sign-ins beyond the newest `keep`
+    async fn record_login(&self, login: &LoginRecord, keep: u32) -> Result<(), ApplicationError>;
+
+    /// Move each user's `last_seen_at` forward to the given time (never back)
+    async fn record_seen(&self, seen: &[(Uuid, DateTime<Utc>)]) -> Result<(), ApplicationError>;
+
+    /// The user's newest sign-ins first
+    async fn recent_logins(&self, user_id: Uuid, limit: u32) -> Result<Vec<LoginRecord>, ApplicationError>;
+
+    /// Drop every sign-in of the user; returns how many
+    async fn forget(&self, user_id: Uuid) -> Result<u64, ApplicationError>;
+}
+
+// ============================================================================
+// User Activity
+// ============================================================================
+
+/// Keeps `last_login_at`, `last_seen_at` and the sign-in history. Sign-ins
+/// are written as they happen. Authenticated requests only mark the user as
+/// seen in memory: a user is written at most once per `debounce`, in one
+/// batch per `flush` (see `spawn_flush`), so busy users cost no write per
+/// request. Marks not yet flushed are lost if the process dies.
+pub struct UserActivity {
+    store: Arc<dyn ActivityStore>,
+    debounce: Duration,
+    seen: Mutex<SeenMarks>,
+}
+
+#[derive(Default)]
+struct SeenMarks {
+    /// When each user was last written (or queued)
+    written: HashMap<Uuid, DateTime<Utc>>,
+    pending: HashMap<Uuid, DateTime<Utc>>,
+}
+
+impl UserActivity {
+    pub fn new(store: Arc<dyn ActivityStore>) -> Self {
+        Self {
+            store,
+            debounce: DEFAULT_SEEN_DEBOUNCE,
+            seen: Mutex::new(SeenMarks::default()),
+        }
+    }
+
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Record a successful sign-in from `client`
+    pub async fn record_login(&self, user_id: Uuid, client: &LoginClient, now: DateTime<Utc>) -> Result<LoginRecord, ApplicationError> {
+        let login = LoginRecord {
+            id: Uuid::new_v4(),
+            user_id,
+            ip: client.ip.clone(),
+            user_agent: client
+                .user_agent
+                .as_deref()
+                .map(str::trim)
+                .filter(|ua| !ua.is_empty())
+                .map(|ua| ua.chars().take(MAX_USER_AGENT_LEN).collect()),
+            created_at: now,
+        };
+        self.store.record_login(&login, MAX_LOGINS_KEPT).await?;
+
+        // The sign-in wrote last_seen_at too
+        let mut seen = self.seen.lock().unwrap();
+        seen.written.insert(user_id, now);
+        seen.pending.remove(&user_id);
+        Ok(login)
+    }
+
+    /// Note a request by `user_id`; queued for the next flush unless the
+    /// user was written less than `debounce` ago. Never blocks on the store.
+    pub fn seen(&self, user_id: Uuid, now: DateTime<Utc>) {
+        let debounce = chrono::Duration::from_std(self.debounce).unwrap_or(chrono::Duration::MAX);
+        let mut seen = self.seen.lock().unwrap();
+        if seen.written.get(&user_id).is_some_and(|at| now - *at < debounce) {
+            return;
+        }
+        seen.written.insert(user_id, now);
+        seen.pending.insert(user_id, now);
+    }
+
+    /// Write the queued marks in one batch; returns how many users
+    pub async fn flush(&self) -> Result<usize, ApplicationError> {
+        let pending: Vec<(Uuid, DateTime<Utc>)> = {
+            let mut seen = self.seen.lock().unwrap();
+            // Users not seen for a whole interval need no entry to be debounced
+            let cutoff = Utc::now() - chrono::Duration::from_std(self.debounce).unwrap_or(chrono::Duration::MAX);
+            seen.written.retain(|_, at| *at > cutoff);
+            seen.pending.drain().collect()
+        };
+        if pending.is_empty() {
+            return Ok(0);
+        }
+        if let Err(e) = self.store.record_seen(&pending).await {
+            // Put them back, unless a newer mark arrived meanwhile
+            let mut seen = self.seen.lock().unwrap();
+            for (user_id, at) in pending {
+                seen.pending.entry(user_id).or_insert(at);
+            }
+            return Err(e);
+        }
+        Ok(pending.len())
+    }
+
+    /// Flush every `interval`
+    pub fn spawn_flush(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(e) = self.flush().await {
+                    tracing::warn!("Failed to record user activity: {}", e);
+                }
+            }
+        });
+    }
+
+    /// The user's newest sign-ins first
+    pub async fn recent_logins(&self, user_id: Uuid, limit: u32) -> Result<Vec<LoginRecord>, ApplicationError> {
+        self.store.recent_logins(user_id, limit.min(MAX_LOGINS_KEPT)).await
+    }
+
+    /// Drop the user's sign-in history, e.g. when the account is erased
+    pub async fn forget(&self, user_id: Uuid) -> Result<u64, ApplicationError> {
+        self.seen.lock().unwrap().pending.remove(&user_id);
+        self.store.forget(user_id).await
+    }
+}
@@ -0,0 +1,324 @@
+use async_trait::async_trait;
+use futures_util::{StreamExt, TryStreamExt};
+use chrono::Utc;
+use domain::{Authorization, DomainError, Page, PaginationParams, User, UserCursor, UserFilter, UserRepository, UserStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::authz::AuthorizationService;
+use crate::jobs::{Job, JobQueue};
+use crate::operations::{Operation, OperationItem, OperationStatus, OperationStore};
+use crate::{tenancy, ApplicationError};
+
+// ============================================================================
+// Admin User Management
+// ============================================================================
+
+/// User management for admins. `admin_id` is the acting admin, who may not
+/// suspend or delete their own account.
+#[async_trait]
+pub trait AdminUserService: Send + Sync {
+    async fn search(&self, filter: &UserFilter, params: &PaginationParams) -> Result<Page<User>, ApplicationError>;
+    /// Next chunk of a full listing (exports): up to `limit` users matching
+    /// `filter` after `after`, with no cap on how far it goes
+    async fn search_after(&self, filter: &UserFilter, after: Option<UserCursor>, limit: u32) -> Result<Vec<User>, ApplicationError>;
+    /// Block sign-in; existing tokens stay valid until they expire
+    async fn suspend(&self, admin_id: Uuid, id: Uuid) -> Result<User, ApplicationError>;
+    async fn unsuspend(&self, id: Uuid) -> Result<User, ApplicationError>;
+    /// Require the user to change their password (see `AuthService::change_password`)
+    async fn force_password_reset(&self, id: Uuid) -> Result<User, ApplicationError>;
+    /// Permanently remove the user
+    async fn delete(&self, admin_id: Uuid, id: Uuid) -> Result<(), ApplicationError>;
+}
+
+pub struct AdminUserServiceImpl {
+    repository: Arc<dyn UserRepository>,
+}
+
+impl AdminUserServiceImpl {
+    pub fn new(repository: Arc<dyn UserRepository>) -> Self {
+        Self { repository }
+    }
+
+    async fn modify(&self, id: Uuid, change: impl FnOnce(&mut User) + Send) -> Result<User, ApplicationError> {
+        let mut user = self
+            .repository
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::not_found("User", id.to_string()))?;
+        change(&mut user);
+        Ok(self.repository.update(&user).await?)
+    }
+}
+
+fn ensure_not_self(admin_id: Uuid, id: Uuid, action: &str) -> Result<(), ApplicationError> {
+    if admin_id == id {
+        return Err(DomainError::validation(format!("Admins cannot {} their own account", action)).into());
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl AdminUserService for AdminUserServiceImpl {
+    async fn search(&self, filter: &UserFilter, params: &PaginationParams) -> Result<Page<User>, ApplicationError> {
+        Ok(self.repository.search(filter, params).await?)
+    }
+
+    async fn search_after(&self, filter: &UserFilter, after: Option<UserCursor>, limit: u32) -> Result<Vec<User>, ApplicationError> {
+        Ok(self.repository.search_after(filter, after, limit).await?)
+    }
+
+    async fn suspend(&self, admin_id: Uuid, id: Uuid) -> Result<User, ApplicationError> {
+        ensure_not_self(admin_id, id, "suspend")?;
+        self.modify(id, |user| user.status = UserStatus::Suspended).await
+    }
+
+    async fn unsuspend(&self, id: Uuid) -> Result<User, ApplicationError> {
+        self.modify(id, |user| user.status = UserStatus::Active).await
+    }
+
+    async fn force_password_reset(&self, id: Uuid) -> Result<User, ApplicationError> {
+        self.modify(id, |user| user.password_reset_required = true).await
+    }
+
+    async fn delete(&self, admin_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        ensure_not_self(admin_id, id, "delete")?;
+        if !self.repository.delete(id).await? {
+            return Err(DomainError::not_found("User", id.to_string()).into());
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Bulk Actions
+// ============================================================================
+
+/// Most users one bulk action may touch
+pub const MAX_BULK_USERS: usize = 10_000;
+/// Results are saved after every batch, so progress shows while it runs
+const PROGRESS_BATCH: usize = 100;
+
+/// What a bulk action does to each user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum BulkUserAction {
+    Suspend,
+    Delete,
+    AddRole { role: String },
+}
+
+impl BulkUserAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Suspend => "suspend",
+            Self::Delete => "delete",
+            Self::AddRole { .. } => "add-role",
+        }
+    }
+}
+
+/// Users a bulk action applies to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkTarget {
+    Ids(Vec<Uuid>),
+    /// Everyone matching the admin search filter when the job runs
+    Filter(UserFilter),
+}
+
+/// Accepts bulk user actions and queues them as operations. Each user is
+/// handled like the single-user admin endpoint would, so admins still
+/// cannot suspend or delete themselves.
+pub struct BulkUserActions {
+    operations: Arc<dyn OperationStore>,
+    job_queue: Arc<dyn JobQueue>,
+}
+
+impl BulkUserActions {
+    pub fn new(operations: Arc<dyn OperationStore>, job_queue: Arc<dyn JobQueue>) -> Self {
+        Self { operations, job_queue }
+    }
+
+    /// Validate the request and queue it; the returned operation is pending
+    pub async fn submit(&self, admin_id: Uuid, action: BulkUserAction, target: BulkTarget) -> Result<Operation, ApplicationError> {
+        let action = match action {
+            BulkUserAction::AddRole { role } => BulkUserAction::AddRole {
+                role: Authorization::normalize_role(&role)?,
+            },
+            action => action,
+        };
+        let target = match target {
+            BulkTarget::Ids(ids) => {
+                let mut seen = HashSet::new();
+                let ids: Vec<Uuid> = ids.into_iter().filter(|id| seen.insert(*id)).collect();
+                if ids.is_empty() {
+                    return Err(DomainError::validation("No user IDs given").into());
+                }
+                if ids.len() > MAX_BULK_USERS {
+                    return Err(DomainError::validation(format!("At most {} users per bulk action", MAX_BULK_USERS)).into());
+                }
+                BulkTarget::Ids(ids)
+            }
+            target => target,
+        };
+
+        let operation = Operation::new(
+            format!("{}.{}", BulkUserActionJob::KIND, action.as_str()),
+            admin_id,
+            tenancy::current_tenant(),
+        );
+        self.operations.create(&operation).await?;
+        let payload = BulkUserActionPayload {
+            operation_id: operation.id,
+            action,
+            target,
+        };
+        let payload = serde_json::to_value(&payload)
+            .map_err(|e| ApplicationError::use_case(format!("Invalid bulk action payload: {}", e)))?;
+        self.job_queue.enqueue(BulkUserActionJob::KIND, payload, Utc::now()).await?;
+
+        tracing::info!(target: "audit", %admin_id, operation_id = %operation.id, kind = %operation.kind, "Bulk user action queued");
+        Ok(operation)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BulkUserActionPayload {
+    operation_id: Uuid,
+    action: BulkUserAction,
+    target: BulkTarget,
+}
+
+/// Carries out a bulk user action, recording a result per user on its
+/// operation. A retried run skips the users it already handled.
+pub struct BulkUserActionJob {
+    operations: Arc<dyn OperationStore>,
+    admin_users: Arc<dyn AdminUserService>,
+    users: Arc<dyn UserRepository>,
+    authz: Arc<AuthorizationService>,
+}
+
+impl BulkUserActionJob {
+    pub const KIND: &'static str = "users.bulk";
+
+    pub fn new(
+        operations: Arc<dyn OperationStore>,
+        admin_users: Arc<dyn AdminUserService>,
+        users: Arc<dyn UserRepository>,
+        authz: Arc<AuthorizationService>,
+    ) -> Self {
+        Self {
+            operations,
+            admin_users,
+            users,
+            authz,
+        }
+    }
+
+    async fn process(&self, operation: &mut Operation, action: &BulkUserAction, target: BulkTarget) -> Result<(), ApplicationError> {
+        let ids = match target {
+            BulkTarget::Ids(ids) => ids,
+            BulkTarget::Filter(filter) => match self.resolve(&filter).await? {
+                Some(ids) => ids,
+                None => {
+                    operation.status = OperationStatus::Failed;
+                    operation.error = Some(format!("The filter matches more than {} users", MAX_BULK_USERS));
+                    return self.operations.update(operation).await;
+                }
+            },
+        };
+        operation.status = OperationStatus::Running;
+        operation.total = Some(ids.len() as u64);
+        self.operations.update(operation).await?;
+
+        let done: HashSet<Uuid> = operation.items.iter().map(|item| item.id).collect();
+        for batch in ids.chunks(PROGRESS_BATCH) {
+            for &id in batch.iter().filter(|id| !done.contains(id)) {
+                let error = self.apply(operation, action, id).await.err().map(|e| e.to_string());
+                operation.items.push(OperationItem { id, error, line: None });
+            }
+            self.operations.update(operation).await?;
+        }
+
+        operation.status = OperationStatus::Succeeded;
+        self.operations.update(operation).await?;
+        tracing::info!(
+            operation_id = %operation.id,
+            total = ids.len(),
+            failed = operation.failed_items(),
+            "Bulk user action finished"
+        );
+        Ok(())
+    }
+
+    /// IDs of the users matching `filter`, streamed rather than paged;
+    /// `None` past `MAX_BULK_USERS`
+    async fn resolve(&self, filter: &UserFilter) -> Result<Option<Vec<Uuid>>, ApplicationError> {
+        let ids: Vec<Uuid> = self
+            .users
+            .stream_all(filter)
+            .map_ok(|user| user.id)
+            .take(MAX_BULK_USERS + 1)
+            .try_collect()
+            .await?;
+        Ok((ids.len() <= MAX_BULK_USERS).then_some(ids))
+    }
+
+    async fn apply(&self, operation: &Operation, action: &BulkUserAction, id: Uuid) -> Result<(), ApplicationError> {
+        let admin_id = operation.created_by;
+        match action {
+            BulkUserAction::Suspend => {
+                self.admin_users.suspend(admin_id, id).await?;
+            }
+            BulkUserAction::Delete => self.admin_users.delete(admin_id, id).await?,
+            BulkUserAction::AddRole { role } => {
+                if self.users.find_by_id(id).await?.is_none() {
+                    return Err(DomainError::not_found("User", id.to_string()).into());
+                }
+                self.authz.grant_role(id, role).await?;
+            }
+        }
+        tracing::info!(
+            target: "audit",
+            %admin_id,
+            user_id = %id,
+            operation_id = %operation.id,
+            action = action.as_str(),
+            "Bulk user action applied"
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Job for BulkUserActionJob {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    async fn run(&self, payload: serde_json::Value) -> Result<(), ApplicationError> {
+        let BulkUserActionPayload {
+            operation_id,
+            action,
+            target,
+        } = serde_json::from_value(payload)
+            .map_err(|e| ApplicationError::use_case(format!("Invalid bulk action payload: {}", e)))?;
+        let Some(mut operation) = self.operations.get(operation_id).await? else {
+            return Ok(());
+        };
+        if operation.status.is_finished() {
+            return Ok(());
+        }
+
+        // Users are looked up in the tenant the action was requested in
+        let tenant = operation.tenant_id;
+        let work = self.process(&mut operation, &action, target);
+        match tenant {
+            Some(tenant) => tenancy::with_tenant(tenant, work).await,
+            None => work.await,
+        }
+    }
+}
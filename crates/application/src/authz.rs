@@ -0,0 +1,142 @@
+use domain::{Authorization, Claims, DomainError, RoleRepository};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::ApplicationError;
+
+/// How long a user's roles are reused across requests. Role changes made on
+/// this instance apply at once; other instances pick them up within this.
+pub const AUTHORIZATION_TTL: Duration = Duration::from_secs(30);
+
+/// User and the roles their token carries
+type CacheKey = (Uuid, Vec<String>);
+
+type Cache = Mutex<HashMap<CacheKey, Arc<Authorization>>>;
+
+// ============================================================================
+// Request Cache
+// ============================================================================
+
+tokio::task_local! {
+    static REQUEST_CACHE: Arc<Cache>;
+}
+
+/// Run `work` as one request: authorization lookups inside it are resolved
+/// once, however many checks the request makes
+pub async fn with_request_cache<F: Future>(work: F) -> F::Output {
+    REQUEST_CACHE.scope(Arc::new(Mutex::new(HashMap::new())), work).await
+}
+
+fn request_cache() -> Option<Arc<Cache>> {
+    REQUEST_CACHE.try_with(Arc::clone).ok()
+}
+
+// ============================================================================
+// Authorization Service
+// ============================================================================
+
+/// Resolves what a caller may do from their token roles plus the roles
+/// stored for them, caching the answer per request and for a short time
+/// across requests
+pub struct AuthorizationService {
+    roles: Arc<dyn RoleRepository>,
+    ttl: Duration,
+    cache: Mutex<HashMap<CacheKey, (Instant, Arc<Authorization>)>>,
+}
+
+impl AuthorizationService {
+    pub fn new(roles: Arc<dyn RoleRepository>) -> Self {
+        Self {
+            roles,
+            ttl: AUTHORIZATION_TTL,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Roles and permissions of the caller `claims` belongs to
+    pub async fn authorization(&self, claims: &Claims) -> Result<Arc<Authorization>, ApplicationError> {
+        let user_id = claims
+            .sub
+            .parse::<Uuid>()
+            .map_err(|_| DomainError::unauthorized("Invalid user ID in token"))?;
+        let mut token_roles = claims.roles.clone();
+        token_roles.sort();
+        let key = (user_id, token_roles);
+
+        let request = request_cache();
+        if let Some(found) = request.as_ref().and_then(|c| c.lock().unwrap().get(&key).cloned()) {
+            return Ok(found);
+        }
+        let cached = self
+            .cache
+            .lock()
+            .unwrap()
+            .get(&key)
+            .filter(|(at, _)| at.elapsed() < self.ttl)
+            .map(|(_, found)| found.clone());
+        let found = match cached {
+            Some(found) => found,
+            None => {
+                let found = Arc::new(self.roles.authorization(user_id, &key.1).await?);
+                let mut cache = self.cache.lock().unwrap();
+                cache.retain(|_, (at, _)| at.elapsed() < self.ttl);
+                cache.insert(key.clone(), (Instant::now(), found.clone()));
+                found
+            }
+        };
+        if let Some(request) = request {
+            request.lock().unwrap().insert(key, found.clone());
+        }
+        Ok(found)
+    }
+
+    pub async fn has_role(&self, claims: &Claims, role: &str) -> Result<bool, ApplicationError> {
+        // Token roles need no lookup
+        if claims.roles.iter().any(|r| r == role) {
+            return Ok(true);
+        }
+        Ok(self.authorization(claims).await?.has_role(role))
+    }
+
+    pub async fn has_permission(&self, claims: &Claims, permission: &str) -> Result<bool, ApplicationError> {
+        Ok(self.authorization(claims).await?.has_permission(permission))
+    }
+
+    /// Grant `role` to `user_id`; false when they already held it
+    pub async fn grant_role(&self, user_id: Uuid, role: &str) -> Result<bool, ApplicationError> {
+        let granted = self.roles.grant(user_id, &Authorization::normalize_role(role)?).await?;
+        self.invalidate(user_id);
+        Ok(granted)
+    }
+
+    pub async fn revoke_role(&self, user_id: Uuid, role: &str) -> Result<(), ApplicationError> {
+        let role = Authorization::normalize_role(role)?;
+        let revoked = self.roles.revoke(user_id, &role).await?;
+        self.invalidate(user_id);
+        if !revoked {
+            return Err(DomainError::not_found("Role", role).into());
+        }
+        Ok(())
+    }
+
+    /// Stored roles of `user_id` and the permissions they grant, uncached
+    pub async fn stored(&self, user_id: Uuid) -> Result<Authorization, ApplicationError> {
+        Ok(self.roles.authorization(user_id, &[]).await?)
+    }
+
+    /// Forget cached decisions about `user_id`, here and in the current request
+    pub fn invalidate(&self, user_id: Uuid) {
+        self.cache.lock().unwrap().retain(|(id, _), _| *id != user_id);
+        if let Some(request) = request_cache() {
+            request.lock().unwrap().retain(|(id, _), _| *id != user_id);
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use domain::{Email, UserRepository, Username};
+use rand::Rng;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{ApplicationError, RateLimiter};
+
+// ============================================================================
+// Sign-up Availability
+// ============================================================================
+
+/// Suggestions offered for a taken username
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// Checks allowed per client within `window`
+#[derive(Debug, Clone, Copy)]
+pub struct AvailabilityLimits {
+    pub per_client: u32,
+    pub window: Duration,
+}
+
+impl Default for AvailabilityLimits {
+    fn default() -> Self {
+        Self {
+            per_client: 30,
+            window: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Whether a username can be registered, with free alternatives when it can't
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsernameAvailability {
+    /// As it would be stored (trimmed)
+    pub username: String,
+    pub available: bool,
+    pub suggestions: Vec<String>,
+}
+
+/// The answer for whichever of the two was asked about
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Availability {
+    pub username: Option<UsernameAvailability>,
+    pub email: Option<bool>,
+}
+
+/// Answers sign-up forms before they are submitted. Registration reports a
+/// taken email or username anyway, so this reveals no more than a boolean's
+/// worth per check: never who holds a value, and only as often as the
+/// per-client limit allows. Give it an unscoped user repository, since
+/// usernames and emails are unique across tenants.
+pub struct AvailabilityService {
+    users: Arc<dyn UserRepository>,
+    rate_limiter: Arc<dyn RateLimiter>,
+    limits: AvailabilityLimits,
+}
+
+impl AvailabilityService {
+    pub fn new(users: Arc<dyn UserRepository>, rate_limiter: Arc<dyn RateLimiter>) -> Self {
+        Self {
+            users,
+            rate_limiter,
+            limits: AvailabilityLimits::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: AvailabilityLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Check `username` and/or `email`, counting one hit against `client_ip`
+    pub async fn check(
+        &self,
+        client_ip: Option<&str>,
+        username: Option<&str>,
+        email: Option<&str>,
+    ) -> Result<Availability, ApplicationError> {
+        if username.is_none() && email.is_none() {
+            return Err(ApplicationError::use_case("Give a username, an email or both"));
+        }
+        // Parse first, so malformed input does not use up the limit
+        let username = username.map(Username::parse).transpose()?;
+        let email = email.map(Email::parse).transpose()?;
+
+        let key = match client_ip {
+            Some(ip) => format!("availability:ip:{}", ip),
+            None => "availability:anonymous".to_string(),
+        };
+        self.rate_limiter
+            .check(&key, self.limits.per_client, self.limits.window)
+            .map_err(|retry_after| ApplicationError::RateLimited { retry_after })?;
+
+        let username = match username {
+            Some(username) => Some(self.username(&username).await?),
+            None => None,
+        };
+        let email = match email {
+            Some(email) => Some(self.users.find_by_email(&email).await?.is_none()),
+            None => None,
+        };
+        Ok(Availability { username, email })
+    }
+
+    /// Looks the username and its candidate replacements up in one query
+    async fn username(&self, username: &Username) -> Result<UsernameAvailability, ApplicationError> {
+        let mut names = vec![username.to_string()];
+        names.extend(suggestion_candidates(username, &mut rand::thread_rng()));
+        let taken: Vec<String> = self
+            .users
+            .find_by_usernames(&names)
+            .await?
+            .into_iter()
+            .map(|u| u.username.to_string())
+            .collect();
+
+        let available = !taken.contains(&names[0]);
+        let suggestions = match available {
+            true => Vec::new(),
+            false => names
+                .into_iter()
+                .skip(1)
+                .filter(|n| !taken.contains(n))
+                .take(MAX_SUGGESTIONS)
+                .collect(),
+        };
+        Ok(UsernameAvailability {
+            username: username.to_string(),
+            available,
+            suggestions,
+        })
+    }
+}
+
+/// Alternatives to `username`: numbered ones first, then random numbers,
+/// shortened where needed to stay within `Username::MAX_LEN`
+pub fn suggestion_candidates(username: &Username, rng: &mut impl Rng) -> Vec<String> {
+    let mut suffixes: Vec<String> = (1..=3).map(|n| n.to_string()).collect();
+    suffixes.extend((0..3).map(|_| rng.gen_range(10..1000).to_string()));
+    suffixes.extend((0..2).map(|_| format!("_{}", rng.gen_range(1000..10_000))));
+
+    let mut candidates: Vec<String> = Vec::new();
+    for suffix in suffixes {
+        let base: String = username.chars().take(Username::MAX_LEN - suffix.len()).collect();
+        let candidate = format!("{}{}", base.trim_end(), suffix);
+        if Username::parse(&candidate).is_ok() && !candidates.contains(&candidate) && candidate != username.as_str() {
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
@@ -0,0 +1,139 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use domain::DomainError;
+use futures_util::future::{select, Either};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+use crate::locks::DistributedLock;
+use crate::ApplicationError;
+
+// ============================================================================
+// Ports
+// ============================================================================
+
+/// Work run on a cron schedule by one instance at a time
+#[async_trait]
+pub trait CronTask: Send + Sync {
+    /// Stable name; `cron.schedules` and the task's lock (`cron:<name>`) are
+    /// keyed by it
+    fn name(&self) -> &'static str;
+
+    async fn run(&self) -> Result<(), ApplicationError>;
+}
+
+// ============================================================================
+// Schedules
+// ============================================================================
+
+/// Parse a cron expression: `min hour day month weekday`, or with a leading
+/// seconds field. Weekdays are best given by name (`Mon-Fri`), since numbers
+/// count from Sunday = 1.
+pub fn parse_schedule(expression: &str) -> Result<Schedule, ApplicationError> {
+    let expression = expression.trim();
+    let full = match expression.split_whitespace().count() {
+        5 => format!("0 {}", expression),
+        _ => expression.to_string(),
+    };
+    Schedule::from_str(&full)
+        .map_err(|e| DomainError::validation(format!("Invalid cron expression '{}': {}", expression, e)).into())
+}
+
+// ============================================================================
+// Scheduler
+// ============================================================================
+
+/// Runs registered tasks on their schedules. Every replica runs a scheduler;
+/// at each due time the replicas race for the task's lock and the winner
+/// runs it. The winner keeps the lock for `clock_skew` past the due time
+/// even when the task finished sooner, so a replica whose clock lags behind
+/// finds it taken instead of running the task a second time.
+pub struct CronScheduler {
+    lock: Arc<dyn DistributedLock>,
+    tasks: Vec<(Schedule, Arc<dyn CronTask>)>,
+    clock_skew: Duration,
+    lock_ttl: Duration,
+}
+
+impl CronScheduler {
+    pub fn new(lock: Arc<dyn DistributedLock>) -> Self {
+        Self {
+            lock,
+            tasks: Vec::new(),
+            clock_skew: Duration::from_secs(5),
+            lock_ttl: Duration::from_secs(60),
+        }
+    }
+
+    /// Run `task` whenever `expression` (see `parse_schedule`) is due
+    pub fn register(mut self, task: Arc<dyn CronTask>, expression: &str) -> Result<Self, ApplicationError> {
+        self.tasks.push((parse_schedule(expression)?, task));
+        Ok(self)
+    }
+
+    pub fn with_clock_skew(mut self, clock_skew: Duration) -> Self {
+        self.clock_skew = clock_skew;
+        self
+    }
+
+    /// How long a task's lock outlives an instance that died running it
+    pub fn with_lock_ttl(mut self, lock_ttl: Duration) -> Self {
+        self.lock_ttl = lock_ttl;
+        self
+    }
+
+    /// Names of the registered tasks
+    pub fn tasks(&self) -> Vec<&'static str> {
+        self.tasks.iter().map(|(_, task)| task.name()).collect()
+    }
+
+    /// Spawn one loop per task. Runs missed while the process was down or a
+    /// task overran its next due time are skipped, not caught up.
+    pub fn spawn(self: Arc<Self>) -> Vec<JoinHandle<()>> {
+        (0..self.tasks.len())
+            .map(|index| {
+                let scheduler = self.clone();
+                tokio::spawn(async move {
+                    let (schedule, task) = &scheduler.tasks[index];
+                    while let Some(due) = schedule.upcoming(Utc).next() {
+                        let wait = (due - Utc::now()).to_std().unwrap_or_default();
+                        tokio::time::sleep(wait).await;
+                        if let Err(e) = scheduler.run(task.as_ref(), due).await {
+                            tracing::error!(task = task.name(), "Cron task failed: {}", e);
+                        }
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Run `task` for its run due at `due`, unless another instance holds
+    /// its lock. Returns whether it ran here.
+    pub async fn run(&self, task: &dyn CronTask, due: DateTime<Utc>) -> Result<bool, ApplicationError> {
+        let lock_name = format!("cron:{}", task.name());
+        let Some(mut guard) = self.lock.try_acquire(&lock_name, self.lock_ttl).await? else {
+            tracing::debug!(task = task.name(), "Cron task running elsewhere; skipped");
+            return Ok(false);
+        };
+
+        let started = std::time::Instant::now();
+        let result = match select(task.run(), Box::pin(guard.lost())).await {
+            Either::Left((result, _)) => result,
+            Either::Right(_) => Err(DomainError::unavailable(format!("Lock '{}' was lost", lock_name)).into()),
+        };
+        if result.is_ok() {
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            tracing::info!(task = task.name(), elapsed_ms, "Cron task finished");
+        }
+
+        let hold_until = due + chrono::Duration::from_std(self.clock_skew).unwrap_or_default();
+        if let Ok(wait) = (hold_until - Utc::now()).to_std() {
+            tokio::time::sleep(wait).await;
+        }
+        drop(guard);
+        result.map(|()| true)
+    }
+}
@@ -0,0 +1,53 @@
+use domain::{DomainError, Entity, Page, PaginationParams, Repository};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::ApplicationError;
+
+// ============================================================================
+// Generic CRUD Service
+// ============================================================================
+
+/// Plain create/read/update/delete use cases over any `Repository<T>`, for
+/// resources without business rules of their own (see `api::crud_entity!`).
+/// Resources that grow rules should get a dedicated service instead.
+pub struct CrudService<T: Entity<Id = Uuid>> {
+    repository: Arc<dyn Repository<T>>,
+    /// Entity name used in not-found errors
+    name: &'static str,
+}
+
+impl<T: Entity<Id = Uuid>> CrudService<T> {
+    pub fn new(repository: Arc<dyn Repository<T>>, name: &'static str) -> Self {
+        Self { repository, name }
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<T, ApplicationError> {
+        self.repository
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::not_found(self.name, id.to_string()).into())
+    }
+
+    pub async fn list(&self, params: &PaginationParams) -> Result<Page<T>, ApplicationError> {
+        Ok(self.repository.find_all(params).await?)
+    }
+
+    pub async fn create(&self, entity: T) -> Result<T, ApplicationError> {
+        Ok(self.repository.create(&entity).await?)
+    }
+
+    /// Load the entity, apply `change` and save it
+    pub async fn update(&self, id: Uuid, change: impl FnOnce(&mut T) + Send) -> Result<T, ApplicationError> {
+        let mut entity = self.get(id).await?;
+        change(&mut entity);
+        Ok(self.repository.update(&entity).await?)
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<(), ApplicationError> {
+        if !self.repository.delete(id).await? {
+            return Err(DomainError::not_found(self.name, id.to_string()).into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use async_trait::async_trait;
+use domain::{DomainError, Page, PaginationParams};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+use crate::ApplicationError;
+
+// ============================================================================
+// Browsable Tables
+// ============================================================================
+
+/// How a sensitive column is shown to support staff
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mask {
+    /// Replaced entirely
+    Redact,
+    /// First character and email domain kept: `j***@example.com`
+    Partial,
+}
+
+/// A table exposed read-only through the admin data browser
+#[derive(Debug)]
+pub struct BrowsableTable {
+    pub name: &'static str,
+    /// Columns returned, in order
+    pub columns: &'static [&'static str],
+    pub masked: &'static [(&'static str, Mask)],
+    pub order_by: &'static str,
+}
+
+/// The only tables the data browser will query
+pub const BROWSABLE_TABLES: &[BrowsableTable] = &[
+    BrowsableTable {
+        name: "users",
+        // Never `password_hash`: it is not even selected to be masked
+        columns: &["id", "username", "email", "avatar_url", "status", "password_reset_required", "tenant_id", "created_at", "updated_at", "version"],
+        masked: &[("email", Mask::Partial)],
+        order_by: "created_at DESC",
+    },
+    BrowsableTable {
+        name: "jobs",
+        columns: &["id", "kind", "payload", "status", "attempts", "last_error", "run_at", "created_at", "updated_at"],
+        masked: &[("payload", Mask::Redact)],
+        order_by: "created_at DESC",
+    },
+];
+
+const REDACTED: &str = "[REDACTED]";
+
+impl Mask {
+    pub fn apply(&self, value: &Value) -> Value {
+        match (self, value) {
+            (_, Value::Null) => Value::Null,
+            (Mask::Partial, Value::String(s)) => Value::String(mask_partial(s)),
+            _ => Value::String(REDACTED.to_string()),
+        }
+    }
+}
+
+fn mask_partial(s: &str) -> String {
+    let (local, domain) = match s.split_once('@') {
+        Some((local, domain)) => (local, Some(domain)),
+        None => (s, None),
+    };
+    let first: String = local.chars().take(1).collect();
+    match domain {
+        Some(domain) => format!("{}***@{}", first, domain),
+        None => format!("{}***", first),
+    }
+}
+
+// ============================================================================
+// Data Browser
+// ============================================================================
+
+/// Read-only row access for dependency injection. Implementations must only
+/// query the columns listed on `table` and must not be able to write.
+#[async_trait]
+pub trait DataBrowser: Send + Sync {
+    async fn fetch_rows(&self, table: &BrowsableTable, params: &PaginationParams) -> Result<Page<Map<String, Value>>, ApplicationError>;
+}
+
+/// Whitelist lookup and masking in front of a `DataBrowser`
+pub struct DataBrowserService {
+    browser: Arc<dyn DataBrowser>,
+}
+
+impl DataBrowserService {
+    pub fn new(browser: Arc<dyn DataBrowser>) -> Self {
+        Self { browser }
+    }
+
+    pub fn tables(&self) -> &'static [BrowsableTable] {
+        BROWSABLE_TABLES
+    }
+
+    pub async fn browse(&self, table: &str, params: &PaginationParams) -> Result<Page<Map<String, Value>>, ApplicationError> {
+        let table = BROWSABLE_TABLES
+            .iter()
+            .find(|t| t.name == table)
+            .ok_or_else(|| DomainError::not_found("Table", table))?;
+
+        let mut page = self.browser.fetch_rows(table, params).await?;
+        for row in &mut page.items {
+            for (column, mask) in table.masked {
+                if let Some(value) = row.get_mut(*column) {
+                    *value = mask.apply(value);
+                }
+            }
+        }
+        Ok(page)
+    }
+}
@@ -0,0 +1,389 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{DomainError, UserRepository};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::email::{EmailJobPayload, EmailTemplate, SendEmailJob};
+use crate::jobs::{Job, JobQueue};
+use crate::refresh_tokens::{RefreshTokenStore, Session};
+use crate::secrets::{hash_token, random_token};
+use crate::storage::{FileStorage, StoredFile};
+use crate::ApplicationError;
+
+/// Exports are stored under this prefix, never under `PUBLIC_PREFIX`
+pub const EXPORT_PREFIX: &str = "exports/";
+/// How long the emailed download link works; the file is deleted afterwards
+pub const DEFAULT_LINK_TTL: Duration = Duration::from_secs(3600);
+/// A user may request one export per window
+pub const EXPORT_WINDOW: Duration = Duration::from_secs(86_400);
+/// Where download links point; the one-time token is appended
+pub const DEFAULT_DOWNLOAD_URL: &str = "/api/v1/exports";
+
+// ============================================================================
+// Export Records
+// ============================================================================
+
+/// File format of an export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// One JSON document
+    #[default]
+    Json,
+    /// A ZIP archive with one JSON file per kind of data
+    Zip,
+}
+
+impl ExportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Zip => "zip",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "json" => Some(Self::Json),
+            "zip" => Some(Self::Zip),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Zip => "application/zip",
+        }
+    }
+}
+
+/// Where an export stands, from the user's point of view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportStatus {
+    /// Queued or being built
+    Pending,
+    /// Built; the emailed link can be used once
+    Ready,
+    Downloaded,
+    /// The link expired unused and the file is gone
+    Expired,
+}
+
+impl ExportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Ready => "ready",
+            Self::Downloaded => "downloaded",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+/// A requested export. `key`, `token_hash` and `expires_at` are set once
+/// the file is built.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataExport {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub format: ExportFormat,
+    pub requested_at: DateTime<Utc>,
+    pub key: Option<String>,
+    /// SHA-256 of the download token, base64url
+    pub token_hash: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub downloaded_at: Option<DateTime<Utc>>,
+}
+
+impl DataExport {
+    pub fn status(&self) -> ExportStatus {
+        match (self.expires_at, self.downloaded_at) {
+            (_, Some(_)) => ExportStatus::Downloaded,
+            (None, None) => ExportStatus::Pending,
+            (Some(expires_at), None) if expires_at <= Utc::now() => ExportStatus::Expired,
+            (Some(_), None) => ExportStatus::Ready,
+        }
+    }
+}
+
+/// Export record storage for dependency injection
+#[async_trait]
+pub trait DataExportStore: Send + Sync {
+    async fn create(&self, export: &DataExport) -> Result<(), ApplicationError>;
+
+    async fn find(&self, id: Uuid) -> Result<Option<DataExport>, ApplicationError>;
+
+    /// The user's most recently requested export
+    async fn latest(&self, user_id: Uuid) -> Result<Option<DataExport>, ApplicationError>;
+
+    /// Record the built file and the hash of its download token
+    async fn mark_ready(&self, id: Uuid, key: &str, token_hash: &str, expires_at: DateTime<Utc>) -> Result<(), ApplicationError>;
+
+    /// Mark the export with this token downloaded and return it, unless it
+    /// already was or expired before `now`. Of two concurrent downloads
+    /// only one gets the export.
+    async fn claim_download(&self, token_hash: &str, now: DateTime<Utc>) -> Result<Option<DataExport>, ApplicationError>;
+}
+
+// ============================================================================
+// Data Export Requests
+// ============================================================================
+
+/// Account data exports (GDPR right of access and data portability). The
+/// export is built by `ExportUserDataJob` and a single-use download link
+/// emailed to the user, so the request returns at once.
+pub struct DataExportService {
+    store: Arc<dyn DataExportStore>,
+    storage: Arc<dyn FileStorage>,
+    job_queue: Arc<dyn JobQueue>,
+}
+
+impl DataExportService {
+    pub fn new(store: Arc<dyn DataExportStore>, storage: Arc<dyn FileStorage>, job_queue: Arc<dyn JobQueue>) -> Self {
+        Self {
+            store,
+            storage,
+            job_queue,
+        }
+    }
+
+    /// Queue an export of `user_id`'s data. Fails with `RateLimited` when
+    /// the user already requested one within `EXPORT_WINDOW`; the stored
+    /// exports decide, so the limit holds across restarts and instances.
+    pub async fn request(&self, user_id: Uuid, format: ExportFormat) -> Result<DataExport, ApplicationError> {
+        let now = Utc::now();
+        if let Some(latest) = self.store.latest(user_id).await? {
+            let next = latest.requested_at + chrono::Duration::from_std(EXPORT_WINDOW).unwrap_or_default();
+            if next > now {
+                let retry_after = (next - now).to_std().unwrap_or_default();
+                return Err(ApplicationError::RateLimited { retry_after });
+            }
+        }
+        let export = DataExport {
+            id: Uuid::new_v4(),
+            user_id,
+            format,
+            requested_at: now,
+            key: None,
+            token_hash: None,
+            expires_at: None,
+            downloaded_at: None,
+        };
+        self.store.create(&export).await?;
+        ExportUserDataJob::enqueue(self.job_queue.as_ref(), export.id).await?;
+        tracing::info!(target: "audit", %user_id, export_id = %export.id, format = format.as_str(), "Data export requested");
+        Ok(export)
+    }
+
+    pub async fn latest(&self, user_id: Uuid) -> Result<Option<DataExport>, ApplicationError> {
+        self.store.latest(user_id).await
+    }
+
+    /// Hand out the export behind a download token, once: the file is
+    /// deleted as soon as it has been read. Unknown, used and expired
+    /// tokens all fail with `NotFound`.
+    pub async fn download(&self, token: &str) -> Result<(DataExport, StoredFile), ApplicationError> {
+        let not_found = || ApplicationError::Domain(DomainError::not_found("Data export", "token"));
+        let export = self
+            .store
+            .claim_download(&hash_token(token), Utc::now())
+            .await?
+            .ok_or_else(not_found)?;
+        let key = export.key.clone().ok_or_else(not_found)?;
+        let file = self.storage.get(&key).await?.ok_or_else(not_found)?;
+        self.storage.delete(&key).await?;
+        tracing::info!(target: "audit", user_id = %export.user_id, export_id = %export.id, "Data export downloaded");
+        Ok((export, file))
+    }
+}
+
+/// Everything stored about a user, as written to the export file
+#[derive(Debug, Serialize)]
+struct UserDataExport {
+    exported_at: DateTime<Utc>,
+    user: domain::User,
+    sessions: Vec<Session>,
+}
+
+impl UserDataExport {
+    fn to_bytes(&self, format: ExportFormat) -> Result<Vec<u8>, ApplicationError> {
+        let serialize_error = |e: &dyn std::fmt::Display| DomainError::internal(format!("Failed to serialize export: {}", e));
+        match format {
+            ExportFormat::Json => Ok(serde_json::to_vec_pretty(self).map_err(|e| serialize_error(&e))?),
+            ExportFormat::Zip => {
+                let files = [
+                    ("user.json", serde_json::to_vec_pretty(&self.user)),
+                    ("sessions.json", serde_json::to_vec_pretty(&self.sessions)),
+                ];
+                let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+                for (name, bytes) in files {
+                    let bytes = bytes.map_err(|e| serialize_error(&e))?;
+                    zip.start_file(name, zip::write::SimpleFileOptions::default())
+                        .map_err(|e| serialize_error(&e))?;
+                    zip.write_all(&bytes).map_err(|e| serialize_error(&e))?;
+                }
+                Ok(zip.finish().map_err(|e| serialize_error(&e))?.into_inner())
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Export Jobs
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportUserDataPayload {
+    export_id: Uuid,
+}
+
+/// Writes a user's profile and sessions to a private file, emails them a
+/// single-use download link and schedules the file's deletion when the link
+/// expires.
+pub struct ExportUserDataJob {
+    exports: Arc<dyn DataExportStore>,
+    users: Arc<dyn UserRepository>,
+    refresh_tokens: Arc<dyn RefreshTokenStore>,
+    storage: Arc<dyn FileStorage>,
+    job_queue: Arc<dyn JobQueue>,
+    link_ttl: Duration,
+    download_url: String,
+}
+
+impl ExportUserDataJob {
+    pub const KIND: &'static str = "user.export";
+
+    pub fn new(
+        exports: Arc<dyn DataExportStore>,
+        users: Arc<dyn UserRepository>,
+        refresh_tokens: Arc<dyn RefreshTokenStore>,
+        storage: Arc<dyn FileStorage>,
+        job_queue: Arc<dyn JobQueue>,
+    ) -> Self {
+        Self {
+            exports,
+            users,
+            refresh_tokens,
+            storage,
+            job_queue,
+            link_ttl: DEFAULT_LINK_TTL,
+            download_url: DEFAULT_DOWNLOAD_URL.to_string(),
+        }
+    }
+
+    pub fn with_link_ttl(mut self, link_ttl: Duration) -> Self {
+        self.link_ttl = link_ttl;
+        self
+    }
+
+    /// Base of the emailed links, e.g. `https://api.example.com/api/v1/exports`
+    pub fn with_download_url(mut self, download_url: impl Into<String>) -> Self {
+        self.download_url = download_url.into();
+        self
+    }
+
+    pub async fn enqueue(queue: &dyn JobQueue, export_id: Uuid) -> Result<(), ApplicationError> {
+        let payload = serde_json::to_value(ExportUserDataPayload { export_id })
+            .map_err(|e| ApplicationError::use_case(format!("Invalid export payload: {}", e)))?;
+        queue.enqueue(Self::KIND, payload, Utc::now()).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Job for ExportUserDataJob {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    async fn run(&self, payload: serde_json::Value) -> Result<(), ApplicationError> {
+        let ExportUserDataPayload { export_id } = serde_json::from_value(payload)
+            .map_err(|e| ApplicationError::use_case(format!("Invalid export payload: {}", e)))?;
+        // Gone with its user. A retry rebuilds the same file with a fresh
+        // link, unless the first one has been used already.
+        let Some(export) = self.exports.find(export_id).await?.filter(|e| e.downloaded_at.is_none()) else {
+            return Ok(());
+        };
+        let Some(user) = self.users.find_by_id(export.user_id).await? else {
+            return Ok(());
+        };
+        let user_id = user.id;
+
+        let sessions = Session::from_tokens(&self.refresh_tokens.list_for_user(user_id).await?);
+        let data = UserDataExport {
+            exported_at: Utc::now(),
+            user,
+            sessions,
+        };
+        let bytes = data.to_bytes(export.format)?;
+        let key = format!("{}{}/{}.{}", EXPORT_PREFIX, user_id, export.id.simple(), export.format.as_str());
+        self.storage.put(&key, bytes, export.format.content_type()).await?;
+
+        // Scheduled first, so a retry after a failure below leaves no file behind
+        let expires_at = Utc::now() + chrono::Duration::from_std(self.link_ttl).unwrap_or_default();
+        DeleteExportJob::enqueue(self.job_queue.as_ref(), &key, expires_at).await?;
+
+        let token = random_token();
+        self.exports.mark_ready(export.id, &key, &hash_token(&token), expires_at).await?;
+
+        let payload = EmailJobPayload {
+            to: data.user.email.to_string(),
+            template: EmailTemplate::DataExport,
+            vars: serde_json::json!({
+                "username": data.user.username,
+                "download_url": format!("{}/{}", self.download_url.trim_end_matches('/'), token),
+                "expires_in_minutes": self.link_ttl.as_secs() / 60,
+            }),
+        };
+        SendEmailJob::enqueue(self.job_queue.as_ref(), payload).await?;
+        tracing::info!(target: "audit", %user_id, export_id = %export.id, key, "Data export delivered");
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DeleteExportPayload {
+    key: String,
+}
+
+/// Deletes an export file once its download link has expired
+pub struct DeleteExportJob {
+    storage: Arc<dyn FileStorage>,
+}
+
+impl DeleteExportJob {
+    pub const KIND: &'static str = "user.export.delete";
+
+    pub fn new(storage: Arc<dyn FileStorage>) -> Self {
+        Self { storage }
+    }
+
+    pub async fn enqueue(queue: &dyn JobQueue, key: &str, run_at: DateTime<Utc>) -> Result<(), ApplicationError> {
+        let payload = serde_json::to_value(DeleteExportPayload { key: key.to_string() })
+            .map_err(|e| ApplicationError::use_case(format!("Invalid export payload: {}", e)))?;
+        queue.enqueue(Self::KIND, payload, run_at).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Job for DeleteExportJob {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    async fn run(&self, payload: serde_json::Value) -> Result<(), ApplicationError> {
+        let DeleteExportPayload { key } = serde_json::from_value(payload)
+            .map_err(|e| ApplicationError::use_case(format!("Invalid export payload: {}", e)))?;
+        // Only ever delete exports, whatever the payload says
+        if !key.starts_with(EXPORT_PREFIX) {
+            return Err(ApplicationError::use_case(format!("Not an export: {}", key)));
+        }
+        self.storage.delete(&key).await
+    }
+}
@@ -0,0 +1,162 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::ApplicationError;
+
+/// How long "remember this device" lasts unless configured
+pub const DEFAULT_TRUST_PERIOD: Duration = Duration::from_secs(30 * 86_400);
+
+/// Longest fingerprint a client may send
+const MAX_FINGERPRINT_LEN: usize = 512;
+
+/// Longest device name kept, in characters
+const MAX_NAME_LEN: usize = 200;
+
+// ============================================================================
+// Devices
+// ============================================================================
+
+/// A device a user has signed in from
+#[derive(Debug, Clone)]
+pub struct Device {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Hex SHA-256 of the client's fingerprint; the fingerprint itself is not kept
+    pub fingerprint_hash: String,
+    /// Label for the device list, from the `User-Agent` of the last sign-in
+    pub name: Option<String>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    /// Set while "remember this device" is in effect
+    pub trusted_until: Option<DateTime<Utc>>,
+}
+
+impl Device {
+    pub fn is_trusted(&self, now: DateTime<Utc>) -> bool {
+        self.trusted_until.is_some_and(|until| until > now)
+    }
+}
+
+/// Persistence of devices, for dependency injection
+#[async_trait]
+pub trait DeviceStore: Send + Sync {
+    async fn find(&self, user_id: Uuid, fingerprint_hash: &str) -> Result<Option<Device>, ApplicationError>;
+    /// Insert, or update the device with the same id
+    async fn save(&self, device: &Device) -> Result<(), ApplicationError>;
+    /// Most recently seen first
+    async fn list(&self, user_id: Uuid) -> Result<Vec<Device>, ApplicationError>;
+    /// False when the user had no device with the id
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<bool, ApplicationError>;
+}
+
+// ============================================================================
+// Device Service
+// ============================================================================
+
+/// Records the devices users sign in from and which of them are trusted.
+/// A device sent with "remember this device" stays trusted for
+/// `trust_period` after that sign-in, so a second factor can be skipped on
+/// it (`is_trusted`); removing the device ends the trust.
+pub struct DeviceService {
+    store: Arc<dyn DeviceStore>,
+    trust_period: Duration,
+}
+
+impl DeviceService {
+    pub fn new(store: Arc<dyn DeviceStore>) -> Self {
+        Self {
+            store,
+            trust_period: DEFAULT_TRUST_PERIOD,
+        }
+    }
+
+    /// Zero turns "remember this device" off
+    pub fn with_trust_period(mut self, trust_period: Duration) -> Self {
+        self.trust_period = trust_period;
+        self
+    }
+
+    /// Note a sign-in from `fingerprint`, trusting the device if asked to.
+    /// Signing in without `remember` leaves an earlier trust as it was.
+    pub async fn record_login(
+        &self,
+        user_id: Uuid,
+        fingerprint: &str,
+        name: Option<&str>,
+        remember: bool,
+        now: DateTime<Utc>,
+    ) -> Result<Device, ApplicationError> {
+        let fingerprint_hash = hash_fingerprint(fingerprint)?;
+        let name = name
+            .map(str::trim)
+            .filter(|n| !n.is_empty())
+            .map(|n| n.chars().take(MAX_NAME_LEN).collect::<String>());
+
+        let mut device = match self.store.find(user_id, &fingerprint_hash).await? {
+            Some(device) => device,
+            None => Device {
+                id: Uuid::new_v4(),
+                user_id,
+                fingerprint_hash,
+                name: None,
+                first_seen_at: now,
+                last_seen_at: now,
+                trusted_until: None,
+            },
+        };
+        device.last_seen_at = now;
+        if name.is_some() {
+            device.name = name;
+        }
+        if remember && !self.trust_period.is_zero() {
+            let period = chrono::Duration::from_std(self.trust_period)
+                .map_err(|_| DomainError::internal("Device trust period is out of range"))?;
+            device.trusted_until = Some(now + period);
+            tracing::info!(target: "audit", user_id = %user_id, device_id = %device.id, "Device trusted");
+        }
+
+        self.store.save(&device).await?;
+        Ok(device)
+    }
+
+    /// Whether `fingerprint` is a device the user asked to remember, and
+    /// the trust has not run out
+    pub async fn is_trusted(&self, user_id: Uuid, fingerprint: &str, now: DateTime<Utc>) -> Result<bool, ApplicationError> {
+        let fingerprint_hash = hash_fingerprint(fingerprint)?;
+        Ok(self
+            .store
+            .find(user_id, &fingerprint_hash)
+            .await?
+            .is_some_and(|d| d.is_trusted(now)))
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<Device>, ApplicationError> {
+        self.store.list(user_id).await
+    }
+
+    /// Forget one of the user's devices; it is no longer trusted
+    pub async fn remove(&self, user_id: Uuid, id: Uuid) -> Result<(), ApplicationError> {
+        if !self.store.delete(user_id, id).await? {
+            return Err(DomainError::not_found("Device", id.to_string()).into());
+        }
+        tracing::info!(target: "audit", user_id = %user_id, device_id = %id, "Device removed");
+        Ok(())
+    }
+}
+
+fn hash_fingerprint(fingerprint: &str) -> Result<String, ApplicationError> {
+    let fingerprint = fingerprint.trim();
+    if fingerprint.is_empty() || fingerprint.len() > MAX_FINGERPRINT_LEN {
+        return Err(DomainError::validation(format!(
+            "Device fingerprint must be 1 to {} characters",
+            MAX_FINGERPRINT_LEN
+        ))
+        .into());
+    }
+    Ok(hex::encode(Sha256::digest(fingerprint.as_bytes())))
+}
@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use domain::DomainEvent;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::jobs::{Job, JobQueue};
+use crate::{ApplicationError, EventBus};
+
+// ============================================================================
+// Email Port
+// ============================================================================
+
+/// Transactional email templates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailTemplate {
+    /// Vars: `username`
+    Welcome,
+    /// Vars: `username`, `reset_url`, `expires_in_minutes`
+    PasswordReset,
+    /// Sent to the support team. Vars: `ticket_id`, `name`, `email`, `subject`, `message`
+    SupportContact,
+    /// Vars: `username`, `download_url`, `expires_in_minutes`
+    DataExport,
+    /// Vars: `organization`, `inviter`, `role`, `accept_url`, `expires_in_days`
+    OrganizationInvitation,
+}
+
+impl EmailTemplate {
+    /// Template file stem (`welcome` → `welcome.subject.txt`, `welcome.txt`, `welcome.html`)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Welcome => "welcome",
+            Self::PasswordReset => "password_reset",
+            Self::SupportContact => "support_contact",
+            Self::DataExport => "data_export",
+            Self::OrganizationInvitation => "organization_invitation",
+        }
+    }
+}
+
+/// Email delivery for dependency injection. Adapters render `template`
+/// with `vars` and send it to `to`.
+#[async_trait]
+pub trait EmailSender: Send + Sync {
+    async fn send(&self, to: &str, template: EmailTemplate, vars: serde_json::Value) -> Result<(), ApplicationError>;
+}
+
+// ============================================================================
+// Email Job
+// ============================================================================
+
+/// Payload of an `email.send` job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailJobPayload {
+    pub to: String,
+    pub template: EmailTemplate,
+    pub vars: serde_json::Value,
+}
+
+/// Sends queued emails, so SMTP hiccups are retried with backoff
+pub struct SendEmailJob {
+    sender: Arc<dyn EmailSender>,
+}
+
+impl SendEmailJob {
+    pub const KIND: &'static str = "email.send";
+
+    pub fn new(sender: Arc<dyn EmailSender>) -> Self {
+        Self { sender }
+    }
+
+    /// Queue an email for delivery by the job workers
+    pub async fn enqueue(queue: &dyn JobQueue, payload: EmailJobPayload) -> Result<(), ApplicationError> {
+        let payload = serde_json::to_value(payload)
+            .map_err(|e| ApplicationError::use_case(format!("Invalid email payload: {}", e)))?;
+        queue.enqueue(Self::KIND, payload, Utc::now()).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Job for SendEmailJob {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    async fn run(&self, payload: serde_json::Value) -> Result<(), ApplicationError> {
+        let payload: EmailJobPayload = serde_json::from_value(payload)
+            .map_err(|e| ApplicationError::use_case(format!("Invalid email payload: {}", e)))?;
+        self.sender.send(&payload.to, payload.template, payload.vars).await
+    }
+}
+
+/// Queue a welcome email for every newly registered user
+pub fn spawn_welcome_emails(event_bus: Arc<dyn EventBus>, queue: Arc<dyn JobQueue>) {
+    let mut events = event_bus.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let envelope = match events.recv().await {
+                Ok(envelope) => envelope,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Welcome mailer lagged behind the event bus");
+                    continue;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
+
+            if let DomainEvent::UserRegistered { username, email, .. } = envelope.event {
+                let payload = EmailJobPayload {
+                    to: email,
+                    template: EmailTemplate::Welcome,
+                    vars: serde_json::json!({ "username": username }),
+                };
+                if let Err(e) = SendEmailJob::enqueue(queue.as_ref(), payload).await {
+                    tracing::error!("Failed to queue welcome email: {}", e);
+                }
+            }
+        }
+    });
+}
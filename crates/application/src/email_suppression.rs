@@ -0,0 +1,203 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::email::{EmailSender, EmailTemplate};
+use crate::ApplicationError;
+
+// ============================================================================
+// Suppression List Port
+// ============================================================================
+
+/// Why an address no longer receives email
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionReason {
+    /// Permanent (hard) bounce: the mailbox does not exist
+    Bounce,
+    /// The recipient marked a message as spam
+    Complaint,
+}
+
+impl SuppressionReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Bounce => "bounce",
+            Self::Complaint => "complaint",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "bounce" => Some(Self::Bounce),
+            "complaint" => Some(Self::Complaint),
+            _ => None,
+        }
+    }
+}
+
+/// An undeliverable address reported by the email provider
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BounceNotice {
+    pub email: String,
+    pub reason: SuppressionReason,
+    /// Provider diagnostic (SMTP response, feedback type)
+    pub detail: Option<String>,
+}
+
+/// A suppressed address
+#[derive(Debug, Clone)]
+pub struct EmailSuppression {
+    /// Normalized (see `normalize_email`)
+    pub email: String,
+    pub reason: SuppressionReason,
+    /// `ses` or `sendgrid`
+    pub provider: String,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Addresses email must not be sent to
+#[async_trait]
+pub trait EmailSuppressionList: Send + Sync {
+    /// Add (or refresh) a suppression
+    async fn suppress(&self, notice: &BounceNotice, provider: &str) -> Result<(), ApplicationError>;
+
+    /// Suppressions among `emails`
+    async fn find_many(&self, emails: &[String]) -> Result<Vec<EmailSuppression>, ApplicationError>;
+
+    /// Lift a suppression; false when the address was not suppressed
+    async fn remove(&self, email: &str) -> Result<bool, ApplicationError>;
+}
+
+/// Addresses are compared case-insensitively
+pub fn normalize_email(email: &str) -> String {
+    email.trim().to_ascii_lowercase()
+}
+
+// ============================================================================
+// Suppressing Sender
+// ============================================================================
+
+/// `EmailSender` that drops messages to suppressed addresses. Dropped
+/// messages succeed, so their jobs are not retried.
+pub struct SuppressingEmailSender {
+    inner: Arc<dyn EmailSender>,
+    suppressions: Arc<dyn EmailSuppressionList>,
+}
+
+impl SuppressingEmailSender {
+    pub fn new(inner: Arc<dyn EmailSender>, suppressions: Arc<dyn EmailSuppressionList>) -> Self {
+        Self { inner, suppressions }
+    }
+}
+
+#[async_trait]
+impl EmailSender for SuppressingEmailSender {
+    async fn send(&self, to: &str, template: EmailTemplate, vars: Value) -> Result<(), ApplicationError> {
+        if let Some(suppression) = self.suppressions.find_many(&[normalize_email(to)]).await?.into_iter().next() {
+            tracing::info!(
+                template = template.name(),
+                reason = suppression.reason.as_str(),
+                "Email to suppressed address dropped"
+            );
+            return Ok(());
+        }
+        self.inner.send(to, template, vars).await
+    }
+}
+
+// ============================================================================
+// Provider Notifications
+// ============================================================================
+
+/// Parsed Amazon SES notification (delivered through SNS)
+#[derive(Debug, PartialEq, Eq)]
+pub enum SesNotification {
+    /// SNS asks to confirm the subscription by visiting `subscribe_url`
+    SubscriptionConfirmation { subscribe_url: String },
+    /// Addresses to suppress (empty for soft bounces and other events)
+    Notices(Vec<BounceNotice>),
+}
+
+/// Parse an SNS-wrapped (or raw) SES bounce/complaint notification. Only
+/// permanent bounces and complaints produce notices.
+pub fn parse_ses_notification(body: &[u8]) -> Result<SesNotification, ApplicationError> {
+    let envelope: Value = serde_json::from_slice(body).map_err(|e| invalid_notification("SES", e))?;
+    let message = match envelope["Type"].as_str() {
+        Some("SubscriptionConfirmation") => {
+            let subscribe_url = envelope["SubscribeURL"]
+                .as_str()
+                .ok_or_else(|| ApplicationError::UseCase("SNS confirmation without SubscribeURL".to_string()))?;
+            return Ok(SesNotification::SubscriptionConfirmation {
+                subscribe_url: subscribe_url.to_string(),
+            });
+        }
+        Some("Notification") => {
+            let raw = envelope["Message"].as_str().unwrap_or_default();
+            serde_json::from_str(raw).map_err(|e| invalid_notification("SES", e))?
+        }
+        _ => envelope,
+    };
+
+    // Event publishing uses `eventType`, identity notifications `notificationType`
+    let kind = message["notificationType"].as_str().or(message["eventType"].as_str());
+    let notices = match kind {
+        Some("Bounce") if message["bounce"]["bounceType"] == "Permanent" => recipients(
+            &message["bounce"]["bouncedRecipients"],
+            SuppressionReason::Bounce,
+            |r| r["diagnosticCode"].as_str().or(message["bounce"]["bounceSubType"].as_str()),
+        ),
+        Some("Complaint") => recipients(
+            &message["complaint"]["complainedRecipients"],
+            SuppressionReason::Complaint,
+            |_| message["complaint"]["complaintFeedbackType"].as_str(),
+        ),
+        _ => Vec::new(),
+    };
+    Ok(SesNotification::Notices(notices))
+}
+
+/// Parse a SendGrid event webhook batch. Hard bounces (not `blocked`) and
+/// spam reports produce notices.
+pub fn parse_sendgrid_events(body: &[u8]) -> Result<Vec<BounceNotice>, ApplicationError> {
+    let events: Vec<Value> = serde_json::from_slice(body).map_err(|e| invalid_notification("SendGrid", e))?;
+    Ok(events
+        .iter()
+        .filter_map(|event| {
+            let reason = match (event["event"].as_str(), event["type"].as_str()) {
+                (Some("bounce"), Some("blocked")) => return None,
+                (Some("bounce"), _) => SuppressionReason::Bounce,
+                (Some("spamreport"), _) => SuppressionReason::Complaint,
+                _ => return None,
+            };
+            Some(BounceNotice {
+                email: normalize_email(event["email"].as_str()?),
+                reason,
+                detail: event["reason"].as_str().map(str::to_string),
+            })
+        })
+        .collect())
+}
+
+fn recipients<'a>(
+    list: &'a Value,
+    reason: SuppressionReason,
+    detail: impl Fn(&'a Value) -> Option<&'a str>,
+) -> Vec<BounceNotice> {
+    list.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|recipient| {
+            Some(BounceNotice {
+                email: normalize_email(recipient["emailAddress"].as_str()?),
+                reason,
+                detail: detail(recipient).map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+fn invalid_notification(provider: &str, err: serde_json::Error) -> ApplicationError {
+    ApplicationError::UseCase(format!("Invalid {} notification: {}", provider, err))
+}
@@ -0,0 +1,142 @@
+use chrono::{DateTime, Utc};
+use domain::{DomainError, DomainEvent, EventEnvelope};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// ============================================================================
+// Versioned Envelope
+// ============================================================================
+
+/// An `EventEnvelope` as stored and sent outside the process (webhook
+/// deliveries), tagged with the version of the event's payload shape.
+/// The JSON is the envelope's plus `schema_version`, so consumers of the
+/// plain envelope keep working.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionedEvent {
+    pub id: u64,
+    pub occurred_at: DateTime<Utc>,
+    /// Rows stored before events were versioned have none: version 1
+    #[serde(default = "first_version")]
+    pub schema_version: u32,
+    /// The event object, with its `type`
+    pub event: serde_json::Value,
+}
+
+fn first_version() -> u32 {
+    1
+}
+
+impl VersionedEvent {
+    /// Tag an envelope with its event's current schema version
+    pub fn new(envelope: &EventEnvelope) -> Result<Self, DomainError> {
+        let event = serde_json::to_value(&envelope.event)
+            .map_err(|e| DomainError::internal(format!("Failed to serialize event: {}", e)))?;
+        Ok(Self {
+            id: envelope.id,
+            occurred_at: envelope.occurred_at,
+            schema_version: DomainEvent::schema_version(envelope.event.name()),
+            event,
+        })
+    }
+
+    /// Parse a stored row, whatever version it was written with
+    pub fn parse(raw: &str) -> Result<Self, DomainError> {
+        serde_json::from_str(raw).map_err(|e| DomainError::validation(format!("Malformed stored event: {}", e)))
+    }
+
+    /// Dotted event name, e.g. `user.registered`
+    pub fn event_type(&self) -> &str {
+        self.event["type"].as_str().unwrap_or_default()
+    }
+
+    pub fn to_json(&self) -> Result<String, DomainError> {
+        serde_json::to_string(self).map_err(|e| DomainError::internal(format!("Failed to serialize event: {}", e)))
+    }
+}
+
+// ============================================================================
+// Upcasting
+// ============================================================================
+
+/// The upcasters of this build. Add a step here whenever an entry in
+/// `DomainEvent::SCHEMA_VERSIONS` is bumped.
+pub fn upcasters() -> EventUpcasters {
+    EventUpcasters::new()
+}
+
+type UpcastFn = Box<dyn Fn(serde_json::Value) -> Result<serde_json::Value, DomainError> + Send + Sync>;
+
+/// Migrations from old event shapes to current ones, applied when a stored
+/// event is consumed. Each step takes one event type from version `n` to
+/// `n + 1`, so a row several versions behind goes through every step.
+///
+/// ```ignore
+/// // user.registered v2 split `username` into `handle` and `display_name`
+/// EventUpcasters::new().register("user.registered", 1, |mut event| {
+///     event["handle"] = event["username"].clone();
+///     event["display_name"] = event["username"].take();
+///     Ok(event)
+/// })
+/// ```
+#[derive(Default)]
+pub struct EventUpcasters {
+    steps: HashMap<(String, u32), UpcastFn>,
+}
+
+impl EventUpcasters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Migrate `event_type` events from `from_version` to the next version.
+    /// `upcast` gets and returns the event object, `type` included.
+    pub fn register<F>(mut self, event_type: &str, from_version: u32, upcast: F) -> Self
+    where
+        F: Fn(serde_json::Value) -> Result<serde_json::Value, DomainError> + Send + Sync + 'static,
+    {
+        self.steps.insert((event_type.to_string(), from_version), Box::new(upcast));
+        self
+    }
+
+    /// Bring `event` to the current schema version of its type. Fails for a
+    /// missing step and for versions newer than this build knows.
+    pub fn upcast(&self, event: VersionedEvent) -> Result<VersionedEvent, DomainError> {
+        let current = DomainEvent::schema_version(event.event_type());
+        self.upcast_to(event, current)
+    }
+
+    /// Bring `event` to `version` of its type
+    pub fn upcast_to(&self, mut event: VersionedEvent, version: u32) -> Result<VersionedEvent, DomainError> {
+        let event_type = event.event_type().to_string();
+        if event.schema_version > version {
+            return Err(DomainError::validation(format!(
+                "{} v{} is newer than this build (v{})",
+                event_type, event.schema_version, version
+            )));
+        }
+        while event.schema_version < version {
+            let step = self.steps.get(&(event_type.clone(), event.schema_version)).ok_or_else(|| {
+                DomainError::internal(format!("No upcaster for {} v{}", event_type, event.schema_version))
+            })?;
+            event.event = step(event.event)?;
+            let Some(object) = event.event.as_object_mut() else {
+                return Err(DomainError::internal(format!("Upcaster for {} v{} returned a non-object", event_type, event.schema_version)));
+            };
+            object.insert("type".to_string(), serde_json::Value::String(event_type.clone()));
+            event.schema_version += 1;
+        }
+        Ok(event)
+    }
+
+    /// Parse a stored row and decode it into the current `EventEnvelope`
+    pub fn decode(&self, raw: &str) -> Result<EventEnvelope, DomainError> {
+        let event = self.upcast(VersionedEvent::parse(raw)?)?;
+        let domain_event = serde_json::from_value(event.event)
+            .map_err(|e| DomainError::validation(format!("Stored event does not match its schema: {}", e)))?;
+        Ok(EventEnvelope {
+            id: event.id,
+            occurred_at: event.occurred_at,
+            event: domain_event,
+        })
+    }
+}
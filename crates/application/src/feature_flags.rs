@@ -0,0 +1,194 @@
+use async_trait::async_trait;
+use domain::{DomainError, FeatureFlag, FlagContext};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use crate::{ApplicationError, FeatureFlagService};
+
+/// Longest accepted flag key
+pub const MAX_FLAG_KEY_LEN: usize = 64;
+
+// ============================================================================
+// Store
+// ============================================================================
+
+/// Flags managed by admins, for dependency injection
+#[async_trait]
+pub trait FeatureFlagStore: Send + Sync {
+    /// Every stored flag, by key
+    async fn list(&self) -> Result<Vec<FeatureFlag>, ApplicationError>;
+
+    /// Insert the flag or replace the one with its key
+    async fn save(&self, flag: &FeatureFlag) -> Result<(), ApplicationError>;
+
+    /// False when no flag had the key
+    async fn delete(&self, key: &str) -> Result<bool, ApplicationError>;
+}
+
+/// Where a flag's current definition comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlagSource {
+    /// `features.flags`, fixed until restart
+    Config,
+    /// Created or edited by an admin
+    Stored,
+}
+
+impl FlagSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::Stored => "stored",
+        }
+    }
+}
+
+// ============================================================================
+// Managed Feature Flags
+// ============================================================================
+
+/// Feature flags from configuration, overridden and extended by flags admins
+/// manage at runtime. Evaluation runs on every flagged request, so stored
+/// flags are cached: changes made here apply at once, changes made through
+/// other instances once `refresh` runs (see `spawn_refresh`).
+pub struct ManagedFeatureFlags {
+    store: Arc<dyn FeatureFlagStore>,
+    defaults: BTreeMap<String, FeatureFlag>,
+    stored: RwLock<BTreeMap<String, FeatureFlag>>,
+}
+
+impl ManagedFeatureFlags {
+    /// `defaults` usually come from `features.flags`; a stored flag with the
+    /// same key takes precedence
+    pub fn new(store: Arc<dyn FeatureFlagStore>, defaults: Vec<FeatureFlag>) -> Self {
+        Self {
+            store,
+            defaults: defaults.into_iter().map(|f| (f.key.clone(), f)).collect(),
+            stored: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Reload the stored flags
+    pub async fn refresh(&self) -> Result<(), ApplicationError> {
+        let flags = self.store.list().await?;
+        *self.stored.write().unwrap() = flags.into_iter().map(|f| (f.key.clone(), f)).collect();
+        Ok(())
+    }
+
+    /// Refresh the cached flags every `interval`
+    pub fn spawn_refresh(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(e) = self.refresh().await {
+                    tracing::warn!("Failed to refresh feature flags: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Every flag in force, by key
+    pub fn list(&self) -> Vec<(FeatureFlag, FlagSource)> {
+        let stored = self.stored.read().unwrap();
+        let mut flags: BTreeMap<&str, (FeatureFlag, FlagSource)> = self
+            .defaults
+            .values()
+            .map(|f| (f.key.as_str(), (f.clone(), FlagSource::Config)))
+            .collect();
+        for flag in stored.values() {
+            flags.insert(flag.key.as_str(), (flag.clone(), FlagSource::Stored));
+        }
+        flags.into_values().collect()
+    }
+
+    pub fn get(&self, key: &str) -> Option<(FeatureFlag, FlagSource)> {
+        if let Some(flag) = self.stored.read().unwrap().get(key) {
+            return Some((flag.clone(), FlagSource::Stored));
+        }
+        self.defaults.get(key).map(|f| (f.clone(), FlagSource::Config))
+    }
+
+    /// Add a flag; its key must be new
+    pub async fn create(&self, flag: FeatureFlag) -> Result<FeatureFlag, ApplicationError> {
+        let flag = validate(flag)?;
+        if self.get(&flag.key).is_some() {
+            return Err(DomainError::conflict(format!("Feature flag '{}' already exists", flag.key)).into());
+        }
+        self.save(flag).await
+    }
+
+    /// Replace the flag `key`. Editing a flag from configuration stores a copy
+    /// that takes precedence over it.
+    pub async fn update(&self, key: &str, flag: FeatureFlag) -> Result<FeatureFlag, ApplicationError> {
+        if self.get(key).is_none() {
+            return Err(DomainError::not_found("FeatureFlag", key).into());
+        }
+        self.save(validate(FeatureFlag { key: key.to_string(), ..flag })?).await
+    }
+
+    /// Delete a stored flag. A flag of the same key in configuration is in
+    /// force again afterwards; configured flags themselves cannot be deleted.
+    pub async fn delete(&self, key: &str) -> Result<(), ApplicationError> {
+        if !self.store.delete(key).await? {
+            let reason = if self.defaults.contains_key(key) {
+                DomainError::validation(format!("Feature flag '{}' is configured in features.flags; disable it instead", key))
+            } else {
+                DomainError::not_found("FeatureFlag", key)
+            };
+            return Err(reason.into());
+        }
+        self.stored.write().unwrap().remove(key);
+        Ok(())
+    }
+
+    async fn save(&self, flag: FeatureFlag) -> Result<FeatureFlag, ApplicationError> {
+        self.store.save(&flag).await?;
+        self.stored.write().unwrap().insert(flag.key.clone(), flag.clone());
+        Ok(flag)
+    }
+}
+
+#[async_trait]
+impl FeatureFlagService for ManagedFeatureFlags {
+    async fn is_enabled(&self, key: &str, ctx: &FlagContext) -> Result<bool, ApplicationError> {
+        Ok(self.get(key).is_some_and(|(flag, _)| flag.is_enabled_for(ctx)))
+    }
+
+    async fn enabled_flags(&self, ctx: &FlagContext) -> Result<Vec<String>, ApplicationError> {
+        Ok(self
+            .list()
+            .into_iter()
+            .filter(|(flag, _)| flag.is_enabled_for(ctx))
+            .map(|(flag, _)| flag.key)
+            .collect())
+    }
+}
+
+// ============================================================================
+// Validation
+// ============================================================================
+
+/// Whether `key` is a valid flag key: lowercase letters, digits, `_`, `-`
+/// and `.`, starting with a letter
+pub fn is_valid_flag_key(key: &str) -> bool {
+    key.len() <= MAX_FLAG_KEY_LEN
+        && key.starts_with(|c: char| c.is_ascii_lowercase())
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-' | '.'))
+}
+
+fn validate(mut flag: FeatureFlag) -> Result<FeatureFlag, ApplicationError> {
+    flag.key = flag.key.trim().to_string();
+    if !is_valid_flag_key(&flag.key) {
+        return Err(DomainError::validation(format!(
+            "Flag keys are up to {} lowercase letters, digits, '_', '-' and '.', starting with a letter",
+            MAX_FLAG_KEY_LEN
+        ))
+        .into());
+    }
+    if !(0.0..=100.0).contains(&flag.rollout.percentage) {
+        return Err(DomainError::validation("Rollout percentage must be between 0 and 100").into());
+    }
+    flag.description = flag.description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+    Ok(flag)
+}
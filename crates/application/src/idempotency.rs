@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::jobs::Job;
+use crate::ApplicationError;
+
+// ============================================================================
+// Idempotency Port
+// ============================================================================
+
+/// Response recorded for an idempotency key and replayed on retries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+/// Outcome of claiming an idempotency key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyClaim {
+    /// First use: process the request, then `complete` or `release` the key
+    Acquired,
+    /// Another request with this key is still being processed
+    InProgress,
+    /// Already processed: replay this response
+    Completed(StoredResponse),
+    /// The key was used for a different request
+    Mismatch,
+}
+
+/// Persistence for `Idempotency-Key` handling. Keys are scoped (per user),
+/// and remembered until they expire.
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    /// Claim `key` for a request identified by `fingerprint`. An expired key,
+    /// or one left in progress past `lock_timeout` (crashed worker), is
+    /// claimed afresh.
+    async fn claim(
+        &self,
+        scope: &str,
+        key: &str,
+        fingerprint: &str,
+        ttl: Duration,
+        lock_timeout: Duration,
+    ) -> Result<IdempotencyClaim, ApplicationError>;
+
+    /// Record the response of an acquired key
+    async fn complete(&self, scope: &str, key: &str, response: &StoredResponse) -> Result<(), ApplicationError>;
+
+    /// Forget an acquired key without a response, so a retry runs again
+    async fn release(&self, scope: &str, key: &str) -> Result<(), ApplicationError>;
+
+    /// Delete keys that expired before `before`; returns how many
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64, ApplicationError>;
+}
+
+// ============================================================================
+// Cleanup Job
+// ============================================================================
+
+/// Recurring job deleting expired idempotency keys
+pub struct PruneIdempotencyKeysJob {
+    store: Arc<dyn IdempotencyStore>,
+}
+
+impl PruneIdempotencyKeysJob {
+    pub const KIND: &'static str = "idempotency.prune";
+
+    pub fn new(store: Arc<dyn IdempotencyStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Job for PruneIdempotencyKeysJob {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    async fn run(&self, _payload: serde_json::Value) -> Result<(), ApplicationError> {
+        let pruned = self.store.prune(Utc::now()).await?;
+        tracing::info!(pruned, "Pruned expired idempotency keys");
+        Ok(())
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use domain::{DomainError, TokenPair, UserRepository};
+use uuid::Uuid;
+
+use crate::authz::AuthorizationService;
+use crate::{ApplicationError, TokenService};
+
+// ============================================================================
+// Impersonation
+// ============================================================================
+
+/// Lets support staff act as a user. Impersonation tokens are ordinary user
+/// tokens that also name the admin (`Claims::act`); they expire after
+/// `jwt.impersonation_expiration_minutes` and come without a refresh token.
+pub struct Impersonation {
+    users: Arc<dyn UserRepository>,
+    authz: Arc<AuthorizationService>,
+    tokens: Arc<dyn TokenService>,
+}
+
+impl Impersonation {
+    pub fn new(users: Arc<dyn UserRepository>, authz: Arc<AuthorizationService>, tokens: Arc<dyn TokenService>) -> Self {
+        Self { users, authz, tokens }
+    }
+
+    /// Token for `user_id` on behalf of `admin_id`. Admins cannot be
+    /// impersonated, so the token never carries more rights than the
+    /// admin's own.
+    pub async fn start(&self, admin_id: Uuid, user_id: Uuid) -> Result<TokenPair, ApplicationError> {
+        if admin_id == user_id {
+            return Err(DomainError::validation("Cannot impersonate yourself").into());
+        }
+        let user = self
+            .users
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| DomainError::not_found("User", user_id.to_string()))?;
+        if self.authz.stored(user_id).await?.has_role("admin") {
+            return Err(DomainError::validation("Admins cannot be impersonated").into());
+        }
+
+        let token = self.tokens.generate_impersonation(&user, admin_id)?;
+        tracing::info!(
+            target: "audit",
+            admin_id = %admin_id,
+            user_id = %user_id,
+            expires_in = token.expires_in,
+            "Impersonation started"
+        );
+        Ok(token)
+    }
+}
@@ -0,0 +1,599 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use domain::{DomainError, Email, User, UserRepository, Username};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::jobs::{Job, JobQueue};
+use crate::operations::{Operation, OperationItem, OperationStatus, OperationStore};
+use crate::password_policy::PasswordPolicy;
+use crate::storage::FileStorage;
+use crate::{tenancy, with_transaction, ApplicationError, PasswordHasher, UnitOfWork};
+
+/// Uploads waiting for their import job are stored under this prefix
+pub const IMPORT_PREFIX: &str = "imports/";
+/// Rows inserted per transaction
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+/// Inputs up to this size are imported during the request
+pub const DEFAULT_INLINE_MAX_BYTES: u64 = 16 * 1024;
+/// Largest input accepted for a background import
+pub const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+// ============================================================================
+// Input Formats
+// ============================================================================
+
+/// How the users to import are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    /// A header row naming `username`, `email` and `password`, then one user
+    /// per row. Fields may be quoted, but not span lines.
+    Csv,
+    /// One JSON object per line with the same fields
+    Ndjson,
+}
+
+impl ImportFormat {
+    /// The format sent as `content_type`, ignoring parameters such as `charset`
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let essence = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+        match essence.as_str() {
+            "text/csv" => Some(Self::Csv),
+            "application/x-ndjson" | "application/ndjson" => Some(Self::Ndjson),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// One user to create, as written in the input
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ImportRow {
+    pub username: String,
+    pub email: String,
+    pub password: String,
+}
+
+/// What one input line holds
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsedLine {
+    /// Blank line or the CSV header
+    Skip,
+    Row(ImportRow),
+    /// A row that could not be read, with the reason
+    Invalid(String),
+}
+
+/// Column positions from the CSV header
+#[derive(Debug, Clone, Copy)]
+struct Columns {
+    username: usize,
+    email: usize,
+    password: usize,
+}
+
+/// Reads input lines one at a time, so inputs are never held whole
+#[derive(Debug)]
+pub struct RowParser {
+    format: ImportFormat,
+    columns: Option<Columns>,
+}
+
+impl RowParser {
+    pub fn new(format: ImportFormat) -> Self {
+        Self { format, columns: None }
+    }
+
+    /// Parse the next line. Fails only for a CSV header without the
+    /// required columns, which makes the whole input unusable.
+    pub fn parse(&mut self, line: &str) -> Result<ParsedLine, DomainError> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.trim().is_empty() {
+            return Ok(ParsedLine::Skip);
+        }
+        if self.format == ImportFormat::Ndjson {
+            return Ok(match serde_json::from_str(line) {
+                Ok(row) => ParsedLine::Row(row),
+                Err(e) => ParsedLine::Invalid(format!("Invalid JSON: {}", e)),
+            });
+        }
+
+        let Some(columns) = self.columns else {
+            let header = split_csv(line).map_err(DomainError::validation)?;
+            let position = |name: &str| {
+                header
+                    .iter()
+                    .position(|column| column.trim().eq_ignore_ascii_case(name))
+                    .ok_or_else(|| DomainError::validation(format!("The CSV header has no `{}` column", name)))
+            };
+            self.columns = Some(Columns {
+                username: position("username")?,
+                email: position("email")?,
+                password: position("password")?,
+            });
+            return Ok(ParsedLine::Skip);
+        };
+        let fields = match split_csv(line) {
+            Ok(fields) => fields,
+            Err(e) => return Ok(ParsedLine::Invalid(e)),
+        };
+        let field = |index: usize| fields.get(index).cloned();
+        Ok(match (field(columns.username), field(columns.email), field(columns.password)) {
+            (Some(username), Some(email), Some(password)) => ParsedLine::Row(ImportRow {
+                username,
+                email,
+                password,
+            }),
+            _ => ParsedLine::Invalid(format!("Expected at least {} fields, found {}", columns.max() + 1, fields.len())),
+        })
+    }
+}
+
+impl Columns {
+    fn max(&self) -> usize {
+        self.username.max(self.email).max(self.password)
+    }
+}
+
+/// Fields of one CSV line; `"` quotes a field and `""` is a literal quote
+fn split_csv(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => fields.push(std::mem::take(&mut field)),
+            (_, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("Unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+// ============================================================================
+// Importing
+// ============================================================================
+
+/// Outcome of one input row. Each row is given the id its user gets; for
+/// failed rows no user has it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedRow {
+    pub line: u64,
+    pub id: Uuid,
+    pub error: Option<String>,
+}
+
+impl From<ImportedRow> for OperationItem {
+    fn from(row: ImportedRow) -> Self {
+        Self {
+            id: row.id,
+            error: row.error,
+            line: Some(row.line),
+        }
+    }
+}
+
+/// Counts and failed rows of a finished import
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportSummary {
+    pub total: u64,
+    pub imported: u64,
+    pub errors: Vec<ImportedRow>,
+}
+
+impl From<Vec<ImportedRow>> for ImportSummary {
+    fn from(rows: Vec<ImportedRow>) -> Self {
+        let total = rows.len() as u64;
+        let errors: Vec<ImportedRow> = rows.into_iter().filter(|row| row.error.is_some()).collect();
+        Self {
+            total,
+            imported: total - errors.len() as u64,
+            errors,
+        }
+    }
+}
+
+/// Creates users from import rows. Each row goes through the same checks as
+/// registration; valid rows are inserted `batch_size` per transaction.
+/// Imported users must change their password after signing in.
+pub struct UserImporter {
+    users: Arc<dyn UserRepository>,
+    password_hasher: Arc<dyn PasswordHasher>,
+    unit_of_work: Arc<dyn UnitOfWork>,
+    password_policy: PasswordPolicy,
+    batch_size: usize,
+}
+
+impl UserImporter {
+    pub fn new(
+        users: Arc<dyn UserRepository>,
+        password_hasher: Arc<dyn PasswordHasher>,
+        unit_of_work: Arc<dyn UnitOfWork>,
+    ) -> Self {
+        Self {
+            users,
+            password_hasher,
+            unit_of_work,
+            password_policy: PasswordPolicy::default(),
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    pub fn with_password_policy(mut self, policy: PasswordPolicy) -> Self {
+        self.password_policy = policy;
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Begin an import; feed it lines, then `finish` it
+    pub fn start(&self, format: ImportFormat) -> ImportRun<'_> {
+        ImportRun {
+            importer: self,
+            parser: RowParser::new(format),
+            line: 0,
+            resume_after: 0,
+            seen: HashSet::new(),
+            batch: Vec::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// The user for `row`, unless it is invalid or taken (`seen` holds the
+    /// lowercased usernames and emails of earlier rows)
+    async fn prepare(&self, row: ImportRow, seen: &mut HashSet<String>) -> Result<User, ApplicationError> {
+        let username = Username::parse(&row.username)?;
+        let email = Email::parse(&row.email)?;
+        let username_key = format!("username:{}", username.to_lowercase());
+        let email_key = format!("email:{}", email);
+        if seen.contains(&email_key) {
+            return Err(DomainError::conflict("Email appears earlier in this import").into());
+        }
+        if seen.contains(&username_key) {
+            return Err(DomainError::conflict("Username appears earlier in this import").into());
+        }
+        self.password_policy.validate(&row.password, &[&username, &email])?;
+        if self.users.find_by_email(&email).await?.is_some() {
+            return Err(DomainError::conflict("Email already registered").into());
+        }
+        if self.users.find_by_username(&username).await?.is_some() {
+            return Err(DomainError::conflict("Username already taken").into());
+        }
+
+        let mut user = User::new(username, email, self.password_hasher.hash(&row.password)?);
+        user.password_reset_required = true;
+        seen.insert(username_key);
+        seen.insert(email_key);
+        Ok(user)
+    }
+}
+
+/// An import in progress
+pub struct ImportRun<'a> {
+    importer: &'a UserImporter,
+    parser: RowParser,
+    line: u64,
+    resume_after: u64,
+    seen: HashSet<String>,
+    /// Valid rows waiting to be inserted, with their lines
+    batch: Vec<(u64, User)>,
+    rows: Vec<ImportedRow>,
+}
+
+impl ImportRun<'_> {
+    /// Read but do not import lines up to `line`, handled by an earlier run
+    pub fn resume_after(mut self, line: u64) -> Self {
+        self.resume_after = line;
+        self
+    }
+
+    /// Outcomes of the rows inserted or rejected so far
+    pub fn rows(&self) -> &[ImportedRow] {
+        &self.rows
+    }
+
+    /// Handle the next input line. Row problems are recorded as failed rows;
+    /// errors are returned for an unusable CSV header and storage failures.
+    pub async fn push_line(&mut self, line: &str) -> Result<(), ApplicationError> {
+        self.line += 1;
+        let line_number = self.line;
+        let parsed = self.parser.parse(line)?;
+        if line_number <= self.resume_after {
+            return Ok(());
+        }
+        let prepared = match parsed {
+            ParsedLine::Skip => return Ok(()),
+            ParsedLine::Invalid(error) => Err(error),
+            ParsedLine::Row(row) => match self.importer.prepare(row, &mut self.seen).await {
+                Ok(user) => Ok(user),
+                Err(
+                    e @ (ApplicationError::Domain(DomainError::Validation(_) | DomainError::Conflict { .. })
+                    | ApplicationError::WeakPassword(_)),
+                ) => Err(e.to_string()),
+                Err(e) => return Err(e),
+            },
+        };
+        match prepared {
+            Ok(user) => {
+                self.batch.push((line_number, user));
+                if self.batch.len() >= self.importer.batch_size {
+                    self.flush().await?;
+                }
+            }
+            Err(error) => self.rows.push(ImportedRow {
+                line: line_number,
+                id: Uuid::new_v4(),
+                error: Some(error),
+            }),
+        }
+        Ok(())
+    }
+
+    /// Insert the remaining rows and return every row's outcome, in input order
+    pub async fn finish(mut self) -> Result<Vec<ImportedRow>, ApplicationError> {
+        self.flush().await?;
+        self.rows.sort_by_key(|row| row.line);
+        Ok(self.rows)
+    }
+
+    /// Insert the batch in one transaction. When an insert fails (a user
+    /// registered meanwhile), the batch is rolled back and its rows are
+    /// inserted one by one, so only the failing rows are lost.
+    async fn flush(&mut self) -> Result<(), ApplicationError> {
+        let batch = std::mem::take(&mut self.batch);
+        if batch.is_empty() {
+            return Ok(());
+        }
+        let users = &self.importer.users;
+        let tx = self.importer.unit_of_work.begin().await?;
+        let inserted = with_transaction(tx.clone(), async {
+            for (_, user) in &batch {
+                users.create(user).await?;
+            }
+            Ok::<_, DomainError>(())
+        })
+        .await;
+
+        match inserted {
+            Ok(()) => {
+                tx.commit().await?;
+                self.rows.extend(batch.into_iter().map(|(line, user)| ImportedRow {
+                    line,
+                    id: user.id,
+                    error: None,
+                }));
+            }
+            Err(_) => {
+                tx.rollback().await?;
+                for (line, user) in batch {
+                    let error = users.create(&user).await.err().map(|e| e.to_string());
+                    self.rows.push(ImportedRow { line, id: user.id, error });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Background Imports
+// ============================================================================
+
+/// Imports users, during the request for small inputs and as an operation
+/// otherwise
+pub struct UserImports {
+    importer: Arc<UserImporter>,
+    operations: Arc<dyn OperationStore>,
+    job_queue: Arc<dyn JobQueue>,
+    storage: Arc<dyn FileStorage>,
+    inline_max_bytes: u64,
+    max_bytes: u64,
+}
+
+impl UserImports {
+    pub fn new(
+        importer: Arc<UserImporter>,
+        operations: Arc<dyn OperationStore>,
+        job_queue: Arc<dyn JobQueue>,
+        storage: Arc<dyn FileStorage>,
+    ) -> Self {
+        Self {
+            importer,
+            operations,
+            job_queue,
+            storage,
+            inline_max_bytes: DEFAULT_INLINE_MAX_BYTES,
+            max_bytes: DEFAULT_MAX_BYTES,
+        }
+    }
+
+    /// Inputs imported during the request, and the largest accepted at all
+    pub fn with_limits(mut self, inline_max_bytes: u64, max_bytes: u64) -> Self {
+        self.inline_max_bytes = inline_max_bytes;
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    /// Whether an input of `length` bytes is imported during the request;
+    /// inputs of unknown length never are
+    pub fn runs_inline(&self, length: Option<u64>) -> bool {
+        length.is_some_and(|length| length <= self.inline_max_bytes)
+    }
+
+    /// Begin an import during the request
+    pub fn start(&self, format: ImportFormat) -> ImportRun<'_> {
+        self.importer.start(format)
+    }
+
+    /// Store `input` and queue its import; the returned operation is pending
+    pub async fn submit(&self, admin_id: Uuid, format: ImportFormat, input: Vec<u8>) -> Result<Operation, ApplicationError> {
+        if input.len() as u64 > self.max_bytes {
+            return Err(DomainError::validation(format!("Imports are limited to {} bytes", self.max_bytes)).into());
+        }
+        let operation = Operation::new(UserImportJob::KIND, admin_id, tenancy::current_tenant());
+        let key = format!("{}{}.{}", IMPORT_PREFIX, operation.id, format.extension());
+        self.storage.put(&key, input, format.content_type()).await?;
+        self.operations.create(&operation).await?;
+        let payload = UserImportPayload {
+            operation_id: operation.id,
+            key,
+            format,
+        };
+        let payload = serde_json::to_value(&payload)
+            .map_err(|e| ApplicationError::use_case(format!("Invalid import payload: {}", e)))?;
+        self.job_queue.enqueue(UserImportJob::KIND, payload, Utc::now()).await?;
+
+        tracing::info!(target: "audit", %admin_id, operation_id = %operation.id, format = format.extension(), "User import queued");
+        Ok(operation)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct UserImportPayload {
+    operation_id: Uuid,
+    key: String,
+    format: ImportFormat,
+}
+
+/// Imports a stored input, recording a result per row on its operation. A
+/// retried run resumes after the last row it recorded.
+pub struct UserImportJob {
+    importer: Arc<UserImporter>,
+    operations: Arc<dyn OperationStore>,
+    storage: Arc<dyn FileStorage>,
+}
+
+impl UserImportJob {
+    pub const KIND: &'static str = "users.import";
+    /// Results are saved after this many rows, so progress shows while it runs
+    const PROGRESS_ROWS: usize = 100;
+
+    pub fn new(importer: Arc<UserImporter>, operations: Arc<dyn OperationStore>, storage: Arc<dyn FileStorage>) -> Self {
+        Self {
+            importer,
+            operations,
+            storage,
+        }
+    }
+
+    async fn process(&self, operation: &mut Operation, key: &str, format: ImportFormat) -> Result<(), ApplicationError> {
+        let Some(file) = self.storage.get(key).await? else {
+            operation.status = OperationStatus::Failed;
+            operation.error = Some("The uploaded input is gone".to_string());
+            return self.operations.update(operation).await;
+        };
+        let Ok(input) = String::from_utf8(file.bytes) else {
+            operation.status = OperationStatus::Failed;
+            operation.error = Some("The input is not UTF-8".to_string());
+            self.operations.update(operation).await?;
+            return self.storage.delete(key).await;
+        };
+
+        let header = usize::from(format == ImportFormat::Csv);
+        let rows = input.lines().filter(|line| !line.trim().is_empty()).count();
+        operation.status = OperationStatus::Running;
+        operation.total = Some(rows.saturating_sub(header) as u64);
+        self.operations.update(operation).await?;
+
+        let done = operation.items.len();
+        let resume_after = operation.items.iter().filter_map(|item| item.line).max().unwrap_or(0);
+        let mut run = self.importer.start(format).resume_after(resume_after);
+        let mut saved = 0;
+        for line in input.lines() {
+            if let Err(e) = run.push_line(line).await {
+                if let ApplicationError::Domain(DomainError::Validation(message)) = e {
+                    operation.status = OperationStatus::Failed;
+                    operation.error = Some(message);
+                    self.operations.update(operation).await?;
+                    return self.storage.delete(key).await;
+                }
+                return Err(e);
+            }
+            if run.rows().len() >= saved + Self::PROGRESS_ROWS {
+                saved = run.rows().len();
+                operation.items.truncate(done);
+                operation.items.extend(run.rows().iter().cloned().map(OperationItem::from));
+                self.operations.update(operation).await?;
+            }
+        }
+
+        let rows = run.finish().await?;
+        operation.items.truncate(done);
+        operation.items.extend(rows.into_iter().map(OperationItem::from));
+        operation.status = OperationStatus::Succeeded;
+        self.operations.update(operation).await?;
+        self.storage.delete(key).await?;
+
+        tracing::info!(
+            target: "audit",
+            admin_id = %operation.created_by,
+            operation_id = %operation.id,
+            total = operation.items.len(),
+            failed = operation.failed_items(),
+            "Users imported"
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Job for UserImportJob {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    async fn run(&self, payload: serde_json::Value) -> Result<(), ApplicationError> {
+        let UserImportPayload {
+            operation_id,
+            key,
+            format,
+        } = serde_json::from_value(payload).map_err(|e| ApplicationError::use_case(format!("Invalid import payload: {}", e)))?;
+        let Some(mut operation) = self.operations.get(operation_id).await? else {
+            return Ok(());
+        };
+        if operation.status.is_finished() {
+            return Ok(());
+        }
+
+        // Users are created in the tenant the import was requested in
+        let tenant = operation.tenant_id;
+        let work = self.process(&mut operation, &key, format);
+        match tenant {
+            Some(tenant) => tenancy::with_tenant(tenant, work).await,
+            None => work.await,
+        }
+    }
+}
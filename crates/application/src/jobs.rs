@@ -0,0 +1,248 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{Page, PaginationParams};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::ApplicationError;
+
+// ============================================================================
+// Job Types
+// ============================================================================
+
+/// Lifecycle of a queued job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Pending,
+    Running,
+    Succeeded,
+    /// Gave up after exhausting its attempts
+    Failed,
+}
+
+impl JobStatus {
+    pub const ALL: [JobStatus; 4] = [Self::Pending, Self::Running, Self::Succeeded, Self::Failed];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|status| status.as_str() == s)
+    }
+}
+
+/// A job as stored in the queue
+#[derive(Debug, Clone, Serialize)]
+pub struct JobRecord {
+    pub id: Uuid,
+    pub kind: String,
+    pub payload: serde_json::Value,
+    pub status: JobStatus,
+    /// Runs started so far, including the current one
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    /// Earliest time the job may (re)run
+    pub run_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Handler for one kind of background job
+#[async_trait]
+pub trait Job: Send + Sync {
+    /// Queue kind this handler processes (e.g. "jobs.prune")
+    fn kind(&self) -> &'static str;
+
+    async fn run(&self, payload: serde_json::Value) -> Result<(), ApplicationError>;
+
+    /// Runs before the job is marked failed
+    fn max_attempts(&self) -> u32 {
+        5
+    }
+}
+
+/// Durable job queue for dependency injection
+#[async_trait]
+pub trait JobQueue: Send + Sync {
+    async fn enqueue(&self, kind: &str, payload: serde_json::Value, run_at: DateTime<Utc>) -> Result<Uuid, ApplicationError>;
+
+    /// Enqueue `kind` unless a pending or running job of that kind exists
+    async fn ensure_scheduled(&self, kind: &str, run_at: DateTime<Utc>) -> Result<(), ApplicationError>;
+
+    /// Take the next due job of one of `kinds` and mark it running.
+    /// Jobs left running since before `stale_before` (crashed worker) are taken again.
+    async fn claim(&self, kinds: &[String], stale_before: DateTime<Utc>) -> Result<Option<JobRecord>, ApplicationError>;
+
+    async fn complete(&self, id: Uuid) -> Result<(), ApplicationError>;
+
+    /// Record a failed run; retried at `retry_at`, or marked failed when `None`
+    async fn fail(&self, id: Uuid, error: &str, retry_at: Option<DateTime<Utc>>) -> Result<(), ApplicationError>;
+
+    async fn list(&self, status: Option<JobStatus>, params: &PaginationParams) -> Result<Page<JobRecord>, ApplicationError>;
+
+    async fn counts(&self) -> Result<HashMap<JobStatus, u64>, ApplicationError>;
+
+    /// Delete finished jobs last updated before `before`
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64, ApplicationError>;
+}
+
+/// Exponential backoff after the `attempt`-th failed run: 5s, 10s, 20s, ... capped at 1h
+pub fn backoff(attempt: u32) -> Duration {
+    let secs = 5u64.saturating_mul(1u64 << attempt.saturating_sub(1).min(20));
+    Duration::from_secs(secs.min(3600))
+}
+
+// ============================================================================
+// Job Runner
+// ============================================================================
+
+/// Polls the queue and dispatches jobs to their registered handlers
+pub struct JobRunner {
+    queue: Arc<dyn JobQueue>,
+    jobs: HashMap<&'static str, Arc<dyn Job>>,
+    recurring: Vec<(&'static str, Duration)>,
+    poll_interval: Duration,
+    lock_timeout: Duration,
+}
+
+impl JobRunner {
+    pub fn new(queue: Arc<dyn JobQueue>) -> Self {
+        Self {
+            queue,
+            jobs: HashMap::new(),
+            recurring: Vec::new(),
+            poll_interval: Duration::from_secs(1),
+            lock_timeout: Duration::from_secs(300),
+        }
+    }
+
+    pub fn register(mut self, job: Arc<dyn Job>) -> Self {
+        self.jobs.insert(job.kind(), job);
+        self
+    }
+
+    /// Register `job` and keep one run of it scheduled every `every`
+    pub fn register_recurring(mut self, job: Arc<dyn Job>, every: Duration) -> Self {
+        self.recurring.push((job.kind(), every));
+        self.register(job)
+    }
+
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Spawn `workers` polling tasks plus one scheduler per recurring job.
+    /// Aborting the returned tasks stops them; a job cut off mid-run is
+    /// claimed again once its lock times out.
+    pub fn spawn(self: Arc<Self>, workers: usize) -> Vec<JoinHandle<()>> {
+        let mut tasks = Vec::with_capacity(workers + self.recurring.len());
+        for _ in 0..workers {
+            let runner = self.clone();
+            tasks.push(tokio::spawn(async move {
+                loop {
+                    match runner.run_once().await {
+                        Ok(true) => {}
+                        Ok(false) => tokio::time::sleep(runner.poll_interval).await,
+                        Err(e) => {
+                            tracing::error!("Job worker error: {}", e);
+                            tokio::time::sleep(runner.poll_interval).await;
+                        }
+                    }
+                }
+            }));
+        }
+
+        for (kind, every) in self.recurring.clone() {
+            let queue = self.queue.clone();
+            tasks.push(tokio::spawn(async move {
+                let mut interval = tokio::time::interval(every);
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = queue.ensure_scheduled(kind, Utc::now()).await {
+                        tracing::error!(kind, "Failed to schedule recurring job: {}", e);
+                    }
+                }
+            }));
+        }
+        tasks
+    }
+
+    /// Run one due job, if any. Returns whether a job was processed.
+    pub async fn run_once(&self) -> Result<bool, ApplicationError> {
+        let kinds: Vec<String> = self.jobs.keys().map(|k| k.to_string()).collect();
+        let stale_before = Utc::now() - chrono::Duration::from_std(self.lock_timeout).unwrap_or_default();
+        let Some(record) = self.queue.claim(&kinds, stale_before).await? else {
+            return Ok(false);
+        };
+        let Some(job) = self.jobs.get(record.kind.as_str()) else {
+            return Ok(false);
+        };
+
+        match job.run(record.payload.clone()).await {
+            Ok(()) => {
+                tracing::debug!(job_id = %record.id, kind = %record.kind, "Job succeeded");
+                self.queue.complete(record.id).await?;
+            }
+            Err(e) => {
+                let retry_at = (record.attempts < job.max_attempts()).then(|| {
+                    Utc::now() + chrono::Duration::from_std(backoff(record.attempts)).unwrap_or_default()
+                });
+                tracing::warn!(
+                    job_id = %record.id,
+                    kind = %record.kind,
+                    attempts = record.attempts,
+                    retrying = retry_at.is_some(),
+                    "Job failed: {}",
+                    e
+                );
+                self.queue.fail(record.id, &e.to_string(), retry_at).await?;
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+// ============================================================================
+// Built-in Jobs
+// ============================================================================
+
+/// Deletes succeeded and failed jobs older than the retention period
+pub struct PruneJobsJob {
+    queue: Arc<dyn JobQueue>,
+    retention: Duration,
+}
+
+impl PruneJobsJob {
+    pub const KIND: &'static str = "jobs.prune";
+
+    pub fn new(queue: Arc<dyn JobQueue>, retention: Duration) -> Self {
+        Self { queue, retention }
+    }
+}
+
+#[async_trait]
+impl Job for PruneJobsJob {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    async fn run(&self, _payload: serde_json::Value) -> Result<(), ApplicationError> {
+        let before = Utc::now() - chrono::Duration::from_std(self.retention).unwrap_or_default();
+        let pruned = self.queue.prune(before).await?;
+        tracing::info!(pruned, "Pruned finished jobs");
+        Ok(())
+    }
+}
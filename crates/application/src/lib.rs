@@ -1,6 +1,64 @@
+pub mod account_deletion;
+pub mod activity;
+pub mod admin;
+pub mod authz;
+pub mod availability;
+pub mod cron;
+pub mod crud;
+pub mod data_browser;
+pub mod data_export;
+pub mod devices;
+pub mod email;
+pub mod email_suppression;
+pub mod event_schema;
+pub mod feature_flags;
+pub mod idempotency;
+pub mod impersonation;
+pub mod imports;
+pub mod jobs;
+pub mod locks;
+pub mod messaging;
+pub mod notes;
+pub mod notifications;
+pub mod oauth;
+pub mod organizations;
+pub mod operations;
+pub mod password_policy;
+pub mod presence;
+pub mod queries;
+pub mod query_stats;
+pub mod rate_limits;
+pub mod refresh_tokens;
+pub mod request_signing;
+pub mod resilience;
+pub mod rollups;
+pub mod secrets;
+pub mod segments;
+pub mod settings;
+pub mod storage;
+pub mod streaming;
+pub mod support;
+pub mod tagging;
+pub mod tenancy;
+pub mod timings;
+pub mod token_versions;
+#[cfg(any(test, feature = "test-util"))]
+pub mod testing;
+pub mod webhooks;
+
 use async_trait::async_trait;
-use domain::{User, UserRepository, DomainError, TokenPair, Claims, PaginationParams, Page};
-use std::sync::Arc;
+use domain::{Email, User, UserFilter, Username, UserRepository, UserStatus, DomainError, DomainEvent, EventEnvelope, Experiment, ExperimentAssignment, FlagContext, TokenPair, Claims, PaginationParams, Page};
+use serde::Serialize;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::activity::{LoginClient, UserActivity};
+use crate::password_policy::{PasswordPolicy, PasswordViolation};
+use crate::refresh_tokens::RefreshTokens;
 
 // ============================================================================
 // Application Errors
@@ -16,6 +74,14 @@ pub enum ApplicationError {
     /// Use case specific errors
     #[error("Use case error: {0}")]
     UseCase(String),
+
+    /// Too many attempts; the caller may retry after the given delay
+    #[error("Too many requests, retry in {}s", retry_after.as_secs().max(1))]
+    RateLimited { retry_after: Duration },
+
+    /// A new password broke the password policy; lists every failed rule
+    #[error("{}", .0.iter().map(|v| v.message.as_str()).collect::<Vec<_>>().join("; "))]
+    WeakPassword(Vec<PasswordViolation>),
 }
 
 impl ApplicationError {
@@ -40,36 +106,315 @@ pub trait PasswordHasher: Send + Sync {
 #[async_trait]
 pub trait TokenService: Send + Sync {
     fn generate(&self, user: &User) -> Result<TokenPair, DomainError>;
+    /// Short-lived token with the `anonymous` role for a guest without an account
+    fn generate_guest(&self, guest_id: Uuid, tenant_id: Uuid) -> Result<TokenPair, DomainError>;
+    /// Short-lived token for `user` recording `actor_id` as the admin acting as them
+    fn generate_impersonation(&self, user: &User, actor_id: Uuid) -> Result<TokenPair, DomainError>;
     fn validate(&self, token: &str) -> Result<Claims, DomainError>;
 }
 
+/// Event pushed to a user's connected real-time clients
+#[derive(Debug, Clone, Serialize)]
+pub struct RealtimeEvent {
+    /// Event name (e.g. "profile.updated")
+    pub event: String,
+    /// Event payload
+    pub data: serde_json::Value,
+}
+
+impl RealtimeEvent {
+    pub fn new(event: impl Into<String>, data: serde_json::Value) -> Self {
+        Self {
+            event: event.into(),
+            data,
+        }
+    }
+}
+
+/// Real-time push channel (WebSocket, SSE, ...) for dependency injection.
+/// Delivery is best-effort: users without live connections simply miss the event.
+pub trait RealtimePublisher: Send + Sync {
+    fn publish(&self, user_id: Uuid, event: RealtimeEvent);
+}
+
+/// Domain event bus for dependency injection.
+///
+/// Subscribers receive events published after they subscribed; `events_since`
+/// replays recent history so clients can resume from their last seen id.
+pub trait EventBus: Send + Sync {
+    fn publish(&self, event: DomainEvent) -> EventEnvelope;
+    fn subscribe(&self) -> tokio::sync::broadcast::Receiver<EventEnvelope>;
+    fn events_since(&self, last_id: u64) -> Vec<EventEnvelope>;
+}
+
+/// Rate limiting for dependency injection.
+///
+/// Keys are `<scope>:<subject>`, e.g. `support:user:<id>` or `support:ip:<addr>`.
+pub trait RateLimiter: Send + Sync {
+    /// Count a hit against `key`. Fails with the time until the next hit is
+    /// allowed once `limit` hits were counted within `window`.
+    fn check(&self, key: &str, limit: u32, window: Duration) -> Result<(), Duration>;
+
+    /// Counters of `subject` (`user:<id>`, `ip:<addr>`, ...) in every scope
+    /// whose window is still open
+    fn counters(&self, subject: &str) -> Vec<RateLimitCounter>;
+
+    /// Drop the counters of `subject` in every scope; returns how many
+    fn reset(&self, subject: &str) -> usize;
+}
+
+/// Hits counted against a rate limit key in its current window
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitCounter {
+    pub key: String,
+    pub count: u32,
+    /// Limit applied by the last check
+    pub limit: u32,
+    /// Until the window closes and the count starts over
+    pub resets_in: Duration,
+}
+
+/// Split a rate limit key into its scope and subject
+pub fn split_rate_limit_key(key: &str) -> Option<(&str, &str)> {
+    key.split_once(':')
+}
+
+/// Feature flag evaluation for dependency injection.
+/// Unknown flags are treated as off.
+#[async_trait]
+pub trait FeatureFlagService: Send + Sync {
+    async fn is_enabled(&self, key: &str, ctx: &FlagContext) -> Result<bool, ApplicationError>;
+    async fn enabled_flags(&self, ctx: &FlagContext) -> Result<Vec<String>, ApplicationError>;
+}
+
+/// Product analytics event, delivered to the configured `AnalyticsSink`
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnalyticsEvent {
+    /// The user was placed in a variant
+    ExperimentAssigned { user_id: Uuid, experiment: String, variant: String },
+    /// The user actually saw the variant
+    ExperimentExposed { user_id: Uuid, experiment: String, variant: String },
+}
+
+/// Analytics sink for dependency injection. Fire-and-forget: tracking must
+/// never fail the request that triggered it.
+pub trait AnalyticsSink: Send + Sync {
+    fn track(&self, event: AnalyticsEvent);
+}
+
+/// Each user's variant per experiment for dependency injection, so
+/// `ExperimentAssigned` is emitted when a user is assigned, not on every read
+#[async_trait]
+pub trait ExperimentAssignmentStore: Send + Sync {
+    /// Remember the user's variant. Returns whether the assignment is new:
+    /// the user had no variant of the experiment yet, or a different one.
+    async fn record(&self, user_id: Uuid, assignment: &ExperimentAssignment) -> Result<bool, ApplicationError>;
+}
+
+// ============================================================================
+// Unit of Work
+// ============================================================================
+
+/// Starts transactions that repositories pick up implicitly.
+///
+/// While a transaction is active on the current task (see `with_transaction`),
+/// every repository call runs inside it instead of on its own connection.
+#[async_trait]
+pub trait UnitOfWork: Send + Sync {
+    async fn begin(&self) -> Result<Arc<dyn Transaction>, DomainError>;
+}
+
+/// An open transaction created by a `UnitOfWork`
+#[async_trait]
+pub trait Transaction: Send + Sync {
+    async fn commit(&self) -> Result<(), DomainError>;
+    async fn rollback(&self) -> Result<(), DomainError>;
+    /// Lets the infrastructure adapter recover its concrete transaction type
+    fn as_any(&self) -> &dyn Any;
+}
+
+tokio::task_local! {
+    static CURRENT_TRANSACTION: Arc<dyn Transaction>;
+}
+
+/// Run `work` with `tx` as the ambient transaction for repository calls
+pub async fn with_transaction<F: Future>(tx: Arc<dyn Transaction>, work: F) -> F::Output {
+    CURRENT_TRANSACTION.scope(tx, work).await
+}
+
+/// The transaction active on the current task, if any
+pub fn current_transaction() -> Option<Arc<dyn Transaction>> {
+    CURRENT_TRANSACTION.try_with(Arc::clone).ok()
+}
+
+// ============================================================================
+// Read-Your-Writes Consistency
+// ============================================================================
+
+/// Replication positions for one request, used to route reads when a replica
+/// is configured. Positions are opaque to this layer (a PostgreSQL LSN in practice).
+#[derive(Debug, Default)]
+pub struct ConsistencySession {
+    required: Mutex<Option<String>>,
+    written: Mutex<Option<String>>,
+}
+
+impl ConsistencySession {
+    /// Start a session whose reads must observe at least `required`
+    pub fn new(required: Option<String>) -> Self {
+        Self {
+            required: Mutex::new(required),
+            written: Mutex::new(None),
+        }
+    }
+
+    /// Position replica reads must have replayed, if any
+    pub fn required_position(&self) -> Option<String> {
+        self.required.lock().unwrap().clone()
+    }
+
+    /// Record the primary's position after a write; later reads in this
+    /// request must observe it too.
+    pub fn record_write(&self, position: String) {
+        *self.required.lock().unwrap() = Some(position.clone());
+        *self.written.lock().unwrap() = Some(position);
+    }
+
+    /// Position of the last write made during this request
+    pub fn written_position(&self) -> Option<String> {
+        self.written.lock().unwrap().clone()
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_CONSISTENCY: Arc<ConsistencySession>;
+}
+
+/// Run `work` with `session` as the read-consistency context for repository calls
+pub async fn with_consistency<F: Future>(session: Arc<ConsistencySession>, work: F) -> F::Output {
+    CURRENT_CONSISTENCY.scope(session, work).await
+}
+
+/// The consistency session active on the current task, if any
+pub fn current_consistency() -> Option<Arc<ConsistencySession>> {
+    CURRENT_CONSISTENCY.try_with(Arc::clone).ok()
+}
+
+/// Remembers each user's last write position for a short window so their
+/// next requests read their own writes even without a consistency token.
+pub struct ConsistencyTracker {
+    ttl: Duration,
+    positions: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl ConsistencyTracker {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            positions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn record(&self, session_key: &str, position: String) {
+        let mut positions = self.positions.lock().unwrap();
+        let now = Instant::now();
+        positions.retain(|_, (_, at)| now.duration_since(*at) < self.ttl);
+        positions.insert(session_key.to_string(), (position, now));
+    }
+
+    pub fn get(&self, session_key: &str) -> Option<String> {
+        let positions = self.positions.lock().unwrap();
+        positions
+            .get(session_key)
+            .filter(|(_, at)| at.elapsed() < self.ttl)
+            .map(|(position, _)| position.clone())
+    }
+}
+
+// ============================================================================
+// Statement Timeout
+// ============================================================================
+
+tokio::task_local! {
+    static STATEMENT_TIMEOUT: Duration;
+}
+
+/// Run `work` with repository statements limited to `timeout` instead of the
+/// pool's `statement_timeout`, e.g. to give one report query more time or a
+/// latency-sensitive lookup less. Zero disables the limit.
+pub async fn with_statement_timeout<F: Future>(timeout: Duration, work: F) -> F::Output {
+    STATEMENT_TIMEOUT.scope(timeout, work).await
+}
+
+/// The statement timeout override active on the current task, if any
+pub fn current_statement_timeout() -> Option<Duration> {
+    STATEMENT_TIMEOUT.try_with(|timeout| *timeout).ok()
+}
+
 // ============================================================================
 // Service Traits (Use Cases)
 // ============================================================================
 
+/// Most users one `UserService::get_users` call looks up
+pub const MAX_BATCH_USERS: usize = 100;
+
 #[async_trait]
 pub trait UserService: Send + Sync {
     async fn get_user(&self, id: uuid::Uuid) -> Result<Option<User>, ApplicationError>;
     async fn list_users(&self, params: &PaginationParams) -> Result<Page<User>, ApplicationError>;
+    /// The users among `ids` that exist, in one query; at most `MAX_BATCH_USERS` ids
+    async fn get_users(&self, ids: &[Uuid]) -> Result<Vec<User>, ApplicationError>;
+    /// Active users whose username starts with `prefix`, best matches first
+    async fn autocomplete(&self, prefix: &str, limit: u32) -> Result<Vec<User>, ApplicationError>;
 }
 
 #[async_trait]
 pub trait AuthService: Send + Sync {
     async fn register(&self, username: String, email: String, password: String) -> Result<User, ApplicationError>;
-    async fn login(&self, email: String, password: String) -> Result<TokenPair, ApplicationError>;
+    /// Sign in; `client` is recorded in the login history when one is kept
+    async fn login(&self, email: String, password: String, client: LoginClient) -> Result<TokenPair, ApplicationError>;
+    /// Exchange a refresh token for a new pair; reusing one revokes its family
+    async fn refresh(&self, refresh_token: String) -> Result<TokenPair, ApplicationError>;
+    /// Replace the password after checking the current one; clears a forced reset
+    async fn change_password(&self, user_id: Uuid, current: String, new: String) -> Result<(), ApplicationError>;
+    /// Token for a new guest of the current tenant
+    async fn guest(&self) -> Result<TokenPair, ApplicationError>;
+    /// Register the guest as a user that keeps the guest's id
+    async fn upgrade_guest(&self, guest_id: Uuid, username: String, email: String, password: String) -> Result<User, ApplicationError>;
+}
+
+#[async_trait]
+pub trait ExperimentService: Send + Sync {
+    /// Every experiment the caller is enrolled in; new assignments are tracked
+    async fn assignments(&self, ctx: &FlagContext) -> Result<Vec<ExperimentAssignment>, ApplicationError>;
+    /// The caller's variant for one experiment, recorded as an exposure
+    async fn expose(&self, key: &str, ctx: &FlagContext) -> Result<Option<ExperimentAssignment>, ApplicationError>;
 }
 
 // ============================================================================
 // Service Implementations
 // ============================================================================
 
+/// How long autocomplete results are reused; pickers fire a query per keystroke
+const AUTOCOMPLETE_TTL: Duration = Duration::from_secs(30);
+/// Cached autocomplete queries before the cache starts over
+const AUTOCOMPLETE_CACHE_ENTRIES: usize = 1024;
+
+/// Tenant, lowercased prefix and limit
+type AutocompleteKey = (Option<Uuid>, String, u32);
+
 pub struct UserServiceImpl {
     repository: Arc<dyn UserRepository>,
+    autocomplete_cache: Mutex<HashMap<AutocompleteKey, (Instant, Vec<User>)>>,
 }
 
 impl UserServiceImpl {
     pub fn new(repository: Arc<dyn UserRepository>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            autocomplete_cache: Mutex::new(HashMap::new()),
+        }
     }
 }
 
@@ -82,6 +427,42 @@ impl UserService for UserServiceImpl {
     async fn list_users(&self, params: &PaginationParams) -> Result<Page<User>, ApplicationError> {
         Ok(self.repository.find_all(params).await?)
     }
+
+    async fn get_users(&self, ids: &[Uuid]) -> Result<Vec<User>, ApplicationError> {
+        let ids: Vec<Uuid> = ids.iter().copied().collect::<HashSet<_>>().into_iter().collect();
+        if ids.len() > MAX_BATCH_USERS {
+            return Err(DomainError::validation(format!("At most {} users per batch", MAX_BATCH_USERS)).into());
+        }
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(self.repository.find_by_ids(&ids).await?)
+    }
+
+    async fn autocomplete(&self, prefix: &str, limit: u32) -> Result<Vec<User>, ApplicationError> {
+        let key = (tenancy::current_tenant(), prefix.to_lowercase(), limit);
+        if let Some((at, users)) = self.autocomplete_cache.lock().unwrap().get(&key) {
+            if at.elapsed() < AUTOCOMPLETE_TTL {
+                return Ok(users.clone());
+            }
+        }
+
+        let filter = UserFilter {
+            status: Some(UserStatus::Active),
+            ..UserFilter::default()
+        };
+        let users = self.repository.autocomplete(prefix, &filter, limit).await?;
+
+        let mut cache = self.autocomplete_cache.lock().unwrap();
+        if cache.len() >= AUTOCOMPLETE_CACHE_ENTRIES {
+            cache.retain(|_, (at, _)| at.elapsed() < AUTOCOMPLETE_TTL);
+            if cache.len() >= AUTOCOMPLETE_CACHE_ENTRIES {
+                cache.clear();
+            }
+        }
+        cache.insert(key, (Instant::now(), users.clone()));
+        Ok(users)
+    }
 }
 
 // ============================================================================
@@ -92,6 +473,10 @@ pub struct AuthServiceImpl {
     repository: Arc<dyn UserRepository>,
     password_hasher: Arc<dyn PasswordHasher>,
     token_service: Arc<dyn TokenService>,
+    event_bus: Arc<dyn EventBus>,
+    password_policy: PasswordPolicy,
+    refresh_tokens: Option<Arc<RefreshTokens>>,
+    activity: Option<Arc<UserActivity>>,
 }
 
 impl AuthServiceImpl {
@@ -99,28 +484,42 @@ impl AuthServiceImpl {
         repository: Arc<dyn UserRepository>,
         password_hasher: Arc<dyn PasswordHasher>,
         token_service: Arc<dyn TokenService>,
+        event_bus: Arc<dyn EventBus>,
     ) -> Self {
         Self {
             repository,
             password_hasher,
             token_service,
+            event_bus,
+            password_policy: PasswordPolicy::default(),
+            refresh_tokens: None,
+            activity: None,
         }
     }
-}
 
-#[async_trait]
-impl AuthService for AuthServiceImpl {
-    async fn register(&self, username: String, email: String, password: String) -> Result<User, ApplicationError> {
+    pub fn with_password_policy(mut self, policy: PasswordPolicy) -> Self {
+        self.password_policy = policy;
+        self
+    }
+
+    /// Issue refresh tokens at login; without them `refresh` always fails
+    pub fn with_refresh_tokens(mut self, refresh_tokens: Arc<RefreshTokens>) -> Self {
+        self.refresh_tokens = Some(refresh_tokens);
+        self
+    }
+
+    /// Record sign-ins in the login history and `last_login_at`
+    pub fn with_activity(mut self, activity: Arc<UserActivity>) -> Self {
+        self.activity = Some(activity);
+        self
+    }
+
+    /// Validate and store a new user, with the given id for upgraded guests
+    async fn create_account(&self, id: Option<Uuid>, username: String, email: String, password: String) -> Result<User, ApplicationError> {
         // Validation
-        if username.is_empty() {
-            return Err(ApplicationError::Domain(DomainError::validation("Username cannot be empty")));
-        }
-        if email.is_empty() {
-            return Err(ApplicationError::Domain(DomainError::validation("Email cannot be empty")));
-        }
-        if password.len() < 8 {
-            return Err(ApplicationError::Domain(DomainError::validation("Password must be at least 8 characters")));
-        }
+        let username = Username::parse(username)?;
+        let email = Email::parse(email)?;
+        self.password_policy.validate(&password, &[&username, &email])?;
 
         // Check if user already exists
         if self.repository.find_by_email(&email).await?.is_some() {
@@ -129,13 +528,32 @@ impl AuthService for AuthServiceImpl {
 
         // Hash password and create user
         let password_hash = self.password_hasher.hash(&password)?;
-        let user = User::new(username, email, password_hash);
-        
-        Ok(self.repository.create(&user).await?)
+        let mut user = User::new(username, email, password_hash);
+        if let Some(id) = id {
+            user.id = id;
+        }
+        let user = self.repository.create(&user).await?;
+
+        self.event_bus.publish(DomainEvent::UserRegistered {
+            user_id: user.id,
+            username: user.username.to_string(),
+            email: user.email.to_string(),
+        });
+
+        Ok(user)
     }
+}
 
-    async fn login(&self, email: String, password: String) -> Result<TokenPair, ApplicationError> {
-        // Find user by email
+#[async_trait]
+impl AuthService for AuthServiceImpl {
+    async fn register(&self, username: String, email: String, password: String) -> Result<User, ApplicationError> {
+        self.create_account(None, username, email, password).await
+    }
+
+    async fn login(&self, email: String, password: String, client: LoginClient) -> Result<TokenPair, ApplicationError> {
+        // Find user by email; no account has an address that does not parse
+        let email = Email::parse(email)
+            .map_err(|_| ApplicationError::Domain(DomainError::unauthorized("Invalid credentials")))?;
         let user = self.repository
             .find_by_email(&email)
             .await?
@@ -146,10 +564,149 @@ impl AuthService for AuthServiceImpl {
         if !valid {
             return Err(ApplicationError::Domain(DomainError::unauthorized("Invalid credentials")));
         }
+        if user.is_suspended() {
+            return Err(ApplicationError::Domain(DomainError::unauthorized("Account suspended")));
+        }
 
         // Generate JWT token
-        let token = self.token_service.generate(&user)?;
+        let mut token = self.token_service.generate(&user)?;
+        if let Some(refresh_tokens) = &self.refresh_tokens {
+            token.refresh_token = Some(refresh_tokens.start_family(user.id, user.token_version).await?);
+        }
+
+        // The sign-in stands even if the history can't be written
+        if let Some(activity) = &self.activity {
+            if let Err(e) = activity.record_login(user.id, &client, chrono::Utc::now()).await {
+                tracing::warn!(user_id = %user.id, "Failed to record sign-in: {}", e);
+            }
+        }
+
+        self.event_bus.publish(DomainEvent::UserLoggedIn { user_id: user.id });
+
         Ok(token)
     }
+
+    async fn refresh(&self, refresh_token: String) -> Result<TokenPair, ApplicationError> {
+        let Some(refresh_tokens) = &self.refresh_tokens else {
+            return Err(DomainError::unauthorized("Refresh tokens are disabled").into());
+        };
+        let (record, next) = refresh_tokens.rotate(&refresh_token).await?;
+
+        let user = self.repository.find_by_id(record.user_id).await?;
+        let Some(user) = user.filter(|user| !user.is_suspended()) else {
+            return Err(DomainError::unauthorized("Account suspended or deleted").into());
+        };
+        // Families started before a logout-all carry an older version
+        if record.token_version != user.token_version {
+            return Err(DomainError::unauthorized("Session has been signed out; sign in again").into());
+        }
+        let mut token = self.token_service.generate(&user)?;
+        token.refresh_token = Some(next);
+        Ok(token)
+    }
+
+    async fn change_password(&self, user_id: Uuid, current: String, new: String) -> Result<(), ApplicationError> {
+        let mut user = self.repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| DomainError::not_found("User", user_id.to_string()))?;
+
+        if !self.password_hasher.verify(&current, &user.password_hash)? {
+            return Err(ApplicationError::Domain(DomainError::unauthorized("Current password is incorrect")));
+        }
+        self.password_policy.validate(&new, &[&user.username, &user.email])?;
+
+        user.password_hash = self.password_hasher.hash(&new)?;
+        user.password_reset_required = false;
+        self.repository.update(&user).await?;
+        Ok(())
+    }
+
+    async fn guest(&self) -> Result<TokenPair, ApplicationError> {
+        let tenant_id = tenancy::current_tenant().unwrap_or(domain::Tenant::DEFAULT_ID);
+        Ok(self.token_service.generate_guest(Uuid::new_v4(), tenant_id)?)
+    }
+
+    async fn upgrade_guest(&self, guest_id: Uuid, username: String, email: String, password: String) -> Result<User, ApplicationError> {
+        if self.repository.find_by_id(guest_id).await?.is_some() {
+            return Err(DomainError::conflict("Guest session was already upgraded").into());
+        }
+        let mut user = self.create_account(Some(guest_id), username, email, password).await?;
+        // Guest tokens carry version 0; the account starts past it
+        user.token_version = self.repository.bump_token_version(user.id).await?;
+        tracing::info!(target: "audit", user_id = %user.id, "Guest upgraded to an account");
+        Ok(user)
+    }
+}
+
+// ============================================================================
+// Experiment Service Implementation
+// ============================================================================
+
+pub struct ExperimentServiceImpl {
+    experiments: Vec<Experiment>,
+    analytics: Arc<dyn AnalyticsSink>,
+    assignments: Arc<dyn ExperimentAssignmentStore>,
+}
+
+impl ExperimentServiceImpl {
+    pub fn new(
+        experiments: Vec<Experiment>,
+        analytics: Arc<dyn AnalyticsSink>,
+        assignments: Arc<dyn ExperimentAssignmentStore>,
+    ) -> Self {
+        Self {
+            experiments,
+            analytics,
+            assignments,
+        }
+    }
+
+    /// Place the caller in a variant of `experiment`, tracking the
+    /// assignment the first time it is made
+    async fn assign(
+        &self,
+        experiment: &Experiment,
+        ctx: &FlagContext,
+    ) -> Result<Option<ExperimentAssignment>, ApplicationError> {
+        let (Some(assignment), Some(user_id)) = (experiment.assign(ctx), ctx.user_id) else {
+            return Ok(None);
+        };
+        if self.assignments.record(user_id, &assignment).await? {
+            self.analytics.track(AnalyticsEvent::ExperimentAssigned {
+                user_id,
+                experiment: assignment.experiment.clone(),
+                variant: assignment.variant.clone(),
+            });
+        }
+        Ok(Some(assignment))
+    }
 }
 
+#[async_trait]
+impl ExperimentService for ExperimentServiceImpl {
+    async fn assignments(&self, ctx: &FlagContext) -> Result<Vec<ExperimentAssignment>, ApplicationError> {
+        let mut assignments = Vec::new();
+        for experiment in &self.experiments {
+            assignments.extend(self.assign(experiment, ctx).await?);
+        }
+        Ok(assignments)
+    }
+
+    async fn expose(&self, key: &str, ctx: &FlagContext) -> Result<Option<ExperimentAssignment>, ApplicationError> {
+        let assignment = match self.experiments.iter().find(|e| e.key == key) {
+            Some(experiment) => self.assign(experiment, ctx).await?,
+            None => None,
+        };
+
+        if let (Some(a), Some(user_id)) = (&assignment, ctx.user_id) {
+            self.analytics.track(AnalyticsEvent::ExperimentExposed {
+                user_id,
+                experiment: a.experiment.clone(),
+                variant: a.variant.clone(),
+            });
+        }
+
+        Ok(assignment)
+    }
+}
@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use domain::DomainError;
+use futures_util::future::{select, Either};
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::ApplicationError;
+
+// ============================================================================
+// Ports
+// ============================================================================
+
+/// A held lock; dropping it releases the lock
+pub struct LockGuard {
+    _release: Box<dyn Send + Sync>,
+    lost: Option<watch::Receiver<bool>>,
+}
+
+impl LockGuard {
+    /// Guard whose `release` frees the lock when dropped
+    pub fn new(release: impl Send + Sync + 'static) -> Self {
+        Self {
+            _release: Box::new(release),
+            lost: None,
+        }
+    }
+
+    /// Also report the lock lost once `lost` turns true, e.g. when a lease
+    /// could not be renewed before it expired
+    pub fn with_lost_signal(mut self, lost: watch::Receiver<bool>) -> Self {
+        self.lost = Some(lost);
+        self
+    }
+
+    /// Whether the lock was taken away while held
+    pub fn is_lost(&self) -> bool {
+        self.lost.as_ref().is_some_and(|lost| *lost.borrow())
+    }
+
+    /// Resolve once the lock is lost; never for locks that cannot be
+    pub async fn lost(&mut self) {
+        match self.lost.as_mut() {
+            Some(lost) => {
+                // A closed channel means the renewal stopped without losing it
+                if lost.wait_for(|lost| *lost).await.is_err() {
+                    std::future::pending::<()>().await;
+                }
+            }
+            None => std::future::pending().await,
+        }
+    }
+}
+
+/// Cluster-wide named locks for dependency injection, so singleton work
+/// runs on one replica at a time
+#[async_trait]
+pub trait DistributedLock: Send + Sync {
+    /// Take the lock named `name`, or `None` while someone else holds it.
+    /// `ttl` bounds how long it outlives a holder that died without
+    /// releasing it; backends that free it with the holder may ignore it.
+    async fn try_acquire(&self, name: &str, ttl: Duration) -> Result<Option<LockGuard>, ApplicationError>;
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Run `fut` under the lock named `name`. Returns `None` without running it
+/// while another instance holds the lock. If the lock is lost midway, `fut`
+/// is dropped and an unavailable error returned, so the work never carries
+/// on next to a new holder.
+pub async fn with_lock<F, T>(
+    lock: &dyn DistributedLock,
+    name: &str,
+    ttl: Duration,
+    fut: F,
+) -> Result<Option<T>, ApplicationError>
+where
+    F: Future<Output = T>,
+{
+    let Some(mut guard) = lock.try_acquire(name, ttl).await? else {
+        tracing::debug!(lock = name, "Lock held elsewhere; skipped");
+        return Ok(None);
+    };
+
+    let lost = guard.lost();
+    tokio::pin!(fut, lost);
+    match select(fut, lost).await {
+        Either::Left((output, _)) => Ok(Some(output)),
+        Either::Right(_) => {
+            tracing::warn!(lock = name, "Lock lost; work cancelled");
+            Err(DomainError::unavailable(format!("Lock '{}' was lost", name)).into())
+        }
+    }
+}
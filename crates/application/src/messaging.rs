@@ -0,0 +1,197 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{DomainError, EventEnvelope};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+use crate::event_schema::VersionedEvent;
+use crate::{ApplicationError, EventBus};
+
+/// Domain events are published as `events.<name>`, e.g. `events.user.registered`
+pub const EVENT_TOPIC_PREFIX: &str = "events.";
+
+// ============================================================================
+// Envelope
+// ============================================================================
+
+/// What travels through the broker: a JSON payload with the metadata
+/// consumers need to route and deduplicate it. Encoded as JSON as a whole,
+/// so any broker and language can read it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageEnvelope {
+    /// Unique per message; redelivered messages keep it
+    pub id: Uuid,
+    /// What the payload is, e.g. `user.registered`
+    pub kind: String,
+    pub created_at: DateTime<Utc>,
+    pub payload: serde_json::Value,
+}
+
+impl MessageEnvelope {
+    pub fn new(kind: impl Into<String>, payload: &impl Serialize) -> Result<Self, ApplicationError> {
+        let payload = serde_json::to_value(payload)
+            .map_err(|e| DomainError::internal(format!("Failed to serialize message: {}", e)))?;
+        Ok(Self {
+            id: Uuid::new_v4(),
+            kind: kind.into(),
+            created_at: Utc::now(),
+            payload,
+        })
+    }
+
+    /// The payload as `T`
+    pub fn payload_as<T: DeserializeOwned>(&self) -> Result<T, ApplicationError> {
+        serde_json::from_value(self.payload.clone())
+            .map_err(|e| DomainError::validation(format!("Unexpected {} payload: {}", self.kind, e)).into())
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        // A Value payload and plain fields always serialize
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, ApplicationError> {
+        serde_json::from_slice(bytes).map_err(|e| DomainError::validation(format!("Malformed message: {}", e)).into())
+    }
+}
+
+// ============================================================================
+// Ports
+// ============================================================================
+
+/// Sends messages to a broker (RabbitMQ, Kafka, ...) for dependency injection
+#[async_trait]
+pub trait MessagePublisher: Send + Sync {
+    /// Publish `message` on `topic`, returning once the broker accepted it.
+    /// `key` keeps related messages in order where the broker partitions
+    /// (Kafka); brokers without partitions ignore it.
+    async fn publish(&self, topic: &str, key: Option<&str>, message: &MessageEnvelope) -> Result<(), ApplicationError>;
+}
+
+/// Handles the messages of one subscription
+#[async_trait]
+pub trait MessageHandler: Send + Sync {
+    async fn handle(&self, message: MessageEnvelope) -> Result<(), ApplicationError>;
+}
+
+/// Receives messages from a broker for dependency injection. Subscriptions
+/// and the topics they receive are part of the broker's configured topology.
+#[async_trait]
+pub trait MessageConsumer: Send + Sync {
+    /// Feed the messages of `subscription` to `handler`, acknowledging each
+    /// one it handled. Returns when the connection ends, and fails when the
+    /// handler does; the broker then redelivers the failed message to the
+    /// next consumer of the subscription. Undecodable messages are logged
+    /// and dropped.
+    async fn consume(&self, subscription: &str, handler: Arc<dyn MessageHandler>) -> Result<(), ApplicationError>;
+}
+
+/// Whether `topic` matches `pattern`, AMQP style: words are separated by
+/// dots, `*` stands for exactly one word and `#` for zero or more
+pub fn topic_matches(pattern: &str, topic: &str) -> bool {
+    fn matches(pattern: &[&str], topic: &[&str]) -> bool {
+        match (pattern.split_first(), topic.split_first()) {
+            (None, None) => true,
+            (Some((&"#", rest)), _) => matches(rest, topic) || (!topic.is_empty() && matches(pattern, &topic[1..])),
+            (Some((&word, rest)), Some((&first, remaining))) => (word == "*" || word == first) && matches(rest, remaining),
+            _ => false,
+        }
+    }
+    let pattern: Vec<&str> = pattern.split('.').collect();
+    let topic: Vec<&str> = topic.split('.').collect();
+    matches(&pattern, &topic)
+}
+
+// ============================================================================
+// Consumer Supervision
+// ============================================================================
+
+/// How long a stopped consumer waits before it is restarted: `initial`,
+/// doubling after each consecutive failure up to `max`
+#[derive(Debug, Clone, Copy)]
+pub struct RestartBackoff {
+    pub initial: Duration,
+    pub max: Duration,
+}
+
+impl Default for RestartBackoff {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(1),
+            max: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Run `subscription` on `consumer` until the task is aborted, restarting it
+/// whenever it stops: after a handler failure (so the message is retried),
+/// a lost connection or a broker restart. A consumer that ran for longer
+/// than `backoff.max` starts over from the initial delay.
+pub fn spawn_consumer(
+    consumer: Arc<dyn MessageConsumer>,
+    subscription: String,
+    handler: Arc<dyn MessageHandler>,
+    backoff: RestartBackoff,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut delay = backoff.initial;
+        loop {
+            let started = Instant::now();
+            match consumer.consume(&subscription, handler.clone()).await {
+                Ok(()) => tracing::warn!(subscription, "Message consumer stopped; restarting"),
+                Err(e) => tracing::error!(subscription, "Message consumer failed: {}; restarting", e),
+            }
+            if started.elapsed() > backoff.max {
+                delay = backoff.initial;
+            }
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(backoff.max);
+        }
+    })
+}
+
+// ============================================================================
+// Domain Event Relay
+// ============================================================================
+
+/// The broker message for a published domain event: its topic, key (the
+/// user it concerns) and envelope, whose payload is the `VersionedEvent`
+/// webhooks receive too
+pub fn event_message(envelope: &EventEnvelope) -> Result<(String, Option<String>, MessageEnvelope), ApplicationError> {
+    let name = envelope.event.name();
+    let message = MessageEnvelope::new(name, &VersionedEvent::new(envelope)?)?;
+    let key = envelope.event.user_id().map(|id| id.to_string());
+    Ok((format!("{}{}", EVENT_TOPIC_PREFIX, name), key, message))
+}
+
+/// Publish every domain event to the broker. Best-effort: events published
+/// while the broker is unreachable are logged and lost.
+pub fn spawn_event_relay(event_bus: Arc<dyn EventBus>, publisher: Arc<dyn MessagePublisher>) {
+    let mut events = event_bus.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let envelope = match events.recv().await {
+                Ok(envelope) => envelope,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Event relay lagged behind the event bus");
+                    continue;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
+
+            let event = envelope.event.name();
+            let published = async {
+                let (topic, key, message) = event_message(&envelope)?;
+                publisher.publish(&topic, key.as_deref(), &message).await
+            };
+            if let Err(e) = published.await {
+                tracing::error!(event, event_id = envelope.id, "Failed to publish event to the broker: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,99 @@
+use domain::{DomainError, NoteVisibility, Page, PaginationParams, UserNote, UserNoteRepository, UserRepository};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::ApplicationError;
+
+// ============================================================================
+// Admin Notes on Users
+// ============================================================================
+
+/// Longest note body, in characters
+pub const MAX_NOTE_LEN: usize = 10_000;
+
+/// Internal notes admins keep on users. `admin_id` is the acting admin:
+/// private notes of other admins behave as if they did not exist, and only
+/// the author may change or delete a note. Users must be visible (i.e. in
+/// the current tenant).
+pub struct UserNoteService {
+    notes: Arc<dyn UserNoteRepository>,
+    users: Arc<dyn UserRepository>,
+}
+
+impl UserNoteService {
+    pub fn new(notes: Arc<dyn UserNoteRepository>, users: Arc<dyn UserRepository>) -> Self {
+        Self { notes, users }
+    }
+
+    pub async fn list(&self, admin_id: Uuid, user_id: Uuid, params: &PaginationParams) -> Result<Page<UserNote>, ApplicationError> {
+        self.ensure_user(user_id).await?;
+        Ok(self.notes.find_for_user(user_id, admin_id, params).await?)
+    }
+
+    pub async fn create(
+        &self,
+        admin_id: Uuid,
+        user_id: Uuid,
+        body: String,
+        visibility: NoteVisibility,
+    ) -> Result<UserNote, ApplicationError> {
+        self.ensure_user(user_id).await?;
+        let note = UserNote::new(user_id, admin_id, validate_body(body)?, visibility);
+        Ok(self.notes.create(&note).await?)
+    }
+
+    /// Replace the body and, when given, the visibility
+    pub async fn update(
+        &self,
+        admin_id: Uuid,
+        user_id: Uuid,
+        note_id: Uuid,
+        body: String,
+        visibility: Option<NoteVisibility>,
+    ) -> Result<UserNote, ApplicationError> {
+        let mut note = self.authored_note(admin_id, user_id, note_id).await?;
+        note.body = validate_body(body)?;
+        if let Some(visibility) = visibility {
+            note.visibility = visibility;
+        }
+        Ok(self.notes.update(&note).await?)
+    }
+
+    pub async fn delete(&self, admin_id: Uuid, user_id: Uuid, note_id: Uuid) -> Result<(), ApplicationError> {
+        self.authored_note(admin_id, user_id, note_id).await?;
+        if !self.notes.delete(note_id).await? {
+            return Err(DomainError::not_found("UserNote", note_id.to_string()).into());
+        }
+        Ok(())
+    }
+
+    async fn ensure_user(&self, user_id: Uuid) -> Result<(), ApplicationError> {
+        if self.users.find_by_id(user_id).await?.is_none() {
+            return Err(DomainError::not_found("User", user_id.to_string()).into());
+        }
+        Ok(())
+    }
+
+    /// The note `note_id` on `user_id`, which `admin_id` wrote
+    async fn authored_note(&self, admin_id: Uuid, user_id: Uuid, note_id: Uuid) -> Result<UserNote, ApplicationError> {
+        self.ensure_user(user_id).await?;
+        let note = self
+            .notes
+            .find_by_id(note_id)
+            .await?
+            .filter(|note| note.user_id == user_id && note.is_visible_to(admin_id))
+            .ok_or_else(|| DomainError::not_found("UserNote", note_id.to_string()))?;
+        if note.author_id != admin_id {
+            return Err(DomainError::validation("Only the author can change or delete a note").into());
+        }
+        Ok(note)
+    }
+}
+
+fn validate_body(body: String) -> Result<String, ApplicationError> {
+    let body = body.trim().to_string();
+    if body.is_empty() || body.chars().count() > MAX_NOTE_LEN {
+        return Err(DomainError::validation(format!("Note must be 1-{} characters", MAX_NOTE_LEN)).into());
+    }
+    Ok(body)
+}
@@ -0,0 +1,111 @@
+use domain::{DomainError, DomainEvent, Notification, NotificationRepository, Page, PaginationParams};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{ApplicationError, EventBus};
+
+// ============================================================================
+// In-app Notifications
+// ============================================================================
+
+/// One page of a user's notifications with their unread total, for badges
+#[derive(Debug, Clone)]
+pub struct Inbox {
+    pub page: Page<Notification>,
+    pub unread: u64,
+}
+
+/// Users' in-app notifications. Each one stored is also published as a
+/// `notification.created` event, which reaches the user's WebSocket and SSE
+/// clients like any other event of theirs.
+pub struct NotificationService {
+    notifications: Arc<dyn NotificationRepository>,
+    event_bus: Arc<dyn EventBus>,
+}
+
+impl NotificationService {
+    pub fn new(notifications: Arc<dyn NotificationRepository>, event_bus: Arc<dyn EventBus>) -> Self {
+        Self { notifications, event_bus }
+    }
+
+    /// Store a notification for `user_id` and announce it
+    pub async fn notify(&self, user_id: Uuid, kind: &str, title: String, body: String) -> Result<Notification, ApplicationError> {
+        let notification = self.notifications.create(&Notification::new(user_id, kind, title, body)).await?;
+        self.event_bus.publish(DomainEvent::NotificationCreated {
+            user_id,
+            notification_id: notification.id,
+            kind: notification.kind.clone(),
+            title: notification.title.clone(),
+        });
+        Ok(notification)
+    }
+
+    /// The user's notifications, newest first, and how many are unread overall
+    pub async fn inbox(&self, user_id: Uuid, unread_only: bool, params: &PaginationParams) -> Result<Inbox, ApplicationError> {
+        let page = self.notifications.list_for_user(user_id, unread_only, params).await?;
+        let unread = self.notifications.unread_count(user_id).await?;
+        Ok(Inbox { page, unread })
+    }
+
+    /// Mark one of the user's notifications read; reading it again changes nothing
+    pub async fn mark_read(&self, user_id: Uuid, id: Uuid) -> Result<Notification, ApplicationError> {
+        self.notifications
+            .mark_read(user_id, id, chrono::Utc::now())
+            .await?
+            .ok_or_else(|| DomainError::not_found("Notification", id.to_string()).into())
+    }
+
+    /// Notify the user `event` concerns, if it is one users hear about
+    pub async fn handle(&self, event: &DomainEvent) -> Result<Option<Notification>, ApplicationError> {
+        let Some((user_id, kind, title, body)) = notification_for(event) else {
+            return Ok(None);
+        };
+        self.notify(user_id, kind, title, body).await.map(Some)
+    }
+}
+
+/// What to tell a user about `event`: their id, the kind, title and body.
+/// Sign-ins are left out (the user just did it), and so are events that
+/// concern no user.
+pub fn notification_for(event: &DomainEvent) -> Option<(Uuid, &'static str, String, String)> {
+    match event {
+        DomainEvent::UserRegistered { user_id, username, .. } => Some((
+            *user_id,
+            "welcome",
+            format!("Welcome, {}!", username),
+            "Your account is ready. Notifications about it will show up here.".to_string(),
+        )),
+        DomainEvent::FileQuarantined { user_id, purpose, .. } => Some((
+            *user_id,
+            "file_quarantined",
+            "Upload blocked".to_string(),
+            format!("Your {} upload failed the virus scan and was not saved.", purpose),
+        )),
+        DomainEvent::UserLoggedIn { .. }
+        | DomainEvent::NotificationCreated { .. }
+        | DomainEvent::ServiceStarted { .. }
+        | DomainEvent::MigrationsApplied { .. }
+        | DomainEvent::ShuttingDown { .. } => None,
+    }
+}
+
+/// Turn the domain events users hear about into notifications.
+pub fn spawn_event_notifications(event_bus: Arc<dyn EventBus>, notifications: Arc<NotificationService>) {
+    let mut events = event_bus.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let envelope = match events.recv().await {
+                Ok(envelope) => envelope,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Notifier lagged behind the event bus");
+                    continue;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
+
+            if let Err(e) = notifications.handle(&envelope.event).await {
+                tracing::error!(event = envelope.event.name(), "Failed to store notification: {}", e);
+            }
+        }
+    });
+}
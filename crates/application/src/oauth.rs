@@ -0,0 +1,145 @@
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::Utc;
+use domain::DomainError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::time::Duration;
+
+use crate::secrets::{constant_time_eq, random_token};
+use crate::ApplicationError;
+
+/// How long a user has to come back from the provider
+pub const OAUTH_STATE_TTL: Duration = Duration::from_secs(600);
+
+// ============================================================================
+// OAuth State Store Port
+// ============================================================================
+
+/// What the authorization redirect committed to, kept until the callback
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingAuthorization {
+    pub provider: String,
+    /// Sent again with the token request
+    pub redirect_uri: String,
+    /// PKCE secret; only its hash left with the redirect
+    pub code_verifier: String,
+    /// Expected in the OIDC id_token
+    pub nonce: String,
+    /// Unix timestamp after which the callback is refused
+    pub expires_at: i64,
+}
+
+impl PendingAuthorization {
+    /// Check the `nonce` claim of the (already signature-checked) id_token
+    /// against the one sent with the authorization request
+    pub fn verify_nonce(&self, id_token_nonce: Option<&str>) -> Result<(), ApplicationError> {
+        match id_token_nonce {
+            Some(nonce) if constant_time_eq(nonce.as_bytes(), self.nonce.as_bytes()) => Ok(()),
+            _ => Err(DomainError::unauthorized("id_token nonce mismatch").into()),
+        }
+    }
+}
+
+/// Pending authorizations keyed by their `state`. Each one can be taken
+/// once; taking it again, or after `ttl`, yields `None`.
+#[async_trait]
+pub trait OAuthStateStore: Send + Sync {
+    async fn save(&self, state: &str, pending: &PendingAuthorization, ttl: Duration) -> Result<(), ApplicationError>;
+
+    /// Remove and return the authorization saved under `state`
+    async fn take(&self, state: &str) -> Result<Option<PendingAuthorization>, ApplicationError>;
+}
+
+// ============================================================================
+// OAuth Flow
+// ============================================================================
+
+/// Parameters to add to the provider's authorization URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorizationRequest {
+    pub state: String,
+    pub nonce: String,
+    pub code_challenge: String,
+    /// Always `S256`
+    pub code_challenge_method: &'static str,
+    pub redirect_uri: String,
+}
+
+/// Authorization code flow bookkeeping: random state, PKCE and nonce per
+/// attempt, redirect URIs restricted to an allowlist, and one-time,
+/// expiring callbacks. Token exchange and id_token signature checks are
+/// up to the provider client.
+pub struct OAuthFlow {
+    /// Exact matches only, as the OAuth security BCP requires
+    redirect_uris: HashSet<String>,
+    ttl: Duration,
+}
+
+impl OAuthFlow {
+    pub fn new<S: Into<String>>(redirect_uris: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            redirect_uris: redirect_uris.into_iter().map(Into::into).collect(),
+            ttl: OAUTH_STATE_TTL,
+        }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn is_allowed_redirect(&self, redirect_uri: &str) -> bool {
+        self.redirect_uris.contains(redirect_uri)
+    }
+
+    /// Start an authorization attempt and remember it in `store`
+    pub async fn begin(
+        &self,
+        store: &dyn OAuthStateStore,
+        provider: &str,
+        redirect_uri: &str,
+    ) -> Result<AuthorizationRequest, ApplicationError> {
+        if !self.is_allowed_redirect(redirect_uri) {
+            return Err(DomainError::validation("redirect_uri is not allowed").into());
+        }
+        let state = random_token();
+        let pending = PendingAuthorization {
+            provider: provider.to_string(),
+            redirect_uri: redirect_uri.to_string(),
+            code_verifier: random_token(),
+            nonce: random_token(),
+            expires_at: Utc::now().timestamp() + self.ttl.as_secs() as i64,
+        };
+        store.save(&state, &pending, self.ttl).await?;
+
+        Ok(AuthorizationRequest {
+            state,
+            nonce: pending.nonce,
+            code_challenge: pkce_challenge(&pending.code_verifier),
+            code_challenge_method: "S256",
+            redirect_uri: pending.redirect_uri,
+        })
+    }
+
+    /// Consume the attempt `state` belongs to. Fails for unknown, replayed
+    /// or expired states.
+    pub async fn complete(&self, store: &dyn OAuthStateStore, state: &str) -> Result<PendingAuthorization, ApplicationError> {
+        let invalid = || ApplicationError::from(DomainError::unauthorized("Invalid or expired OAuth state"));
+        if state.is_empty() {
+            return Err(invalid());
+        }
+        let pending = store.take(state).await?.ok_or_else(invalid)?;
+        if pending.expires_at <= Utc::now().timestamp() || !self.is_allowed_redirect(&pending.redirect_uri) {
+            return Err(invalid());
+        }
+        Ok(pending)
+    }
+}
+
+/// S256 code challenge for a PKCE verifier (RFC 7636)
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
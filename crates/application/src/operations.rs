@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::ApplicationError;
+
+// ============================================================================
+// Operation Types
+// ============================================================================
+
+/// Lifecycle of a long-running operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationStatus {
+    Pending,
+    Running,
+    /// Every item was processed; some may have failed individually
+    Succeeded,
+    /// Stopped before processing its items, see `error`
+    Failed,
+}
+
+impl OperationStatus {
+    pub const ALL: [OperationStatus; 4] = [Self::Pending, Self::Running, Self::Succeeded, Self::Failed];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|status| status.as_str() == s)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed)
+    }
+}
+
+/// Outcome for one item of an operation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationItem {
+    pub id: Uuid,
+    /// Why this item failed; `None` when it succeeded
+    pub error: Option<String>,
+    /// Input line the item was read from, for imports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u64>,
+}
+
+/// Work accepted by the API and carried out by a background job. Callers
+/// poll it for progress and per-item results.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Operation {
+    pub id: Uuid,
+    /// What the operation does, e.g. `users.bulk.suspend`
+    pub kind: String,
+    pub status: OperationStatus,
+    /// The admin who started it
+    pub created_by: Uuid,
+    /// Tenant it was started in; only visible there
+    pub tenant_id: Option<Uuid>,
+    /// Items to process, known once the job has resolved its targets
+    pub total: Option<u64>,
+    /// Items processed so far, in processing order
+    pub items: Vec<OperationItem>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Operation {
+    pub fn new(kind: impl Into<String>, created_by: Uuid, tenant_id: Option<Uuid>) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            kind: kind.into(),
+            status: OperationStatus::Pending,
+            created_by,
+            tenant_id,
+            total: None,
+            items: Vec::new(),
+            error: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn failed_items(&self) -> usize {
+        self.items.iter().filter(|item| item.error.is_some()).count()
+    }
+}
+
+/// Storage of operations for dependency injection
+#[async_trait]
+pub trait OperationStore: Send + Sync {
+    async fn create(&self, operation: &Operation) -> Result<(), ApplicationError>;
+
+    async fn get(&self, id: Uuid) -> Result<Option<Operation>, ApplicationError>;
+
+    /// Save status, total, items and error
+    async fn update(&self, operation: &Operation) -> Result<(), ApplicationError>;
+}
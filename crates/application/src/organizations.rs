@@ -0,0 +1,384 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{
+    DomainError, Membership, MembershipRepository, OrgRole, Organization, OrganizationRepository, Page,
+    PaginationParams, UserRepository,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::email::{EmailJobPayload, EmailTemplate, SendEmailJob};
+use crate::jobs::JobQueue;
+use crate::secrets::{hash_token, random_token};
+use crate::ApplicationError;
+
+/// How long an emailed invitation can be accepted
+pub const DEFAULT_INVITATION_TTL: Duration = Duration::from_secs(7 * 86_400);
+/// Where invitation links point; the token is appended
+pub const DEFAULT_INVITATION_URL: &str = "/api/v1/orgs/invitations";
+
+// ============================================================================
+// Invitations
+// ============================================================================
+
+/// Invitation to join an organization, sent by email. Only the hash of the
+/// token in the link is stored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invitation {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    /// Lowercased; only the user with this email may accept
+    pub email: String,
+    /// Role the invitee gets on accepting
+    pub role: OrgRole,
+    /// SHA-256 of the token, base64url
+    pub token_hash: String,
+    pub invited_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+}
+
+impl Invitation {
+    pub fn is_pending(&self, now: DateTime<Utc>) -> bool {
+        self.accepted_at.is_none() && self.expires_at > now
+    }
+}
+
+/// Invitation storage for dependency injection
+#[async_trait]
+pub trait InvitationStore: Send + Sync {
+    async fn create(&self, invitation: &Invitation) -> Result<(), ApplicationError>;
+
+    async fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<Invitation>, ApplicationError>;
+
+    /// Invitations to the organization that are neither accepted nor expired
+    /// at `now`, newest first
+    async fn list_pending(&self, organization_id: Uuid, now: DateTime<Utc>) -> Result<Vec<Invitation>, ApplicationError>;
+
+    /// Mark the invitation accepted unless it already was or expired before
+    /// `now`; of two concurrent accepts only one succeeds
+    async fn claim(&self, id: Uuid, now: DateTime<Utc>) -> Result<bool, ApplicationError>;
+
+    async fn delete(&self, organization_id: Uuid, id: Uuid) -> Result<bool, ApplicationError>;
+}
+
+// ============================================================================
+// Organization Service
+// ============================================================================
+
+/// Organizations, their members and invitations. `actor_id` is the calling
+/// user: organizations they do not belong to behave as if they did not
+/// exist, and what they may change follows their `OrgRole`.
+pub struct OrganizationService {
+    organizations: Arc<dyn OrganizationRepository>,
+    members: Arc<dyn MembershipRepository>,
+    invitations: Arc<dyn InvitationStore>,
+    users: Arc<dyn UserRepository>,
+    job_queue: Arc<dyn JobQueue>,
+    invitation_ttl: Duration,
+    invitation_url: String,
+}
+
+impl OrganizationService {
+    pub fn new(
+        organizations: Arc<dyn OrganizationRepository>,
+        members: Arc<dyn MembershipRepository>,
+        invitations: Arc<dyn InvitationStore>,
+        users: Arc<dyn UserRepository>,
+        job_queue: Arc<dyn JobQueue>,
+    ) -> Self {
+        Self {
+            organizations,
+            members,
+            invitations,
+            users,
+            job_queue,
+            invitation_ttl: DEFAULT_INVITATION_TTL,
+            invitation_url: DEFAULT_INVITATION_URL.to_string(),
+        }
+    }
+
+    pub fn with_invitation_ttl(mut self, invitation_ttl: Duration) -> Self {
+        self.invitation_ttl = invitation_ttl;
+        self
+    }
+
+    /// Base of the emailed links, e.g. `https://app.example.com/invitations`
+    pub fn with_invitation_url(mut self, invitation_url: impl Into<String>) -> Self {
+        self.invitation_url = invitation_url.into();
+        self
+    }
+
+    /// Create an organization with `actor_id` as its owner
+    pub async fn create(&self, actor_id: Uuid, name: String) -> Result<Organization, ApplicationError> {
+        let organization = Organization::new(validate_name(name)?, actor_id);
+        let owner = Membership::new(organization.id, actor_id, OrgRole::Owner);
+        let organization = self.organizations.create_with_owner(&organization, &owner).await?;
+        tracing::info!(target: "audit", %actor_id, organization_id = %organization.id, "Organization created");
+        Ok(organization)
+    }
+
+    /// The actor's organizations with their role in each
+    pub async fn list(&self, actor_id: Uuid) -> Result<Vec<(Organization, OrgRole)>, ApplicationError> {
+        Ok(self.organizations.list_for_user(actor_id).await?)
+    }
+
+    /// The organization and the actor's role in it
+    pub async fn get(&self, actor_id: Uuid, organization_id: Uuid) -> Result<(Organization, OrgRole), ApplicationError> {
+        let role = self.role(actor_id, organization_id).await?;
+        let organization = self
+            .organizations
+            .find_by_id(organization_id)
+            .await?
+            .ok_or_else(|| not_found(organization_id))?;
+        Ok((organization, role))
+    }
+
+    /// Rename the organization (admins and owners)
+    pub async fn rename(&self, actor_id: Uuid, organization_id: Uuid, name: String) -> Result<Organization, ApplicationError> {
+        let (mut organization, role) = self.get(actor_id, organization_id).await?;
+        require(role >= OrgRole::Admin, "Only admins and owners can rename the organization")?;
+        organization.name = validate_name(name)?;
+        Ok(self.organizations.update(&organization).await?)
+    }
+
+    /// Delete the organization with its memberships and invitations (owners)
+    pub async fn delete(&self, actor_id: Uuid, organization_id: Uuid) -> Result<(), ApplicationError> {
+        require(
+            self.role(actor_id, organization_id).await? == OrgRole::Owner,
+            "Only owners can delete the organization",
+        )?;
+        if !self.organizations.delete(organization_id).await? {
+            return Err(not_found(organization_id).into());
+        }
+        tracing::info!(target: "audit", %actor_id, %organization_id, "Organization deleted");
+        Ok(())
+    }
+
+    pub async fn members(
+        &self,
+        actor_id: Uuid,
+        organization_id: Uuid,
+        params: &PaginationParams,
+    ) -> Result<Page<Membership>, ApplicationError> {
+        self.role(actor_id, organization_id).await?;
+        Ok(self.members.list(organization_id, params).await?)
+    }
+
+    /// Change a member's role. Only owners may grant or take away ownership,
+    /// and the last owner cannot step down.
+    pub async fn change_role(
+        &self,
+        actor_id: Uuid,
+        organization_id: Uuid,
+        user_id: Uuid,
+        role: OrgRole,
+    ) -> Result<Membership, ApplicationError> {
+        let actor_role = self.role(actor_id, organization_id).await?;
+        let member = self.member(organization_id, user_id).await?;
+        require(
+            actor_role.can_manage(member.role) && actor_role.can_manage(role),
+            format!("A {} cannot change this member's role to {}", actor_role.as_str(), role.as_str()),
+        )?;
+        if member.role == OrgRole::Owner && role != OrgRole::Owner {
+            self.ensure_other_owner(organization_id).await?;
+        }
+
+        let member = self
+            .members
+            .set_role(organization_id, user_id, role)
+            .await?
+            .ok_or_else(|| DomainError::not_found("Membership", user_id.to_string()))?;
+        tracing::info!(target: "audit", %actor_id, %organization_id, %user_id, role = role.as_str(), "Organization role changed");
+        Ok(member)
+    }
+
+    /// Remove a member, or leave when `user_id` is the actor. The last owner
+    /// cannot leave; they delete the organization or hand it over first.
+    pub async fn remove_member(&self, actor_id: Uuid, organization_id: Uuid, user_id: Uuid) -> Result<(), ApplicationError> {
+        let actor_role = self.role(actor_id, organization_id).await?;
+        let member = self.member(organization_id, user_id).await?;
+        require(
+            user_id == actor_id || actor_role.can_manage(member.role),
+            format!("A {} cannot remove this member", actor_role.as_str()),
+        )?;
+        if member.role == OrgRole::Owner {
+            self.ensure_other_owner(organization_id).await?;
+        }
+
+        self.members.remove(organization_id, user_id).await?;
+        tracing::info!(target: "audit", %actor_id, %organization_id, %user_id, "Organization member removed");
+        Ok(())
+    }
+
+    /// Invite `email` with `role` (admins and owners; only owners invite
+    /// owners). The link is emailed; the token is not returned.
+    pub async fn invite(
+        &self,
+        actor_id: Uuid,
+        organization_id: Uuid,
+        email: &str,
+        role: OrgRole,
+    ) -> Result<Invitation, ApplicationError> {
+        let (organization, actor_role) = self.get(actor_id, organization_id).await?;
+        require(
+            actor_role >= OrgRole::Admin && actor_role.can_manage(role),
+            format!("A {} cannot invite a {}", actor_role.as_str(), role.as_str()),
+        )?;
+        let email = email.trim().to_lowercase();
+        if let Some(user) = self.users.find_by_email(&email).await? {
+            if self.members.find(organization_id, user.id).await?.is_some() {
+                return Err(DomainError::conflict(format!("{} is already a member", email)).into());
+            }
+        }
+        let inviter = self
+            .users
+            .find_by_id(actor_id)
+            .await?
+            .map(|user| String::from(user.username))
+            .unwrap_or_default();
+
+        let token = random_token();
+        let now = Utc::now();
+        let invitation = Invitation {
+            id: Uuid::new_v4(),
+            organization_id,
+            email,
+            role,
+            token_hash: hash_token(&token),
+            invited_by: actor_id,
+            created_at: now,
+            expires_at: now + chrono::Duration::from_std(self.invitation_ttl).unwrap_or_default(),
+            accepted_at: None,
+        };
+        self.invitations.create(&invitation).await?;
+
+        let payload = EmailJobPayload {
+            to: invitation.email.clone(),
+            template: EmailTemplate::OrganizationInvitation,
+            vars: serde_json::json!({
+                "organization": organization.name,
+                "inviter": inviter,
+                "role": role.as_str(),
+                "accept_url": format!("{}/{}", self.invitation_url.trim_end_matches('/'), token),
+                "expires_in_days": self.invitation_ttl.as_secs() / 86_400,
+            }),
+        };
+        SendEmailJob::enqueue(self.job_queue.as_ref(), payload).await?;
+        tracing::info!(
+            target: "audit",
+            %actor_id,
+            %organization_id,
+            invitation_id = %invitation.id,
+            role = role.as_str(),
+            "Organization invitation sent"
+        );
+        Ok(invitation)
+    }
+
+    /// Pending invitations (admins and owners)
+    pub async fn invitations(&self, actor_id: Uuid, organization_id: Uuid) -> Result<Vec<Invitation>, ApplicationError> {
+        require(
+            self.role(actor_id, organization_id).await? >= OrgRole::Admin,
+            "Only admins and owners can see invitations",
+        )?;
+        self.invitations.list_pending(organization_id, Utc::now()).await
+    }
+
+    /// Revoke a pending invitation (admins and owners)
+    pub async fn revoke_invitation(&self, actor_id: Uuid, organization_id: Uuid, invitation_id: Uuid) -> Result<(), ApplicationError> {
+        require(
+            self.role(actor_id, organization_id).await? >= OrgRole::Admin,
+            "Only admins and owners can revoke invitations",
+        )?;
+        if !self.invitations.delete(organization_id, invitation_id).await? {
+            return Err(DomainError::not_found("Invitation", invitation_id.to_string()).into());
+        }
+        Ok(())
+    }
+
+    /// Join the organization through an emailed invitation. The actor's
+    /// email must be the one invited; unknown, used and expired tokens are
+    /// all `NotFound`.
+    pub async fn accept_invitation(&self, actor_id: Uuid, token: &str) -> Result<Membership, ApplicationError> {
+        let now = Utc::now();
+        let invitation = self
+            .invitations
+            .find_by_token_hash(&hash_token(token))
+            .await?
+            .filter(|invitation| invitation.is_pending(now))
+            .ok_or_else(|| DomainError::not_found("Invitation", "token"))?;
+        let user = self
+            .users
+            .find_by_id(actor_id)
+            .await?
+            .ok_or_else(|| DomainError::not_found("User", actor_id.to_string()))?;
+        if !user.email.eq_ignore_ascii_case(&invitation.email) {
+            return Err(DomainError::forbidden("This invitation was sent to another email address").into());
+        }
+
+        if !self.invitations.claim(invitation.id, now).await? {
+            return Err(DomainError::not_found("Invitation", "token").into());
+        }
+        let membership = Membership::new(invitation.organization_id, actor_id, invitation.role);
+        let membership = self.members.add(&membership).await?;
+        tracing::info!(
+            target: "audit",
+            %actor_id,
+            organization_id = %invitation.organization_id,
+            invitation_id = %invitation.id,
+            "Organization invitation accepted"
+        );
+        Ok(membership)
+    }
+
+    async fn role(&self, actor_id: Uuid, organization_id: Uuid) -> Result<OrgRole, ApplicationError> {
+        Ok(self
+            .members
+            .find(organization_id, actor_id)
+            .await?
+            .ok_or_else(|| not_found(organization_id))?
+            .role)
+    }
+
+    async fn member(&self, organization_id: Uuid, user_id: Uuid) -> Result<Membership, ApplicationError> {
+        Ok(self
+            .members
+            .find(organization_id, user_id)
+            .await?
+            .ok_or_else(|| DomainError::not_found("Membership", user_id.to_string()))?)
+    }
+
+    async fn ensure_other_owner(&self, organization_id: Uuid) -> Result<(), ApplicationError> {
+        if self.members.count_owners(organization_id).await? <= 1 {
+            return Err(DomainError::validation("An organization needs at least one owner").into());
+        }
+        Ok(())
+    }
+}
+
+fn not_found(organization_id: Uuid) -> DomainError {
+    DomainError::not_found("Organization", organization_id.to_string())
+}
+
+fn require(allowed: bool, message: impl Into<String>) -> Result<(), ApplicationError> {
+    if allowed {
+        Ok(())
+    } else {
+        Err(DomainError::forbidden(message).into())
+    }
+}
+
+fn validate_name(name: String) -> Result<String, ApplicationError> {
+    let name = name.trim().to_string();
+    if name.is_empty() || name.chars().count() > Organization::MAX_NAME_LEN {
+        return Err(DomainError::validation(format!(
+            "Organization name must be 1-{} characters",
+            Organization::MAX_NAME_LEN
+        ))
+        .into());
+    }
+    Ok(name)
+}
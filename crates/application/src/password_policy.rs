@@ -0,0 +1,251 @@
+use serde::Serialize;
+use std::collections::HashSet;
+
+use crate::ApplicationError;
+
+// ============================================================================
+// Password Policy
+// ============================================================================
+
+/// Built-in list of the most common passwords, one per line, lowercase
+const COMMON_PASSWORDS: &str = include_str!("common_passwords.txt");
+
+/// One rule a password failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PasswordViolation {
+    /// Stable identifier: `min_length`, `lowercase`, `uppercase`, `digit`,
+    /// `symbol`, `common` or `strength`
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl PasswordViolation {
+    fn new(rule: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            rule,
+            message: message.into(),
+        }
+    }
+}
+
+/// Rules new passwords must pass, on registration and password changes
+/// (including forced resets)
+#[derive(Debug, Clone)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    pub require_lowercase: bool,
+    pub require_uppercase: bool,
+    pub require_digit: bool,
+    pub require_symbol: bool,
+    /// Lowest accepted `score`, 0 (anything goes) to 4
+    pub min_score: u8,
+    /// Rejected on top of the built-in common passwords, lowercase
+    pub banned: HashSet<String>,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            require_lowercase: false,
+            require_uppercase: false,
+            require_digit: false,
+            require_symbol: false,
+            min_score: 2,
+            banned: HashSet::new(),
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Fail with every rule `password` breaks. `user_inputs` (username,
+    /// email, ...) count as easy to guess.
+    pub fn validate(&self, password: &str, user_inputs: &[&str]) -> Result<(), ApplicationError> {
+        let violations = self.check(password, user_inputs);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ApplicationError::WeakPassword(violations))
+        }
+    }
+
+    /// Every rule `password` breaks, in a stable order
+    pub fn check(&self, password: &str, user_inputs: &[&str]) -> Vec<PasswordViolation> {
+        let mut violations = Vec::new();
+        if password.chars().count() < self.min_length {
+            violations.push(PasswordViolation::new(
+                "min_length",
+                format!("Password must be at least {} characters", self.min_length),
+            ));
+        }
+        let classes = [
+            (self.require_lowercase, "lowercase", "a lowercase letter", char::is_lowercase as fn(char) -> bool),
+            (self.require_uppercase, "uppercase", "an uppercase letter", char::is_uppercase),
+            (self.require_digit, "digit", "a digit", |c: char| c.is_ascii_digit()),
+            (self.require_symbol, "symbol", "a symbol", |c: char| !c.is_alphanumeric()),
+        ];
+        for (required, rule, name, matches) in classes {
+            if required && !password.chars().any(matches) {
+                violations.push(PasswordViolation::new(rule, format!("Password must contain {}", name)));
+            }
+        }
+        if self.is_common(password) {
+            violations.push(PasswordViolation::new("common", "Password is too common"));
+        }
+        let score = self.score(password, user_inputs);
+        if score < self.min_score {
+            violations.push(PasswordViolation::new(
+                "strength",
+                format!("Password is too easy to guess (strength {} of 4, {} required)", score, self.min_score),
+            ));
+        }
+        violations
+    }
+
+    fn is_common(&self, password: &str) -> bool {
+        let lower = password.to_lowercase();
+        let unleeted: String = lower.chars().map(unleet).collect();
+        [lower, unleeted]
+            .iter()
+            .any(|p| self.banned.contains(p) || COMMON_PASSWORDS.lines().any(|common| common == p))
+    }
+
+    /// Estimated strength from 0 (guessed instantly) to 4 (very hard), in
+    /// the spirit of zxcvbn: the password is split greedily into common
+    /// words, `user_inputs`, repeats, sequences, keyboard runs and years,
+    /// which cost far fewer guesses than the random characters around them.
+    pub fn score(&self, password: &str, user_inputs: &[&str]) -> u8 {
+        let bits = self.entropy_bits(password, user_inputs);
+        // zxcvbn's guess thresholds: 10^3, 10^6, 10^8 and 10^10
+        match bits {
+            b if b < 10.0 => 0,
+            b if b < 20.0 => 1,
+            b if b < 26.6 => 2,
+            b if b < 33.2 => 3,
+            _ => 4,
+        }
+    }
+
+    fn entropy_bits(&self, password: &str, user_inputs: &[&str]) -> f64 {
+        let chars: Vec<char> = password.chars().collect();
+        let lower: Vec<char> = chars.iter().map(|c| c.to_ascii_lowercase()).collect();
+        let unleeted: Vec<char> = lower.iter().map(|&c| unleet(c)).collect();
+
+        let common_bits = ((COMMON_PASSWORDS.lines().count() + self.banned.len()) as f64).log2();
+        let mut words: Vec<(Vec<char>, f64)> = COMMON_PASSWORDS
+            .lines()
+            .chain(self.banned.iter().map(String::as_str))
+            .map(|word| (word.chars().collect(), common_bits))
+            .collect();
+        for input in user_inputs {
+            for part in input.to_lowercase().split(|c: char| !c.is_alphanumeric()) {
+                words.push((part.chars().collect(), 1.0));
+            }
+        }
+        words.retain(|(word, _)| word.len() >= 3);
+
+        let random_bits = charset_size(password).log2();
+        let mut bits = 0.0;
+        let mut i = 0;
+        while i < chars.len() {
+            let rest = &lower[i..];
+            let mut best: Option<(usize, f64)> = None;
+            let mut consider = |len: usize, cost: f64| {
+                if len >= 3 && best.is_none_or(|(best_len, best_cost)| len > best_len || (len == best_len && cost < best_cost)) {
+                    best = Some((len, cost));
+                }
+            };
+
+            for (word, cost) in &words {
+                // One extra bit each for capitals and letter substitutions
+                let capitals = if chars[i..].iter().take(word.len()).any(|c| c.is_uppercase()) { 1.0 } else { 0.0 };
+                if rest.starts_with(word) {
+                    consider(word.len(), cost + capitals);
+                } else if unleeted[i..].starts_with(word) {
+                    consider(word.len(), cost + capitals + 1.0);
+                }
+            }
+            let repeat = run_length(rest, |a, b| a == b);
+            consider(repeat, random_bits + (repeat as f64).log2());
+            let ascending = run_length(rest, |a, b| b as i32 - a as i32 == 1);
+            let descending = run_length(rest, |a, b| a as i32 - b as i32 == 1);
+            let sequence = ascending.max(descending);
+            consider(sequence, 4.7 + (sequence as f64).log2());
+            let keyboard = run_length(rest, adjacent_keys);
+            consider(keyboard, 6.5 + (keyboard as f64).log2());
+            if rest.len() >= 4 && is_year(&rest[..4]) {
+                consider(4, 7.6);
+            }
+
+            match best {
+                Some((len, cost)) => {
+                    bits += cost;
+                    i += len;
+                }
+                None => {
+                    bits += random_bits;
+                    i += 1;
+                }
+            }
+        }
+        bits
+    }
+}
+
+/// Undo the usual letter substitutions
+fn unleet(c: char) -> char {
+    match c {
+        '4' | '@' => 'a',
+        '3' => 'e',
+        '1' | '!' => 'i',
+        '0' => 'o',
+        '5' | '$' => 's',
+        '7' => 't',
+        c => c,
+    }
+}
+
+/// Size of the alphabet the password draws from, by character class
+fn charset_size(password: &str) -> f64 {
+    let mut size = 0.0;
+    if password.chars().any(|c| c.is_ascii_lowercase()) {
+        size += 26.0;
+    }
+    if password.chars().any(|c| c.is_ascii_uppercase()) {
+        size += 26.0;
+    }
+    if password.chars().any(|c| c.is_ascii_digit()) {
+        size += 10.0;
+    }
+    if password.chars().any(|c| c.is_ascii_punctuation() || c == ' ') {
+        size += 33.0;
+    }
+    if !password.is_ascii() {
+        size += 100.0;
+    }
+    f64::max(size, 2.0)
+}
+
+/// Length of the prefix of `chars` in which each neighbour pair satisfies `linked`
+fn run_length(chars: &[char], linked: impl Fn(char, char) -> bool) -> usize {
+    if chars.is_empty() {
+        return 0;
+    }
+    1 + chars.windows(2).take_while(|pair| linked(pair[0], pair[1])).count()
+}
+
+/// Neighbours on the same row of a QWERTY keyboard
+fn adjacent_keys(a: char, b: char) -> bool {
+    const ROWS: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"];
+    ROWS.iter().any(|row| {
+        let (Some(x), Some(y)) = (row.find(a), row.find(b)) else {
+            return false;
+        };
+        x.abs_diff(y) == 1
+    })
+}
+
+/// 1900-2099
+fn is_year(chars: &[char]) -> bool {
+    chars.iter().all(char::is_ascii_digit) && matches!(chars[..2], ['1', '9'] | ['2', '0'])
+}
@@ -0,0 +1,119 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::ApplicationError;
+
+/// How long a connection counts as live without a heartbeat
+pub const PRESENCE_TTL: Duration = Duration::from_secs(60);
+
+// ============================================================================
+// Presence Store Port
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresenceStatus {
+    Online,
+    Offline,
+}
+
+/// A user's presence across every instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Presence {
+    pub user_id: Uuid,
+    pub status: PresenceStatus,
+    /// Last connect, heartbeat or disconnect; `None` if never connected
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+/// A user came online (first live connection) or went offline (last one closed)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PresenceChange {
+    pub user_id: Uuid,
+    pub status: PresenceStatus,
+    pub at: DateTime<Utc>,
+}
+
+/// Live connections per user, shared by all instances.
+/// `connection` ids must be unique across instances.
+#[async_trait]
+pub trait PresenceStore: Send + Sync {
+    /// Mark `connection` live for another `ttl`; true when the user had no
+    /// other live connection (i.e. just came online)
+    async fn heartbeat(&self, user_id: Uuid, connection: &str, ttl: Duration) -> Result<bool, ApplicationError>;
+
+    /// Drop `connection`; true when it was the user's last live connection
+    async fn disconnect(&self, user_id: Uuid, connection: &str) -> Result<bool, ApplicationError>;
+
+    async fn presence(&self, user_id: Uuid) -> Result<Presence, ApplicationError>;
+
+    /// Deliver `change` to subscribers on every instance
+    async fn announce(&self, change: &PresenceChange) -> Result<(), ApplicationError>;
+
+    /// Changes announced from now on, by any instance
+    fn subscribe(&self) -> broadcast::Receiver<PresenceChange>;
+}
+
+// ============================================================================
+// Presence Tracker
+// ============================================================================
+
+/// Records connection lifecycles and announces online/offline transitions.
+/// Connections of a crashed instance lapse after the TTL without an
+/// offline announcement.
+pub struct PresenceTracker {
+    store: Arc<dyn PresenceStore>,
+    ttl: Duration,
+}
+
+impl PresenceTracker {
+    pub fn new(store: Arc<dyn PresenceStore>) -> Self {
+        Self {
+            store,
+            ttl: PRESENCE_TTL,
+        }
+    }
+
+    /// How often connections should call `heartbeat` to stay live
+    pub fn heartbeat_interval(&self) -> Duration {
+        self.ttl / 3
+    }
+
+    /// Register a new connection, or keep an existing one live
+    pub async fn heartbeat(&self, user_id: Uuid, connection: &str) -> Result<(), ApplicationError> {
+        if self.store.heartbeat(user_id, connection, self.ttl).await? {
+            self.announce(user_id, PresenceStatus::Online).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn disconnected(&self, user_id: Uuid, connection: &str) -> Result<(), ApplicationError> {
+        if self.store.disconnect(user_id, connection).await? {
+            self.announce(user_id, PresenceStatus::Offline).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn presence(&self, user_id: Uuid) -> Result<Presence, ApplicationError> {
+        self.store.presence(user_id).await
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<PresenceChange> {
+        self.store.subscribe()
+    }
+
+    async fn announce(&self, user_id: Uuid, status: PresenceStatus) -> Result<(), ApplicationError> {
+        let change = PresenceChange {
+            user_id,
+            status,
+            at: Utc::now(),
+        };
+        tracing::debug!(%user_id, ?status, "presence changed");
+        self.store.announce(&change).await
+    }
+}
@@ -0,0 +1,32 @@
+use domain::{Page, PaginationParams, UserReadModel, UserView};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::tenancy::current_tenant;
+use crate::ApplicationError;
+
+// ============================================================================
+// User Queries
+// ============================================================================
+
+/// Read path for users: lightweight views served by the read model, scoped
+/// to the current tenant. Writes keep going through `UserRepository`.
+pub struct UserQueryService {
+    read_model: Arc<dyn UserReadModel>,
+}
+
+impl UserQueryService {
+    pub fn new(read_model: Arc<dyn UserReadModel>) -> Self {
+        Self { read_model }
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<Option<UserView>, ApplicationError> {
+        Ok(self.read_model.find_view(id, current_tenant()).await?)
+    }
+
+    /// Newest first
+    pub async fn list(&self, params: &PaginationParams) -> Result<Page<UserView>, ApplicationError> {
+        params.validate()?;
+        Ok(self.read_model.list_views(params, current_tenant()).await?)
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+// ============================================================================
+// Query Statistics
+// ============================================================================
+
+/// Upper bounds of the latency buckets, in milliseconds; slower queries fall
+/// into a last, unbounded bucket
+pub const LATENCY_BUCKETS_MS: [u64; 12] = [1, 2, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000];
+
+/// Distinct query shapes tracked; later ones are only counted in `untracked`
+pub const MAX_TRACKED_QUERIES: usize = 1000;
+
+/// Latency of one query shape since startup (or the last reset)
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryStats {
+    pub fingerprint: String,
+    /// Normalized statement, without literal or bound values
+    pub statement: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub total: Duration,
+    pub max: Duration,
+    /// Calls per bucket of `LATENCY_BUCKETS_MS`, then the overflow bucket
+    pub buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+}
+
+impl QueryStats {
+    fn new(fingerprint: &str, statement: &str) -> Self {
+        Self {
+            fingerprint: fingerprint.to_string(),
+            statement: statement.to_string(),
+            calls: 0,
+            errors: 0,
+            total: Duration::ZERO,
+            max: Duration::ZERO,
+            buckets: [0; LATENCY_BUCKETS_MS.len() + 1],
+        }
+    }
+
+    pub fn mean(&self) -> Duration {
+        match u32::try_from(self.calls) {
+            Ok(0) => Duration::ZERO,
+            Ok(calls) => self.total / calls,
+            Err(_) => Duration::from_secs_f64(self.total.as_secs_f64() / self.calls as f64),
+        }
+    }
+
+    /// Upper bound of the bucket holding the `quantile` (0-1) call, or
+    /// `max` when that call is in the overflow bucket
+    pub fn percentile(&self, quantile: f64) -> Duration {
+        let rank = ((self.calls as f64 * quantile.clamp(0.0, 1.0)).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bound, count) in LATENCY_BUCKETS_MS.iter().zip(&self.buckets) {
+            seen += count;
+            if seen >= rank {
+                return Duration::from_millis(*bound).min(self.max);
+            }
+        }
+        self.max
+    }
+}
+
+/// Per-query latency, recorded by the database adapter for every statement
+/// and read by `/admin/db-stats`. Queries are grouped by fingerprint, so the
+/// values they ran with never reach it.
+#[derive(Debug, Default)]
+pub struct QueryStatsRegistry {
+    queries: Mutex<HashMap<String, QueryStats>>,
+    untracked: AtomicU64,
+    /// 0 turns slow-query logging off
+    slow_threshold_ms: AtomicU64,
+}
+
+impl QueryStatsRegistry {
+    /// Count one call; true when it took longer than the slow-query threshold
+    pub fn record(&self, fingerprint: &str, statement: &str, elapsed: Duration, failed: bool) -> bool {
+        {
+            let mut queries = self.queries.lock().unwrap();
+            if !queries.contains_key(fingerprint) && queries.len() < MAX_TRACKED_QUERIES {
+                queries.insert(fingerprint.to_string(), QueryStats::new(fingerprint, statement));
+            }
+            match queries.get_mut(fingerprint) {
+                Some(stats) => {
+                    stats.calls += 1;
+                    stats.errors += u64::from(failed);
+                    stats.total += elapsed;
+                    stats.max = stats.max.max(elapsed);
+                    let bucket = LATENCY_BUCKETS_MS
+                        .iter()
+                        .position(|bound| elapsed <= Duration::from_millis(*bound))
+                        .unwrap_or(LATENCY_BUCKETS_MS.len());
+                    stats.buckets[bucket] += 1;
+                }
+                None => {
+                    self.untracked.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        self.slow_threshold().is_some_and(|threshold| elapsed > threshold)
+    }
+
+    /// Every tracked query, the most total time first
+    pub fn snapshot(&self) -> Vec<QueryStats> {
+        let mut queries: Vec<QueryStats> = self.queries.lock().unwrap().values().cloned().collect();
+        queries.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.fingerprint.cmp(&b.fingerprint)));
+        queries
+    }
+
+    /// Calls not tracked because `MAX_TRACKED_QUERIES` shapes were already seen
+    pub fn untracked(&self) -> u64 {
+        self.untracked.load(Ordering::Relaxed)
+    }
+
+    /// Start over, e.g. after a deploy or an index change
+    pub fn reset(&self) {
+        self.queries.lock().unwrap().clear();
+        self.untracked.store(0, Ordering::Relaxed);
+    }
+
+    pub fn slow_threshold(&self) -> Option<Duration> {
+        match self.slow_threshold_ms.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        }
+    }
+
+    /// Log queries slower than `threshold` (`database.slow_query_ms`); `None` turns it off
+    pub fn set_slow_threshold(&self, threshold: Option<Duration>) {
+        let ms = threshold.map_or(0, |t| u64::try_from(t.as_millis()).unwrap_or(u64::MAX).max(1));
+        self.slow_threshold_ms.store(ms, Ordering::Relaxed);
+    }
+}
+
+static QUERY_STATS: OnceLock<QueryStatsRegistry> = OnceLock::new();
+
+/// The process-wide registry
+pub fn query_stats() -> &'static QueryStatsRegistry {
+    QUERY_STATS.get_or_init(QueryStatsRegistry::default)
+}
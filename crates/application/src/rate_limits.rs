@@ -0,0 +1,223 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::{split_rate_limit_key, ApplicationError, RateLimitCounter, RateLimiter};
+
+/// Longest an override may last
+pub const MAX_OVERRIDE_TTL: Duration = Duration::from_secs(30 * 86_400);
+
+// ============================================================================
+// Subjects and Overrides
+// ============================================================================
+
+/// Who a rate limit counts against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RateLimitSubject {
+    User(Uuid),
+    Ip(IpAddr),
+}
+
+impl fmt::Display for RateLimitSubject {
+    /// The subject part of rate limit keys, e.g. `user:<id>`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::User(id) => write!(f, "user:{}", id),
+            Self::Ip(ip) => write!(f, "ip:{}", ip),
+        }
+    }
+}
+
+/// A temporary limit granted by an admin, replacing the configured limit of
+/// the subject's checks in `scope` (every scope when `None`) until it expires
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitOverride {
+    pub id: Uuid,
+    /// As in rate limit keys, e.g. `user:<id>`
+    pub subject: String,
+    /// Key prefix such as `support` or `availability`
+    pub scope: Option<String>,
+    pub limit: u32,
+    pub reason: Option<String>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl RateLimitOverride {
+    fn applies_to(&self, key: &str, now: DateTime<Utc>) -> bool {
+        let Some((scope, subject)) = split_rate_limit_key(key) else {
+            return false;
+        };
+        subject == self.subject && self.scope.as_deref().is_none_or(|s| s == scope) && self.expires_at > now
+    }
+}
+
+/// Override storage for dependency injection
+#[async_trait]
+pub trait RateLimitOverrideStore: Send + Sync {
+    async fn create(&self, entry: &RateLimitOverride) -> Result<(), ApplicationError>;
+
+    /// Overrides not expired at `now`, oldest first
+    async fn list_active(&self, now: DateTime<Utc>) -> Result<Vec<RateLimitOverride>, ApplicationError>;
+
+    /// False when there was no such override
+    async fn delete(&self, id: Uuid) -> Result<bool, ApplicationError>;
+}
+
+/// What a subject has used and the overrides that apply to it
+#[derive(Debug, Clone)]
+pub struct RateLimitUsage {
+    pub counters: Vec<RateLimitCounter>,
+    pub overrides: Vec<RateLimitOverride>,
+}
+
+// ============================================================================
+// Managed Rate Limiter
+// ============================================================================
+
+/// Rate limiter honouring admin overrides. Checks are synchronous, so the
+/// active overrides are cached: changes made here apply at once, changes
+/// made through other instances once `refresh` runs (see `spawn_refresh`).
+pub struct ManagedRateLimiter {
+    inner: Arc<dyn RateLimiter>,
+    store: Arc<dyn RateLimitOverrideStore>,
+    overrides: RwLock<Vec<RateLimitOverride>>,
+}
+
+impl ManagedRateLimiter {
+    pub fn new(inner: Arc<dyn RateLimiter>, store: Arc<dyn RateLimitOverrideStore>) -> Self {
+        Self {
+            inner,
+            store,
+            overrides: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Reload the active overrides from the store
+    pub async fn refresh(&self) -> Result<(), ApplicationError> {
+        let active = self.store.list_active(Utc::now()).await?;
+        *self.overrides.write().unwrap() = active;
+        Ok(())
+    }
+
+    /// Refresh the cached overrides every `interval`
+    pub fn spawn_refresh(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                if let Err(e) = self.refresh().await {
+                    tracing::warn!("Failed to refresh rate limit overrides: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Active overrides, oldest first
+    pub fn overrides(&self) -> Vec<RateLimitOverride> {
+        let now = Utc::now();
+        let overrides = self.overrides.read().unwrap();
+        overrides.iter().filter(|o| o.expires_at > now).cloned().collect()
+    }
+
+    /// Counters report the limit in force now, overrides granted since their
+    /// last check included
+    pub fn usage(&self, subject: &RateLimitSubject) -> RateLimitUsage {
+        let subject = subject.to_string();
+        let mut counters = self.inner.counters(&subject);
+        for counter in &mut counters {
+            counter.limit = self.limit_for(&counter.key, counter.limit);
+        }
+        RateLimitUsage {
+            counters,
+            overrides: self.overrides().into_iter().filter(|o| o.subject == subject).collect(),
+        }
+    }
+
+    /// Let `subject` use `limit` hits per window in `scope` (every scope when
+    /// `None`) for `ttl`, whatever the configured limit
+    pub async fn grant(
+        &self,
+        admin_id: Uuid,
+        subject: &RateLimitSubject,
+        scope: Option<String>,
+        limit: u32,
+        ttl: Duration,
+        reason: Option<String>,
+    ) -> Result<RateLimitOverride, ApplicationError> {
+        if limit == 0 {
+            return Err(DomainError::validation("Limit must be at least 1").into());
+        }
+        if ttl.is_zero() || ttl > MAX_OVERRIDE_TTL {
+            return Err(DomainError::validation(format!(
+                "Overrides last between 1 second and {} days",
+                MAX_OVERRIDE_TTL.as_secs() / 86_400
+            ))
+            .into());
+        }
+        let scope = scope.map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty());
+        if scope.as_deref().is_some_and(|s| !s.chars().all(|c| c.is_ascii_lowercase() || c == '_')) {
+            return Err(DomainError::validation("Scope must be a rate limit key prefix such as 'support'").into());
+        }
+
+        let now = Utc::now();
+        let entry = RateLimitOverride {
+            id: Uuid::new_v4(),
+            subject: subject.to_string(),
+            scope,
+            limit,
+            reason: reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty()),
+            created_by: admin_id,
+            created_at: now,
+            expires_at: now + chrono::Duration::from_std(ttl).unwrap_or_default(),
+        };
+        self.store.create(&entry).await?;
+        self.overrides.write().unwrap().push(entry.clone());
+        Ok(entry)
+    }
+
+    pub async fn revoke(&self, id: Uuid) -> Result<(), ApplicationError> {
+        if !self.store.delete(id).await? {
+            return Err(DomainError::not_found("RateLimitOverride", id.to_string()).into());
+        }
+        self.overrides.write().unwrap().retain(|o| o.id != id);
+        Ok(())
+    }
+
+    /// The most generous of the overrides that apply to `key`, or `configured`
+    fn limit_for(&self, key: &str, configured: u32) -> u32 {
+        let now = Utc::now();
+        self.overrides
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|o| o.applies_to(key, now))
+            .map(|o| o.limit)
+            .max()
+            .unwrap_or(configured)
+    }
+
+    /// Start every window of `subject` over; returns how many were reset
+    pub fn reset_counters(&self, subject: &RateLimitSubject) -> usize {
+        self.inner.reset(&subject.to_string())
+    }
+}
+
+impl RateLimiter for ManagedRateLimiter {
+    fn check(&self, key: &str, limit: u32, window: Duration) -> Result<(), Duration> {
+        self.inner.check(key, self.limit_for(key, limit), window)
+    }
+
+    fn counters(&self, subject: &str) -> Vec<RateLimitCounter> {
+        self.inner.counters(subject)
+    }
+
+    fn reset(&self, subject: &str) -> usize {
+        self.inner.reset(subject)
+    }
+}
@@ -0,0 +1,236 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::cron::CronTask;
+use crate::jobs::Job;
+use crate::secrets::{hash_token, random_token};
+use crate::ApplicationError;
+
+// ============================================================================
+// Refresh Token Store
+// ============================================================================
+
+/// A refresh token as stored: only its hash is kept. Every login starts a
+/// family; each refresh marks the presented token used and issues the next
+/// token of the same family.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefreshTokenRecord {
+    pub id: Uuid,
+    pub family_id: Uuid,
+    pub user_id: Uuid,
+    /// SHA-256 of the token, base64url
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    /// When it was exchanged for a new pair
+    pub used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    /// The user's token version when the family was started
+    pub token_version: i64,
+}
+
+/// Refresh token storage for dependency injection
+#[async_trait]
+pub trait RefreshTokenStore: Send + Sync {
+    async fn create(&self, token: &RefreshTokenRecord) -> Result<(), ApplicationError>;
+
+    async fn find(&self, token_hash: &str) -> Result<Option<RefreshTokenRecord>, ApplicationError>;
+
+    /// Mark the token used; false when it already was, so of two concurrent
+    /// refreshes with one token only one wins
+    async fn mark_used(&self, id: Uuid) -> Result<bool, ApplicationError>;
+
+    /// Revoke every token of the family; returns how many were not yet revoked
+    async fn revoke_family(&self, family_id: Uuid) -> Result<u64, ApplicationError>;
+
+    /// Every stored token of the user, oldest first
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<RefreshTokenRecord>, ApplicationError>;
+
+    /// Delete tokens that expired before `before`
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64, ApplicationError>;
+
+    /// Delete tokens revoked before `before`, ending the sessions the user
+    /// still sees as signed out; returns how many
+    async fn prune_revoked(&self, before: DateTime<Utc>) -> Result<u64, ApplicationError>;
+}
+
+// ============================================================================
+// Rotation
+// ============================================================================
+
+/// Issues refresh tokens and rotates them on use. Presenting a token that
+/// was already exchanged means someone else holds a copy: the whole family
+/// is revoked, signing out both the thief and the user.
+pub struct RefreshTokens {
+    store: Arc<dyn RefreshTokenStore>,
+    ttl: Duration,
+}
+
+impl RefreshTokens {
+    pub fn new(store: Arc<dyn RefreshTokenStore>, ttl: Duration) -> Self {
+        Self { store, ttl }
+    }
+
+    /// First token of a new family, issued at login to a user whose token
+    /// version is `token_version`
+    pub async fn start_family(&self, user_id: Uuid, token_version: i64) -> Result<String, ApplicationError> {
+        self.issue(user_id, Uuid::new_v4(), token_version).await
+    }
+
+    /// Exchange `token` for the next one of its family. Returns the record
+    /// of the token exchanged and the new token.
+    pub async fn rotate(&self, token: &str) -> Result<(RefreshTokenRecord, String), ApplicationError> {
+        let invalid = || ApplicationError::Domain(DomainError::unauthorized("Invalid refresh token"));
+        let record = self.store.find(&hash_token(token)).await?.ok_or_else(invalid)?;
+        if record.revoked_at.is_some() || record.expires_at <= Utc::now() {
+            return Err(invalid());
+        }
+        if record.used_at.is_some() || !self.store.mark_used(record.id).await? {
+            let revoked = self.store.revoke_family(record.family_id).await?;
+            tracing::warn!(
+                target: "audit",
+                user_id = %record.user_id,
+                family_id = %record.family_id,
+                revoked,
+                "Refresh token reused; token family revoked"
+            );
+            return Err(DomainError::unauthorized("Refresh token already used; sign in again").into());
+        }
+        let next = self.issue(record.user_id, record.family_id, record.token_version).await?;
+        Ok((record, next))
+    }
+
+    async fn issue(&self, user_id: Uuid, family_id: Uuid, token_version: i64) -> Result<String, ApplicationError> {
+        let token = random_token();
+
+        let now = Utc::now();
+        self.store
+            .create(&RefreshTokenRecord {
+                id: Uuid::new_v4(),
+                family_id,
+                user_id,
+                token_hash: hash_token(&token),
+                expires_at: now + chrono::Duration::from_std(self.ttl).unwrap_or_default(),
+                created_at: now,
+                used_at: None,
+                revoked_at: None,
+                token_version,
+            })
+            .await?;
+        Ok(token)
+    }
+}
+
+// ============================================================================
+// Sessions
+// ============================================================================
+
+/// A login and the refreshes that followed it, i.e. one token family
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Session {
+    pub id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub last_refreshed_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl Session {
+    /// Group tokens by family, in the order each session started
+    pub fn from_tokens(tokens: &[RefreshTokenRecord]) -> Vec<Session> {
+        let mut sessions: Vec<Session> = Vec::new();
+        for token in tokens {
+            match sessions.iter_mut().find(|s| s.id == token.family_id) {
+                Some(session) => {
+                    session.started_at = session.started_at.min(token.created_at);
+                    session.last_refreshed_at = session.last_refreshed_at.max(token.created_at);
+                    session.expires_at = session.expires_at.max(token.expires_at);
+                    session.revoked_at = session.revoked_at.or(token.revoked_at);
+                }
+                None => sessions.push(Session {
+                    id: token.family_id,
+                    started_at: token.created_at,
+                    last_refreshed_at: token.created_at,
+                    expires_at: token.expires_at,
+                    revoked_at: token.revoked_at,
+                }),
+            }
+        }
+        sessions.sort_by_key(|s| s.started_at);
+        sessions
+    }
+}
+
+// ============================================================================
+// Jobs
+// ============================================================================
+
+/// Deletes expired refresh tokens; scheduled by cron as `prune_refresh_tokens`.
+/// Still a job, so runs queued before it moved to cron complete.
+pub struct PruneRefreshTokensJob {
+    store: Arc<dyn RefreshTokenStore>,
+}
+
+impl PruneRefreshTokensJob {
+    pub const KIND: &'static str = "refresh_tokens.prune";
+
+    pub fn new(store: Arc<dyn RefreshTokenStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl Job for PruneRefreshTokensJob {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    async fn run(&self, _payload: serde_json::Value) -> Result<(), ApplicationError> {
+        CronTask::run(self).await
+    }
+}
+
+#[async_trait]
+impl CronTask for PruneRefreshTokensJob {
+    fn name(&self) -> &'static str {
+        "prune_refresh_tokens"
+    }
+
+    async fn run(&self) -> Result<(), ApplicationError> {
+        let pruned = self.store.prune(Utc::now()).await?;
+        tracing::info!(pruned, "Pruned expired refresh tokens");
+        Ok(())
+    }
+}
+
+/// Cron task deleting sessions revoked longer than the retention period
+/// ago, which until then are listed as signed out
+pub struct PurgeRevokedSessionsTask {
+    store: Arc<dyn RefreshTokenStore>,
+    retention: Duration,
+}
+
+impl PurgeRevokedSessionsTask {
+    pub fn new(store: Arc<dyn RefreshTokenStore>, retention: Duration) -> Self {
+        Self { store, retention }
+    }
+}
+
+#[async_trait]
+impl CronTask for PurgeRevokedSessionsTask {
+    fn name(&self) -> &'static str {
+        "purge_revoked_sessions"
+    }
+
+    async fn run(&self) -> Result<(), ApplicationError> {
+        let before = Utc::now() - chrono::Duration::from_std(self.retention).unwrap_or_default();
+        let purged = self.store.prune_revoked(before).await?;
+        tracing::info!(purged, "Purged revoked sessions");
+        Ok(())
+    }
+}
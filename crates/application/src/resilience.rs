@@ -0,0 +1,196 @@
+use async_trait::async_trait;
+use domain::{DomainError, Entity, Page, PaginationParams, Queryable, Repository, Specification};
+use rand::Rng;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Retries after the first attempt, by default
+pub const DEFAULT_MAX_RETRIES: u32 = 2;
+/// First retry delay; doubled per retry up to `MAX_RETRY_DELAY`
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(50);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// Consecutive failed calls that open the circuit, by default
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+/// How long an open circuit rejects calls before letting one through
+pub const DEFAULT_OPEN_FOR: Duration = Duration::from_secs(30);
+
+// ============================================================================
+// Circuit Breaker
+// ============================================================================
+
+#[derive(Default)]
+struct Circuit {
+    consecutive_failures: u32,
+    /// Calls are rejected until then
+    open_until: Option<Instant>,
+    /// Start of the trial call let through after the open period (half-open).
+    /// A trial that never reports back (cancelled) is replaced after `open_for`.
+    probe_since: Option<Instant>,
+}
+
+/// Whether a failed call says something about the database's health.
+/// Not-found, validation and conflict errors are normal answers.
+fn is_failure(err: &DomainError) -> bool {
+    matches!(err, DomainError::Unavailable(_) | DomainError::Timeout(_) | DomainError::Internal(_))
+}
+
+// ============================================================================
+// Resilient Repository
+// ============================================================================
+
+/// `Repository<T>` decorator that retries transient failures
+/// (`DomainError::Unavailable`) with jittered exponential backoff and stops
+/// calling the inner repository after repeated failures.
+///
+/// After `failure_threshold` consecutive failed calls the circuit opens:
+/// calls fail at once with `DomainError::Internal("circuit open")` for
+/// `open_for`, then a single trial call decides whether it closes again.
+///
+/// Retried writes are never applied twice: if a create or versioned update
+/// did commit before the connection dropped, the retry fails with a conflict
+/// on the id or version instead.
+pub struct ResilientRepository<T: Entity> {
+    inner: Arc<dyn Repository<T>>,
+    max_retries: u32,
+    base_delay: Duration,
+    failure_threshold: u32,
+    open_for: Duration,
+    circuit: Mutex<Circuit>,
+}
+
+impl<T: Entity> ResilientRepository<T> {
+    pub fn new(inner: Arc<dyn Repository<T>>) -> Self {
+        Self {
+            inner,
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: DEFAULT_BASE_DELAY,
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            open_for: DEFAULT_OPEN_FOR,
+            circuit: Mutex::new(Circuit::default()),
+        }
+    }
+
+    /// Retry up to `max_retries` times, starting `base_delay` apart
+    pub fn with_retries(mut self, max_retries: u32, base_delay: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Open the circuit after `failure_threshold` consecutive failed calls, for `open_for`
+    pub fn with_circuit(mut self, failure_threshold: u32, open_for: Duration) -> Self {
+        self.failure_threshold = failure_threshold.max(1);
+        self.open_for = open_for;
+        self
+    }
+
+    /// True while calls are being rejected
+    pub fn is_open(&self) -> bool {
+        let circuit = self.circuit.lock().unwrap();
+        circuit.open_until.is_some_and(|until| Instant::now() < until) || circuit.probe_since.is_some()
+    }
+
+    fn admit(&self) -> Result<(), DomainError> {
+        let mut circuit = self.circuit.lock().unwrap();
+        let Some(until) = circuit.open_until else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        let probing = circuit.probe_since.is_some_and(|since| now.duration_since(since) < self.open_for);
+        if now < until || probing {
+            return Err(DomainError::internal("circuit open"));
+        }
+        circuit.probe_since = Some(now);
+        Ok(())
+    }
+
+    fn record<R>(&self, result: &Result<R, DomainError>) {
+        let mut circuit = self.circuit.lock().unwrap();
+        match result {
+            Err(err) if is_failure(err) => {
+                circuit.consecutive_failures += 1;
+                if circuit.probe_since.is_some() || circuit.consecutive_failures >= self.failure_threshold {
+                    tracing::warn!(
+                        failures = circuit.consecutive_failures,
+                        "Repository circuit open for {:?}: {}",
+                        self.open_for,
+                        err
+                    );
+                    circuit.open_until = Some(Instant::now() + self.open_for);
+                    circuit.probe_since = None;
+                }
+            }
+            _ => {
+                if circuit.open_until.is_some() {
+                    tracing::info!("Repository circuit closed");
+                }
+                *circuit = Circuit::default();
+            }
+        }
+    }
+
+    /// Delay before retry `attempt` (0-based): half the exponential step plus
+    /// a random share of the other half, so callers do not retry in lockstep
+    fn backoff(&self, attempt: u32) -> Duration {
+        let step = self.base_delay.saturating_mul(1 << attempt.min(16)).min(MAX_RETRY_DELAY);
+        let half = step / 2;
+        half + half.mul_f64(rand::thread_rng().gen::<f64>())
+    }
+
+    async fn call<R, F, Fut>(&self, operation: F) -> Result<R, DomainError>
+    where
+        F: Fn() -> Fut + Send,
+        Fut: Future<Output = Result<R, DomainError>> + Send,
+        R: Send,
+    {
+        self.admit()?;
+        let mut attempt = 0;
+        let result = loop {
+            match operation().await {
+                Err(DomainError::Unavailable(reason)) if attempt < self.max_retries => {
+                    tracing::debug!(attempt, "Retrying repository call: {}", reason);
+                    tokio::time::sleep(self.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                result => break result,
+            }
+        };
+        self.record(&result);
+        result
+    }
+}
+
+#[async_trait]
+impl<T: Entity + 'static> Repository<T> for ResilientRepository<T> {
+    async fn find_by_id(&self, id: T::Id) -> Result<Option<T>, DomainError> {
+        self.call(|| self.inner.find_by_id(id.clone())).await
+    }
+
+    async fn find_all(&self, params: &PaginationParams) -> Result<Page<T>, DomainError> {
+        self.call(|| self.inner.find_all(params)).await
+    }
+
+    async fn create(&self, entity: &T) -> Result<T, DomainError> {
+        self.call(|| self.inner.create(entity)).await
+    }
+
+    async fn update(&self, entity: &T) -> Result<T, DomainError> {
+        self.call(|| self.inner.update(entity)).await
+    }
+
+    async fn delete(&self, id: T::Id) -> Result<bool, DomainError> {
+        self.call(|| self.inner.delete(id.clone())).await
+    }
+
+    async fn count(&self) -> Result<u64, DomainError> {
+        self.call(|| self.inner.count()).await
+    }
+
+    async fn find_by_spec(&self, spec: &Specification<T>, params: &PaginationParams) -> Result<Page<T>, DomainError>
+    where
+        T: Queryable,
+    {
+        self.call(|| self.inner.find_by_spec(spec, params)).await
+    }
+}
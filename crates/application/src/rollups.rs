@@ -0,0 +1,77 @@
+use async_trait::async_trait;
+use chrono::{Days, NaiveDate, Utc};
+use std::sync::Arc;
+
+use crate::cron::CronTask;
+use crate::ApplicationError;
+
+// ============================================================================
+// Daily Metrics
+// ============================================================================
+
+/// One day's value of a metric, e.g. `signups` on 2026-10-16
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyMetric {
+    pub day: NaiveDate,
+    pub metric: String,
+    pub value: i64,
+}
+
+/// Daily rollups of the user tables for dependency injection
+#[async_trait]
+pub trait MetricRollupStore: Send + Sync {
+    /// The newest day rolled up so far
+    async fn last_day(&self) -> Result<Option<NaiveDate>, ApplicationError>;
+
+    /// Count `signups`, `logins` and `active_users` (distinct users signing
+    /// in) of the UTC `day` and store them, replacing earlier values
+    async fn rollup(&self, day: NaiveDate) -> Result<Vec<DailyMetric>, ApplicationError>;
+}
+
+/// Cron task rolling up every complete day since the last rollup, at most
+/// `max_backfill_days` of them, so days missed while no instance ran are
+/// filled in
+pub struct RollupMetricsTask {
+    store: Arc<dyn MetricRollupStore>,
+    max_backfill_days: u64,
+}
+
+impl RollupMetricsTask {
+    pub fn new(store: Arc<dyn MetricRollupStore>) -> Self {
+        Self {
+            store,
+            max_backfill_days: 7,
+        }
+    }
+
+    pub fn with_max_backfill_days(mut self, days: u64) -> Self {
+        self.max_backfill_days = days.max(1);
+        self
+    }
+
+    /// Days to roll up when today is `today`, oldest first
+    pub async fn due_days(&self, today: NaiveDate) -> Result<Vec<NaiveDate>, ApplicationError> {
+        let yesterday = today - Days::new(1);
+        let earliest = today - Days::new(self.max_backfill_days);
+        let first = match self.store.last_day().await? {
+            Some(last) => (last + Days::new(1)).max(earliest),
+            None => yesterday,
+        };
+        Ok(first.iter_days().take_while(|day| *day <= yesterday).collect())
+    }
+}
+
+#[async_trait]
+impl CronTask for RollupMetricsTask {
+    fn name(&self) -> &'static str {
+        "rollup_metrics"
+    }
+
+    async fn run(&self) -> Result<(), ApplicationError> {
+        for day in self.due_days(Utc::now().date_naive()).await? {
+            let metrics = self.store.rollup(day).await?;
+            tracing::info!(%day, metrics = metrics.len(), "Rolled up daily metrics");
+        }
+        Ok(())
+    }
+}
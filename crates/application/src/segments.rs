@@ -0,0 +1,87 @@
+use domain::{DomainError, Page, PaginationParams, Tag, Tenant, User, UserFilter, UserRepository, UserSegment, UserSegmentRepository};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::tenancy::current_tenant;
+use crate::ApplicationError;
+
+// ============================================================================
+// User Segments
+// ============================================================================
+
+/// Named user filters saved by admins of the current tenant (the default
+/// tenant's outside a tenant context). Other tenants' segments behave as if
+/// they did not exist.
+pub struct SegmentService {
+    segments: Arc<dyn UserSegmentRepository>,
+    users: Arc<dyn UserRepository>,
+}
+
+impl SegmentService {
+    pub fn new(segments: Arc<dyn UserSegmentRepository>, users: Arc<dyn UserRepository>) -> Self {
+        Self { segments, users }
+    }
+
+    fn tenant() -> Uuid {
+        current_tenant().unwrap_or(Tenant::DEFAULT_ID)
+    }
+
+    pub async fn list(&self) -> Result<Vec<UserSegment>, ApplicationError> {
+        Ok(self.segments.list(Self::tenant()).await?)
+    }
+
+    /// Save `filter` as `name`; fails with `Conflict` when the name is taken
+    pub async fn create(&self, admin_id: Uuid, name: String, filter: UserFilter) -> Result<UserSegment, ApplicationError> {
+        let segment = UserSegment::new(Self::tenant(), validate_name(name)?, normalize(filter)?, admin_id);
+        Ok(self.segments.create(&segment).await?)
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<UserSegment, ApplicationError> {
+        let segment = self
+            .segments
+            .find_by_id(id)
+            .await?
+            .filter(|segment| segment.tenant_id == Self::tenant())
+            .ok_or_else(|| DomainError::not_found("UserSegment", id.to_string()))?;
+        Ok(segment)
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<(), ApplicationError> {
+        self.get(id).await?;
+        if !self.segments.delete(id).await? {
+            return Err(DomainError::not_found("UserSegment", id.to_string()).into());
+        }
+        Ok(())
+    }
+
+    /// Users matching the segment's filter right now
+    pub async fn members(&self, id: Uuid, params: &PaginationParams) -> Result<Page<User>, ApplicationError> {
+        let segment = self.get(id).await?;
+        Ok(self.users.search(&segment.filter, params).await?)
+    }
+}
+
+fn validate_name(name: String) -> Result<String, ApplicationError> {
+    let name = name.trim().to_string();
+    if name.is_empty() || name.chars().count() > UserSegment::MAX_NAME_LEN {
+        return Err(DomainError::validation(format!("Segment name must be 1-{} characters", UserSegment::MAX_NAME_LEN)).into());
+    }
+    Ok(name)
+}
+
+/// Trimmed query and normalized, deduplicated tags, as the search uses them
+fn normalize(filter: UserFilter) -> Result<UserFilter, ApplicationError> {
+    let mut tags = Vec::new();
+    for tag in &filter.tags {
+        let tag = Tag::normalize(tag)?;
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    Ok(UserFilter {
+        query: filter.query.map(|q| q.trim().to_string()).filter(|q| !q.is_empty()),
+        status: filter.status,
+        tenant_id: None,
+        tags,
+    })
+}
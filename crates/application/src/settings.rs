@@ -0,0 +1,142 @@
+use chrono::Utc;
+use domain::{DomainError, UserSettings, UserSettingsRepository};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::ApplicationError;
+
+// ============================================================================
+// User Settings
+// ============================================================================
+
+/// Longest accepted locale tag, per RFC 5646's practical limit
+pub const MAX_LOCALE_LEN: usize = 35;
+
+/// IANA areas a time zone name may start with
+const TIMEZONE_AREAS: &[&str] = &[
+    "Africa", "America", "Antarctica", "Arctic", "Asia", "Atlantic", "Australia", "Europe", "Indian", "Pacific", "Etc",
+];
+
+/// Partial update of a user's settings; `None` leaves a field as it is
+#[derive(Debug, Clone, Default)]
+pub struct SettingsUpdate {
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub notifications: NotificationUpdate,
+}
+
+/// Partial update of the notification preferences
+#[derive(Debug, Clone, Default)]
+pub struct NotificationUpdate {
+    pub email: Option<bool>,
+    pub push: Option<bool>,
+    pub security_alerts: Option<bool>,
+    pub product_updates: Option<bool>,
+    pub weekly_digest: Option<bool>,
+}
+
+/// Users' own settings. Reads fall back to the defaults until the first
+/// update; updates merge into what is saved.
+pub struct UserSettingsService {
+    settings: Arc<dyn UserSettingsRepository>,
+}
+
+impl UserSettingsService {
+    pub fn new(settings: Arc<dyn UserSettingsRepository>) -> Self {
+        Self { settings }
+    }
+
+    pub async fn get(&self, user_id: Uuid) -> Result<UserSettings, ApplicationError> {
+        Ok(self
+            .settings
+            .find(user_id)
+            .await?
+            .unwrap_or_else(|| UserSettings::defaults(user_id)))
+    }
+
+    /// Validate `update`, apply it over the current settings and save them
+    pub async fn update(&self, user_id: Uuid, update: SettingsUpdate) -> Result<UserSettings, ApplicationError> {
+        let mut settings = self.get(user_id).await?;
+
+        if let Some(locale) = update.locale {
+            settings.locale = normalize_locale(&locale)?;
+        }
+        if let Some(timezone) = update.timezone {
+            settings.timezone = validate_timezone(&timezone)?;
+        }
+        let notifications = &mut settings.notifications;
+        let NotificationUpdate { email, push, security_alerts, product_updates, weekly_digest } = update.notifications;
+        for (field, value) in [
+            (&mut notifications.email, email),
+            (&mut notifications.push, push),
+            (&mut notifications.security_alerts, security_alerts),
+            (&mut notifications.product_updates, product_updates),
+            (&mut notifications.weekly_digest, weekly_digest),
+        ] {
+            if let Some(value) = value {
+                *field = value;
+            }
+        }
+        settings.updated_at = Utc::now();
+
+        Ok(self.settings.upsert(&settings).await?)
+    }
+}
+
+/// A BCP 47 tag in canonical case: `pt-br` becomes `pt-BR`, `zh-hant` `zh-Hant`
+pub fn normalize_locale(locale: &str) -> Result<String, ApplicationError> {
+    let invalid = || DomainError::validation(format!("'{}' is not a valid locale (e.g. en or pt-BR)", locale));
+    let locale = locale.trim();
+    if locale.is_empty() || locale.len() > MAX_LOCALE_LEN {
+        return Err(invalid().into());
+    }
+
+    let mut parts = locale.split(['-', '_']);
+    let language = parts.next().unwrap_or_default();
+    if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(invalid().into());
+    }
+    let mut tag = language.to_ascii_lowercase();
+    for part in parts {
+        if !(1..=8).contains(&part.len()) || !part.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(invalid().into());
+        }
+        tag.push('-');
+        match part.len() {
+            2 if part.chars().all(|c| c.is_ascii_alphabetic()) => tag.push_str(&part.to_ascii_uppercase()),
+            4 if part.chars().all(|c| c.is_ascii_alphabetic()) => {
+                tag.push_str(&part[..1].to_ascii_uppercase());
+                tag.push_str(&part[1..].to_ascii_lowercase());
+            }
+            _ => tag.push_str(&part.to_ascii_lowercase()),
+        }
+    }
+    Ok(tag)
+}
+
+/// `UTC` or an IANA `Area/Location` name such as `America/Argentina/Salta`
+pub fn validate_timezone(timezone: &str) -> Result<String, ApplicationError> {
+    let timezone = timezone.trim();
+    if timezone == "UTC" {
+        return Ok(timezone.to_string());
+    }
+
+    let mut parts = timezone.split('/');
+    let area = parts.next().unwrap_or_default();
+    let locations: Vec<&str> = parts.collect();
+    let valid = TIMEZONE_AREAS.contains(&area)
+        && !locations.is_empty()
+        && locations.iter().all(|part| {
+            !part.is_empty()
+                && part.len() <= 30
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+'))
+        });
+    if !valid {
+        return Err(DomainError::validation(format!(
+            "'{}' is not a valid time zone (e.g. UTC or Europe/Berlin)",
+            timezone
+        ))
+        .into());
+    }
+    Ok(timezone.to_string())
+}
@@ -0,0 +1,355 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use domain::{DomainError, DomainEvent, User, UserRepository};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::jobs::{Job, JobQueue};
+use crate::{ApplicationError, EventBus};
+
+// ============================================================================
+// File Storage Port
+// ============================================================================
+
+/// Keys under this prefix are publicly readable at `FileStorage::public_url`;
+/// everything else needs a presigned URL.
+pub const PUBLIC_PREFIX: &str = "public/";
+
+/// Stored object with its content type
+#[derive(Debug, Clone)]
+pub struct StoredFile {
+    pub bytes: Vec<u8>,
+    pub content_type: String,
+}
+
+/// Blob storage for dependency injection (local disk, S3-compatible stores).
+/// Keys are `/`-separated relative paths such as `public/avatars/<id>.png`.
+#[async_trait]
+pub trait FileStorage: Send + Sync {
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<(), ApplicationError>;
+
+    async fn get(&self, key: &str) -> Result<Option<StoredFile>, ApplicationError>;
+
+    /// Remove an object; missing objects are not an error
+    async fn delete(&self, key: &str) -> Result<(), ApplicationError>;
+
+    /// Time-limited download URL for any key
+    async fn presign(&self, key: &str, expires_in: Duration) -> Result<String, ApplicationError>;
+
+    /// Permanent URL of a key under `PUBLIC_PREFIX`
+    fn public_url(&self, key: &str) -> String;
+
+    /// Check a URL signed by `presign`. Stores that serve their own presigned
+    /// URLs (S3) never route them through the API and keep the default.
+    fn verify_presigned(&self, _key: &str, _expires: i64, _signature: &str) -> bool {
+        false
+    }
+}
+
+// ============================================================================
+// Virus Scanning
+// ============================================================================
+
+/// Keys under this prefix hold uploads that failed scanning, kept for review
+pub const QUARANTINE_PREFIX: &str = "quarantine/";
+
+/// Outcome of scanning one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanVerdict {
+    Clean,
+    /// Malware found; `signature` names it as reported by the scanner
+    Infected { signature: String },
+}
+
+/// Malware scanner for dependency injection (ClamAV, ICAP, no-op)
+#[async_trait]
+pub trait FileScanner: Send + Sync {
+    /// Fails when the file could not be scanned; callers must not store it then
+    async fn scan(&self, bytes: &[u8]) -> Result<ScanVerdict, ApplicationError>;
+}
+
+/// Scans uploads before they are stored. Infected files are written under
+/// `QUARANTINE_PREFIX` instead and the uploader is notified with a
+/// `file.quarantined` event.
+pub struct UploadScanner {
+    scanner: Arc<dyn FileScanner>,
+    storage: Arc<dyn FileStorage>,
+    event_bus: Arc<dyn EventBus>,
+}
+
+impl UploadScanner {
+    pub fn new(scanner: Arc<dyn FileScanner>, storage: Arc<dyn FileStorage>, event_bus: Arc<dyn EventBus>) -> Self {
+        Self {
+            scanner,
+            storage,
+            event_bus,
+        }
+    }
+
+    /// Fail unless `bytes`, uploaded by `user_id` as `purpose` (e.g. `avatar`), are clean
+    pub async fn check(&self, user_id: Uuid, purpose: &str, bytes: &[u8], content_type: &str) -> Result<(), ApplicationError> {
+        let signature = match self.scanner.scan(bytes).await {
+            Ok(ScanVerdict::Clean) => return Ok(()),
+            Ok(ScanVerdict::Infected { signature }) => signature,
+            Err(e) => {
+                tracing::error!(%user_id, purpose, "Upload could not be scanned: {}", e);
+                return Err(DomainError::internal("The file could not be scanned, please try again later").into());
+            }
+        };
+
+        let key = format!("{}{}/{}/{}", QUARANTINE_PREFIX, purpose, user_id, Uuid::new_v4().simple());
+        if let Err(e) = self.storage.put(&key, bytes.to_vec(), content_type).await {
+            tracing::error!(%user_id, key, "Failed to quarantine upload: {}", e);
+        }
+        tracing::info!(target: "audit", %user_id, purpose, key, signature, "Upload quarantined");
+        self.event_bus.publish(DomainEvent::FileQuarantined {
+            user_id,
+            purpose: purpose.to_string(),
+            signature,
+        });
+        Err(DomainError::validation("The file was rejected by the virus scanner").into())
+    }
+}
+
+// ============================================================================
+// Avatars
+// ============================================================================
+
+/// Accepted avatar formats with their file extension and magic bytes
+const AVATAR_TYPES: &[(&str, &str, &[u8])] = &[
+    ("image/png", "png", b"\x89PNG\r\n\x1a\n"),
+    ("image/jpeg", "jpg", b"\xff\xd8\xff"),
+    ("image/gif", "gif", b"GIF8"),
+    ("image/webp", "webp", b"RIFF"),
+];
+
+/// Upload and removal of user avatars
+pub struct AvatarService {
+    users: Arc<dyn UserRepository>,
+    storage: Arc<dyn FileStorage>,
+    max_bytes: usize,
+    scanner: Option<Arc<UploadScanner>>,
+    jobs: Option<Arc<dyn JobQueue>>,
+}
+
+impl AvatarService {
+    pub fn new(users: Arc<dyn UserRepository>, storage: Arc<dyn FileStorage>, max_bytes: usize) -> Self {
+        Self {
+            users,
+            storage,
+            max_bytes,
+            scanner: None,
+            jobs: None,
+        }
+    }
+
+    /// Scan uploads before storing them
+    pub fn with_scanner(mut self, scanner: Arc<UploadScanner>) -> Self {
+        self.scanner = Some(scanner);
+        self
+    }
+
+    /// Queue a `ProcessAvatarJob` after each upload
+    pub fn with_processing(mut self, jobs: Arc<dyn JobQueue>) -> Self {
+        self.jobs = Some(jobs);
+        self
+    }
+
+    /// Largest accepted upload in bytes
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    /// Store a new avatar and point `avatar_url` at it, replacing any previous one.
+    /// The declared content type must be an accepted image type and match the bytes.
+    pub async fn upload(&self, user_id: Uuid, bytes: Vec<u8>, content_type: &str) -> Result<User, ApplicationError> {
+        if bytes.is_empty() {
+            return Err(DomainError::validation("Avatar file is empty").into());
+        }
+        if bytes.len() > self.max_bytes {
+            return Err(DomainError::validation(format!("Avatar must be at most {} bytes", self.max_bytes)).into());
+        }
+        let (content_type, extension, magic) = AVATAR_TYPES
+            .iter()
+            .find(|(mime, _, _)| mime.eq_ignore_ascii_case(content_type))
+            .ok_or_else(|| DomainError::validation("Avatar must be a PNG, JPEG, GIF or WebP image"))?;
+        let webp_ok = *extension != "webp" || bytes.get(8..12) == Some(b"WEBP");
+        if !bytes.starts_with(magic) || !webp_ok {
+            return Err(DomainError::validation(format!("File content is not {}", content_type)).into());
+        }
+
+        let mut user = self
+            .users
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| DomainError::not_found("User", user_id.to_string()))?;
+        if let Some(scanner) = &self.scanner {
+            scanner.check(user_id, "avatar", &bytes, content_type).await?;
+        }
+
+        // A fresh key per upload, so caches never serve the old image
+        let key = format!("{}avatars/{}/{}.{}", PUBLIC_PREFIX, user_id, Uuid::new_v4().simple(), extension);
+        self.storage.put(&key, bytes, content_type).await?;
+
+        let previous = user.avatar_url.replace(self.storage.public_url(&key));
+        let user = self.users.update(&user).await?;
+        if let Some(previous) = previous {
+            self.delete_by_url(&previous).await;
+        }
+        if let Some(jobs) = &self.jobs {
+            // Until the job has run, the unprocessed upload is served
+            if let Err(e) = ProcessAvatarJob::enqueue(jobs.as_ref(), user_id, &key).await {
+                tracing::error!(%user_id, key, "Failed to queue avatar processing: {}", e);
+            }
+        }
+        Ok(user)
+    }
+
+    /// Clear the avatar
+    pub async fn remove(&self, user_id: Uuid) -> Result<User, ApplicationError> {
+        let mut user = self
+            .users
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| DomainError::not_found("User", user_id.to_string()))?;
+
+        let Some(previous) = user.avatar_url.take() else {
+            return Ok(user);
+        };
+        let user = self.users.update(&user).await?;
+        self.delete_by_url(&previous).await;
+        Ok(user)
+    }
+
+    /// Best-effort cleanup of an avatar and its variants; a leftover object is harmless
+    async fn delete_by_url(&self, url: &str) {
+        let base = self.storage.public_url("");
+        if let Some(key) = url.strip_prefix(&base) {
+            delete_avatar(self.storage.as_ref(), key).await;
+        }
+    }
+}
+
+async fn delete_avatar(storage: &dyn FileStorage, key: &str) {
+    let keys = std::iter::once(key.to_string()).chain(AVATAR_SIZES.iter().map(|&size| avatar_variant_key(key, size)));
+    for key in keys {
+        if let Err(e) = storage.delete(&key).await {
+            tracing::warn!(key, "Failed to delete replaced avatar: {}", e);
+        }
+    }
+}
+
+// ============================================================================
+// Avatar Processing
+// ============================================================================
+
+/// Square variants generated for every avatar, in pixels
+pub const AVATAR_SIZES: [u32; 3] = [64, 128, 256];
+
+/// Key of the `size` variant of the avatar at `key`:
+/// `public/avatars/<user>/<id>.png` becomes `public/avatars/<user>/<id>_128.png`
+pub fn avatar_variant_key(key: &str, size: u32) -> String {
+    match key.rsplit_once('.') {
+        Some((stem, extension)) if !extension.contains('/') => format!("{}_{}.{}", stem, size, extension),
+        _ => format!("{}_{}", key, size),
+    }
+}
+
+/// An encoded image, in the format it was uploaded in
+#[derive(Debug, Clone)]
+pub struct ProcessedImage {
+    /// The image re-encoded without metadata (EXIF, ICC text, comments)
+    pub original: Vec<u8>,
+    /// One per requested size, in the same order
+    pub variants: Vec<Vec<u8>>,
+}
+
+/// Image decoding and resizing for dependency injection (in-process or an
+/// external service). CPU-bound; called off the async runtime.
+pub trait ImageProcessor: Send + Sync {
+    /// Fails with a validation error when `bytes` is not a usable image
+    fn process(&self, bytes: &[u8], sizes: &[u32]) -> Result<ProcessedImage, ApplicationError>;
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ProcessAvatarPayload {
+    user_id: Uuid,
+    key: String,
+}
+
+/// Validates an uploaded avatar, strips its metadata in place and stores the
+/// `AVATAR_SIZES` variants next to it. Images that fail to decode are
+/// removed from the user.
+pub struct ProcessAvatarJob {
+    users: Arc<dyn UserRepository>,
+    storage: Arc<dyn FileStorage>,
+    processor: Arc<dyn ImageProcessor>,
+}
+
+impl ProcessAvatarJob {
+    pub const KIND: &'static str = "avatar.process";
+
+    pub fn new(users: Arc<dyn UserRepository>, storage: Arc<dyn FileStorage>, processor: Arc<dyn ImageProcessor>) -> Self {
+        Self {
+            users,
+            storage,
+            processor,
+        }
+    }
+
+    pub async fn enqueue(queue: &dyn JobQueue, user_id: Uuid, key: &str) -> Result<(), ApplicationError> {
+        let payload = serde_json::to_value(ProcessAvatarPayload {
+            user_id,
+            key: key.to_string(),
+        })
+        .map_err(|e| ApplicationError::use_case(format!("Invalid avatar payload: {}", e)))?;
+        queue.enqueue(Self::KIND, payload, Utc::now()).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Job for ProcessAvatarJob {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    async fn run(&self, payload: serde_json::Value) -> Result<(), ApplicationError> {
+        let ProcessAvatarPayload { user_id, key } = serde_json::from_value(payload)
+            .map_err(|e| ApplicationError::use_case(format!("Invalid avatar payload: {}", e)))?;
+        let url = self.storage.public_url(&key);
+        // Replaced or removed since the upload: nothing to do
+        let Some(mut user) = self.users.find_by_id(user_id).await? else {
+            return Ok(());
+        };
+        if user.avatar_url.as_deref() != Some(url.as_str()) {
+            return Ok(());
+        }
+        let Some(file) = self.storage.get(&key).await? else {
+            return Ok(());
+        };
+
+        let processor = self.processor.clone();
+        let processed = tokio::task::spawn_blocking(move || processor.process(&file.bytes, &AVATAR_SIZES))
+            .await
+            .map_err(|e| DomainError::internal(format!("Avatar processing panicked: {}", e)))?;
+        let processed = match processed {
+            Ok(processed) => processed,
+            Err(ApplicationError::Domain(DomainError::Validation(reason))) => {
+                tracing::warn!(%user_id, key, reason, "Removing avatar that is not a valid image");
+                user.avatar_url = None;
+                self.users.update(&user).await?;
+                delete_avatar(self.storage.as_ref(), &key).await;
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
+
+        for (size, bytes) in AVATAR_SIZES.iter().zip(processed.variants) {
+            self.storage.put(&avatar_variant_key(&key, *size), bytes, &file.content_type).await?;
+        }
+        self.storage.put(&key, processed.original, &file.content_type).await
+    }
+}
@@ -0,0 +1,61 @@
+use futures_util::stream::{self, BoxStream, Stream, StreamExt};
+use std::future::Future;
+
+use crate::ApplicationError;
+
+// ============================================================================
+// Chunked Processing
+// ============================================================================
+
+/// Group `items` into chunks of `size` (the last may be shorter). Items are
+/// pulled only as chunks are taken, so no more than one chunk is held. The
+/// first error ends the stream, dropping the items read before it in that chunk.
+///
+/// # Panics
+///
+/// When `size` is zero.
+pub fn chunks<'a, T, E>(items: impl Stream<Item = Result<T, E>> + Send + 'a, size: usize) -> BoxStream<'a, Result<Vec<T>, E>>
+where
+    T: Send + 'a,
+    E: Send + 'a,
+{
+    assert!(size > 0, "chunk size must be positive");
+    stream::try_unfold(Some(items.boxed()), move |items| async move {
+        let Some(mut items) = items else {
+            return Ok(None);
+        };
+        let mut chunk = Vec::with_capacity(size);
+        while chunk.len() < size {
+            match items.next().await {
+                Some(item) => chunk.push(item?),
+                None => return Ok((!chunk.is_empty()).then_some((chunk, None))),
+            }
+        }
+        Ok(Some((chunk, Some(items))))
+    })
+    .boxed()
+}
+
+/// Run `process` on each chunk of `items` in turn, e.g. to write a batch in one
+/// transaction or save progress. Returns how many items were processed and
+/// stops at the first error, from the stream or from `process`.
+pub async fn for_each_chunk<'a, T, E, F, Fut>(
+    items: impl Stream<Item = Result<T, E>> + Send + 'a,
+    size: usize,
+    mut process: F,
+) -> Result<u64, ApplicationError>
+where
+    T: Send + 'a,
+    E: Into<ApplicationError> + Send + 'a,
+    F: FnMut(Vec<T>) -> Fut,
+    Fut: Future<Output = Result<(), ApplicationError>>,
+{
+    let mut chunks = chunks(items, size);
+    let mut processed = 0;
+    while let Some(chunk) = chunks.next().await {
+        let chunk = chunk.map_err(Into::into)?;
+        processed += chunk.len() as u64;
+        process(chunk).await?;
+    }
+    Ok(processed)
+}
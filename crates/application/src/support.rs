@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use domain::{DomainError, SupportTicket, SupportTicketRepository};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::email::{EmailJobPayload, EmailTemplate, SendEmailJob};
+use crate::jobs::JobQueue;
+use crate::{ApplicationError, RateLimiter};
+
+// ============================================================================
+// Support Contact
+// ============================================================================
+
+/// Contact form submission
+#[derive(Debug, Clone)]
+pub struct ContactRequest {
+    /// Signed-in sender, if any
+    pub user_id: Option<Uuid>,
+    /// Client address used for rate limiting anonymous senders
+    pub client_ip: Option<String>,
+    pub name: String,
+    pub email: String,
+    pub subject: String,
+    pub message: String,
+}
+
+#[async_trait]
+pub trait SupportService: Send + Sync {
+    /// Store the message and notify the support team
+    async fn contact(&self, request: ContactRequest) -> Result<SupportTicket, ApplicationError>;
+}
+
+/// Per-sender and per-address limits, both counted over `window`
+#[derive(Debug, Clone, Copy)]
+pub struct ContactLimits {
+    pub per_sender: u32,
+    pub per_email: u32,
+    pub window: Duration,
+}
+
+impl Default for ContactLimits {
+    fn default() -> Self {
+        Self {
+            per_sender: 5,
+            per_email: 3,
+            window: Duration::from_secs(3600),
+        }
+    }
+}
+
+pub struct SupportServiceImpl {
+    repository: Arc<dyn SupportTicketRepository>,
+    rate_limiter: Arc<dyn RateLimiter>,
+    job_queue: Arc<dyn JobQueue>,
+    /// Support team addresses notified of new tickets
+    notify: Vec<String>,
+    limits: ContactLimits,
+}
+
+impl SupportServiceImpl {
+    pub fn new(
+        repository: Arc<dyn SupportTicketRepository>,
+        rate_limiter: Arc<dyn RateLimiter>,
+        job_queue: Arc<dyn JobQueue>,
+        notify: Vec<String>,
+    ) -> Self {
+        Self {
+            repository,
+            rate_limiter,
+            job_queue,
+            notify,
+            limits: ContactLimits::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: ContactLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    fn limit(&self, key: String, limit: u32) -> Result<(), ApplicationError> {
+        self.rate_limiter
+            .check(&key, limit, self.limits.window)
+            .map_err(|retry_after| ApplicationError::RateLimited { retry_after })
+    }
+}
+
+#[async_trait]
+impl SupportService for SupportServiceImpl {
+    async fn contact(&self, request: ContactRequest) -> Result<SupportTicket, ApplicationError> {
+        let sender = match (request.user_id, &request.client_ip) {
+            (Some(user_id), _) => format!("support:user:{}", user_id),
+            (None, Some(ip)) => format!("support:ip:{}", ip),
+            (None, None) => "support:anonymous".to_string(),
+        };
+        self.limit(sender, self.limits.per_sender)?;
+        self.limit(format!("support:email:{}", request.email.to_lowercase()), self.limits.per_email)?;
+
+        if request.message.trim().is_empty() {
+            return Err(DomainError::validation("Message cannot be empty").into());
+        }
+
+        let ticket = SupportTicket::new(
+            request.user_id,
+            request.name,
+            request.email,
+            request.subject,
+            request.message,
+        );
+        let ticket = self.repository.create(&ticket).await?;
+
+        for to in &self.notify {
+            let payload = EmailJobPayload {
+                to: to.clone(),
+                template: EmailTemplate::SupportContact,
+                vars: serde_json::json!({
+                    "ticket_id": ticket.id,
+                    "name": ticket.name,
+                    "email": ticket.email,
+                    "subject": ticket.subject,
+                    "message": ticket.message,
+                }),
+            };
+            if let Err(e) = SendEmailJob::enqueue(self.job_queue.as_ref(), payload).await {
+                tracing::error!(ticket_id = %ticket.id, "Failed to queue support notification: {}", e);
+            }
+        }
+
+        Ok(ticket)
+    }
+}
@@ -0,0 +1,68 @@
+use domain::{DomainError, Tag, TagRepository, Taggable, Tenant};
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::tenancy::current_tenant;
+use crate::ApplicationError;
+
+// ============================================================================
+// Tagging
+// ============================================================================
+
+/// Tags entities of any `Taggable` type with the current tenant's tags
+/// (the default tenant's outside a tenant context). Callers load the entity
+/// first, so only entities visible to the tenant can be tagged.
+pub struct TagService {
+    tags: Arc<dyn TagRepository>,
+}
+
+impl TagService {
+    pub fn new(tags: Arc<dyn TagRepository>) -> Self {
+        Self { tags }
+    }
+
+    fn tenant() -> Uuid {
+        current_tenant().unwrap_or(Tenant::DEFAULT_ID)
+    }
+
+    pub async fn list(&self) -> Result<Vec<Tag>, ApplicationError> {
+        Ok(self.tags.list(Self::tenant()).await?)
+    }
+
+    /// Attach the tag `name`, creating it on first use
+    pub async fn tag<T: Taggable>(&self, entity: &T, name: &str) -> Result<Tag, ApplicationError> {
+        let tag = self.tags.find_or_create(Self::tenant(), &Tag::normalize(name)?).await?;
+        self.tags.attach(tag.id, T::ENTITY_TYPE, entity.id()).await?;
+        Ok(tag)
+    }
+
+    pub async fn untag<T: Taggable>(&self, entity: &T, name: &str) -> Result<(), ApplicationError> {
+        let name = Tag::normalize(name)?;
+        if !self.tags.detach(Self::tenant(), &name, T::ENTITY_TYPE, entity.id()).await? {
+            return Err(DomainError::not_found("Tag", name).into());
+        }
+        Ok(())
+    }
+
+    /// Tags of each of `entities` by name; untagged entities are left out
+    pub async fn tags_of<T: Taggable>(&self, entities: &[T]) -> Result<HashMap<Uuid, Vec<Tag>>, ApplicationError> {
+        if entities.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let ids: Vec<Uuid> = entities.iter().map(|e| e.id()).collect();
+        Ok(self.tags.tags_of(T::ENTITY_TYPE, &ids).await?)
+    }
+}
+
+/// Normalized, deduplicated tag names from a comma-separated list
+pub fn parse_tag_list(list: &str) -> Result<Vec<String>, ApplicationError> {
+    let mut names = Vec::new();
+    for name in list.split(',').filter(|n| !n.trim().is_empty()) {
+        let name = Tag::normalize(name)?;
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    Ok(names)
+}
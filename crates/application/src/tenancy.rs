@@ -0,0 +1,208 @@
+use async_trait::async_trait;
+use domain::{DomainError, Page, PaginationParams, Repository, Specification, TenantRepository, User, UserCriterion, UserCursor, UserFilter, UserRepository, UserStream};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::ApplicationError;
+
+// ============================================================================
+// Tenant Context
+// ============================================================================
+
+tokio::task_local! {
+    static CURRENT_TENANT: Uuid;
+}
+
+/// Run `work` on behalf of `tenant`: tenant-scoped repositories only see its rows
+pub async fn with_tenant<F: Future>(tenant: Uuid, work: F) -> F::Output {
+    CURRENT_TENANT.scope(tenant, work).await
+}
+
+/// The tenant of the current task, if any. Background work (jobs, gRPC) runs
+/// without one and is not scoped.
+pub fn current_tenant() -> Option<Uuid> {
+    CURRENT_TENANT.try_with(|tenant| *tenant).ok()
+}
+
+// ============================================================================
+// Tenant Resolution
+// ============================================================================
+
+/// How long a resolved slug is trusted before it is looked up again
+const RESOLVE_TTL: Duration = Duration::from_secs(60);
+
+/// Maps the tenant named by a request (id or slug) to its id, caching hits
+pub struct TenantDirectory {
+    tenants: Arc<dyn TenantRepository>,
+    cache: Mutex<HashMap<String, (Uuid, Instant)>>,
+}
+
+impl TenantDirectory {
+    pub fn new(tenants: Arc<dyn TenantRepository>) -> Self {
+        Self {
+            tenants,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The tenant with id or slug `key`; `None` when there is no such tenant
+    pub async fn resolve(&self, key: &str) -> Result<Option<Uuid>, ApplicationError> {
+        let key = key.trim().to_ascii_lowercase();
+        if let Some((id, at)) = self.cache.lock().unwrap().get(&key) {
+            if at.elapsed() < RESOLVE_TTL {
+                return Ok(Some(*id));
+            }
+        }
+
+        let tenant = match key.parse::<Uuid>() {
+            Ok(id) => self.tenants.find_by_id(id).await?,
+            Err(_) => self.tenants.find_by_slug(&key).await?,
+        };
+        let Some(tenant) = tenant else {
+            return Ok(None);
+        };
+        self.cache.lock().unwrap().insert(key, (tenant.id, Instant::now()));
+        Ok(Some(tenant.id))
+    }
+}
+
+// ============================================================================
+// Tenant-Scoped User Repository
+// ============================================================================
+
+/// `UserRepository` decorator confining every query to the current tenant.
+/// Rows of other tenants behave as if they did not exist; outside a tenant
+/// context calls pass through unchanged.
+pub struct TenantScopedUserRepository {
+    inner: Arc<dyn UserRepository>,
+}
+
+impl TenantScopedUserRepository {
+    pub fn new(inner: Arc<dyn UserRepository>) -> Self {
+        Self { inner }
+    }
+
+    fn visible(user: Option<User>) -> Option<User> {
+        match current_tenant() {
+            Some(tenant) => user.filter(|u| u.tenant_id == tenant),
+            None => user,
+        }
+    }
+
+    fn scoped(filter: &UserFilter) -> UserFilter {
+        UserFilter {
+            tenant_id: current_tenant().or(filter.tenant_id),
+            ..filter.clone()
+        }
+    }
+}
+
+#[async_trait]
+impl Repository<User> for TenantScopedUserRepository {
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, DomainError> {
+        Ok(Self::visible(self.inner.find_by_id(id).await?))
+    }
+
+    async fn find_all(&self, params: &PaginationParams) -> Result<Page<User>, DomainError> {
+        match current_tenant() {
+            Some(_) => self.inner.search(&Self::scoped(&UserFilter::default()), params).await,
+            None => self.inner.find_all(params).await,
+        }
+    }
+
+    async fn create(&self, user: &User) -> Result<User, DomainError> {
+        let mut user = user.clone();
+        if let Some(tenant) = current_tenant() {
+            user.tenant_id = tenant;
+        }
+        self.inner.create(&user).await
+    }
+
+    /// The adapter matches on `tenant_id` too, so a copy claiming another
+    /// tenant's user cannot be written through
+    async fn update(&self, user: &User) -> Result<User, DomainError> {
+        if matches!(current_tenant(), Some(tenant) if tenant != user.tenant_id) {
+            return Err(DomainError::not_found("User", user.id.to_string()));
+        }
+        self.inner.update(user).await
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, DomainError> {
+        if self.find_by_id(id).await?.is_none() {
+            return Ok(false);
+        }
+        self.inner.delete(id).await
+    }
+
+    /// Within a tenant the spec is narrowed to it
+    async fn find_by_spec(&self, spec: &Specification<User>, params: &PaginationParams) -> Result<Page<User>, DomainError> {
+        match current_tenant() {
+            Some(tenant) => {
+                let scoped = Specification::from(UserCriterion::InTenant(tenant)).and(spec.clone());
+                self.inner.find_by_spec(&scoped, params).await
+            }
+            None => self.inner.find_by_spec(spec, params).await,
+        }
+    }
+
+    async fn count(&self) -> Result<u64, DomainError> {
+        match current_tenant() {
+            Some(_) => {
+                let page = self
+                    .inner
+                    .search(&Self::scoped(&UserFilter::default()), &PaginationParams::new(1, 1))
+                    .await?;
+                Ok(page.total)
+            }
+            None => self.inner.count().await,
+        }
+    }
+}
+
+#[async_trait]
+impl UserRepository for TenantScopedUserRepository {
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, DomainError> {
+        Ok(Self::visible(self.inner.find_by_email(email).await?))
+    }
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, DomainError> {
+        Ok(Self::visible(self.inner.find_by_username(username).await?))
+    }
+
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<User>, DomainError> {
+        let users = self.inner.find_by_ids(ids).await?;
+        Ok(users.into_iter().filter_map(|u| Self::visible(Some(u))).collect())
+    }
+
+    async fn find_by_usernames(&self, usernames: &[String]) -> Result<Vec<User>, DomainError> {
+        let users = self.inner.find_by_usernames(usernames).await?;
+        Ok(users.into_iter().filter_map(|u| Self::visible(Some(u))).collect())
+    }
+
+    async fn search(&self, filter: &UserFilter, params: &PaginationParams) -> Result<Page<User>, DomainError> {
+        self.inner.search(&Self::scoped(filter), params).await
+    }
+
+    async fn search_after(&self, filter: &UserFilter, after: Option<UserCursor>, limit: u32) -> Result<Vec<User>, DomainError> {
+        self.inner.search_after(&Self::scoped(filter), after, limit).await
+    }
+
+    /// Scoped to the tenant current when the stream is created
+    fn stream_all(&self, filter: &UserFilter) -> UserStream {
+        self.inner.stream_all(&Self::scoped(filter))
+    }
+
+    async fn autocomplete(&self, prefix: &str, filter: &UserFilter, limit: u32) -> Result<Vec<User>, DomainError> {
+        self.inner.autocomplete(prefix, &Self::scoped(filter), limit).await
+    }
+
+    async fn bump_token_version(&self, id: Uuid) -> Result<i64, DomainError> {
+        if self.find_by_id(id).await?.is_none() {
+            return Err(DomainError::not_found("User", id.to_string()));
+        }
+        self.inner.bump_token_version(id).await
+    }
+}
@@ -0,0 +1,451 @@
+//! In-memory stand-ins for the ports services depend on, so services can be
+//! unit-tested without a database or real crypto. Enabled by the `test-util`
+//! feature:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! application = { path = "../application", features = ["test-util"] }
+//! ```
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{Claims, DomainError, Page, PaginationParams, Repository, Specification, TokenPair, User, UserCursor, UserFilter, UserReadModel, UserRepository, UserStream, UserView};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use crate::jobs::{JobQueue, JobRecord, JobStatus};
+use crate::{ApplicationError, PasswordHasher, TokenService};
+
+// ============================================================================
+// User Repository
+// ============================================================================
+
+/// Users kept in memory with the same rules as the Postgres repository:
+/// unique usernames and emails, versioned updates and newest-first listings.
+/// Tag filters are not supported and match every user.
+#[derive(Default)]
+pub struct MockUserRepository {
+    users: Mutex<Vec<User>>,
+    failing: AtomicBool,
+}
+
+impl MockUserRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Repository already holding `users`
+    pub fn with_users(users: impl IntoIterator<Item = User>) -> Self {
+        Self {
+            users: Mutex::new(users.into_iter().collect()),
+            failing: AtomicBool::new(false),
+        }
+    }
+
+    /// Snapshot of the stored users, in insertion order
+    pub fn users(&self) -> Vec<User> {
+        self.users.lock().unwrap().clone()
+    }
+
+    /// Make every following call fail with `DomainError::Internal`, as an
+    /// unreachable database would
+    pub fn set_failing(&self, failing: bool) {
+        self.failing.store(failing, Ordering::SeqCst);
+    }
+
+    fn check(&self) -> Result<(), DomainError> {
+        if self.failing.load(Ordering::SeqCst) {
+            return Err(DomainError::internal("Mock repository failure"));
+        }
+        Ok(())
+    }
+
+    fn matching(&self, filter: &UserFilter) -> Vec<User> {
+        let query = filter.query.as_deref().map(str::to_lowercase);
+        let mut users: Vec<User> = self
+            .users
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|u| filter.status.is_none_or(|s| u.status == s))
+            .filter(|u| filter.tenant_id.is_none_or(|t| u.tenant_id == t))
+            .filter(|u| {
+                query.as_deref().is_none_or(|q| {
+                    u.username.to_lowercase().contains(q) || u.email.to_lowercase().contains(q)
+                })
+            })
+            .cloned()
+            .collect();
+        users.sort_by_key(|u| std::cmp::Reverse((u.created_at, u.id)));
+        users
+    }
+}
+
+fn page(users: Vec<User>, params: &PaginationParams) -> Page<User> {
+    let total = users.len() as u64;
+    let items = users
+        .into_iter()
+        .skip(params.offset() as usize)
+        .take(params.limit() as usize)
+        .collect();
+    Page::new(items, total, params)
+}
+
+#[async_trait]
+impl Repository<User> for MockUserRepository {
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, DomainError> {
+        self.check()?;
+        Ok(self.users.lock().unwrap().iter().find(|u| u.id == id).cloned())
+    }
+
+    async fn find_all(&self, params: &PaginationParams) -> Result<Page<User>, DomainError> {
+        self.check()?;
+        params.validate()?;
+        Ok(page(self.matching(&UserFilter::default()), params))
+    }
+
+    async fn create(&self, user: &User) -> Result<User, DomainError> {
+        self.check()?;
+        let mut users = self.users.lock().unwrap();
+        if users
+            .iter()
+            .any(|u| u.id == user.id || u.username == user.username || u.email.normalized() == user.email.normalized())
+        {
+            return Err(DomainError::conflict("User already exists"));
+        }
+        users.push(user.clone());
+        Ok(user.clone())
+    }
+
+    async fn update(&self, user: &User) -> Result<User, DomainError> {
+        self.check()?;
+        let mut users = self.users.lock().unwrap();
+        let stored = users
+            .iter_mut()
+            .find(|u| u.id == user.id && u.tenant_id == user.tenant_id)
+            .ok_or_else(|| DomainError::not_found("User", user.id.to_string()))?;
+        if stored.version != user.version {
+            return Err(DomainError::version_conflict("User", user.id.to_string(), stored.version));
+        }
+        *stored = User {
+            updated_at: Utc::now(),
+            version: user.version + 1,
+            token_version: stored.token_version,
+            ..user.clone()
+        };
+        Ok(stored.clone())
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<bool, DomainError> {
+        self.check()?;
+        let mut users = self.users.lock().unwrap();
+        let before = users.len();
+        users.retain(|u| u.id != id);
+        Ok(users.len() < before)
+    }
+
+    async fn count(&self) -> Result<u64, DomainError> {
+        self.check()?;
+        Ok(self.users.lock().unwrap().len() as u64)
+    }
+
+    async fn find_by_spec(&self, spec: &Specification<User>, params: &PaginationParams) -> Result<Page<User>, DomainError> {
+        self.check()?;
+        let mut users: Vec<User> = self.users.lock().unwrap().iter().filter(|u| spec.is_satisfied_by(u)).cloned().collect();
+        users.sort_by_key(|u| std::cmp::Reverse(u.created_at));
+        Ok(page(users, params))
+    }
+}
+
+#[async_trait]
+impl UserRepository for MockUserRepository {
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, DomainError> {
+        self.check()?;
+        let email = domain::normalize_email(email);
+        Ok(self.users.lock().unwrap().iter().find(|u| u.email.normalized() == email).cloned())
+    }
+
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, DomainError> {
+        self.check()?;
+        Ok(self.users.lock().unwrap().iter().find(|u| u.username == username).cloned())
+    }
+
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<User>, DomainError> {
+        self.check()?;
+        Ok(self.users.lock().unwrap().iter().filter(|u| ids.contains(&u.id)).cloned().collect())
+    }
+
+    async fn find_by_usernames(&self, usernames: &[String]) -> Result<Vec<User>, DomainError> {
+        self.check()?;
+        let users = self.users.lock().unwrap();
+        Ok(users.iter().filter(|u| usernames.iter().any(|n| u.username == n.as_str())).cloned().collect())
+    }
+
+    async fn search(&self, filter: &UserFilter, params: &PaginationParams) -> Result<Page<User>, DomainError> {
+        self.check()?;
+        params.validate()?;
+        Ok(page(self.matching(filter), params))
+    }
+
+    async fn search_after(&self, filter: &UserFilter, after: Option<UserCursor>, limit: u32) -> Result<Vec<User>, DomainError> {
+        self.check()?;
+        let after = after.map(|c| (c.created_at, c.id));
+        Ok(self
+            .matching(filter)
+            .into_iter()
+            .filter(|u| after.is_none_or(|after| (u.created_at, u.id) < after))
+            .take(limit as usize)
+            .collect())
+    }
+
+    fn stream_all(&self, filter: &UserFilter) -> UserStream {
+        let users: Vec<Result<User, DomainError>> = match self.check() {
+            Ok(()) => self.matching(filter).into_iter().map(Ok).collect(),
+            Err(e) => vec![Err(e)],
+        };
+        Box::pin(futures_util::stream::iter(users))
+    }
+
+    async fn autocomplete(&self, prefix: &str, filter: &UserFilter, limit: u32) -> Result<Vec<User>, DomainError> {
+        self.check()?;
+        let prefix = prefix.to_lowercase();
+        let filter = UserFilter { query: None, ..filter.clone() };
+        let mut users: Vec<User> = self
+            .matching(&filter)
+            .into_iter()
+            .filter(|u| u.username.to_lowercase().starts_with(&prefix))
+            .collect();
+        users.sort_by_key(|u| (u.username.to_lowercase() != prefix, u.username.len(), u.username.clone()));
+        users.truncate(limit as usize);
+        Ok(users)
+    }
+
+    async fn bump_token_version(&self, id: Uuid) -> Result<i64, DomainError> {
+        self.check()?;
+        let mut users = self.users.lock().unwrap();
+        let user = users
+            .iter_mut()
+            .find(|u| u.id == id)
+            .ok_or_else(|| DomainError::not_found("User", id.to_string()))?;
+        user.token_version += 1;
+        Ok(user.token_version)
+    }
+}
+
+/// Views projected from the stored users
+#[async_trait]
+impl UserReadModel for MockUserRepository {
+    async fn find_view(&self, id: Uuid, tenant: Option<Uuid>) -> Result<Option<UserView>, DomainError> {
+        self.check()?;
+        let users = self.users.lock().unwrap();
+        Ok(users
+            .iter()
+            .find(|u| u.id == id && tenant.is_none_or(|t| u.tenant_id == t))
+            .map(UserView::from))
+    }
+
+    async fn list_views(&self, params: &PaginationParams, tenant: Option<Uuid>) -> Result<Page<UserView>, DomainError> {
+        self.check()?;
+        let filter = UserFilter {
+            tenant_id: tenant,
+            ..UserFilter::default()
+        };
+        let users = page(self.matching(&filter), params);
+        Ok(Page::new(users.items.iter().map(UserView::from).collect(), users.total, params))
+    }
+}
+
+// ============================================================================
+// Password Hasher
+// ============================================================================
+
+/// Reversible stand-in for Argon2: `hash("pw")` is `"mock$pw"`
+#[derive(Default)]
+pub struct MockPasswordHasher;
+
+impl MockPasswordHasher {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl PasswordHasher for MockPasswordHasher {
+    fn hash(&self, password: &str) -> Result<String, DomainError> {
+        Ok(format!("mock${}", password))
+    }
+
+    fn verify(&self, password: &str, hash: &str) -> Result<bool, DomainError> {
+        Ok(hash.strip_prefix("mock$") == Some(password))
+    }
+}
+
+// ============================================================================
+// Token Service
+// ============================================================================
+
+/// Issues opaque random tokens and remembers their claims; any other token
+/// is rejected as a real signature check would
+#[derive(Default)]
+pub struct MockTokenService {
+    issued: Mutex<HashMap<String, Claims>>,
+}
+
+impl MockTokenService {
+    /// Lifetime reported in `TokenPair::expires_in`
+    pub const EXPIRES_IN: i64 = 3600;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a token with hand-made claims (e.g. extra roles)
+    pub fn insert(&self, token: impl Into<String>, claims: Claims) {
+        self.issued.lock().unwrap().insert(token.into(), claims);
+    }
+
+    /// Tokens issued so far
+    pub fn issued(&self) -> usize {
+        self.issued.lock().unwrap().len()
+    }
+}
+
+impl TokenService for MockTokenService {
+    fn generate(&self, user: &User) -> Result<TokenPair, DomainError> {
+        let now = Utc::now().timestamp();
+        let token = format!("mock-{}", Uuid::new_v4());
+        self.insert(
+            token.clone(),
+            Claims {
+                sub: user.id.to_string(),
+                email: user.email.to_string(),
+                roles: vec!["user".to_string()],
+                exp: now + Self::EXPIRES_IN,
+                iat: now,
+                tenant_id: Some(user.tenant_id.to_string()),
+                token_version: user.token_version,
+                act: None,
+            },
+        );
+        Ok(TokenPair::new(token, Self::EXPIRES_IN))
+    }
+
+    fn generate_impersonation(&self, user: &User, actor_id: Uuid) -> Result<TokenPair, DomainError> {
+        let now = Utc::now().timestamp();
+        let token = format!("mock-{}", Uuid::new_v4());
+        self.insert(
+            token.clone(),
+            Claims {
+                sub: user.id.to_string(),
+                email: user.email.to_string(),
+                roles: vec!["user".to_string()],
+                exp: now + Self::EXPIRES_IN,
+                iat: now,
+                tenant_id: Some(user.tenant_id.to_string()),
+                token_version: user.token_version,
+                act: Some(actor_id.to_string()),
+            },
+        );
+        Ok(TokenPair::new(token, Self::EXPIRES_IN))
+    }
+
+    fn generate_guest(&self, guest_id: Uuid, tenant_id: Uuid) -> Result<TokenPair, DomainError> {
+        let now = Utc::now().timestamp();
+        let token = format!("mock-{}", Uuid::new_v4());
+        self.insert(
+            token.clone(),
+            Claims {
+                sub: guest_id.to_string(),
+                email: String::new(),
+                roles: vec![Claims::ANONYMOUS_ROLE.to_string()],
+                exp: now + Self::EXPIRES_IN,
+                iat: now,
+                tenant_id: Some(tenant_id.to_string()),
+                token_version: 0,
+                act: None,
+            },
+        );
+        Ok(TokenPair::new(token, Self::EXPIRES_IN))
+    }
+
+    fn validate(&self, token: &str) -> Result<Claims, DomainError> {
+        self.issued
+            .lock()
+            .unwrap()
+            .get(token)
+            .cloned()
+            .ok_or_else(|| DomainError::unauthorized("Invalid token"))
+    }
+}
+
+// ============================================================================
+// Job Queue
+// ============================================================================
+
+/// Records enqueued jobs instead of running them; nothing is ever claimed
+#[derive(Default)]
+pub struct RecordingJobQueue {
+    jobs: Mutex<Vec<(String, serde_json::Value, DateTime<Utc>)>>,
+}
+
+impl RecordingJobQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove and return the jobs of `kind`, oldest first
+    pub fn take(&self, kind: &str) -> Vec<(serde_json::Value, DateTime<Utc>)> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let (taken, rest) = jobs.drain(..).partition(|(k, _, _)| k == kind);
+        *jobs = rest;
+        taken.into_iter().map(|(_, payload, run_at)| (payload, run_at)).collect()
+    }
+
+    /// Payloads of the jobs of `kind` still queued, oldest first
+    pub fn payloads(&self, kind: &str) -> Vec<serde_json::Value> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(k, _, _)| k == kind)
+            .map(|(_, payload, _)| payload.clone())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl JobQueue for RecordingJobQueue {
+    async fn enqueue(&self, kind: &str, payload: serde_json::Value, run_at: DateTime<Utc>) -> Result<Uuid, ApplicationError> {
+        self.jobs.lock().unwrap().push((kind.to_string(), payload, run_at));
+        Ok(Uuid::new_v4())
+    }
+
+    async fn ensure_scheduled(&self, _kind: &str, _run_at: DateTime<Utc>) -> Result<(), ApplicationError> {
+        Ok(())
+    }
+
+    async fn claim(&self, _kinds: &[String], _stale_before: DateTime<Utc>) -> Result<Option<JobRecord>, ApplicationError> {
+        Ok(None)
+    }
+
+    async fn complete(&self, _id: Uuid) -> Result<(), ApplicationError> {
+        Ok(())
+    }
+
+    async fn fail(&self, _id: Uuid, _error: &str, _retry_at: Option<DateTime<Utc>>) -> Result<(), ApplicationError> {
+        Ok(())
+    }
+
+    async fn list(&self, _status: Option<JobStatus>, params: &PaginationParams) -> Result<Page<JobRecord>, ApplicationError> {
+        Ok(Page::new(Vec::new(), 0, params))
+    }
+
+    async fn counts(&self) -> Result<HashMap<JobStatus, u64>, ApplicationError> {
+        Ok(HashMap::new())
+    }
+
+    async fn prune(&self, _before: DateTime<Utc>) -> Result<u64, ApplicationError> {
+        Ok(0)
+    }
+}
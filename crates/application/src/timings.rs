@@ -0,0 +1,88 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+// ============================================================================
+// Request Timings
+// ============================================================================
+
+/// Checking the caller's token, their token version and roles
+pub const AUTH: &str = "auth";
+/// Database queries, from sending the statement to the last row decoded
+pub const DB: &str = "db";
+/// Encoding the response body
+pub const SERIALIZE: &str = "serialize";
+
+/// Time one request spent in each phase, for the `Server-Timing` header.
+/// Phases are summed over every time they are entered and may overlap: the
+/// queries made while authenticating count towards both `auth` and `db`.
+#[derive(Debug, Default)]
+pub struct RequestTimings {
+    phases: Mutex<Vec<PhaseTiming>>,
+}
+
+/// Total time spent in one phase, and how many times it was entered
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseTiming {
+    pub phase: &'static str,
+    pub elapsed: Duration,
+    pub count: u32,
+}
+
+impl RequestTimings {
+    pub fn record(&self, phase: &'static str, elapsed: Duration) {
+        let mut phases = self.phases.lock().unwrap();
+        match phases.iter_mut().find(|p| p.phase == phase) {
+            Some(timing) => {
+                timing.elapsed += elapsed;
+                timing.count += 1;
+            }
+            None => phases.push(PhaseTiming { phase, elapsed, count: 1 }),
+        }
+    }
+
+    /// Phases in the order they were first entered
+    pub fn phases(&self) -> Vec<PhaseTiming> {
+        self.phases.lock().unwrap().clone()
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_TIMINGS: Arc<RequestTimings>;
+}
+
+/// Run `work` with `timings` collecting the phases it goes through
+pub async fn with_timings<F: Future>(timings: Arc<RequestTimings>, work: F) -> F::Output {
+    CURRENT_TIMINGS.scope(timings, work).await
+}
+
+/// The timings collected on the current task, if any
+pub fn current_timings() -> Option<Arc<RequestTimings>> {
+    CURRENT_TIMINGS.try_with(Arc::clone).ok()
+}
+
+/// Start timing `phase` of the current request; the time is recorded when
+/// the returned timer is dropped. Outside a request nothing is recorded.
+pub fn start(phase: &'static str) -> PhaseTimer {
+    PhaseTimer {
+        phase,
+        started: Instant::now(),
+        timings: current_timings(),
+    }
+}
+
+/// Records its phase's elapsed time when dropped (see `start`)
+#[must_use = "the phase ends when the timer is dropped"]
+pub struct PhaseTimer {
+    phase: &'static str,
+    started: Instant,
+    timings: Option<Arc<RequestTimings>>,
+}
+
+impl Drop for PhaseTimer {
+    fn drop(&mut self) {
+        if let Some(timings) = &self.timings {
+            timings.record(self.phase, self.started.elapsed());
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use domain::{Claims, DomainError, UserRepository};
+use uuid::Uuid;
+
+use crate::{ApplicationError, TokenService};
+
+// ============================================================================
+// Token Versions
+// ============================================================================
+
+/// How long a user's token version is reused before it is read again
+pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Cached versions before the cache starts over
+const CACHE_ENTRIES: usize = 10_000;
+
+/// Soft logout-all. Every token carries the user's token version at issue;
+/// bumping the version rejects all of them without a revocation list.
+///
+/// Versions are cached for a few seconds so checking one is not a query per
+/// request. The instance that bumps a version forgets it at once; other
+/// instances notice within the cache TTL.
+pub struct TokenVersions {
+    users: Arc<dyn UserRepository>,
+    ttl: Duration,
+    cache: Mutex<HashMap<Uuid, (Instant, i64)>>,
+}
+
+impl TokenVersions {
+    pub fn new(users: Arc<dyn UserRepository>) -> Self {
+        Self {
+            users,
+            ttl: DEFAULT_CACHE_TTL,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Zero reads the version on every check
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// The user's current token version; `None` for unknown users
+    pub async fn current(&self, user_id: Uuid) -> Result<Option<i64>, ApplicationError> {
+        if let Some((at, version)) = self.cache.lock().unwrap().get(&user_id) {
+            if at.elapsed() < self.ttl {
+                return Ok(Some(*version));
+            }
+        }
+
+        let Some(user) = self.users.find_by_id(user_id).await? else {
+            return Ok(None);
+        };
+        self.remember(user_id, user.token_version);
+        Ok(Some(user.token_version))
+    }
+
+    /// Reject tokens issued before the user's last logout-all. Tokens of
+    /// unknown users have nothing to compare against and pass.
+    pub async fn check(&self, claims: &Claims) -> Result<(), ApplicationError> {
+        let Ok(user_id) = claims.sub.parse() else {
+            return Ok(());
+        };
+        match self.current(user_id).await? {
+            Some(version) if claims.token_version < version => {
+                Err(DomainError::unauthorized("Token has been revoked; sign in again").into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Validate `token` and reject it if revoked; for transports that take
+    /// tokens outside the HTTP auth middleware (WebSockets, gRPC)
+    pub async fn authenticate(&self, tokens: &dyn TokenService, token: &str) -> Result<Claims, ApplicationError> {
+        let claims = tokens.validate(token)?;
+        self.check(&claims).await?;
+        Ok(claims)
+    }
+
+    /// Invalidate every access and refresh token of the user; returns the new version
+    pub async fn revoke_all(&self, user_id: Uuid) -> Result<i64, ApplicationError> {
+        let version = self.users.bump_token_version(user_id).await?;
+        self.remember(user_id, version);
+        tracing::info!(target: "audit", user_id = %user_id, token_version = version, "Signed out everywhere");
+        Ok(version)
+    }
+
+    fn remember(&self, user_id: Uuid, version: i64) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut cache = self.cache.lock().unwrap();
+        if cache.len() >= CACHE_ENTRIES {
+            cache.retain(|_, (at, _)| at.elapsed() < self.ttl);
+            if cache.len() >= CACHE_ENTRIES {
+                cache.clear();
+            }
+        }
+        cache.insert(user_id, (Instant::now(), version));
+    }
+}
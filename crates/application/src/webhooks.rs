@@ -0,0 +1,265 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use domain::{
+    DeliveryStatus, DomainError, DomainEvent, Page, PaginationParams, Webhook, WebhookDelivery,
+    WebhookDeliveryRepository, WebhookRepository,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::event_schema::{self, EventUpcasters, VersionedEvent};
+use crate::jobs::{Job, JobQueue};
+use crate::{ApplicationError, EventBus};
+
+// ============================================================================
+// Webhook Ports
+// ============================================================================
+
+/// Outbound HTTP for webhook deliveries. Adapters sign `delivery.payload`
+/// with the webhook secret, POST it to `webhook.url` and return the HTTP
+/// status; an `Err` means the endpoint could not be reached at all.
+#[async_trait]
+pub trait WebhookSender: Send + Sync {
+    async fn send(&self, webhook: &Webhook, delivery: &WebhookDelivery) -> Result<u16, ApplicationError>;
+}
+
+/// New webhook registration
+#[derive(Debug, Clone)]
+pub struct CreateWebhook {
+    pub url: String,
+    /// Generated when not provided
+    pub secret: Option<String>,
+    /// Event names to deliver; empty means all
+    pub events: Vec<String>,
+}
+
+#[async_trait]
+pub trait WebhookService: Send + Sync {
+    /// Register an endpoint; the returned webhook carries its secret
+    async fn create(&self, request: CreateWebhook) -> Result<Webhook, ApplicationError>;
+    async fn list(&self, params: &PaginationParams) -> Result<Page<Webhook>, ApplicationError>;
+    /// Remove an endpoint together with its delivery history
+    async fn delete(&self, id: Uuid) -> Result<(), ApplicationError>;
+    /// Delivery history of one webhook, newest first
+    async fn deliveries(&self, webhook_id: Uuid, params: &PaginationParams) -> Result<Page<WebhookDelivery>, ApplicationError>;
+}
+
+// ============================================================================
+// Webhook Service
+// ============================================================================
+
+pub struct WebhookServiceImpl {
+    webhooks: Arc<dyn WebhookRepository>,
+    deliveries: Arc<dyn WebhookDeliveryRepository>,
+}
+
+impl WebhookServiceImpl {
+    pub fn new(webhooks: Arc<dyn WebhookRepository>, deliveries: Arc<dyn WebhookDeliveryRepository>) -> Self {
+        Self { webhooks, deliveries }
+    }
+
+    async fn find(&self, id: Uuid) -> Result<Webhook, ApplicationError> {
+        self.webhooks
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| DomainError::not_found("Webhook", id.to_string()).into())
+    }
+}
+
+#[async_trait]
+impl WebhookService for WebhookServiceImpl {
+    async fn create(&self, request: CreateWebhook) -> Result<Webhook, ApplicationError> {
+        if !(request.url.starts_with("https://") || request.url.starts_with("http://")) {
+            return Err(DomainError::validation("Webhook URL must be http(s)").into());
+        }
+        if let Some(unknown) = request.events.iter().find(|e| !DomainEvent::NAMES.contains(&e.as_str())) {
+            return Err(DomainError::validation(format!("Unknown event '{}'", unknown)).into());
+        }
+
+        let secret = request
+            .secret
+            .unwrap_or_else(|| format!("whsec_{}", Uuid::new_v4().simple()));
+        let webhook = Webhook::new(request.url, secret, request.events);
+        Ok(self.webhooks.create(&webhook).await?)
+    }
+
+    async fn list(&self, params: &PaginationParams) -> Result<Page<Webhook>, ApplicationError> {
+        Ok(self.webhooks.find_all(params).await?)
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<(), ApplicationError> {
+        if !self.webhooks.delete(id).await? {
+            return Err(DomainError::not_found("Webhook", id.to_string()).into());
+        }
+        Ok(())
+    }
+
+    async fn deliveries(&self, webhook_id: Uuid, params: &PaginationParams) -> Result<Page<WebhookDelivery>, ApplicationError> {
+        self.find(webhook_id).await?;
+        Ok(self.deliveries.find_by_webhook(webhook_id, params).await?)
+    }
+}
+
+// ============================================================================
+// Delivery Job
+// ============================================================================
+
+/// Attempts per delivery before it is marked failed
+pub const MAX_DELIVERY_ATTEMPTS: u32 = 8;
+
+/// Payload of a `webhook.deliver` job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookJobPayload {
+    pub delivery_id: Uuid,
+}
+
+/// Sends one delivery; failures are retried by the job runner with backoff
+pub struct DeliverWebhookJob {
+    webhooks: Arc<dyn WebhookRepository>,
+    deliveries: Arc<dyn WebhookDeliveryRepository>,
+    sender: Arc<dyn WebhookSender>,
+    upcasters: Arc<EventUpcasters>,
+}
+
+impl DeliverWebhookJob {
+    pub const KIND: &'static str = "webhook.deliver";
+
+    pub fn new(
+        webhooks: Arc<dyn WebhookRepository>,
+        deliveries: Arc<dyn WebhookDeliveryRepository>,
+        sender: Arc<dyn WebhookSender>,
+    ) -> Self {
+        Self {
+            webhooks,
+            deliveries,
+            sender,
+            upcasters: Arc::new(event_schema::upcasters()),
+        }
+    }
+
+    /// Replace the build's upcasters (`event_schema::upcasters`)
+    pub fn with_upcasters(mut self, upcasters: Arc<EventUpcasters>) -> Self {
+        self.upcasters = upcasters;
+        self
+    }
+}
+
+#[async_trait]
+impl Job for DeliverWebhookJob {
+    fn kind(&self) -> &'static str {
+        Self::KIND
+    }
+
+    fn max_attempts(&self) -> u32 {
+        MAX_DELIVERY_ATTEMPTS
+    }
+
+    async fn run(&self, payload: serde_json::Value) -> Result<(), ApplicationError> {
+        let payload: WebhookJobPayload = serde_json::from_value(payload)
+            .map_err(|e| ApplicationError::use_case(format!("Invalid webhook payload: {}", e)))?;
+
+        // Deleting a webhook removes its deliveries; nothing left to send
+        let Some(mut delivery) = self.deliveries.find_by_id(payload.delivery_id).await? else {
+            return Ok(());
+        };
+        let Some(webhook) = self.webhooks.find_by_id(delivery.webhook_id).await? else {
+            return Ok(());
+        };
+        if delivery.status == DeliveryStatus::Succeeded {
+            return Ok(());
+        }
+
+        // Queued before a schema change: send (and keep) the current shape
+        let event = self.upcasters.upcast(VersionedEvent::parse(&delivery.payload)?)?;
+        delivery.payload = event.to_json()?;
+
+        delivery.attempts += 1;
+        let error = match self.sender.send(&webhook, &delivery).await {
+            Ok(status) => {
+                delivery.response_status = Some(status);
+                (!(200..300).contains(&status)).then(|| format!("Endpoint answered HTTP {}", status))
+            }
+            Err(e) => {
+                delivery.response_status = None;
+                Some(e.to_string())
+            }
+        };
+
+        match error {
+            None => {
+                delivery.status = DeliveryStatus::Succeeded;
+                delivery.last_error = None;
+                delivery.delivered_at = Some(Utc::now());
+                self.deliveries.update(&delivery).await?;
+                Ok(())
+            }
+            Some(error) => {
+                delivery.status = if delivery.attempts >= MAX_DELIVERY_ATTEMPTS {
+                    DeliveryStatus::Failed
+                } else {
+                    DeliveryStatus::Pending
+                };
+                delivery.last_error = Some(error.clone());
+                self.deliveries.update(&delivery).await?;
+                Err(ApplicationError::use_case(error))
+            }
+        }
+    }
+}
+
+/// Record a delivery and queue a `webhook.deliver` job for every webhook
+/// subscribed to each published event. The payload is the serialized
+/// `VersionedEvent`.
+pub fn spawn_webhook_dispatcher(
+    event_bus: Arc<dyn EventBus>,
+    webhooks: Arc<dyn WebhookRepository>,
+    deliveries: Arc<dyn WebhookDeliveryRepository>,
+    queue: Arc<dyn JobQueue>,
+) {
+    let mut events = event_bus.subscribe();
+    tokio::spawn(async move {
+        loop {
+            let envelope = match events.recv().await {
+                Ok(envelope) => envelope,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Webhook dispatcher lagged behind the event bus");
+                    continue;
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            };
+
+            let event = envelope.event.name();
+            let subscribed = match webhooks.find_subscribed(event).await {
+                Ok(subscribed) => subscribed,
+                Err(e) => {
+                    tracing::error!(event, "Failed to load webhooks: {}", e);
+                    continue;
+                }
+            };
+            if subscribed.is_empty() {
+                continue;
+            }
+
+            let payload = match VersionedEvent::new(&envelope).and_then(|event| event.to_json()) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    tracing::error!(event, "Failed to serialize event for webhooks: {}", e);
+                    continue;
+                }
+            };
+
+            for webhook in subscribed {
+                let delivery = WebhookDelivery::new(webhook.id, envelope.id, event.to_string(), payload.clone());
+                let queued = async {
+                    let delivery = deliveries.create(&delivery).await?;
+                    let job = serde_json::json!({ "delivery_id": delivery.id });
+                    queue.enqueue(DeliverWebhookJob::KIND, job, Utc::now()).await
+                };
+                if let Err(e) = queued.await {
+                    tracing::error!(webhook_id = %webhook.id, event, "Failed to queue webhook delivery: {}", e);
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,212 @@
+//! Typed async client for the rust_base HTTP API.
+
+pub mod models;
+
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+
+pub use models::*;
+
+// ============================================================================
+// Client Errors
+// ============================================================================
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    /// Connection, timeout or body decoding failure
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The API answered with an error response
+    #[error("{status} {code}: {message}")]
+    Api {
+        status: StatusCode,
+        code: String,
+        message: String,
+        details: Option<serde_json::Value>,
+        /// Invalid request fields, on a 422 "VALIDATION_ERROR"
+        errors: Vec<FieldError>,
+    },
+}
+
+impl ClientError {
+    /// HTTP status of an API error
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Api { status, .. } => Some(*status),
+            Self::Http(err) => err.status(),
+        }
+    }
+
+    /// Machine-readable code of an API error, e.g. "NOT_FOUND"
+    pub fn code(&self) -> Option<&str> {
+        match self {
+            Self::Api { code, .. } => Some(code),
+            Self::Http(_) => None,
+        }
+    }
+}
+
+// ============================================================================
+// Client
+// ============================================================================
+
+/// Client for one API deployment. Cheap to clone; clones share the
+/// connection pool.
+#[derive(Debug, Clone)]
+pub struct RustBaseClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl RustBaseClient {
+    /// `base_url` is the server root, e.g. `http://localhost:3000`
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token: None,
+        }
+    }
+
+    /// Use a preconfigured `reqwest` client (timeouts, proxies, ...)
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Send `token` as the bearer token on every request
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn set_token(&mut self, token: Option<String>) {
+        self.token = token;
+    }
+
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    // ------------------------------------------------------------------------
+    // Health
+    // ------------------------------------------------------------------------
+
+    pub async fn health(&self) -> Result<HealthResponse, ClientError> {
+        self.json(self.request(Method::GET, "/health")).await
+    }
+
+    // ------------------------------------------------------------------------
+    // Authentication
+    // ------------------------------------------------------------------------
+
+    pub async fn register(&self, request: &RegisterRequest) -> Result<AuthResponse, ClientError> {
+        self.json(self.request(Method::POST, "/api/v1/auth/register").json(request)).await
+    }
+
+    /// Log in and keep the access token for later calls
+    pub async fn login(&mut self, email: &str, password: &str) -> Result<TokenResponse, ClientError> {
+        let request = LoginRequest {
+            email: email.to_string(),
+            password: password.to_string(),
+            ..LoginRequest::default()
+        };
+        let token: TokenResponse = self.json(self.request(Method::POST, "/api/v1/auth/login").json(&request)).await?;
+        self.token = Some(token.access_token.clone());
+        Ok(token)
+    }
+
+    /// Exchange a refresh token and keep the new access token. The old
+    /// refresh token is spent: use the one returned from now on.
+    pub async fn refresh(&mut self, refresh_token: &str) -> Result<TokenResponse, ClientError> {
+        let request = RefreshRequest {
+            refresh_token: refresh_token.to_string(),
+        };
+        let token: TokenResponse = self.json(self.request(Method::POST, "/api/v1/auth/refresh").json(&request)).await?;
+        self.token = Some(token.access_token.clone());
+        Ok(token)
+    }
+
+    pub async fn change_password(&self, current_password: &str, new_password: &str) -> Result<(), ClientError> {
+        let request = ChangePasswordRequest {
+            current_password: current_password.to_string(),
+            new_password: new_password.to_string(),
+        };
+        self.send(self.request(Method::PUT, "/api/v1/me/password").json(&request)).await?;
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------------
+    // Users
+    // ------------------------------------------------------------------------
+
+    pub async fn me(&self) -> Result<UserResponse, ClientError> {
+        self.json(self.request(Method::GET, "/api/v1/me")).await
+    }
+
+    pub async fn get_user(&self, id: &str) -> Result<UserResponse, ClientError> {
+        self.json(self.request(Method::GET, &format!("/api/v1/users/{}", id))).await
+    }
+
+    /// Users among `ids` that exist, keyed by id
+    pub async fn get_users(&self, ids: &[&str]) -> Result<BatchUsersResponse, ClientError> {
+        let request = BatchUsersRequest {
+            ids: ids.iter().map(|id| id.to_string()).collect(),
+        };
+        self.json(self.request(Method::POST, "/api/v1/users/batch").json(&request)).await
+    }
+
+    pub async fn list_users(&self, page: u32, per_page: u32) -> Result<PaginatedUserResponse, ClientError> {
+        let query = [("page", page), ("per_page", per_page)];
+        self.json(self.request(Method::GET, "/api/v1/users").query(&query)).await
+    }
+
+    pub async fn autocomplete_users(&self, prefix: &str, limit: u32) -> Result<UserSuggestionsResponse, ClientError> {
+        let query = [("q", prefix.to_string()), ("limit", limit.to_string())];
+        self.json(self.request(Method::GET, "/api/v1/users/autocomplete").query(&query)).await
+    }
+
+    // ------------------------------------------------------------------------
+    // Plumbing
+    // ------------------------------------------------------------------------
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self.http.request(method, format!("{}{}", self.base_url, path));
+        match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Response, ClientError> {
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await?;
+        Err(match serde_json::from_str::<ErrorResponse>(&body) {
+            Ok(ErrorResponse { error }) => ClientError::Api {
+                status,
+                code: error.code,
+                message: error.message,
+                details: error.details,
+                errors: error.errors,
+            },
+            // Proxies and load balancers answer with their own bodies
+            Err(_) => ClientError::Api {
+                status,
+                code: status.as_str().to_string(),
+                message: body,
+                details: None,
+                errors: Vec::new(),
+            },
+        })
+    }
+
+    async fn json<T: DeserializeOwned>(&self, request: RequestBuilder) -> Result<T, ClientError> {
+        Ok(self.send(request).await?.json().await?)
+    }
+}
@@ -1,8 +1,14 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use std::collections::{BTreeSet, HashMap};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use chrono::{DateTime, Utc};
 
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
 // ============================================================================
 // Domain Errors
 // ============================================================================
@@ -20,16 +26,34 @@ pub enum DomainError {
     Validation(String),
 
     /// Conflict errors (duplicate entries, concurrent modifications)
-    #[error("Conflict: {0}")]
-    Conflict(String),
+    #[error("Conflict: {message}")]
+    Conflict {
+        message: String,
+        /// Stored version, when an optimistic concurrency check failed
+        current_version: Option<i64>,
+    },
 
     /// Internal/unexpected errors (database failures, etc.)
     #[error("Internal error: {0}")]
     Internal(String),
 
-    /// Authentication/Authorization errors
+    /// Authentication errors (missing or invalid credentials)
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
+
+    /// The caller is known but not allowed to do this
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    /// A dependency failed transiently (dropped connection, deadlock,
+    /// serialization failure); retrying the same call may succeed
+    #[error("Temporarily unavailable: {0}")]
+    Unavailable(String),
+
+    /// A dependency did not answer in time (statement timeout, exhausted
+    /// pool). Not worth retrying right away: the retry would likely wait as long.
+    #[error("Timed out: {0}")]
+    Timeout(String),
 }
 
 impl DomainError {
@@ -48,7 +72,23 @@ impl DomainError {
 
     /// Create a conflict error (e.g., duplicate username)
     pub fn conflict<T: Into<String>>(message: T) -> Self {
-        Self::Conflict(message.into())
+        Self::Conflict {
+            message: message.into(),
+            current_version: None,
+        }
+    }
+
+    /// Create a conflict error for an update based on a stale version
+    pub fn version_conflict(entity: &'static str, id: impl AsRef<str>, current_version: i64) -> Self {
+        Self::Conflict {
+            message: format!(
+                "{} {} was modified concurrently (current version {})",
+                entity,
+                id.as_ref(),
+                current_version
+            ),
+            current_version: Some(current_version),
+        }
     }
 
     /// Create an internal error
@@ -60,8 +100,218 @@ impl DomainError {
     pub fn unauthorized<T: Into<String>>(message: T) -> Self {
         Self::Unauthorized(message.into())
     }
+
+    /// Create a forbidden error (authenticated, but not permitted)
+    pub fn forbidden<T: Into<String>>(message: T) -> Self {
+        Self::Forbidden(message.into())
+    }
+
+    /// Create an unavailable error (transient failures)
+    pub fn unavailable<T: Into<String>>(message: T) -> Self {
+        Self::Unavailable(message.into())
+    }
+
+    /// Create a timeout error
+    pub fn timeout<T: Into<String>>(message: T) -> Self {
+        Self::Timeout(message.into())
+    }
 }
 
+// ============================================================================
+// Value Objects
+// ============================================================================
+
+/// A user's email address, trimmed and lowercased (ASCII) so each address
+/// has one spelling. Only the shape is checked: something before a single
+/// `@`, and a dotted domain after it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Email(String);
+
+impl Email {
+    /// Longest address SMTP can deliver to
+    pub const MAX_LEN: usize = 254;
+
+    pub fn parse(value: impl AsRef<str>) -> Result<Self, DomainError> {
+        let email = value.as_ref().trim().to_ascii_lowercase();
+        if email.is_empty() {
+            return Err(DomainError::validation("Email cannot be empty"));
+        }
+        if email.len() > Self::MAX_LEN {
+            return Err(DomainError::validation(format!("Email cannot be longer than {} characters", Self::MAX_LEN)));
+        }
+        let valid = match email.split_once('@') {
+            Some((local, domain)) => {
+                !local.is_empty()
+                    && !domain.contains('@')
+                    && domain.contains('.')
+                    && !domain.starts_with('.')
+                    && !domain.ends_with('.')
+                    && !email.chars().any(|c| c.is_whitespace() || c.is_control())
+            }
+            None => false,
+        };
+        if !valid {
+            return Err(DomainError::validation("Email is not a valid address"));
+        }
+        Ok(Self(email))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The part after the `@`
+    pub fn domain(&self) -> &str {
+        self.0.rsplit_once('@').map(|(_, domain)| domain).unwrap_or_default()
+    }
+
+    /// The key accounts are unique by (`users.email_normalized`), with the
+    /// process-wide gmail setting (see `set_ignore_gmail_dots`)
+    pub fn normalized(&self) -> String {
+        self.normalized_with(ignore_gmail_dots())
+    }
+
+    /// The address itself, or with `ignore_gmail_dots` and a Gmail domain,
+    /// without the dots in the local part, which Gmail ignores when delivering
+    pub fn normalized_with(&self, ignore_gmail_dots: bool) -> String {
+        match self.0.rsplit_once('@') {
+            Some((local, domain)) if ignore_gmail_dots && GMAIL_DOMAINS.contains(&domain) => {
+                format!("{}@{}", local.replace('.', ""), domain)
+            }
+            _ => self.0.clone(),
+        }
+    }
+}
+
+/// Domains whose local part Gmail delivers regardless of dots
+pub const GMAIL_DOMAINS: [&str; 2] = ["gmail.com", "googlemail.com"];
+
+static IGNORE_GMAIL_DOTS: AtomicBool = AtomicBool::new(false);
+
+/// Whether `Email::normalized` drops dots from Gmail addresses (`account.ignore_gmail_dots`)
+pub fn ignore_gmail_dots() -> bool {
+    IGNORE_GMAIL_DOTS.load(Ordering::Relaxed)
+}
+
+/// Set the process-wide Gmail normalization, once at startup
+pub fn set_ignore_gmail_dots(ignore: bool) {
+    IGNORE_GMAIL_DOTS.store(ignore, Ordering::Relaxed);
+}
+
+/// `Email::normalized` for an address that may not parse, e.g. a lookup
+/// key straight from a caller: trimmed and lowercased at least
+pub fn normalize_email(email: &str) -> String {
+    match Email::parse(email) {
+        Ok(email) => email.normalized(),
+        Err(_) => email.trim().to_ascii_lowercase(),
+    }
+}
+
+/// A user's display handle, trimmed and 3 to 50 characters long. Case is
+/// kept as entered.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Username(String);
+
+impl Username {
+    pub const MIN_LEN: usize = 3;
+    pub const MAX_LEN: usize = 50;
+
+    pub fn parse(value: impl AsRef<str>) -> Result<Self, DomainError> {
+        let username = value.as_ref().trim();
+        if username.is_empty() {
+            return Err(DomainError::validation("Username cannot be empty"));
+        }
+        let len = username.chars().count();
+        if !(Self::MIN_LEN..=Self::MAX_LEN).contains(&len) {
+            return Err(DomainError::validation(format!(
+                "Username must be {} to {} characters",
+                Self::MIN_LEN,
+                Self::MAX_LEN
+            )));
+        }
+        if username.chars().any(char::is_control) {
+            return Err(DomainError::validation("Username cannot contain control characters"));
+        }
+        Ok(Self(username.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Conversions shared by the string value objects. Every way of building
+/// one (serde, `FromStr`, `TryFrom`) goes through `parse`, so an invalid
+/// value never exists; it reads like a `&str` through `Deref`.
+macro_rules! string_value_object {
+    ($name:ident) => {
+        impl std::ops::Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = DomainError;
+
+            fn from_str(value: &str) -> Result<Self, DomainError> {
+                Self::parse(value)
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = DomainError;
+
+            fn try_from(value: String) -> Result<Self, DomainError> {
+                Self::parse(value)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(value: $name) -> String {
+                value.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<String> for $name {
+            fn eq(&self, other: &String) -> bool {
+                &self.0 == other
+            }
+        }
+    };
+}
+
+string_value_object!(Email);
+string_value_object!(Username);
+
 // ============================================================================
 // Domain Entities
 // ============================================================================
@@ -69,21 +319,622 @@ impl DomainError {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct User {
     pub id: Uuid,
-    pub username: String,
-    pub email: String,
+    pub username: Username,
+    pub email: Email,
     #[serde(skip_serializing)] // Never expose password hash in responses
     pub password_hash: String,
+    /// Public URL of the uploaded avatar
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+    #[serde(default)]
+    pub status: UserStatus,
+    /// Set by an admin; cleared when the user changes their password
+    #[serde(default)]
+    pub password_reset_required: bool,
+    /// Owning tenant; `Tenant::DEFAULT_ID` for single-tenant deployments
+    #[serde(default = "default_tenant")]
+    pub tenant_id: Uuid,
     pub created_at: DateTime<Utc>,
+    /// Bumped by every update; the basis of HTTP ETags
+    pub updated_at: DateTime<Utc>,
+    /// Optimistic concurrency version, incremented by every update. Updating
+    /// a stale copy fails with `DomainError::Conflict`.
+    #[serde(default = "initial_version")]
+    pub version: i64,
+    /// Stamped into every token issued to the user; bumping it invalidates
+    /// all of them at once
+    #[serde(default)]
+    pub token_version: i64,
+    /// Last successful sign-in
+    #[serde(default)]
+    pub last_login_at: Option<DateTime<Utc>>,
+    /// Last authenticated request, written at most once per debounce
+    /// interval (see `application::activity`), so it can lag a few minutes
+    #[serde(default)]
+    pub last_seen_at: Option<DateTime<Utc>>,
+}
+
+fn initial_version() -> i64 {
+    1
+}
+
+fn default_tenant() -> Uuid {
+    Tenant::DEFAULT_ID
 }
 
 impl User {
-    pub fn new(username: String, email: String, password_hash: String) -> Self {
+    pub fn new(username: Username, email: Email, password_hash: String) -> Self {
+        let now = Utc::now();
         Self {
             id: Uuid::new_v4(),
             username,
             email,
             password_hash,
+            avatar_url: None,
+            status: UserStatus::Active,
+            password_reset_required: false,
+            tenant_id: Tenant::DEFAULT_ID,
+            created_at: now,
+            updated_at: now,
+            version: initial_version(),
+            token_version: 0,
+            last_login_at: None,
+            last_seen_at: None,
+        }
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.status == UserStatus::Suspended
+    }
+}
+
+/// Read-side projection of a user: what queries display, without the
+/// password hash or token version, which are never loaded for it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserView {
+    pub id: Uuid,
+    pub username: Username,
+    pub email: Email,
+    pub avatar_url: Option<String>,
+    pub status: UserStatus,
+    pub password_reset_required: bool,
+    pub tenant_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub version: i64,
+}
+
+impl From<&User> for UserView {
+    fn from(user: &User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username.clone(),
+            email: user.email.clone(),
+            avatar_url: user.avatar_url.clone(),
+            status: user.status,
+            password_reset_required: user.password_reset_required,
+            tenant_id: user.tenant_id,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+            version: user.version,
+        }
+    }
+}
+
+/// Whether a user may sign in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserStatus {
+    #[default]
+    Active,
+    /// Blocked by an admin: login is refused
+    Suspended,
+}
+
+impl UserStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Suspended => "suspended",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "active" => Some(Self::Active),
+            "suspended" => Some(Self::Suspended),
+            _ => None,
+        }
+    }
+}
+
+/// Admin user search criteria; unset fields match everything
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserFilter {
+    /// Case-insensitive substring of the username or email
+    pub query: Option<String>,
+    pub status: Option<UserStatus>,
+    /// Restrict to one tenant (set by the tenant-scoped repository)
+    #[serde(skip)]
+    pub tenant_id: Option<Uuid>,
+    /// Users carrying every one of these (normalized) tag names
+    pub tags: Vec<String>,
+}
+
+/// Position in a newest-first listing of users: the last user already seen.
+/// Listings continue strictly after it, so paging by cursor does not slow down
+/// or skip rows the way deep offsets do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl From<&User> for UserCursor {
+    fn from(user: &User) -> Self {
+        Self {
+            created_at: user.created_at,
+            id: user.id,
+        }
+    }
+}
+
+/// Customer organization owning a set of users
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tenant {
+    pub id: Uuid,
+    /// Lowercase subdomain label, e.g. `acme` for `acme.example.com`
+    pub slug: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Tenant {
+    /// Tenant of requests that name none; created by the migration and never deleted
+    pub const DEFAULT_ID: Uuid = Uuid::nil();
+
+    pub fn new(slug: String, name: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            slug: slug.to_ascii_lowercase(),
+            name,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Label attached to entities of any `Taggable` type; names are unique per tenant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    /// Normalized, see `Tag::normalize`
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Tag {
+    pub const MAX_NAME_LEN: usize = 50;
+
+    pub fn new(tenant_id: Uuid, name: &str) -> Result<Self, DomainError> {
+        Ok(Self {
+            id: Uuid::new_v4(),
+            tenant_id,
+            name: Self::normalize(name)?,
             created_at: Utc::now(),
+        })
+    }
+
+    /// Lowercase `name`, which must then be 1-50 letters, digits, `-`, `_` or `:`
+    pub fn normalize(name: &str) -> Result<String, DomainError> {
+        let name = name.trim().to_lowercase();
+        let valid = !name.is_empty()
+            && name.len() <= Self::MAX_NAME_LEN
+            && name
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'-' | b'_' | b':'));
+        if !valid {
+            return Err(DomainError::validation(format!(
+                "Tag '{}' must be 1-{} letters, digits, '-', '_' or ':'",
+                name,
+                Self::MAX_NAME_LEN
+            )));
+        }
+        Ok(name)
+    }
+}
+
+/// Who can read an admin note
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoteVisibility {
+    /// Every admin of the tenant
+    #[default]
+    Team,
+    /// Only the author
+    Private,
+}
+
+impl NoteVisibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Team => "team",
+            Self::Private => "private",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "team" => Some(Self::Team),
+            "private" => Some(Self::Private),
+            _ => None,
+        }
+    }
+}
+
+/// Internal note left on a user by an admin (support and ops context); never
+/// shown to the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserNote {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// Admin who wrote the note; only they may change or delete it
+    pub author_id: Uuid,
+    pub body: String,
+    pub visibility: NoteVisibility,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UserNote {
+    pub fn new(user_id: Uuid, author_id: Uuid, body: String, visibility: NoteVisibility) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            author_id,
+            body,
+            visibility,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Whether `reader_id` may see the note
+    pub fn is_visible_to(&self, reader_id: Uuid) -> bool {
+        self.visibility == NoteVisibility::Team || self.author_id == reader_id
+    }
+}
+
+/// In-app message to a user, e.g. about something that happened to their
+/// account. Shown in the user's inbox until they read it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// What it is about, e.g. `welcome` or `file_quarantined`; clients pick icons and links by it
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Notification {
+    pub fn new(user_id: Uuid, kind: impl Into<String>, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            kind: kind.into(),
+            title: title.into(),
+            body: body.into(),
+            read_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    pub fn is_read(&self) -> bool {
+        self.read_at.is_some()
+    }
+}
+
+/// Saved admin user search, e.g. "active VIPs". Its filter is evaluated
+/// whenever the segment is used, so membership follows the users' data.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserSegment {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    /// Unique per tenant, case-insensitively
+    pub name: String,
+    pub filter: UserFilter,
+    /// Admin who saved it
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UserSegment {
+    pub const MAX_NAME_LEN: usize = 100;
+
+    pub fn new(tenant_id: Uuid, name: String, filter: UserFilter, created_by: Uuid) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            tenant_id,
+            name,
+            filter,
+            created_by,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Which notifications a user receives. Stored as JSONB; keys missing from
+/// a stored document take their defaults.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationPreferences {
+    pub email: bool,
+    pub push: bool,
+    /// New sign-ins, password and email changes
+    pub security_alerts: bool,
+    pub product_updates: bool,
+    pub weekly_digest: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            email: true,
+            push: true,
+            security_alerts: true,
+            product_updates: false,
+            weekly_digest: false,
+        }
+    }
+}
+
+/// A user's own preferences, one row per user. Users who never saved any
+/// get `UserSettings::defaults`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserSettings {
+    pub user_id: Uuid,
+    /// BCP 47 language tag, e.g. `en` or `pt-BR`
+    pub locale: String,
+    /// IANA time zone name, e.g. `Europe/Berlin`
+    pub timezone: String,
+    pub notifications: NotificationPreferences,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl UserSettings {
+    pub const DEFAULT_LOCALE: &'static str = "en";
+    pub const DEFAULT_TIMEZONE: &'static str = "UTC";
+
+    pub fn defaults(user_id: Uuid) -> Self {
+        let now = Utc::now();
+        Self {
+            user_id,
+            locale: Self::DEFAULT_LOCALE.to_string(),
+            timezone: Self::DEFAULT_TIMEZONE.to_string(),
+            notifications: NotificationPreferences::default(),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Group of users sharing resources, e.g. a company or a team
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    /// User who created it, its first owner
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Organization {
+    pub const MAX_NAME_LEN: usize = 100;
+
+    pub fn new(name: String, created_by: Uuid) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            created_by,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// A member's role within one organization, from least to most powerful
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrgRole {
+    /// Sees the organization and its members
+    Member,
+    /// Also renames it, invites people and manages members and admins
+    Admin,
+    /// Also manages owners and deletes the organization
+    Owner,
+}
+
+impl OrgRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Member => "member",
+            Self::Admin => "admin",
+            Self::Owner => "owner",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "member" => Some(Self::Member),
+            "admin" => Some(Self::Admin),
+            "owner" => Some(Self::Owner),
+            _ => None,
+        }
+    }
+
+    /// Whether this role may invite, remove or re-role someone with `role`
+    pub fn can_manage(&self, role: OrgRole) -> bool {
+        match self {
+            Self::Owner => true,
+            Self::Admin => role != Self::Owner,
+            Self::Member => false,
+        }
+    }
+}
+
+/// A user's membership of an organization
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Membership {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub role: OrgRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+impl Membership {
+    pub fn new(organization_id: Uuid, user_id: Uuid, role: OrgRole) -> Self {
+        Self {
+            organization_id,
+            user_id,
+            role,
+            joined_at: Utc::now(),
+        }
+    }
+}
+
+/// Message sent to support through the contact form
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupportTicket {
+    pub id: Uuid,
+    /// Set when the sender was signed in
+    pub user_id: Option<Uuid>,
+    pub name: String,
+    pub email: String,
+    pub subject: String,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SupportTicket {
+    pub fn new(user_id: Option<Uuid>, name: String, email: String, subject: String, message: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            name,
+            email,
+            subject,
+            message,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Endpoint registered by an admin to receive domain events as signed POSTs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub url: String,
+    /// HMAC key used to sign deliveries; shown once on creation
+    #[serde(skip_serializing)]
+    pub secret: String,
+    /// Event names to deliver (see `DomainEvent::NAMES`); empty means all
+    pub events: Vec<String>,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    pub fn new(url: String, secret: String, events: Vec<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            url,
+            secret,
+            events,
+            active: true,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Whether this webhook should receive `event`
+    pub fn subscribes_to(&self, event: &str) -> bool {
+        self.active && (self.events.is_empty() || self.events.iter().any(|e| e == event))
+    }
+}
+
+/// Outcome of a webhook delivery so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Waiting for its first or next attempt
+    Pending,
+    Succeeded,
+    /// Gave up after exhausting its attempts
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "succeeded" => Some(Self::Succeeded),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One domain event sent (or being sent) to one webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    /// Event bus id of the delivered event
+    pub event_id: u64,
+    pub event: String,
+    /// Exact JSON body that is signed and POSTed
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: u32,
+    /// HTTP status of the last attempt, if the endpoint answered
+    pub response_status: Option<u16>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl WebhookDelivery {
+    pub fn new(webhook_id: Uuid, event_id: u64, event: String, payload: String) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            webhook_id,
+            event_id,
+            event,
+            payload,
+            status: DeliveryStatus::Pending,
+            attempts: 0,
+            response_status: None,
+            last_error: None,
+            created_at: Utc::now(),
+            delivered_at: None,
         }
     }
 }
@@ -105,6 +956,9 @@ pub struct TokenPair {
     pub access_token: String,
     pub token_type: String,
     pub expires_in: i64,
+    /// Exchanged for a new pair once the access token expires; single use
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
 }
 
 impl TokenPair {
@@ -113,18 +967,162 @@ impl TokenPair {
             access_token,
             token_type: "Bearer".to_string(),
             expires_in,
+            refresh_token: None,
+        }
+    }
+}
+
+/// JWT Claims structure with role-based access control
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Claims {
+    pub sub: String,           // User ID
+    pub email: String,
+    pub roles: Vec<String>,    // User roles for RBAC
+    pub exp: i64,              // Expiration timestamp
+    pub iat: i64,              // Issued at timestamp
+    /// Tenant the user belongs to; tokens from before multi-tenancy have none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    /// The user's token version at issue; older tokens are rejected
+    #[serde(default)]
+    pub token_version: i64,
+    /// Admin acting as `sub` with an impersonation token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<String>,
+}
+
+impl Claims {
+    /// Role of guest tokens, which belong to no account yet
+    pub const ANONYMOUS_ROLE: &'static str = "anonymous";
+
+    pub fn is_anonymous(&self) -> bool {
+        self.roles.iter().any(|r| r == Self::ANONYMOUS_ROLE)
+    }
+
+    /// The admin impersonating the user, if this is an impersonation token
+    pub fn actor(&self) -> Option<&str> {
+        self.act.as_deref()
+    }
+}
+
+/// Roles a user holds and the permissions they grant
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Authorization {
+    pub roles: BTreeSet<String>,
+    /// `resource:action`, e.g. `users:delete`
+    pub permissions: BTreeSet<String>,
+}
+
+impl Authorization {
+    /// Longest accepted role name
+    pub const MAX_ROLE_LEN: usize = 50;
+
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.contains(role)
+    }
+
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.contains(permission)
+    }
+
+    /// Trimmed, lowercase role name made of letters, digits, `-`, `_` and `:`
+    pub fn normalize_role(name: &str) -> Result<String, DomainError> {
+        let name = name.trim().to_lowercase();
+        let valid = !name.is_empty()
+            && name.len() <= Self::MAX_ROLE_LEN
+            && name
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'-' | b'_' | b':'));
+        if !valid {
+            return Err(DomainError::validation(format!(
+                "Role '{}' must be 1-{} letters, digits, '-', '_' or ':'",
+                name,
+                Self::MAX_ROLE_LEN
+            )));
+        }
+        Ok(name)
+    }
+}
+
+// ============================================================================
+// Domain Events
+// ============================================================================
+
+/// Something that happened in the domain, published after the change is persisted.
+/// Lifecycle events (`service.*`) describe the running instance rather than a user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DomainEvent {
+    UserRegistered { user_id: Uuid, username: String, email: String },
+    UserLoggedIn { user_id: Uuid },
+    /// An upload failed the virus scan and was quarantined instead of stored
+    FileQuarantined { user_id: Uuid, purpose: String, signature: String },
+    /// An instance finished starting and is accepting requests
+    ServiceStarted { service: String, region: String, version: String },
+    /// Migrations run at startup, by version; only published when some were pending
+    MigrationsApplied { versions: Vec<i64> },
+    /// An instance received a shutdown signal and stopped accepting requests
+    ShuttingDown { service: String, region: String },
+    /// A notification landed in the user's inbox
+    NotificationCreated { user_id: Uuid, notification_id: Uuid, kind: String, title: String },
+}
+
+impl DomainEvent {
+    /// Every event name, for validating subscriptions
+    pub const NAMES: &'static [&'static str] = &[
+        "user.registered",
+        "user.logged_in",
+        "file.quarantined",
+        "service.started",
+        "service.migrations_applied",
+        "service.shutting_down",
+        "notification.created",
+    ];
+
+    /// Payload versions of the events whose shape changed since version 1.
+    /// Bump an entry (or add one) with every breaking change to an event and
+    /// register an upcaster from the previous version (`application::event_schema`).
+    const SCHEMA_VERSIONS: &'static [(&'static str, u32)] = &[];
+
+    /// Current payload version of the event named `name`
+    pub fn schema_version(name: &str) -> u32 {
+        Self::SCHEMA_VERSIONS
+            .iter()
+            .find(|(event, _)| *event == name)
+            .map_or(1, |(_, version)| *version)
+    }
+
+    /// Stable dotted event name (e.g. "user.registered")
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::UserRegistered { .. } => "user.registered",
+            Self::UserLoggedIn { .. } => "user.logged_in",
+            Self::FileQuarantined { .. } => "file.quarantined",
+            Self::ServiceStarted { .. } => "service.started",
+            Self::MigrationsApplied { .. } => "service.migrations_applied",
+            Self::ShuttingDown { .. } => "service.shutting_down",
+            Self::NotificationCreated { .. } => "notification.created",
+        }
+    }
+
+    /// The user this event concerns; none for lifecycle events
+    pub fn user_id(&self) -> Option<Uuid> {
+        match self {
+            Self::UserRegistered { user_id, .. }
+            | Self::UserLoggedIn { user_id }
+            | Self::FileQuarantined { user_id, .. }
+            | Self::NotificationCreated { user_id, .. } => Some(*user_id),
+            Self::ServiceStarted { .. } | Self::MigrationsApplied { .. } | Self::ShuttingDown { .. } => None,
         }
     }
 }
 
-/// JWT Claims structure with role-based access control
+/// Published event with its bus-assigned, monotonically increasing id
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Claims {
-    pub sub: String,           // User ID
-    pub email: String,
-    pub roles: Vec<String>,    // User roles for RBAC
-    pub exp: i64,              // Expiration timestamp
-    pub iat: i64,              // Issued at timestamp
+pub struct EventEnvelope {
+    pub id: u64,
+    pub occurred_at: DateTime<Utc>,
+    pub event: DomainEvent,
 }
 
 // ============================================================================
@@ -155,13 +1153,46 @@ impl PaginationParams {
 
     /// Calculate offset for SQL queries
     pub fn offset(&self) -> u32 {
-        (self.page.saturating_sub(1)) * self.per_page
+        self.page.saturating_sub(1).saturating_mul(self.per_page)
     }
 
     /// Get limit for SQL queries
     pub fn limit(&self) -> u32 {
         self.per_page.min(100)
     }
+
+    /// Reject pages starting past `max_offset()`; repositories call this
+    /// before querying so a deep page never reaches Postgres
+    pub fn validate(&self) -> Result<(), DomainError> {
+        self.validate_offset(max_offset())
+    }
+
+    /// `validate` against an explicit limit
+    pub fn validate_offset(&self, max_offset: u32) -> Result<(), DomainError> {
+        let offset = self.offset();
+        if offset > max_offset {
+            return Err(DomainError::validation(format!(
+                "page {} starts at offset {}, past the maximum of {}; narrow the results with filters instead of paging this deep",
+                self.page, offset, max_offset
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Offset allowed when nothing else is configured
+pub const DEFAULT_MAX_OFFSET: u32 = 10_000;
+
+static MAX_OFFSET: AtomicU32 = AtomicU32::new(DEFAULT_MAX_OFFSET);
+
+/// Deepest offset `PaginationParams::validate` accepts (`pagination.max_offset`)
+pub fn max_offset() -> u32 {
+    MAX_OFFSET.load(Ordering::Relaxed)
+}
+
+/// Set the process-wide maximum offset, once at startup
+pub fn set_max_offset(max_offset: u32) {
+    MAX_OFFSET.store(max_offset, Ordering::Relaxed);
 }
 
 /// Paginated response wrapper
@@ -177,21 +1208,364 @@ pub struct Page<T> {
     pub per_page: u32,
     /// Total number of pages
     pub total_pages: u32,
+    /// A later page has items
+    pub has_next: bool,
+    /// This is not the first page
+    pub has_prev: bool,
 }
 
 impl<T> Page<T> {
     pub fn new(items: Vec<T>, total: u64, params: &PaginationParams) -> Self {
-        let total_pages = ((total as f64) / (params.per_page as f64)).ceil() as u32;
+        let per_page = u64::from(params.per_page.max(1));
+        let total_pages = u32::try_from(total.div_ceil(per_page)).unwrap_or(u32::MAX);
         Self {
             items,
             total,
             page: params.page,
             per_page: params.per_page,
             total_pages,
+            has_next: params.page < total_pages,
+            has_prev: params.page > 1,
+        }
+    }
+}
+
+// ============================================================================
+// Feature Rollout
+// ============================================================================
+
+/// Number of buckets users are hashed into (0.01% resolution)
+pub const ROLLOUT_BUCKETS: u32 = 10_000;
+
+/// A feature flag with its rollout rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeatureFlag {
+    pub key: String,
+    /// Kill switch: a disabled flag is off for everyone
+    pub enabled: bool,
+    #[serde(default)]
+    pub rollout: Rollout,
+    /// What the flag gates, for admins
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Who gets a flag: users matching any cohort (or everyone, if no cohorts
+/// are listed), further narrowed to a stable percentage of them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rollout {
+    /// Share of eligible users that see the feature, 0.0–100.0
+    #[serde(default = "default_percentage")]
+    pub percentage: f64,
+    #[serde(default)]
+    pub cohorts: Vec<Cohort>,
+}
+
+fn default_percentage() -> f64 { 100.0 }
+
+impl Default for Rollout {
+    fn default() -> Self {
+        Self {
+            percentage: default_percentage(),
+            cohorts: Vec::new(),
+        }
+    }
+}
+
+/// User cohort targeted by a rollout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Cohort {
+    Role(String),
+    Tenant(String),
+    SignedUpBefore(DateTime<Utc>),
+    SignedUpAfter(DateTime<Utc>),
+    Users(Vec<Uuid>),
+}
+
+/// What is known about the caller when evaluating a flag
+#[derive(Debug, Clone, Default)]
+pub struct FlagContext {
+    pub user_id: Option<Uuid>,
+    pub roles: Vec<String>,
+    pub tenant_id: Option<String>,
+    pub signed_up_at: Option<DateTime<Utc>>,
+}
+
+impl FlagContext {
+    pub fn for_user(user_id: Uuid) -> Self {
+        Self {
+            user_id: Some(user_id),
+            ..Self::default()
+        }
+    }
+
+    /// Context for an authenticated caller (user id and roles from the token)
+    pub fn from_claims(claims: &Claims) -> Self {
+        Self {
+            user_id: claims.sub.parse().ok(),
+            roles: claims.roles.clone(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_roles(mut self, roles: Vec<String>) -> Self {
+        self.roles = roles;
+        self
+    }
+
+    pub fn with_tenant(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    pub fn with_signed_up_at(mut self, signed_up_at: DateTime<Utc>) -> Self {
+        self.signed_up_at = Some(signed_up_at);
+        self
+    }
+}
+
+impl Cohort {
+    pub fn matches(&self, ctx: &FlagContext) -> bool {
+        match self {
+            Self::Role(role) => ctx.roles.iter().any(|r| r == role),
+            Self::Tenant(tenant) => ctx.tenant_id.as_deref() == Some(tenant.as_str()),
+            Self::SignedUpBefore(at) => ctx.signed_up_at.is_some_and(|s| s < *at),
+            Self::SignedUpAfter(at) => ctx.signed_up_at.is_some_and(|s| s >= *at),
+            Self::Users(ids) => ctx.user_id.is_some_and(|id| ids.contains(&id)),
+        }
+    }
+}
+
+impl Rollout {
+    /// Bucket threshold for the configured percentage
+    pub fn threshold(&self) -> u32 {
+        let percentage = if self.percentage.is_nan() { 0.0 } else { self.percentage.clamp(0.0, 100.0) };
+        (percentage * f64::from(ROLLOUT_BUCKETS) / 100.0).round() as u32
+    }
+
+    /// Whether `ctx` falls inside this rollout. `salt` keeps buckets
+    /// independent between flags; anonymous callers only see full rollouts.
+    pub fn includes(&self, salt: &str, ctx: &FlagContext) -> bool {
+        if !self.cohorts.is_empty() && !self.cohorts.iter().any(|c| c.matches(ctx)) {
+            return false;
+        }
+        let threshold = self.threshold();
+        if threshold >= ROLLOUT_BUCKETS {
+            return true;
+        }
+        ctx.user_id
+            .is_some_and(|id| stable_bucket(salt, id) < threshold)
+    }
+}
+
+impl FeatureFlag {
+    pub fn is_enabled_for(&self, ctx: &FlagContext) -> bool {
+        self.enabled && self.rollout.includes(&self.key, ctx)
+    }
+}
+
+/// Deterministic bucket in `0..ROLLOUT_BUCKETS` for a user.
+///
+/// Uses FNV-1a rather than `std`'s hasher, whose output may change between
+/// Rust releases: raising a percentage must only ever add users.
+pub fn stable_bucket(salt: &str, user_id: Uuid) -> u32 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let hash = salt
+        .as_bytes()
+        .iter()
+        .chain(b":")
+        .chain(user_id.as_bytes())
+        .fold(OFFSET, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(PRIME));
+    (hash % u64::from(ROLLOUT_BUCKETS)) as u32
+}
+
+// ============================================================================
+// Experiments
+// ============================================================================
+
+/// A/B experiment: users in the audience are split between variants by weight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Experiment {
+    pub key: String,
+    #[serde(default = "default_active")]
+    pub active: bool,
+    /// Who is enrolled; defaults to every user
+    #[serde(default)]
+    pub audience: Rollout,
+    pub variants: Vec<Variant>,
+}
+
+fn default_active() -> bool { true }
+
+/// Experiment arm with its relative traffic weight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Variant {
+    pub name: String,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 { 1 }
+
+/// The variant a user was placed in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExperimentAssignment {
+    pub experiment: String,
+    pub variant: String,
+}
+
+impl Experiment {
+    /// Deterministically place `ctx` in a variant, or `None` if not enrolled.
+    /// Enrollment and variant choice hash with different salts so that
+    /// widening the audience does not reshuffle existing users.
+    pub fn assign(&self, ctx: &FlagContext) -> Option<ExperimentAssignment> {
+        let user_id = ctx.user_id?;
+        let total: u64 = self.variants.iter().map(|v| u64::from(v.weight)).sum();
+        if !self.active || total == 0 || !self.audience.includes(&self.key, ctx) {
+            return None;
+        }
+
+        let bucket = stable_bucket(&format!("{}:variant", self.key), user_id);
+        let mut point = u64::from(bucket) * total / u64::from(ROLLOUT_BUCKETS);
+        let variant = self.variants.iter().find(|v| {
+            let weight = u64::from(v.weight);
+            if point < weight {
+                true
+            } else {
+                point -= weight;
+                false
+            }
+        })?;
+
+        Some(ExperimentAssignment {
+            experiment: self.key.clone(),
+            variant: variant.name.clone(),
+        })
+    }
+}
+
+// ============================================================================
+// Specifications
+// ============================================================================
+
+/// An entity that can be looked up by `Specification`: it names the
+/// conditions repositories know how to query, and checks them in memory
+pub trait Queryable: Entity {
+    type Criterion: std::fmt::Debug + Clone + PartialEq + Send + Sync;
+
+    fn satisfies(&self, criterion: &Self::Criterion) -> bool;
+}
+
+/// A composable query over `T`, so a new question is a new combination
+/// rather than a new repository method. Stores translate it (see
+/// `Repository::find_by_spec`); `is_satisfied_by` evaluates it in memory.
+///
+/// ```rust,ignore
+/// let spec = Specification::from(UserCriterion::ByEmailDomain("example.com".into()))
+///     .and(UserCriterion::CreatedAfter(last_week).into());
+/// let page = users.find_by_spec(&spec, &params).await?;
+/// ```
+pub enum Specification<T: Queryable> {
+    Where(T::Criterion),
+    And(Box<Specification<T>>, Box<Specification<T>>),
+    Or(Box<Specification<T>>, Box<Specification<T>>),
+    Not(Box<Specification<T>>),
+}
+
+// Written out: derives would also require `T` itself to be Clone/Debug/PartialEq
+impl<T: Queryable> Clone for Specification<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Where(criterion) => Self::Where(criterion.clone()),
+            Self::And(a, b) => Self::And(a.clone(), b.clone()),
+            Self::Or(a, b) => Self::Or(a.clone(), b.clone()),
+            Self::Not(spec) => Self::Not(spec.clone()),
+        }
+    }
+}
+
+impl<T: Queryable> std::fmt::Debug for Specification<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Where(criterion) => f.debug_tuple("Where").field(criterion).finish(),
+            Self::And(a, b) => f.debug_tuple("And").field(a).field(b).finish(),
+            Self::Or(a, b) => f.debug_tuple("Or").field(a).field(b).finish(),
+            Self::Not(spec) => f.debug_tuple("Not").field(spec).finish(),
+        }
+    }
+}
+
+impl<T: Queryable> PartialEq for Specification<T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Where(a), Self::Where(b)) => a == b,
+            (Self::And(a1, b1), Self::And(a2, b2)) | (Self::Or(a1, b1), Self::Or(a2, b2)) => a1 == a2 && b1 == b2,
+            (Self::Not(a), Self::Not(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl<T: Queryable> Specification<T> {
+    pub fn and(self, other: Self) -> Self {
+        Self::And(Box::new(self), Box::new(other))
+    }
+
+    pub fn or(self, other: Self) -> Self {
+        Self::Or(Box::new(self), Box::new(other))
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Self::Not(Box::new(self))
+    }
+
+    pub fn is_satisfied_by(&self, candidate: &T) -> bool {
+        match self {
+            Self::Where(criterion) => candidate.satisfies(criterion),
+            Self::And(a, b) => a.is_satisfied_by(candidate) && b.is_satisfied_by(candidate),
+            Self::Or(a, b) => a.is_satisfied_by(candidate) || b.is_satisfied_by(candidate),
+            Self::Not(spec) => !spec.is_satisfied_by(candidate),
+        }
+    }
+}
+
+/// What users can be queried by
+#[derive(Debug, Clone, PartialEq)]
+pub enum UserCriterion {
+    /// Email address at this domain (case-insensitive, exact: no subdomains)
+    ByEmailDomain(String),
+    /// Signed up strictly after this time
+    CreatedAfter(DateTime<Utc>),
+    /// Signed up strictly before this time
+    CreatedBefore(DateTime<Utc>),
+    HasStatus(UserStatus),
+    InTenant(Uuid),
+}
+
+impl Queryable for User {
+    type Criterion = UserCriterion;
+
+    fn satisfies(&self, criterion: &UserCriterion) -> bool {
+        match criterion {
+            UserCriterion::ByEmailDomain(domain) => self.email.domain().eq_ignore_ascii_case(domain.trim()),
+            UserCriterion::CreatedAfter(at) => self.created_at > *at,
+            UserCriterion::CreatedBefore(at) => self.created_at < *at,
+            UserCriterion::HasStatus(status) => self.status == *status,
+            UserCriterion::InTenant(tenant_id) => self.tenant_id == *tenant_id,
         }
     }
 }
 
+impl From<UserCriterion> for Specification<User> {
+    fn from(criterion: UserCriterion) -> Self {
+        Self::Where(criterion)
+    }
+}
+
 // ============================================================================
 // Repository Traits (Ports)
 // ============================================================================
@@ -211,6 +1585,80 @@ impl Entity for User {
     }
 }
 
+impl Entity for Tenant {
+    type Id = Uuid;
+
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+}
+
+impl Entity for UserNote {
+    type Id = Uuid;
+
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+}
+
+impl Entity for Notification {
+    type Id = Uuid;
+
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+}
+
+impl Entity for UserSegment {
+    type Id = Uuid;
+
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+}
+
+impl Entity for Organization {
+    type Id = Uuid;
+
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+}
+
+impl Entity for SupportTicket {
+    type Id = Uuid;
+
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+}
+
+impl Entity for Webhook {
+    type Id = Uuid;
+
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+}
+
+impl Entity for WebhookDelivery {
+    type Id = Uuid;
+
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+}
+
+/// Entities that can carry tags, recorded in `taggings` under `ENTITY_TYPE`.
+/// Deleting the entity must drop its taggings (see the `delete_taggings` trigger).
+pub trait Taggable: Entity<Id = Uuid> {
+    const ENTITY_TYPE: &'static str;
+}
+
+impl Taggable for User {
+    const ENTITY_TYPE: &'static str = "user";
+}
+
 /// Generic repository trait with common CRUD operations
 /// Similar to C# base repository pattern with Dapper
 #[async_trait]
@@ -224,7 +1672,8 @@ pub trait Repository<T: Entity>: Send + Sync {
     /// Create a new entity
     async fn create(&self, entity: &T) -> Result<T, DomainError>;
     
-    /// Update an existing entity
+    /// Update an existing entity. Versioned entities (e.g. `User`) fail with
+    /// `DomainError::Conflict` when the stored version moved on.
     async fn update(&self, entity: &T) -> Result<T, DomainError>;
     
     /// Delete entity by ID
@@ -237,17 +1686,191 @@ pub trait Repository<T: Entity>: Send + Sync {
     async fn exists(&self, id: T::Id) -> Result<bool, DomainError> {
         Ok(self.find_by_id(id).await?.is_some())
     }
+
+    /// Entities satisfying `spec`, newest first. Stores that cannot
+    /// translate specifications leave this out and fail.
+    async fn find_by_spec(&self, spec: &Specification<T>, params: &PaginationParams) -> Result<Page<T>, DomainError>
+    where
+        T: Queryable,
+    {
+        let _ = (spec, params);
+        Err(DomainError::internal("This repository does not support specifications"))
+    }
 }
 
+/// Users read one at a time, for listings too large to hold in memory
+pub type UserStream = Pin<Box<dyn futures_core::Stream<Item = Result<User, DomainError>> + Send>>;
+
 /// User-specific repository with additional methods
 #[async_trait]
 pub trait UserRepository: Repository<User> {
-    /// Find user by email (for authentication)
+    /// Find user by email (for authentication), compared by `normalize_email`
+    /// so case (and, when configured, Gmail dots) does not matter
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, DomainError>;
     
     /// Find user by username
     async fn find_by_username(&self, username: &str) -> Result<Option<User>, DomainError>;
+
+    /// The users among `ids` that exist, in no particular order
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<User>, DomainError>;
+
+    /// The users holding any of `usernames` (compared exactly), in no particular order
+    async fn find_by_usernames(&self, usernames: &[String]) -> Result<Vec<User>, DomainError>;
+
+    /// Users matching `filter`, newest first
+    async fn search(&self, filter: &UserFilter, params: &PaginationParams) -> Result<Page<User>, DomainError>;
+
+    /// Up to `limit` users matching `filter` that come after `after` in the
+    /// newest-first order (ties broken by id), from the start when `None`
+    async fn search_after(&self, filter: &UserFilter, after: Option<UserCursor>, limit: u32) -> Result<Vec<User>, DomainError>;
+
+    /// Every user matching `filter`, newest first, read from the database as
+    /// the stream is polled. The stream owns what it needs (a Postgres adapter
+    /// holds one connection until it ends or is dropped), and the filter is
+    /// fixed when it is created.
+    fn stream_all(&self, filter: &UserFilter) -> UserStream;
+
+    /// Up to `limit` users whose username starts with `prefix`
+    /// (case-insensitive) and who match `filter`'s status and tenant: an
+    /// exact match first, then the shortest names
+    async fn autocomplete(&self, prefix: &str, filter: &UserFilter, limit: u32) -> Result<Vec<User>, DomainError>;
+
+    /// Increment the user's token version, returning the new one
+    async fn bump_token_version(&self, id: Uuid) -> Result<i64, DomainError>;
+}
+
+/// Read side of users: projections for display, kept apart from
+/// `UserRepository`, which loads whole users for writes. `tenant`, when set,
+/// hides every other tenant's users.
+#[async_trait]
+pub trait UserReadModel: Send + Sync {
+    async fn find_view(&self, id: Uuid, tenant: Option<Uuid>) -> Result<Option<UserView>, DomainError>;
+
+    /// Newest first
+    async fn list_views(&self, params: &PaginationParams, tenant: Option<Uuid>) -> Result<Page<UserView>, DomainError>;
+}
+
+/// Tenant repository
+#[async_trait]
+pub trait TenantRepository: Repository<Tenant> {
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<Tenant>, DomainError>;
+}
+
+/// Tags and the entities carrying them
+#[async_trait]
+pub trait TagRepository: Send + Sync {
+    /// A tenant's tags by name
+    async fn list(&self, tenant_id: Uuid) -> Result<Vec<Tag>, DomainError>;
+
+    /// The tenant's tag called `name` (normalized), created if missing
+    async fn find_or_create(&self, tenant_id: Uuid, name: &str) -> Result<Tag, DomainError>;
+
+    /// Tag an entity; false when it already carried the tag
+    async fn attach(&self, tag_id: Uuid, entity_type: &str, entity_id: Uuid) -> Result<bool, DomainError>;
+
+    /// Remove the tenant's tag `name` from an entity; false when it did not carry it
+    async fn detach(&self, tenant_id: Uuid, name: &str, entity_type: &str, entity_id: Uuid) -> Result<bool, DomainError>;
+
+    /// Tags of each entity in `entity_ids` by name; untagged entities are left out
+    async fn tags_of(&self, entity_type: &str, entity_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<Tag>>, DomainError>;
+}
+
+/// Roles granted to users and the permissions of each role
+#[async_trait]
+pub trait RoleRepository: Send + Sync {
+    /// Stored roles of `user_id` plus `extra_roles` (e.g. from the token),
+    /// with the permissions of all of them
+    async fn authorization(&self, user_id: Uuid, extra_roles: &[String]) -> Result<Authorization, DomainError>;
+
+    /// Grant a role; false when the user already held it
+    async fn grant(&self, user_id: Uuid, role: &str) -> Result<bool, DomainError>;
+
+    /// Revoke a role; false when the user did not hold it
+    async fn revoke(&self, user_id: Uuid, role: &str) -> Result<bool, DomainError>;
 }
 
+/// Admin notes on users
+#[async_trait]
+pub trait UserNoteRepository: Repository<UserNote> {
+    /// Notes on `user_id` that `reader_id` may see, newest first
+    async fn find_for_user(&self, user_id: Uuid, reader_id: Uuid, params: &PaginationParams) -> Result<Page<UserNote>, DomainError>;
+}
+
+/// Users' in-app notifications. Every method is scoped to one user, so a
+/// notification id alone never reaches another user's inbox.
+#[async_trait]
+pub trait NotificationRepository: Send + Sync {
+    async fn create(&self, notification: &Notification) -> Result<Notification, DomainError>;
+
+    /// The user's notifications, newest first; only unread ones when `unread_only`
+    async fn list_for_user(&self, user_id: Uuid, unread_only: bool, params: &PaginationParams) -> Result<Page<Notification>, DomainError>;
+
+    async fn unread_count(&self, user_id: Uuid) -> Result<u64, DomainError>;
+
+    /// Mark the user's notification read at `at`, keeping the first read time
+    /// of one already read; `None` when the user has no such notification
+    async fn mark_read(&self, user_id: Uuid, id: Uuid, at: DateTime<Utc>) -> Result<Option<Notification>, DomainError>;
+}
+
+/// Saved user segments
+#[async_trait]
+pub trait UserSegmentRepository: Repository<UserSegment> {
+    /// A tenant's segments by name
+    async fn list(&self, tenant_id: Uuid) -> Result<Vec<UserSegment>, DomainError>;
+}
+
+/// Per-user settings, keyed by user ID
+#[async_trait]
+pub trait UserSettingsRepository: Send + Sync {
+    /// Saved settings, `None` when the user never changed any
+    async fn find(&self, user_id: Uuid) -> Result<Option<UserSettings>, DomainError>;
+
+    /// Insert or replace the user's settings
+    async fn upsert(&self, settings: &UserSettings) -> Result<UserSettings, DomainError>;
+}
+
+/// Organizations
+#[async_trait]
+pub trait OrganizationRepository: Repository<Organization> {
+    /// Create `organization` together with its first owner's membership
+    async fn create_with_owner(&self, organization: &Organization, owner: &Membership) -> Result<Organization, DomainError>;
+
+    /// Organizations `user_id` belongs to, with their role, by name
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<(Organization, OrgRole)>, DomainError>;
+}
+
+/// Organization memberships, keyed by organization and user
+#[async_trait]
+pub trait MembershipRepository: Send + Sync {
+    async fn find(&self, organization_id: Uuid, user_id: Uuid) -> Result<Option<Membership>, DomainError>;
+
+    /// An organization's members, owners first, then by join date
+    async fn list(&self, organization_id: Uuid, params: &PaginationParams) -> Result<Page<Membership>, DomainError>;
+
+    /// Add a member; `Conflict` when the user already is one
+    async fn add(&self, membership: &Membership) -> Result<Membership, DomainError>;
+
+    /// Change a member's role, `None` when they are not a member
+    async fn set_role(&self, organization_id: Uuid, user_id: Uuid, role: OrgRole) -> Result<Option<Membership>, DomainError>;
 
+    async fn remove(&self, organization_id: Uuid, user_id: Uuid) -> Result<bool, DomainError>;
 
+    async fn count_owners(&self, organization_id: Uuid) -> Result<u64, DomainError>;
+}
+
+/// Support ticket repository
+pub trait SupportTicketRepository: Repository<SupportTicket> {}
+
+/// Webhook repository
+#[async_trait]
+pub trait WebhookRepository: Repository<Webhook> {
+    /// Active webhooks that should receive `event`
+    async fn find_subscribed(&self, event: &str) -> Result<Vec<Webhook>, DomainError>;
+}
+
+/// Webhook delivery history
+#[async_trait]
+pub trait WebhookDeliveryRepository: Repository<WebhookDelivery> {
+    /// Deliveries to one webhook, newest first
+    async fn find_by_webhook(&self, webhook_id: Uuid, params: &PaginationParams) -> Result<Page<WebhookDelivery>, DomainError>;
+}
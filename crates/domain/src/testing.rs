@@ -0,0 +1,133 @@
+//! Proptest strategies for domain types, shared by this crate's property
+//! tests and (via the `testing` feature) by other crates in the workspace.
+
+use proptest::prelude::*;
+
+use uuid::Uuid;
+
+use crate::PaginationParams;
+
+/// Params as built by `PaginationParams::new` (clamped to valid ranges).
+pub fn pagination_params() -> impl Strategy<Value = PaginationParams> {
+    (any::<u32>(), any::<u32>()).prop_map(|(page, per_page)| PaginationParams::new(page, per_page))
+}
+
+/// Params as they may arrive from a query string, before any clamping.
+pub fn raw_pagination_params() -> impl Strategy<Value = PaginationParams> {
+    (any::<u32>(), any::<u32>()).prop_map(|(page, per_page)| PaginationParams { page, per_page })
+}
+
+/// Realistic row counts for a single table.
+pub fn total_count() -> impl Strategy<Value = u64> {
+    0u64..10_000_000
+}
+
+/// Arbitrary user ids for rollout bucketing.
+pub fn user_id() -> impl Strategy<Value = Uuid> {
+    any::<u128>().prop_map(Uuid::from_u128)
+}
+
+/// Rollout percentages, including out-of-range config values.
+pub fn rollout_percentage() -> impl Strategy<Value = f64> {
+    -10.0f64..110.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{stable_bucket, Experiment, FlagContext, Page, Rollout, Variant, ROLLOUT_BUCKETS};
+
+    proptest! {
+        #[test]
+        fn new_clamps_into_valid_range(params in pagination_params()) {
+            prop_assert!(params.page >= 1);
+            prop_assert!((1..=100).contains(&params.per_page));
+            prop_assert!((1..=100).contains(&params.limit()));
+        }
+
+        #[test]
+        fn limit_never_exceeds_max_for_raw_input(params in raw_pagination_params()) {
+            prop_assert!(params.limit() <= 100);
+        }
+
+        #[test]
+        fn offset_matches_page_math_or_saturates(params in raw_pagination_params()) {
+            let expected = u64::from(params.page.saturating_sub(1)) * u64::from(params.per_page);
+            prop_assert_eq!(u64::from(params.offset()), expected.min(u64::from(u32::MAX)));
+        }
+
+        #[test]
+        fn consecutive_pages_do_not_overlap(params in pagination_params()) {
+            let next = PaginationParams::new(params.page.saturating_add(1), params.per_page);
+            if next.page > params.page && next.offset() < u32::MAX {
+                prop_assert_eq!(next.offset() - params.offset(), params.limit());
+            }
+        }
+
+        #[test]
+        fn total_pages_covers_every_item(params in pagination_params(), total in total_count()) {
+            let page = Page::<()>::new(Vec::new(), total, &params);
+            let per_page = u64::from(params.per_page);
+            let pages = u64::from(page.total_pages);
+
+            prop_assert!(pages * per_page >= total);
+            if total == 0 {
+                prop_assert_eq!(pages, 0);
+            } else {
+                prop_assert!((pages - 1) * per_page < total);
+            }
+        }
+
+        #[test]
+        fn total_pages_never_panics_on_raw_input(params in raw_pagination_params(), total in any::<u64>()) {
+            let page = Page::<()>::new(Vec::new(), total, &params);
+            prop_assert!(total == 0 || page.total_pages >= 1);
+        }
+
+        #[test]
+        fn bucket_is_stable_and_in_range(salt in "[a-z_]{1,16}", id in user_id()) {
+            let bucket = stable_bucket(&salt, id);
+            prop_assert!(bucket < ROLLOUT_BUCKETS);
+            prop_assert_eq!(bucket, stable_bucket(&salt, id));
+        }
+
+        #[test]
+        fn raising_percentage_only_adds_users(
+            id in user_id(),
+            low in rollout_percentage(),
+            high in rollout_percentage(),
+        ) {
+            let (low, high) = if low <= high { (low, high) } else { (high, low) };
+            let ctx = FlagContext::for_user(id);
+            let at = |percentage| Rollout { percentage, cohorts: Vec::new() }.includes("flag", &ctx);
+            prop_assert!(!at(low) || at(high));
+        }
+
+        #[test]
+        fn every_enrolled_user_gets_a_weighted_variant(
+            id in user_id(),
+            weights in proptest::collection::vec(0u32..5, 1..5),
+        ) {
+            let experiment = Experiment {
+                key: "exp".to_string(),
+                active: true,
+                audience: Rollout::default(),
+                variants: weights
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &weight)| Variant { name: i.to_string(), weight })
+                    .collect(),
+            };
+            let assignment = experiment.assign(&FlagContext::for_user(id));
+
+            if weights.iter().all(|&w| w == 0) {
+                prop_assert!(assignment.is_none());
+            } else {
+                let assignment = assignment.unwrap();
+                let index: usize = assignment.variant.parse().unwrap();
+                prop_assert!(weights[index] > 0);
+                prop_assert_eq!(Some(assignment), experiment.assign(&FlagContext::for_user(id)));
+            }
+        }
+    }
+}
@@ -0,0 +1,238 @@
+//! gRPC transport exposing the application-layer user and auth services.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use application::activity::LoginClient;
+use application::token_versions::TokenVersions;
+use application::{ApplicationError, AuthService, TokenService, UserService};
+use domain::{DomainError, PaginationParams, User};
+use tonic::{transport::Server, Request, Response, Status};
+
+/// Generated in the `proto` crate
+pub mod pb {
+    pub use proto::v1::*;
+    pub use proto::FILE_DESCRIPTOR_SET;
+}
+
+use pb::auth_service_server::{AuthService as AuthRpc, AuthServiceServer};
+use pb::user_service_server::{UserService as UserRpc, UserServiceServer};
+
+// ============================================================================
+// Server
+// ============================================================================
+
+/// Application services shared with the REST API
+#[derive(Clone)]
+pub struct GrpcServices {
+    pub user_service: Arc<dyn UserService>,
+    pub auth_service: Arc<dyn AuthService>,
+    pub token_service: Arc<dyn TokenService>,
+    /// Rejects tokens issued before the user's last logout-all
+    pub token_versions: Arc<TokenVersions>,
+}
+
+/// Serve the gRPC API, the standard health service and server reflection on `addr`.
+pub async fn serve(addr: SocketAddr, services: GrpcServices) -> Result<(), tonic::transport::Error> {
+    let (mut health_reporter, health_service) = tonic_health::server::health_reporter();
+    health_reporter
+        .set_serving::<UserServiceServer<UserGrpcService>>()
+        .await;
+    health_reporter
+        .set_serving::<AuthServiceServer<AuthGrpcService>>()
+        .await;
+
+    let reflection = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(pb::FILE_DESCRIPTOR_SET)
+        .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+        .build_v1()
+        .expect("embedded descriptor sets are valid");
+
+    Server::builder()
+        .add_service(health_service)
+        .add_service(reflection)
+        .add_service(UserServiceServer::new(UserGrpcService::new(services.clone())))
+        .add_service(AuthServiceServer::new(AuthGrpcService {
+            auth_service: services.auth_service,
+        }))
+        .serve(addr)
+        .await
+}
+
+// ============================================================================
+// Error Mapping
+// ============================================================================
+
+fn domain_status(err: DomainError) -> Status {
+    match &err {
+        DomainError::NotFound { .. } => Status::not_found(err.to_string()),
+        DomainError::Validation(_) => Status::invalid_argument(err.to_string()),
+        DomainError::Conflict { .. } => Status::already_exists(err.to_string()),
+        DomainError::Internal(_) => Status::internal(err.to_string()),
+        DomainError::Unauthorized(_) => Status::unauthenticated(err.to_string()),
+        DomainError::Forbidden(_) => Status::permission_denied(err.to_string()),
+        DomainError::Unavailable(_) => Status::unavailable(err.to_string()),
+        DomainError::Timeout(_) => Status::deadline_exceeded(err.to_string()),
+    }
+}
+
+fn application_status(err: ApplicationError) -> Status {
+    match err {
+        ApplicationError::Domain(domain_err) => domain_status(domain_err),
+        ApplicationError::UseCase(msg) => Status::invalid_argument(msg),
+        err @ ApplicationError::RateLimited { .. } => Status::resource_exhausted(err.to_string()),
+        err @ ApplicationError::WeakPassword(_) => Status::invalid_argument(err.to_string()),
+    }
+}
+
+fn to_pb_user(user: User) -> pb::User {
+    pb::User {
+        id: user.id.to_string(),
+        username: user.username.into(),
+        email: user.email.into(),
+    }
+}
+
+// ============================================================================
+// User Service
+// ============================================================================
+
+pub struct UserGrpcService {
+    services: GrpcServices,
+}
+
+impl UserGrpcService {
+    pub fn new(services: GrpcServices) -> Self {
+        Self { services }
+    }
+}
+
+#[tonic::async_trait]
+impl UserRpc for UserGrpcService {
+    async fn get_user(&self, request: Request<pb::GetUserRequest>) -> Result<Response<pb::User>, Status> {
+        let id = request
+            .into_inner()
+            .id
+            .parse::<uuid::Uuid>()
+            .map_err(|_| Status::invalid_argument("id must be a UUID"))?;
+
+        let user = self
+            .services
+            .user_service
+            .get_user(id)
+            .await
+            .map_err(application_status)?
+            .ok_or_else(|| Status::not_found(format!("User with id {} not found", id)))?;
+
+        Ok(Response::new(to_pb_user(user)))
+    }
+
+    async fn list_users(
+        &self,
+        request: Request<pb::ListUsersRequest>,
+    ) -> Result<Response<pb::ListUsersResponse>, Status> {
+        let req = request.into_inner();
+        // proto3 zero values mean "not set"
+        let page = if req.page == 0 { 1 } else { req.page };
+        let per_page = if req.per_page == 0 { 20 } else { req.per_page };
+        let params = PaginationParams::new(page, per_page);
+
+        let page = self
+            .services
+            .user_service
+            .list_users(&params)
+            .await
+            .map_err(application_status)?;
+
+        Ok(Response::new(pb::ListUsersResponse {
+            items: page.items.into_iter().map(to_pb_user).collect(),
+            total: page.total,
+            page: page.page,
+            per_page: page.per_page,
+            total_pages: page.total_pages,
+        }))
+    }
+
+    async fn get_current_user(
+        &self,
+        request: Request<pb::GetCurrentUserRequest>,
+    ) -> Result<Response<pb::User>, Status> {
+        let token = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or_else(|| Status::unauthenticated("Missing bearer token"))?;
+
+        let claims = self
+            .services
+            .token_versions
+            .authenticate(self.services.token_service.as_ref(), token)
+            .await
+            .map_err(application_status)?;
+        let user_id = claims
+            .sub
+            .parse::<uuid::Uuid>()
+            .map_err(|_| Status::internal("Invalid user ID in token"))?;
+
+        let user = self
+            .services
+            .user_service
+            .get_user(user_id)
+            .await
+            .map_err(application_status)?
+            .ok_or_else(|| Status::not_found("Current user not found"))?;
+
+        Ok(Response::new(to_pb_user(user)))
+    }
+}
+
+// ============================================================================
+// Auth Service
+// ============================================================================
+
+pub struct AuthGrpcService {
+    auth_service: Arc<dyn AuthService>,
+}
+
+#[tonic::async_trait]
+impl AuthRpc for AuthGrpcService {
+    async fn register(
+        &self,
+        request: Request<pb::RegisterRequest>,
+    ) -> Result<Response<pb::RegisterResponse>, Status> {
+        let req = request.into_inner();
+        let user = self
+            .auth_service
+            .register(req.username, req.email, req.password)
+            .await
+            .map_err(application_status)?;
+
+        Ok(Response::new(pb::RegisterResponse {
+            user: Some(to_pb_user(user)),
+        }))
+    }
+
+    async fn login(&self, request: Request<pb::LoginRequest>) -> Result<Response<pb::TokenResponse>, Status> {
+        let client = LoginClient {
+            ip: request.remote_addr().map(|addr| addr.ip().to_string()),
+            user_agent: request
+                .metadata()
+                .get("user-agent")
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string),
+        };
+        let req = request.into_inner();
+        let token = self
+            .auth_service
+            .login(req.email, req.password, client)
+            .await
+            .map_err(application_status)?;
+
+        Ok(Response::new(pb::TokenResponse {
+            access_token: token.access_token,
+            token_type: token.token_type,
+            expires_in: token.expires_in,
+        }))
+    }
+}
@@ -0,0 +1,92 @@
+use application::account_deletion::{AccountDeletionStore, ScheduledDeletion};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use uuid::Uuid;
+
+use crate::db::{Database, DbConnection};
+
+// ============================================================================
+// Postgres Account Deletion Store
+// ============================================================================
+
+/// Pending deletions in the `account_deletions` table, one row per user
+pub struct PgAccountDeletionStore {
+    db: Database,
+}
+
+impl PgAccountDeletionStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn conn(&self) -> Result<DbConnection, ApplicationError> {
+        Ok(self.db.acquire().await?)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ScheduledDeletionRow {
+    user_id: Uuid,
+    requested_at: DateTime<Utc>,
+    delete_after: DateTime<Utc>,
+}
+
+impl From<ScheduledDeletionRow> for ScheduledDeletion {
+    fn from(row: ScheduledDeletionRow) -> Self {
+        Self {
+            user_id: row.user_id,
+            requested_at: row.requested_at,
+            delete_after: row.delete_after,
+        }
+    }
+}
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("Account deletion store error: {}", err)).into()
+}
+
+#[async_trait]
+impl AccountDeletionStore for PgAccountDeletionStore {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "AccountDeletion", operation = "schedule"))]
+    async fn schedule(&self, deletion: &ScheduledDeletion) -> Result<(), ApplicationError> {
+        sqlx::query(
+            r#"
+            INSERT INTO account_deletions (user_id, requested_at, delete_after)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO UPDATE SET requested_at = $2, delete_after = $3
+            "#,
+        )
+        .bind(deletion.user_id)
+        .bind(deletion.requested_at)
+        .bind(deletion.delete_after)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "AccountDeletion", operation = "find"))]
+    async fn find(&self, user_id: Uuid) -> Result<Option<ScheduledDeletion>, ApplicationError> {
+        let row = sqlx::query_as::<_, ScheduledDeletionRow>(
+            "SELECT user_id, requested_at, delete_after FROM account_deletions WHERE user_id = $1",
+        )
+        .bind(user_id)
+        .fetch_optional(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+
+        Ok(row.map(ScheduledDeletion::from))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "AccountDeletion", operation = "remove"))]
+    async fn remove(&self, user_id: Uuid) -> Result<bool, ApplicationError> {
+        let result = sqlx::query("DELETE FROM account_deletions WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(map_err)?;
+        Ok(result.rows_affected() > 0)
+    }
+}
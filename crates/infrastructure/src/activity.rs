@@ -0,0 +1,141 @@
+use application::activity::{ActivityStore, LoginRecord};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use uuid::Uuid;
+
+use crate::db::{Database, DbConnection};
+
+// ============================================================================
+// Postgres Activity Store
+// ============================================================================
+
+/// Sign-ins in `user_logins`, and the `last_login_at` / `last_seen_at`
+/// columns of `users`. Neither touches `updated_at` or `version`: activity
+/// is not an edit, so it changes no ETag and causes no version conflict.
+pub struct PgActivityStore {
+    db: Database,
+}
+
+impl PgActivityStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn conn(&self) -> Result<DbConnection, ApplicationError> {
+        Ok(self.db.acquire().await?)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct LoginRow {
+    id: Uuid,
+    user_id: Uuid,
+    ip: Option<String>,
+    user_agent: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<LoginRow> for LoginRecord {
+    fn from(row: LoginRow) -> Self {
+        Self {
+            id: row.id,
+            user_id: row.user_id,
+            ip: row.ip,
+            user_agent: row.user_agent,
+            created_at: row.created_at,
+        }
+    }
+}
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("Activity store error: {}", err)).into()
+}
+
+#[async_trait]
+impl ActivityStore for PgActivityStore {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Login", operation = "record_login"))]
+    async fn record_login(&self, login: &LoginRecord, keep: u32) -> Result<(), ApplicationError> {
+        let mut conn = self.conn().await?;
+        sqlx::query("INSERT INTO user_logins (id, user_id, ip, user_agent, created_at) VALUES ($1, $2, $3, $4, $5)")
+            .bind(login.id)
+            .bind(login.user_id)
+            .bind(&login.ip)
+            .bind(&login.user_agent)
+            .bind(login.created_at)
+            .execute(&mut conn)
+            .await
+            .map_err(map_err)?;
+        sqlx::query("UPDATE users SET last_login_at = $2, last_seen_at = GREATEST(last_seen_at, $2) WHERE id = $1")
+            .bind(login.user_id)
+            .bind(login.created_at)
+            .execute(&mut conn)
+            .await
+            .map_err(map_err)?;
+        sqlx::query(
+            r#"
+            DELETE FROM user_logins
+            WHERE user_id = $1 AND id IN (
+                SELECT id FROM user_logins WHERE user_id = $1
+                ORDER BY created_at DESC
+                OFFSET $2)
+            "#,
+        )
+        .bind(login.user_id)
+        .bind(i64::from(keep))
+        .execute(&mut conn)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "record_seen"))]
+    async fn record_seen(&self, seen: &[(Uuid, DateTime<Utc>)]) -> Result<(), ApplicationError> {
+        let (ids, times): (Vec<Uuid>, Vec<DateTime<Utc>>) = seen.iter().copied().unzip();
+        sqlx::query(
+            r#"
+            UPDATE users u
+            SET last_seen_at = GREATEST(u.last_seen_at, s.seen_at)
+            FROM UNNEST($1::uuid[], $2::timestamptz[]) AS s (id, seen_at)
+            WHERE u.id = s.id
+            "#,
+        )
+        .bind(&ids)
+        .bind(&times)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Login", operation = "recent_logins"))]
+    async fn recent_logins(&self, user_id: Uuid, limit: u32) -> Result<Vec<LoginRecord>, ApplicationError> {
+        let rows = sqlx::query_as::<_, LoginRow>(
+            r#"
+            SELECT id, user_id, ip, user_agent, created_at
+            FROM user_logins
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(i64::from(limit))
+        .fetch_all(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+
+        Ok(rows.into_iter().map(LoginRecord::from).collect())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Login", operation = "forget"))]
+    async fn forget(&self, user_id: Uuid) -> Result<u64, ApplicationError> {
+        let result = sqlx::query("DELETE FROM user_logins WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(map_err)?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,25 @@
+use application::{AnalyticsEvent, AnalyticsSink};
+
+// ============================================================================
+// Tracing Analytics Sink
+// ============================================================================
+
+/// Writes analytics events to the log under the `analytics` target, one JSON
+/// object per event, for a log shipper to forward to the analytics pipeline.
+#[derive(Default)]
+pub struct TracingAnalyticsSink;
+
+impl TracingAnalyticsSink {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl AnalyticsSink for TracingAnalyticsSink {
+    fn track(&self, event: AnalyticsEvent) {
+        match serde_json::to_string(&event) {
+            Ok(json) => tracing::info!(target: "analytics", event = %json),
+            Err(e) => tracing::warn!("Failed to serialize analytics event: {}", e),
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use std::net::IpAddr;
+
+use domain::DomainError;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use sqlx::{PgPool, Row};
+use uuid::Uuid;
+
+use crate::map_sqlx_error;
+
+// ============================================================================
+// PII Catalog
+// ============================================================================
+
+/// Replacement applied to a PII column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fake {
+    /// `user_<hex>`
+    Username,
+    /// `first.last.<hex>@example.com`; the same address always maps to the same fake
+    Email,
+    /// `First Last`
+    Name,
+    /// IPv4 in 10.0.0.0/8, IPv6 in fd00::/8
+    Ip,
+    /// Free text, replaced by a placeholder that only keeps its length
+    Text,
+    /// Constant value
+    Fixed(&'static str),
+    Null,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PiiTable {
+    pub name: &'static str,
+    /// Columns rewritten in place; rows are walked by their `id` (UUID) key
+    pub columns: &'static [(&'static str, Fake)],
+}
+
+/// Every column holding personal data. Add new tables here when they store PII.
+pub const PII_TABLES: &[PiiTable] = &[
+    PiiTable {
+        name: "users",
+        columns: &[("username", Fake::Username), ("email", Fake::Email), ("avatar_url", Fake::Null)],
+    },
+    PiiTable {
+        name: "support_tickets",
+        columns: &[("name", Fake::Name), ("email", Fake::Email), ("message", Fake::Text)],
+    },
+    PiiTable {
+        name: "webhooks",
+        columns: &[("secret", Fake::Fixed("whsec_anonymized"))],
+    },
+    PiiTable {
+        name: "webhook_deliveries",
+        columns: &[("payload", Fake::Fixed("{}"))],
+    },
+];
+
+/// Rows deleted outright: queued emails must never go out from a copy,
+/// remembered idempotent responses can echo any personal data, and the
+/// suppression list is nothing but real addresses
+pub const PII_PURGES: &[(&str, &str)] = &[
+    ("jobs", "kind = 'email.send'"),
+    ("idempotency_keys", "TRUE"),
+    ("email_suppressions", "TRUE"),
+];
+
+// ============================================================================
+// Deterministic Fake Data
+// ============================================================================
+
+const FIRST_NAMES: &[&str] = &[
+    "Alex", "Blake", "Casey", "Dana", "Eden", "Frankie", "Gray", "Harper", "Indy", "Jordan", "Kai", "Logan",
+    "Morgan", "Noel", "Oakley", "Parker", "Quinn", "Riley", "Sage", "Taylor", "Uma", "Val", "Wren", "Yael",
+];
+const LAST_NAMES: &[&str] = &[
+    "Adams", "Baker", "Clark", "Diaz", "Evans", "Fischer", "Garcia", "Hughes", "Ito", "Jensen", "Khan", "Lopez",
+    "Meyer", "Nguyen", "Okafor", "Patel", "Rossi", "Silva", "Tanaka", "Usman", "Varga", "Weber", "Young", "Zhang",
+];
+
+/// Maps real values to fake ones with HMAC-SHA256 keyed by a salt: the same
+/// input and salt always give the same output, so joins and uniqueness survive,
+/// but the mapping cannot be reversed without the salt.
+pub struct Faker {
+    salt: Vec<u8>,
+}
+
+impl Faker {
+    pub fn new(salt: impl Into<Vec<u8>>) -> Self {
+        Self { salt: salt.into() }
+    }
+
+    fn digest(&self, kind: &str, value: &str) -> [u8; 32] {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.salt).expect("HMAC accepts any key length");
+        mac.update(kind.as_bytes());
+        mac.update(b"\0");
+        mac.update(value.as_bytes());
+        mac.finalize().into_bytes().into()
+    }
+
+    /// Fake replacement for `value`; `None` means NULL
+    pub fn fake(&self, fake: Fake, value: &str) -> Option<String> {
+        match fake {
+            Fake::Username => Some(format!("user_{}", hex::encode(&self.digest("username", value)[..6]))),
+            Fake::Email => {
+                let d = self.digest("email", &value.trim().to_lowercase());
+                let first = FIRST_NAMES[d[0] as usize % FIRST_NAMES.len()].to_lowercase();
+                let last = LAST_NAMES[d[1] as usize % LAST_NAMES.len()].to_lowercase();
+                Some(format!("{}.{}.{}@example.com", first, last, hex::encode(&d[2..8])))
+            }
+            Fake::Name => {
+                let d = self.digest("name", value);
+                Some(format!(
+                    "{} {}",
+                    FIRST_NAMES[d[0] as usize % FIRST_NAMES.len()],
+                    LAST_NAMES[d[1] as usize % LAST_NAMES.len()]
+                ))
+            }
+            Fake::Ip => {
+                let d = self.digest("ip", value);
+                Some(match value.parse::<IpAddr>() {
+                    Ok(IpAddr::V6(_)) => {
+                        let mut octets = [0u8; 16];
+                        octets[0] = 0xfd;
+                        octets[1..].copy_from_slice(&d[..15]);
+                        std::net::Ipv6Addr::from(octets).to_string()
+                    }
+                    _ => std::net::Ipv4Addr::new(10, d[0], d[1], d[2]).to_string(),
+                })
+            }
+            Fake::Text => Some(format!("[anonymized {} characters]", value.chars().count())),
+            Fake::Fixed(text) => Some(text.to_string()),
+            Fake::Null => None,
+        }
+    }
+}
+
+// ============================================================================
+// Anonymizer
+// ============================================================================
+
+/// Rows rewritten or deleted per table
+#[derive(Debug, Default)]
+pub struct AnonymizeReport {
+    pub tables: Vec<(&'static str, u64)>,
+}
+
+/// Rewrites every `PII_TABLES` column in place. Meant for restored copies of
+/// production. Batches commit one by one, so restart an interrupted run on a
+/// fresh copy: rewriting fake values again breaks the cross-table mapping.
+pub struct Anonymizer {
+    pool: PgPool,
+    faker: Faker,
+    batch_size: i64,
+}
+
+impl Anonymizer {
+    pub fn new(pool: PgPool, faker: Faker, batch_size: u32) -> Self {
+        Self {
+            pool,
+            faker,
+            batch_size: batch_size.max(1) as i64,
+        }
+    }
+
+    /// Name of the connected database, for the CLI's confirmation check
+    pub async fn database_name(&self) -> Result<String, DomainError> {
+        sqlx::query_scalar("SELECT current_database()")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Database"))
+    }
+
+    pub async fn run(&self) -> Result<AnonymizeReport, DomainError> {
+        let mut report = AnonymizeReport::default();
+
+        for (table, condition) in PII_PURGES {
+            if !self.table_exists(table).await? {
+                continue;
+            }
+            let deleted = sqlx::query(&format!("DELETE FROM {} WHERE {}", table, condition))
+                .execute(&self.pool)
+                .await
+                .map_err(|e| map_sqlx_error(e, "Anonymize"))?
+                .rows_affected();
+            tracing::info!(table, deleted, "Purged rows");
+            report.tables.push((table, deleted));
+        }
+
+        for table in PII_TABLES {
+            if !self.table_exists(table.name).await? {
+                tracing::warn!(table = table.name, "Table missing, skipped");
+                continue;
+            }
+            let rewritten = self.rewrite(table).await?;
+            tracing::info!(table = table.name, rewritten, "Anonymized rows");
+            report.tables.push((table.name, rewritten));
+        }
+
+        Ok(report)
+    }
+
+    async fn table_exists(&self, table: &str) -> Result<bool, DomainError> {
+        sqlx::query_scalar::<_, bool>("SELECT to_regclass($1) IS NOT NULL")
+            .bind(table)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Anonymize"))
+    }
+
+    /// Walk the table in id order and rewrite each batch in one transaction
+    async fn rewrite(&self, table: &PiiTable) -> Result<u64, DomainError> {
+        let columns: Vec<&str> = table.columns.iter().map(|(c, _)| *c).collect();
+        let select = format!(
+            "SELECT id, {} FROM {} WHERE id > $1 ORDER BY id LIMIT $2",
+            columns.join(", "),
+            table.name
+        );
+        let assignments: Vec<String> = columns.iter().enumerate().map(|(i, c)| format!("{} = ${}", c, i + 2)).collect();
+        let update = format!("UPDATE {} SET {} WHERE id = $1", table.name, assignments.join(", "));
+
+        let mut last = Uuid::nil();
+        let mut rewritten = 0;
+        loop {
+            let rows = sqlx::query(&select)
+                .bind(last)
+                .bind(self.batch_size)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| map_sqlx_error(e, "Anonymize"))?;
+            let Some(tail) = rows.last() else {
+                break;
+            };
+            last = tail.get("id");
+
+            let mut tx = self.pool.begin().await.map_err(|e| map_sqlx_error(e, "Anonymize"))?;
+            for row in &rows {
+                let mut query = sqlx::query(&update).bind(row.get::<Uuid, _>("id"));
+                for (column, fake) in table.columns {
+                    let value: Option<String> = row.get(column);
+                    query = query.bind(value.and_then(|v| self.faker.fake(*fake, &v)));
+                }
+                query.execute(&mut *tx).await.map_err(|e| map_sqlx_error(e, "Anonymize"))?;
+            }
+            tx.commit().await.map_err(|e| map_sqlx_error(e, "Anonymize"))?;
+
+            rewritten += rows.len() as u64;
+        }
+
+        Ok(rewritten)
+    }
+}
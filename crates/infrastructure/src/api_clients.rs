@@ -0,0 +1,123 @@
+use application::request_signing::{ApiClient, ApiClientStore};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use uuid::Uuid;
+
+use crate::db::{Database, DbConnection};
+
+// ============================================================================
+// Postgres API Client Store
+// ============================================================================
+
+/// API clients in the `api_clients` table
+pub struct PgApiClientStore {
+    db: Database,
+}
+
+impl PgApiClientStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn conn(&self) -> Result<DbConnection, ApplicationError> {
+        Ok(self.db.acquire().await?)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ApiClientRow {
+    id: Uuid,
+    name: String,
+    key_id: String,
+    secret: String,
+    roles: Vec<String>,
+    created_by: Uuid,
+    created_at: DateTime<Utc>,
+}
+
+impl From<ApiClientRow> for ApiClient {
+    fn from(row: ApiClientRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            key_id: row.key_id,
+            secret: row.secret,
+            roles: row.roles,
+            created_by: row.created_by,
+            created_at: row.created_at,
+        }
+    }
+}
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("API client store error: {}", err)).into()
+}
+
+#[async_trait]
+impl ApiClientStore for PgApiClientStore {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "ApiClient", operation = "create"))]
+    async fn create(&self, client: &ApiClient) -> Result<(), ApplicationError> {
+        sqlx::query(
+            r#"
+            INSERT INTO api_clients (id, name, key_id, secret, roles, created_by, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(client.id)
+        .bind(&client.name)
+        .bind(&client.key_id)
+        .bind(&client.secret)
+        .bind(&client.roles)
+        .bind(client.created_by)
+        .bind(client.created_at)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "ApiClient", operation = "find_by_key_id"))]
+    async fn find_by_key_id(&self, key_id: &str) -> Result<Option<ApiClient>, ApplicationError> {
+        let row = sqlx::query_as::<_, ApiClientRow>(
+            r#"
+            SELECT id, name, key_id, secret, roles, created_by, created_at
+            FROM api_clients
+            WHERE key_id = $1
+            "#,
+        )
+        .bind(key_id)
+        .fetch_optional(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+
+        Ok(row.map(ApiClient::from))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "ApiClient", operation = "list"))]
+    async fn list(&self) -> Result<Vec<ApiClient>, ApplicationError> {
+        let rows = sqlx::query_as::<_, ApiClientRow>(
+            r#"
+            SELECT id, name, key_id, secret, roles, created_by, created_at
+            FROM api_clients
+            ORDER BY created_at
+            "#,
+        )
+        .fetch_all(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+
+        Ok(rows.into_iter().map(ApiClient::from).collect())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "ApiClient", operation = "delete"))]
+    async fn delete(&self, id: Uuid) -> Result<bool, ApplicationError> {
+        let result = sqlx::query("DELETE FROM api_clients WHERE id = $1")
+            .bind(id)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(map_err)?;
+        Ok(result.rows_affected() > 0)
+    }
+}
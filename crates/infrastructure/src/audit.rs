@@ -0,0 +1,317 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use domain::DomainError;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
+use shared::{AuditSettings, LogSettings};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use uuid::Uuid;
+
+// ============================================================================
+// Audit Export Configuration
+// ============================================================================
+
+/// Where audit records are shipped
+#[derive(Debug, Clone)]
+pub enum AuditSinkConfig {
+    /// RFC 5424 messages over UDP, one per record, naming the host and service
+    Syslog { addr: String, hostname: String, app_name: String },
+    /// Batches POSTed as NDJSON (Splunk HEC, Logstash/Elastic HTTP input, ...)
+    Http { url: String, authorization: Option<String> },
+    /// One NDJSON object per batch, keyed `{prefix}YYYY/MM/DD/<time>-<uuid>.ndjson`
+    S3 { bucket: String, prefix: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditExportConfig {
+    pub sink: AuditSinkConfig,
+    /// Records per shipment
+    pub batch_size: usize,
+    /// Ship a partial batch after this long
+    pub flush_interval: Duration,
+    /// Records kept while the sink is unreachable; the oldest are dropped beyond this
+    pub buffer: usize,
+}
+
+impl AuditExportConfig {
+    /// From the `[audit]` settings, already validated; syslog records name
+    /// `log.hostname` and `log.service_name`. Returns `None` when export is disabled.
+    pub fn from_settings(settings: &AuditSettings, log: &LogSettings) -> Option<Self> {
+        let sink = match settings.sink.as_str() {
+            "syslog" => AuditSinkConfig::Syslog {
+                addr: settings.syslog_addr.clone(),
+                hostname: log.hostname.clone().unwrap_or_else(|| "-".to_string()),
+                app_name: log.service_name.clone(),
+            },
+            "http" => AuditSinkConfig::Http {
+                url: settings.http_url.clone()?,
+                authorization: settings.http_authorization.clone(),
+            },
+            "s3" => AuditSinkConfig::S3 {
+                bucket: settings.s3_bucket.clone()?,
+                prefix: settings.s3_prefix.clone(),
+            },
+            _ => return None,
+        };
+
+        Some(Self {
+            sink,
+            batch_size: settings.batch_size.max(1),
+            flush_interval: Duration::from_secs(settings.flush_secs.max(1)),
+            buffer: settings.buffer.max(1),
+        })
+    }
+}
+
+// ============================================================================
+// Audit Sinks
+// ============================================================================
+
+/// Destination for batches of audit records
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    async fn ship(&self, batch: &[serde_json::Value]) -> Result<(), DomainError>;
+}
+
+fn ndjson(batch: &[serde_json::Value]) -> String {
+    batch.iter().map(|r| format!("{}\n", r)).collect()
+}
+
+pub struct SyslogAuditSink {
+    socket: UdpSocket,
+    addr: String,
+    hostname: String,
+    app_name: String,
+}
+
+impl SyslogAuditSink {
+    pub async fn new(addr: String, hostname: String, app_name: String) -> Result<Self, DomainError> {
+        let socket = UdpSocket::bind("0.0.0.0:0")
+            .await
+            .map_err(|e| DomainError::internal(format!("Failed to open syslog socket: {}", e)))?;
+
+        Ok(Self {
+            socket,
+            addr,
+            hostname,
+            app_name,
+        })
+    }
+}
+
+#[async_trait]
+impl AuditSink for SyslogAuditSink {
+    async fn ship(&self, batch: &[serde_json::Value]) -> Result<(), DomainError> {
+        // PRI 110 = facility 13 (log audit) * 8 + severity 6 (informational)
+        for record in batch {
+            let message = format!(
+                "<110>1 {} {} {} - - - {}",
+                Utc::now().to_rfc3339(),
+                self.hostname,
+                self.app_name,
+                record
+            );
+            self.socket
+                .send_to(message.as_bytes(), &self.addr)
+                .await
+                .map_err(|e| DomainError::internal(format!("Syslog send failed: {}", e)))?;
+        }
+        Ok(())
+    }
+}
+
+pub struct HttpAuditSink {
+    client: reqwest::Client,
+    url: String,
+    authorization: Option<String>,
+}
+
+impl HttpAuditSink {
+    pub fn new(url: String, authorization: Option<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("valid audit HTTP client");
+
+        Self {
+            client,
+            url,
+            authorization,
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for HttpAuditSink {
+    async fn ship(&self, batch: &[serde_json::Value]) -> Result<(), DomainError> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("Content-Type", "application/x-ndjson")
+            .body(ndjson(batch));
+        if let Some(authorization) = &self.authorization {
+            request = request.header("Authorization", authorization);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| DomainError::internal(format!("Audit collector unreachable: {}", e)))?;
+        if !response.status().is_success() {
+            return Err(DomainError::internal(format!("Audit collector answered HTTP {}", response.status())));
+        }
+        Ok(())
+    }
+}
+
+/// Writes batch files to S3 (or a compatible store). Credentials and region
+/// come from the standard `AWS_*` variables.
+pub struct S3AuditSink {
+    store: AmazonS3,
+    prefix: String,
+}
+
+impl S3AuditSink {
+    pub fn new(bucket: String, prefix: String) -> Result<Self, DomainError> {
+        let store = AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .map_err(|e| DomainError::internal(format!("Invalid S3 configuration: {}", e)))?;
+
+        Ok(Self { store, prefix })
+    }
+}
+
+#[async_trait]
+impl AuditSink for S3AuditSink {
+    async fn ship(&self, batch: &[serde_json::Value]) -> Result<(), DomainError> {
+        let now = Utc::now();
+        let key = format!(
+            "{}{}/{}-{}.ndjson",
+            self.prefix,
+            now.format("%Y/%m/%d"),
+            now.format("%H%M%S"),
+            Uuid::new_v4().simple()
+        );
+
+        self.store
+            .put(&ObjectPath::from(key), ndjson(batch).into())
+            .await
+            .map_err(|e| DomainError::internal(format!("S3 upload failed: {}", e)))?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Audit Exporter
+// ============================================================================
+
+/// Cheap handle that queues audit records for a background shipping task.
+/// Records are batched, failed batches are retried with backoff, and the
+/// buffer is bounded so a dead sink cannot exhaust memory.
+#[derive(Clone)]
+pub struct AuditExporter {
+    tx: mpsc::Sender<serde_json::Value>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl AuditExporter {
+    /// Build the configured sink and start the shipping task
+    pub async fn spawn(config: AuditExportConfig) -> Result<Self, DomainError> {
+        let sink: Arc<dyn AuditSink> = match config.sink.clone() {
+            AuditSinkConfig::Syslog { addr, hostname, app_name } => {
+                Arc::new(SyslogAuditSink::new(addr, hostname, app_name).await?)
+            }
+            AuditSinkConfig::Http { url, authorization } => Arc::new(HttpAuditSink::new(url, authorization)),
+            AuditSinkConfig::S3 { bucket, prefix } => Arc::new(S3AuditSink::new(bucket, prefix)?),
+        };
+        Ok(Self::with_sink(sink, config))
+    }
+
+    /// Start shipping to a custom sink
+    pub fn with_sink(sink: Arc<dyn AuditSink>, config: AuditExportConfig) -> Self {
+        let (tx, rx) = mpsc::channel(config.buffer);
+        let dropped = Arc::new(AtomicU64::new(0));
+        tokio::spawn(ship_loop(sink, config, rx, dropped.clone()));
+        Self { tx, dropped }
+    }
+
+    /// Queue a record without blocking; counted as dropped when the buffer is full
+    pub fn export(&self, record: serde_json::Value) {
+        if self.tx.try_send(record).is_err() {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Records lost to a full buffer since startup
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+async fn ship_loop(
+    sink: Arc<dyn AuditSink>,
+    config: AuditExportConfig,
+    mut rx: mpsc::Receiver<serde_json::Value>,
+    dropped: Arc<AtomicU64>,
+) {
+    let mut pending: VecDeque<serde_json::Value> = VecDeque::new();
+    let mut failures: u32 = 0;
+    let mut next_attempt = Instant::now();
+    let mut ticker = tokio::time::interval(config.flush_interval);
+
+    loop {
+        let closed = tokio::select! {
+            record = rx.recv() => match record {
+                Some(record) => {
+                    pending.push_back(record);
+                    if pending.len() > config.buffer {
+                        pending.pop_front();
+                        dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                    if pending.len() < config.batch_size {
+                        continue;
+                    }
+                    false
+                }
+                None => true,
+            },
+            _ = ticker.tick() => false,
+        };
+
+        if Instant::now() >= next_attempt || closed {
+            while !pending.is_empty() {
+                let size = pending.len().min(config.batch_size);
+                let batch: Vec<_> = pending.range(..size).cloned().collect();
+                match sink.ship(&batch).await {
+                    Ok(()) => {
+                        pending.drain(..size);
+                        failures = 0;
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        let delay = config.flush_interval * 2u32.pow(failures.min(6));
+                        next_attempt = Instant::now() + delay;
+                        tracing::warn!(pending = pending.len(), retry_in = ?delay, "Audit export failed: {}", e);
+                        break;
+                    }
+                }
+            }
+        }
+
+        if closed {
+            if !pending.is_empty() {
+                tracing::error!(lost = pending.len(), "Audit exporter stopped with unshipped records");
+            }
+            break;
+        }
+    }
+}
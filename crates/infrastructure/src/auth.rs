@@ -4,8 +4,12 @@ use argon2::{
 };
 use async_trait::async_trait;
 use domain::{Claims, DomainError, TokenPair, User};
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::errors::ErrorKind;
+use jsonwebtoken::{decode, decode_header, encode, DecodingKey, EncodingKey, Header, Validation};
 use application::{PasswordHasher, TokenService};
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
 
 // ============================================================================
 // Argon2 Password Hasher
@@ -54,33 +58,114 @@ impl PasswordHasher for ArgonPasswordHasher {
 // ============================================================================
 
 pub struct JwtConfig {
+    /// Signs new tokens
     pub secret: String,
+    /// Still accepted when checking tokens, so a rotated-out secret keeps
+    /// working until the tokens it signed expire
+    pub previous_secrets: Vec<String>,
     pub expiration_hours: i64,
+    pub guest_expiration_minutes: i64,
+    pub impersonation_expiration_minutes: i64,
 }
 
 impl JwtConfig {
     pub fn new(secret: String, expiration_hours: i64) -> Self {
-        Self { secret, expiration_hours }
+        Self {
+            secret,
+            previous_secrets: Vec::new(),
+            expiration_hours,
+            guest_expiration_minutes: 60,
+            impersonation_expiration_minutes: 15,
+        }
+    }
+
+    pub fn with_previous_secrets(mut self, secrets: Vec<String>) -> Self {
+        self.previous_secrets = secrets;
+        self
+    }
+
+    pub fn with_guest_expiration_minutes(mut self, minutes: i64) -> Self {
+        self.guest_expiration_minutes = minutes;
+        self
     }
 
-    pub fn from_env() -> Self {
+    pub fn with_impersonation_expiration_minutes(mut self, minutes: i64) -> Self {
+        self.impersonation_expiration_minutes = minutes;
+        self
+    }
+}
+
+/// HMAC keys derived from the secrets, each named by a `kid` (the first
+/// 8 bytes of the secret's SHA-256, hex)
+struct JwtKeys {
+    secrets: Vec<String>,
+    signing: (String, EncodingKey),
+    decoding: Vec<(String, DecodingKey)>,
+}
+
+impl JwtKeys {
+    fn new(secret: &str, previous_secrets: &[String]) -> Self {
+        let secrets: Vec<String> = std::iter::once(secret.to_string())
+            .chain(previous_secrets.iter().cloned())
+            .collect();
         Self {
-            secret: std::env::var("JWT_SECRET").unwrap_or_else(|_| "super-secret-key-change-in-production".to_string()),
-            expiration_hours: std::env::var("JWT_EXPIRATION_HOURS")
-                .ok()
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(24),
+            signing: (key_id(secret), EncodingKey::from_secret(secret.as_bytes())),
+            decoding: secrets.iter().map(|s| (key_id(s), DecodingKey::from_secret(s.as_bytes()))).collect(),
+            secrets,
         }
     }
 }
 
+fn key_id(secret: &str) -> String {
+    hex::encode(&Sha256::digest(secret.as_bytes())[..8])
+}
+
+/// Signs with the current secret and accepts tokens signed with it or any
+/// previous one. `set_secrets` swaps the keys in place, so secrets rotate
+/// without a restart.
 pub struct JwtTokenService {
     config: JwtConfig,
+    keys: RwLock<Arc<JwtKeys>>,
 }
 
 impl JwtTokenService {
     pub fn new(config: JwtConfig) -> Self {
-        Self { config }
+        let keys = JwtKeys::new(&config.secret, &config.previous_secrets);
+        Self {
+            config,
+            keys: RwLock::new(Arc::new(keys)),
+        }
+    }
+
+    /// Replace the signing secret and the previous ones still accepted.
+    /// Returns whether anything changed.
+    pub fn set_secrets(&self, secret: &str, previous_secrets: &[String]) -> bool {
+        let keys = JwtKeys::new(secret, previous_secrets);
+        let mut current = self.keys.write().unwrap();
+        if current.secrets == keys.secrets {
+            return false;
+        }
+        *current = Arc::new(keys);
+        true
+    }
+
+    /// `kid`s of the accepted keys, the signing one first
+    pub fn key_ids(&self) -> Vec<String> {
+        self.keys().decoding.iter().map(|(kid, _)| kid.clone()).collect()
+    }
+
+    fn keys(&self) -> Arc<JwtKeys> {
+        self.keys.read().unwrap().clone()
+    }
+
+    fn encode(&self, claims: &Claims) -> Result<String, DomainError> {
+        let keys = self.keys();
+        let (kid, key) = &keys.signing;
+        let header = Header {
+            kid: Some(kid.clone()),
+            ..Header::default()
+        };
+        encode(&header, claims, key).map_err(|e| DomainError::internal(format!("Token generation failed: {}", e)))
     }
 }
 
@@ -92,30 +177,76 @@ impl TokenService for JwtTokenService {
         
         let claims = Claims {
             sub: user.id.to_string(),
-            email: user.email.clone(),
+            email: user.email.to_string(),
             roles: vec!["user".to_string()], // Default role, can be extended
             exp: exp.timestamp(),
             iat: now.timestamp(),
+            tenant_id: Some(user.tenant_id.to_string()),
+            token_version: user.token_version,
+            act: None,
         };
 
-        let token = encode(
-            &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(self.config.secret.as_bytes()),
-        )
-        .map_err(|e| DomainError::internal(format!("Token generation failed: {}", e)))?;
-
+        let token = self.encode(&claims)?;
         Ok(TokenPair::new(token, self.config.expiration_hours * 3600))
     }
 
+    fn generate_guest(&self, guest_id: Uuid, tenant_id: Uuid) -> Result<TokenPair, DomainError> {
+        let now = chrono::Utc::now();
+        let exp = now + chrono::Duration::minutes(self.config.guest_expiration_minutes);
+
+        let claims = Claims {
+            sub: guest_id.to_string(),
+            email: String::new(),
+            roles: vec![Claims::ANONYMOUS_ROLE.to_string()],
+            exp: exp.timestamp(),
+            iat: now.timestamp(),
+            tenant_id: Some(tenant_id.to_string()),
+            token_version: 0,
+            act: None,
+        };
+
+        let token = self.encode(&claims)?;
+        Ok(TokenPair::new(token, self.config.guest_expiration_minutes * 60))
+    }
+
+    fn generate_impersonation(&self, user: &User, actor_id: Uuid) -> Result<TokenPair, DomainError> {
+        let now = chrono::Utc::now();
+        let exp = now + chrono::Duration::minutes(self.config.impersonation_expiration_minutes);
+
+        let claims = Claims {
+            sub: user.id.to_string(),
+            email: user.email.to_string(),
+            roles: vec!["user".to_string()],
+            exp: exp.timestamp(),
+            iat: now.timestamp(),
+            tenant_id: Some(user.tenant_id.to_string()),
+            token_version: user.token_version,
+            act: Some(actor_id.to_string()),
+        };
+
+        let token = self.encode(&claims)?;
+        Ok(TokenPair::new(token, self.config.impersonation_expiration_minutes * 60))
+    }
+
     fn validate(&self, token: &str) -> Result<Claims, DomainError> {
-        let token_data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(self.config.secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map_err(|e| DomainError::unauthorized(format!("Invalid token: {}", e)))?;
-
-        Ok(token_data.claims)
+        let invalid = |e: jsonwebtoken::errors::Error| DomainError::unauthorized(format!("Invalid token: {}", e));
+        let kid = decode_header(token).map_err(invalid)?.kid;
+
+        // A known `kid` names the key; tokens without one (issued before
+        // keys were named) or with an unknown one are tried against each
+        let keys = self.keys();
+        let named = keys.decoding.iter().find(|(id, _)| Some(id) == kid.as_ref());
+        let candidates: Vec<&DecodingKey> = match named {
+            Some((_, key)) => vec![key],
+            None => keys.decoding.iter().map(|(_, key)| key).collect(),
+        };
+        for key in candidates {
+            match decode::<Claims>(token, key, &Validation::default()) {
+                Ok(token_data) => return Ok(token_data.claims),
+                Err(e) if matches!(e.kind(), ErrorKind::InvalidSignature) => continue,
+                Err(e) => return Err(invalid(e)),
+            }
+        }
+        Err(invalid(ErrorKind::InvalidSignature.into()))
     }
 }
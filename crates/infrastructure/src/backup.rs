@@ -0,0 +1,332 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::{BufRead, Write};
+
+use application::webhooks::DeliverWebhookJob;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::map_sqlx_error;
+
+// ============================================================================
+// Queue Tables
+// ============================================================================
+
+/// Tables holding undelivered work, in restore order, with the condition
+/// selecting unfinished rows. Webhook deliveries act as the outbox for
+/// domain events; the jobs that send them point at their ids.
+pub const QUEUE_TABLES: &[(&str, &str)] = &[
+    ("webhook_deliveries", "status = 'pending'"),
+    ("jobs", "status IN ('pending', 'running')"),
+];
+
+const FORMAT: &str = "rust_base.queue_backup";
+const VERSION: u32 = 1;
+
+// ============================================================================
+// Snapshot File
+// ============================================================================
+
+/// First line of a backup file
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupHeader {
+    pub format: String,
+    pub version: u32,
+    pub database: String,
+    pub exported_at: DateTime<Utc>,
+    /// Only pending/running rows were exported
+    pub unfinished_only: bool,
+    pub tables: BTreeMap<String, TableDigest>,
+}
+
+/// Row count and SHA-256 over the rows, to detect truncated or edited files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableDigest {
+    pub rows: u64,
+    pub sha256: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BackupLine {
+    table: String,
+    row: Value,
+}
+
+/// Rows of the queue tables taken in one snapshot. Written as JSON lines: a
+/// `BackupHeader`, then one `{"table", "row"}` object per row.
+pub struct QueueSnapshot {
+    pub header: BackupHeader,
+    pub rows: Vec<(String, Value)>,
+}
+
+impl QueueSnapshot {
+    pub fn write_to(&self, mut out: impl Write) -> std::io::Result<()> {
+        serde_json::to_writer(&mut out, &self.header)?;
+        out.write_all(b"\n")?;
+        for (table, row) in &self.rows {
+            serde_json::to_writer(
+                &mut out,
+                &BackupLine {
+                    table: table.clone(),
+                    row: row.clone(),
+                },
+            )?;
+            out.write_all(b"\n")?;
+        }
+        out.flush()
+    }
+
+    /// Parse a backup file and check it against its header
+    pub fn read_from(input: impl BufRead) -> Result<Self, DomainError> {
+        let mut lines = input.lines();
+        let header: BackupHeader = match lines.next() {
+            Some(line) => serde_json::from_str(&line.map_err(io_error)?)
+                .map_err(|e| DomainError::validation(format!("Invalid backup header: {}", e)))?,
+            None => return Err(DomainError::validation("Backup file is empty")),
+        };
+        if header.format != FORMAT || header.version != VERSION {
+            return Err(DomainError::validation(format!(
+                "Unsupported backup format {} v{} (expected {} v{})",
+                header.format, header.version, FORMAT, VERSION
+            )));
+        }
+
+        let mut rows = Vec::new();
+        for (number, line) in lines.enumerate() {
+            let line = line.map_err(io_error)?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let line: BackupLine = serde_json::from_str(&line)
+                .map_err(|e| DomainError::validation(format!("Invalid backup line {}: {}", number + 2, e)))?;
+            rows.push((line.table, line.row));
+        }
+
+        let snapshot = Self { header, rows };
+        let digests = snapshot.digests();
+        if digests != snapshot.header.tables {
+            return Err(DomainError::validation(format!(
+                "Backup rows do not match the header (expected {:?}, found {:?}); the file is truncated or was edited",
+                snapshot.header.tables, digests
+            )));
+        }
+        Ok(snapshot)
+    }
+
+    fn digests(&self) -> BTreeMap<String, TableDigest> {
+        let mut hashers: BTreeMap<String, (u64, Sha256)> = BTreeMap::new();
+        for (table, row) in &self.rows {
+            let (rows, hasher) = hashers.entry(table.clone()).or_insert_with(|| (0, Sha256::new()));
+            *rows += 1;
+            hasher.update(row.to_string().as_bytes());
+            hasher.update(b"\n");
+        }
+        hashers
+            .into_iter()
+            .map(|(table, (rows, hasher))| {
+                (
+                    table,
+                    TableDigest {
+                        rows,
+                        sha256: hex::encode(hasher.finalize()),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// `(job, delivery)` for `webhook.deliver` jobs whose delivery is not in
+    /// the snapshot; it must already exist in the target database
+    fn external_deliveries(&self) -> Vec<(Uuid, Uuid)> {
+        let deliveries: HashSet<Uuid> = self.ids("webhook_deliveries").collect();
+        self.rows
+            .iter()
+            .filter(|(table, row)| table == "jobs" && row["kind"] == DeliverWebhookJob::KIND)
+            .filter_map(|(_, row)| Some((row_id(row)?, row["payload"]["delivery_id"].as_str()?.parse().ok()?)))
+            .filter(|(_, delivery)| !deliveries.contains(delivery))
+            .collect()
+    }
+
+    fn ids<'a>(&'a self, table: &'a str) -> impl Iterator<Item = Uuid> + 'a {
+        self.rows.iter().filter(move |(t, _)| t == table).filter_map(|(_, row)| row_id(row))
+    }
+}
+
+fn row_id(row: &Value) -> Option<Uuid> {
+    row["id"].as_str()?.parse().ok()
+}
+
+fn io_error(err: std::io::Error) -> DomainError {
+    DomainError::internal(format!("Failed to read backup: {}", err))
+}
+
+// ============================================================================
+// Backup & Restore
+// ============================================================================
+
+/// Rows restored per table
+#[derive(Debug, Default)]
+pub struct RestoreReport {
+    /// (table, inserted, skipped because the id already exists)
+    pub tables: Vec<(String, u64, u64)>,
+}
+
+/// Exports and re-imports `QUEUE_TABLES` around maintenance windows
+pub struct QueueBackup {
+    pool: PgPool,
+}
+
+impl QueueBackup {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Name of the connected database, for the CLI's confirmation check
+    pub async fn database_name(&self) -> Result<String, DomainError> {
+        sqlx::query_scalar("SELECT current_database()")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Database"))
+    }
+
+    /// Read every queue table in one repeatable-read snapshot, so jobs and
+    /// the deliveries they reference are consistent with each other
+    pub async fn export(&self, unfinished_only: bool) -> Result<QueueSnapshot, DomainError> {
+        let mut tx = self.pool.begin().await.map_err(|e| map_sqlx_error(e, "Backup"))?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Backup"))?;
+
+        let database: String = sqlx::query_scalar("SELECT current_database()")
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Backup"))?;
+
+        let mut rows = Vec::new();
+        for (table, unfinished) in QUEUE_TABLES {
+            let condition = if unfinished_only { unfinished } else { "TRUE" };
+            let sql = format!(
+                "SELECT row_to_json(t)::text FROM (SELECT * FROM {} WHERE {} ORDER BY created_at, id) t",
+                table, condition
+            );
+            let json: Vec<String> = sqlx::query_scalar(&sql)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| map_sqlx_error(e, "Backup"))?;
+            for row in json {
+                let row = serde_json::from_str(&row)
+                    .map_err(|e| DomainError::internal(format!("Unreadable {} row: {}", table, e)))?;
+                rows.push((table.to_string(), row));
+            }
+        }
+        tx.rollback().await.map_err(|e| map_sqlx_error(e, "Backup"))?;
+
+        let mut snapshot = QueueSnapshot {
+            header: BackupHeader {
+                format: FORMAT.to_string(),
+                version: VERSION,
+                database,
+                exported_at: Utc::now(),
+                unfinished_only,
+                tables: BTreeMap::new(),
+            },
+            rows,
+        };
+        snapshot.header.tables = snapshot.digests();
+        Ok(snapshot)
+    }
+
+    /// Insert the snapshot's rows in one transaction, skipping ids that
+    /// already exist. Fails before writing anything if deliveries reference
+    /// missing webhooks or delivery jobs reference missing deliveries.
+    /// With `dry_run` the transaction is rolled back.
+    pub async fn restore(&self, snapshot: &QueueSnapshot, dry_run: bool) -> Result<RestoreReport, DomainError> {
+        if let Some(table) = snapshot
+            .rows
+            .iter()
+            .map(|(table, _)| table)
+            .find(|table| !QUEUE_TABLES.iter().any(|(name, _)| name == table))
+        {
+            return Err(DomainError::validation(format!("Backup contains unknown table '{}'", table)));
+        }
+
+        let mut tx = self.pool.begin().await.map_err(|e| map_sqlx_error(e, "Restore"))?;
+
+        let webhooks: Vec<Uuid> = snapshot
+            .rows
+            .iter()
+            .filter(|(table, _)| table == "webhook_deliveries")
+            .filter_map(|(_, row)| row["webhook_id"].as_str()?.parse().ok())
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let known: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM webhooks WHERE id = ANY($1)")
+            .bind(&webhooks)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Restore"))?;
+        let missing: Vec<_> = webhooks.iter().filter(|id| !known.contains(id)).collect();
+        if !missing.is_empty() {
+            return Err(DomainError::validation(format!(
+                "Deliveries reference {} webhook(s) missing from this database: {:?}",
+                missing.len(),
+                missing
+            )));
+        }
+
+        let external = snapshot.external_deliveries();
+        let referenced: Vec<Uuid> = external.iter().map(|(_, delivery)| *delivery).collect();
+        let existing: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM webhook_deliveries WHERE id = ANY($1)")
+            .bind(&referenced)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Restore"))?;
+        let orphans: Vec<Uuid> = external
+            .into_iter()
+            .filter(|(_, delivery)| !existing.contains(delivery))
+            .map(|(job, _)| job)
+            .collect();
+        if !orphans.is_empty() {
+            return Err(DomainError::validation(format!(
+                "{} webhook job(s) reference deliveries that are neither in the backup nor in this database: {:?}",
+                orphans.len(),
+                orphans
+            )));
+        }
+
+        let mut report = RestoreReport::default();
+        for (table, _) in QUEUE_TABLES {
+            let sql = format!(
+                "INSERT INTO {0} SELECT * FROM json_populate_record(NULL::{0}, $1::json) ON CONFLICT (id) DO NOTHING",
+                table
+            );
+            let (mut inserted, mut skipped) = (0, 0);
+            for (_, row) in snapshot.rows.iter().filter(|(t, _)| t == table) {
+                let affected = sqlx::query(&sql)
+                    .bind(row.to_string())
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| map_sqlx_error(e, "Restore"))?
+                    .rows_affected();
+                if affected > 0 {
+                    inserted += 1;
+                } else {
+                    skipped += 1;
+                }
+            }
+            report.tables.push((table.to_string(), inserted, skipped));
+        }
+
+        if dry_run {
+            tx.rollback().await.map_err(|e| map_sqlx_error(e, "Restore"))?;
+        } else {
+            tx.commit().await.map_err(|e| map_sqlx_error(e, "Restore"))?;
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,71 @@
+use application::data_browser::{BrowsableTable, DataBrowser};
+use application::ApplicationError;
+use async_trait::async_trait;
+use domain::{DomainError, Page, PaginationParams};
+use serde_json::{Map, Value};
+use sqlx::Connection;
+
+use crate::db::Database;
+
+// ============================================================================
+// Postgres Data Browser
+// ============================================================================
+
+/// Reads whitelisted tables inside a `READ ONLY` transaction that is always
+/// rolled back. Table and column names come from `BROWSABLE_TABLES`, never
+/// from the request. Uses the replica when one is configured.
+pub struct PgDataBrowser {
+    db: Database,
+}
+
+impl PgDataBrowser {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("Data browser query failed: {}", err)).into()
+}
+
+#[async_trait]
+impl DataBrowser for PgDataBrowser {
+    async fn fetch_rows(&self, table: &BrowsableTable, params: &PaginationParams) -> Result<Page<Map<String, Value>>, ApplicationError> {
+        params.validate()?;
+        let mut conn = self.db.acquire_read().await?;
+        let mut tx = conn.begin().await.map_err(map_err)?;
+
+        sqlx::query("SET TRANSACTION READ ONLY")
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err)?;
+
+        let rows: Vec<(Value,)> = sqlx::query_as(&format!(
+            "SELECT row_to_json(t)::jsonb FROM (SELECT {} FROM {} ORDER BY {} LIMIT $1 OFFSET $2) t",
+            table.columns.join(", "),
+            table.name,
+            table.order_by,
+        ))
+        .bind(params.limit() as i64)
+        .bind(params.offset() as i64)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(map_err)?;
+
+        let total: (i64,) = sqlx::query_as(&format!("SELECT COUNT(*) FROM {}", table.name))
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(map_err)?;
+
+        tx.rollback().await.map_err(map_err)?;
+
+        let items = rows
+            .into_iter()
+            .filter_map(|(row,)| match row {
+                Value::Object(map) => Some(map),
+                _ => None,
+            })
+            .collect();
+        Ok(Page::new(items, total.0 as u64, params))
+    }
+}
@@ -0,0 +1,144 @@
+use application::data_export::{DataExport, DataExportStore, ExportFormat};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use uuid::Uuid;
+
+use crate::db::{Database, DbConnection};
+
+// ============================================================================
+// Postgres Data Export Store
+// ============================================================================
+
+/// Export records in the `data_exports` table
+pub struct PgDataExportStore {
+    db: Database,
+}
+
+impl PgDataExportStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn conn(&self) -> Result<DbConnection, ApplicationError> {
+        Ok(self.db.acquire().await?)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct DataExportRow {
+    id: Uuid,
+    user_id: Uuid,
+    format: String,
+    requested_at: DateTime<Utc>,
+    key: Option<String>,
+    token_hash: Option<String>,
+    expires_at: Option<DateTime<Utc>>,
+    downloaded_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<DataExportRow> for DataExport {
+    type Error = ApplicationError;
+
+    fn try_from(row: DataExportRow) -> Result<Self, Self::Error> {
+        let format = ExportFormat::parse(&row.format)
+            .ok_or_else(|| DomainError::internal(format!("Unknown export format: {}", row.format)))?;
+        Ok(Self {
+            id: row.id,
+            user_id: row.user_id,
+            format,
+            requested_at: row.requested_at,
+            key: row.key,
+            token_hash: row.token_hash,
+            expires_at: row.expires_at,
+            downloaded_at: row.downloaded_at,
+        })
+    }
+}
+
+const COLUMNS: &str = "id, user_id, format, requested_at, key, token_hash, expires_at, downloaded_at";
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("Data export store error: {}", err)).into()
+}
+
+#[async_trait]
+impl DataExportStore for PgDataExportStore {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "DataExport", operation = "create"))]
+    async fn create(&self, export: &DataExport) -> Result<(), ApplicationError> {
+        sqlx::query(&format!(
+            "INSERT INTO data_exports ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            COLUMNS
+        ))
+        .bind(export.id)
+        .bind(export.user_id)
+        .bind(export.format.as_str())
+        .bind(export.requested_at)
+        .bind(&export.key)
+        .bind(&export.token_hash)
+        .bind(export.expires_at)
+        .bind(export.downloaded_at)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "DataExport", operation = "find"))]
+    async fn find(&self, id: Uuid) -> Result<Option<DataExport>, ApplicationError> {
+        sqlx::query_as::<_, DataExportRow>(&format!("SELECT {} FROM data_exports WHERE id = $1", COLUMNS))
+            .bind(id)
+            .fetch_optional(&mut self.conn().await?)
+            .await
+            .map_err(map_err)?
+            .map(DataExport::try_from)
+            .transpose()
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "DataExport", operation = "latest"))]
+    async fn latest(&self, user_id: Uuid) -> Result<Option<DataExport>, ApplicationError> {
+        sqlx::query_as::<_, DataExportRow>(&format!(
+            "SELECT {} FROM data_exports WHERE user_id = $1 ORDER BY requested_at DESC LIMIT 1",
+            COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_optional(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?
+        .map(DataExport::try_from)
+        .transpose()
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "DataExport", operation = "mark_ready"))]
+    async fn mark_ready(&self, id: Uuid, key: &str, token_hash: &str, expires_at: DateTime<Utc>) -> Result<(), ApplicationError> {
+        sqlx::query("UPDATE data_exports SET key = $2, token_hash = $3, expires_at = $4 WHERE id = $1")
+            .bind(id)
+            .bind(key)
+            .bind(token_hash)
+            .bind(expires_at)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "DataExport", operation = "claim_download"))]
+    async fn claim_download(&self, token_hash: &str, now: DateTime<Utc>) -> Result<Option<DataExport>, ApplicationError> {
+        sqlx::query_as::<_, DataExportRow>(&format!(
+            r#"
+            UPDATE data_exports SET downloaded_at = $2
+            WHERE token_hash = $1 AND downloaded_at IS NULL AND expires_at > $2
+            RETURNING {}
+            "#,
+            COLUMNS
+        ))
+        .bind(token_hash)
+        .bind(now)
+        .fetch_optional(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?
+        .map(DataExport::try_from)
+        .transpose()
+    }
+}
@@ -0,0 +1,642 @@
+use std::any::Any;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use application::query_stats::query_stats;
+use application::timings::{self, PhaseTimer};
+use application::{current_consistency, current_statement_timeout, current_transaction, Transaction, UnitOfWork};
+use async_trait::async_trait;
+use domain::{DomainError, PaginationParams};
+use futures_util::future::BoxFuture;
+use futures_util::stream::BoxStream;
+use futures_util::{FutureExt, StreamExt};
+use sqlx::pool::PoolConnection;
+use sqlx::postgres::{PgQueryResult, PgRow, PgStatement, PgTypeInfo};
+use sqlx::{Describe, Either, Execute, Executor, FromRow, PgConnection, PgPool, Postgres, Row};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+use tracing::Instrument;
+
+type PgTx = sqlx::Transaction<'static, Postgres>;
+
+// ============================================================================
+// Database (primary + optional read replica)
+// ============================================================================
+
+/// Primary pool plus an optional streaming replica used for reads.
+///
+/// Reads go to the replica only when it has replayed every write the current
+/// request must observe (read-your-writes), and, with a lag limit, when it is
+/// not lagging behind the primary; otherwise they fall back to the primary.
+#[derive(Clone)]
+pub struct Database {
+    primary: PgPool,
+    replica: Option<PgPool>,
+    max_replica_lag: Option<Duration>,
+    /// Last measured replica lag in milliseconds (`LAG_UNKNOWN` until measured)
+    replica_lag_ms: Arc<AtomicU64>,
+    /// Page queries count their total with `COUNT(*) OVER()`
+    window_counts: bool,
+}
+
+/// Replica unreachable or not measured yet
+const LAG_UNKNOWN: u64 = u64::MAX;
+
+impl Database {
+    pub fn new(primary: PgPool) -> Self {
+        Self {
+            primary,
+            replica: None,
+            max_replica_lag: None,
+            replica_lag_ms: Arc::new(AtomicU64::new(LAG_UNKNOWN)),
+            window_counts: false,
+        }
+    }
+
+    /// Route eligible reads to `replica`
+    pub fn with_replica(mut self, replica: PgPool) -> Self {
+        self.replica = Some(replica);
+        self
+    }
+
+    /// Send reads to the primary while the replica is more than `max` behind
+    /// (e.g. a cross-region replica after a network partition). Takes effect
+    /// once `spawn_replica_lag_monitor` runs.
+    pub fn with_max_replica_lag(mut self, max: Duration) -> Self {
+        self.max_replica_lag = Some(max);
+        self
+    }
+
+    /// Count page totals in the page query itself (`pagination.count = "window"`)
+    /// instead of a second `COUNT(*)`. One round trip fewer, but Postgres
+    /// reads every matching row before applying `LIMIT`, which can cost more
+    /// than an index-only count on wide rows.
+    pub fn with_window_counts(mut self, enabled: bool) -> Self {
+        self.window_counts = enabled;
+        self
+    }
+
+    /// Select-list suffix for a page query, read back by `Counted`:
+    /// the window count when enabled, `NULL` otherwise
+    pub(crate) fn total_column(&self) -> &'static str {
+        if self.window_counts {
+            ", COUNT(*) OVER() AS total_count"
+        } else {
+            ", NULL::bigint AS total_count"
+        }
+    }
+
+    /// Measure the replica's lag every `interval` in the background
+    pub fn spawn_replica_lag_monitor(&self, interval: Duration) {
+        let Some(replica) = self.replica.clone() else {
+            return;
+        };
+        let lag_ms = self.replica_lag_ms.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                // Caught up when everything received is replayed; otherwise the
+                // age of the last replayed transaction. NULL: not in recovery.
+                let measured = sqlx::query_scalar::<_, Option<i64>>(
+                    r#"
+                    SELECT CASE
+                        WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0
+                        ELSE (EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp()) * 1000)::bigint
+                    END
+                    "#,
+                )
+                .fetch_one(&replica)
+                .await;
+                let lag = match measured {
+                    Ok(lag) => lag.unwrap_or(0).max(0) as u64,
+                    Err(e) => {
+                        tracing::warn!("Failed to measure replica lag: {}", e);
+                        LAG_UNKNOWN
+                    }
+                };
+                lag_ms.store(lag, Ordering::Relaxed);
+            }
+        });
+    }
+
+    /// Last measured replica lag; `None` without a replica or measurement
+    pub fn replica_lag(&self) -> Option<Duration> {
+        match self.replica_lag_ms.load(Ordering::Relaxed) {
+            LAG_UNKNOWN => None,
+            ms => self.replica.as_ref().map(|_| Duration::from_millis(ms)),
+        }
+    }
+
+    fn replica_lagging(&self) -> bool {
+        let Some(max) = self.max_replica_lag else {
+            return false;
+        };
+        match self.replica_lag_ms.load(Ordering::Relaxed) {
+            LAG_UNKNOWN => true,
+            ms => Duration::from_millis(ms) > max,
+        }
+    }
+
+    pub fn primary(&self) -> &PgPool {
+        &self.primary
+    }
+
+    pub fn has_replica(&self) -> bool {
+        self.replica.is_some()
+    }
+
+    /// Connection for writes (and reads that must hit the primary)
+    pub async fn acquire(&self) -> Result<DbConnection, DomainError> {
+        DbConnection::acquire(&self.primary).await
+    }
+
+    /// Connection for reads: the replica when it is caught up with this
+    /// request's required position, the primary otherwise.
+    pub async fn acquire_read(&self) -> Result<DbConnection, DomainError> {
+        let Some(replica) = &self.replica else {
+            return self.acquire().await;
+        };
+        // Reads inside a unit of work must see its uncommitted writes
+        if current_transaction().is_some() {
+            return self.acquire().await;
+        }
+        if self.replica_lagging() {
+            tracing::debug!("replica lag above limit, reading from primary");
+            return self.acquire().await;
+        }
+
+        let mut conn = DbConnection::acquire(replica).await?;
+        let Some(required) = current_consistency().and_then(|s| s.required_position()) else {
+            return Ok(conn);
+        };
+
+        let replayed: Option<String> = sqlx::query_scalar("SELECT pg_last_wal_replay_lsn()::text")
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| DomainError::internal(format!("Failed to read replica position: {}", e)))?;
+
+        // NULL means the server is not in recovery, i.e. it is a primary itself
+        let caught_up = match (replayed.as_deref().and_then(parse_lsn), parse_lsn(&required)) {
+            (Some(replayed), Some(required)) => replayed >= required,
+            _ => replayed.is_none(),
+        };
+
+        if caught_up {
+            Ok(conn)
+        } else {
+            tracing::debug!(%required, "replica behind session position, reading from primary");
+            self.acquire().await
+        }
+    }
+
+    /// Record the primary's WAL position after an autocommitted write so the
+    /// session's following reads can wait for it. Writes inside a unit of work
+    /// are recorded when the transaction commits instead.
+    pub async fn record_write(&self) {
+        if self.replica.is_some() && current_transaction().is_none() {
+            record_primary_position(&self.primary).await;
+        }
+    }
+}
+
+async fn record_primary_position(pool: &PgPool) {
+    let Some(session) = current_consistency() else {
+        return;
+    };
+    match sqlx::query_scalar::<_, String>("SELECT pg_current_wal_lsn()::text")
+        .fetch_one(pool)
+        .await
+    {
+        Ok(position) => session.record_write(position),
+        Err(e) => tracing::warn!("Failed to read primary WAL position: {}", e),
+    }
+}
+
+/// Parse a PostgreSQL LSN (`16/B374D848`) into a comparable integer
+fn parse_lsn(lsn: &str) -> Option<u64> {
+    let (hi, lo) = lsn.split_once('/')?;
+    Some((u64::from_str_radix(hi, 16).ok()? << 32) | u64::from_str_radix(lo, 16).ok()?)
+}
+
+// ============================================================================
+// Page Totals
+// ============================================================================
+
+/// Row of a page query selecting `Database::total_column`
+pub(crate) struct Counted<R> {
+    pub row: R,
+    pub total_count: Option<i64>,
+}
+
+impl<'r, R: FromRow<'r, PgRow>> FromRow<'r, PgRow> for Counted<R> {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            row: R::from_row(row)?,
+            total_count: row.try_get("total_count")?,
+        })
+    }
+}
+
+/// Total of a page read with `Counted` rows, when the rows tell: the window
+/// count, or zero for an empty first page. `None` means counting separately
+/// (window counts off, or a page past the end).
+pub(crate) fn page_total<R>(rows: &[Counted<R>], params: &PaginationParams) -> Option<u64> {
+    match rows.first() {
+        Some(first) => first.total_count.map(|total| total as u64),
+        None if params.offset() == 0 => Some(0),
+        None => None,
+    }
+}
+
+// ============================================================================
+// Unit of Work
+// ============================================================================
+
+/// PostgreSQL unit of work: each `begin` opens a new transaction on the primary.
+pub struct PgUnitOfWork {
+    db: Database,
+}
+
+impl PgUnitOfWork {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl UnitOfWork for PgUnitOfWork {
+    async fn begin(&self) -> Result<Arc<dyn Transaction>, DomainError> {
+        let tx = self
+            .db
+            .primary()
+            .begin()
+            .await
+            .map_err(|e| DomainError::internal(format!("Failed to begin transaction: {}", e)))?;
+        Ok(Arc::new(PgTransaction {
+            inner: Arc::new(Mutex::new(Some(tx))),
+            db: self.db.clone(),
+        }))
+    }
+}
+
+/// Open PostgreSQL transaction; `None` once committed or rolled back.
+pub struct PgTransaction {
+    inner: Arc<Mutex<Option<PgTx>>>,
+    db: Database,
+}
+
+#[async_trait]
+impl Transaction for PgTransaction {
+    async fn commit(&self) -> Result<(), DomainError> {
+        let tx = self
+            .inner
+            .lock()
+            .await
+            .take()
+            .ok_or_else(|| DomainError::internal("Transaction already finished"))?;
+        tx.commit()
+            .await
+            .map_err(|e| DomainError::internal(format!("Failed to commit transaction: {}", e)))?;
+
+        if self.db.has_replica() {
+            record_primary_position(self.db.primary()).await;
+        }
+        Ok(())
+    }
+
+    async fn rollback(&self) -> Result<(), DomainError> {
+        match self.inner.lock().await.take() {
+            Some(tx) => tx
+                .rollback()
+                .await
+                .map_err(|e| DomainError::internal(format!("Failed to roll back transaction: {}", e))),
+            None => Ok(()),
+        }
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// ============================================================================
+// Connection Acquisition
+// ============================================================================
+
+/// Connection for a single repository query: the ambient unit-of-work
+/// transaction when one is active on this task, otherwise a pooled connection.
+///
+/// Statements run under the pool's `statement_timeout` unless the task is in
+/// a `with_statement_timeout` scope. Inside a transaction the override lasts
+/// until the transaction ends; a pooled connection is reset before it goes
+/// back to the pool.
+///
+/// Hold it for one statement only; a repository method that keeps it while
+/// calling another method would deadlock on the shared transaction.
+pub enum DbConnection {
+    Pooled(Box<PoolConnection<Postgres>>),
+    Transaction(OwnedMutexGuard<Option<PgTx>>),
+    /// Pooled connection with an overridden statement timeout
+    Overridden(OverriddenConnection),
+}
+
+impl DbConnection {
+    pub async fn acquire(pool: &PgPool) -> Result<Self, DomainError> {
+        let timeout = current_statement_timeout();
+        if let Some(tx) = current_transaction() {
+            if let Some(pg) = tx.as_any().downcast_ref::<PgTransaction>() {
+                let guard = pg.inner.clone().lock_owned().await;
+                if guard.is_none() {
+                    return Err(DomainError::internal("Transaction already finished"));
+                }
+                let mut conn = Self::Transaction(guard);
+                if let Some(timeout) = timeout {
+                    set_statement_timeout(&mut conn, timeout, true).await?;
+                }
+                return Ok(conn);
+            }
+        }
+
+        let conn = pool.acquire().await.map_err(|e| match e {
+            sqlx::Error::PoolTimedOut => DomainError::timeout("Timed out waiting for a database connection"),
+            e if crate::is_transient(&e) => DomainError::unavailable(format!("Failed to acquire connection: {}", e)),
+            e => DomainError::internal(format!("Failed to acquire connection: {}", e)),
+        })?;
+        match timeout {
+            Some(timeout) => {
+                let mut conn = Self::Overridden(OverriddenConnection(Some(Box::new(conn))));
+                set_statement_timeout(&mut conn, timeout, false).await?;
+                Ok(conn)
+            }
+            None => Ok(Self::Pooled(Box::new(conn))),
+        }
+    }
+}
+
+/// `statement_timeout` for the session, or with `local` the transaction
+async fn set_statement_timeout(conn: &mut PgConnection, timeout: Duration, local: bool) -> Result<(), DomainError> {
+    sqlx::query("SELECT set_config('statement_timeout', $1, $2)")
+        .bind(timeout.as_millis().to_string())
+        .bind(local)
+        .execute(conn)
+        .await
+        .map(|_| ())
+        .map_err(|e| DomainError::internal(format!("Failed to set statement timeout: {}", e)))
+}
+
+/// Pooled connection whose session `statement_timeout` was changed. Dropping
+/// it restores the pool's default in the background, closing the connection
+/// instead if that fails.
+pub struct OverriddenConnection(Option<Box<PoolConnection<Postgres>>>);
+
+impl Drop for OverriddenConnection {
+    fn drop(&mut self) {
+        let Some(mut conn) = self.0.take() else {
+            return;
+        };
+        tokio::spawn(async move {
+            if sqlx::query("RESET statement_timeout").execute(&mut **conn).await.is_err() {
+                let _ = conn.close().await;
+            }
+        });
+    }
+}
+
+impl Deref for DbConnection {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &PgConnection {
+        match self {
+            Self::Pooled(conn) => conn,
+            Self::Transaction(guard) => guard.as_ref().expect("checked in acquire"),
+            Self::Overridden(conn) => conn.0.as_ref().expect("taken on drop"),
+        }
+    }
+}
+
+impl DerefMut for DbConnection {
+    fn deref_mut(&mut self) -> &mut PgConnection {
+        match self {
+            Self::Pooled(conn) => conn,
+            Self::Transaction(guard) => guard.as_mut().expect("checked in acquire"),
+            Self::Overridden(conn) => conn.0.as_mut().expect("taken on drop"),
+        }
+    }
+}
+
+impl std::fmt::Debug for DbConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Pooled(_) => "DbConnection::Pooled",
+            Self::Transaction(_) => "DbConnection::Transaction",
+            Self::Overridden(_) => "DbConnection::Overridden",
+        })
+    }
+}
+
+// ============================================================================
+// Query Tracing
+// ============================================================================
+
+/// Queries run through `&mut DbConnection` get a `db.query` span with the
+/// normalized statement, its fingerprint and the number of rows returned or
+/// affected. The span lives as long as the query, so its duration is the time
+/// spent in the database (plus decoding). The same time counts towards the
+/// request's `db` phase (see `application::timings`) and the query's latency
+/// histogram (see `application::query_stats`).
+impl<'c> Executor<'c> for &'c mut DbConnection {
+    type Database = Postgres;
+
+    fn fetch_many<'e, 'q: 'e, E>(self, query: E) -> BoxStream<'e, Result<Either<PgQueryResult, PgRow>, sqlx::Error>>
+    where
+        'c: 'e,
+        E: Execute<'q, Postgres> + 'q,
+    {
+        // The stream owns the recorder, so the query ends when it does
+        let mut recorder = QueryRecorder::start(query.sql());
+        let conn: &'c mut PgConnection = self;
+        let (mut returned, mut affected) = (0u64, 0u64);
+        // RETURNING and SELECT report their rows in the result as well
+        conn.fetch_many(query)
+            .inspect(move |step| {
+                match step {
+                    Ok(Either::Left(done)) => affected += done.rows_affected(),
+                    Ok(Either::Right(_)) => returned += 1,
+                    Err(_) => {
+                        recorder.failed = true;
+                        return;
+                    }
+                }
+                recorder.set_rows(returned.max(affected));
+            })
+            .boxed()
+    }
+
+    fn fetch_optional<'e, 'q: 'e, E>(self, query: E) -> BoxFuture<'e, Result<Option<PgRow>, sqlx::Error>>
+    where
+        'c: 'e,
+        E: Execute<'q, Postgres> + 'q,
+    {
+        let mut recorder = QueryRecorder::start(query.sql());
+        let span = recorder.span.clone();
+        let conn: &'c mut PgConnection = self;
+        let row = conn.fetch_optional(query);
+        async move {
+            let row = row.await;
+            match &row {
+                Ok(row) => recorder.set_rows(u64::from(row.is_some())),
+                Err(_) => recorder.failed = true,
+            }
+            drop(recorder);
+            row
+        }
+        .instrument(span)
+        .boxed()
+    }
+
+    fn prepare_with<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+        parameters: &'e [PgTypeInfo],
+    ) -> BoxFuture<'e, Result<PgStatement<'q>, sqlx::Error>>
+    where
+        'c: 'e,
+    {
+        let conn: &'c mut PgConnection = self;
+        conn.prepare_with(sql, parameters)
+    }
+
+    fn describe<'e, 'q: 'e>(self, sql: &'q str) -> BoxFuture<'e, Result<Describe<Postgres>, sqlx::Error>>
+    where
+        'c: 'e,
+    {
+        let conn: &'c mut PgConnection = self;
+        conn.describe(sql)
+    }
+}
+
+/// One statement in flight: its span, the request's `db` phase and, once
+/// dropped (finished or abandoned), its entry in `application::query_stats`.
+/// Statements slower than the slow-query threshold are logged under
+/// `slow_query` by shape only; bound parameters are never logged.
+struct QueryRecorder {
+    span: tracing::Span,
+    fingerprint: String,
+    statement: String,
+    started: Instant,
+    rows: u64,
+    failed: bool,
+    _timer: PhaseTimer,
+}
+
+impl QueryRecorder {
+    fn start(sql: &str) -> Self {
+        let statement = normalize_query(sql);
+        let fingerprint = query_fingerprint(&statement);
+        let span = tracing::info_span!(
+            "db.query",
+            db.system = "postgresql",
+            db.fingerprint = %fingerprint,
+            db.statement = %statement,
+            db.rows = tracing::field::Empty,
+        );
+        Self {
+            span,
+            fingerprint,
+            statement,
+            started: Instant::now(),
+            rows: 0,
+            failed: false,
+            _timer: timings::start(timings::DB),
+        }
+    }
+
+    fn set_rows(&mut self, rows: u64) {
+        self.rows = rows;
+        self.span.record("db.rows", rows);
+    }
+}
+
+impl Drop for QueryRecorder {
+    fn drop(&mut self) {
+        let elapsed = self.started.elapsed();
+        shared::telemetry::telemetry().observe(
+            "db_query_duration_seconds",
+            elapsed.as_secs_f64(),
+            &[("fingerprint", &self.fingerprint)],
+        );
+        if query_stats().record(&self.fingerprint, &self.statement, elapsed, self.failed) {
+            self.span.in_scope(|| {
+                tracing::warn!(
+                    target: "slow_query",
+                    fingerprint = %self.fingerprint,
+                    statement = %self.statement,
+                    rows = self.rows,
+                    failed = self.failed,
+                    duration_ms = elapsed.as_millis() as u64,
+                    "Slow query"
+                )
+            });
+        }
+    }
+}
+
+/// The query's shape without its values: whitespace collapsed, string and
+/// numeric literals and bind parameters replaced by `?`, and lists of them
+/// (`IN (?, ?, ?)`) collapsed to one.
+pub fn normalize_query(sql: &str) -> String {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                if !out.is_empty() {
+                    out.push(' ');
+                }
+            }
+            '\'' => {
+                // '' inside a literal is an escaped quote
+                while let Some(c) = chars.next() {
+                    if c == '\'' && chars.next_if_eq(&'\'').is_none() {
+                        break;
+                    }
+                }
+                push_placeholder(&mut out);
+            }
+            '$' if chars.peek().is_some_and(char::is_ascii_digit) => {
+                while chars.next_if(char::is_ascii_digit).is_some() {}
+                push_placeholder(&mut out);
+            }
+            c if c.is_ascii_digit() && !out.ends_with(|p: char| p.is_alphanumeric() || p == '_') => {
+                while chars.next_if(|c| c.is_ascii_digit() || *c == '.').is_some() {}
+                push_placeholder(&mut out);
+            }
+            c => out.push(c),
+        }
+    }
+    out.truncate(out.trim_end().len());
+    out
+}
+
+/// Appends `?`, unless it would only extend a `?, ?` list
+fn push_placeholder(out: &mut String) {
+    if out.ends_with("?, ") {
+        out.truncate(out.len() - 2);
+    } else if out.ends_with("?,") {
+        out.truncate(out.len() - 1);
+    } else {
+        out.push('?');
+    }
+}
+
+/// Stable 64-bit FNV-1a hash of a normalized query, as 16 hex digits, for
+/// grouping spans of the same query regardless of its values
+pub fn query_fingerprint(normalized: &str) -> String {
+    let hash = normalized.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,131 @@
+use application::devices::{Device, DeviceStore};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use uuid::Uuid;
+
+use crate::db::{Database, DbConnection};
+
+// ============================================================================
+// Postgres Device Store
+// ============================================================================
+
+/// Users' devices in the `devices` table
+pub struct PgDeviceStore {
+    db: Database,
+}
+
+impl PgDeviceStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn conn(&self) -> Result<DbConnection, ApplicationError> {
+        Ok(self.db.acquire().await?)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct DeviceRow {
+    id: Uuid,
+    user_id: Uuid,
+    fingerprint_hash: String,
+    name: Option<String>,
+    first_seen_at: DateTime<Utc>,
+    last_seen_at: DateTime<Utc>,
+    trusted_until: Option<DateTime<Utc>>,
+}
+
+impl From<DeviceRow> for Device {
+    fn from(row: DeviceRow) -> Self {
+        Self {
+            id: row.id,
+            user_id: row.user_id,
+            fingerprint_hash: row.fingerprint_hash,
+            name: row.name,
+            first_seen_at: row.first_seen_at,
+            last_seen_at: row.last_seen_at,
+            trusted_until: row.trusted_until,
+        }
+    }
+}
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("Device store error: {}", err)).into()
+}
+
+#[async_trait]
+impl DeviceStore for PgDeviceStore {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Device", operation = "find"))]
+    async fn find(&self, user_id: Uuid, fingerprint_hash: &str) -> Result<Option<Device>, ApplicationError> {
+        let row = sqlx::query_as::<_, DeviceRow>(
+            r#"
+            SELECT id, user_id, fingerprint_hash, name, first_seen_at, last_seen_at, trusted_until
+            FROM devices
+            WHERE user_id = $1 AND fingerprint_hash = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(fingerprint_hash)
+        .fetch_optional(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+
+        Ok(row.map(Device::from))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Device", operation = "save"))]
+    async fn save(&self, device: &Device) -> Result<(), ApplicationError> {
+        sqlx::query(
+            r#"
+            INSERT INTO devices (id, user_id, fingerprint_hash, name, first_seen_at, last_seen_at, trusted_until)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (id) DO UPDATE
+            SET name = EXCLUDED.name,
+                last_seen_at = EXCLUDED.last_seen_at,
+                trusted_until = EXCLUDED.trusted_until
+            "#,
+        )
+        .bind(device.id)
+        .bind(device.user_id)
+        .bind(&device.fingerprint_hash)
+        .bind(&device.name)
+        .bind(device.first_seen_at)
+        .bind(device.last_seen_at)
+        .bind(device.trusted_until)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Device", operation = "list"))]
+    async fn list(&self, user_id: Uuid) -> Result<Vec<Device>, ApplicationError> {
+        let rows = sqlx::query_as::<_, DeviceRow>(
+            r#"
+            SELECT id, user_id, fingerprint_hash, name, first_seen_at, last_seen_at, trusted_until
+            FROM devices
+            WHERE user_id = $1
+            ORDER BY last_seen_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+
+        Ok(rows.into_iter().map(Device::from).collect())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Device", operation = "delete"))]
+    async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<bool, ApplicationError> {
+        let result = sqlx::query("DELETE FROM devices WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(map_err)?;
+        Ok(result.rows_affected() > 0)
+    }
+}
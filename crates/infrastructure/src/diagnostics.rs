@@ -0,0 +1,126 @@
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use sqlx::migrate::Migrator;
+use sqlx::PgPool;
+
+use crate::map_sqlx_error;
+
+/// Migrations shipped with this build, compared against `_sqlx_migrations`
+/// and applied by `run_migrations`
+static MIGRATOR: Migrator = sqlx::migrate!("../../migrations");
+
+// ============================================================================
+// Migration Status
+// ============================================================================
+
+/// How the database schema compares with the migrations in this build
+#[derive(Debug, Default)]
+pub struct MigrationStatus {
+    pub applied: usize,
+    /// `version_description` of migrations not yet applied
+    pub pending: Vec<String>,
+    /// Applied versions this build does not know (a newer release ran)
+    pub unknown: Vec<i64>,
+    /// Migrations that failed half-way
+    pub failed: Vec<i64>,
+    /// Applied migrations whose file changed since
+    pub modified: Vec<i64>,
+}
+
+impl MigrationStatus {
+    pub fn is_current(&self) -> bool {
+        self.pending.is_empty() && self.unknown.is_empty() && self.failed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Apply the migrations of this build that are still pending; returns the
+/// versions applied, oldest first
+pub async fn run_migrations(pool: &PgPool) -> Result<Vec<i64>, DomainError> {
+    let before = DatabaseDiagnostics::new(pool.clone()).migration_status().await?;
+    // Index builds and backfills may outlast the pool's statement_timeout
+    let mut conn = pool
+        .acquire()
+        .await
+        .map_err(|e| DomainError::internal(format!("Failed to acquire connection: {}", e)))?;
+    sqlx::query("SET statement_timeout = 0")
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| DomainError::internal(format!("Failed to disable statement timeout: {}", e)))?;
+    let migrated = MIGRATOR.run(&mut *conn).await;
+    // Not returned to the pool with the timeout disabled
+    let _ = conn.close().await;
+    migrated.map_err(|e| DomainError::internal(format!("Migration failed: {}", e)))?;
+    Ok(MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .filter(|m| before.pending.contains(&migration_name(m)))
+        .map(|m| m.version)
+        .collect())
+}
+
+/// `version_description`, as in the migration's file name
+fn migration_name(migration: &sqlx::migrate::Migration) -> String {
+    format!("{}_{}", migration.version, migration.description.replace(' ', "_"))
+}
+
+// ============================================================================
+// Database Diagnostics
+// ============================================================================
+
+/// Read-only probes run by the startup self-check
+pub struct DatabaseDiagnostics {
+    pool: PgPool,
+}
+
+impl DatabaseDiagnostics {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Compare `_sqlx_migrations` with the migrations embedded at build time
+    pub async fn migration_status(&self) -> Result<MigrationStatus, DomainError> {
+        let tracked: bool = sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Migrations"))?;
+        let applied: Vec<(i64, bool, Vec<u8>)> = if tracked {
+            sqlx::query_as("SELECT version, success, checksum FROM _sqlx_migrations ORDER BY version")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| map_sqlx_error(e, "Migrations"))?
+        } else {
+            Vec::new()
+        };
+
+        let mut status = MigrationStatus {
+            applied: applied.len(),
+            ..Default::default()
+        };
+        for migration in MIGRATOR.iter().filter(|m| !m.migration_type.is_down_migration()) {
+            match applied.iter().find(|(version, _, _)| *version == migration.version) {
+                None => status.pending.push(migration_name(migration)),
+                Some((version, false, _)) => status.failed.push(*version),
+                Some((version, true, checksum)) if *checksum != *migration.checksum => status.modified.push(*version),
+                Some(_) => {}
+            }
+        }
+        status.unknown = applied
+            .iter()
+            .map(|(version, _, _)| *version)
+            .filter(|version| !MIGRATOR.iter().any(|m| m.version == *version))
+            .collect();
+        Ok(status)
+    }
+
+    /// Database clock minus local clock, measured against the midpoint of
+    /// the round trip
+    pub async fn clock_skew(&self) -> Result<chrono::Duration, DomainError> {
+        let before = Utc::now();
+        let database: DateTime<Utc> = sqlx::query_scalar("SELECT clock_timestamp()")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Clock"))?;
+        let after = Utc::now();
+        Ok(database - (before + (after - before) / 2))
+    }
+}
@@ -0,0 +1,235 @@
+use application::email::{EmailSender, EmailTemplate};
+use application::ApplicationError;
+use async_trait::async_trait;
+use domain::DomainError;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use minijinja::{Environment, UndefinedBehavior};
+use shared::EmailSettings;
+
+// ============================================================================
+// Email Configuration
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailTransport {
+    /// Log emails instead of sending them (development)
+    Console,
+    Smtp,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    /// Implicit TLS (usually port 465)
+    Tls,
+    /// STARTTLS upgrade (usually port 587)
+    StartTls,
+    /// Plaintext, for local catchers such as MailHog
+    None,
+}
+
+#[derive(Debug, Clone)]
+pub struct EmailConfig {
+    pub transport: EmailTransport,
+    pub from: String,
+    /// Product name available to every template as `app_name`
+    pub app_name: String,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub smtp_security: SmtpSecurity,
+}
+
+impl EmailConfig {
+    /// From the `[email]` settings, already validated
+    pub fn from_settings(settings: &EmailSettings) -> Self {
+        let transport = match settings.transport.as_str() {
+            "smtp" => EmailTransport::Smtp,
+            _ => EmailTransport::Console,
+        };
+        let smtp_security = match settings.smtp_security.as_str() {
+            "tls" => SmtpSecurity::Tls,
+            "none" => SmtpSecurity::None,
+            _ => SmtpSecurity::StartTls,
+        };
+        let default_port = match smtp_security {
+            SmtpSecurity::Tls => 465,
+            SmtpSecurity::StartTls => 587,
+            SmtpSecurity::None => 1025,
+        };
+
+        Self {
+            transport,
+            from: settings.from.clone(),
+            app_name: settings.app_name.clone(),
+            smtp_host: settings.smtp_host.clone(),
+            smtp_port: settings.smtp_port.unwrap_or(default_port),
+            smtp_username: settings.smtp_username.clone(),
+            smtp_password: settings.smtp_password.clone(),
+            smtp_security,
+        }
+    }
+}
+
+// ============================================================================
+// Template Rendering
+// ============================================================================
+
+/// Email ready to hand to a transport
+#[derive(Debug, Clone)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+}
+
+/// Renders the bundled templates with minijinja. HTML bodies are
+/// auto-escaped; a variable missing from `vars` is an error, not a blank.
+pub struct EmailRenderer {
+    env: Environment<'static>,
+    app_name: String,
+}
+
+macro_rules! email_templates {
+    ($env:expr, $($name:literal),+) => {
+        $(
+            $env.add_template(concat!($name, ".subject.txt"), include_str!(concat!("../templates/email/", $name, ".subject.txt")))
+                .expect("valid email template");
+            $env.add_template(concat!($name, ".txt"), include_str!(concat!("../templates/email/", $name, ".txt")))
+                .expect("valid email template");
+            $env.add_template(concat!($name, ".html"), include_str!(concat!("../templates/email/", $name, ".html")))
+                .expect("valid email template");
+        )+
+    };
+}
+
+impl EmailRenderer {
+    pub fn new(app_name: impl Into<String>) -> Self {
+        let mut env = Environment::new();
+        env.set_undefined_behavior(UndefinedBehavior::Strict);
+        email_templates!(env, "welcome", "password_reset", "support_contact", "data_export", "organization_invitation");
+
+        Self {
+            env,
+            app_name: app_name.into(),
+        }
+    }
+
+    pub fn render(&self, template: EmailTemplate, vars: &serde_json::Value) -> Result<RenderedEmail, DomainError> {
+        let mut context = match vars {
+            serde_json::Value::Object(map) => map.clone(),
+            _ => serde_json::Map::new(),
+        };
+        context
+            .entry("app_name")
+            .or_insert_with(|| self.app_name.clone().into());
+
+        let render = |suffix: &str| {
+            let name = format!("{}.{}", template.name(), suffix);
+            self.env
+                .get_template(&name)
+                .and_then(|t| t.render(&context))
+                .map_err(|e| DomainError::internal(format!("Failed to render {}: {}", name, e)))
+        };
+
+        Ok(RenderedEmail {
+            subject: render("subject.txt")?.trim().to_string(),
+            text: render("txt")?,
+            html: render("html")?,
+        })
+    }
+}
+
+// ============================================================================
+// Console Adapter
+// ============================================================================
+
+/// Logs rendered emails instead of sending them
+pub struct ConsoleEmailSender {
+    renderer: EmailRenderer,
+}
+
+impl ConsoleEmailSender {
+    pub fn new(renderer: EmailRenderer) -> Self {
+        Self { renderer }
+    }
+}
+
+#[async_trait]
+impl EmailSender for ConsoleEmailSender {
+    async fn send(&self, to: &str, template: EmailTemplate, vars: serde_json::Value) -> Result<(), ApplicationError> {
+        let email = self.renderer.render(template, &vars)?;
+        tracing::info!(
+            to,
+            template = template.name(),
+            subject = %email.subject,
+            "📧 Email (console transport)\n{}",
+            email.text
+        );
+        Ok(())
+    }
+}
+
+// ============================================================================
+// SMTP Adapter
+// ============================================================================
+
+/// Sends multipart (text + HTML) emails over SMTP with lettre
+pub struct SmtpEmailSender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    renderer: EmailRenderer,
+}
+
+impl SmtpEmailSender {
+    pub fn new(config: &EmailConfig, renderer: EmailRenderer) -> Result<Self, DomainError> {
+        let smtp_error = |e: lettre::transport::smtp::Error| DomainError::internal(format!("Invalid SMTP configuration: {}", e));
+
+        let mut builder = match config.smtp_security {
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host).map_err(smtp_error)?,
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host).map_err(smtp_error)?,
+            SmtpSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host),
+        }
+        .port(config.smtp_port);
+
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        let from = config
+            .from
+            .parse()
+            .map_err(|e| DomainError::internal(format!("Invalid EMAIL_FROM: {}", e)))?;
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+            renderer,
+        })
+    }
+}
+
+#[async_trait]
+impl EmailSender for SmtpEmailSender {
+    async fn send(&self, to: &str, template: EmailTemplate, vars: serde_json::Value) -> Result<(), ApplicationError> {
+        let email = self.renderer.render(template, &vars)?;
+        let to: Mailbox = to
+            .parse()
+            .map_err(|e| DomainError::validation(format!("Invalid recipient address: {}", e)))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(email.subject)
+            .multipart(MultiPart::alternative_plain_html(email.text, email.html))
+            .map_err(|e| DomainError::internal(format!("Failed to build email: {}", e)))?;
+
+        self.transport
+            .send(message)
+            .await
+            .map_err(|e| DomainError::internal(format!("SMTP delivery failed: {}", e)))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use application::email_suppression::{BounceNotice, EmailSuppression, EmailSuppressionList, SuppressionReason};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+
+use crate::db::Database;
+
+// ============================================================================
+// Postgres Suppression List
+// ============================================================================
+
+/// Suppressed addresses in the `email_suppressions` table
+pub struct PgEmailSuppressionList {
+    db: Database,
+}
+
+impl PgEmailSuppressionList {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SuppressionRow {
+    email: String,
+    reason: String,
+    provider: String,
+    detail: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("Email suppression list error: {}", err)).into()
+}
+
+#[async_trait]
+impl EmailSuppressionList for PgEmailSuppressionList {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "EmailSuppression", operation = "suppress"))]
+    async fn suppress(&self, notice: &BounceNotice, provider: &str) -> Result<(), ApplicationError> {
+        sqlx::query(
+            r#"
+            INSERT INTO email_suppressions (email, reason, provider, detail)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (email) DO UPDATE
+            SET reason = EXCLUDED.reason, provider = EXCLUDED.provider, detail = EXCLUDED.detail
+            "#,
+        )
+        .bind(&notice.email)
+        .bind(notice.reason.as_str())
+        .bind(provider)
+        .bind(&notice.detail)
+        .execute(&mut self.db.acquire().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "EmailSuppression", operation = "find_many"))]
+    async fn find_many(&self, emails: &[String]) -> Result<Vec<EmailSuppression>, ApplicationError> {
+        let rows = sqlx::query_as::<_, SuppressionRow>(
+            "SELECT email, reason, provider, detail, created_at FROM email_suppressions WHERE email = ANY($1)",
+        )
+        .bind(emails)
+        .fetch_all(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(map_err)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| EmailSuppression {
+                email: row.email,
+                reason: SuppressionReason::parse(&row.reason).unwrap_or(SuppressionReason::Bounce),
+                provider: row.provider,
+                detail: row.detail,
+                created_at: row.created_at,
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "EmailSuppression", operation = "remove"))]
+    async fn remove(&self, email: &str) -> Result<bool, ApplicationError> {
+        let result = sqlx::query("DELETE FROM email_suppressions WHERE email = $1")
+            .bind(email)
+            .execute(&mut self.db.acquire().await?)
+            .await
+            .map_err(map_err)?;
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use application::EventBus;
+use chrono::{TimeZone, Utc};
+use domain::{DomainError, DomainEvent, EventEnvelope};
+use prost::Message;
+use proto::events::v1 as pb;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+// ============================================================================
+// In-Memory Event Bus
+// ============================================================================
+
+/// Process-local event bus backed by a tokio broadcast channel.
+/// Keeps the last `history_size` events so subscribers can resume after a reconnect.
+pub struct InMemoryEventBus {
+    sender: broadcast::Sender<EventEnvelope>,
+    state: Mutex<BusState>,
+    history_size: usize,
+}
+
+struct BusState {
+    next_id: u64,
+    history: VecDeque<EventEnvelope>,
+}
+
+impl InMemoryEventBus {
+    pub fn new(history_size: usize) -> Self {
+        let (sender, _) = broadcast::channel(history_size.max(1));
+        Self {
+            sender,
+            state: Mutex::new(BusState {
+                next_id: 1,
+                history: VecDeque::with_capacity(history_size),
+            }),
+            history_size,
+        }
+    }
+}
+
+impl Default for InMemoryEventBus {
+    fn default() -> Self {
+        Self::new(1024)
+    }
+}
+
+impl EventBus for InMemoryEventBus {
+    fn publish(&self, event: DomainEvent) -> EventEnvelope {
+        // Hold the lock while sending so ids reach subscribers in order
+        let mut state = self.state.lock().unwrap();
+        let envelope = EventEnvelope {
+            id: state.next_id,
+            occurred_at: chrono::Utc::now(),
+            event,
+        };
+        state.next_id += 1;
+
+        if self.history_size > 0 {
+            if state.history.len() == self.history_size {
+                state.history.pop_front();
+            }
+            state.history.push_back(envelope.clone());
+        }
+
+        tracing::debug!(event_id = envelope.id, event = envelope.event.name(), "domain event published");
+        // No subscribers is not an error
+        let _ = self.sender.send(envelope.clone());
+        envelope
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<EventEnvelope> {
+        self.sender.subscribe()
+    }
+
+    fn events_since(&self, last_id: u64) -> Vec<EventEnvelope> {
+        self.state
+            .lock()
+            .unwrap()
+            .history
+            .iter()
+            .filter(|e| e.id > last_id)
+            .cloned()
+            .collect()
+    }
+}
+
+// ============================================================================
+// Protobuf Encoding
+// ============================================================================
+
+/// Encode an envelope as `rust_base.events.v1.EventEnvelope`, the versioned
+/// contract in the `proto` crate, for consumers outside this process.
+pub fn encode_event(envelope: &EventEnvelope) -> Vec<u8> {
+    let event = match envelope.event.clone() {
+        DomainEvent::UserRegistered { user_id, username, email } => pb::event_envelope::Event::UserRegistered(pb::UserRegistered {
+            user_id: user_id.to_string(),
+            username,
+            email,
+        }),
+        DomainEvent::UserLoggedIn { user_id } => pb::event_envelope::Event::UserLoggedIn(pb::UserLoggedIn {
+            user_id: user_id.to_string(),
+        }),
+        DomainEvent::FileQuarantined { user_id, purpose, signature } => pb::event_envelope::Event::FileQuarantined(pb::FileQuarantined {
+            user_id: user_id.to_string(),
+            purpose,
+            signature,
+        }),
+        DomainEvent::ServiceStarted { service, region, version } => {
+            pb::event_envelope::Event::ServiceStarted(pb::ServiceStarted { service, region, version })
+        }
+        DomainEvent::MigrationsApplied { versions } => pb::event_envelope::Event::MigrationsApplied(pb::MigrationsApplied { versions }),
+        DomainEvent::ShuttingDown { service, region } => pb::event_envelope::Event::ShuttingDown(pb::ShuttingDown { service, region }),
+        DomainEvent::NotificationCreated { user_id, notification_id, kind, title } => {
+            pb::event_envelope::Event::NotificationCreated(pb::NotificationCreated {
+                user_id: user_id.to_string(),
+                notification_id: notification_id.to_string(),
+                kind,
+                title,
+            })
+        }
+    };
+    pb::EventEnvelope {
+        id: envelope.id,
+        occurred_at: Some(prost_types::Timestamp {
+            seconds: envelope.occurred_at.timestamp(),
+            nanos: envelope.occurred_at.timestamp_subsec_nanos() as i32,
+        }),
+        name: envelope.event.name().to_string(),
+        event: Some(event),
+    }
+    .encode_to_vec()
+}
+
+/// Decode what `encode_event` produced. Fails on malformed bytes and on
+/// events this build does not know, e.g. ones added by a newer producer.
+pub fn decode_event(bytes: &[u8]) -> Result<EventEnvelope, DomainError> {
+    let envelope = pb::EventEnvelope::decode(bytes).map_err(|e| DomainError::validation(format!("Malformed event: {}", e)))?;
+    let user_id = |id: &str| Uuid::parse_str(id).map_err(|_| DomainError::validation(format!("Malformed user id in event: {}", id)));
+    let event = match envelope.event {
+        Some(pb::event_envelope::Event::UserRegistered(e)) => DomainEvent::UserRegistered {
+            user_id: user_id(&e.user_id)?,
+            username: e.username,
+            email: e.email,
+        },
+        Some(pb::event_envelope::Event::UserLoggedIn(e)) => DomainEvent::UserLoggedIn { user_id: user_id(&e.user_id)? },
+        Some(pb::event_envelope::Event::FileQuarantined(e)) => DomainEvent::FileQuarantined {
+            user_id: user_id(&e.user_id)?,
+            purpose: e.purpose,
+            signature: e.signature,
+        },
+        Some(pb::event_envelope::Event::ServiceStarted(e)) => DomainEvent::ServiceStarted {
+            service: e.service,
+            region: e.region,
+            version: e.version,
+        },
+        Some(pb::event_envelope::Event::MigrationsApplied(e)) => DomainEvent::MigrationsApplied { versions: e.versions },
+        Some(pb::event_envelope::Event::ShuttingDown(e)) => DomainEvent::ShuttingDown {
+            service: e.service,
+            region: e.region,
+        },
+        Some(pb::event_envelope::Event::NotificationCreated(e)) => DomainEvent::NotificationCreated {
+            user_id: user_id(&e.user_id)?,
+            notification_id: Uuid::parse_str(&e.notification_id)
+                .map_err(|_| DomainError::validation(format!("Malformed notification id in event: {}", e.notification_id)))?,
+            kind: e.kind,
+            title: e.title,
+        },
+        None => return Err(DomainError::validation(format!("Unknown event: {}", envelope.name))),
+    };
+    let occurred_at = envelope
+        .occurred_at
+        .and_then(|t| Utc.timestamp_opt(t.seconds, t.nanos.max(0) as u32).single())
+        .ok_or_else(|| DomainError::validation("Event without a valid occurred_at"))?;
+    Ok(EventEnvelope {
+        id: envelope.id,
+        occurred_at,
+        event,
+    })
+}
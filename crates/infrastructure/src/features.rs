@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use application::feature_flags::FeatureFlagStore;
+use application::{ApplicationError, ExperimentAssignmentStore, FeatureFlagService};
+use async_trait::async_trait;
+use domain::{DomainError, Experiment, ExperimentAssignment, FeatureFlag, FlagContext, Rollout};
+use serde::de::DeserializeOwned;
+use shared::{ExperimentSettings, FeatureFlagSettings};
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::db::{Database, DbConnection};
+
+// ============================================================================
+// Static Feature Flags
+// ============================================================================
+
+/// Feature flags loaded once from configuration (`features.flags`, see
+/// `feature_flags_from_settings`)
+pub struct StaticFeatureFlags {
+    flags: HashMap<String, FeatureFlag>,
+}
+
+impl StaticFeatureFlags {
+    pub fn new(flags: Vec<FeatureFlag>) -> Self {
+        Self {
+            flags: flags.into_iter().map(|f| (f.key.clone(), f)).collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl FeatureFlagService for StaticFeatureFlags {
+    async fn is_enabled(&self, key: &str, ctx: &FlagContext) -> Result<bool, ApplicationError> {
+        Ok(self.flags.get(key).is_some_and(|f| f.is_enabled_for(ctx)))
+    }
+
+    async fn enabled_flags(&self, ctx: &FlagContext) -> Result<Vec<String>, ApplicationError> {
+        let mut keys: Vec<String> = self
+            .flags
+            .values()
+            .filter(|f| f.is_enabled_for(ctx))
+            .map(|f| f.key.clone())
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+/// Flag definitions from `features.flags`, e.g.
+/// `[{"key":"new_dashboard","enabled":true,"rollout":{"percentage":10,"cohorts":[{"role":"beta"}]}}]`
+pub fn feature_flags_from_settings(settings: &FeatureFlagSettings) -> Result<Vec<FeatureFlag>, DomainError> {
+    parse_list("features.flags", &settings.flags)
+}
+
+// ============================================================================
+// Postgres Feature Flag Store
+// ============================================================================
+
+/// Admin-managed flags in the `feature_flags` table, with the rollout rules
+/// in a JSONB column
+pub struct PgFeatureFlagStore {
+    db: Database,
+}
+
+impl PgFeatureFlagStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn conn(&self) -> Result<DbConnection, ApplicationError> {
+        Ok(self.db.acquire().await?)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct FeatureFlagRow {
+    key: String,
+    enabled: bool,
+    rollout: Json<Rollout>,
+    description: Option<String>,
+}
+
+impl From<FeatureFlagRow> for FeatureFlag {
+    fn from(row: FeatureFlagRow) -> Self {
+        Self {
+            key: row.key,
+            enabled: row.enabled,
+            rollout: row.rollout.0,
+            description: row.description,
+        }
+    }
+}
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("Feature flag store error: {}", err)).into()
+}
+
+#[async_trait]
+impl FeatureFlagStore for PgFeatureFlagStore {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "FeatureFlag", operation = "list"))]
+    async fn list(&self) -> Result<Vec<FeatureFlag>, ApplicationError> {
+        let rows = sqlx::query_as::<_, FeatureFlagRow>(
+            "SELECT key, enabled, rollout, description FROM feature_flags ORDER BY key",
+        )
+        .fetch_all(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+
+        Ok(rows.into_iter().map(FeatureFlag::from).collect())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "FeatureFlag", operation = "save"))]
+    async fn save(&self, flag: &FeatureFlag) -> Result<(), ApplicationError> {
+        sqlx::query(
+            r#"
+            INSERT INTO feature_flags (key, enabled, rollout, description)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (key) DO UPDATE
+            SET enabled = EXCLUDED.enabled, rollout = EXCLUDED.rollout,
+                description = EXCLUDED.description, updated_at = now()
+            "#,
+        )
+        .bind(&flag.key)
+        .bind(flag.enabled)
+        .bind(Json(&flag.rollout))
+        .bind(&flag.description)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "FeatureFlag", operation = "delete"))]
+    async fn delete(&self, key: &str) -> Result<bool, ApplicationError> {
+        let result = sqlx::query("DELETE FROM feature_flags WHERE key = $1")
+            .bind(key)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(map_err)?;
+        Ok(result.rows_affected() == 1)
+    }
+}
+
+// ============================================================================
+// Experiments
+// ============================================================================
+
+/// Experiment definitions from `experiments.definitions`, e.g.
+/// `[{"key":"checkout_copy","variants":[{"name":"control","weight":50},{"name":"short","weight":50}]}]`
+pub fn experiments_from_settings(settings: &ExperimentSettings) -> Result<Vec<Experiment>, DomainError> {
+    parse_list("experiments.definitions", &settings.definitions)
+}
+
+fn parse_list<T: DeserializeOwned>(setting: &str, values: &[serde_json::Value]) -> Result<Vec<T>, DomainError> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            serde_json::from_value(value.clone())
+                .map_err(|e| DomainError::validation(format!("{}[{}]: {}", setting, i, e)))
+        })
+        .collect()
+}
+
+// ============================================================================
+// Experiment Assignments
+// ============================================================================
+
+/// Assignments in the `experiment_assignments` table, one row per user and
+/// experiment
+pub struct PgExperimentAssignmentStore {
+    db: Database,
+}
+
+impl PgExperimentAssignmentStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ExperimentAssignmentStore for PgExperimentAssignmentStore {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "ExperimentAssignment", operation = "record"))]
+    async fn record(&self, user_id: Uuid, assignment: &ExperimentAssignment) -> Result<bool, ApplicationError> {
+        // Writes only when the row is new or the variant changed
+        let result = sqlx::query(
+            r#"
+            INSERT INTO experiment_assignments (user_id, experiment, variant)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, experiment) DO UPDATE
+            SET variant = EXCLUDED.variant, assigned_at = now()
+            WHERE experiment_assignments.variant <> EXCLUDED.variant
+            "#,
+        )
+        .bind(user_id)
+        .bind(&assignment.experiment)
+        .bind(&assignment.variant)
+        .execute(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| DomainError::internal(format!("Experiment assignment store error: {}", e)))?;
+        Ok(result.rows_affected() == 1)
+    }
+}
+
+/// Process-local assignments for tests and single-instance development
+#[derive(Default)]
+pub struct InMemoryExperimentAssignmentStore {
+    variants: Mutex<HashMap<(Uuid, String), String>>,
+}
+
+impl InMemoryExperimentAssignmentStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ExperimentAssignmentStore for InMemoryExperimentAssignmentStore {
+    async fn record(&self, user_id: Uuid, assignment: &ExperimentAssignment) -> Result<bool, ApplicationError> {
+        let previous = self
+            .variants
+            .lock()
+            .unwrap()
+            .insert((user_id, assignment.experiment.clone()), assignment.variant.clone());
+        Ok(previous.as_deref() != Some(assignment.variant.as_str()))
+    }
+}
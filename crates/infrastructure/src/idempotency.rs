@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+use application::idempotency::{IdempotencyClaim, IdempotencyStore, StoredResponse};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+
+use crate::db::{Database, DbConnection};
+
+// ============================================================================
+// Postgres Idempotency Store
+// ============================================================================
+
+/// Idempotency keys in the `idempotency_keys` table. Claiming is a single
+/// upsert, so concurrent retries of one key race safely across instances.
+pub struct PgIdempotencyStore {
+    db: Database,
+}
+
+impl PgIdempotencyStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn conn(&self) -> Result<DbConnection, ApplicationError> {
+        Ok(self.db.acquire().await?)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct KeyRow {
+    fingerprint: String,
+    status: String,
+    response_status: Option<i32>,
+    response_headers: Option<serde_json::Value>,
+    response_body: Option<Vec<u8>>,
+}
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("Idempotency store error: {}", err)).into()
+}
+
+#[async_trait]
+impl IdempotencyStore for PgIdempotencyStore {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "IdempotencyKey", operation = "claim"))]
+    async fn claim(
+        &self,
+        scope: &str,
+        key: &str,
+        fingerprint: &str,
+        ttl: Duration,
+        lock_timeout: Duration,
+    ) -> Result<IdempotencyClaim, ApplicationError> {
+        let mut conn = self.conn().await?;
+
+        // Takes over expired keys, and abandoned ones for the same request
+        let acquired = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (scope, key, fingerprint, status, locked_at, expires_at)
+            VALUES ($1, $2, $3, 'processing', now(), now() + make_interval(secs => $4))
+            ON CONFLICT (scope, key) DO UPDATE
+            SET fingerprint = EXCLUDED.fingerprint,
+                status = 'processing',
+                response_status = NULL,
+                response_headers = NULL,
+                response_body = NULL,
+                locked_at = now(),
+                expires_at = EXCLUDED.expires_at
+            WHERE idempotency_keys.expires_at <= now()
+               OR (idempotency_keys.status = 'processing'
+                   AND idempotency_keys.fingerprint = EXCLUDED.fingerprint
+                   AND idempotency_keys.locked_at <= now() - make_interval(secs => $5))
+            "#,
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(fingerprint)
+        .bind(ttl.as_secs_f64())
+        .bind(lock_timeout.as_secs_f64())
+        .execute(&mut conn)
+        .await
+        .map_err(map_err)?
+        .rows_affected()
+            > 0;
+        if acquired {
+            return Ok(IdempotencyClaim::Acquired);
+        }
+
+        let row = sqlx::query_as::<_, KeyRow>(
+            r#"
+            SELECT fingerprint, status, response_status, response_headers, response_body
+            FROM idempotency_keys
+            WHERE scope = $1 AND key = $2
+            "#,
+        )
+        .bind(scope)
+        .bind(key)
+        .fetch_optional(&mut conn)
+        .await
+        .map_err(map_err)?;
+
+        // A row pruned in between reads as in progress; the client retries
+        let Some(row) = row else {
+            return Ok(IdempotencyClaim::InProgress);
+        };
+        if row.fingerprint != fingerprint {
+            return Ok(IdempotencyClaim::Mismatch);
+        }
+        match (row.status.as_str(), row.response_status) {
+            ("completed", Some(status)) => Ok(IdempotencyClaim::Completed(StoredResponse {
+                status: u16::try_from(status).unwrap_or(500),
+                headers: row
+                    .response_headers
+                    .and_then(|h| serde_json::from_value(h).ok())
+                    .unwrap_or_default(),
+                body: row.response_body.unwrap_or_default(),
+            })),
+            _ => Ok(IdempotencyClaim::InProgress),
+        }
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "IdempotencyKey", operation = "complete"))]
+    async fn complete(&self, scope: &str, key: &str, response: &StoredResponse) -> Result<(), ApplicationError> {
+        let headers = serde_json::to_value(&response.headers)
+            .map_err(|e| DomainError::internal(format!("Invalid response headers: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            UPDATE idempotency_keys
+            SET status = 'completed', response_status = $3, response_headers = $4, response_body = $5
+            WHERE scope = $1 AND key = $2
+            "#,
+        )
+        .bind(scope)
+        .bind(key)
+        .bind(i32::from(response.status))
+        .bind(headers)
+        .bind(&response.body)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "IdempotencyKey", operation = "release"))]
+    async fn release(&self, scope: &str, key: &str) -> Result<(), ApplicationError> {
+        sqlx::query("DELETE FROM idempotency_keys WHERE scope = $1 AND key = $2 AND status = 'processing'")
+            .bind(scope)
+            .bind(key)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "IdempotencyKey", operation = "prune"))]
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64, ApplicationError> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at < $1")
+            .bind(before)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(map_err)?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,82 @@
+use std::io::Cursor;
+
+use application::storage::{ImageProcessor, ProcessedImage};
+use application::ApplicationError;
+use domain::DomainError;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits};
+
+// ============================================================================
+// Image Processor
+// ============================================================================
+
+/// Decodes and resizes in-process with the `image` crate. Re-encoding drops
+/// all metadata; the EXIF orientation is applied to the pixels first.
+pub struct NativeImageProcessor {
+    /// Largest accepted width or height, against decompression bombs
+    max_dimension: u32,
+}
+
+impl NativeImageProcessor {
+    pub fn new() -> Self {
+        Self { max_dimension: 8192 }
+    }
+
+    pub fn with_max_dimension(mut self, max_dimension: u32) -> Self {
+        self.max_dimension = max_dimension;
+        self
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<(DynamicImage, ImageFormat), ApplicationError> {
+        let invalid = |e: image::ImageError| DomainError::validation(format!("Not a valid image: {}", e));
+        let mut reader = ImageReader::new(Cursor::new(bytes))
+            .with_guessed_format()
+            .map_err(|e| DomainError::validation(format!("Not a valid image: {}", e)))?;
+        let format = reader
+            .format()
+            .ok_or_else(|| DomainError::validation("Unrecognized image format"))?;
+        let mut limits = Limits::default();
+        limits.max_image_width = Some(self.max_dimension);
+        limits.max_image_height = Some(self.max_dimension);
+        reader.limits(limits);
+
+        let mut decoder = reader.into_decoder().map_err(invalid)?;
+        let orientation = decoder.orientation().map_err(invalid)?;
+        let mut image = DynamicImage::from_decoder(decoder).map_err(invalid)?;
+        image.apply_orientation(orientation);
+        Ok((image, format))
+    }
+}
+
+impl Default for NativeImageProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat) -> Result<Vec<u8>, ApplicationError> {
+    // JPEG has no alpha channel
+    let image = match format {
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8()),
+        _ => image.clone(),
+    };
+    let mut bytes = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut bytes), format)
+        .map_err(|e| DomainError::internal(format!("Failed to encode image: {}", e)))?;
+    Ok(bytes)
+}
+
+impl ImageProcessor for NativeImageProcessor {
+    fn process(&self, bytes: &[u8], sizes: &[u32]) -> Result<ProcessedImage, ApplicationError> {
+        let (image, format) = self.decode(bytes)?;
+        let variants = sizes
+            .iter()
+            .map(|&size| encode(&image.resize_to_fill(size, size, FilterType::Lanczos3), format))
+            .collect::<Result<_, _>>()?;
+        Ok(ProcessedImage {
+            original: encode(&image, format)?,
+            variants,
+        })
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+
+use application::jobs::{JobQueue, JobRecord, JobStatus};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{DomainError, Page, PaginationParams};
+use uuid::Uuid;
+
+use crate::db::{Database, DbConnection};
+
+// ============================================================================
+// Postgres Job Queue
+// ============================================================================
+
+/// Job queue on the `jobs` table. Workers claim rows with
+/// `FOR UPDATE SKIP LOCKED`, so any number of instances can poll it.
+/// Enqueueing inside a unit of work commits the job with the surrounding writes.
+pub struct PgJobQueue {
+    db: Database,
+}
+
+impl PgJobQueue {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn conn(&self) -> Result<DbConnection, ApplicationError> {
+        Ok(self.db.acquire().await?)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct JobRow {
+    id: Uuid,
+    kind: String,
+    payload: serde_json::Value,
+    status: String,
+    attempts: i32,
+    last_error: Option<String>,
+    run_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<JobRow> for JobRecord {
+    fn from(row: JobRow) -> Self {
+        Self {
+            id: row.id,
+            kind: row.kind,
+            payload: row.payload,
+            status: JobStatus::parse(&row.status).unwrap_or(JobStatus::Failed),
+            attempts: u32::try_from(row.attempts).unwrap_or_default(),
+            last_error: row.last_error,
+            run_at: row.run_at,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+const JOB_COLUMNS: &str = "id, kind, payload, status, attempts, last_error, run_at, created_at, updated_at";
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("Job queue error: {}", err)).into()
+}
+
+#[async_trait]
+impl JobQueue for PgJobQueue {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Job", operation = "enqueue"))]
+    async fn enqueue(&self, kind: &str, payload: serde_json::Value, run_at: DateTime<Utc>) -> Result<Uuid, ApplicationError> {
+        let id = Uuid::new_v4();
+        sqlx::query("INSERT INTO jobs (id, kind, payload, run_at) VALUES ($1, $2, $3, $4)")
+            .bind(id)
+            .bind(kind)
+            .bind(payload)
+            .bind(run_at)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(map_err)?;
+        Ok(id)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Job", operation = "ensure_scheduled"))]
+    async fn ensure_scheduled(&self, kind: &str, run_at: DateTime<Utc>) -> Result<(), ApplicationError> {
+        sqlx::query(
+            r#"
+            INSERT INTO jobs (id, kind, run_at)
+            SELECT $1, $2, $3
+            WHERE NOT EXISTS (
+                SELECT 1 FROM jobs WHERE kind = $2 AND status IN ('pending', 'running')
+            )
+            "#,
+        )
+        .bind(Uuid::new_v4())
+        .bind(kind)
+        .bind(run_at)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Job", operation = "claim"))]
+    async fn claim(&self, kinds: &[String], stale_before: DateTime<Utc>) -> Result<Option<JobRecord>, ApplicationError> {
+        let row = sqlx::query_as::<_, JobRow>(&format!(
+            r#"
+            UPDATE jobs
+            SET status = 'running', attempts = attempts + 1, locked_at = now(), updated_at = now()
+            WHERE id = (
+                SELECT id FROM jobs
+                WHERE kind = ANY($1)
+                  AND ((status = 'pending' AND run_at <= now())
+                       OR (status = 'running' AND locked_at < $2))
+                ORDER BY run_at
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {JOB_COLUMNS}
+            "#
+        ))
+        .bind(kinds)
+        .bind(stale_before)
+        .fetch_optional(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+
+        Ok(row.map(Into::into))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Job", operation = "complete"))]
+    async fn complete(&self, id: Uuid) -> Result<(), ApplicationError> {
+        sqlx::query(
+            "UPDATE jobs SET status = 'succeeded', locked_at = NULL, updated_at = now() WHERE id = $1",
+        )
+        .bind(id)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Job", operation = "fail"))]
+    async fn fail(&self, id: Uuid, error: &str, retry_at: Option<DateTime<Utc>>) -> Result<(), ApplicationError> {
+        sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = CASE WHEN $3::timestamptz IS NULL THEN 'failed' ELSE 'pending' END,
+                run_at = COALESCE($3, run_at),
+                last_error = $2,
+                locked_at = NULL,
+                updated_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .bind(retry_at)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Job", operation = "list"))]
+    async fn list(&self, status: Option<JobStatus>, params: &PaginationParams) -> Result<Page<JobRecord>, ApplicationError> {
+        params.validate()?;
+        let status = status.map(|s| s.as_str());
+        let mut conn = self.db.acquire_read().await?;
+
+        let rows = sqlx::query_as::<_, JobRow>(&format!(
+            r#"
+            SELECT {JOB_COLUMNS}
+            FROM jobs
+            WHERE $1::text IS NULL OR status = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#
+        ))
+        .bind(status)
+        .bind(params.limit() as i64)
+        .bind(params.offset() as i64)
+        .fetch_all(&mut conn)
+        .await
+        .map_err(map_err)?;
+
+        let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM jobs WHERE $1::text IS NULL OR status = $1")
+            .bind(status)
+            .fetch_one(&mut conn)
+            .await
+            .map_err(map_err)?;
+
+        let jobs = rows.into_iter().map(Into::into).collect();
+        Ok(Page::new(jobs, total.0 as u64, params))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Job", operation = "counts"))]
+    async fn counts(&self) -> Result<HashMap<JobStatus, u64>, ApplicationError> {
+        let rows: Vec<(String, i64)> = sqlx::query_as("SELECT status, COUNT(*) FROM jobs GROUP BY status")
+            .fetch_all(&mut self.db.acquire_read().await?)
+            .await
+            .map_err(map_err)?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(status, count)| Some((JobStatus::parse(&status)?, count as u64)))
+            .collect())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Job", operation = "prune"))]
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64, ApplicationError> {
+        let result = sqlx::query(
+            "DELETE FROM jobs WHERE status IN ('succeeded', 'failed') AND updated_at < $1",
+        )
+        .bind(before)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,151 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use application::messaging::{MessageConsumer, MessageEnvelope, MessageHandler, MessagePublisher};
+use application::ApplicationError;
+use async_trait::async_trait;
+use domain::DomainError;
+use rdkafka::admin::{AdminClient, AdminOptions, NewTopic, TopicReplication};
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::error::KafkaError;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::types::RDKafkaErrorCode;
+use rdkafka::{ClientConfig, Message};
+use shared::{BrokerSettings, SubscriptionSettings};
+
+/// How long a publish may wait in the producer queue
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Topics are Kafka topics, and each subscription is a consumer group
+/// `<group>.<name>`, so instances of a service split its partitions and each
+/// message is handled once per subscription. Offsets are committed after the
+/// handler succeeded; messages with the same key land in the same partition
+/// and are handled in order.
+pub struct KafkaBroker {
+    servers: String,
+    group: String,
+    subscriptions: Vec<SubscriptionSettings>,
+    producer: FutureProducer,
+}
+
+impl KafkaBroker {
+    /// Create the producer and the subscriptions' literal topics
+    pub async fn connect(settings: &BrokerSettings) -> Result<Self, DomainError> {
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", &settings.url)
+            .set("enable.idempotence", "true")
+            .create()
+            .map_err(kafka_error)?;
+
+        // Wildcard subscriptions only see topics once they exist, which
+        // brokers with `auto.create.topics.enable` take care of
+        let literal: Vec<NewTopic> = settings
+            .subscriptions
+            .iter()
+            .flat_map(|s| &s.topics)
+            .filter(|t| !t.split('.').any(|word| word == "*" || word == "#"))
+            .map(|t| NewTopic::new(t, settings.partitions, TopicReplication::Fixed(settings.replication_factor)))
+            .collect();
+        if !literal.is_empty() {
+            let admin: AdminClient<_> = ClientConfig::new()
+                .set("bootstrap.servers", &settings.url)
+                .create()
+                .map_err(kafka_error)?;
+            for result in admin.create_topics(&literal, &AdminOptions::new()).await.map_err(kafka_error)? {
+                match result {
+                    Ok(_) | Err((_, RDKafkaErrorCode::TopicAlreadyExists)) => {}
+                    Err((topic, code)) => {
+                        return Err(DomainError::unavailable(format!("Failed to create Kafka topic {}: {}", topic, code)))
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            servers: settings.url.clone(),
+            group: settings.group.clone(),
+            subscriptions: settings.subscriptions.clone(),
+            producer,
+        })
+    }
+}
+
+fn kafka_error(err: KafkaError) -> DomainError {
+    DomainError::unavailable(format!("Kafka error: {}", err))
+}
+
+/// A topic pattern as the regex Kafka subscribes with (`^` marks one), e.g.
+/// `events.*.created` becomes `^events\.[^.]+\.created$`
+fn subscription_topic(pattern: &str) -> String {
+    if !pattern.split('.').any(|word| word == "*" || word == "#") {
+        return pattern.to_string();
+    }
+    let words: Vec<String> = pattern
+        .split('.')
+        .map(|word| match word {
+            "*" => "[^.]+".to_string(),
+            "#" => ".*".to_string(),
+            word => regex_escape(word),
+        })
+        .collect();
+    format!("^{}$", words.join(r"\."))
+}
+
+fn regex_escape(word: &str) -> String {
+    word.chars()
+        .flat_map(|c| {
+            let escape = !c.is_ascii_alphanumeric() && c != '_';
+            escape.then_some('\\').into_iter().chain([c])
+        })
+        .collect()
+}
+
+#[async_trait]
+impl MessagePublisher for KafkaBroker {
+    async fn publish(&self, topic: &str, key: Option<&str>, message: &MessageEnvelope) -> Result<(), ApplicationError> {
+        let payload = message.encode();
+        let mut record = FutureRecord::<str, [u8]>::to(topic).payload(&payload);
+        if let Some(key) = key {
+            record = record.key(key);
+        }
+        self.producer
+            .send(record, SEND_TIMEOUT)
+            .await
+            .map_err(|(e, _)| kafka_error(e))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MessageConsumer for KafkaBroker {
+    async fn consume(&self, subscription: &str, handler: Arc<dyn MessageHandler>) -> Result<(), ApplicationError> {
+        let settings = self
+            .subscriptions
+            .iter()
+            .find(|s| s.name == subscription)
+            .ok_or_else(|| DomainError::not_found("Subscription", subscription))?;
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &self.servers)
+            .set("group.id", format!("{}.{}", self.group, settings.name))
+            .set("enable.auto.commit", "false")
+            .set("auto.offset.reset", "earliest")
+            .create()
+            .map_err(kafka_error)?;
+        let topics: Vec<String> = settings.topics.iter().map(|t| subscription_topic(t)).collect();
+        let topics: Vec<&str> = topics.iter().map(String::as_str).collect();
+        consumer.subscribe(&topics).map_err(kafka_error)?;
+
+        loop {
+            let record = consumer.recv().await.map_err(kafka_error)?;
+            match MessageEnvelope::decode(record.payload().unwrap_or_default()) {
+                Ok(message) => {
+                    // Leaving the offset uncommitted makes the group's next
+                    // consumer start over at this message
+                    handler.handle(message).await?;
+                }
+                Err(e) => tracing::warn!(subscription, topic = record.topic(), "Skipping undecodable message: {}", e),
+            }
+            consumer.commit_message(&record, CommitMode::Async).map_err(kafka_error)?;
+        }
+    }
+}
@@ -1,23 +1,184 @@
+pub mod account_deletion;
+pub mod activity;
+pub mod api_clients;
+pub mod analytics;
+pub mod anonymize;
+pub mod audit;
 pub mod auth;
+pub mod backup;
+pub mod data_browser;
+pub mod data_export;
+pub mod db;
+pub mod devices;
+pub mod diagnostics;
+pub mod email;
+pub mod email_suppression;
+pub mod events;
+pub mod features;
+pub mod idempotency;
+pub mod images;
+pub mod jobs;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod locks;
+pub(crate) mod macros;
+pub mod messaging;
+pub mod notes;
+pub mod notifications;
+pub mod oauth;
+pub mod operations;
+pub mod organizations;
+pub mod presence;
+pub mod queries;
+#[cfg(feature = "rabbitmq")]
+pub mod rabbitmq;
+pub mod rate_limit;
+pub mod rate_limit_overrides;
+pub mod refresh_tokens;
+pub mod roles;
+pub mod rollups;
+pub mod scanning;
+pub mod segments;
+pub mod settings;
+pub mod specifications;
+pub mod storage;
+pub mod support;
+pub mod tags;
+pub mod telemetry;
+pub mod tenants;
+pub mod webhooks;
 
 use async_trait::async_trait;
-use domain::{User, UserRepository, Repository, DomainError, PaginationParams, Page};
-use sqlx::PgPool;
+use futures_util::TryStreamExt;
+use domain::{Email, Specification, User, UserCursor, UserFilter, UserStream, Username, UserRepository, UserStatus, Repository, DomainError, PaginationParams, Page};
+use shared::PoolConfig;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::{PgPool, QueryBuilder};
+use std::time::Duration;
 use uuid::Uuid;
 
+pub use account_deletion::PgAccountDeletionStore;
+pub use activity::PgActivityStore;
+pub use api_clients::PgApiClientStore;
+pub use analytics::TracingAnalyticsSink;
+pub use anonymize::{Anonymizer, Faker};
+pub use audit::{AuditExportConfig, AuditExporter, AuditSink, AuditSinkConfig};
 pub use auth::{ArgonPasswordHasher, JwtTokenService, JwtConfig};
+pub use backup::{QueueBackup, QueueSnapshot};
+pub use data_browser::PgDataBrowser;
+pub use data_export::PgDataExportStore;
+use db::{page_total, Counted};
+pub use db::{normalize_query, query_fingerprint, Database, DbConnection, PgUnitOfWork};
+pub use devices::PgDeviceStore;
+pub use diagnostics::{run_migrations, DatabaseDiagnostics, MigrationStatus};
+pub use email::{ConsoleEmailSender, EmailConfig, EmailRenderer, EmailTransport, SmtpEmailSender};
+pub use email_suppression::PgEmailSuppressionList;
+pub use events::{decode_event, encode_event, InMemoryEventBus};
+pub use features::{experiments_from_settings, feature_flags_from_settings, InMemoryExperimentAssignmentStore, PgExperimentAssignmentStore, PgFeatureFlagStore, StaticFeatureFlags};
+pub use idempotency::PgIdempotencyStore;
+pub use images::NativeImageProcessor;
+pub use jobs::PgJobQueue;
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaBroker;
+pub use locks::{InMemoryLock, PgAdvisoryLock};
+#[cfg(feature = "redis")]
+pub use locks::RedisLock;
+pub use messaging::{broker_from_settings, Broker, InMemoryBroker};
+pub use notes::PostgresUserNoteRepository;
+pub use notifications::PostgresNotificationRepository;
+pub use oauth::InMemoryOAuthStateStore;
+#[cfg(feature = "redis")]
+pub use oauth::RedisOAuthStateStore;
+pub use operations::PgOperationStore;
+pub use organizations::{PgInvitationStore, PostgresMembershipRepository, PostgresOrganizationRepository};
+pub use presence::InMemoryPresenceStore;
+#[cfg(feature = "redis")]
+pub use presence::RedisPresenceStore;
+pub use queries::PgUserReadModel;
+#[cfg(feature = "rabbitmq")]
+pub use rabbitmq::RabbitMqBroker;
+pub use rate_limit::InMemoryRateLimiter;
+pub use rate_limit_overrides::PgRateLimitOverrideStore;
+pub use refresh_tokens::PgRefreshTokenStore;
+pub use roles::PostgresRoleRepository;
+pub use rollups::PgMetricRollupStore;
+pub use scanning::{ClamAvScanner, IcapScanner, NoopFileScanner, ScannerConfig};
+pub use segments::PostgresUserSegmentRepository;
+pub use settings::PostgresUserSettingsRepository;
+pub use specifications::{push_specification, SqlCriterion};
+pub use storage::{LocalFileStorage, S3FileStorage, StorageBackend, StorageConfig};
+pub use support::PostgresSupportTicketRepository;
+pub use tags::PostgresTagRepository;
+pub use telemetry::{telemetry_from_settings, PrometheusTelemetry, StatsdTelemetry, TelemetryBackend};
+pub use tenants::PostgresTenantRepository;
+pub use webhooks::{HttpWebhookSender, PostgresWebhookDeliveryRepository, PostgresWebhookRepository};
+
+// ============================================================================
+// Database Connection
+// ============================================================================
+
+/// Open the PostgreSQL connection pool used by the repositories
+pub async fn connect_pool(database_url: &str, config: &PoolConfig) -> Result<PgPool, DomainError> {
+    let mut options: PgConnectOptions = database_url
+        .parse()
+        .map_err(|e| DomainError::internal(format!("Invalid database URL: {}", e)))?;
+    if config.statement_timeout_ms > 0 {
+        options = options.options([("statement_timeout", config.statement_timeout_ms.to_string())]);
+    }
+    options = options.statement_cache_capacity(config.statement_cache_capacity);
+    PgPoolOptions::new()
+        .max_connections(config.max_connections)
+        .min_connections(config.min_connections)
+        .acquire_timeout(Duration::from_secs(config.acquire_timeout_secs))
+        .idle_timeout((config.idle_timeout_secs > 0).then(|| Duration::from_secs(config.idle_timeout_secs)))
+        .test_before_acquire(config.test_before_acquire)
+        .connect_with(options)
+        .await
+        .map_err(|e| DomainError::internal(format!("Database connection failed: {}", e)))
+}
+
+/// Log the size and idle connections of `pool` every `interval`, warning
+/// when every connection is busy; also recorded as `db_pool_*` gauges
+pub fn spawn_pool_metrics(pool: PgPool, name: &'static str, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let (size, idle, max) = (pool.size(), pool.num_idle() as u32, pool.options().get_max_connections());
+            let metrics = shared::telemetry::telemetry();
+            metrics.gauge("db_pool_connections", size as f64, &[("pool", name)]);
+            metrics.gauge("db_pool_idle", idle as f64, &[("pool", name)]);
+            metrics.gauge("db_pool_max", max as f64, &[("pool", name)]);
+            if size >= max && idle == 0 {
+                tracing::warn!(pool = name, size, idle, max, "Database pool exhausted");
+            } else {
+                tracing::info!(pool = name, size, idle, max, "Database pool");
+            }
+        }
+    });
+}
 
 // ============================================================================
 // Repository Implementations (Adapters)
 // ============================================================================
 
 pub struct PostgresUserRepository {
-    pool: PgPool,
+    db: Database,
 }
 
 impl PostgresUserRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Write connection for one query (joins the ambient unit-of-work transaction, if any)
+    async fn conn(&self) -> Result<DbConnection, DomainError> {
+        self.db.acquire().await
+    }
+
+    /// Read connection for one query (replica when it satisfies read-your-writes)
+    async fn read_conn(&self) -> Result<DbConnection, DomainError> {
+        self.db.acquire_read().await
     }
 }
 
@@ -27,18 +188,41 @@ struct UserRow {
     username: String,
     email: String,
     password_hash: String,
+    avatar_url: Option<String>,
+    status: String,
+    password_reset_required: bool,
+    tenant_id: Uuid,
     created_at: chrono::DateTime<chrono::Utc>,
+    updated_at: chrono::DateTime<chrono::Utc>,
+    version: i64,
+    token_version: i64,
+    last_login_at: Option<chrono::DateTime<chrono::Utc>>,
+    last_seen_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
-impl From<UserRow> for User {
-    fn from(row: UserRow) -> Self {
-        Self {
+/// Stored values pass the same checks as new ones, so a row edited by hand
+/// into an invalid state fails loudly instead of reaching the application
+impl TryFrom<UserRow> for User {
+    type Error = DomainError;
+
+    fn try_from(row: UserRow) -> Result<Self, DomainError> {
+        let invalid = |e: DomainError| DomainError::internal(format!("Stored user {} is invalid: {}", row.id, e));
+        Ok(Self {
             id: row.id,
-            username: row.username,
-            email: row.email,
+            username: Username::parse(&row.username).map_err(invalid)?,
+            email: Email::parse(&row.email).map_err(invalid)?,
             password_hash: row.password_hash,
+            avatar_url: row.avatar_url,
+            status: UserStatus::parse(&row.status).unwrap_or_default(),
+            password_reset_required: row.password_reset_required,
+            tenant_id: row.tenant_id,
             created_at: row.created_at,
-        }
+            updated_at: row.updated_at,
+            version: row.version,
+            token_version: row.token_version,
+            last_login_at: row.last_login_at,
+            last_seen_at: row.last_seen_at,
+        })
     }
 }
 
@@ -55,14 +239,57 @@ fn is_unique_violation(err: &sqlx::Error) -> bool {
     false
 }
 
+/// Helper to detect foreign key violations (deleting a row still referenced)
+fn is_foreign_key_violation(err: &sqlx::Error) -> bool {
+    if let sqlx::Error::Database(db_err) = err {
+        return db_err.code().map(|c| c == "23503").unwrap_or(false);
+    }
+    false
+}
+
+/// Helper to detect statements cancelled by `statement_timeout`
+fn is_statement_timeout(err: &sqlx::Error) -> bool {
+    if let sqlx::Error::Database(db_err) = err {
+        // query_canceled, also raised by pg_cancel_backend
+        return db_err.code().map(|c| c == "57014").unwrap_or(false);
+    }
+    false
+}
+
+/// Helper to detect failures where the same statement may succeed when
+/// retried: lost connections, server restarts, deadlocks and serialization
+/// failures
+pub(crate) fn is_transient(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Io(_) => true,
+        sqlx::Error::Database(db_err) => db_err.code().is_some_and(|c| {
+            // connection_exception class, serialization_failure, deadlock_detected,
+            // admin/crash shutdown, cannot_connect_now, too_many_connections
+            c.starts_with("08") || matches!(c.as_ref(), "40001" | "40P01" | "57P01" | "57P02" | "57P03" | "53300")
+        }),
+        _ => false,
+    }
+}
+
 /// Map SQLx errors to domain errors with proper context
-fn map_sqlx_error(err: sqlx::Error, entity: &'static str) -> DomainError {
+pub(crate) fn map_sqlx_error(err: sqlx::Error, entity: &'static str) -> DomainError {
     if is_unique_violation(&err) {
         return DomainError::conflict(format!("{} already exists", entity));
     }
+    if is_foreign_key_violation(&err) {
+        return DomainError::conflict(format!("{} is still referenced", entity));
+    }
+
+    if is_statement_timeout(&err) {
+        return DomainError::timeout(format!("{} query exceeded the statement timeout", entity));
+    }
+    if is_transient(&err) {
+        return DomainError::unavailable(format!("{} query failed transiently: {}", entity, err));
+    }
 
     match err {
         sqlx::Error::RowNotFound => DomainError::not_found(entity, "unknown"),
+        sqlx::Error::PoolTimedOut => DomainError::timeout("Timed out waiting for a database connection"),
         _ => DomainError::internal(err.to_string()),
     }
 }
@@ -73,102 +300,185 @@ fn map_sqlx_error(err: sqlx::Error, entity: &'static str) -> DomainError {
 
 #[async_trait]
 impl Repository<User> for PostgresUserRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "find_by_id"))]
     async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, DomainError> {
         let row = sqlx::query_as::<_, UserRow>(
             r#"
-            SELECT id, username, email, password_hash, created_at
+            SELECT id, username, email, password_hash, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version, token_version, last_login_at, last_seen_at
             FROM users
             WHERE id = $1
             "#,
         )
         .bind(id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut self.read_conn().await?)
         .await
         .map_err(|e| map_sqlx_error(e, "User"))?;
 
-        Ok(row.map(Into::into))
+        row.map(User::try_from).transpose()
     }
 
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "find_all"))]
     async fn find_all(&self, params: &PaginationParams) -> Result<Page<User>, DomainError> {
-        let rows = sqlx::query_as::<_, UserRow>(
+        params.validate()?;
+        let sql = format!(
             r#"
-            SELECT id, username, email, password_hash, created_at
+            SELECT id, username, email, password_hash, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version, token_version, last_login_at, last_seen_at{}
             FROM users
             ORDER BY created_at DESC
             LIMIT $1 OFFSET $2
             "#,
-        )
-        .bind(params.limit() as i64)
-        .bind(params.offset() as i64)
-        .fetch_all(&self.pool)
-        .await
-        .map_err(|e| map_sqlx_error(e, "User"))?;
+            self.db.total_column()
+        );
+        let rows = sqlx::query_as::<_, Counted<UserRow>>(&sql)
+            .bind(params.limit() as i64)
+            .bind(params.offset() as i64)
+            .fetch_all(&mut self.read_conn().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "User"))?;
 
-        let total = self.count().await?;
-        let users: Vec<User> = rows.into_iter().map(Into::into).collect();
+        let total = match page_total(&rows, params) {
+            Some(total) => total,
+            None => self.count().await?,
+        };
+        let users: Vec<User> = rows.into_iter().map(|r| User::try_from(r.row)).collect::<Result<_, _>>()?;
 
         Ok(Page::new(users, total, params))
     }
 
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "create"))]
     async fn create(&self, user: &User) -> Result<User, DomainError> {
         let row = sqlx::query_as::<_, UserRow>(
             r#"
-            INSERT INTO users (id, username, email, password_hash, created_at)
-            VALUES ($1, $2, $3, $4, $5)
-            RETURNING id, username, email, password_hash, created_at
+            INSERT INTO users (id, username, email, password_hash, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version, email_normalized)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            RETURNING id, username, email, password_hash, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version, token_version, last_login_at, last_seen_at
             "#,
         )
         .bind(user.id)
-        .bind(&user.username)
-        .bind(&user.email)
+        .bind(user.username.as_str())
+        .bind(user.email.as_str())
         .bind(&user.password_hash)
+        .bind(&user.avatar_url)
+        .bind(user.status.as_str())
+        .bind(user.password_reset_required)
+        .bind(user.tenant_id)
         .bind(user.created_at)
-        .fetch_one(&self.pool)
+        .bind(user.updated_at)
+        .bind(user.version)
+        .bind(user.email.normalized())
+        .fetch_one(&mut self.conn().await?)
         .await
         .map_err(|e| map_sqlx_error(e, "User"))?;
 
-        Ok(row.into())
+        self.db.record_write().await;
+        row.try_into()
     }
 
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "update"))]
     async fn update(&self, user: &User) -> Result<User, DomainError> {
+        let mut conn = self.conn().await?;
         let row = sqlx::query_as::<_, UserRow>(
             r#"
             UPDATE users
-            SET username = $2, email = $3, password_hash = $4
-            WHERE id = $1
-            RETURNING id, username, email, password_hash, created_at
+            SET username = $2, email = $3, password_hash = $4, avatar_url = $5,
+                status = $6, password_reset_required = $7, email_normalized = $10,
+                updated_at = now(), version = version + 1
+            WHERE id = $1 AND version = $8 AND tenant_id = $9
+            RETURNING id, username, email, password_hash, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version, token_version, last_login_at, last_seen_at
             "#,
         )
         .bind(user.id)
-        .bind(&user.username)
-        .bind(&user.email)
+        .bind(user.username.as_str())
+        .bind(user.email.as_str())
         .bind(&user.password_hash)
-        .fetch_optional(&self.pool)
+        .bind(&user.avatar_url)
+        .bind(user.status.as_str())
+        .bind(user.password_reset_required)
+        .bind(user.version)
+        .bind(user.tenant_id)
+        .bind(user.email.normalized())
+        .fetch_optional(&mut conn)
         .await
-        .map_err(|e| map_sqlx_error(e, "User"))?
-        .ok_or_else(|| DomainError::not_found("User", user.id.to_string()))?;
+        .map_err(|e| map_sqlx_error(e, "User"))?;
 
-        Ok(row.into())
+        // No row: either the user is gone (from this tenant) or someone else updated it first
+        let Some(row) = row else {
+            let current: Option<i64> = sqlx::query_scalar("SELECT version FROM users WHERE id = $1 AND tenant_id = $2")
+                .bind(user.id)
+                .bind(user.tenant_id)
+                .fetch_optional(&mut conn)
+                .await
+                .map_err(|e| map_sqlx_error(e, "User"))?;
+            return Err(match current {
+                Some(version) => DomainError::version_conflict("User", user.id.to_string(), version),
+                None => DomainError::not_found("User", user.id.to_string()),
+            });
+        };
+
+        self.db.record_write().await;
+        row.try_into()
     }
 
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "delete"))]
     async fn delete(&self, id: Uuid) -> Result<bool, DomainError> {
         let result = sqlx::query("DELETE FROM users WHERE id = $1")
             .bind(id)
-            .execute(&self.pool)
+            .execute(&mut self.conn().await?)
             .await
             .map_err(|e| map_sqlx_error(e, "User"))?;
 
+        self.db.record_write().await;
         Ok(result.rows_affected() > 0)
     }
 
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "count"))]
     async fn count(&self) -> Result<u64, DomainError> {
         let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM users")
-            .fetch_one(&self.pool)
+            .fetch_one(&mut self.read_conn().await?)
             .await
             .map_err(|e| map_sqlx_error(e, "User"))?;
 
         Ok(count.0 as u64)
     }
+
+    /// Translated to one `WHERE` clause (see `specifications`)
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "find_by_spec"))]
+    async fn find_by_spec(&self, spec: &Specification<User>, params: &PaginationParams) -> Result<Page<User>, DomainError> {
+        params.validate()?;
+
+        let mut query = QueryBuilder::new(
+            "SELECT id, username, email, password_hash, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version, token_version, last_login_at, last_seen_at",
+        );
+        query.push(self.db.total_column()).push(" FROM users WHERE ");
+        push_specification(&mut query, spec);
+        query
+            .push(" ORDER BY created_at DESC LIMIT ")
+            .push_bind(params.limit() as i64)
+            .push(" OFFSET ")
+            .push_bind(params.offset() as i64);
+        let rows = query
+            .build_query_as::<Counted<UserRow>>()
+            .fetch_all(&mut self.read_conn().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "User"))?;
+
+        let total = match page_total(&rows, params) {
+            Some(total) => total,
+            None => {
+                let mut count = QueryBuilder::new("SELECT COUNT(*) FROM users WHERE ");
+                push_specification(&mut count, spec);
+                let total: i64 = count
+                    .build_query_scalar()
+                    .fetch_one(&mut self.read_conn().await?)
+                    .await
+                    .map_err(|e| map_sqlx_error(e, "User"))?;
+                total as u64
+            }
+        };
+
+        let users: Vec<User> = rows.into_iter().map(|r| User::try_from(r.row)).collect::<Result<_, _>>()?;
+        Ok(Page::new(users, total, params))
+    }
 }
 
 // ============================================================================
@@ -177,38 +487,248 @@ impl Repository<User> for PostgresUserRepository {
 
 #[async_trait]
 impl UserRepository for PostgresUserRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "find_by_email"))]
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, DomainError> {
         let row = sqlx::query_as::<_, UserRow>(
             r#"
-            SELECT id, username, email, password_hash, created_at
+            SELECT id, username, email, password_hash, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version, token_version, last_login_at, last_seen_at
             FROM users
-            WHERE email = $1
+            WHERE email_normalized = $1
             "#,
         )
-        .bind(email)
-        .fetch_optional(&self.pool)
+        .bind(domain::normalize_email(email))
+        .fetch_optional(&mut self.read_conn().await?)
         .await
         .map_err(|e| map_sqlx_error(e, "User"))?;
 
-        Ok(row.map(Into::into))
+        row.map(User::try_from).transpose()
     }
 
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "find_by_username"))]
     async fn find_by_username(&self, username: &str) -> Result<Option<User>, DomainError> {
         let row = sqlx::query_as::<_, UserRow>(
             r#"
-            SELECT id, username, email, password_hash, created_at
+            SELECT id, username, email, password_hash, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version, token_version, last_login_at, last_seen_at
             FROM users
             WHERE username = $1
             "#,
         )
         .bind(username)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut self.read_conn().await?)
         .await
         .map_err(|e| map_sqlx_error(e, "User"))?;
 
-        Ok(row.map(Into::into))
+        row.map(User::try_from).transpose()
     }
-}
 
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "find_by_ids"))]
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<User>, DomainError> {
+        let rows = sqlx::query_as::<_, UserRow>(
+            r#"
+            SELECT id, username, email, password_hash, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version, token_version, last_login_at, last_seen_at
+            FROM users
+            WHERE id = ANY($1)
+            "#,
+        )
+        .bind(ids)
+        .fetch_all(&mut self.read_conn().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "User"))?;
+
+        rows.into_iter().map(User::try_from).collect()
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "find_by_usernames"))]
+    async fn find_by_usernames(&self, usernames: &[String]) -> Result<Vec<User>, DomainError> {
+        let rows = sqlx::query_as::<_, UserRow>(
+            r#"
+            SELECT id, username, email, password_hash, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version, token_version, last_login_at, last_seen_at
+            FROM users
+            WHERE username = ANY($1)
+            "#,
+        )
+        .bind(usernames)
+        .fetch_all(&mut self.read_conn().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "User"))?;
+
+        rows.into_iter().map(User::try_from).collect()
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "search"))]
+    async fn search(&self, filter: &UserFilter, params: &PaginationParams) -> Result<Page<User>, DomainError> {
+        params.validate()?;
+        let pattern = filter.query.as_deref().map(like_pattern);
+        let status = filter.status.map(|s| s.as_str());
+
+        let sql = format!(
+            r#"
+            SELECT id, username, email, password_hash, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version, token_version, last_login_at, last_seen_at{}
+            FROM users
+            WHERE ($1::text IS NULL OR username ILIKE $1 OR email ILIKE $1)
+              AND ($2::text IS NULL OR status = $2)
+              AND ($3::uuid IS NULL OR tenant_id = $3)
+              AND (cardinality($6::text[]) = 0 OR id IN (
+                  SELECT tg.entity_id FROM taggings tg JOIN tags t ON t.id = tg.tag_id
+                  WHERE tg.entity_type = 'user' AND t.name = ANY($6)
+                  GROUP BY tg.entity_id HAVING COUNT(*) = cardinality($6)))
+            ORDER BY created_at DESC
+            LIMIT $4 OFFSET $5
+            "#,
+            self.db.total_column()
+        );
+        let rows = sqlx::query_as::<_, Counted<UserRow>>(&sql)
+            .bind(&pattern)
+            .bind(status)
+            .bind(filter.tenant_id)
+            .bind(params.limit() as i64)
+            .bind(params.offset() as i64)
+            .bind(&filter.tags)
+            .fetch_all(&mut self.read_conn().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "User"))?;
+        let total = page_total(&rows, params);
+        let users: Vec<User> = rows.into_iter().map(|r| User::try_from(r.row)).collect::<Result<_, _>>()?;
+        if let Some(total) = total {
+            return Ok(Page::new(users, total, params));
+        }
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM users
+            WHERE ($1::text IS NULL OR username ILIKE $1 OR email ILIKE $1)
+              AND ($2::text IS NULL OR status = $2)
+              AND ($3::uuid IS NULL OR tenant_id = $3)
+              AND (cardinality($4::text[]) = 0 OR id IN (
+                  SELECT tg.entity_id FROM taggings tg JOIN tags t ON t.id = tg.tag_id
+                  WHERE tg.entity_type = 'user' AND t.name = ANY($4)
+                  GROUP BY tg.entity_id HAVING COUNT(*) = cardinality($4)))
+            "#,
+        )
+        .bind(&pattern)
+        .bind(status)
+        .bind(filter.tenant_id)
+        .bind(&filter.tags)
+        .fetch_one(&mut self.read_conn().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "User"))?;
+
+        Ok(Page::new(users, total as u64, params))
+    }
+
+    /// Keyset paging on `(created_at, id)`, served by `idx_users_created_at_id`
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "search_after"))]
+    async fn search_after(&self, filter: &UserFilter, after: Option<UserCursor>, limit: u32) -> Result<Vec<User>, DomainError> {
+        let rows = sqlx::query_as::<_, UserRow>(
+            r#"
+            SELECT id, username, email, password_hash, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version, token_version, last_login_at, last_seen_at
+            FROM users
+            WHERE ($1::text IS NULL OR username ILIKE $1 OR email ILIKE $1)
+              AND ($2::text IS NULL OR status = $2)
+              AND ($3::uuid IS NULL OR tenant_id = $3)
+              AND (cardinality($4::text[]) = 0 OR id IN (
+                  SELECT tg.entity_id FROM taggings tg JOIN tags t ON t.id = tg.tag_id
+                  WHERE tg.entity_type = 'user' AND t.name = ANY($4)
+                  GROUP BY tg.entity_id HAVING COUNT(*) = cardinality($4)))
+              AND ($5::timestamptz IS NULL OR (created_at, id) < ($5, $6))
+            ORDER BY created_at DESC, id DESC
+            LIMIT $7
+            "#,
+        )
+        .bind(filter.query.as_deref().map(like_pattern))
+        .bind(filter.status.map(|s| s.as_str()))
+        .bind(filter.tenant_id)
+        .bind(&filter.tags)
+        .bind(after.map(|c| c.created_at))
+        .bind(after.map(|c| c.id))
+        .bind(limit as i64)
+        .fetch_all(&mut self.read_conn().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "User"))?;
+
+        rows.into_iter().map(User::try_from).collect()
+    }
+
+    /// One `fetch()` on one read connection: rows are decoded as Postgres sends
+    /// them, and while the consumer is slow TCP flow control holds the server back
+    fn stream_all(&self, filter: &UserFilter) -> UserStream {
+        let db = self.db.clone();
+        let filter = filter.clone();
+        Box::pin(async_stream::try_stream! {
+            let mut conn = db.acquire_read().await?;
+            let mut rows = sqlx::query_as::<_, UserRow>(
+                r#"
+                SELECT id, username, email, password_hash, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version, token_version, last_login_at, last_seen_at
+                FROM users
+                WHERE ($1::text IS NULL OR username ILIKE $1 OR email ILIKE $1)
+                  AND ($2::text IS NULL OR status = $2)
+                  AND ($3::uuid IS NULL OR tenant_id = $3)
+                  AND (cardinality($4::text[]) = 0 OR id IN (
+                      SELECT tg.entity_id FROM taggings tg JOIN tags t ON t.id = tg.tag_id
+                      WHERE tg.entity_type = 'user' AND t.name = ANY($4)
+                      GROUP BY tg.entity_id HAVING COUNT(*) = cardinality($4)))
+                ORDER BY created_at DESC, id DESC
+                "#,
+            )
+            .bind(filter.query.as_deref().map(like_pattern))
+            .bind(filter.status.map(|s| s.as_str()))
+            .bind(filter.tenant_id)
+            .bind(&filter.tags)
+            .fetch(&mut conn);
+
+            while let Some(row) = rows.try_next().await.map_err(|e| map_sqlx_error(e, "User"))? {
+                yield User::try_from(row)?;
+            }
+        })
+    }
+
+    /// `username ILIKE 'prefix%'` is served by the trigram index on `username`
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "autocomplete"))]
+    async fn autocomplete(&self, prefix: &str, filter: &UserFilter, limit: u32) -> Result<Vec<User>, DomainError> {
+        let rows = sqlx::query_as::<_, UserRow>(
+            r#"
+            SELECT id, username, email, password_hash, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version, token_version, last_login_at, last_seen_at
+            FROM users
+            WHERE username ILIKE $1
+              AND ($2::text IS NULL OR status = $2)
+              AND ($3::uuid IS NULL OR tenant_id = $3)
+            ORDER BY lower(username) = lower($4) DESC, length(username), username
+            LIMIT $5
+            "#,
+        )
+        .bind(format!("{}%", escape_like(prefix)))
+        .bind(filter.status.map(|s| s.as_str()))
+        .bind(filter.tenant_id)
+        .bind(prefix)
+        .bind(limit as i64)
+        .fetch_all(&mut self.read_conn().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "User"))?;
 
+        rows.into_iter().map(User::try_from).collect()
+    }
+
+    /// Leaves `version` and `updated_at` alone: signing out is not an edit
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "User", operation = "bump_token_version"))]
+    async fn bump_token_version(&self, id: Uuid) -> Result<i64, DomainError> {
+        let version: Option<i64> =
+            sqlx::query_scalar("UPDATE users SET token_version = token_version + 1 WHERE id = $1 RETURNING token_version")
+                .bind(id)
+                .fetch_optional(&mut self.conn().await?)
+                .await
+                .map_err(|e| map_sqlx_error(e, "User"))?;
+
+        let version = version.ok_or_else(|| DomainError::not_found("User", id.to_string()))?;
+        self.db.record_write().await;
+        Ok(version)
+    }
+}
 
+/// `%query%` with LIKE wildcards in `query` escaped
+fn like_pattern(query: &str) -> String {
+    format!("%{}%", escape_like(query))
+}
+
+fn escape_like(query: &str) -> String {
+    query.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
@@ -0,0 +1,266 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use application::locks::{DistributedLock, LockGuard};
+use application::ApplicationError;
+use async_trait::async_trait;
+use domain::DomainError;
+#[cfg(feature = "redis")]
+use redis::aio::ConnectionManager;
+use sqlx::pool::PoolConnection;
+use sqlx::Postgres;
+#[cfg(feature = "redis")]
+use tokio::sync::watch;
+#[cfg(feature = "redis")]
+use tokio::task::JoinHandle;
+#[cfg(feature = "redis")]
+use uuid::Uuid;
+
+use crate::db::Database;
+
+/// First key of the two-key advisory locks taken here, keeping them apart
+/// from other advisory locks; the second is `hashtext(name)`
+const LOCK_SPACE: i32 = 0x6c6f_636b; // "lock"
+
+// ============================================================================
+// Postgres Advisory Lock
+// ============================================================================
+
+/// Session-level advisory locks on the primary. The session holding one
+/// keeps its pooled connection until the lock is released, and the server
+/// frees it by itself if the instance dies, so the TTL is not needed.
+pub struct PgAdvisoryLock {
+    db: Database,
+}
+
+impl PgAdvisoryLock {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("Lock error: {}", err)).into()
+}
+
+#[async_trait]
+impl DistributedLock for PgAdvisoryLock {
+    async fn try_acquire(&self, name: &str, _ttl: Duration) -> Result<Option<LockGuard>, ApplicationError> {
+        let mut conn = self.db.primary().acquire().await.map_err(map_err)?;
+        let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_lock($1, hashtext($2))")
+            .bind(LOCK_SPACE)
+            .bind(name)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(map_err)?;
+        Ok(locked.then(|| {
+            LockGuard::new(AdvisoryLock {
+                conn: Some(conn),
+                name: name.to_string(),
+            })
+        }))
+    }
+}
+
+/// Unlocks in the background on drop, closing the connection instead if
+/// that fails so the lock cannot outlive it in the pool
+struct AdvisoryLock {
+    conn: Option<PoolConnection<Postgres>>,
+    name: String,
+}
+
+impl Drop for AdvisoryLock {
+    fn drop(&mut self) {
+        let Some(mut conn) = self.conn.take() else {
+            return;
+        };
+        let name = std::mem::take(&mut self.name);
+        tokio::spawn(async move {
+            let unlocked: Result<bool, _> = sqlx::query_scalar("SELECT pg_advisory_unlock($1, hashtext($2))")
+                .bind(LOCK_SPACE)
+                .bind(&name)
+                .fetch_one(&mut *conn)
+                .await;
+            if !matches!(unlocked, Ok(true)) {
+                tracing::warn!(lock = %name, "Failed to release advisory lock; closing its connection");
+                let _ = conn.close().await;
+            }
+        });
+    }
+}
+
+// ============================================================================
+// Redis Lock
+// ============================================================================
+
+/// Extends the lease only while it still carries the holder's token
+#[cfg(feature = "redis")]
+const RENEW_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("PEXPIRE", KEYS[1], ARGV[2])
+end
+return 0
+"#;
+
+/// Deletes the lease only while it still carries the holder's token
+#[cfg(feature = "redis")]
+const RELEASE_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0
+"#;
+
+/// Leases in Redis (`lock:<name>`, set with `NX PX ttl` to a random token).
+/// The holder renews the lease every third of the TTL; if a renewal fails
+/// or finds the lease gone, the guard reports the lock lost. A holder that
+/// dies frees it once the TTL runs out.
+#[cfg(feature = "redis")]
+pub struct RedisLock {
+    redis: ConnectionManager,
+}
+
+#[cfg(feature = "redis")]
+impl RedisLock {
+    pub async fn connect(url: &str) -> Result<Self, DomainError> {
+        let client = redis::Client::open(url).map_err(redis_error)?;
+        let redis = client.get_connection_manager().await.map_err(redis_error)?;
+        Ok(Self { redis })
+    }
+}
+
+#[cfg(feature = "redis")]
+fn redis_error(err: redis::RedisError) -> DomainError {
+    DomainError::internal(format!("Redis error: {}", err))
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl DistributedLock for RedisLock {
+    async fn try_acquire(&self, name: &str, ttl: Duration) -> Result<Option<LockGuard>, ApplicationError> {
+        let key = format!("lock:{}", name);
+        let token = Uuid::new_v4().to_string();
+        let ttl_ms = ttl.as_millis().max(1) as u64;
+        let acquired: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg(&token)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl_ms)
+            .query_async(&mut self.redis.clone())
+            .await
+            .map_err(redis_error)?;
+        if acquired.is_none() {
+            return Ok(None);
+        }
+
+        let (lost_tx, lost_rx) = watch::channel(false);
+        let renewal = tokio::spawn(renew_lease(self.redis.clone(), key.clone(), token.clone(), ttl, lost_tx));
+        let lease = Lease {
+            redis: self.redis.clone(),
+            key,
+            token,
+            renewal,
+        };
+        Ok(Some(LockGuard::new(lease).with_lost_signal(lost_rx)))
+    }
+}
+
+/// Keep extending the lease until it is released or cannot be renewed
+#[cfg(feature = "redis")]
+async fn renew_lease(mut redis: ConnectionManager, key: String, token: String, ttl: Duration, lost: watch::Sender<bool>) {
+    let script = redis::Script::new(RENEW_SCRIPT);
+    loop {
+        tokio::time::sleep(ttl / 3).await;
+        let renewed: Result<i64, _> = script
+            .key(&key)
+            .arg(&token)
+            .arg(ttl.as_millis().max(1) as u64)
+            .invoke_async(&mut redis)
+            .await;
+        match renewed {
+            Ok(1) => {}
+            Ok(_) => {
+                tracing::warn!(lock = %key, "Lock lease expired before it was renewed");
+                let _ = lost.send(true);
+                return;
+            }
+            Err(e) => {
+                tracing::warn!(lock = %key, error = %e, "Failed to renew lock lease");
+                let _ = lost.send(true);
+                return;
+            }
+        }
+    }
+}
+
+/// Stops renewing and deletes the lease in the background on drop
+#[cfg(feature = "redis")]
+struct Lease {
+    redis: ConnectionManager,
+    key: String,
+    token: String,
+    renewal: JoinHandle<()>,
+}
+
+#[cfg(feature = "redis")]
+impl Drop for Lease {
+    fn drop(&mut self) {
+        self.renewal.abort();
+        let mut redis = self.redis.clone();
+        let key = std::mem::take(&mut self.key);
+        let token = std::mem::take(&mut self.token);
+        tokio::spawn(async move {
+            let released: Result<i64, _> = redis::Script::new(RELEASE_SCRIPT)
+                .key(&key)
+                .arg(&token)
+                .invoke_async(&mut redis)
+                .await;
+            if let Err(e) = released {
+                tracing::warn!(lock = %key, error = %e, "Failed to release lock; it expires with its lease");
+            }
+        });
+    }
+}
+
+// ============================================================================
+// In-Memory Lock
+// ============================================================================
+
+/// Process-local locks for single-instance deployments and tests; released
+/// only by dropping the guard, so the TTL is not needed
+#[derive(Default)]
+pub struct InMemoryLock {
+    held: Arc<Mutex<HashSet<String>>>,
+}
+
+impl InMemoryLock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DistributedLock for InMemoryLock {
+    async fn try_acquire(&self, name: &str, _ttl: Duration) -> Result<Option<LockGuard>, ApplicationError> {
+        if !self.held.lock().unwrap().insert(name.to_string()) {
+            return Ok(None);
+        }
+        Ok(Some(LockGuard::new(HeldName {
+            held: self.held.clone(),
+            name: name.to_string(),
+        })))
+    }
+}
+
+struct HeldName {
+    held: Arc<Mutex<HashSet<String>>>,
+    name: String,
+}
+
+impl Drop for HeldName {
+    fn drop(&mut self) {
+        self.held.lock().unwrap().remove(&self.name);
+    }
+}
@@ -0,0 +1,191 @@
+// ============================================================================
+// Repository Scaffolding
+// ============================================================================
+
+/// Generates a Postgres repository struct (`new(db)`) and its
+/// `Repository<Entity>` impl for an entity whose fields map 1:1 onto the
+/// table's columns with sqlx-encodable types.
+///
+/// Columns marked `#[immutable]` are written on insert but never updated.
+///
+/// ```ignore
+/// pg_repository! {
+///     pub struct PostgresNoteRepository for Note in "notes" order by "created_at DESC" {
+///         id: Uuid,
+///         #[immutable] author_id: Uuid,
+///         title: String,
+///         #[immutable] created_at: DateTime<Utc>,
+///     }
+/// }
+/// ```
+macro_rules! pg_repository {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $repo:ident for $entity:ident in $table:literal order by $order:literal {
+            id: $id_ty:ty,
+            $( $(#[$flag:ident])? $col:ident : $col_ty:ty ),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $repo {
+            db: $crate::db::Database,
+        }
+
+        impl $repo {
+            pub fn new(db: $crate::db::Database) -> Self {
+                Self { db }
+            }
+        }
+
+        const _: () = {
+            use ::domain::{DomainError, Page, PaginationParams, Repository};
+            use $crate::map_sqlx_error;
+
+            const ENTITY: &str = stringify!($entity);
+            const COLUMNS: &[(&str, bool)] = &[$( (stringify!($col), $crate::macros::pg_repository!(@updatable $($flag)?)) ),+];
+
+            fn column_list() -> String {
+                std::iter::once("id")
+                    .chain(COLUMNS.iter().map(|(name, _)| *name))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            }
+
+            #[derive(sqlx::FromRow)]
+            struct Row {
+                id: $id_ty,
+                $( $col: $col_ty, )+
+            }
+
+            impl From<Row> for $entity {
+                fn from(row: Row) -> Self {
+                    Self {
+                        id: row.id,
+                        $( $col: row.$col, )+
+                    }
+                }
+            }
+
+            #[async_trait::async_trait]
+            impl Repository<$entity> for $repo {
+                #[tracing::instrument(name = "repository", skip_all, fields(entity = stringify!($entity), operation = "find_by_id"))]
+                async fn find_by_id(&self, id: $id_ty) -> Result<Option<$entity>, DomainError> {
+                    let sql = format!("SELECT {} FROM {} WHERE id = $1", column_list(), $table);
+                    let row = sqlx::query_as::<_, Row>(&sql)
+                        .bind(id)
+                        .fetch_optional(&mut self.db.acquire_read().await?)
+                        .await
+                        .map_err(|e| map_sqlx_error(e, ENTITY))?;
+
+                    Ok(row.map(Into::into))
+                }
+
+                #[tracing::instrument(name = "repository", skip_all, fields(entity = stringify!($entity), operation = "find_all"))]
+                async fn find_all(&self, params: &PaginationParams) -> Result<Page<$entity>, DomainError> {
+                    params.validate()?;
+                    let sql = format!(
+                        "SELECT {}{} FROM {} ORDER BY {} LIMIT $1 OFFSET $2",
+                        column_list(),
+                        self.db.total_column(),
+                        $table,
+                        $order
+                    );
+                    let rows = sqlx::query_as::<_, $crate::db::Counted<Row>>(&sql)
+                        .bind(params.limit() as i64)
+                        .bind(params.offset() as i64)
+                        .fetch_all(&mut self.db.acquire_read().await?)
+                        .await
+                        .map_err(|e| map_sqlx_error(e, ENTITY))?;
+
+                    let total = match $crate::db::page_total(&rows, params) {
+                        Some(total) => total,
+                        None => self.count().await?,
+                    };
+                    let items = rows.into_iter().map(|r| r.row.into()).collect();
+
+                    Ok(Page::new(items, total, params))
+                }
+
+                #[tracing::instrument(name = "repository", skip_all, fields(entity = stringify!($entity), operation = "create"))]
+                async fn create(&self, entity: &$entity) -> Result<$entity, DomainError> {
+                    let placeholders: Vec<String> = (1..=COLUMNS.len() + 1).map(|i| format!("${}", i)).collect();
+                    let sql = format!(
+                        "INSERT INTO {} ({}) VALUES ({}) RETURNING {}",
+                        $table,
+                        column_list(),
+                        placeholders.join(", "),
+                        column_list()
+                    );
+                    let row = sqlx::query_as::<_, Row>(&sql)
+                        .bind(&entity.id)
+                        $( .bind(&entity.$col) )+
+                        .fetch_one(&mut self.db.acquire().await?)
+                        .await
+                        .map_err(|e| map_sqlx_error(e, ENTITY))?;
+
+                    self.db.record_write().await;
+                    Ok(row.into())
+                }
+
+                #[tracing::instrument(name = "repository", skip_all, fields(entity = stringify!($entity), operation = "update"))]
+                async fn update(&self, entity: &$entity) -> Result<$entity, DomainError> {
+                    let assignments: Vec<String> = COLUMNS
+                        .iter()
+                        .filter(|(_, updatable)| *updatable)
+                        .enumerate()
+                        .map(|(i, (name, _))| format!("{} = ${}", name, i + 2))
+                        .collect();
+                    let sql = format!(
+                        "UPDATE {} SET {} WHERE id = $1 RETURNING {}",
+                        $table,
+                        assignments.join(", "),
+                        column_list()
+                    );
+                    let mut query = sqlx::query_as::<_, Row>(&sql).bind(&entity.id);
+                    $(
+                        if $crate::macros::pg_repository!(@updatable $($flag)?) {
+                            query = query.bind(&entity.$col);
+                        }
+                    )+
+                    let row = query
+                        .fetch_optional(&mut self.db.acquire().await?)
+                        .await
+                        .map_err(|e| map_sqlx_error(e, ENTITY))?
+                        .ok_or_else(|| DomainError::not_found(ENTITY, entity.id.to_string()))?;
+
+                    self.db.record_write().await;
+                    Ok(row.into())
+                }
+
+                #[tracing::instrument(name = "repository", skip_all, fields(entity = stringify!($entity), operation = "delete"))]
+                async fn delete(&self, id: $id_ty) -> Result<bool, DomainError> {
+                    let sql = format!("DELETE FROM {} WHERE id = $1", $table);
+                    let result = sqlx::query(&sql)
+                        .bind(id)
+                        .execute(&mut self.db.acquire().await?)
+                        .await
+                        .map_err(|e| map_sqlx_error(e, ENTITY))?;
+
+                    self.db.record_write().await;
+                    Ok(result.rows_affected() > 0)
+                }
+
+                #[tracing::instrument(name = "repository", skip_all, fields(entity = stringify!($entity), operation = "count"))]
+                async fn count(&self) -> Result<u64, DomainError> {
+                    let sql = format!("SELECT COUNT(*) FROM {}", $table);
+                    let count: (i64,) = sqlx::query_as(&sql)
+                        .fetch_one(&mut self.db.acquire_read().await?)
+                        .await
+                        .map_err(|e| map_sqlx_error(e, ENTITY))?;
+
+                    Ok(count.0 as u64)
+                }
+            }
+        };
+    };
+
+    (@updatable immutable) => { false };
+    (@updatable) => { true };
+}
+
+pub(crate) use pg_repository;
@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use application::messaging::{
+    spawn_consumer, topic_matches, MessageConsumer, MessageEnvelope, MessageHandler, MessagePublisher, RestartBackoff,
+};
+use application::ApplicationError;
+use async_trait::async_trait;
+use domain::DomainError;
+use shared::{BrokerSettings, SubscriptionSettings};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+// ============================================================================
+// Broker Selection
+// ============================================================================
+
+/// Both halves of the configured broker
+pub struct Broker {
+    pub publisher: Arc<dyn MessagePublisher>,
+    pub consumer: Arc<dyn MessageConsumer>,
+    /// From `broker.restart_delay_ms` and `max_restart_delay_secs`
+    pub backoff: RestartBackoff,
+}
+
+impl Broker {
+    fn new<B: MessagePublisher + MessageConsumer + 'static>(broker: Arc<B>, settings: &BrokerSettings) -> Self {
+        Self {
+            publisher: broker.clone(),
+            consumer: broker,
+            backoff: RestartBackoff {
+                initial: Duration::from_millis(settings.restart_delay_ms),
+                max: Duration::from_secs(settings.max_restart_delay_secs),
+            },
+        }
+    }
+
+    /// Feed `subscription` to `handler` in a supervised task (see
+    /// `application::messaging::spawn_consumer`)
+    pub fn spawn_consumer(&self, subscription: &str, handler: Arc<dyn MessageHandler>) -> JoinHandle<()> {
+        spawn_consumer(self.consumer.clone(), subscription.to_string(), handler, self.backoff)
+    }
+}
+
+/// Connect to the configured broker and declare its topology; `None` for
+/// the `none` backend
+pub async fn broker_from_settings(settings: &BrokerSettings) -> Result<Option<Broker>, DomainError> {
+    match settings.backend.as_str() {
+        "memory" => {
+            let broker = Arc::new(InMemoryBroker::new(settings.subscriptions.clone()));
+            Ok(Some(Broker::new(broker, settings)))
+        }
+        #[cfg(feature = "rabbitmq")]
+        "rabbitmq" => {
+            let broker = Arc::new(crate::rabbitmq::RabbitMqBroker::connect(settings).await?);
+            Ok(Some(Broker::new(broker, settings)))
+        }
+        #[cfg(feature = "kafka")]
+        "kafka" => {
+            let broker = Arc::new(crate::kafka::KafkaBroker::connect(settings).await?);
+            Ok(Some(Broker::new(broker, settings)))
+        }
+        #[cfg(not(feature = "rabbitmq"))]
+        "rabbitmq" => Err(missing_feature("rabbitmq")),
+        #[cfg(not(feature = "kafka"))]
+        "kafka" => Err(missing_feature("kafka")),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(not(all(feature = "rabbitmq", feature = "kafka")))]
+fn missing_feature(backend: &str) -> DomainError {
+    DomainError::validation(format!(
+        "broker.backend is '{0}', but this build leaves out the `{0}` feature (build with `--features {0}`)",
+        backend
+    ))
+}
+
+// ============================================================================
+// In-Memory Broker
+// ============================================================================
+
+/// Process-local broker with the semantics of the real ones: each
+/// subscription queues the messages whose topic matches one of its patterns
+/// until they are handled, and a message whose handler fails goes back to
+/// the front of the queue. Nothing survives a restart.
+pub struct InMemoryBroker {
+    subscriptions: Vec<(SubscriptionSettings, Arc<Queue>)>,
+}
+
+#[derive(Default)]
+struct Queue {
+    messages: Mutex<VecDeque<MessageEnvelope>>,
+    ready: Notify,
+}
+
+impl InMemoryBroker {
+    pub fn new(subscriptions: Vec<SubscriptionSettings>) -> Self {
+        Self {
+            subscriptions: subscriptions.into_iter().map(|s| (s, Arc::default())).collect(),
+        }
+    }
+
+    /// Messages queued for `subscription` and not yet handled
+    pub fn pending(&self, subscription: &str) -> usize {
+        self.queue(subscription).map_or(0, |queue| queue.messages.lock().unwrap().len())
+    }
+
+    fn queue(&self, subscription: &str) -> Option<Arc<Queue>> {
+        self.subscriptions
+            .iter()
+            .find(|(s, _)| s.name == subscription)
+            .map(|(_, queue)| queue.clone())
+    }
+}
+
+#[async_trait]
+impl MessagePublisher for InMemoryBroker {
+    async fn publish(&self, topic: &str, _key: Option<&str>, message: &MessageEnvelope) -> Result<(), ApplicationError> {
+        for (subscription, queue) in &self.subscriptions {
+            if subscription.topics.iter().any(|pattern| topic_matches(pattern, topic)) {
+                queue.messages.lock().unwrap().push_back(message.clone());
+                queue.ready.notify_one();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MessageConsumer for InMemoryBroker {
+    async fn consume(&self, subscription: &str, handler: Arc<dyn MessageHandler>) -> Result<(), ApplicationError> {
+        let queue = self
+            .queue(subscription)
+            .ok_or_else(|| DomainError::not_found("Subscription", subscription))?;
+        loop {
+            let notified = queue.ready.notified();
+            let next = queue.messages.lock().unwrap().pop_front();
+            let Some(message) = next else {
+                notified.await;
+                continue;
+            };
+            if let Err(e) = handler.handle(message.clone()).await {
+                queue.messages.lock().unwrap().push_front(message);
+                queue.ready.notify_one();
+                return Err(e);
+            }
+        }
+    }
+}
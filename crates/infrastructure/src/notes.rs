@@ -0,0 +1,184 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{DomainError, NoteVisibility, Page, PaginationParams, Repository, UserNote, UserNoteRepository};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::map_sqlx_error;
+
+// ============================================================================
+// User Note Repository
+// ============================================================================
+
+pub struct PostgresUserNoteRepository {
+    db: Database,
+}
+
+impl PostgresUserNoteRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+const NOTE_COLUMNS: &str = "id, user_id, author_id, body, visibility, created_at, updated_at";
+
+#[derive(sqlx::FromRow)]
+struct NoteRow {
+    id: Uuid,
+    user_id: Uuid,
+    author_id: Uuid,
+    body: String,
+    visibility: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<NoteRow> for UserNote {
+    fn from(row: NoteRow) -> Self {
+        Self {
+            id: row.id,
+            user_id: row.user_id,
+            author_id: row.author_id,
+            body: row.body,
+            // Unknown values are hidden from everyone but the author
+            visibility: NoteVisibility::parse(&row.visibility).unwrap_or(NoteVisibility::Private),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[async_trait]
+impl Repository<UserNote> for PostgresUserNoteRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserNote", operation = "find_by_id"))]
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<UserNote>, DomainError> {
+        let row = sqlx::query_as::<_, NoteRow>(&format!("SELECT {} FROM user_notes WHERE id = $1", NOTE_COLUMNS))
+            .bind(id)
+            .fetch_optional(&mut self.db.acquire_read().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "UserNote"))?;
+
+        Ok(row.map(Into::into))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserNote", operation = "find_all"))]
+    async fn find_all(&self, params: &PaginationParams) -> Result<Page<UserNote>, DomainError> {
+        params.validate()?;
+        let rows = sqlx::query_as::<_, NoteRow>(&format!(
+            "SELECT {} FROM user_notes ORDER BY created_at DESC LIMIT $1 OFFSET $2",
+            NOTE_COLUMNS
+        ))
+        .bind(params.limit() as i64)
+        .bind(params.offset() as i64)
+        .fetch_all(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "UserNote"))?;
+
+        let total = self.count().await?;
+        Ok(Page::new(rows.into_iter().map(Into::into).collect(), total, params))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserNote", operation = "create"))]
+    async fn create(&self, note: &UserNote) -> Result<UserNote, DomainError> {
+        let row = sqlx::query_as::<_, NoteRow>(&format!(
+            r#"
+            INSERT INTO user_notes (id, user_id, author_id, body, visibility, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            NOTE_COLUMNS
+        ))
+        .bind(note.id)
+        .bind(note.user_id)
+        .bind(note.author_id)
+        .bind(&note.body)
+        .bind(note.visibility.as_str())
+        .bind(note.created_at)
+        .bind(note.updated_at)
+        .fetch_one(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "UserNote"))?;
+
+        self.db.record_write().await;
+        Ok(row.into())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserNote", operation = "update"))]
+    async fn update(&self, note: &UserNote) -> Result<UserNote, DomainError> {
+        let row = sqlx::query_as::<_, NoteRow>(&format!(
+            r#"
+            UPDATE user_notes SET body = $2, visibility = $3, updated_at = now()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            NOTE_COLUMNS
+        ))
+        .bind(note.id)
+        .bind(&note.body)
+        .bind(note.visibility.as_str())
+        .fetch_optional(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "UserNote"))?
+        .ok_or_else(|| DomainError::not_found("UserNote", note.id.to_string()))?;
+
+        self.db.record_write().await;
+        Ok(row.into())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserNote", operation = "delete"))]
+    async fn delete(&self, id: Uuid) -> Result<bool, DomainError> {
+        let result = sqlx::query("DELETE FROM user_notes WHERE id = $1")
+            .bind(id)
+            .execute(&mut self.db.acquire().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "UserNote"))?;
+
+        self.db.record_write().await;
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserNote", operation = "count"))]
+    async fn count(&self) -> Result<u64, DomainError> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_notes")
+            .fetch_one(&mut self.db.acquire_read().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "UserNote"))?;
+
+        Ok(count as u64)
+    }
+}
+
+#[async_trait]
+impl UserNoteRepository for PostgresUserNoteRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserNote", operation = "find_for_user"))]
+    async fn find_for_user(&self, user_id: Uuid, reader_id: Uuid, params: &PaginationParams) -> Result<Page<UserNote>, DomainError> {
+        params.validate()?;
+        let rows = sqlx::query_as::<_, NoteRow>(&format!(
+            r#"
+            SELECT {} FROM user_notes
+            WHERE user_id = $1 AND (visibility = 'team' OR author_id = $2)
+            ORDER BY created_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+            NOTE_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(reader_id)
+        .bind(params.limit() as i64)
+        .bind(params.offset() as i64)
+        .fetch_all(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "UserNote"))?;
+
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM user_notes WHERE user_id = $1 AND (visibility = 'team' OR author_id = $2)",
+        )
+        .bind(user_id)
+        .bind(reader_id)
+        .fetch_one(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "UserNote"))?;
+
+        Ok(Page::new(rows.into_iter().map(Into::into).collect(), total as u64, params))
+    }
+}
@@ -0,0 +1,138 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{DomainError, Notification, NotificationRepository, Page, PaginationParams};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::map_sqlx_error;
+
+// ============================================================================
+// Notification Repository
+// ============================================================================
+
+pub struct PostgresNotificationRepository {
+    db: Database,
+}
+
+impl PostgresNotificationRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+const NOTIFICATION_COLUMNS: &str = "id, user_id, kind, title, body, read_at, created_at";
+
+#[derive(sqlx::FromRow)]
+struct NotificationRow {
+    id: Uuid,
+    user_id: Uuid,
+    kind: String,
+    title: String,
+    body: String,
+    read_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<NotificationRow> for Notification {
+    fn from(row: NotificationRow) -> Self {
+        Self {
+            id: row.id,
+            user_id: row.user_id,
+            kind: row.kind,
+            title: row.title,
+            body: row.body,
+            read_at: row.read_at,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationRepository for PostgresNotificationRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Notification", operation = "create"))]
+    async fn create(&self, notification: &Notification) -> Result<Notification, DomainError> {
+        let row = sqlx::query_as::<_, NotificationRow>(&format!(
+            r#"
+            INSERT INTO notifications (id, user_id, kind, title, body, read_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            NOTIFICATION_COLUMNS
+        ))
+        .bind(notification.id)
+        .bind(notification.user_id)
+        .bind(&notification.kind)
+        .bind(&notification.title)
+        .bind(&notification.body)
+        .bind(notification.read_at)
+        .bind(notification.created_at)
+        .fetch_one(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Notification"))?;
+
+        self.db.record_write().await;
+        Ok(row.into())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Notification", operation = "list_for_user"))]
+    async fn list_for_user(&self, user_id: Uuid, unread_only: bool, params: &PaginationParams) -> Result<Page<Notification>, DomainError> {
+        params.validate()?;
+        let rows = sqlx::query_as::<_, NotificationRow>(&format!(
+            r#"
+            SELECT {} FROM notifications
+            WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)
+            ORDER BY created_at DESC, id DESC
+            LIMIT $3 OFFSET $4
+            "#,
+            NOTIFICATION_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(unread_only)
+        .bind(params.limit() as i64)
+        .bind(params.offset() as i64)
+        .fetch_all(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Notification"))?;
+
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)")
+            .bind(user_id)
+            .bind(unread_only)
+            .fetch_one(&mut self.db.acquire_read().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Notification"))?;
+
+        Ok(Page::new(rows.into_iter().map(Into::into).collect(), total as u64, params))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Notification", operation = "unread_count"))]
+    async fn unread_count(&self, user_id: Uuid) -> Result<u64, DomainError> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM notifications WHERE user_id = $1 AND read_at IS NULL")
+            .bind(user_id)
+            .fetch_one(&mut self.db.acquire_read().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Notification"))?;
+
+        Ok(count as u64)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Notification", operation = "mark_read"))]
+    async fn mark_read(&self, user_id: Uuid, id: Uuid, at: DateTime<Utc>) -> Result<Option<Notification>, DomainError> {
+        let row = sqlx::query_as::<_, NotificationRow>(&format!(
+            r#"
+            UPDATE notifications SET read_at = COALESCE(read_at, $3)
+            WHERE id = $1 AND user_id = $2
+            RETURNING {}
+            "#,
+            NOTIFICATION_COLUMNS
+        ))
+        .bind(id)
+        .bind(user_id)
+        .bind(at)
+        .fetch_optional(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Notification"))?;
+
+        self.db.record_write().await;
+        Ok(row.map(Into::into))
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use application::oauth::{OAuthStateStore, PendingAuthorization};
+use application::ApplicationError;
+use async_trait::async_trait;
+#[cfg(feature = "redis")]
+use domain::DomainError;
+#[cfg(feature = "redis")]
+use redis::aio::ConnectionManager;
+
+// ============================================================================
+// Redis OAuth State Store
+// ============================================================================
+
+/// Pending authorizations under `oauth:state:<state>`, shared by every
+/// instance. `GETDEL` makes each state usable once even when two callbacks
+/// race.
+#[cfg(feature = "redis")]
+pub struct RedisOAuthStateStore {
+    redis: ConnectionManager,
+}
+
+#[cfg(feature = "redis")]
+impl RedisOAuthStateStore {
+    pub async fn connect(url: &str) -> Result<Self, DomainError> {
+        let client = redis::Client::open(url).map_err(redis_error)?;
+        let redis = client.get_connection_manager().await.map_err(redis_error)?;
+        Ok(Self { redis })
+    }
+}
+
+#[cfg(feature = "redis")]
+fn state_key(state: &str) -> String {
+    format!("oauth:state:{}", state)
+}
+
+#[cfg(feature = "redis")]
+fn redis_error(err: redis::RedisError) -> DomainError {
+    DomainError::internal(format!("Redis error: {}", err))
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl OAuthStateStore for RedisOAuthStateStore {
+    async fn save(&self, state: &str, pending: &PendingAuthorization, ttl: Duration) -> Result<(), ApplicationError> {
+        let payload = serde_json::to_string(pending).map_err(|e| DomainError::internal(e.to_string()))?;
+        redis::cmd("SET")
+            .arg(state_key(state))
+            .arg(payload)
+            .arg("EX")
+            .arg(ttl.as_secs().max(1))
+            .query_async::<_, ()>(&mut self.redis.clone())
+            .await
+            .map_err(redis_error)?;
+        Ok(())
+    }
+
+    async fn take(&self, state: &str) -> Result<Option<PendingAuthorization>, ApplicationError> {
+        let payload: Option<String> = redis::cmd("GETDEL")
+            .arg(state_key(state))
+            .query_async(&mut self.redis.clone())
+            .await
+            .map_err(redis_error)?;
+        Ok(payload.and_then(|p| serde_json::from_str(&p).ok()))
+    }
+}
+
+// ============================================================================
+// In-Memory OAuth State Store
+// ============================================================================
+
+/// Process-local pending authorizations for single-instance deployments
+#[derive(Default)]
+pub struct InMemoryOAuthStateStore {
+    states: Mutex<HashMap<String, (PendingAuthorization, Instant)>>,
+}
+
+impl InMemoryOAuthStateStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl OAuthStateStore for InMemoryOAuthStateStore {
+    async fn save(&self, state: &str, pending: &PendingAuthorization, ttl: Duration) -> Result<(), ApplicationError> {
+        let now = Instant::now();
+        let mut states = self.states.lock().unwrap();
+        // Abandoned attempts are never taken; drop them as we go
+        states.retain(|_, (_, expires)| *expires > now);
+        states.insert(state.to_string(), (pending.clone(), now + ttl));
+        Ok(())
+    }
+
+    async fn take(&self, state: &str) -> Result<Option<PendingAuthorization>, ApplicationError> {
+        let taken = self.states.lock().unwrap().remove(state);
+        Ok(taken
+            .filter(|(_, expires)| *expires > Instant::now())
+            .map(|(pending, _)| pending))
+    }
+}
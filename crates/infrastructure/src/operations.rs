@@ -0,0 +1,137 @@
+use application::operations::{Operation, OperationItem, OperationStatus, OperationStore};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use uuid::Uuid;
+
+use crate::db::{Database, DbConnection};
+
+// ============================================================================
+// Postgres Operation Store
+// ============================================================================
+
+/// Operations in the `operations` table, with their per-item results in a
+/// JSONB column
+pub struct PgOperationStore {
+    db: Database,
+}
+
+impl PgOperationStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn conn(&self) -> Result<DbConnection, ApplicationError> {
+        Ok(self.db.acquire().await?)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct OperationRow {
+    id: Uuid,
+    kind: String,
+    status: String,
+    created_by: Uuid,
+    tenant_id: Option<Uuid>,
+    total: Option<i64>,
+    items: serde_json::Value,
+    error: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<OperationRow> for Operation {
+    type Error = ApplicationError;
+
+    fn try_from(row: OperationRow) -> Result<Self, Self::Error> {
+        let items: Vec<OperationItem> = serde_json::from_value(row.items)
+            .map_err(|e| DomainError::internal(format!("Malformed operation items: {}", e)))?;
+        Ok(Self {
+            id: row.id,
+            kind: row.kind,
+            status: OperationStatus::parse(&row.status).unwrap_or(OperationStatus::Failed),
+            created_by: row.created_by,
+            tenant_id: row.tenant_id,
+            total: row.total.map(|total| total as u64),
+            items,
+            error: row.error,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("Operation store error: {}", err)).into()
+}
+
+fn items_json(operation: &Operation) -> Result<serde_json::Value, ApplicationError> {
+    serde_json::to_value(&operation.items)
+        .map_err(|e| DomainError::internal(format!("Failed to serialize operation items: {}", e)).into())
+}
+
+#[async_trait]
+impl OperationStore for PgOperationStore {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Operation", operation = "create"))]
+    async fn create(&self, operation: &Operation) -> Result<(), ApplicationError> {
+        sqlx::query(
+            r#"
+            INSERT INTO operations (id, kind, status, created_by, tenant_id, total, items, error, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+        )
+        .bind(operation.id)
+        .bind(&operation.kind)
+        .bind(operation.status.as_str())
+        .bind(operation.created_by)
+        .bind(operation.tenant_id)
+        .bind(operation.total.map(|total| total as i64))
+        .bind(items_json(operation)?)
+        .bind(&operation.error)
+        .bind(operation.created_at)
+        .bind(operation.updated_at)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Operation", operation = "get"))]
+    async fn get(&self, id: Uuid) -> Result<Option<Operation>, ApplicationError> {
+        // Read from the primary: callers poll for progress right after starting
+        let row = sqlx::query_as::<_, OperationRow>(
+            r#"
+            SELECT id, kind, status, created_by, tenant_id, total, items, error, created_at, updated_at
+            FROM operations
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_optional(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+
+        row.map(Operation::try_from).transpose()
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Operation", operation = "update"))]
+    async fn update(&self, operation: &Operation) -> Result<(), ApplicationError> {
+        sqlx::query(
+            r#"
+            UPDATE operations
+            SET status = $2, total = $3, items = $4, error = $5, updated_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(operation.id)
+        .bind(operation.status.as_str())
+        .bind(operation.total.map(|total| total as i64))
+        .bind(items_json(operation)?)
+        .bind(&operation.error)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,507 @@
+use application::organizations::{Invitation, InvitationStore};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{
+    DomainError, Membership, MembershipRepository, OrgRole, Organization, OrganizationRepository, Page,
+    PaginationParams, Repository,
+};
+use sqlx::Connection;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::map_sqlx_error;
+
+fn parse_role(role: &str) -> Result<OrgRole, DomainError> {
+    OrgRole::parse(role).ok_or_else(|| DomainError::internal(format!("Unknown organization role: {}", role)))
+}
+
+// ============================================================================
+// Organization Repository
+// ============================================================================
+
+/// Organizations in `organizations`
+pub struct PostgresOrganizationRepository {
+    db: Database,
+}
+
+impl PostgresOrganizationRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+const ORGANIZATION_COLUMNS: &str = "id, name, created_by, created_at, updated_at";
+
+#[derive(sqlx::FromRow)]
+struct OrganizationRow {
+    id: Uuid,
+    name: String,
+    created_by: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<OrganizationRow> for Organization {
+    fn from(row: OrganizationRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            created_by: row.created_by,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct OrganizationWithRoleRow {
+    #[sqlx(flatten)]
+    organization: OrganizationRow,
+    role: String,
+}
+
+#[async_trait]
+impl Repository<Organization> for PostgresOrganizationRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Organization", operation = "find_by_id"))]
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Organization>, DomainError> {
+        let row = sqlx::query_as::<_, OrganizationRow>(&format!(
+            "SELECT {} FROM organizations WHERE id = $1",
+            ORGANIZATION_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Organization"))?;
+
+        Ok(row.map(Into::into))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Organization", operation = "find_all"))]
+    async fn find_all(&self, params: &PaginationParams) -> Result<Page<Organization>, DomainError> {
+        params.validate()?;
+        let rows = sqlx::query_as::<_, OrganizationRow>(&format!(
+            "SELECT {} FROM organizations ORDER BY lower(name), id LIMIT $1 OFFSET $2",
+            ORGANIZATION_COLUMNS
+        ))
+        .bind(params.limit() as i64)
+        .bind(params.offset() as i64)
+        .fetch_all(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Organization"))?;
+
+        let total = self.count().await?;
+        Ok(Page::new(rows.into_iter().map(Into::into).collect(), total, params))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Organization", operation = "create"))]
+    async fn create(&self, organization: &Organization) -> Result<Organization, DomainError> {
+        let row = sqlx::query_as::<_, OrganizationRow>(&format!(
+            r#"
+            INSERT INTO organizations (id, name, created_by, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING {}
+            "#,
+            ORGANIZATION_COLUMNS
+        ))
+        .bind(organization.id)
+        .bind(&organization.name)
+        .bind(organization.created_by)
+        .bind(organization.created_at)
+        .bind(organization.updated_at)
+        .fetch_one(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Organization"))?;
+
+        self.db.record_write().await;
+        Ok(row.into())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Organization", operation = "update"))]
+    async fn update(&self, organization: &Organization) -> Result<Organization, DomainError> {
+        let row = sqlx::query_as::<_, OrganizationRow>(&format!(
+            "UPDATE organizations SET name = $2, updated_at = now() WHERE id = $1 RETURNING {}",
+            ORGANIZATION_COLUMNS
+        ))
+        .bind(organization.id)
+        .bind(&organization.name)
+        .fetch_optional(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Organization"))?
+        .ok_or_else(|| DomainError::not_found("Organization", organization.id.to_string()))?;
+
+        self.db.record_write().await;
+        Ok(row.into())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Organization", operation = "delete"))]
+    async fn delete(&self, id: Uuid) -> Result<bool, DomainError> {
+        let result = sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(id)
+            .execute(&mut self.db.acquire().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Organization"))?;
+
+        self.db.record_write().await;
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Organization", operation = "count"))]
+    async fn count(&self) -> Result<u64, DomainError> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM organizations")
+            .fetch_one(&mut self.db.acquire_read().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Organization"))?;
+
+        Ok(count as u64)
+    }
+}
+
+#[async_trait]
+impl OrganizationRepository for PostgresOrganizationRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Organization", operation = "create_with_owner"))]
+    async fn create_with_owner(&self, organization: &Organization, owner: &Membership) -> Result<Organization, DomainError> {
+        let map_err = |e| map_sqlx_error(e, "Organization");
+        let mut conn = self.db.acquire().await?;
+        let mut tx = conn.begin().await.map_err(map_err)?;
+
+        let row = sqlx::query_as::<_, OrganizationRow>(&format!(
+            r#"
+            INSERT INTO organizations (id, name, created_by, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING {}
+            "#,
+            ORGANIZATION_COLUMNS
+        ))
+        .bind(organization.id)
+        .bind(&organization.name)
+        .bind(organization.created_by)
+        .bind(organization.created_at)
+        .bind(organization.updated_at)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(map_err)?;
+
+        sqlx::query("INSERT INTO organization_members (organization_id, user_id, role, joined_at) VALUES ($1, $2, $3, $4)")
+            .bind(owner.organization_id)
+            .bind(owner.user_id)
+            .bind(owner.role.as_str())
+            .bind(owner.joined_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(map_err)?;
+
+        tx.commit().await.map_err(map_err)?;
+        self.db.record_write().await;
+        Ok(row.into())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Organization", operation = "list_for_user"))]
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<(Organization, OrgRole)>, DomainError> {
+        let rows = sqlx::query_as::<_, OrganizationWithRoleRow>(
+            r#"
+            SELECT o.id, o.name, o.created_by, o.created_at, o.updated_at, m.role
+            FROM organizations o
+            JOIN organization_members m ON m.organization_id = o.id
+            WHERE m.user_id = $1
+            ORDER BY lower(o.name), o.id
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Organization"))?;
+
+        rows.into_iter()
+            .map(|row| Ok((row.organization.into(), parse_role(&row.role)?)))
+            .collect()
+    }
+}
+
+// ============================================================================
+// Membership Repository
+// ============================================================================
+
+/// Memberships in `organization_members`
+pub struct PostgresMembershipRepository {
+    db: Database,
+}
+
+impl PostgresMembershipRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+const MEMBER_COLUMNS: &str = "organization_id, user_id, role, joined_at";
+
+#[derive(sqlx::FromRow)]
+struct MemberRow {
+    organization_id: Uuid,
+    user_id: Uuid,
+    role: String,
+    joined_at: DateTime<Utc>,
+}
+
+impl TryFrom<MemberRow> for Membership {
+    type Error = DomainError;
+
+    fn try_from(row: MemberRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            organization_id: row.organization_id,
+            user_id: row.user_id,
+            role: parse_role(&row.role)?,
+            joined_at: row.joined_at,
+        })
+    }
+}
+
+#[async_trait]
+impl MembershipRepository for PostgresMembershipRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Membership", operation = "find"))]
+    async fn find(&self, organization_id: Uuid, user_id: Uuid) -> Result<Option<Membership>, DomainError> {
+        sqlx::query_as::<_, MemberRow>(&format!(
+            "SELECT {} FROM organization_members WHERE organization_id = $1 AND user_id = $2",
+            MEMBER_COLUMNS
+        ))
+        .bind(organization_id)
+        .bind(user_id)
+        .fetch_optional(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Membership"))?
+        .map(Membership::try_from)
+        .transpose()
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Membership", operation = "list"))]
+    async fn list(&self, organization_id: Uuid, params: &PaginationParams) -> Result<Page<Membership>, DomainError> {
+        params.validate()?;
+        let mut conn = self.db.acquire_read().await?;
+        let rows = sqlx::query_as::<_, MemberRow>(&format!(
+            r#"
+            SELECT {} FROM organization_members WHERE organization_id = $1
+            ORDER BY CASE role WHEN 'owner' THEN 0 WHEN 'admin' THEN 1 ELSE 2 END, joined_at, user_id
+            LIMIT $2 OFFSET $3
+            "#,
+            MEMBER_COLUMNS
+        ))
+        .bind(organization_id)
+        .bind(params.limit() as i64)
+        .bind(params.offset() as i64)
+        .fetch_all(&mut conn)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Membership"))?;
+
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM organization_members WHERE organization_id = $1")
+            .bind(organization_id)
+            .fetch_one(&mut conn)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Membership"))?;
+
+        let members = rows.into_iter().map(Membership::try_from).collect::<Result<Vec<_>, _>>()?;
+        Ok(Page::new(members, total as u64, params))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Membership", operation = "add"))]
+    async fn add(&self, membership: &Membership) -> Result<Membership, DomainError> {
+        let row = sqlx::query_as::<_, MemberRow>(&format!(
+            "INSERT INTO organization_members ({}) VALUES ($1, $2, $3, $4) RETURNING {}",
+            MEMBER_COLUMNS, MEMBER_COLUMNS
+        ))
+        .bind(membership.organization_id)
+        .bind(membership.user_id)
+        .bind(membership.role.as_str())
+        .bind(membership.joined_at)
+        .fetch_one(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Membership"))?;
+
+        self.db.record_write().await;
+        row.try_into()
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Membership", operation = "set_role"))]
+    async fn set_role(&self, organization_id: Uuid, user_id: Uuid, role: OrgRole) -> Result<Option<Membership>, DomainError> {
+        let row = sqlx::query_as::<_, MemberRow>(&format!(
+            "UPDATE organization_members SET role = $3 WHERE organization_id = $1 AND user_id = $2 RETURNING {}",
+            MEMBER_COLUMNS
+        ))
+        .bind(organization_id)
+        .bind(user_id)
+        .bind(role.as_str())
+        .fetch_optional(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Membership"))?;
+
+        self.db.record_write().await;
+        row.map(Membership::try_from).transpose()
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Membership", operation = "remove"))]
+    async fn remove(&self, organization_id: Uuid, user_id: Uuid) -> Result<bool, DomainError> {
+        let result = sqlx::query("DELETE FROM organization_members WHERE organization_id = $1 AND user_id = $2")
+            .bind(organization_id)
+            .bind(user_id)
+            .execute(&mut self.db.acquire().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Membership"))?;
+
+        self.db.record_write().await;
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Membership", operation = "count_owners"))]
+    async fn count_owners(&self, organization_id: Uuid) -> Result<u64, DomainError> {
+        // Read from the primary: the last-owner check must see the latest changes
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM organization_members WHERE organization_id = $1 AND role = 'owner'",
+        )
+        .bind(organization_id)
+        .fetch_one(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Membership"))?;
+
+        Ok(count as u64)
+    }
+}
+
+// ============================================================================
+// Invitation Store
+// ============================================================================
+
+/// Invitations in `organization_invitations`
+pub struct PgInvitationStore {
+    db: Database,
+}
+
+impl PgInvitationStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+const INVITATION_COLUMNS: &str =
+    "id, organization_id, email, role, token_hash, invited_by, created_at, expires_at, accepted_at";
+
+#[derive(sqlx::FromRow)]
+struct InvitationRow {
+    id: Uuid,
+    organization_id: Uuid,
+    email: String,
+    role: String,
+    token_hash: String,
+    invited_by: Uuid,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    accepted_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<InvitationRow> for Invitation {
+    type Error = ApplicationError;
+
+    fn try_from(row: InvitationRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: row.id,
+            organization_id: row.organization_id,
+            email: row.email,
+            role: parse_role(&row.role)?,
+            token_hash: row.token_hash,
+            invited_by: row.invited_by,
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+            accepted_at: row.accepted_at,
+        })
+    }
+}
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    map_sqlx_error(err, "Invitation").into()
+}
+
+#[async_trait]
+impl InvitationStore for PgInvitationStore {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Invitation", operation = "create"))]
+    async fn create(&self, invitation: &Invitation) -> Result<(), ApplicationError> {
+        sqlx::query(&format!(
+            "INSERT INTO organization_invitations ({}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+            INVITATION_COLUMNS
+        ))
+        .bind(invitation.id)
+        .bind(invitation.organization_id)
+        .bind(&invitation.email)
+        .bind(invitation.role.as_str())
+        .bind(&invitation.token_hash)
+        .bind(invitation.invited_by)
+        .bind(invitation.created_at)
+        .bind(invitation.expires_at)
+        .bind(invitation.accepted_at)
+        .execute(&mut self.db.acquire().await?)
+        .await
+        .map_err(map_err)?;
+
+        self.db.record_write().await;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Invitation", operation = "find_by_token_hash"))]
+    async fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<Invitation>, ApplicationError> {
+        sqlx::query_as::<_, InvitationRow>(&format!(
+            "SELECT {} FROM organization_invitations WHERE token_hash = $1",
+            INVITATION_COLUMNS
+        ))
+        .bind(token_hash)
+        .fetch_optional(&mut self.db.acquire().await?)
+        .await
+        .map_err(map_err)?
+        .map(Invitation::try_from)
+        .transpose()
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Invitation", operation = "list_pending"))]
+    async fn list_pending(&self, organization_id: Uuid, now: DateTime<Utc>) -> Result<Vec<Invitation>, ApplicationError> {
+        sqlx::query_as::<_, InvitationRow>(&format!(
+            r#"
+            SELECT {} FROM organization_invitations
+            WHERE organization_id = $1 AND accepted_at IS NULL AND expires_at > $2
+            ORDER BY created_at DESC
+            "#,
+            INVITATION_COLUMNS
+        ))
+        .bind(organization_id)
+        .bind(now)
+        .fetch_all(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(map_err)?
+        .into_iter()
+        .map(Invitation::try_from)
+        .collect()
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Invitation", operation = "claim"))]
+    async fn claim(&self, id: Uuid, now: DateTime<Utc>) -> Result<bool, ApplicationError> {
+        let result = sqlx::query(
+            "UPDATE organization_invitations SET accepted_at = $2 WHERE id = $1 AND accepted_at IS NULL AND expires_at > $2",
+        )
+        .bind(id)
+        .bind(now)
+        .execute(&mut self.db.acquire().await?)
+        .await
+        .map_err(map_err)?;
+
+        self.db.record_write().await;
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Invitation", operation = "delete"))]
+    async fn delete(&self, organization_id: Uuid, id: Uuid) -> Result<bool, ApplicationError> {
+        let result = sqlx::query("DELETE FROM organization_invitations WHERE organization_id = $1 AND id = $2")
+            .bind(organization_id)
+            .bind(id)
+            .execute(&mut self.db.acquire().await?)
+            .await
+            .map_err(map_err)?;
+
+        self.db.record_write().await;
+        Ok(result.rows_affected() > 0)
+    }
+}
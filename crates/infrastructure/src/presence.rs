@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use application::presence::{Presence, PresenceChange, PresenceStatus, PresenceStore};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+#[cfg(feature = "redis")]
+use domain::DomainError;
+#[cfg(feature = "redis")]
+use futures_util::StreamExt;
+#[cfg(feature = "redis")]
+use redis::aio::ConnectionManager;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Pub/sub channel carrying `PresenceChange`s between instances
+#[cfg(feature = "redis")]
+const PRESENCE_CHANNEL: &str = "presence";
+
+/// Changes buffered per subscriber before it starts lagging
+const SUBSCRIBER_BUFFER: usize = 256;
+
+// ============================================================================
+// Redis Presence Store
+// ============================================================================
+
+/// Presence shared through Redis. Each user has a sorted set of connection
+/// ids scored by expiry (`presence:<user>:connections`) and a last-seen
+/// timestamp (`presence:<user>:last_seen`); changes travel over pub/sub.
+#[cfg(feature = "redis")]
+pub struct RedisPresenceStore {
+    redis: ConnectionManager,
+    changes: broadcast::Sender<PresenceChange>,
+}
+
+#[cfg(feature = "redis")]
+impl RedisPresenceStore {
+    /// Connect to `url` and start relaying changes published by any instance
+    pub async fn connect(url: &str) -> Result<Self, DomainError> {
+        let client = redis::Client::open(url).map_err(redis_error)?;
+        let redis = client.get_connection_manager().await.map_err(redis_error)?;
+        let (changes, _) = broadcast::channel(SUBSCRIBER_BUFFER);
+        spawn_listener(client, changes.clone());
+        Ok(Self { redis, changes })
+    }
+}
+
+/// Feed changes from the pub/sub channel to local subscribers, resubscribing
+/// after connection loss
+#[cfg(feature = "redis")]
+fn spawn_listener(client: redis::Client, changes: broadcast::Sender<PresenceChange>) {
+    tokio::spawn(async move {
+        loop {
+            match client.get_async_pubsub().await {
+                Ok(mut pubsub) => match pubsub.subscribe(PRESENCE_CHANNEL).await {
+                    Ok(()) => {
+                        let mut messages = pubsub.on_message();
+                        while let Some(message) = messages.next().await {
+                            let payload: String = message.get_payload().unwrap_or_default();
+                            match serde_json::from_str::<PresenceChange>(&payload) {
+                                // No receivers just means no socket is watching
+                                Ok(change) => {
+                                    let _ = changes.send(change);
+                                }
+                                Err(e) => tracing::warn!(error = %e, "ignoring malformed presence message"),
+                            }
+                        }
+                        tracing::warn!("presence subscription closed, reconnecting");
+                    }
+                    Err(e) => tracing::warn!(error = %e, "presence subscribe failed"),
+                },
+                Err(e) => tracing::warn!(error = %e, "presence pub/sub connection failed"),
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+        }
+    });
+}
+
+#[cfg(feature = "redis")]
+fn connections_key(user_id: Uuid) -> String {
+    format!("presence:{}:connections", user_id)
+}
+
+#[cfg(feature = "redis")]
+fn last_seen_key(user_id: Uuid) -> String {
+    format!("presence:{}:last_seen", user_id)
+}
+
+#[cfg(feature = "redis")]
+fn redis_error(err: redis::RedisError) -> DomainError {
+    DomainError::internal(format!("Redis error: {}", err))
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl PresenceStore for RedisPresenceStore {
+    async fn heartbeat(&self, user_id: Uuid, connection: &str, ttl: Duration) -> Result<bool, ApplicationError> {
+        let now = Utc::now().timestamp_millis();
+        let key = connections_key(user_id);
+        let (live,): (u64,) = redis::pipe()
+            .atomic()
+            .zrembyscore(&key, "-inf", now)
+            .ignore()
+            .zcard(&key)
+            .zadd(&key, connection, now + ttl.as_millis() as i64)
+            .ignore()
+            .pexpire(&key, ttl.as_millis() as i64)
+            .ignore()
+            .set(last_seen_key(user_id), now)
+            .ignore()
+            .query_async(&mut self.redis.clone())
+            .await
+            .map_err(redis_error)?;
+        Ok(live == 0)
+    }
+
+    async fn disconnect(&self, user_id: Uuid, connection: &str) -> Result<bool, ApplicationError> {
+        let now = Utc::now().timestamp_millis();
+        let key = connections_key(user_id);
+        let (removed, live): (u64, u64) = redis::pipe()
+            .atomic()
+            .zrem(&key, connection)
+            .zrembyscore(&key, "-inf", now)
+            .ignore()
+            .zcard(&key)
+            .set(last_seen_key(user_id), now)
+            .ignore()
+            .query_async(&mut self.redis.clone())
+            .await
+            .map_err(redis_error)?;
+        Ok(removed > 0 && live == 0)
+    }
+
+    async fn presence(&self, user_id: Uuid) -> Result<Presence, ApplicationError> {
+        let now = Utc::now().timestamp_millis();
+        let (live, last_seen): (u64, Option<i64>) = redis::pipe()
+            .zcount(connections_key(user_id), format!("({}", now), "+inf")
+            .get(last_seen_key(user_id))
+            .query_async(&mut self.redis.clone())
+            .await
+            .map_err(redis_error)?;
+        Ok(Presence {
+            user_id,
+            status: if live > 0 { PresenceStatus::Online } else { PresenceStatus::Offline },
+            last_seen: last_seen.and_then(DateTime::from_timestamp_millis),
+        })
+    }
+
+    async fn announce(&self, change: &PresenceChange) -> Result<(), ApplicationError> {
+        let payload = serde_json::to_string(change).map_err(|e| DomainError::internal(e.to_string()))?;
+        redis::cmd("PUBLISH")
+            .arg(PRESENCE_CHANNEL)
+            .arg(payload)
+            .query_async::<_, ()>(&mut self.redis.clone())
+            .await
+            .map_err(redis_error)?;
+        Ok(())
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<PresenceChange> {
+        self.changes.subscribe()
+    }
+}
+
+// ============================================================================
+// In-Memory Presence Store
+// ============================================================================
+
+/// Process-local presence for single-instance deployments (no `redis.url`)
+pub struct InMemoryPresenceStore {
+    users: Mutex<HashMap<Uuid, UserPresence>>,
+    changes: broadcast::Sender<PresenceChange>,
+}
+
+#[derive(Default)]
+struct UserPresence {
+    /// Connection id -> expiry
+    connections: HashMap<String, Instant>,
+    last_seen: Option<DateTime<Utc>>,
+}
+
+impl UserPresence {
+    fn prune(&mut self, now: Instant) {
+        self.connections.retain(|_, expires| *expires > now);
+    }
+}
+
+impl InMemoryPresenceStore {
+    pub fn new() -> Self {
+        let (changes, _) = broadcast::channel(SUBSCRIBER_BUFFER);
+        Self {
+            users: Mutex::new(HashMap::new()),
+            changes,
+        }
+    }
+}
+
+impl Default for InMemoryPresenceStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl PresenceStore for InMemoryPresenceStore {
+    async fn heartbeat(&self, user_id: Uuid, connection: &str, ttl: Duration) -> Result<bool, ApplicationError> {
+        let now = Instant::now();
+        let mut users = self.users.lock().unwrap();
+        let user = users.entry(user_id).or_default();
+        user.prune(now);
+        let came_online = user.connections.is_empty();
+        user.connections.insert(connection.to_string(), now + ttl);
+        user.last_seen = Some(Utc::now());
+        Ok(came_online)
+    }
+
+    async fn disconnect(&self, user_id: Uuid, connection: &str) -> Result<bool, ApplicationError> {
+        let mut users = self.users.lock().unwrap();
+        let Some(user) = users.get_mut(&user_id) else {
+            return Ok(false);
+        };
+        let removed = user.connections.remove(connection).is_some();
+        user.prune(Instant::now());
+        user.last_seen = Some(Utc::now());
+        Ok(removed && user.connections.is_empty())
+    }
+
+    async fn presence(&self, user_id: Uuid) -> Result<Presence, ApplicationError> {
+        let mut users = self.users.lock().unwrap();
+        let (live, last_seen) = match users.get_mut(&user_id) {
+            Some(user) => {
+                user.prune(Instant::now());
+                (!user.connections.is_empty(), user.last_seen)
+            }
+            None => (false, None),
+        };
+        Ok(Presence {
+            user_id,
+            status: if live { PresenceStatus::Online } else { PresenceStatus::Offline },
+            last_seen,
+        })
+    }
+
+    async fn announce(&self, change: &PresenceChange) -> Result<(), ApplicationError> {
+        let _ = self.changes.send(change.clone());
+        Ok(())
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<PresenceChange> {
+        self.changes.subscribe()
+    }
+}
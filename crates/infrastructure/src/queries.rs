@@ -0,0 +1,119 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{DomainError, Email, Page, PaginationParams, UserReadModel, UserStatus, UserView, Username};
+use uuid::Uuid;
+
+use crate::db::{page_total, Counted, Database, DbConnection};
+use crate::map_sqlx_error;
+
+// ============================================================================
+// Postgres User Read Model
+// ============================================================================
+
+/// User views read straight from the `users` table. The queries select only
+/// the displayed columns; password hashes never leave the database here.
+pub struct PgUserReadModel {
+    db: Database,
+}
+
+impl PgUserReadModel {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Replica when it satisfies read-your-writes
+    async fn conn(&self) -> Result<DbConnection, DomainError> {
+        self.db.acquire_read().await
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct UserViewRow {
+    id: Uuid,
+    username: String,
+    email: String,
+    avatar_url: Option<String>,
+    status: String,
+    password_reset_required: bool,
+    tenant_id: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    version: i64,
+}
+
+impl TryFrom<UserViewRow> for UserView {
+    type Error = DomainError;
+
+    fn try_from(row: UserViewRow) -> Result<Self, DomainError> {
+        let invalid = |e: DomainError| DomainError::internal(format!("Stored user {} is invalid: {}", row.id, e));
+        Ok(Self {
+            id: row.id,
+            username: Username::parse(&row.username).map_err(invalid)?,
+            email: Email::parse(&row.email).map_err(invalid)?,
+            avatar_url: row.avatar_url,
+            status: UserStatus::parse(&row.status).unwrap_or_default(),
+            password_reset_required: row.password_reset_required,
+            tenant_id: row.tenant_id,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            version: row.version,
+        })
+    }
+}
+
+#[async_trait]
+impl UserReadModel for PgUserReadModel {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserView", operation = "find_view"))]
+    async fn find_view(&self, id: Uuid, tenant: Option<Uuid>) -> Result<Option<UserView>, DomainError> {
+        let row = sqlx::query_as::<_, UserViewRow>(
+            r#"
+            SELECT id, username, email, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version
+            FROM users
+            WHERE id = $1 AND ($2::uuid IS NULL OR tenant_id = $2)
+            "#,
+        )
+        .bind(id)
+        .bind(tenant)
+        .fetch_optional(&mut self.conn().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "User"))?;
+
+        row.map(UserView::try_from).transpose()
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserView", operation = "list_views"))]
+    async fn list_views(&self, params: &PaginationParams, tenant: Option<Uuid>) -> Result<Page<UserView>, DomainError> {
+        let sql = format!(
+            r#"
+            SELECT id, username, email, avatar_url, status, password_reset_required, tenant_id, created_at, updated_at, version{}
+            FROM users
+            WHERE ($1::uuid IS NULL OR tenant_id = $1)
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            self.db.total_column()
+        );
+        let rows = sqlx::query_as::<_, Counted<UserViewRow>>(&sql)
+            .bind(tenant)
+            .bind(params.limit() as i64)
+            .bind(params.offset() as i64)
+            .fetch_all(&mut self.conn().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "User"))?;
+
+        let total = match page_total(&rows, params) {
+            Some(total) => total,
+            None => {
+                let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users WHERE ($1::uuid IS NULL OR tenant_id = $1)")
+                    .bind(tenant)
+                    .fetch_one(&mut self.conn().await?)
+                    .await
+                    .map_err(|e| map_sqlx_error(e, "User"))?;
+                total as u64
+            }
+        };
+
+        let views: Vec<UserView> = rows.into_iter().map(|r| UserView::try_from(r.row)).collect::<Result<_, _>>()?;
+        Ok(Page::new(views, total, params))
+    }
+}
@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use application::messaging::{MessageConsumer, MessageEnvelope, MessageHandler, MessagePublisher};
+use application::ApplicationError;
+use async_trait::async_trait;
+use domain::DomainError;
+use futures_util::StreamExt;
+use lapin::options::{
+    BasicConsumeOptions, BasicNackOptions, BasicPublishOptions, BasicQosOptions, BasicRejectOptions, ConfirmSelectOptions,
+    ExchangeDeclareOptions, QueueBindOptions, QueueDeclareOptions,
+};
+use lapin::types::FieldTable;
+use lapin::{BasicProperties, Channel, Connection, ConnectionProperties, ExchangeKind};
+use shared::{BrokerSettings, SubscriptionSettings};
+use tokio::sync::Mutex;
+
+/// Messages go through one durable topic exchange, with the topic as the
+/// routing key. Each subscription is a durable queue `<group>.<name>` bound
+/// to the exchange once per topic pattern, so instances of a service share
+/// its queues and each message is handled once per subscription.
+pub struct RabbitMqBroker {
+    url: String,
+    exchange: String,
+    group: String,
+    prefetch: u16,
+    subscriptions: Vec<SubscriptionSettings>,
+    connection: Mutex<Option<Connection>>,
+    /// Channel in confirm mode, shared by all publishes
+    publisher: Mutex<Option<Channel>>,
+}
+
+impl RabbitMqBroker {
+    /// Connect and declare the exchange and every subscription's queue
+    pub async fn connect(settings: &BrokerSettings) -> Result<Self, DomainError> {
+        let broker = Self {
+            url: settings.url.clone(),
+            exchange: settings.exchange.clone(),
+            group: settings.group.clone(),
+            prefetch: settings.prefetch,
+            subscriptions: settings.subscriptions.clone(),
+            connection: Mutex::new(None),
+            publisher: Mutex::new(None),
+        };
+        let channel = broker.channel().await?;
+        for subscription in &broker.subscriptions {
+            broker.declare(&channel, subscription).await?;
+        }
+        channel.close(200, "topology declared").await.map_err(amqp_error)?;
+        Ok(broker)
+    }
+
+    /// A new channel, reconnecting first when the connection was lost
+    async fn channel(&self) -> Result<Channel, DomainError> {
+        let mut connection = self.connection.lock().await;
+        if !connection.as_ref().is_some_and(|c| c.status().connected()) {
+            *connection = Some(Connection::connect(&self.url, ConnectionProperties::default()).await.map_err(amqp_error)?);
+        }
+        connection.as_ref().unwrap().create_channel().await.map_err(amqp_error)
+    }
+
+    /// Declare the exchange and `subscription`'s queue with its bindings;
+    /// redeclaring what exists is a no-op
+    async fn declare(&self, channel: &Channel, subscription: &SubscriptionSettings) -> Result<String, DomainError> {
+        let durable = ExchangeDeclareOptions {
+            durable: true,
+            ..Default::default()
+        };
+        channel
+            .exchange_declare(&self.exchange, ExchangeKind::Topic, durable, FieldTable::default())
+            .await
+            .map_err(amqp_error)?;
+
+        let queue = format!("{}.{}", self.group, subscription.name);
+        let durable = QueueDeclareOptions {
+            durable: true,
+            ..Default::default()
+        };
+        channel.queue_declare(&queue, durable, FieldTable::default()).await.map_err(amqp_error)?;
+        for pattern in &subscription.topics {
+            channel
+                .queue_bind(&queue, &self.exchange, pattern, QueueBindOptions::default(), FieldTable::default())
+                .await
+                .map_err(amqp_error)?;
+        }
+        Ok(queue)
+    }
+}
+
+fn amqp_error(err: lapin::Error) -> DomainError {
+    DomainError::unavailable(format!("RabbitMQ error: {}", err))
+}
+
+#[async_trait]
+impl MessagePublisher for RabbitMqBroker {
+    async fn publish(&self, topic: &str, _key: Option<&str>, message: &MessageEnvelope) -> Result<(), ApplicationError> {
+        let channel = {
+            let mut publisher = self.publisher.lock().await;
+            if !publisher.as_ref().is_some_and(|c| c.status().connected()) {
+                let channel = self.channel().await?;
+                channel.confirm_select(ConfirmSelectOptions::default()).await.map_err(amqp_error)?;
+                *publisher = Some(channel);
+            }
+            publisher.clone().unwrap()
+        };
+
+        let properties = BasicProperties::default()
+            .with_message_id(message.id.to_string().into())
+            .with_content_type("application/json".into())
+            // Persistent, so queued messages survive a broker restart
+            .with_delivery_mode(2);
+        let confirmation = channel
+            .basic_publish(&self.exchange, topic, BasicPublishOptions::default(), &message.encode(), properties)
+            .await
+            .map_err(amqp_error)?
+            .await
+            .map_err(amqp_error)?;
+        if confirmation.is_nack() {
+            return Err(DomainError::unavailable(format!("RabbitMQ refused message {}", message.id)).into());
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl MessageConsumer for RabbitMqBroker {
+    async fn consume(&self, subscription: &str, handler: Arc<dyn MessageHandler>) -> Result<(), ApplicationError> {
+        let settings = self
+            .subscriptions
+            .iter()
+            .find(|s| s.name == subscription)
+            .ok_or_else(|| DomainError::not_found("Subscription", subscription))?;
+        let channel = self.channel().await?;
+        // The queue may have been deleted since startup
+        let queue = self.declare(&channel, settings).await?;
+        channel
+            .basic_qos(self.prefetch, BasicQosOptions::default())
+            .await
+            .map_err(amqp_error)?;
+        let mut deliveries = channel
+            .basic_consume(&queue, "", BasicConsumeOptions::default(), FieldTable::default())
+            .await
+            .map_err(amqp_error)?;
+
+        while let Some(delivery) = deliveries.next().await {
+            let delivery = delivery.map_err(amqp_error)?;
+            let message = match MessageEnvelope::decode(&delivery.data) {
+                Ok(message) => message,
+                Err(e) => {
+                    tracing::warn!(subscription, "Dropping undecodable message: {}", e);
+                    delivery.reject(BasicRejectOptions { requeue: false }).await.map_err(amqp_error)?;
+                    continue;
+                }
+            };
+            if let Err(e) = handler.handle(message).await {
+                let requeue = BasicNackOptions {
+                    requeue: true,
+                    ..Default::default()
+                };
+                delivery.nack(requeue).await.map_err(amqp_error)?;
+                // Closing hands the prefetched messages back too
+                let _ = channel.close(200, "handler failed").await;
+                return Err(e);
+            }
+            delivery.ack(Default::default()).await.map_err(amqp_error)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use application::{split_rate_limit_key, RateLimitCounter, RateLimiter};
+
+// ============================================================================
+// In-Memory Rate Limiter
+// ============================================================================
+
+/// Fixed-window counters kept in process memory. Limits are per instance;
+/// swap in a shared store when running several replicas.
+#[derive(Default)]
+pub struct InMemoryRateLimiter {
+    windows: Mutex<HashMap<String, Window>>,
+}
+
+struct Window {
+    started: Instant,
+    count: u32,
+    limit: u32,
+    length: Duration,
+}
+
+impl Window {
+    fn is_open(&self, now: Instant) -> bool {
+        now.duration_since(self.started) < self.length
+    }
+}
+
+impl InMemoryRateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+fn is_subject(key: &str, subject: &str) -> bool {
+    split_rate_limit_key(key).is_some_and(|(_, s)| s == subject)
+}
+
+impl RateLimiter for InMemoryRateLimiter {
+    fn check(&self, key: &str, limit: u32, window: Duration) -> Result<(), Duration> {
+        let mut windows = self.windows.lock().unwrap();
+        let now = Instant::now();
+
+        // Keep the map bounded by dropping expired windows as we go
+        if windows.len() > 10_000 {
+            windows.retain(|_, w| w.is_open(now));
+        }
+
+        let w = windows.entry(key.to_string()).or_insert(Window {
+            started: now,
+            count: 0,
+            limit,
+            length: window,
+        });
+        w.limit = limit;
+        w.length = window;
+        if !w.is_open(now) {
+            w.started = now;
+            w.count = 0;
+        }
+        if w.count >= limit {
+            return Err(window.saturating_sub(now.duration_since(w.started)));
+        }
+        w.count += 1;
+        Ok(())
+    }
+
+    fn counters(&self, subject: &str) -> Vec<RateLimitCounter> {
+        let windows = self.windows.lock().unwrap();
+        let now = Instant::now();
+        let mut counters: Vec<RateLimitCounter> = windows
+            .iter()
+            .filter(|(key, w)| is_subject(key, subject) && w.is_open(now))
+            .map(|(key, w)| RateLimitCounter {
+                key: key.clone(),
+                count: w.count,
+                limit: w.limit,
+                resets_in: w.length.saturating_sub(now.duration_since(w.started)),
+            })
+            .collect();
+        counters.sort_by(|a, b| a.key.cmp(&b.key));
+        counters
+    }
+
+    fn reset(&self, subject: &str) -> usize {
+        let mut windows = self.windows.lock().unwrap();
+        let count = windows.len();
+        windows.retain(|key, _| !is_subject(key, subject));
+        count - windows.len()
+    }
+}
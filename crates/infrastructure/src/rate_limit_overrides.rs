@@ -0,0 +1,112 @@
+use application::rate_limits::{RateLimitOverride, RateLimitOverrideStore};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use uuid::Uuid;
+
+use crate::db::{Database, DbConnection};
+
+// ============================================================================
+// Postgres Rate Limit Override Store
+// ============================================================================
+
+/// Overrides in the `rate_limit_overrides` table. Expired rows are left in
+/// place as a record of what was granted.
+pub struct PgRateLimitOverrideStore {
+    db: Database,
+}
+
+impl PgRateLimitOverrideStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn conn(&self) -> Result<DbConnection, ApplicationError> {
+        Ok(self.db.acquire().await?)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct RateLimitOverrideRow {
+    id: Uuid,
+    subject: String,
+    scope: Option<String>,
+    hit_limit: i32,
+    reason: Option<String>,
+    created_by: Uuid,
+    created_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+
+impl From<RateLimitOverrideRow> for RateLimitOverride {
+    fn from(row: RateLimitOverrideRow) -> Self {
+        Self {
+            id: row.id,
+            subject: row.subject,
+            scope: row.scope,
+            limit: row.hit_limit.max(1) as u32,
+            reason: row.reason,
+            created_by: row.created_by,
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+        }
+    }
+}
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("Rate limit override store error: {}", err)).into()
+}
+
+#[async_trait]
+impl RateLimitOverrideStore for PgRateLimitOverrideStore {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "RateLimitOverride", operation = "create"))]
+    async fn create(&self, entry: &RateLimitOverride) -> Result<(), ApplicationError> {
+        sqlx::query(
+            r#"
+            INSERT INTO rate_limit_overrides (id, subject, scope, hit_limit, reason, created_by, created_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(entry.id)
+        .bind(&entry.subject)
+        .bind(&entry.scope)
+        .bind(i32::try_from(entry.limit).unwrap_or(i32::MAX))
+        .bind(&entry.reason)
+        .bind(entry.created_by)
+        .bind(entry.created_at)
+        .bind(entry.expires_at)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "RateLimitOverride", operation = "list_active"))]
+    async fn list_active(&self, now: DateTime<Utc>) -> Result<Vec<RateLimitOverride>, ApplicationError> {
+        let rows = sqlx::query_as::<_, RateLimitOverrideRow>(
+            r#"
+            SELECT id, subject, scope, hit_limit, reason, created_by, created_at, expires_at
+            FROM rate_limit_overrides
+            WHERE expires_at > $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(now)
+        .fetch_all(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+
+        Ok(rows.into_iter().map(RateLimitOverride::from).collect())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "RateLimitOverride", operation = "delete"))]
+    async fn delete(&self, id: Uuid) -> Result<bool, ApplicationError> {
+        let result = sqlx::query("DELETE FROM rate_limit_overrides WHERE id = $1")
+            .bind(id)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(map_err)?;
+        Ok(result.rows_affected() > 0)
+    }
+}
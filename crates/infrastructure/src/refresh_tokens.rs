@@ -0,0 +1,162 @@
+use application::refresh_tokens::{RefreshTokenRecord, RefreshTokenStore};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use uuid::Uuid;
+
+use crate::db::{Database, DbConnection};
+
+// ============================================================================
+// Postgres Refresh Token Store
+// ============================================================================
+
+/// Refresh tokens in the `refresh_tokens` table, looked up by hash
+pub struct PgRefreshTokenStore {
+    db: Database,
+}
+
+impl PgRefreshTokenStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn conn(&self) -> Result<DbConnection, ApplicationError> {
+        Ok(self.db.acquire().await?)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct RefreshTokenRow {
+    id: Uuid,
+    family_id: Uuid,
+    user_id: Uuid,
+    token_hash: String,
+    expires_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+    used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+    token_version: i64,
+}
+
+impl From<RefreshTokenRow> for RefreshTokenRecord {
+    fn from(row: RefreshTokenRow) -> Self {
+        Self {
+            id: row.id,
+            family_id: row.family_id,
+            user_id: row.user_id,
+            token_hash: row.token_hash,
+            expires_at: row.expires_at,
+            created_at: row.created_at,
+            used_at: row.used_at,
+            revoked_at: row.revoked_at,
+            token_version: row.token_version,
+        }
+    }
+}
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("Refresh token store error: {}", err)).into()
+}
+
+#[async_trait]
+impl RefreshTokenStore for PgRefreshTokenStore {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "RefreshToken", operation = "create"))]
+    async fn create(&self, token: &RefreshTokenRecord) -> Result<(), ApplicationError> {
+        sqlx::query(
+            r#"
+            INSERT INTO refresh_tokens (id, family_id, user_id, token_hash, expires_at, created_at, used_at, revoked_at, token_version)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(token.id)
+        .bind(token.family_id)
+        .bind(token.user_id)
+        .bind(&token.token_hash)
+        .bind(token.expires_at)
+        .bind(token.created_at)
+        .bind(token.used_at)
+        .bind(token.revoked_at)
+        .bind(token.token_version)
+        .execute(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "RefreshToken", operation = "find"))]
+    async fn find(&self, token_hash: &str) -> Result<Option<RefreshTokenRecord>, ApplicationError> {
+        let row = sqlx::query_as::<_, RefreshTokenRow>(
+            r#"
+            SELECT id, family_id, user_id, token_hash, expires_at, created_at, used_at, revoked_at, token_version
+            FROM refresh_tokens
+            WHERE token_hash = $1
+            "#,
+        )
+        .bind(token_hash)
+        .fetch_optional(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+
+        Ok(row.map(RefreshTokenRecord::from))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "RefreshToken", operation = "mark_used"))]
+    async fn mark_used(&self, id: Uuid) -> Result<bool, ApplicationError> {
+        let result = sqlx::query("UPDATE refresh_tokens SET used_at = now() WHERE id = $1 AND used_at IS NULL")
+            .bind(id)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(map_err)?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "RefreshToken", operation = "revoke_family"))]
+    async fn revoke_family(&self, family_id: Uuid) -> Result<u64, ApplicationError> {
+        let result =
+            sqlx::query("UPDATE refresh_tokens SET revoked_at = now() WHERE family_id = $1 AND revoked_at IS NULL")
+                .bind(family_id)
+                .execute(&mut self.conn().await?)
+                .await
+                .map_err(map_err)?;
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "RefreshToken", operation = "list_for_user"))]
+    async fn list_for_user(&self, user_id: Uuid) -> Result<Vec<RefreshTokenRecord>, ApplicationError> {
+        let rows = sqlx::query_as::<_, RefreshTokenRow>(
+            r#"
+            SELECT id, family_id, user_id, token_hash, expires_at, created_at, used_at, revoked_at, token_version
+            FROM refresh_tokens
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+
+        Ok(rows.into_iter().map(RefreshTokenRecord::from).collect())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "RefreshToken", operation = "prune"))]
+    async fn prune(&self, before: DateTime<Utc>) -> Result<u64, ApplicationError> {
+        let result = sqlx::query("DELETE FROM refresh_tokens WHERE expires_at < $1")
+            .bind(before)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(map_err)?;
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "RefreshToken", operation = "prune_revoked"))]
+    async fn prune_revoked(&self, before: DateTime<Utc>) -> Result<u64, ApplicationError> {
+        let result = sqlx::query("DELETE FROM refresh_tokens WHERE revoked_at < $1")
+            .bind(before)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(map_err)?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,78 @@
+use async_trait::async_trait;
+use domain::{Authorization, DomainError, RoleRepository};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::map_sqlx_error;
+
+// ============================================================================
+// Role Repository
+// ============================================================================
+
+/// Grants in `user_roles`, permissions in `role_permissions`
+pub struct PostgresRoleRepository {
+    db: Database,
+}
+
+impl PostgresRoleRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl RoleRepository for PostgresRoleRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Role", operation = "authorization"))]
+    async fn authorization(&self, user_id: Uuid, extra_roles: &[String]) -> Result<Authorization, DomainError> {
+        // Always the primary: a revoked role must not linger on a lagging replica
+        let rows: Vec<(String, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT r.role, rp.permission
+            FROM (
+                SELECT role FROM user_roles WHERE user_id = $1
+                UNION
+                SELECT unnest($2::text[])
+            ) r
+            LEFT JOIN role_permissions rp ON rp.role = r.role
+            "#,
+        )
+        .bind(user_id)
+        .bind(extra_roles)
+        .fetch_all(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Role"))?;
+
+        let mut authorization = Authorization::default();
+        for (role, permission) in rows {
+            authorization.roles.insert(role);
+            authorization.permissions.extend(permission);
+        }
+        Ok(authorization)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Role", operation = "grant"))]
+    async fn grant(&self, user_id: Uuid, role: &str) -> Result<bool, DomainError> {
+        let result = sqlx::query("INSERT INTO user_roles (user_id, role) VALUES ($1, $2) ON CONFLICT DO NOTHING")
+            .bind(user_id)
+            .bind(role)
+            .execute(&mut self.db.acquire().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Role"))?;
+
+        self.db.record_write().await;
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Role", operation = "revoke"))]
+    async fn revoke(&self, user_id: Uuid, role: &str) -> Result<bool, DomainError> {
+        let result = sqlx::query("DELETE FROM user_roles WHERE user_id = $1 AND role = $2")
+            .bind(user_id)
+            .bind(role)
+            .execute(&mut self.db.acquire().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Role"))?;
+
+        self.db.record_write().await;
+        Ok(result.rows_affected() > 0)
+    }
+}
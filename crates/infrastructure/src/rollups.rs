@@ -0,0 +1,81 @@
+use application::rollups::{DailyMetric, MetricRollupStore};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{Days, NaiveDate};
+use domain::DomainError;
+
+use crate::db::{Database, DbConnection};
+
+// ============================================================================
+// Postgres Metric Rollups
+// ============================================================================
+
+/// Rollups in `daily_metrics`, counted from `users` and `user_logins`.
+/// Sign-ins beyond each user's kept history are gone, so `logins` and
+/// `active_users` of old days shrink if those days are rolled up again.
+pub struct PgMetricRollupStore {
+    db: Database,
+}
+
+impl PgMetricRollupStore {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn conn(&self) -> Result<DbConnection, ApplicationError> {
+        Ok(self.db.acquire().await?)
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct MetricRow {
+    day: NaiveDate,
+    metric: String,
+    value: i64,
+}
+
+fn map_err(err: sqlx::Error) -> ApplicationError {
+    DomainError::internal(format!("Metric rollup error: {}", err)).into()
+}
+
+#[async_trait]
+impl MetricRollupStore for PgMetricRollupStore {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "DailyMetric", operation = "last_day"))]
+    async fn last_day(&self) -> Result<Option<NaiveDate>, ApplicationError> {
+        sqlx::query_scalar("SELECT max(day) FROM daily_metrics")
+            .fetch_one(&mut self.conn().await?)
+            .await
+            .map_err(map_err)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "DailyMetric", operation = "rollup"))]
+    async fn rollup(&self, day: NaiveDate) -> Result<Vec<DailyMetric>, ApplicationError> {
+        let start = day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let end = (day + Days::new(1)).and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+        let rows: Vec<MetricRow> = sqlx::query_as(
+            "INSERT INTO daily_metrics (day, metric, value) \
+             SELECT $1, metric, value FROM (VALUES \
+                 ('signups', (SELECT count(*) FROM users WHERE created_at >= $2 AND created_at < $3)), \
+                 ('logins', (SELECT count(*) FROM user_logins WHERE created_at >= $2 AND created_at < $3)), \
+                 ('active_users', (SELECT count(DISTINCT user_id) FROM user_logins WHERE created_at >= $2 AND created_at < $3)) \
+             ) AS counts (metric, value) \
+             ON CONFLICT (day, metric) DO UPDATE SET value = EXCLUDED.value, computed_at = now() \
+             RETURNING day, metric, value",
+        )
+        .bind(day)
+        .bind(start)
+        .bind(end)
+        .fetch_all(&mut self.conn().await?)
+        .await
+        .map_err(map_err)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DailyMetric {
+                day: row.day,
+                metric: row.metric,
+                value: row.value,
+            })
+            .collect())
+    }
+}
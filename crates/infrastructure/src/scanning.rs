@@ -0,0 +1,226 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use application::storage::{FileScanner, ScanVerdict};
+use application::ApplicationError;
+use async_trait::async_trait;
+use domain::DomainError;
+use shared::ScannerSettings;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+
+/// Upper bound for one scan, including connecting
+const SCAN_TIMEOUT: Duration = Duration::from_secs(30);
+
+// ============================================================================
+// Scanner Configuration
+// ============================================================================
+
+#[derive(Debug, Clone)]
+pub enum ScannerConfig {
+    /// Accept every file
+    None,
+    /// clamd's `INSTREAM` command over TCP
+    ClamAv { addr: String },
+    /// An ICAP (RFC 3507) `RESPMOD` service, e.g. `icap://scanner:1344/avscan`
+    Icap { url: String },
+}
+
+impl ScannerConfig {
+    /// From the `[scanner]` settings, already validated
+    pub fn from_settings(settings: &ScannerSettings) -> Self {
+        match (settings.backend.as_str(), &settings.icap_url) {
+            ("clamav", _) => Self::ClamAv {
+                addr: settings.clamav_addr.clone(),
+            },
+            ("icap", Some(url)) => Self::Icap { url: url.clone() },
+            _ => Self::None,
+        }
+    }
+
+    pub fn build(&self) -> Result<Arc<dyn FileScanner>, DomainError> {
+        Ok(match self {
+            Self::None => Arc::new(NoopFileScanner),
+            Self::ClamAv { addr } => Arc::new(ClamAvScanner::new(addr.clone())),
+            Self::Icap { url } => Arc::new(IcapScanner::new(url)?),
+        })
+    }
+}
+
+fn scan_error(context: &str, e: impl std::fmt::Display) -> ApplicationError {
+    DomainError::internal(format!("{}: {}", context, e)).into()
+}
+
+// ============================================================================
+// No-op Scanner
+// ============================================================================
+
+/// Treats every file as clean, for environments without a scanner
+pub struct NoopFileScanner;
+
+#[async_trait]
+impl FileScanner for NoopFileScanner {
+    async fn scan(&self, _bytes: &[u8]) -> Result<ScanVerdict, ApplicationError> {
+        Ok(ScanVerdict::Clean)
+    }
+}
+
+// ============================================================================
+// ClamAV
+// ============================================================================
+
+/// Streams files to clamd (`zINSTREAM`). clamd's `StreamMaxLength` must
+/// allow the largest upload, or scans fail.
+pub struct ClamAvScanner {
+    addr: String,
+}
+
+impl ClamAvScanner {
+    /// Chunk size of the INSTREAM framing
+    const CHUNK: usize = 64 * 1024;
+
+    pub fn new(addr: String) -> Self {
+        Self { addr }
+    }
+
+    async fn instream(&self, bytes: &[u8]) -> std::io::Result<String> {
+        let mut stream = TcpStream::connect(&self.addr).await?;
+        stream.write_all(b"zINSTREAM\0").await?;
+        for chunk in bytes.chunks(Self::CHUNK) {
+            stream.write_all(&(chunk.len() as u32).to_be_bytes()).await?;
+            stream.write_all(chunk).await?;
+        }
+        stream.write_all(&0u32.to_be_bytes()).await?;
+
+        let mut reply = Vec::new();
+        stream.read_to_end(&mut reply).await?;
+        Ok(String::from_utf8_lossy(&reply).trim_end_matches('\0').trim().to_string())
+    }
+}
+
+/// `stream: OK`, `stream: <signature> FOUND` or `... ERROR`
+fn parse_clamd_reply(reply: &str) -> Result<ScanVerdict, ApplicationError> {
+    let result = reply.strip_prefix("stream:").unwrap_or(reply).trim();
+    if result == "OK" {
+        return Ok(ScanVerdict::Clean);
+    }
+    match result.strip_suffix(" FOUND") {
+        Some(signature) => Ok(ScanVerdict::Infected {
+            signature: signature.trim().to_string(),
+        }),
+        None => Err(scan_error("clamd", reply)),
+    }
+}
+
+#[async_trait]
+impl FileScanner for ClamAvScanner {
+    async fn scan(&self, bytes: &[u8]) -> Result<ScanVerdict, ApplicationError> {
+        let reply = tokio::time::timeout(SCAN_TIMEOUT, self.instream(bytes))
+            .await
+            .map_err(|_| scan_error("clamd", "timed out"))?
+            .map_err(|e| scan_error("clamd", e))?;
+        parse_clamd_reply(&reply)
+    }
+}
+
+// ============================================================================
+// ICAP
+// ============================================================================
+
+/// Sends files as the body of a `RESPMOD` request. `204 No Content` means
+/// clean; a `200` (the service rewrote the response) means blocked.
+pub struct IcapScanner {
+    url: String,
+    /// `host:port`
+    addr: String,
+    host: String,
+}
+
+impl IcapScanner {
+    pub fn new(url: &str) -> Result<Self, DomainError> {
+        let rest = url
+            .strip_prefix("icap://")
+            .ok_or_else(|| DomainError::internal(format!("ICAP_URL must start with icap://, got '{}'", url)))?;
+        let authority = rest.split('/').next().unwrap_or(rest);
+        let host = authority.split(':').next().unwrap_or(authority).to_string();
+        let addr = if authority.contains(':') {
+            authority.to_string()
+        } else {
+            format!("{}:1344", authority)
+        };
+        Ok(Self {
+            url: url.to_string(),
+            addr,
+            host,
+        })
+    }
+
+    async fn respmod(&self, bytes: &[u8]) -> std::io::Result<(u16, Vec<(String, String)>)> {
+        let http_headers = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: {}\r\n\r\n",
+            bytes.len()
+        );
+        let icap_headers = format!(
+            "RESPMOD {} ICAP/1.0\r\nHost: {}\r\nAllow: 204\r\nConnection: close\r\nEncapsulated: res-hdr=0, res-body={}\r\n\r\n",
+            self.url,
+            self.host,
+            http_headers.len()
+        );
+
+        let mut stream = TcpStream::connect(&self.addr).await?;
+        stream.write_all(icap_headers.as_bytes()).await?;
+        stream.write_all(http_headers.as_bytes()).await?;
+        if !bytes.is_empty() {
+            stream.write_all(format!("{:x}\r\n", bytes.len()).as_bytes()).await?;
+            stream.write_all(bytes).await?;
+            stream.write_all(b"\r\n").await?;
+        }
+        stream.write_all(b"0\r\n\r\n").await?;
+
+        let mut reader = BufReader::new(stream);
+        let mut status_line = String::new();
+        reader.read_line(&mut status_line).await?;
+        let status = status_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| std::io::Error::other(format!("bad ICAP status line '{}'", status_line.trim())))?;
+
+        let mut headers = Vec::new();
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+            }
+        }
+        Ok((status, headers))
+    }
+}
+
+#[async_trait]
+impl FileScanner for IcapScanner {
+    async fn scan(&self, bytes: &[u8]) -> Result<ScanVerdict, ApplicationError> {
+        let (status, headers) = tokio::time::timeout(SCAN_TIMEOUT, self.respmod(bytes))
+            .await
+            .map_err(|_| scan_error("ICAP", "timed out"))?
+            .map_err(|e| scan_error("ICAP", e))?;
+
+        match status {
+            204 => Ok(ScanVerdict::Clean),
+            200 => {
+                // Vendors name the threat in one of these headers
+                let signature = headers
+                    .iter()
+                    .find(|(name, _)| matches!(name.as_str(), "x-infection-found" | "x-virus-id" | "x-violations-found"))
+                    .map(|(_, value)| value.clone())
+                    .unwrap_or_else(|| "unknown".to_string());
+                Ok(ScanVerdict::Infected { signature })
+            }
+            status => Err(scan_error("ICAP", format!("unexpected status {}", status))),
+        }
+    }
+}
+
@@ -0,0 +1,167 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{DomainError, Page, PaginationParams, Repository, UserFilter, UserSegment, UserSegmentRepository};
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::map_sqlx_error;
+
+// ============================================================================
+// User Segment Repository
+// ============================================================================
+
+/// Segments in `user_segments`, with the filter in a JSONB column
+pub struct PostgresUserSegmentRepository {
+    db: Database,
+}
+
+impl PostgresUserSegmentRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+const SEGMENT_COLUMNS: &str = "id, tenant_id, name, filter, created_by, created_at, updated_at";
+
+#[derive(sqlx::FromRow)]
+struct SegmentRow {
+    id: Uuid,
+    tenant_id: Uuid,
+    name: String,
+    filter: Json<UserFilter>,
+    created_by: Uuid,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<SegmentRow> for UserSegment {
+    fn from(row: SegmentRow) -> Self {
+        Self {
+            id: row.id,
+            tenant_id: row.tenant_id,
+            name: row.name,
+            filter: row.filter.0,
+            created_by: row.created_by,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[async_trait]
+impl Repository<UserSegment> for PostgresUserSegmentRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserSegment", operation = "find_by_id"))]
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<UserSegment>, DomainError> {
+        let row = sqlx::query_as::<_, SegmentRow>(&format!("SELECT {} FROM user_segments WHERE id = $1", SEGMENT_COLUMNS))
+            .bind(id)
+            .fetch_optional(&mut self.db.acquire_read().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "UserSegment"))?;
+
+        Ok(row.map(Into::into))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserSegment", operation = "find_all"))]
+    async fn find_all(&self, params: &PaginationParams) -> Result<Page<UserSegment>, DomainError> {
+        params.validate()?;
+        let rows = sqlx::query_as::<_, SegmentRow>(&format!(
+            "SELECT {} FROM user_segments ORDER BY name LIMIT $1 OFFSET $2",
+            SEGMENT_COLUMNS
+        ))
+        .bind(params.limit() as i64)
+        .bind(params.offset() as i64)
+        .fetch_all(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "UserSegment"))?;
+
+        let total = self.count().await?;
+        Ok(Page::new(rows.into_iter().map(Into::into).collect(), total, params))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserSegment", operation = "create"))]
+    async fn create(&self, segment: &UserSegment) -> Result<UserSegment, DomainError> {
+        let row = sqlx::query_as::<_, SegmentRow>(&format!(
+            r#"
+            INSERT INTO user_segments (id, tenant_id, name, filter, created_by, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING {}
+            "#,
+            SEGMENT_COLUMNS
+        ))
+        .bind(segment.id)
+        .bind(segment.tenant_id)
+        .bind(&segment.name)
+        .bind(Json(&segment.filter))
+        .bind(segment.created_by)
+        .bind(segment.created_at)
+        .bind(segment.updated_at)
+        .fetch_one(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "UserSegment"))?;
+
+        self.db.record_write().await;
+        Ok(row.into())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserSegment", operation = "update"))]
+    async fn update(&self, segment: &UserSegment) -> Result<UserSegment, DomainError> {
+        let row = sqlx::query_as::<_, SegmentRow>(&format!(
+            r#"
+            UPDATE user_segments SET name = $2, filter = $3, updated_at = now()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            SEGMENT_COLUMNS
+        ))
+        .bind(segment.id)
+        .bind(&segment.name)
+        .bind(Json(&segment.filter))
+        .fetch_optional(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "UserSegment"))?
+        .ok_or_else(|| DomainError::not_found("UserSegment", segment.id.to_string()))?;
+
+        self.db.record_write().await;
+        Ok(row.into())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserSegment", operation = "delete"))]
+    async fn delete(&self, id: Uuid) -> Result<bool, DomainError> {
+        let result = sqlx::query("DELETE FROM user_segments WHERE id = $1")
+            .bind(id)
+            .execute(&mut self.db.acquire().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "UserSegment"))?;
+
+        self.db.record_write().await;
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserSegment", operation = "count"))]
+    async fn count(&self) -> Result<u64, DomainError> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM user_segments")
+            .fetch_one(&mut self.db.acquire_read().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "UserSegment"))?;
+
+        Ok(count as u64)
+    }
+}
+
+#[async_trait]
+impl UserSegmentRepository for PostgresUserSegmentRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserSegment", operation = "list"))]
+    async fn list(&self, tenant_id: Uuid) -> Result<Vec<UserSegment>, DomainError> {
+        let rows = sqlx::query_as::<_, SegmentRow>(&format!(
+            "SELECT {} FROM user_segments WHERE tenant_id = $1 ORDER BY lower(name)",
+            SEGMENT_COLUMNS
+        ))
+        .bind(tenant_id)
+        .fetch_all(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "UserSegment"))?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{DomainError, NotificationPreferences, UserSettings, UserSettingsRepository};
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::map_sqlx_error;
+
+// ============================================================================
+// User Settings Repository
+// ============================================================================
+
+/// Settings in `user_settings`, with the notification preferences in a JSONB column
+pub struct PostgresUserSettingsRepository {
+    db: Database,
+}
+
+impl PostgresUserSettingsRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+const SETTINGS_COLUMNS: &str = "user_id, locale, timezone, notifications, created_at, updated_at";
+
+#[derive(sqlx::FromRow)]
+struct SettingsRow {
+    user_id: Uuid,
+    locale: String,
+    timezone: String,
+    notifications: Json<NotificationPreferences>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<SettingsRow> for UserSettings {
+    fn from(row: SettingsRow) -> Self {
+        Self {
+            user_id: row.user_id,
+            locale: row.locale,
+            timezone: row.timezone,
+            notifications: row.notifications.0,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[async_trait]
+impl UserSettingsRepository for PostgresUserSettingsRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserSettings", operation = "find"))]
+    async fn find(&self, user_id: Uuid) -> Result<Option<UserSettings>, DomainError> {
+        let row = sqlx::query_as::<_, SettingsRow>(&format!(
+            "SELECT {} FROM user_settings WHERE user_id = $1",
+            SETTINGS_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_optional(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "UserSettings"))?;
+
+        Ok(row.map(Into::into))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "UserSettings", operation = "upsert"))]
+    async fn upsert(&self, settings: &UserSettings) -> Result<UserSettings, DomainError> {
+        let row = sqlx::query_as::<_, SettingsRow>(&format!(
+            r#"
+            INSERT INTO user_settings (user_id, locale, timezone, notifications, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (user_id) DO UPDATE
+            SET locale = EXCLUDED.locale,
+                timezone = EXCLUDED.timezone,
+                notifications = EXCLUDED.notifications,
+                updated_at = EXCLUDED.updated_at
+            RETURNING {}
+            "#,
+            SETTINGS_COLUMNS
+        ))
+        .bind(settings.user_id)
+        .bind(&settings.locale)
+        .bind(&settings.timezone)
+        .bind(Json(&settings.notifications))
+        .bind(settings.created_at)
+        .bind(settings.updated_at)
+        .fetch_one(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "UserSettings"))?;
+
+        self.db.record_write().await;
+        Ok(row.into())
+    }
+}
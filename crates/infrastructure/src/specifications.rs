@@ -0,0 +1,70 @@
+use domain::{Queryable, Specification, UserCriterion};
+use sqlx::{Postgres, QueryBuilder};
+
+// ============================================================================
+// SQL Translation
+// ============================================================================
+
+/// A criterion as a condition on its entity's table
+pub trait SqlCriterion {
+    /// Append the condition to `query`, binding every value
+    fn push_sql(&self, query: &mut QueryBuilder<'_, Postgres>);
+}
+
+/// Append `spec` to `query` as one parenthesized condition, e.g. after `WHERE`
+pub fn push_specification<T>(query: &mut QueryBuilder<'_, Postgres>, spec: &Specification<T>)
+where
+    T: Queryable,
+    T::Criterion: SqlCriterion,
+{
+    match spec {
+        Specification::Where(criterion) => {
+            query.push("(");
+            criterion.push_sql(query);
+            query.push(")");
+        }
+        Specification::And(a, b) => push_pair(query, a, " AND ", b),
+        Specification::Or(a, b) => push_pair(query, a, " OR ", b),
+        Specification::Not(spec) => {
+            query.push("(NOT ");
+            push_specification(query, spec);
+            query.push(")");
+        }
+    }
+}
+
+fn push_pair<T>(query: &mut QueryBuilder<'_, Postgres>, a: &Specification<T>, operator: &str, b: &Specification<T>)
+where
+    T: Queryable,
+    T::Criterion: SqlCriterion,
+{
+    query.push("(");
+    push_specification(query, a);
+    query.push(operator);
+    push_specification(query, b);
+    query.push(")");
+}
+
+/// Conditions on the `users` table. Emails are stored normalized, with a
+/// single `@`.
+impl SqlCriterion for UserCriterion {
+    fn push_sql(&self, query: &mut QueryBuilder<'_, Postgres>) {
+        match self {
+            UserCriterion::ByEmailDomain(domain) => {
+                query.push("split_part(email, '@', 2) = ").push_bind(domain.trim().to_ascii_lowercase());
+            }
+            UserCriterion::CreatedAfter(at) => {
+                query.push("created_at > ").push_bind(*at);
+            }
+            UserCriterion::CreatedBefore(at) => {
+                query.push("created_at < ").push_bind(*at);
+            }
+            UserCriterion::HasStatus(status) => {
+                query.push("status = ").push_bind(status.as_str());
+            }
+            UserCriterion::InTenant(tenant_id) => {
+                query.push("tenant_id = ").push_bind(*tenant_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use application::storage::{FileStorage, StoredFile};
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::Utc;
+use domain::DomainError;
+use hmac::{Hmac, Mac};
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path as ObjectPath;
+use object_store::signer::Signer;
+use object_store::{Attribute, Attributes, ObjectStore, PutOptions};
+use sha2::Sha256;
+use shared::StorageSettings;
+
+// ============================================================================
+// Storage Configuration
+// ============================================================================
+
+#[derive(Debug, Clone)]
+pub enum StorageBackend {
+    /// Files under a local directory, served by the API at `/files`
+    Local { root: PathBuf },
+    /// S3 or a compatible store (MinIO, R2, ...); credentials from `AWS_*`
+    S3 { bucket: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    /// Base of public URLs; keys are appended after a `/`
+    pub public_url: String,
+    /// HMAC key for local presigned URLs
+    pub signing_secret: String,
+}
+
+impl StorageConfig {
+    /// From the `[storage]` settings, already validated
+    pub fn from_settings(settings: &StorageSettings) -> Self {
+        let backend = match (settings.backend.as_str(), &settings.s3_bucket) {
+            ("s3", Some(bucket)) => StorageBackend::S3 { bucket: bucket.clone() },
+            _ => StorageBackend::Local {
+                root: settings.local_dir.clone().into(),
+            },
+        };
+        let public_url = settings.public_url.clone().unwrap_or_else(|| match &backend {
+            StorageBackend::Local { .. } => "/files".to_string(),
+            StorageBackend::S3 { bucket } => format!("https://{}.s3.amazonaws.com", bucket),
+        });
+        let signing_secret = settings.signing_secret.clone().unwrap_or_else(|| {
+            tracing::warn!("storage.signing_secret not set; presigned file URLs will not survive a restart");
+            uuid::Uuid::new_v4().to_string()
+        });
+
+        Self {
+            backend,
+            public_url,
+            signing_secret,
+        }
+    }
+}
+
+fn join_url(base: &str, key: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), key)
+}
+
+fn storage_error(context: &str, e: impl std::fmt::Display) -> ApplicationError {
+    DomainError::internal(format!("{}: {}", context, e)).into()
+}
+
+// ============================================================================
+// Local Disk Adapter
+// ============================================================================
+
+/// Stores files under a root directory. The content type is derived from the
+/// key's extension. Presigned URLs point at the API's `/files` route and carry
+/// an HMAC over the key and expiry.
+pub struct LocalFileStorage {
+    root: PathBuf,
+    public_url: String,
+    signing_secret: String,
+}
+
+impl LocalFileStorage {
+    pub fn new(root: impl Into<PathBuf>, public_url: impl Into<String>, signing_secret: impl Into<String>) -> Self {
+        Self {
+            root: root.into(),
+            public_url: public_url.into(),
+            signing_secret: signing_secret.into(),
+        }
+    }
+
+    /// Resolve a key inside the root, rejecting anything that could escape it
+    fn path(&self, key: &str) -> Result<PathBuf, ApplicationError> {
+        let relative = Path::new(key);
+        let safe = !key.is_empty()
+            && !key.contains('\\')
+            && relative.components().all(|c| matches!(c, Component::Normal(_)));
+        if !safe {
+            return Err(DomainError::validation(format!("Invalid storage key '{}'", key)).into());
+        }
+        Ok(self.root.join(relative))
+    }
+
+    /// HMAC over `"{key}\n{expires}"`
+    fn mac(&self, key: &str, expires: i64) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.signing_secret.as_bytes()).expect("HMAC accepts any key length");
+        mac.update(key.as_bytes());
+        mac.update(b"\n");
+        mac.update(expires.to_string().as_bytes());
+        mac
+    }
+}
+
+fn content_type_for(key: &str) -> &'static str {
+    match key.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase()).as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("json") => "application/json",
+        Some("ndjson") => "application/x-ndjson",
+        Some("csv") => "text/csv",
+        Some("txt") => "text/plain",
+        Some("zip") => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+#[async_trait]
+impl FileStorage for LocalFileStorage {
+    async fn put(&self, key: &str, bytes: Vec<u8>, _content_type: &str) -> Result<(), ApplicationError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| storage_error("Failed to create storage directory", e))?;
+        }
+        tokio::fs::write(&path, bytes)
+            .await
+            .map_err(|e| storage_error("Failed to write file", e))
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<StoredFile>, ApplicationError> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(bytes) => Ok(Some(StoredFile {
+                bytes,
+                content_type: content_type_for(key).to_string(),
+            })),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(storage_error("Failed to read file", e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), ApplicationError> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(storage_error("Failed to delete file", e)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn presign(&self, key: &str, expires_in: Duration) -> Result<String, ApplicationError> {
+        self.path(key)?;
+        let expires = Utc::now().timestamp() + expires_in.as_secs() as i64;
+        Ok(format!(
+            "{}?expires={}&signature={}",
+            join_url(&self.public_url, key),
+            expires,
+            hex::encode(self.mac(key, expires).finalize().into_bytes())
+        ))
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        join_url(&self.public_url, key)
+    }
+
+    fn verify_presigned(&self, key: &str, expires: i64, signature: &str) -> bool {
+        let Ok(signature) = hex::decode(signature) else {
+            return false;
+        };
+        expires >= Utc::now().timestamp() && self.mac(key, expires).verify_slice(&signature).is_ok()
+    }
+}
+
+// ============================================================================
+// S3 Adapter
+// ============================================================================
+
+/// Stores files in an S3-compatible bucket. Public keys are read straight from
+/// the bucket (grant public read on `public/*`), private ones via presigned GETs.
+pub struct S3FileStorage {
+    store: AmazonS3,
+    public_url: String,
+}
+
+impl S3FileStorage {
+    pub fn new(bucket: impl Into<String>, public_url: impl Into<String>) -> Result<Self, DomainError> {
+        let store = AmazonS3Builder::from_env()
+            .with_bucket_name(bucket)
+            .build()
+            .map_err(|e| DomainError::internal(format!("Invalid S3 configuration: {}", e)))?;
+
+        Ok(Self {
+            store,
+            public_url: public_url.into(),
+        })
+    }
+}
+
+#[async_trait]
+impl FileStorage for S3FileStorage {
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<(), ApplicationError> {
+        let mut attributes = Attributes::new();
+        attributes.insert(Attribute::ContentType, content_type.to_string().into());
+        let options = PutOptions {
+            attributes,
+            ..Default::default()
+        };
+
+        self.store
+            .put_opts(&ObjectPath::from(key), bytes.into(), options)
+            .await
+            .map_err(|e| storage_error("S3 upload failed", e))?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<StoredFile>, ApplicationError> {
+        let result = match self.store.get(&ObjectPath::from(key)).await {
+            Ok(result) => result,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => return Err(storage_error("S3 download failed", e)),
+        };
+        let content_type = result
+            .attributes
+            .get(&Attribute::ContentType)
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| content_type_for(key).to_string());
+        let bytes = result.bytes().await.map_err(|e| storage_error("S3 download failed", e))?;
+
+        Ok(Some(StoredFile {
+            bytes: bytes.to_vec(),
+            content_type,
+        }))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), ApplicationError> {
+        match self.store.delete(&ObjectPath::from(key)).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(storage_error("S3 delete failed", e)),
+        }
+    }
+
+    async fn presign(&self, key: &str, expires_in: Duration) -> Result<String, ApplicationError> {
+        let url = self
+            .store
+            .signed_url(reqwest::Method::GET, &ObjectPath::from(key), expires_in)
+            .await
+            .map_err(|e| storage_error("S3 presign failed", e))?;
+        Ok(url.to_string())
+    }
+
+    fn public_url(&self, key: &str) -> String {
+        join_url(&self.public_url, key)
+    }
+}
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use domain::{SupportTicket, SupportTicketRepository};
+use uuid::Uuid;
+
+use crate::macros::pg_repository;
+
+// ============================================================================
+// Support Ticket Repository
+// ============================================================================
+
+pg_repository! {
+    pub struct PostgresSupportTicketRepository for SupportTicket in "support_tickets" order by "created_at DESC" {
+        id: Uuid,
+        #[immutable] user_id: Option<Uuid>,
+        name: String,
+        email: String,
+        subject: String,
+        message: String,
+        #[immutable] created_at: DateTime<Utc>,
+    }
+}
+
+impl SupportTicketRepository for PostgresSupportTicketRepository {}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{DomainError, Tag, TagRepository};
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::map_sqlx_error;
+
+// ============================================================================
+// Tag Repository
+// ============================================================================
+
+/// Tags in `tags`, attached to entities through `taggings`
+pub struct PostgresTagRepository {
+    db: Database,
+}
+
+impl PostgresTagRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct TagRow {
+    id: Uuid,
+    tenant_id: Uuid,
+    name: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<TagRow> for Tag {
+    fn from(row: TagRow) -> Self {
+        Self {
+            id: row.id,
+            tenant_id: row.tenant_id,
+            name: row.name,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[async_trait]
+impl TagRepository for PostgresTagRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Tag", operation = "list"))]
+    async fn list(&self, tenant_id: Uuid) -> Result<Vec<Tag>, DomainError> {
+        let rows = sqlx::query_as::<_, TagRow>(
+            "SELECT id, tenant_id, name, created_at FROM tags WHERE tenant_id = $1 ORDER BY name",
+        )
+        .bind(tenant_id)
+        .fetch_all(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Tag"))?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Tag", operation = "find_or_create"))]
+    async fn find_or_create(&self, tenant_id: Uuid, name: &str) -> Result<Tag, DomainError> {
+        let tag = Tag::new(tenant_id, name)?;
+        // The no-op update makes RETURNING yield the existing row on conflict
+        let row = sqlx::query_as::<_, TagRow>(
+            r#"
+            INSERT INTO tags (id, tenant_id, name, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (tenant_id, name) DO UPDATE SET name = EXCLUDED.name
+            RETURNING id, tenant_id, name, created_at
+            "#,
+        )
+        .bind(tag.id)
+        .bind(tag.tenant_id)
+        .bind(&tag.name)
+        .bind(tag.created_at)
+        .fetch_one(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Tag"))?;
+
+        self.db.record_write().await;
+        Ok(row.into())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Tag", operation = "attach"))]
+    async fn attach(&self, tag_id: Uuid, entity_type: &str, entity_id: Uuid) -> Result<bool, DomainError> {
+        let result = sqlx::query(
+            "INSERT INTO taggings (tag_id, entity_type, entity_id) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+        )
+        .bind(tag_id)
+        .bind(entity_type)
+        .bind(entity_id)
+        .execute(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Tagging"))?;
+
+        self.db.record_write().await;
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Tag", operation = "detach"))]
+    async fn detach(&self, tenant_id: Uuid, name: &str, entity_type: &str, entity_id: Uuid) -> Result<bool, DomainError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM taggings tg
+            USING tags t
+            WHERE tg.tag_id = t.id AND t.tenant_id = $1 AND t.name = $2
+              AND tg.entity_type = $3 AND tg.entity_id = $4
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(name)
+        .bind(entity_type)
+        .bind(entity_id)
+        .execute(&mut self.db.acquire().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Tagging"))?;
+
+        self.db.record_write().await;
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Tag", operation = "tags_of"))]
+    async fn tags_of(&self, entity_type: &str, entity_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<Tag>>, DomainError> {
+        let rows: Vec<(Uuid, Uuid, Uuid, String, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            SELECT tg.entity_id, t.id, t.tenant_id, t.name, t.created_at
+            FROM taggings tg
+            JOIN tags t ON t.id = tg.tag_id
+            WHERE tg.entity_type = $1 AND tg.entity_id = ANY($2)
+            ORDER BY t.name
+            "#,
+        )
+        .bind(entity_type)
+        .bind(entity_ids)
+        .fetch_all(&mut self.db.acquire_read().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Tagging"))?;
+
+        let mut tags: HashMap<Uuid, Vec<Tag>> = HashMap::new();
+        for (entity_id, id, tenant_id, name, created_at) in rows {
+            tags.entry(entity_id).or_default().push(Tag {
+                id,
+                tenant_id,
+                name,
+                created_at,
+            });
+        }
+        Ok(tags)
+    }
+}
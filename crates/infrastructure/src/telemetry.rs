@@ -0,0 +1,346 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::net::UdpSocket;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use domain::DomainError;
+use serde_json::{json, Value};
+use shared::telemetry::{Labels, NoopTelemetry, Telemetry};
+use shared::TelemetrySettings;
+
+/// Histogram bucket upper bounds, suited to durations in seconds
+pub const HISTOGRAM_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// The backend chosen by `telemetry.backend`
+pub struct TelemetryBackend {
+    pub telemetry: Arc<dyn Telemetry>,
+    /// Set for the `prometheus` backend, so the caller can serve `/metrics`
+    pub prometheus: Option<Arc<PrometheusTelemetry>>,
+}
+
+/// Build the configured backend; OTLP export, when configured, is already
+/// running when this returns
+pub fn telemetry_from_settings(settings: &TelemetrySettings, service_name: &str) -> Result<TelemetryBackend, DomainError> {
+    match settings.backend.as_str() {
+        "prometheus" => {
+            let registry = Arc::new(PrometheusTelemetry::new(&settings.prefix));
+            if let Some(endpoint) = &settings.otlp_endpoint {
+                registry.clone().spawn_otlp_export(
+                    endpoint.clone(),
+                    service_name.to_string(),
+                    Duration::from_secs(settings.otlp_interval_secs),
+                );
+            }
+            Ok(TelemetryBackend {
+                telemetry: registry.clone(),
+                prometheus: Some(registry),
+            })
+        }
+        "statsd" => Ok(TelemetryBackend {
+            telemetry: Arc::new(StatsdTelemetry::new(&settings.statsd_addr, &settings.prefix)?),
+            prometheus: None,
+        }),
+        _ => Ok(TelemetryBackend {
+            telemetry: Arc::new(NoopTelemetry),
+            prometheus: None,
+        }),
+    }
+}
+
+// ============================================================================
+// Prometheus / OTLP
+// ============================================================================
+
+/// Metric name and sorted labels
+type SeriesKey = (String, Vec<(String, String)>);
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    /// Observations per bucket of `HISTOGRAM_BUCKETS`, plus one for larger values
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Default)]
+struct Registry {
+    counters: BTreeMap<SeriesKey, u64>,
+    gauges: BTreeMap<SeriesKey, f64>,
+    histograms: BTreeMap<SeriesKey, Histogram>,
+}
+
+/// Keeps every series in memory, rendered in the Prometheus text format for
+/// scraping and optionally pushed to an OTLP/HTTP collector as JSON
+pub struct PrometheusTelemetry {
+    prefix: String,
+    started_at: DateTime<Utc>,
+    registry: Mutex<Registry>,
+}
+
+fn series(name: &str, labels: Labels<'_>) -> SeriesKey {
+    let mut labels: Vec<(String, String)> = labels.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+    labels.sort();
+    (name.to_string(), labels)
+}
+
+impl PrometheusTelemetry {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            started_at: Utc::now(),
+            registry: Mutex::new(Registry::default()),
+        }
+    }
+
+    fn full_name(&self, name: &str) -> String {
+        if self.prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}_{}", self.prefix, name)
+        }
+    }
+
+    /// Every series in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let registry = self.registry.lock().unwrap();
+        let mut out = String::new();
+        let mut declared = None;
+        let mut declare = |out: &mut String, name: &str, kind: &str| {
+            if declared.as_deref() != Some(name) {
+                let _ = writeln!(out, "# TYPE {} {}", name, kind);
+                declared = Some(name.to_string());
+            }
+        };
+
+        for ((name, labels), value) in &registry.counters {
+            let name = self.full_name(name);
+            declare(&mut out, &name, "counter");
+            let _ = writeln!(out, "{}{} {}", name, label_set(labels, None), value);
+        }
+        for ((name, labels), value) in &registry.gauges {
+            let name = self.full_name(name);
+            declare(&mut out, &name, "gauge");
+            let _ = writeln!(out, "{}{} {}", name, label_set(labels, None), value);
+        }
+        for ((name, labels), histogram) in &registry.histograms {
+            let name = self.full_name(name);
+            declare(&mut out, &name, "histogram");
+            let mut cumulative = 0;
+            for (i, bound) in HISTOGRAM_BUCKETS.iter().enumerate() {
+                cumulative += histogram.buckets[i];
+                let le = bound.to_string();
+                let _ = writeln!(out, "{}_bucket{} {}", name, label_set(labels, Some(&le)), cumulative);
+            }
+            let _ = writeln!(out, "{}_bucket{} {}", name, label_set(labels, Some("+Inf")), histogram.count);
+            let _ = writeln!(out, "{}_sum{} {}", name, label_set(labels, None), histogram.sum);
+            let _ = writeln!(out, "{}_count{} {}", name, label_set(labels, None), histogram.count);
+        }
+        out
+    }
+
+    /// Every series as an OTLP `ExportMetricsServiceRequest` (JSON encoding),
+    /// with cumulative temporality
+    pub fn otlp_request(&self, service_name: &str) -> Value {
+        let registry = self.registry.lock().unwrap();
+        let start = unix_nanos(self.started_at);
+        let now = unix_nanos(Utc::now());
+        let mut metrics: BTreeMap<String, Value> = BTreeMap::new();
+        let mut point = |name: &str, kind: &str, data_point: Value| {
+            let metric = metrics.entry(name.to_string()).or_insert_with(|| {
+                let data = match kind {
+                    "sum" => json!({ "dataPoints": [], "aggregationTemporality": 2, "isMonotonic": true }),
+                    "histogram" => json!({ "dataPoints": [], "aggregationTemporality": 2 }),
+                    _ => json!({ "dataPoints": [] }),
+                };
+                json!({ "name": self.full_name(name), kind: data })
+            });
+            if let Some(points) = metric[kind]["dataPoints"].as_array_mut() {
+                points.push(data_point);
+            }
+        };
+
+        for ((name, labels), value) in &registry.counters {
+            point(
+                name,
+                "sum",
+                json!({
+                    "attributes": otlp_attributes(labels),
+                    "asInt": value.to_string(),
+                    "startTimeUnixNano": start,
+                    "timeUnixNano": now,
+                }),
+            );
+        }
+        for ((name, labels), value) in &registry.gauges {
+            point(
+                name,
+                "gauge",
+                json!({ "attributes": otlp_attributes(labels), "asDouble": value, "timeUnixNano": now }),
+            );
+        }
+        for ((name, labels), histogram) in &registry.histograms {
+            point(
+                name,
+                "histogram",
+                json!({
+                    "attributes": otlp_attributes(labels),
+                    "count": histogram.count.to_string(),
+                    "sum": histogram.sum,
+                    "bucketCounts": histogram.buckets.iter().map(u64::to_string).collect::<Vec<_>>(),
+                    "explicitBounds": HISTOGRAM_BUCKETS,
+                    "startTimeUnixNano": start,
+                    "timeUnixNano": now,
+                }),
+            );
+        }
+
+        json!({
+            "resourceMetrics": [{
+                "resource": { "attributes": [{ "key": "service.name", "value": { "stringValue": service_name } }] },
+                "scopeMetrics": [{
+                    "scope": { "name": env!("CARGO_PKG_NAME"), "version": env!("CARGO_PKG_VERSION") },
+                    "metrics": metrics.into_values().collect::<Vec<_>>(),
+                }],
+            }],
+        })
+    }
+
+    /// POST `otlp_request` to `endpoint` every `interval`; failures are logged
+    /// and the next push carries the cumulative values again
+    pub fn spawn_otlp_export(self: Arc<Self>, endpoint: String, service_name: String, interval: Duration) {
+        let client = reqwest::Client::new();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let body = self.otlp_request(&service_name).to_string();
+                let sent = client
+                    .post(&endpoint)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(body)
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status());
+                if let Err(e) = sent {
+                    tracing::warn!(endpoint = %endpoint, "OTLP metrics export failed: {}", e);
+                }
+            }
+        });
+    }
+}
+
+impl Telemetry for PrometheusTelemetry {
+    fn increment(&self, name: &str, value: u64, labels: Labels<'_>) {
+        *self.registry.lock().unwrap().counters.entry(series(name, labels)).or_default() += value;
+    }
+
+    fn gauge(&self, name: &str, value: f64, labels: Labels<'_>) {
+        self.registry.lock().unwrap().gauges.insert(series(name, labels), value);
+    }
+
+    fn observe(&self, name: &str, value: f64, labels: Labels<'_>) {
+        let mut registry = self.registry.lock().unwrap();
+        let histogram = registry.histograms.entry(series(name, labels)).or_insert_with(|| Histogram {
+            buckets: vec![0; HISTOGRAM_BUCKETS.len() + 1],
+            ..Default::default()
+        });
+        let bucket = HISTOGRAM_BUCKETS
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(HISTOGRAM_BUCKETS.len());
+        histogram.buckets[bucket] += 1;
+        histogram.sum += value;
+        histogram.count += 1;
+    }
+}
+
+/// `{a="1",b="2"}` with an optional `le` bucket label; empty without labels
+fn label_set(labels: &[(String, String)], le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{}\"", le));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn otlp_attributes(labels: &[(String, String)]) -> Vec<Value> {
+    labels
+        .iter()
+        .map(|(k, v)| json!({ "key": k, "value": { "stringValue": v } }))
+        .collect()
+}
+
+fn unix_nanos(at: DateTime<Utc>) -> String {
+    at.timestamp_nanos_opt().unwrap_or_default().to_string()
+}
+
+// ============================================================================
+// StatsD
+// ============================================================================
+
+/// Sends each measurement as one UDP datagram in the DogStatsD format
+/// (`prefix.name:value|type|#label:value`). Send errors are ignored: a
+/// missing agent must not slow requests down.
+pub struct StatsdTelemetry {
+    socket: UdpSocket,
+    prefix: String,
+}
+
+impl StatsdTelemetry {
+    pub fn new(addr: &str, prefix: &str) -> Result<Self, DomainError> {
+        let socket = UdpSocket::bind("0.0.0.0:0")
+            .and_then(|socket| socket.connect(addr).map(|_| socket))
+            .and_then(|socket| socket.set_nonblocking(true).map(|_| socket))
+            .map_err(|e| DomainError::internal(format!("Failed to open statsd socket to {}: {}", addr, e)))?;
+        Ok(Self {
+            socket,
+            prefix: prefix.to_string(),
+        })
+    }
+
+    /// The datagram for one measurement
+    pub fn line(&self, name: &str, value: &str, kind: &str, labels: Labels<'_>) -> String {
+        let mut line = if self.prefix.is_empty() {
+            format!("{}:{}|{}", name, value, kind)
+        } else {
+            format!("{}.{}:{}|{}", self.prefix, name, value, kind)
+        };
+        if !labels.is_empty() {
+            let tags: Vec<String> = labels.iter().map(|(k, v)| format!("{}:{}", k, v)).collect();
+            let _ = write!(line, "|#{}", tags.join(","));
+        }
+        line
+    }
+
+    fn send(&self, line: String) {
+        let _ = self.socket.send(line.as_bytes());
+    }
+}
+
+impl Telemetry for StatsdTelemetry {
+    fn increment(&self, name: &str, value: u64, labels: Labels<'_>) {
+        self.send(self.line(name, &value.to_string(), "c", labels));
+    }
+
+    fn gauge(&self, name: &str, value: f64, labels: Labels<'_>) {
+        self.send(self.line(name, &value.to_string(), "g", labels));
+    }
+
+    fn observe(&self, name: &str, value: f64, labels: Labels<'_>) {
+        self.send(self.line(name, &value.to_string(), "h", labels));
+    }
+}
@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{DomainError, Tenant, TenantRepository};
+use uuid::Uuid;
+
+use crate::macros::pg_repository;
+use crate::map_sqlx_error;
+
+// ============================================================================
+// Tenant Repository
+// ============================================================================
+
+pg_repository! {
+    pub struct PostgresTenantRepository for Tenant in "tenants" order by "created_at DESC" {
+        id: Uuid,
+        slug: String,
+        name: String,
+        #[immutable] created_at: DateTime<Utc>,
+    }
+}
+
+#[async_trait]
+impl TenantRepository for PostgresTenantRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Tenant", operation = "find_by_slug"))]
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<Tenant>, DomainError> {
+        let row: Option<(Uuid, String, String, DateTime<Utc>)> =
+            sqlx::query_as("SELECT id, slug, name, created_at FROM tenants WHERE slug = $1")
+                .bind(slug)
+                .fetch_optional(&mut self.db.acquire_read().await?)
+                .await
+                .map_err(|e| map_sqlx_error(e, "Tenant"))?;
+
+        Ok(row.map(|(id, slug, name, created_at)| Tenant {
+            id,
+            slug,
+            name,
+            created_at,
+        }))
+    }
+}
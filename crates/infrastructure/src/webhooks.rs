@@ -0,0 +1,444 @@
+use std::time::Duration;
+
+use application::webhooks::WebhookSender;
+use application::ApplicationError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use domain::{
+    DeliveryStatus, DomainError, Page, PaginationParams, Repository, Webhook, WebhookDelivery,
+    WebhookDeliveryRepository, WebhookRepository,
+};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::db::{Database, DbConnection};
+use crate::map_sqlx_error;
+
+// ============================================================================
+// Webhook Repository
+// ============================================================================
+
+pub struct PostgresWebhookRepository {
+    db: Database,
+}
+
+impl PostgresWebhookRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn conn(&self) -> Result<DbConnection, DomainError> {
+        self.db.acquire().await
+    }
+
+    async fn read_conn(&self) -> Result<DbConnection, DomainError> {
+        self.db.acquire_read().await
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct WebhookRow {
+    id: Uuid,
+    url: String,
+    secret: String,
+    events: Vec<String>,
+    active: bool,
+    created_at: DateTime<Utc>,
+}
+
+impl From<WebhookRow> for Webhook {
+    fn from(row: WebhookRow) -> Self {
+        Self {
+            id: row.id,
+            url: row.url,
+            secret: row.secret,
+            events: row.events,
+            active: row.active,
+            created_at: row.created_at,
+        }
+    }
+}
+
+const WEBHOOK_COLUMNS: &str = "id, url, secret, events, active, created_at";
+
+#[async_trait]
+impl Repository<Webhook> for PostgresWebhookRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Webhook", operation = "find_by_id"))]
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<Webhook>, DomainError> {
+        let row = sqlx::query_as::<_, WebhookRow>(&format!("SELECT {} FROM webhooks WHERE id = $1", WEBHOOK_COLUMNS))
+            .bind(id)
+            .fetch_optional(&mut self.read_conn().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Webhook"))?;
+
+        Ok(row.map(Into::into))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Webhook", operation = "find_all"))]
+    async fn find_all(&self, params: &PaginationParams) -> Result<Page<Webhook>, DomainError> {
+        params.validate()?;
+        let rows = sqlx::query_as::<_, WebhookRow>(&format!(
+            "SELECT {} FROM webhooks ORDER BY created_at DESC LIMIT $1 OFFSET $2",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(params.limit() as i64)
+        .bind(params.offset() as i64)
+        .fetch_all(&mut self.read_conn().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Webhook"))?;
+
+        let total = self.count().await?;
+        let webhooks = rows.into_iter().map(Into::into).collect();
+
+        Ok(Page::new(webhooks, total, params))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Webhook", operation = "create"))]
+    async fn create(&self, webhook: &Webhook) -> Result<Webhook, DomainError> {
+        let row = sqlx::query_as::<_, WebhookRow>(&format!(
+            r#"
+            INSERT INTO webhooks (id, url, secret, events, active, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            WEBHOOK_COLUMNS
+        ))
+        .bind(webhook.id)
+        .bind(&webhook.url)
+        .bind(&webhook.secret)
+        .bind(&webhook.events)
+        .bind(webhook.active)
+        .bind(webhook.created_at)
+        .fetch_one(&mut self.conn().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Webhook"))?;
+
+        self.db.record_write().await;
+        Ok(row.into())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Webhook", operation = "update"))]
+    async fn update(&self, webhook: &Webhook) -> Result<Webhook, DomainError> {
+        let row = sqlx::query_as::<_, WebhookRow>(&format!(
+            r#"
+            UPDATE webhooks
+            SET url = $2, secret = $3, events = $4, active = $5
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            WEBHOOK_COLUMNS
+        ))
+        .bind(webhook.id)
+        .bind(&webhook.url)
+        .bind(&webhook.secret)
+        .bind(&webhook.events)
+        .bind(webhook.active)
+        .fetch_optional(&mut self.conn().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Webhook"))?
+        .ok_or_else(|| DomainError::not_found("Webhook", webhook.id.to_string()))?;
+
+        self.db.record_write().await;
+        Ok(row.into())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Webhook", operation = "delete"))]
+    async fn delete(&self, id: Uuid) -> Result<bool, DomainError> {
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = $1")
+            .bind(id)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Webhook"))?;
+
+        self.db.record_write().await;
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Webhook", operation = "count"))]
+    async fn count(&self) -> Result<u64, DomainError> {
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM webhooks")
+            .fetch_one(&mut self.read_conn().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "Webhook"))?;
+
+        Ok(count.0 as u64)
+    }
+}
+
+#[async_trait]
+impl WebhookRepository for PostgresWebhookRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "Webhook", operation = "find_subscribed"))]
+    async fn find_subscribed(&self, event: &str) -> Result<Vec<Webhook>, DomainError> {
+        let rows = sqlx::query_as::<_, WebhookRow>(&format!(
+            "SELECT {} FROM webhooks WHERE active AND (cardinality(events) = 0 OR $1 = ANY(events))",
+            WEBHOOK_COLUMNS
+        ))
+        .bind(event)
+        .fetch_all(&mut self.conn().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "Webhook"))?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
+
+// ============================================================================
+// Webhook Delivery Repository
+// ============================================================================
+
+pub struct PostgresWebhookDeliveryRepository {
+    db: Database,
+}
+
+impl PostgresWebhookDeliveryRepository {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    async fn conn(&self) -> Result<DbConnection, DomainError> {
+        self.db.acquire().await
+    }
+
+    async fn read_conn(&self) -> Result<DbConnection, DomainError> {
+        self.db.acquire_read().await
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct DeliveryRow {
+    id: Uuid,
+    webhook_id: Uuid,
+    event_id: i64,
+    event: String,
+    payload: String,
+    status: String,
+    attempts: i32,
+    response_status: Option<i32>,
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+    delivered_at: Option<DateTime<Utc>>,
+}
+
+impl From<DeliveryRow> for WebhookDelivery {
+    fn from(row: DeliveryRow) -> Self {
+        Self {
+            id: row.id,
+            webhook_id: row.webhook_id,
+            event_id: u64::try_from(row.event_id).unwrap_or_default(),
+            event: row.event,
+            payload: row.payload,
+            status: DeliveryStatus::parse(&row.status).unwrap_or(DeliveryStatus::Failed),
+            attempts: u32::try_from(row.attempts).unwrap_or_default(),
+            response_status: row.response_status.and_then(|s| u16::try_from(s).ok()),
+            last_error: row.last_error,
+            created_at: row.created_at,
+            delivered_at: row.delivered_at,
+        }
+    }
+}
+
+const DELIVERY_COLUMNS: &str =
+    "id, webhook_id, event_id, event, payload, status, attempts, response_status, last_error, created_at, delivered_at";
+
+#[async_trait]
+impl Repository<WebhookDelivery> for PostgresWebhookDeliveryRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "WebhookDelivery", operation = "find_by_id"))]
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<WebhookDelivery>, DomainError> {
+        // Read from the primary: the dispatcher queues a job right after inserting
+        let row = sqlx::query_as::<_, DeliveryRow>(&format!(
+            "SELECT {} FROM webhook_deliveries WHERE id = $1",
+            DELIVERY_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(&mut self.conn().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "WebhookDelivery"))?;
+
+        Ok(row.map(Into::into))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "WebhookDelivery", operation = "find_all"))]
+    async fn find_all(&self, params: &PaginationParams) -> Result<Page<WebhookDelivery>, DomainError> {
+        params.validate()?;
+        let rows = sqlx::query_as::<_, DeliveryRow>(&format!(
+            "SELECT {} FROM webhook_deliveries ORDER BY created_at DESC LIMIT $1 OFFSET $2",
+            DELIVERY_COLUMNS
+        ))
+        .bind(params.limit() as i64)
+        .bind(params.offset() as i64)
+        .fetch_all(&mut self.read_conn().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "WebhookDelivery"))?;
+
+        let total = self.count().await?;
+        let deliveries = rows.into_iter().map(Into::into).collect();
+
+        Ok(Page::new(deliveries, total, params))
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "WebhookDelivery", operation = "create"))]
+    async fn create(&self, delivery: &WebhookDelivery) -> Result<WebhookDelivery, DomainError> {
+        let row = sqlx::query_as::<_, DeliveryRow>(&format!(
+            r#"
+            INSERT INTO webhook_deliveries
+                (id, webhook_id, event_id, event, payload, status, attempts, response_status, last_error, created_at, delivered_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            RETURNING {}
+            "#,
+            DELIVERY_COLUMNS
+        ))
+        .bind(delivery.id)
+        .bind(delivery.webhook_id)
+        .bind(delivery.event_id as i64)
+        .bind(&delivery.event)
+        .bind(&delivery.payload)
+        .bind(delivery.status.as_str())
+        .bind(delivery.attempts as i32)
+        .bind(delivery.response_status.map(i32::from))
+        .bind(&delivery.last_error)
+        .bind(delivery.created_at)
+        .bind(delivery.delivered_at)
+        .fetch_one(&mut self.conn().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "WebhookDelivery"))?;
+
+        self.db.record_write().await;
+        Ok(row.into())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "WebhookDelivery", operation = "update"))]
+    async fn update(&self, delivery: &WebhookDelivery) -> Result<WebhookDelivery, DomainError> {
+        let row = sqlx::query_as::<_, DeliveryRow>(&format!(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2, attempts = $3, response_status = $4, last_error = $5, delivered_at = $6
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            DELIVERY_COLUMNS
+        ))
+        .bind(delivery.id)
+        .bind(delivery.status.as_str())
+        .bind(delivery.attempts as i32)
+        .bind(delivery.response_status.map(i32::from))
+        .bind(&delivery.last_error)
+        .bind(delivery.delivered_at)
+        .fetch_optional(&mut self.conn().await?)
+        .await
+        .map_err(|e| map_sqlx_error(e, "WebhookDelivery"))?
+        .ok_or_else(|| DomainError::not_found("WebhookDelivery", delivery.id.to_string()))?;
+
+        self.db.record_write().await;
+        Ok(row.into())
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "WebhookDelivery", operation = "delete"))]
+    async fn delete(&self, id: Uuid) -> Result<bool, DomainError> {
+        let result = sqlx::query("DELETE FROM webhook_deliveries WHERE id = $1")
+            .bind(id)
+            .execute(&mut self.conn().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "WebhookDelivery"))?;
+
+        self.db.record_write().await;
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "WebhookDelivery", operation = "count"))]
+    async fn count(&self) -> Result<u64, DomainError> {
+        let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM webhook_deliveries")
+            .fetch_one(&mut self.read_conn().await?)
+            .await
+            .map_err(|e| map_sqlx_error(e, "WebhookDelivery"))?;
+
+        Ok(count.0 as u64)
+    }
+}
+
+#[async_trait]
+impl WebhookDeliveryRepository for PostgresWebhookDeliveryRepository {
+    #[tracing::instrument(name = "repository", skip_all, fields(entity = "WebhookDelivery", operation = "find_by_webhook"))]
+    async fn find_by_webhook(&self, webhook_id: Uuid, params: &PaginationParams) -> Result<Page<WebhookDelivery>, DomainError> {
+        params.validate()?;
+        let mut conn = self.read_conn().await?;
+        let rows = sqlx::query_as::<_, DeliveryRow>(&format!(
+            "SELECT {} FROM webhook_deliveries WHERE webhook_id = $1 ORDER BY created_at DESC LIMIT $2 OFFSET $3",
+            DELIVERY_COLUMNS
+        ))
+        .bind(webhook_id)
+        .bind(params.limit() as i64)
+        .bind(params.offset() as i64)
+        .fetch_all(&mut conn)
+        .await
+        .map_err(|e| map_sqlx_error(e, "WebhookDelivery"))?;
+
+        let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM webhook_deliveries WHERE webhook_id = $1")
+            .bind(webhook_id)
+            .fetch_one(&mut conn)
+            .await
+            .map_err(|e| map_sqlx_error(e, "WebhookDelivery"))?;
+
+        let deliveries = rows.into_iter().map(Into::into).collect();
+        Ok(Page::new(deliveries, total.0 as u64, params))
+    }
+}
+
+// ============================================================================
+// HTTP Sender
+// ============================================================================
+
+/// POSTs deliveries with reqwest. Receivers verify `X-Webhook-Signature`
+/// (`sha256=<hex HMAC-SHA256 of "{timestamp}.{body}">` keyed with the
+/// webhook secret) and reject stale `X-Webhook-Timestamp` values.
+pub struct HttpWebhookSender {
+    client: reqwest::Client,
+}
+
+impl HttpWebhookSender {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .user_agent(concat!("rust-base-webhooks/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .expect("valid webhook HTTP client");
+
+        Self { client }
+    }
+}
+
+impl Default for HttpWebhookSender {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `sha256=` signature header value for a delivery body
+pub fn sign_webhook(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[async_trait]
+impl WebhookSender for HttpWebhookSender {
+    async fn send(&self, webhook: &Webhook, delivery: &WebhookDelivery) -> Result<u16, ApplicationError> {
+        let timestamp = Utc::now().timestamp();
+        let response = self
+            .client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .header("X-Webhook-Id", delivery.id.to_string())
+            .header("X-Webhook-Event", &delivery.event)
+            .header("X-Webhook-Timestamp", timestamp.to_string())
+            .header("X-Webhook-Signature", sign_webhook(&webhook.secret, timestamp, &delivery.payload))
+            .body(delivery.payload.clone())
+            .send()
+            .await
+            .map_err(|e| DomainError::internal(format!("Webhook request failed: {}", e)))?;
+
+        Ok(response.status().as_u16())
+    }
+}
@@ -0,0 +1,18 @@
+use std::path::PathBuf;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Use the vendored protoc unless the environment provides one
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
+    tonic_build::configure()
+        .file_descriptor_set_path(out_dir.join("rust_base_descriptor.bin"))
+        .compile_protos(
+            &["proto/rust_base/v1/rust_base.proto", "proto/rust_base/events/v1/events.proto"],
+            &["proto"],
+        )?;
+
+    Ok(())
+}
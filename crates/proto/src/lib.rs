@@ -0,0 +1,21 @@
+//! Protobuf contracts: the gRPC services and the domain event payloads.
+//!
+//! Generated at build time from `proto/`, so the `.proto` files are the
+//! source of truth. Downstream consumers depend on this crate alone, without
+//! pulling in the server.
+
+/// `rust_base.v1`: the user and auth gRPC services, with servers and clients
+pub mod v1 {
+    tonic::include_proto!("rust_base.v1");
+}
+
+/// Event payloads, versioned by package
+pub mod events {
+    /// `rust_base.events.v1`
+    pub mod v1 {
+        tonic::include_proto!("rust_base.events.v1");
+    }
+}
+
+/// Encoded descriptors of every package, for gRPC server reflection
+pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("rust_base_descriptor");
@@ -0,0 +1,48 @@
+//! Metrics facade. Code records through `telemetry()`, and the binary picks
+//! the backend (`telemetry.backend`) once at startup with `set_telemetry`.
+//! Until then, and with the `none` backend, everything is discarded.
+
+use std::sync::{Arc, OnceLock};
+
+/// `name=value` pairs identifying one series of a metric
+pub type Labels<'a> = &'a [(&'a str, &'a str)];
+
+/// Where metrics go (Prometheus/OTLP, statsd, nowhere). Names are
+/// snake_case without the service prefix, which backends add themselves.
+pub trait Telemetry: Send + Sync {
+    /// Add `value` to a monotonically increasing counter
+    fn increment(&self, name: &str, value: u64, labels: Labels<'_>);
+
+    /// Set a value that goes up and down (pool size, queue length, ...)
+    fn gauge(&self, name: &str, value: f64, labels: Labels<'_>);
+
+    /// Record one observation of a distribution, e.g. a duration in seconds
+    fn observe(&self, name: &str, value: f64, labels: Labels<'_>);
+}
+
+/// Discards everything
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopTelemetry;
+
+impl Telemetry for NoopTelemetry {
+    fn increment(&self, _name: &str, _value: u64, _labels: Labels<'_>) {}
+
+    fn gauge(&self, _name: &str, _value: f64, _labels: Labels<'_>) {}
+
+    fn observe(&self, _name: &str, _value: f64, _labels: Labels<'_>) {}
+}
+
+static TELEMETRY: OnceLock<Arc<dyn Telemetry>> = OnceLock::new();
+
+/// Install the process-wide backend; false when one was already installed
+pub fn set_telemetry(telemetry: Arc<dyn Telemetry>) -> bool {
+    TELEMETRY.set(telemetry).is_ok()
+}
+
+/// The installed backend, or `NoopTelemetry`
+pub fn telemetry() -> &'static dyn Telemetry {
+    match TELEMETRY.get() {
+        Some(telemetry) => telemetry.as_ref(),
+        None => &NoopTelemetry,
+    }
+}
@@ -0,0 +1,19 @@
+#![no_main]
+
+use application::TokenService;
+use infrastructure::{JwtConfig, JwtTokenService};
+use libfuzzer_sys::fuzz_target;
+
+// Authorization header -> bearer token -> JWT header -> full validation.
+fuzz_target!(|data: &[u8]| {
+    let Ok(header) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(token) = api::middleware::bearer_token(Some(header)) {
+        let _ = jsonwebtoken::decode_header(token);
+
+        let service = JwtTokenService::new(JwtConfig::new("fuzz-secret".to_string(), 1));
+        let _ = service.validate(token);
+    }
+});
@@ -0,0 +1,22 @@
+#![no_main]
+
+use axum::extract::Query;
+use axum::http::Uri;
+use domain::{Page, PaginationParams};
+use libfuzzer_sys::fuzz_target;
+
+// Query string -> `PaginationParams` exactly as the `/users` handler sees it.
+fuzz_target!(|data: &[u8]| {
+    let Ok(query) = std::str::from_utf8(data) else {
+        return;
+    };
+    let Ok(uri) = format!("/users?{}", query).parse::<Uri>() else {
+        return;
+    };
+
+    if let Ok(Query(params)) = Query::<PaginationParams>::try_from_uri(&uri) {
+        assert!(params.limit() <= 100);
+        let _ = params.offset();
+        let _ = Page::<()>::new(Vec::new(), u64::from(params.offset()), &params);
+    }
+});
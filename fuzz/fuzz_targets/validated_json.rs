@@ -0,0 +1,10 @@
+#![no_main]
+
+use api::auth::{parse_validated, LoginRequest, RegisterRequest};
+use libfuzzer_sys::fuzz_target;
+
+// Every malformed or invalid body must map to an `ApiError`, never a panic.
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_validated::<RegisterRequest>(data);
+    let _ = parse_validated::<LoginRequest>(data);
+});
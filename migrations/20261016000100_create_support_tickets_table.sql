@@ -0,0 +1,12 @@
+-- Messages submitted through POST /support/contact
+CREATE TABLE IF NOT EXISTS support_tickets (
+    id UUID PRIMARY KEY,
+    user_id UUID REFERENCES users (id) ON DELETE SET NULL,
+    name TEXT NOT NULL,
+    email TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS support_tickets_created_at_idx ON support_tickets (created_at DESC);
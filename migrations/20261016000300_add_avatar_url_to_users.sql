@@ -0,0 +1,2 @@
+-- Public URL of the avatar uploaded through POST /me/avatar
+ALTER TABLE users ADD COLUMN IF NOT EXISTS avatar_url TEXT;
@@ -0,0 +1,2 @@
+-- Optimistic concurrency: updates must name the version they read
+ALTER TABLE users ADD COLUMN IF NOT EXISTS version BIGINT NOT NULL DEFAULT 1;
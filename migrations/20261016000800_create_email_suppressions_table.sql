@@ -0,0 +1,10 @@
+-- Addresses email is no longer sent to (see application::email_suppression)
+CREATE TABLE IF NOT EXISTS email_suppressions (
+    -- Lowercased
+    email TEXT PRIMARY KEY,
+    reason TEXT NOT NULL CHECK (reason IN ('bounce', 'complaint')),
+    -- ses or sendgrid
+    provider TEXT NOT NULL,
+    detail TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
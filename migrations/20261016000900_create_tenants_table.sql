@@ -0,0 +1,34 @@
+-- Customer organizations; every user belongs to exactly one
+CREATE TABLE IF NOT EXISTS tenants (
+    id UUID PRIMARY KEY,
+    -- Subdomain label (acme.example.com -> acme)
+    slug TEXT NOT NULL UNIQUE CHECK (slug ~ '^[a-z0-9]([a-z0-9-]*[a-z0-9])?$'),
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Tenant of requests that name none (domain::Tenant::DEFAULT_ID)
+INSERT INTO tenants (id, slug, name)
+VALUES ('00000000-0000-0000-0000-000000000000', 'default', 'Default')
+ON CONFLICT (id) DO NOTHING;
+
+CREATE OR REPLACE FUNCTION protect_default_tenant() RETURNS trigger AS $$
+BEGIN
+    IF OLD.id = '00000000-0000-0000-0000-000000000000' THEN
+        RAISE EXCEPTION 'the default tenant cannot be deleted' USING ERRCODE = 'foreign_key_violation';
+    END IF;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER tenants_protect_default
+    BEFORE DELETE ON tenants
+    FOR EACH ROW EXECUTE FUNCTION protect_default_tenant();
+
+-- Existing users join the default tenant. Usernames and emails stay
+-- globally unique, so one login identifies one tenant.
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS tenant_id UUID NOT NULL
+        DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES tenants (id);
+
+CREATE INDEX IF NOT EXISTS idx_users_tenant_id ON users (tenant_id, created_at DESC);
@@ -0,0 +1,4 @@
+-- Trigram index for username autocomplete (ILIKE 'prefix%') and admin search
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS idx_users_username_trgm ON users USING gin (username gin_trgm_ops);
@@ -0,0 +1,33 @@
+-- Labels attached to rows of any taggable table (domain::Taggable)
+CREATE TABLE IF NOT EXISTS tags (
+    id UUID PRIMARY KEY,
+    tenant_id UUID NOT NULL REFERENCES tenants (id) ON DELETE CASCADE,
+    name TEXT NOT NULL CHECK (name ~ '^[a-z0-9_:-]{1,50}$'),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (tenant_id, name)
+);
+
+-- Which rows carry which tag. entity_type is Taggable::ENTITY_TYPE, so
+-- entity_id cannot reference the tagged table.
+CREATE TABLE IF NOT EXISTS taggings (
+    tag_id UUID NOT NULL REFERENCES tags (id) ON DELETE CASCADE,
+    entity_type TEXT NOT NULL,
+    entity_id UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (tag_id, entity_type, entity_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_taggings_entity ON taggings (entity_type, entity_id);
+
+-- Drops the taggings of a deleted row; attach to every taggable table with
+-- its entity type as the argument
+CREATE OR REPLACE FUNCTION delete_taggings() RETURNS trigger AS $$
+BEGIN
+    DELETE FROM taggings WHERE entity_type = TG_ARGV[0] AND entity_id = OLD.id;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER users_delete_taggings
+    AFTER DELETE ON users
+    FOR EACH ROW EXECUTE FUNCTION delete_taggings('user');
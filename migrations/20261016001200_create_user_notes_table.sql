@@ -0,0 +1,13 @@
+-- Internal notes admins leave on users
+CREATE TABLE IF NOT EXISTS user_notes (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    -- No foreign key: notes outlive the admin accounts that wrote them
+    author_id UUID NOT NULL,
+    body TEXT NOT NULL,
+    visibility TEXT NOT NULL DEFAULT 'team' CHECK (visibility IN ('team', 'private')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_notes_user_id ON user_notes (user_id, created_at DESC);
@@ -0,0 +1,14 @@
+-- Long-running operations started through the API (see application::operations)
+CREATE TABLE IF NOT EXISTS operations (
+    id UUID PRIMARY KEY,
+    kind TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_by UUID NOT NULL,
+    tenant_id UUID REFERENCES tenants (id) ON DELETE CASCADE,
+    total BIGINT,
+    -- Per-item results: [{"id": "...", "error": null}, ...]
+    items JSONB NOT NULL DEFAULT '[]',
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
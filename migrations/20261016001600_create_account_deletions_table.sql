@@ -0,0 +1,7 @@
+-- Account deletions requested by users, pending until `delete_after`
+-- (see application::account_deletion)
+CREATE TABLE IF NOT EXISTS account_deletions (
+    user_id UUID PRIMARY KEY REFERENCES users (id) ON DELETE CASCADE,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    delete_after TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,13 @@
+-- Saved admin user searches (domain::UserSegment)
+CREATE TABLE IF NOT EXISTS user_segments (
+    id UUID PRIMARY KEY,
+    tenant_id UUID NOT NULL REFERENCES tenants (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    -- domain::UserFilter: {"query": ..., "status": ..., "tags": [...]}
+    filter JSONB NOT NULL,
+    created_by UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_segments_tenant_name ON user_segments (tenant_id, lower(name));
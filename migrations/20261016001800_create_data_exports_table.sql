@@ -0,0 +1,18 @@
+-- Account data exports (see application::data_export). `key` and the
+-- download token's SHA-256 are set once the file is built; `downloaded_at`
+-- makes the download link single-use.
+CREATE TABLE IF NOT EXISTS data_exports (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    format TEXT NOT NULL,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    key TEXT,
+    token_hash TEXT UNIQUE,
+    expires_at TIMESTAMPTZ,
+    downloaded_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_data_exports_user_id ON data_exports (user_id, requested_at DESC);
+
+-- Sessions are listed per user for exports
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_user_id ON refresh_tokens (user_id);
@@ -0,0 +1,15 @@
+-- Temporary rate limit overrides granted by admins (see
+-- application::rate_limits). `subject` and `scope` match the parts of the
+-- limiter's `<scope>:<subject>` keys; a NULL scope covers every scope.
+CREATE TABLE IF NOT EXISTS rate_limit_overrides (
+    id UUID PRIMARY KEY,
+    subject TEXT NOT NULL,
+    scope TEXT,
+    hit_limit INTEGER NOT NULL CHECK (hit_limit > 0),
+    reason TEXT,
+    created_by UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    expires_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_limit_overrides_expires_at ON rate_limit_overrides (expires_at);
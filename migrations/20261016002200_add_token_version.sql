@@ -0,0 +1,4 @@
+-- Soft logout-all: tokens carry the user's token_version at issue and are
+-- rejected once it has been bumped
+ALTER TABLE users ADD COLUMN IF NOT EXISTS token_version BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE refresh_tokens ADD COLUMN IF NOT EXISTS token_version BIGINT NOT NULL DEFAULT 0;
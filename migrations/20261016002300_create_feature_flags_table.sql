@@ -0,0 +1,10 @@
+-- Feature flags managed by admins (see application::feature_flags). A row
+-- takes precedence over a flag of the same key in FEATURE_FLAGS.
+CREATE TABLE IF NOT EXISTS feature_flags (
+    key TEXT PRIMARY KEY,
+    enabled BOOLEAN NOT NULL,
+    rollout JSONB NOT NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,12 @@
+-- Servers calling the API with HMAC-signed requests (see
+-- application::request_signing). The secret is kept as issued: verifying a
+-- signature needs it.
+CREATE TABLE IF NOT EXISTS api_clients (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    key_id TEXT NOT NULL UNIQUE,
+    secret TEXT NOT NULL,
+    roles TEXT[] NOT NULL DEFAULT '{}',
+    created_by UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
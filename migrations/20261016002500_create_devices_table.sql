@@ -0,0 +1,12 @@
+-- Devices users sign in from (see application::devices). Only a hash of the
+-- client's fingerprint is kept; trusted_until is set by "remember this device".
+CREATE TABLE IF NOT EXISTS devices (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    fingerprint_hash TEXT NOT NULL,
+    name TEXT,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    trusted_until TIMESTAMPTZ,
+    UNIQUE (user_id, fingerprint_hash)
+);
@@ -0,0 +1,11 @@
+-- Users now hold domain::Email and domain::Username, which trim (and, for
+-- email, lowercase) at construction. Bring older rows in line so lookups by
+-- the normalized value find them. Fails on a unique violation if two
+-- accounts differ only in case; merge those by hand first.
+UPDATE users
+SET email = lower(btrim(email))
+WHERE email <> lower(btrim(email));
+
+UPDATE users
+SET username = btrim(username)
+WHERE username <> btrim(username);
@@ -0,0 +1,3 @@
+-- Keyset listings (exports) walk users newest first and continue after the
+-- last (created_at, id) seen
+CREATE INDEX IF NOT EXISTS idx_users_created_at_id ON users (created_at DESC, id DESC);
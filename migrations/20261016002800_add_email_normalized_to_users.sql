@@ -0,0 +1,13 @@
+-- Accounts are unique by their normalized address: lowercased, and without
+-- the dots of Gmail addresses when account.ignore_gmail_dots is on. The app
+-- writes it on every insert and update; rows are filled with the lowercase
+-- address here, which is the normalized form with that setting off.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS email_normalized TEXT;
+
+UPDATE users
+SET email_normalized = lower(btrim(email))
+WHERE email_normalized IS NULL;
+
+ALTER TABLE users ALTER COLUMN email_normalized SET NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_email_normalized ON users (email_normalized);
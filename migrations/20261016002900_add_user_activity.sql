@@ -0,0 +1,15 @@
+-- Sign-in history and activity timestamps (see application::activity).
+-- last_seen_at is written in debounced batches, so it can lag a few minutes;
+-- only the newest sign-ins of each user are kept.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS last_login_at TIMESTAMPTZ;
+ALTER TABLE users ADD COLUMN IF NOT EXISTS last_seen_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS user_logins (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    ip TEXT,
+    user_agent TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_logins_user_created ON user_logins (user_id, created_at DESC);
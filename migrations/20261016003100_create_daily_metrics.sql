@@ -0,0 +1,12 @@
+-- Daily rollups of the user tables (see application::rollups), one row per
+-- UTC day and metric; recomputing a day overwrites its rows
+CREATE TABLE IF NOT EXISTS daily_metrics (
+    day DATE NOT NULL,
+    metric TEXT NOT NULL,
+    value BIGINT NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (day, metric)
+);
+
+-- Sign-ins of a day, across users
+CREATE INDEX IF NOT EXISTS idx_user_logins_created ON user_logins (created_at);
@@ -0,0 +1,9 @@
+-- Experiment variants users were assigned (see application::ExperimentAssignmentStore).
+-- No foreign key: guests are assigned too and have no users row.
+CREATE TABLE IF NOT EXISTS experiment_assignments (
+    user_id UUID NOT NULL,
+    experiment TEXT NOT NULL,
+    variant TEXT NOT NULL,
+    assigned_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (user_id, experiment)
+);